@@ -52,6 +52,11 @@ export class SchedulerService extends EventEmitter {
 
   // Initialize scheduler with existing workflows
   async initialize(): Promise<void> {
+    // The desktop app starts the server in safe mode after repeated startup crashes
+    if (process.env.MADEASY_SAFE_MODE === '1') {
+      console.log('Safe mode: workflow schedules are paused');
+      return;
+    }
     try {
       const workflows = await storage.getWatchedWorkflows();
       
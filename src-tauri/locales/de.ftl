@@ -0,0 +1,56 @@
+# Anwendungsmenü
+menu-file = Datei
+menu-view = Ansicht
+menu-window = Fenster
+menu-help = Hilfe
+menu-new-window = Neues Fenster
+menu-reopen-closed-window = Geschlossenes Fenster wieder öffnen
+menu-share-page = Seite teilen…
+menu-settings = Einstellungen
+menu-close = Schließen
+menu-quit = Beenden
+menu-devtools = Entwicklerwerkzeuge
+menu-next-window = Nächstes Fenster
+menu-previous-window = Vorheriges Fenster
+menu-about = Über
+menu-ask-ai-selection = KI zur Auswahl fragen
+
+# Infobereich
+tray-show = Anzeigen
+tray-hide = Ausblenden
+tray-new-window = Neues Fenster
+tray-quit = Beenden
+tray-tooltip = MadEasy Browser
+tray-tooltip-workflow = MadEasy Browser - Workflow läuft
+tray-tooltip-download = MadEasy Browser - Download läuft
+tray-tooltip-ai = MadEasy Browser - KI antwortet
+jumplist-tasks = Aufgaben
+jumplist-new-private-window = Neues privates Fenster
+jumplist-pinned-workflows = Angeheftete Workflows
+jumplist-recent = Zuletzt besucht
+
+# Benachrichtigungen
+notice-about-title = Über MadEasy Browser
+notice-about-body =
+    MadEasy Browser v{ $version }
+    Erstellt mit Tauri und Rust
+notice-audit-broken-title = Integritätsprüfung des Audit-Logs fehlgeschlagen
+notice-audit-broken-body = Audit-Log-Eintrag { $id } stimmt nicht mit seinem gespeicherten Hash überein. Das Log wurde möglicherweise manipuliert.
+notice-backup-failed-title = Sicherung fehlgeschlagen
+notice-dnd-summary-title = Während Sie konzentriert waren
+notice-dnd-summary-body = { $count } Benachrichtigungen sind eingegangen. Öffnen Sie die Benachrichtigungszentrale, um sie anzusehen.
+notice-download-failed-title = Download fehlgeschlagen
+notice-download-failed-body = { $url } konnte nicht heruntergeladen werden.
+notice-download-retry = Download wiederholen
+notice-feed-new-items =
+    { $count ->
+        [one] Ein neuer Eintrag
+       *[other] { $count } neue Einträge
+    }
+notice-server-stopped-title = Backend-Server gestoppt
+notice-server-stopped-body = Der Server ist wiederholt abgestürzt und wurde nicht neu gestartet.
+notice-workflow-failed-title = Workflow „{ $workflow }“ fehlgeschlagen
+notice-workflow-open-run = Lauf öffnen
+
+# Teilen
+share-no-artifacts = Dieser Lauf hat keine gespeicherten Dateien zum Teilen.
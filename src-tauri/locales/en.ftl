@@ -0,0 +1,56 @@
+# Application menu
+menu-file = File
+menu-view = View
+menu-window = Window
+menu-help = Help
+menu-new-window = New Window
+menu-reopen-closed-window = Reopen Closed Window
+menu-share-page = Share Page…
+menu-settings = Settings
+menu-close = Close
+menu-quit = Quit
+menu-devtools = Developer Tools
+menu-next-window = Next Window
+menu-previous-window = Previous Window
+menu-about = About
+menu-ask-ai-selection = Ask AI about selection
+
+# System tray
+tray-show = Show
+tray-hide = Hide
+tray-new-window = New Window
+tray-quit = Quit
+tray-tooltip = MadEasy Browser
+tray-tooltip-workflow = MadEasy Browser - Workflow running
+tray-tooltip-download = MadEasy Browser - Downloading
+tray-tooltip-ai = MadEasy Browser - AI responding
+jumplist-tasks = Tasks
+jumplist-new-private-window = New private window
+jumplist-pinned-workflows = Pinned workflows
+jumplist-recent = Recent
+
+# Notifications
+notice-about-title = About MadEasy Browser
+notice-about-body =
+    MadEasy Browser v{ $version }
+    Built with Tauri and Rust
+notice-audit-broken-title = Audit log integrity check failed
+notice-audit-broken-body = Audit log entry { $id } does not match its recorded hash. The log may have been tampered with.
+notice-backup-failed-title = Backup failed
+notice-dnd-summary-title = While you were focused
+notice-dnd-summary-body = { $count } notifications arrived. Open the notification center to review them.
+notice-download-failed-title = Download failed
+notice-download-failed-body = { $url } could not be downloaded.
+notice-download-retry = Retry download
+notice-feed-new-items =
+    { $count ->
+        [one] One new item
+       *[other] { $count } new items
+    }
+notice-server-stopped-title = Backend server stopped
+notice-server-stopped-body = The server kept crashing and was not restarted.
+notice-workflow-failed-title = Workflow "{ $workflow }" failed
+notice-workflow-open-run = Open run
+
+# Sharing
+share-no-artifacts = This run has no saved files to share.
@@ -0,0 +1,56 @@
+# Menú de la aplicación
+menu-file = Archivo
+menu-view = Ver
+menu-window = Ventana
+menu-help = Ayuda
+menu-new-window = Nueva ventana
+menu-reopen-closed-window = Reabrir ventana cerrada
+menu-share-page = Compartir página…
+menu-settings = Configuración
+menu-close = Cerrar
+menu-quit = Salir
+menu-devtools = Herramientas de desarrollo
+menu-next-window = Ventana siguiente
+menu-previous-window = Ventana anterior
+menu-about = Acerca de
+menu-ask-ai-selection = Preguntar a la IA sobre la selección
+
+# Bandeja del sistema
+tray-show = Mostrar
+tray-hide = Ocultar
+tray-new-window = Nueva ventana
+tray-quit = Salir
+tray-tooltip = MadEasy Browser
+tray-tooltip-workflow = MadEasy Browser - Flujo de trabajo en curso
+tray-tooltip-download = MadEasy Browser - Descargando
+tray-tooltip-ai = MadEasy Browser - La IA está respondiendo
+jumplist-tasks = Tareas
+jumplist-new-private-window = Nueva ventana privada
+jumplist-pinned-workflows = Flujos de trabajo fijados
+jumplist-recent = Recientes
+
+# Notificaciones
+notice-about-title = Acerca de MadEasy Browser
+notice-about-body =
+    MadEasy Browser v{ $version }
+    Creado con Tauri y Rust
+notice-audit-broken-title = Falló la comprobación de integridad del registro de auditoría
+notice-audit-broken-body = La entrada { $id } del registro de auditoría no coincide con su hash guardado. Es posible que el registro haya sido manipulado.
+notice-backup-failed-title = La copia de seguridad falló
+notice-dnd-summary-title = Mientras estabas concentrado
+notice-dnd-summary-body = Llegaron { $count } notificaciones. Abre el centro de notificaciones para revisarlas.
+notice-download-failed-title = Error en la descarga
+notice-download-failed-body = No se pudo descargar { $url }.
+notice-download-retry = Reintentar descarga
+notice-feed-new-items =
+    { $count ->
+        [one] Un elemento nuevo
+       *[other] { $count } elementos nuevos
+    }
+notice-server-stopped-title = Servidor detenido
+notice-server-stopped-body = El servidor falló repetidamente y no se reinició.
+notice-workflow-failed-title = El flujo de trabajo «{ $workflow }» falló
+notice-workflow-open-run = Abrir ejecución
+
+# Compartir
+share-no-artifacts = Esta ejecución no tiene archivos guardados para compartir.
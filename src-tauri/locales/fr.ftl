@@ -0,0 +1,56 @@
+# Menu de l'application
+menu-file = Fichier
+menu-view = Affichage
+menu-window = Fenêtre
+menu-help = Aide
+menu-new-window = Nouvelle fenêtre
+menu-reopen-closed-window = Rouvrir la fenêtre fermée
+menu-share-page = Partager la page…
+menu-settings = Paramètres
+menu-close = Fermer
+menu-quit = Quitter
+menu-devtools = Outils de développement
+menu-next-window = Fenêtre suivante
+menu-previous-window = Fenêtre précédente
+menu-about = À propos
+menu-ask-ai-selection = Demander à l'IA à propos de la sélection
+
+# Zone de notification
+tray-show = Afficher
+tray-hide = Masquer
+tray-new-window = Nouvelle fenêtre
+tray-quit = Quitter
+tray-tooltip = MadEasy Browser
+tray-tooltip-workflow = MadEasy Browser - Workflow en cours
+tray-tooltip-download = MadEasy Browser - Téléchargement en cours
+tray-tooltip-ai = MadEasy Browser - L'IA répond
+jumplist-tasks = Tâches
+jumplist-new-private-window = Nouvelle fenêtre privée
+jumplist-pinned-workflows = Workflows épinglés
+jumplist-recent = Récents
+
+# Notifications
+notice-about-title = À propos de MadEasy Browser
+notice-about-body =
+    MadEasy Browser v{ $version }
+    Conçu avec Tauri et Rust
+notice-audit-broken-title = Échec de la vérification d'intégrité du journal d'audit
+notice-audit-broken-body = L'entrée { $id } du journal d'audit ne correspond pas à son empreinte enregistrée. Le journal a peut-être été altéré.
+notice-backup-failed-title = Échec de la sauvegarde
+notice-dnd-summary-title = Pendant que vous étiez concentré
+notice-dnd-summary-body = { $count } notifications sont arrivées. Ouvrez le centre de notifications pour les consulter.
+notice-download-failed-title = Échec du téléchargement
+notice-download-failed-body = { $url } n'a pas pu être téléchargé.
+notice-download-retry = Relancer le téléchargement
+notice-feed-new-items =
+    { $count ->
+        [one] Un nouvel article
+       *[other] { $count } nouveaux articles
+    }
+notice-server-stopped-title = Serveur arrêté
+notice-server-stopped-body = Le serveur a planté à plusieurs reprises et n'a pas été redémarré.
+notice-workflow-failed-title = Échec du workflow « { $workflow } »
+notice-workflow-open-run = Ouvrir l'exécution
+
+# Partage
+share-no-artifacts = Cette exécution n'a aucun fichier enregistré à partager.
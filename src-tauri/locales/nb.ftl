@@ -0,0 +1,56 @@
+# Programmeny
+menu-file = Fil
+menu-view = Vis
+menu-window = Vindu
+menu-help = Hjelp
+menu-new-window = Nytt vindu
+menu-reopen-closed-window = Åpne lukket vindu igjen
+menu-share-page = Del side…
+menu-settings = Innstillinger
+menu-close = Lukk
+menu-quit = Avslutt
+menu-devtools = Utviklerverktøy
+menu-next-window = Neste vindu
+menu-previous-window = Forrige vindu
+menu-about = Om
+menu-ask-ai-selection = Spør KI om markeringen
+
+# Systemstatusfelt
+tray-show = Vis
+tray-hide = Skjul
+tray-new-window = Nytt vindu
+tray-quit = Avslutt
+tray-tooltip = MadEasy Browser
+tray-tooltip-workflow = MadEasy Browser - Arbeidsflyt kjører
+tray-tooltip-download = MadEasy Browser - Laster ned
+tray-tooltip-ai = MadEasy Browser - AI svarer
+jumplist-tasks = Oppgaver
+jumplist-new-private-window = Nytt privat vindu
+jumplist-pinned-workflows = Festede arbeidsflyter
+jumplist-recent = Nylig
+
+# Varsler
+notice-about-title = Om MadEasy Browser
+notice-about-body =
+    MadEasy Browser v{ $version }
+    Laget med Tauri og Rust
+notice-audit-broken-title = Integritetskontroll av revisjonsloggen feilet
+notice-audit-broken-body = Oppføring { $id } i revisjonsloggen stemmer ikke med den lagrede hashen. Loggen kan ha blitt endret.
+notice-backup-failed-title = Sikkerhetskopiering feilet
+notice-dnd-summary-title = Mens du var fokusert
+notice-dnd-summary-body = { $count } varsler kom inn. Åpne varselsenteret for å se dem.
+notice-download-failed-title = Nedlastingen feilet
+notice-download-failed-body = { $url } kunne ikke lastes ned.
+notice-download-retry = Prøv nedlastingen igjen
+notice-feed-new-items =
+    { $count ->
+        [one] Ett nytt innlegg
+       *[other] { $count } nye innlegg
+    }
+notice-server-stopped-title = Serveren har stoppet
+notice-server-stopped-body = Serveren krasjet gjentatte ganger og ble ikke startet på nytt.
+notice-workflow-failed-title = Arbeidsflyten «{ $workflow }» feilet
+notice-workflow-open-run = Åpne kjøringen
+
+# Deling
+share-no-artifacts = Denne kjøringen har ingen lagrede filer å dele.
@@ -0,0 +1,201 @@
+// Accessibility audit
+// A small axe-style ruleset covering the WCAG failures that show up most
+// often (missing text alternatives and labels, contrast, language, ids,
+// focus order). Rules run in the page and report each failing element with a
+// CSS selector so the frontend can highlight it.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::pagequery;
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(20);
+// Per rule; the total is reported separately
+const MAX_NODES: usize = 50;
+
+const RULES_SCRIPT: &str = r#"
+var MAX_NODES = __MAX_NODES__;
+var selectorOf = function(el) {
+  if (el.id && document.querySelectorAll('#' + CSS.escape(el.id)).length === 1) return '#' + CSS.escape(el.id);
+  var parts = [];
+  while (el && el.nodeType === 1 && el !== document.documentElement) {
+    var part = el.tagName.toLowerCase();
+    var parent = el.parentElement;
+    if (parent) {
+      var same = Array.prototype.filter.call(parent.children, function(c) { return c.tagName === el.tagName; });
+      if (same.length > 1) part += ':nth-of-type(' + (same.indexOf(el) + 1) + ')';
+    }
+    parts.unshift(part);
+    el = parent;
+  }
+  return parts.join(' > ');
+};
+var visible = function(el) {
+  var style = getComputedStyle(el);
+  return style.display !== 'none' && style.visibility !== 'hidden' && el.getClientRects().length > 0;
+};
+var text = function(el) { return (el.textContent || '').replace(/\s+/g, ' ').trim(); };
+var labelledBy = function(el) {
+  var ids = (el.getAttribute('aria-labelledby') || '').split(/\s+/).filter(Boolean);
+  return ids.map(function(id) { var t = document.getElementById(id); return t ? text(t) : ''; }).join(' ').trim();
+};
+var accessibleName = function(el) {
+  return (el.getAttribute('aria-label') || '').trim() || labelledBy(el) || (el.getAttribute('title') || '').trim();
+};
+var luminance = function(rgb) {
+  var c = rgb.map(function(v) { v /= 255; return v <= 0.03928 ? v / 12.92 : Math.pow((v + 0.055) / 1.055, 2.4); });
+  return 0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2];
+};
+var parseColor = function(value) {
+  var m = value.match(/rgba?\(([\d.]+),\s*([\d.]+),\s*([\d.]+)(?:,\s*([\d.]+))?/);
+  return m ? { rgb: [+m[1], +m[2], +m[3]], alpha: m[4] === undefined ? 1 : +m[4] } : null;
+};
+var background = function(el) {
+  while (el && el.nodeType === 1) {
+    var style = getComputedStyle(el);
+    if (style.backgroundImage !== 'none') return null;
+    var color = parseColor(style.backgroundColor);
+    if (color && color.alpha >= 1) return color.rgb;
+    if (color && color.alpha > 0) return null;
+    el = el.parentElement;
+  }
+  return [255, 255, 255];
+};
+
+var rules = [
+  { id: 'image-alt', impact: 'critical', wcag: '1.1.1', description: 'Images must have alternative text',
+    select: 'img', fails: function(el) { return !el.hasAttribute('alt') && !accessibleName(el) && el.getAttribute('role') !== 'presentation'; } },
+  { id: 'input-image-alt', impact: 'critical', wcag: '1.1.1', description: 'Image buttons must have alternative text',
+    select: 'input[type="image"]', fails: function(el) { return !(el.getAttribute('alt') || '').trim() && !accessibleName(el); } },
+  { id: 'label', impact: 'critical', wcag: '1.3.1, 4.1.2', description: 'Form fields must have labels',
+    select: 'input:not([type="hidden"]):not([type="submit"]):not([type="button"]):not([type="reset"]):not([type="image"]), select, textarea',
+    fails: function(el) {
+      if (accessibleName(el)) return false;
+      if (el.labels && Array.prototype.some.call(el.labels, function(l) { return text(l); })) return false;
+      return true;
+    } },
+  { id: 'button-name', impact: 'critical', wcag: '4.1.2', description: 'Buttons must have discernible text',
+    select: 'button, [role="button"], input[type="submit"], input[type="button"], input[type="reset"]',
+    fails: function(el) {
+      if (el.tagName === 'INPUT') return !(el.value || '').trim() && !accessibleName(el) && el.type !== 'submit' && el.type !== 'reset';
+      return !text(el) && !accessibleName(el) && !el.querySelector('img[alt]:not([alt=""])');
+    } },
+  { id: 'link-name', impact: 'serious', wcag: '2.4.4, 4.1.2', description: 'Links must have discernible text',
+    select: 'a[href]', fails: function(el) { return visible(el) && !text(el) && !accessibleName(el) && !el.querySelector('img[alt]:not([alt=""])'); } },
+  { id: 'frame-title', impact: 'serious', wcag: '4.1.2', description: 'Frames must have a title',
+    select: 'iframe, frame', fails: function(el) { return !(el.getAttribute('title') || '').trim() && !accessibleName(el); } },
+  { id: 'html-has-lang', impact: 'serious', wcag: '3.1.1', description: 'The html element must have a lang attribute',
+    select: 'html', fails: function(el) { return !(el.getAttribute('lang') || '').trim(); } },
+  { id: 'document-title', impact: 'serious', wcag: '2.4.2', description: 'Documents must have a title',
+    select: 'html', fails: function() { return !document.title.trim(); } },
+  { id: 'duplicate-id', impact: 'minor', wcag: '4.1.1', description: 'Ids must be unique',
+    select: '[id]', fails: function(el) { return el.id && document.querySelectorAll('[id="' + el.id.replace(/"/g, '\\"') + '"]').length > 1; } },
+  { id: 'empty-heading', impact: 'minor', wcag: '1.3.1', description: 'Headings must not be empty',
+    select: 'h1, h2, h3, h4, h5, h6', fails: function(el) { return visible(el) && !text(el) && !accessibleName(el); } },
+  { id: 'tabindex', impact: 'serious', wcag: '2.4.3', description: 'Elements should not have a tabindex greater than zero',
+    select: '[tabindex]', fails: function(el) { return parseInt(el.getAttribute('tabindex'), 10) > 0; } },
+  { id: 'aria-hidden-focus', impact: 'serious', wcag: '4.1.2', description: 'aria-hidden elements must not contain focusable elements',
+    select: '[aria-hidden="true"]', fails: function(el) {
+      return !!el.querySelector('a[href], button:not([disabled]), input:not([disabled]), select, textarea, [tabindex]:not([tabindex="-1"])');
+    } },
+  { id: 'meta-viewport', impact: 'critical', wcag: '1.4.4', description: 'Zooming and scaling must not be disabled',
+    select: 'meta[name="viewport"]', fails: function(el) {
+      var content = (el.getAttribute('content') || '').toLowerCase().replace(/\s/g, '');
+      var max = content.match(/maximum-scale=([\d.]+)/);
+      return content.indexOf('user-scalable=no') !== -1 || (max && parseFloat(max[1]) < 2);
+    } },
+  { id: 'color-contrast', impact: 'serious', wcag: '1.4.3', description: 'Text must have sufficient color contrast',
+    select: 'p, span, a, li, td, th, label, button, h1, h2, h3, h4, h5, h6', fails: function(el) {
+      var own = Array.prototype.some.call(el.childNodes, function(n) { return n.nodeType === 3 && n.nodeValue.trim(); });
+      if (!own || !visible(el)) return false;
+      var style = getComputedStyle(el);
+      var fg = parseColor(style.color), bg = background(el);
+      if (!fg || fg.alpha < 1 || !bg) return false;
+      var l1 = luminance(fg.rgb), l2 = luminance(bg);
+      var ratio = (Math.max(l1, l2) + 0.05) / (Math.min(l1, l2) + 0.05);
+      var size = parseFloat(style.fontSize), bold = parseInt(style.fontWeight, 10) >= 700;
+      var large = size >= 24 || (bold && size >= 18.66);
+      return ratio < (large ? 3 : 4.5);
+    } }
+];
+
+return rules.map(function(rule) {
+  var failing = Array.prototype.filter.call(document.querySelectorAll(rule.select), function(el) {
+    try { return rule.fails(el); } catch (e) { return false; }
+  });
+  return {
+    id: rule.id, impact: rule.impact, wcag: rule.wcag, description: rule.description,
+    total: failing.length,
+    nodes: failing.slice(0, MAX_NODES).map(function(el) {
+      return { selector: selectorOf(el), html: el.outerHTML.slice(0, 200) };
+    })
+  };
+}).filter(function(result) { return result.total > 0; });
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Impact {
+    Critical,
+    Serious,
+    Moderate,
+    Minor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ViolationNode {
+    selector: String,
+    html: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Violation {
+    id: String,
+    impact: Impact,
+    wcag: String,
+    description: String,
+    // Failing elements; `nodes` lists at most MAX_NODES of them
+    total: usize,
+    nodes: Vec<ViolationNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AccessibilityReport {
+    url: String,
+    violations: Vec<Violation>,
+    critical: usize,
+    serious: usize,
+    moderate: usize,
+    minor: usize,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn audit_accessibility(app_handle: AppHandle, window_id: String) -> Result<AccessibilityReport, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    let url = window.url().map_err(|e| e.to_string())?.to_string();
+    let script = RULES_SCRIPT.replace("__MAX_NODES__", &MAX_NODES.to_string());
+    let result = pagequery::run(&app_handle, &window, &script, QUERY_TIMEOUT).await?;
+    let mut violations: Vec<Violation> = serde_json::from_value(result).map_err(|e| e.to_string())?;
+    violations.sort_by(|a, b| a.impact.cmp(&b.impact).then(b.total.cmp(&a.total)));
+
+    let count = |impact: Impact| {
+        violations
+            .iter()
+            .filter(|v| v.impact == impact)
+            .map(|v| v.total)
+            .sum()
+    };
+    Ok(AccessibilityReport {
+        url,
+        critical: count(Impact::Critical),
+        serious: count(Impact::Serious),
+        moderate: count(Impact::Moderate),
+        minor: count(Impact::Minor),
+        violations,
+    })
+}
@@ -0,0 +1,564 @@
+// AI provider used by backend features (omnibox answers and the like)
+// Any OpenAI-compatible chat completions endpoint; the key falls back to
+// OPENAI_API_KEY so it matches the Node server's configuration. Optionally
+// several providers can be listed (`set_ai_routing`): routing rules pick the
+// order per task, e.g. a cheap model for summaries and a strong one for
+// extraction, and a rate-limited or unreachable provider is skipped for the
+// next one. Requests and tokens are counted per provider.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::events::{self, AppEvent};
+use crate::{bandwidth, outbound, storage};
+
+const AI_FILE: &str = "ai-provider.json";
+const ROUTING_FILE: &str = "ai-routing.json";
+const USAGE_FILE: &str = "ai-usage.json";
+// Usage stats name for the provider from set_ai_config
+const DEFAULT_PROVIDER: &str = "default";
+const STREAM_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct AiConfig {
+    pub endpoint: String,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://api.openai.com/v1/chat/completions".to_string(),
+            model: "gpt-5".to_string(),
+            api_key: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AiStatus {
+    endpoint: String,
+    model: String,
+    configured: bool,
+}
+
+// What a request is for; routing rules pick providers per task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AiTask {
+    // Conversational answers, omnibox and plugin prompts
+    Chat,
+    Summary,
+    // Structured output such as workflow generation
+    Extraction,
+    // Prompts with images; the model must accept image input
+    Vision,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AiProvider {
+    name: String,
+    endpoint: String,
+    model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RoutingRule {
+    task: AiTask,
+    // Provider names tried first, in order
+    providers: Vec<String>,
+    // Without this the remaining providers follow as fallbacks
+    #[serde(default)]
+    exclusive: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct AiRouting {
+    // Tried in order; when empty only the provider from set_ai_config is used
+    providers: Vec<AiProvider>,
+    rules: Vec<RoutingRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ProviderStatus {
+    name: String,
+    endpoint: String,
+    model: String,
+    configured: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RoutingStatus {
+    providers: Vec<ProviderStatus>,
+    rules: Vec<RoutingRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ProviderUsage {
+    model: String,
+    requests: u64,
+    failures: u64,
+    // Requests handed to the next provider after a rate limit or outage
+    failovers: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    last_error: Option<String>,
+    last_used: Option<i64>,
+}
+
+#[derive(Default)]
+pub struct AiState {
+    config: Mutex<AiConfig>,
+    routing: Mutex<AiRouting>,
+    // Provider name -> usage
+    usage: Mutex<HashMap<String, ProviderUsage>>,
+}
+
+impl AiState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, AI_FILE)),
+            routing: Mutex::new(storage::load(app, ROUTING_FILE)),
+            usage: Mutex::new(storage::load(app, USAGE_FILE)),
+        }
+    }
+}
+
+fn api_key(key: &Option<String>) -> Option<String> {
+    key.clone()
+        .filter(|k| !k.is_empty())
+        .or_else(|| std::env::var("OPENAI_API_KEY").ok())
+}
+
+struct Target {
+    name: String,
+    endpoint: String,
+    model: String,
+    key: Option<String>,
+}
+
+// Providers to try for `task`, in order
+fn targets(app: &AppHandle, task: AiTask) -> Vec<Target> {
+    let state = app.state::<AiState>();
+    let routing = state.routing.lock().unwrap();
+    if routing.providers.is_empty() {
+        let config = state.config.lock().unwrap();
+        return vec![Target {
+            name: DEFAULT_PROVIDER.to_string(),
+            endpoint: config.endpoint.clone(),
+            model: config.model.clone(),
+            key: api_key(&config.api_key),
+        }];
+    }
+    let rule = routing.rules.iter().find(|r| r.task == task);
+    let mut order: Vec<&AiProvider> = rule
+        .map(|r| {
+            r.providers
+                .iter()
+                .filter_map(|name| routing.providers.iter().find(|p| &p.name == name))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !rule.map_or(false, |r| r.exclusive) {
+        for provider in &routing.providers {
+            if !order.iter().any(|p| p.name == provider.name) {
+                order.push(provider);
+            }
+        }
+    }
+    order
+        .into_iter()
+        .map(|p| Target {
+            name: p.name.clone(),
+            endpoint: p.endpoint.clone(),
+            model: p.model.clone(),
+            key: api_key(&p.api_key),
+        })
+        .collect()
+}
+
+pub fn is_configured(app: &AppHandle) -> bool {
+    targets(app, AiTask::Chat).iter().any(|t| t.key.is_some())
+}
+
+fn record_usage(app: &AppHandle, target: &Target, update: impl FnOnce(&mut ProviderUsage)) {
+    let state = app.state::<AiState>();
+    let mut usage = state.usage.lock().unwrap();
+    let entry = usage.entry(target.name.clone()).or_default();
+    entry.model = target.model.clone();
+    entry.last_used = Some(chrono::Utc::now().timestamp_millis());
+    update(entry);
+    let _ = storage::save(app, USAGE_FILE, &*usage);
+}
+
+fn record_tokens(app: &AppHandle, target: &Target, usage: &Value) {
+    let count = |key: &str| usage[key].as_u64().unwrap_or(0);
+    let (prompt, completion) = (count("prompt_tokens"), count("completion_tokens"));
+    if prompt + completion > 0 {
+        record_usage(app, target, |u| {
+            u.prompt_tokens += prompt;
+            u.completion_tokens += completion;
+        });
+    }
+}
+
+// Send a chat completions request to the first provider for `task` that
+// answers, moving on after a rate limit or outage; `extra` is merged into the
+// request body
+async fn send(
+    app: &AppHandle,
+    task: AiTask,
+    action: &str,
+    messages: Value,
+    extra: Option<Value>,
+    timeout: Duration,
+) -> Result<(reqwest::Response, Target), String> {
+    let targets: Vec<Target> = targets(app, task).into_iter().filter(|t| t.key.is_some()).collect();
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut last_error = "No AI provider configured".to_string();
+    let count = targets.len();
+    for (index, target) in targets.into_iter().enumerate() {
+        audit::record(
+            app,
+            AuditCategory::AiTool,
+            action,
+            json!({ "provider": target.name, "endpoint": target.endpoint, "model": target.model }),
+        );
+        let mut body = json!({ "model": target.model, "messages": messages });
+        if let (Some(Value::Object(extra)), Some(body)) = (extra.clone(), body.as_object_mut()) {
+            body.extend(extra);
+        }
+        let request = client
+            .post(&target.endpoint)
+            .bearer_auth(target.key.as_deref().unwrap_or_default())
+            .json(&body);
+        let limit_key = format!("ai:{}", target.name);
+        // Retrying only pays off on the last provider; before that, fail over
+        let last = index + 1 == count;
+        let result = if last {
+            outbound::send(app, &limit_key, request).await
+        } else {
+            outbound::send_once(app, &limit_key, request).await
+        };
+        let error = match result {
+            Ok(response) if response.status().is_success() => {
+                record_usage(app, &target, |u| u.requests += 1);
+                return Ok((response, target));
+            }
+            Ok(response) if outbound::retryable(response.status()) => {
+                format!("{} returned {}", target.name, response.status())
+            }
+            Ok(response) => {
+                // Bad requests and auth errors would fail the same way elsewhere
+                let error = format!("{} returned {}", target.name, response.status());
+                record_usage(app, &target, |u| {
+                    u.requests += 1;
+                    u.failures += 1;
+                    u.last_error = Some(error.clone());
+                });
+                return Err(error);
+            }
+            Err(e) => format!("{}: {}", target.name, e),
+        };
+        record_usage(app, &target, |u| {
+            u.requests += 1;
+            u.failures += 1;
+            if !last {
+                u.failovers += 1;
+            }
+            u.last_error = Some(error.clone());
+        });
+        last_error = error;
+    }
+    Err(last_error)
+}
+
+// Single-turn completion; returns the assistant's text
+async fn chat(
+    app: &AppHandle,
+    task: AiTask,
+    action: &str,
+    messages: Value,
+    extra: Option<Value>,
+    timeout: Duration,
+) -> Result<String, String> {
+    let (response, target) = send(app, task, action, messages, extra, timeout).await?;
+    let response: Value = response.json().await.map_err(|e| e.to_string())?;
+    record_tokens(app, &target, &response["usage"]);
+
+    response["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "AI provider returned no content".to_string())
+}
+
+pub async fn complete(
+    app: &AppHandle,
+    task: AiTask,
+    system: &str,
+    prompt: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let messages = json!([
+        { "role": "system", "content": system },
+        { "role": "user", "content": prompt },
+    ]);
+    chat(app, task, "ai.complete", messages, None, timeout).await
+}
+
+// Models sometimes wrap JSON in a Markdown code fence despite instructions
+pub fn parse_json_reply(reply: &str) -> Result<Value, String> {
+    let trimmed = reply.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed);
+    serde_json::from_str(unfenced.trim()).map_err(|e| format!("Model did not return valid JSON: {}", e))
+}
+
+// Message content part carrying a PNG image
+pub fn image_part(png: &[u8]) -> Value {
+    let image = format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+    );
+    json!({ "type": "image_url", "image_url": { "url": image, "detail": "high" } })
+}
+
+// Ask a vision-capable model about a PNG image, constraining the reply to a
+// JSON schema where the provider supports structured outputs
+pub async fn complete_vision(
+    app: &AppHandle,
+    system: &str,
+    prompt: &str,
+    png: &[u8],
+    schema: &Value,
+    timeout: Duration,
+) -> Result<String, String> {
+    let messages = json!([
+        { "role": "system", "content": system },
+        { "role": "user", "content": [
+            { "type": "text", "text": prompt },
+            image_part(png),
+        ] },
+    ]);
+    let format = json!({
+        "response_format": {
+            "type": "json_schema",
+            "json_schema": { "name": "extraction", "schema": schema, "strict": false },
+        },
+    });
+    chat(app, AiTask::Vision, "ai.vision", messages, Some(format), timeout).await
+}
+
+// Streamed completion: each content delta is published as AiTokenReceived
+// under `request_id`, and the full text is returned at the end
+pub async fn stream(
+    app: &AppHandle,
+    task: AiTask,
+    action: &str,
+    messages: Value,
+    request_id: &str,
+) -> Result<String, String> {
+    let extra = Some(json!({ "stream": true }));
+    let (mut response, target) = send(app, task, action, messages, extra, STREAM_TIMEOUT).await?;
+    // Event streams have no Content-Length, so the bytes are counted as they arrive
+    let url = response.url().to_string();
+    let counted = response.content_length().is_some();
+    let mut received = 0;
+
+    // Server-sent events: `data: {...}` lines, ending with `data: [DONE]`
+    // Bytes are buffered until a full line, so characters split across chunks survive
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut text = String::new();
+    'chunks: while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        received += chunk.len() as u64;
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            let Some(data) = line.strip_prefix("data:").map(str::trim) else { continue };
+            if data == "[DONE]" {
+                break 'chunks;
+            }
+            let Ok(event) = serde_json::from_str::<Value>(data) else { continue };
+            // Some providers report usage on the last chunk
+            if event["usage"].is_object() {
+                record_tokens(app, &target, &event["usage"]);
+            }
+            if let Some(token) = event["choices"][0]["delta"]["content"].as_str().filter(|t| !t.is_empty()) {
+                text.push_str(token);
+                events::publish(
+                    app,
+                    AppEvent::AiTokenReceived {
+                        request_id: request_id.to_string(),
+                        token: token.to_string(),
+                    },
+                );
+            }
+        }
+    }
+    if !counted {
+        bandwidth::record_received(app, &url, received);
+    }
+    Ok(text)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn stream_ai_completion(
+    app_handle: AppHandle,
+    request_id: String,
+    system: Option<String>,
+    prompt: String,
+) -> Result<String, String> {
+    let mut messages = Vec::new();
+    if let Some(system) = system.filter(|s| !s.trim().is_empty()) {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+    messages.push(json!({ "role": "user", "content": prompt }));
+    stream(&app_handle, AiTask::Chat, "ai.stream", Value::Array(messages), &request_id).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_ai_config(state: tauri::State<'_, AiState>) -> Result<AiStatus, String> {
+    let config = state.config.lock().unwrap();
+    Ok(AiStatus {
+        endpoint: config.endpoint.clone(),
+        model: config.model.clone(),
+        configured: api_key(&config.api_key).is_some(),
+    })
+}
+
+// A missing api_key keeps the stored one
+#[tauri::command]
+#[specta::specta]
+pub async fn set_ai_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, AiState>,
+    config: AiConfig,
+) -> Result<(), String> {
+    url::Url::parse(&config.endpoint).map_err(|e| format!("Invalid endpoint: {}", e))?;
+    if config.model.trim().is_empty() {
+        return Err("Model cannot be empty".to_string());
+    }
+
+    audit::record(
+        &app_handle,
+        AuditCategory::Settings,
+        "ai.config",
+        json!({ "endpoint": config.endpoint, "model": config.model, "api_key_changed": config.api_key.is_some() }),
+    );
+    let mut current = state.config.lock().unwrap();
+    let api_key = config.api_key.or_else(|| current.api_key.clone());
+    let config = AiConfig { api_key, ..config };
+    storage::save(&app_handle, AI_FILE, &config)?;
+    *current = config;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_ai_routing(state: tauri::State<'_, AiState>) -> Result<RoutingStatus, String> {
+    let routing = state.routing.lock().unwrap();
+    Ok(RoutingStatus {
+        providers: routing
+            .providers
+            .iter()
+            .map(|p| ProviderStatus {
+                name: p.name.clone(),
+                endpoint: p.endpoint.clone(),
+                model: p.model.clone(),
+                configured: api_key(&p.api_key).is_some(),
+            })
+            .collect(),
+        rules: routing.rules.clone(),
+    })
+}
+
+// A provider without api_key keeps the stored key of the same name
+#[tauri::command]
+#[specta::specta]
+pub async fn set_ai_routing(
+    app_handle: AppHandle,
+    state: tauri::State<'_, AiState>,
+    mut routing: AiRouting,
+) -> Result<(), String> {
+    for (index, provider) in routing.providers.iter().enumerate() {
+        if provider.name.trim().is_empty() || provider.model.trim().is_empty() {
+            return Err("Providers need a name and a model".to_string());
+        }
+        if routing.providers[..index].iter().any(|p| p.name == provider.name) {
+            return Err(format!("Duplicate provider: {}", provider.name));
+        }
+        url::Url::parse(&provider.endpoint).map_err(|e| format!("Invalid endpoint for {}: {}", provider.name, e))?;
+    }
+    for rule in &routing.rules {
+        if let Some(name) = rule.providers.iter().find(|n| !routing.providers.iter().any(|p| &p.name == *n)) {
+            return Err(format!("Routing rule refers to unknown provider {}", name));
+        }
+        if rule.exclusive && rule.providers.is_empty() {
+            return Err("An exclusive routing rule needs at least one provider".to_string());
+        }
+    }
+
+    let mut current = state.routing.lock().unwrap();
+    for provider in routing.providers.iter_mut() {
+        if provider.api_key.is_none() {
+            provider.api_key = current
+                .providers
+                .iter()
+                .find(|p| p.name == provider.name)
+                .and_then(|p| p.api_key.clone());
+        }
+    }
+    let providers: Vec<Value> = routing
+        .providers
+        .iter()
+        .map(|p| json!({ "name": p.name, "endpoint": p.endpoint, "model": p.model }))
+        .collect();
+    audit::record(
+        &app_handle,
+        AuditCategory::Settings,
+        "ai.routing",
+        json!({ "providers": providers, "rules": routing.rules.len() }),
+    );
+    storage::save(&app_handle, ROUTING_FILE, &routing)?;
+    *current = routing;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_ai_usage(state: tauri::State<'_, AiState>) -> Result<HashMap<String, ProviderUsage>, String> {
+    Ok(state.usage.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn reset_ai_usage(app_handle: AppHandle, state: tauri::State<'_, AiState>) -> Result<(), String> {
+    let mut usage = state.usage.lock().unwrap();
+    usage.clear();
+    storage::save(&app_handle, USAGE_FILE, &*usage)
+}
@@ -0,0 +1,221 @@
+// Internal pages over app://
+// Settings, the new tab page and the workflow editor are routes of the bundled
+// frontend, served from the app's embedded assets so they load without the
+// server. Generated content is served from Rust: page thumbnails
+// (`/thumbnails?url=...`), saved reading-list articles (`/reading/<id>`) and
+// the crash page (`/crash?url=...&reason=...`), which pool windows switch to
+// when their web content process dies, and the safe mode recovery page
+// (`/recovery`). Anything not bundled (e.g. in development builds) redirects
+// to the server URL.
+
+use sha2::{Digest, Sha256};
+use tauri::http::{Request, Response, ResponseBuilder};
+use tauri::{AppHandle, Window};
+
+use crate::{readinglist, server, thumbnails};
+
+pub const APP_SCHEME: &str = "app";
+const CRASH_PAGE: &str = include_str!("../assets/crash.html");
+const RECOVERY_PAGE: &str = include_str!("../assets/recovery.html");
+// Frontend routes that are answered with index.html
+const PAGE_ROUTES: [&str; 4] = ["/", "/settings", "/newtab", "/workflows"];
+const NO_CACHE: &str = "no-cache";
+// Bundler output under /assets/ has content hashes in its file names
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const THUMBNAIL_CACHE: &str = "private, max-age=3600";
+
+pub fn app_url(path: &str) -> String {
+    // WebView2 only serves custom schemes through the https://<scheme>.localhost form
+    if cfg!(windows) {
+        format!("https://{}.localhost{}", APP_SCHEME, path)
+    } else {
+        format!("{}://localhost{}", APP_SCHEME, path)
+    }
+}
+
+fn crash_url(page_url: &str, reason: &str) -> String {
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("url", page_url)
+        .append_pair("reason", reason)
+        .finish();
+    app_url(&format!("/crash?{}", query))
+}
+
+// Called on every window built by windowpool::build_window
+pub fn attach(window: &Window) {
+    if let Err(e) = platform::watch_crashes(window) {
+        eprintln!("Failed to install crash handler: {}", e);
+    }
+}
+
+fn mime_for(path: &str) -> &'static str {
+    let extension = path.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "html" => "text/html",
+        "js" | "mjs" => "text/javascript",
+        "css" => "text/css",
+        "json" | "map" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "wasm" => "application/wasm",
+        "txt" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+fn respond(
+    request: &Request,
+    bytes: Vec<u8>,
+    mime: &str,
+    cache_control: &str,
+) -> Result<Response, Box<dyn std::error::Error>> {
+    let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&bytes)[..16]));
+    let unchanged = request
+        .headers()
+        .get("if-none-match")
+        .and_then(|v| v.to_str().ok())
+        .map_or(false, |v| v.split(',').any(|tag| tag.trim() == etag));
+    let builder = ResponseBuilder::new()
+        .header("Cache-Control", cache_control)
+        .header("ETag", &etag)
+        .header("X-Content-Type-Options", "nosniff");
+    if unchanged {
+        return builder.status(304).body(Vec::new());
+    }
+    builder.mimetype(mime).status(200).body(bytes)
+}
+
+fn not_found(message: &str) -> Result<Response, Box<dyn std::error::Error>> {
+    ResponseBuilder::new()
+        .mimetype("text/plain")
+        .status(404)
+        .body(message.as_bytes().to_vec())
+}
+
+fn bundled(app: &AppHandle, request: &Request, path: &str) -> Result<Response, Box<dyn std::error::Error>> {
+    let asset_path = if PAGE_ROUTES.contains(&path) { "/index.html" } else { path };
+    if let Some(asset) = app.asset_resolver().get(asset_path.to_string()) {
+        let cache_control = if asset_path.starts_with("/assets/") { IMMUTABLE } else { NO_CACHE };
+        let mime = if asset.mime_type.is_empty() { mime_for(asset_path) } else { &asset.mime_type };
+        return respond(request, asset.bytes, mime, cache_control);
+    }
+    // Development builds don't embed the frontend
+    ResponseBuilder::new()
+        .header("Location", format!("{}{}", server::url(app), path))
+        .header("Cache-Control", NO_CACHE)
+        .status(302)
+        .body(Vec::new())
+}
+
+pub fn serve(app: &AppHandle, request: &Request) -> Result<Response, Box<dyn std::error::Error>> {
+    let Ok(uri) = url::Url::parse(request.uri()) else {
+        return not_found("Invalid address");
+    };
+    let path = uri.path();
+    let query = |key: &str| uri.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.into_owned());
+
+    if path == "/crash" {
+        return respond(request, CRASH_PAGE.as_bytes().to_vec(), "text/html", NO_CACHE);
+    }
+    // Shown in the main window in safe mode (see safemode.rs)
+    if path == "/recovery" {
+        return respond(request, RECOVERY_PAGE.as_bytes().to_vec(), "text/html", NO_CACHE);
+    }
+    if path == "/thumbnails" {
+        return match query("url").and_then(|url| thumbnails::cached(app, &url)) {
+            Some(jpeg) => respond(request, jpeg, "image/jpeg", THUMBNAIL_CACHE),
+            None => not_found("No thumbnail for this page"),
+        };
+    }
+    if let Some(id) = path.strip_prefix("/reading/") {
+        let Ok(id) = id.parse::<i64>() else {
+            return not_found("Unknown article");
+        };
+        return match readinglist::render(app, id) {
+            Ok(html) => respond(request, html.into_bytes(), "text/html", NO_CACHE),
+            Err(e) => not_found(&e),
+        };
+    }
+    // Paths with ".." never reach here as Url::parse normalizes them
+    bundled(app, request, path)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::Window;
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED, COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE,
+    };
+    use webview2_com::ProcessFailedEventHandler;
+    use windows_webview2::core::{HSTRING, PWSTR};
+    use windows_webview2::Win32::System::WinRT::EventRegistrationToken;
+
+    pub fn watch_crashes(window: &Window) -> Result<(), String> {
+        window
+            .with_webview(|webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let core = webview.controller().CoreWebView2()?;
+                    let handler = ProcessFailedEventHandler::create(Box::new(|core, args| {
+                        let (Some(core), Some(args)) = (core, args) else { return Ok(()) };
+                        let mut kind = Default::default();
+                        args.ProcessFailedKind(&mut kind)?;
+                        let reason = match kind {
+                            COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED => "The page's process exited",
+                            COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE => "The page stopped responding",
+                            // Subframe and helper process failures leave the page usable
+                            _ => return Ok(()),
+                        };
+                        let mut uri = PWSTR::null();
+                        core.Source(&mut uri)?;
+                        let url = webview2_com::take_pwstr(uri);
+                        core.Navigate(&HSTRING::from(super::crash_url(&url, reason)))
+                    }));
+                    let mut token = EventRegistrationToken::default();
+                    core.add_ProcessFailed(&handler, &mut token)
+                })();
+                if let Err(e) = result {
+                    eprintln!("Failed to install crash handler: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use tauri::Window;
+    use webkit2gtk::{WebProcessTerminationReason, WebViewExt};
+
+    pub fn watch_crashes(window: &Window) -> Result<(), String> {
+        window
+            .with_webview(|webview| {
+                webview.inner().connect_web_process_terminated(|view, reason| {
+                    let reason = match reason {
+                        WebProcessTerminationReason::ExceededMemoryLimit => "The page used too much memory",
+                        WebProcessTerminationReason::TerminatedByApi => return,
+                        _ => "The page's process exited",
+                    };
+                    let url = view.uri().map(|u| u.to_string()).unwrap_or_default();
+                    view.load_uri(&super::crash_url(&url, reason));
+                });
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    use tauri::Window;
+
+    // WKWebView reloads crashed content itself
+    pub fn watch_crashes(_window: &Window) -> Result<(), String> {
+        Ok(())
+    }
+}
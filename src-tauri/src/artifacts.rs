@@ -0,0 +1,408 @@
+// Artifact store for workflow run outputs
+// Datasets, screenshots, logs and HAR files are copied under
+// `<app data>/artifacts/<run id>/` and indexed in SQLite. An artifact name is
+// versioned across runs of the same workflow. Retention rules (per workflow,
+// with a default) are applied after every save and by `prune_artifacts`.
+
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::db::Database;
+use crate::storage;
+use crate::workflow::{self, RunReport};
+
+const RETENTION_FILE: &str = "artifact-retention.json";
+const ARTIFACTS_DIR: &str = "artifacts";
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS artifacts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL,
+    workflow_name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS artifacts_run ON artifacts (run_id);
+CREATE INDEX IF NOT EXISTS artifacts_workflow ON artifacts (workflow_name, created_at);
+";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    Dataset,
+    Screenshot,
+    Log,
+    Har,
+    Other,
+}
+
+impl ArtifactKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ArtifactKind::Dataset => "dataset",
+            ArtifactKind::Screenshot => "screenshot",
+            ArtifactKind::Log => "log",
+            ArtifactKind::Har => "har",
+            ArtifactKind::Other => "other",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "dataset" => ArtifactKind::Dataset,
+            "screenshot" => ArtifactKind::Screenshot,
+            "log" => ArtifactKind::Log,
+            "har" => ArtifactKind::Har,
+            _ => ArtifactKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Artifact {
+    id: i64,
+    run_id: String,
+    workflow_name: String,
+    kind: ArtifactKind,
+    name: String,
+    version: i64,
+    path: String,
+    size: i64,
+    sha256: String,
+    created_at: i64,
+}
+
+impl Artifact {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            run_id: row.get(1)?,
+            workflow_name: row.get(2)?,
+            kind: ArtifactKind::parse(&row.get::<_, String>(3)?),
+            name: row.get(4)?,
+            version: row.get(5)?,
+            path: row.get(6)?,
+            size: row.get(7)?,
+            sha256: row.get(8)?,
+            created_at: row.get(9)?,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, run_id, workflow_name, kind, name, version, path, size, sha256, created_at FROM artifacts";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct RetentionPolicy {
+    // Keep artifacts of the newest N runs
+    #[serde(default)]
+    keep_runs: Option<usize>,
+    #[serde(default)]
+    max_age_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RetentionRules {
+    default: RetentionPolicy,
+    // Workflow name -> policy overriding the default
+    #[serde(default)]
+    workflows: HashMap<String, RetentionPolicy>,
+}
+
+impl Default for RetentionRules {
+    fn default() -> Self {
+        Self {
+            default: RetentionPolicy {
+                keep_runs: Some(20),
+                max_age_days: Some(90),
+            },
+            workflows: HashMap::new(),
+        }
+    }
+}
+
+impl RetentionRules {
+    fn policy(&self, workflow_name: &str) -> &RetentionPolicy {
+        self.workflows.get(workflow_name).unwrap_or(&self.default)
+    }
+}
+
+#[derive(Default)]
+pub struct ArtifactState {
+    rules: Mutex<RetentionRules>,
+}
+
+impl ArtifactState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            rules: Mutex::new(storage::load(app, RETENTION_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct PruneResult {
+    removed: usize,
+    freed_bytes: u64,
+}
+
+// The workflow action: store a file, or the run's rows as CSV when no path is given
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveArtifactAction {
+    pub kind: ArtifactKind,
+    pub name: String,
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+pub fn safe_name(value: &str) -> String {
+    let name: String = value
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "artifact".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn run_dir(app: &AppHandle, run_id: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "App data directory unavailable".to_string())?
+        .join(ARTIFACTS_DIR)
+        .join(safe_name(run_id));
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+// Store `bytes` as a new version of the named artifact
+pub fn save(
+    app: &AppHandle,
+    run_id: &str,
+    workflow_name: &str,
+    kind: ArtifactKind,
+    name: &str,
+    bytes: &[u8],
+) -> Result<Artifact, String> {
+    let db = app.state::<Database>();
+    let version: i64 = db.with(|conn| {
+        conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) + 1 FROM artifacts WHERE workflow_name = ?1 AND name = ?2",
+            params![workflow_name, name],
+            |row| row.get(0),
+        )
+    })?;
+
+    let path = run_dir(app, run_id)?.join(format!("v{}-{}", version, safe_name(name)));
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+
+    let path_text = path.to_string_lossy().to_string();
+    let sha256 = hex::encode(Sha256::digest(bytes));
+    let created_at = chrono::Utc::now().timestamp();
+    let id = db.with(|conn| {
+        conn.execute(
+            "INSERT INTO artifacts (run_id, workflow_name, kind, name, version, path, size, sha256, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                run_id,
+                workflow_name,
+                kind.as_str(),
+                name,
+                version,
+                path_text,
+                bytes.len() as i64,
+                sha256,
+                created_at
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    })?;
+
+    prune_workflow(app, workflow_name)?;
+    Ok(Artifact {
+        id,
+        run_id: run_id.to_string(),
+        workflow_name: workflow_name.to_string(),
+        kind,
+        name: name.to_string(),
+        version,
+        path: path_text,
+        size: bytes.len() as i64,
+        sha256,
+        created_at,
+    })
+}
+
+fn remove(db: &Database, artifacts: &[Artifact], result: &mut PruneResult) -> Result<(), String> {
+    for artifact in artifacts {
+        let path = Path::new(&artifact.path);
+        if path.exists() {
+            std::fs::remove_file(path).map_err(|e| e.to_string())?;
+        }
+        // Drop the run directory once its last artifact is gone
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::remove_dir(dir);
+        }
+        db.with(|conn| conn.execute("DELETE FROM artifacts WHERE id = ?1", params![artifact.id]))?;
+        result.removed += 1;
+        result.freed_bytes += artifact.size.max(0) as u64;
+    }
+    Ok(())
+}
+
+fn prune_workflow(app: &AppHandle, workflow_name: &str) -> Result<PruneResult, String> {
+    let policy = app.state::<ArtifactState>().rules.lock().unwrap().policy(workflow_name).clone();
+    let db = app.state::<Database>();
+    let artifacts: Vec<Artifact> = db.with(|conn| {
+        let mut stmt = conn.prepare(&format!("{} WHERE workflow_name = ?1 ORDER BY created_at DESC, id DESC", SELECT_COLUMNS))?;
+        let rows = stmt.query_map(params![workflow_name], Artifact::from_row)?;
+        rows.collect()
+    })?;
+
+    let cutoff = policy
+        .max_age_days
+        .map(|days| chrono::Utc::now().timestamp() - days * 86_400);
+    let mut runs: Vec<&str> = Vec::new();
+    let mut expired = Vec::new();
+    for artifact in &artifacts {
+        if !runs.contains(&artifact.run_id.as_str()) {
+            runs.push(&artifact.run_id);
+        }
+        let too_many = policy.keep_runs.map_or(false, |keep| runs.len() > keep);
+        let too_old = cutoff.map_or(false, |cutoff| artifact.created_at < cutoff);
+        if too_many || too_old {
+            expired.push(artifact.clone());
+        }
+    }
+
+    let mut result = PruneResult::default();
+    remove(&db, &expired, &mut result)?;
+    Ok(result)
+}
+
+pub async fn run_action(app: &AppHandle, action: &SaveArtifactAction, report: &RunReport) -> Result<Value, String> {
+    let bytes = match &action.path {
+        Some(path) => std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path, e))?,
+        None => workflow::rows_to_csv(&report.rows)?,
+    };
+    let artifact = save(app, &report.run_id, &report.workflow_name, action.kind, &action.name, &bytes)?;
+    Ok(json!(artifact))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn save_artifact(
+    app_handle: AppHandle,
+    run_id: String,
+    workflow_name: String,
+    kind: ArtifactKind,
+    name: String,
+    path: Option<String>,
+    content: Option<String>,
+) -> Result<Artifact, String> {
+    let bytes = match (path, content) {
+        (Some(path), _) => std::fs::read(&path).map_err(|e| format!("Cannot read {}: {}", path, e))?,
+        (None, Some(content)) => content.into_bytes(),
+        (None, None) => return Err("Either a path or content is required".to_string()),
+    };
+    save(&app_handle, &run_id, &workflow_name, kind, &name, &bytes)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_artifacts(db: tauri::State<'_, Database>, run_id: String) -> Result<Vec<Artifact>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!("{} WHERE run_id = ?1 ORDER BY created_at, id", SELECT_COLUMNS))?;
+        let rows = stmt.query_map(params![run_id], Artifact::from_row)?;
+        rows.collect()
+    })
+}
+
+// Files of a run's artifacts that are still on disk, oldest first
+pub fn run_paths(app: &AppHandle, run_id: &str) -> Result<Vec<PathBuf>, String> {
+    let paths: Vec<String> = app.state::<Database>().with(|conn| {
+        let mut stmt = conn.prepare("SELECT path FROM artifacts WHERE run_id = ?1 ORDER BY created_at, id")?;
+        let rows = stmt.query_map(params![run_id], |row| row.get(0))?;
+        rows.collect()
+    })?;
+    Ok(paths.into_iter().map(PathBuf::from).filter(|p| p.is_file()).collect())
+}
+
+// Open the artifact with the system's default application
+#[tauri::command]
+#[specta::specta]
+pub async fn open_artifact(db: tauri::State<'_, Database>, artifact_id: i64) -> Result<(), String> {
+    let artifact = db
+        .with(|conn| {
+            conn.query_row(&format!("{} WHERE id = ?1", SELECT_COLUMNS), params![artifact_id], Artifact::from_row)
+                .optional()
+        })?
+        .ok_or_else(|| format!("Artifact not found: {}", artifact_id))?;
+    if !Path::new(&artifact.path).is_file() {
+        return Err(format!("Artifact file is missing: {}", artifact.path));
+    }
+    tauri::api::shell::open(&tauri::api::shell::Scope::default(), &artifact.path, None).map_err(|e| e.to_string())
+}
+
+// Apply retention rules to one workflow, or to all of them
+#[tauri::command]
+#[specta::specta]
+pub async fn prune_artifacts(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    workflow_name: Option<String>,
+) -> Result<PruneResult, String> {
+    let workflows: Vec<String> = match workflow_name {
+        Some(name) => vec![name],
+        None => db.with(|conn| {
+            let mut stmt = conn.prepare("SELECT DISTINCT workflow_name FROM artifacts")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect()
+        })?,
+    };
+
+    let mut total = PruneResult::default();
+    for workflow_name in workflows {
+        let result = prune_workflow(&app_handle, &workflow_name)?;
+        total.removed += result.removed;
+        total.freed_bytes += result.freed_bytes;
+    }
+    Ok(total)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_retention_rules(state: tauri::State<'_, ArtifactState>) -> Result<RetentionRules, String> {
+    Ok(state.rules.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_retention_rules(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ArtifactState>,
+    rules: RetentionRules,
+) -> Result<(), String> {
+    storage::save(&app_handle, RETENTION_FILE, &rules)?;
+    audit::record(&app_handle, AuditCategory::Settings, "artifacts.retention", json!(rules));
+    *state.rules.lock().unwrap() = rules;
+    Ok(())
+}
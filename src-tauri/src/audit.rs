@@ -0,0 +1,238 @@
+// Append-only audit log of sensitive actions
+// Credential use, AI calls, exports and settings changes are recorded in
+// SQLite. Each entry's hash covers the previous entry's hash, so editing or
+// removing a row breaks the chain; triggers reject UPDATE and DELETE outright.
+// The chain is verified at startup and on demand.
+
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::i18n;
+use crate::notifications::{self, Notice, NotificationCategory};
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    category TEXT NOT NULL,
+    action TEXT NOT NULL,
+    details TEXT NOT NULL,
+    prev_hash TEXT NOT NULL,
+    hash TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS audit_log_category ON audit_log (category, timestamp);
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END;
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END;
+";
+
+// Hash the first entry chains onto
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCategory {
+    Credential,
+    AiTool,
+    Export,
+    Settings,
+    Automation,
+}
+
+impl AuditCategory {
+    fn as_str(self) -> &'static str {
+        match self {
+            AuditCategory::Credential => "credential",
+            AuditCategory::AiTool => "ai_tool",
+            AuditCategory::Export => "export",
+            AuditCategory::Settings => "settings",
+            AuditCategory::Automation => "automation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AuditEntry {
+    id: i64,
+    timestamp: i64,
+    category: String,
+    action: String,
+    details: Value,
+    prev_hash: String,
+    hash: String,
+}
+
+impl AuditEntry {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let details: String = row.get(4)?;
+        Ok(Self {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            category: row.get(2)?,
+            action: row.get(3)?,
+            details: serde_json::from_str(&details).unwrap_or(Value::String(details)),
+            prev_hash: row.get(5)?,
+            hash: row.get(6)?,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, timestamp, category, action, details, prev_hash, hash FROM audit_log";
+
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    #[serde(default)]
+    category: Option<AuditCategory>,
+    // Substring of the action name, e.g. "smtp"
+    #[serde(default)]
+    action: Option<String>,
+    #[serde(default)]
+    since: Option<i64>,
+    #[serde(default)]
+    until: Option<i64>,
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AuditVerification {
+    intact: bool,
+    checked: u64,
+    // First entry whose hash or link does not match
+    first_broken_id: Option<i64>,
+}
+
+fn entry_hash(prev_hash: &str, timestamp: i64, category: &str, action: &str, details: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [prev_hash, &timestamp.to_string(), category, action, details] {
+        hasher.update(part.as_bytes());
+        hasher.update([0x1f]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn append(db: &Database, category: AuditCategory, action: &str, details: &Value) -> Result<(), String> {
+    let details = details.to_string();
+    db.with(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        let prev_hash: String = tx
+            .query_row("SELECT hash FROM audit_log ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+            .optional()?
+            .unwrap_or_else(|| GENESIS_HASH.to_string());
+        let timestamp = chrono::Utc::now().timestamp();
+        let hash = entry_hash(&prev_hash, timestamp, category.as_str(), action, &details);
+        tx.execute(
+            "INSERT INTO audit_log (timestamp, category, action, details, prev_hash, hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![timestamp, category.as_str(), action, details, prev_hash, hash],
+        )?;
+        tx.commit()
+    })
+}
+
+// Record a sensitive action. Never fails the action itself; errors are logged.
+// `details` must not contain secrets or customer data, only what identifies the action.
+pub fn record(app: &AppHandle, category: AuditCategory, action: &str, details: Value) {
+    if let Err(e) = append(&app.state::<Database>(), category, action, &details) {
+        eprintln!("Failed to write audit log entry {}: {}", action, e);
+    }
+}
+
+pub fn verify(db: &Database) -> Result<AuditVerification, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!("{} ORDER BY id", SELECT_COLUMNS))?;
+        let mut rows = stmt.query([])?;
+        let mut expected_prev = GENESIS_HASH.to_string();
+        let mut checked = 0;
+
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let timestamp: i64 = row.get(1)?;
+            let category: String = row.get(2)?;
+            let action: String = row.get(3)?;
+            let details: String = row.get(4)?;
+            let prev_hash: String = row.get(5)?;
+            let hash: String = row.get(6)?;
+
+            let valid = prev_hash == expected_prev
+                && hash == entry_hash(&prev_hash, timestamp, &category, &action, &details);
+            if !valid {
+                return Ok(AuditVerification {
+                    intact: false,
+                    checked,
+                    first_broken_id: Some(id),
+                });
+            }
+            checked += 1;
+            expected_prev = hash;
+        }
+
+        Ok(AuditVerification {
+            intact: true,
+            checked,
+            first_broken_id: None,
+        })
+    })
+}
+
+// Verify the chain in the background and raise a notification if it is broken
+// Runs on the deferred startup thread
+pub fn verify_on_startup(app: &AppHandle) {
+    match verify(&app.state::<Database>()) {
+        Ok(result) if !result.intact => {
+            let body = i18n::text_with(
+                app,
+                "notice-audit-broken-body",
+                &[("id", result.first_broken_id.unwrap_or_default().into())],
+            );
+            let title = i18n::text(app, "notice-audit-broken-title");
+            let notice = Notice::new(NotificationCategory::System, title, body);
+            let _ = notifications::notify(app, notice.owned_by("audit"));
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to verify audit log: {}", e),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn query_audit_log(db: tauri::State<'_, Database>, query: Option<AuditQuery>) -> Result<Vec<AuditEntry>, String> {
+    let query = query.unwrap_or_default();
+    let action = query
+        .action
+        .map(|a| format!("%{}%", a.replace('%', "\\%").replace('_', "\\_")));
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE (?1 IS NULL OR category = ?1)
+               AND (?2 IS NULL OR action LIKE ?2 ESCAPE '\\')
+               AND (?3 IS NULL OR timestamp >= ?3)
+               AND (?4 IS NULL OR timestamp <= ?4)
+             ORDER BY id DESC LIMIT ?5",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                query.category.map(AuditCategory::as_str),
+                action,
+                query.since,
+                query.until,
+                query.limit.unwrap_or(500)
+            ],
+            AuditEntry::from_row,
+        )?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_audit_log(db: tauri::State<'_, Database>) -> Result<AuditVerification, String> {
+    verify(&db)
+}
@@ -0,0 +1,593 @@
+// Encrypted backups of user data
+// A backup is a zip of the database (bookmarks, history, feeds...), the JSON
+// settings, profiles, workflows and scripts, plus the vault and two-factor
+// secrets from the keychain, encrypted with XChaCha20-Poly1305 under a key
+// derived with Argon2id from the user's backup passphrase. The salt is stored
+// in each file header, so a backup restores on any machine given the
+// passphrase; the derived key is kept in the keychain for scheduled backups.
+// The manifest inside records a SHA-256 per file.
+// Restores are verified in full, staged in RESTORE_DIR and moved into place by
+// `apply_pending_restore` on the next start, before any state is loaded.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::{Database, DB_FILE};
+use crate::notifications::{self, Notice, NotificationCategory};
+use crate::power::{self, GuardKind};
+use crate::{battery, i18n, secrets, storage, tasks, totp, vault};
+
+const CONFIG_FILE: &str = "backup.json";
+const BACKUPS_DIR: &str = "backups";
+const RESTORE_DIR: &str = "restore-pending";
+// Salt and derived key of the current passphrase
+const KEY_SECRET: &str = "backup:passphrase-key";
+// Random key of backups made before passphrases; only ever read
+const LEGACY_KEY_SECRET: &str = "backup:key";
+const MAGIC: &[u8] = b"MEBACKUP1";
+// Backup files: FILE_MAGIC, salt, sealed archive
+const FILE_MAGIC: &[u8] = b"MEBACKUP2";
+pub const SALT_LEN: usize = 16;
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+const EXTENSION: &str = "mebackup";
+const MANIFEST_FILE: &str = "manifest.json";
+const SECRETS_FILE: &str = "secrets.json";
+const DATA_PREFIX: &str = "data/";
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+// Directories under app data included besides the top-level JSON files
+const DATA_DIRS: [&str; 3] = ["profiles", "workflows", "scripts"];
+// Decompressed size limits, checked before anything is read into memory
+const MAX_MANIFEST_BYTES: u64 = 16 * 1024 * 1024;
+const MAX_ENTRY_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+const MAX_TOTAL_BYTES: u64 = 8 * 1024 * 1024 * 1024;
+// Keychain prefixes a backup may restore
+const SECRET_PREFIXES: [&str; 2] = ["vault:", "totp:"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct BackupConfig {
+    enabled: bool,
+    interval_hours: u64,
+    // Number of scheduled backups kept; older ones are deleted
+    keep: usize,
+    // Defaults to "backups" in the app data directory
+    directory: Option<String>,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            keep: 7,
+            directory: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BackupState {
+    config: Mutex<BackupConfig>,
+    // Held while a backup or restore runs
+    running: Mutex<()>,
+}
+
+impl BackupState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ManifestEntry {
+    path: String,
+    size: u64,
+    sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupManifest {
+    version: u32,
+    app_version: String,
+    created_at: i64,
+    files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BackupInfo {
+    path: String,
+    file_name: String,
+    size: u64,
+    created_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RestoreReport {
+    created_at: i64,
+    files: usize,
+    secrets: usize,
+    // Files are put in place on the next start
+    restart_required: bool,
+}
+
+fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "App data directory unavailable".to_string())
+}
+
+fn backups_dir(app: &AppHandle, config: &BackupConfig) -> Result<PathBuf, String> {
+    let dir = match &config.directory {
+        Some(dir) if !dir.trim().is_empty() => PathBuf::from(dir),
+        _ => data_dir(app)?.join(BACKUPS_DIR),
+    };
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+pub fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+fn decode_key(hex_key: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(hex_key).map_err(|e| e.to_string())?;
+    bytes.try_into().map_err(|_| "Stored backup key is invalid".to_string())
+}
+
+// Salt and key for new backups; None until a passphrase is set
+fn backup_key() -> Result<Option<(Vec<u8>, [u8; 32])>, String> {
+    let Some(stored) = secrets::get(KEY_SECRET)? else { return Ok(None) };
+    let (salt, key) = stored.split_once(':').ok_or("Stored backup key is invalid")?;
+    Ok(Some((hex::decode(salt).map_err(|e| e.to_string())?, decode_key(key)?)))
+}
+
+// Decrypt a backup file. Without a passphrase only the stored key is tried,
+// which fits backups made here since the passphrase was last set.
+fn open_file(bytes: &[u8], passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+    let Some(rest) = bytes.strip_prefix(FILE_MAGIC) else {
+        if !bytes.starts_with(MAGIC) {
+            return Err("Not a backup file".to_string());
+        }
+        let stored = secrets::get(LEGACY_KEY_SECRET)?.ok_or("This backup was made on another installation")?;
+        return open(&decode_key(&stored)?, bytes);
+    };
+    if rest.len() < SALT_LEN {
+        return Err("Backup file is truncated".to_string());
+    }
+    let (salt, sealed) = rest.split_at(SALT_LEN);
+    let key = match passphrase {
+        Some(passphrase) => derive_key(passphrase, salt)?,
+        None => match backup_key()? {
+            Some((stored_salt, key)) if stored_salt == salt => key,
+            _ => return Err("Enter the passphrase this backup was made with".to_string()),
+        },
+    };
+    open(&key, sealed).map_err(|_| "Wrong passphrase or damaged backup".to_string())
+}
+
+pub fn seal(key: &[u8; 32], plain: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let mut nonce = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let sealed = cipher
+        .encrypt(XNonce::from_slice(&nonce), plain)
+        .map_err(|_| "Encryption failed".to_string())?;
+    Ok([MAGIC, &nonce[..], &sealed[..]].concat())
+}
+
+pub fn open(key: &[u8; 32], bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let rest = bytes.strip_prefix(MAGIC).ok_or("Not a backup file")?;
+    if rest.len() < 24 {
+        return Err("Backup file is truncated".to_string());
+    }
+    let (nonce, sealed) = rest.split_at(24);
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), sealed)
+        .map_err(|_| "Backup is corrupted or was encrypted with a different key".to_string())
+}
+
+fn is_safe_relative(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+// Whether `data_files` could have produced this relative path; archives may
+// come from elsewhere (see profile_archive.rs) and must not place anything else
+fn is_backed_up(relative: &str) -> bool {
+    relative == DB_FILE
+        || (!relative.contains('/') && relative.ends_with(".json"))
+        || DATA_DIRS.iter().any(|dir| relative.strip_prefix(dir).map_or(false, |rest| rest.starts_with('/')))
+}
+
+fn relative_name(base: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(base).ok()?;
+    Some(relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if path.extension().map_or(true, |e| e != "tmp") {
+            files.push(path);
+        }
+    }
+}
+
+// Relative path -> contents for everything a backup covers, minus secrets
+fn data_files(app: &AppHandle) -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let base = data_dir(app)?;
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(&base).map_err(|e| e.to_string())?.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_file() && path.extension().map_or(false, |e| e == "json") {
+            paths.push(path);
+        }
+    }
+    for dir in DATA_DIRS {
+        collect_files(&base.join(dir), &mut paths);
+    }
+
+    let mut files = BTreeMap::new();
+    for path in paths {
+        if let Some(name) = relative_name(&base, &path) {
+            files.insert(name, std::fs::read(&path).map_err(|e| e.to_string())?);
+        }
+    }
+
+    // VACUUM INTO gives a consistent copy while the database stays in use
+    let snapshot = storage::cache_dir(app, "backup")?.join(format!("{}.snapshot", DB_FILE));
+    let _ = std::fs::remove_file(&snapshot);
+    let target = snapshot.to_string_lossy().to_string();
+    app.state::<Database>()
+        .with(|conn| conn.execute("VACUUM INTO ?1", params![target]))?;
+    let bytes = std::fs::read(&snapshot).map_err(|e| e.to_string());
+    let _ = std::fs::remove_file(&snapshot);
+    files.insert(DB_FILE.to_string(), bytes?);
+    Ok(files)
+}
+
+fn secret_values(app: &AppHandle) -> Result<BTreeMap<String, String>, String> {
+    let mut values = BTreeMap::new();
+    for key in vault::keychain_keys(app).into_iter().chain(totp::keychain_keys(app)) {
+        if let Some(value) = secrets::get(&key)? {
+            values.insert(key, value);
+        }
+    }
+    Ok(values)
+}
+
+// Unencrypted zip of the user's data and secrets
+pub fn build_archive(app: &AppHandle) -> Result<Vec<u8>, String> {
+    let mut entries: Vec<(String, Vec<u8>)> = data_files(app)?
+        .into_iter()
+        .map(|(path, bytes)| (format!("{}{}", DATA_PREFIX, path), bytes))
+        .collect();
+    let secrets = serde_json::to_vec(&secret_values(app)?).map_err(|e| e.to_string())?;
+    entries.push((SECRETS_FILE.to_string(), secrets));
+
+    let manifest = BackupManifest {
+        version: 1,
+        app_version: app.package_info().version.to_string(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+        files: entries
+            .iter()
+            .map(|(path, bytes)| ManifestEntry {
+                path: path.clone(),
+                size: bytes.len() as u64,
+                sha256: hex::encode(Sha256::digest(bytes)),
+            })
+            .collect(),
+    };
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    for (path, bytes) in std::iter::once((MANIFEST_FILE.to_string(), manifest)).chain(entries) {
+        zip.start_file(path, options).map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+    Ok(zip.finish().map_err(|e| e.to_string())?.into_inner())
+}
+
+// Read an archive, checking every file against the manifest
+fn verify_archive(bytes: Vec<u8>) -> Result<(BackupManifest, BTreeMap<String, Vec<u8>>), String> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    // Reads at most `limit` bytes, whatever the zip headers claim
+    let read = |zip: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str, limit: u64| -> Result<Vec<u8>, String> {
+        let file = zip.by_name(name).map_err(|_| format!("Backup is missing {}", name))?;
+        let mut bytes = Vec::new();
+        file.take(limit + 1).read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        if bytes.len() as u64 > limit {
+            return Err(format!("{} in the backup is too large", name));
+        }
+        Ok(bytes)
+    };
+    let manifest: BackupManifest = serde_json::from_slice(&read(&mut zip, MANIFEST_FILE, MAX_MANIFEST_BYTES)?)
+        .map_err(|e| format!("Invalid manifest: {}", e))?;
+    if manifest.version != 1 {
+        return Err(format!("Unsupported backup version {}", manifest.version));
+    }
+    if manifest.files.iter().fold(0u64, |total, entry| total.saturating_add(entry.size)) > MAX_TOTAL_BYTES {
+        return Err("Backup is too large".to_string());
+    }
+
+    let mut files = BTreeMap::new();
+    for entry in &manifest.files {
+        let expected = entry.path == SECRETS_FILE
+            || entry.path.strip_prefix(DATA_PREFIX).map_or(false, is_backed_up);
+        if !is_safe_relative(&entry.path) || !expected {
+            return Err(format!("Invalid path in backup: {}", entry.path));
+        }
+        if entry.size > MAX_ENTRY_BYTES {
+            return Err(format!("{} in the backup is too large", entry.path));
+        }
+        let bytes = read(&mut zip, &entry.path, entry.size)?;
+        if bytes.len() as u64 != entry.size || hex::encode(Sha256::digest(&bytes)) != entry.sha256 {
+            return Err(format!("Integrity check failed for {}", entry.path));
+        }
+        files.insert(entry.path.clone(), bytes);
+    }
+    Ok((manifest, files))
+}
+
+// Verify an archive, restore its secrets and stage its files for the next start
+pub fn stage_restore(app: &AppHandle, archive: Vec<u8>) -> Result<RestoreReport, String> {
+    let (manifest, mut files) = verify_archive(archive)?;
+    let values: BTreeMap<String, String> = match files.remove(SECRETS_FILE) {
+        Some(bytes) => serde_json::from_slice(&bytes).map_err(|e| e.to_string())?,
+        None => BTreeMap::new(),
+    };
+
+    let staging = data_dir(app)?.join(RESTORE_DIR);
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    let mut staged = 0;
+    for (path, bytes) in files {
+        let Some(relative) = path.strip_prefix(DATA_PREFIX) else { continue };
+        let target = staging.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(target, bytes).map_err(|e| e.to_string())?;
+        staged += 1;
+    }
+
+    let mut restored = 0;
+    for (key, value) in &values {
+        if SECRET_PREFIXES.iter().any(|p| key.starts_with(p)) {
+            secrets::set(key, value)?;
+            restored += 1;
+        }
+    }
+    Ok(RestoreReport {
+        created_at: manifest.created_at,
+        files: staged,
+        secrets: restored,
+        restart_required: true,
+    })
+}
+
+// Called at the very start of setup: move a staged restore into place
+pub fn apply_pending_restore(app: &AppHandle) {
+    let Ok(base) = data_dir(app) else { return };
+    let staging = base.join(RESTORE_DIR);
+    if !staging.exists() {
+        return;
+    }
+    let mut files = Vec::new();
+    collect_files(&staging, &mut files);
+    for path in files {
+        let Some(relative) = relative_name(&staging, &path) else { continue };
+        let target = base.join(&relative);
+        if relative == DB_FILE {
+            for suffix in ["-wal", "-shm"] {
+                let _ = std::fs::remove_file(base.join(format!("{}{}", DB_FILE, suffix)));
+            }
+        }
+        if let Some(parent) = target.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::rename(&path, &target).or_else(|_| std::fs::copy(&path, &target).map(|_| ())) {
+            eprintln!("Failed to restore {}: {}", relative, e);
+        }
+    }
+    if let Err(e) = std::fs::remove_dir_all(&staging) {
+        eprintln!("Failed to clean up restored backup: {}", e);
+    }
+}
+
+fn list(app: &AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let config = app.state::<BackupState>().config.lock().unwrap().clone();
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(backups_dir(app, &config)?).map_err(|e| e.to_string())?.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().map_or(true, |e| e != EXTENSION) {
+            continue;
+        }
+        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        backups.push(BackupInfo {
+            path: path.to_string_lossy().to_string(),
+            file_name: entry.file_name().to_string_lossy().to_string(),
+            size: metadata.len(),
+            created_at: metadata
+                .modified()
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp_millis()),
+        });
+    }
+    // Newest first
+    backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    Ok(backups)
+}
+
+fn create(app: &AppHandle) -> Result<BackupInfo, String> {
+    let state = app.state::<BackupState>();
+    let _running = state.running.lock().unwrap();
+    let config = state.config.lock().unwrap().clone();
+
+    let (salt, key) = backup_key()?.ok_or("Set a backup passphrase first")?;
+    let archive = build_archive(app)?;
+    let sealed = [FILE_MAGIC, &salt[..], &seal(&key, &archive)?[..]].concat();
+    let file_name = format!("backup-{}.{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), EXTENSION);
+    let path = backups_dir(app, &config)?.join(&file_name);
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, &sealed).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+
+    Ok(BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        file_name,
+        size: sealed.len() as u64,
+        created_at: Some(chrono::Utc::now().timestamp_millis()),
+    })
+}
+
+fn prune(app: &AppHandle, keep: usize) {
+    let Ok(backups) = list(app) else { return };
+    for backup in backups.iter().skip(keep.max(1)) {
+        if let Err(e) = std::fs::remove_file(&backup.path) {
+            eprintln!("Failed to remove old backup {}: {}", backup.file_name, e);
+        }
+    }
+}
+
+pub fn start_scheduler(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        let config = app.state::<BackupState>().config.lock().unwrap().clone();
+        // Nothing to encrypt with until the user sets a passphrase
+        let keyed = matches!(backup_key(), Ok(Some(_)));
+        // Backups are due again on the next check once power allows
+        if !config.enabled || !keyed || battery::should_defer_heavy_jobs(&app) {
+            continue;
+        }
+        let last = list(&app)
+            .ok()
+            .and_then(|backups| backups.iter().filter_map(|b| b.created_at).max());
+        let due = last.map_or(true, |last| {
+            chrono::Utc::now().timestamp_millis() - last >= (config.interval_hours.max(1) * 3_600_000) as i64
+        });
+        if !due {
+            continue;
+        }
+        let _awake = power::hold(&app, GuardKind::Backup, "scheduled backup");
+        match create(&app) {
+            Ok(_) => prune(&app, config.keep),
+            Err(e) => {
+                eprintln!("Scheduled backup failed: {}", e);
+                let notice = Notice::new(NotificationCategory::System, i18n::text(&app, "notice-backup-failed-title"), e).owned_by("backup");
+                let _ = notifications::notify(&app, notice);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_backup_config(state: tauri::State<'_, BackupState>) -> Result<BackupConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_backup_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, BackupState>,
+    config: BackupConfig,
+) -> Result<(), String> {
+    if config.interval_hours == 0 || config.keep == 0 {
+        return Err("interval_hours and keep must be at least 1".to_string());
+    }
+    backups_dir(&app_handle, &config)?;
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+// Later backups are encrypted under the new passphrase; earlier ones still
+// need the passphrase they were made with
+#[tauri::command]
+#[specta::specta]
+pub async fn set_backup_passphrase(passphrase: String) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    tasks::blocking(move || {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = derive_key(&passphrase, &salt)?;
+        secrets::set(KEY_SECRET, &format!("{}:{}", hex::encode(salt), hex::encode(key)))
+    })
+    .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn has_backup_passphrase() -> Result<bool, String> {
+    tasks::blocking(|| backup_key().map(|key| key.is_some())).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_backup(app_handle: AppHandle) -> Result<BackupInfo, String> {
+    tasks::blocking(move || create(&app_handle)).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_backups(app_handle: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    tasks::blocking(move || list(&app_handle)).await
+}
+
+// Decrypt and check a backup without restoring it
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_backup(path: String, passphrase: Option<String>) -> Result<BackupManifest, String> {
+    tasks::blocking(move || {
+        let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+        let archive = open_file(&bytes, passphrase.as_deref())?;
+        verify_archive(archive).map(|(manifest, _)| manifest)
+    })
+    .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_backup(
+    app_handle: AppHandle,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<RestoreReport, String> {
+    tasks::blocking(move || {
+        let state = app_handle.state::<BackupState>();
+        let _running = state.running.lock().unwrap();
+        let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+        let archive = open_file(&bytes, passphrase.as_deref())?;
+        stage_restore(&app_handle, archive)
+    })
+    .await
+}
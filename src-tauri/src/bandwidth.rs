@@ -0,0 +1,337 @@
+// Bandwidth accounting
+// Bytes sent and received by the Rust HTTP clients (the shared outbound
+// client, AI streams, feed polls, crawls, link checks, previews and favicons,
+// the reading list, plugin fetches and the plugin registry, SEO audits,
+// search suggestions, URL cleaner rule updates), by S3/SFTP/FTPS uploads and
+// by webview downloads are added up per day and domain, and per workflow run
+// for traffic caused by a native workflow action. Responses count their
+// Content-Length, or the bytes read where the body is consumed here; uploads
+// count the file sizes as sent. With a monthly cap configured, background
+// jobs pause once it is reached (see network::transfers_paused); per-domain
+// caps hold back background polls of that domain only.
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::events::AppEvent;
+use crate::storage;
+
+const BANDWIDTH_FILE: &str = "bandwidth.json";
+const DEFAULT_STATS_DAYS: u32 = 30;
+const MAX_STATS_ROWS: u32 = 200;
+const MEGABYTE: u64 = 1024 * 1024;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS bandwidth (
+    day TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    -- '' for traffic outside a workflow run
+    run_id TEXT NOT NULL DEFAULT '',
+    workflow_id TEXT,
+    bytes_in INTEGER NOT NULL DEFAULT 0,
+    bytes_out INTEGER NOT NULL DEFAULT 0,
+    requests INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, domain, run_id)
+);
+CREATE INDEX IF NOT EXISTS bandwidth_run ON bandwidth (run_id);
+";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct BandwidthConfig {
+    // Sent plus received per calendar month; None is unlimited
+    monthly_cap_mb: Option<u64>,
+    // Domain ("example.com") -> monthly cap for that domain
+    domain_caps_mb: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Usage {
+    // Domain or day, depending on the list
+    key: String,
+    bytes_in: u64,
+    bytes_out: u64,
+    requests: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RunUsage {
+    run_id: String,
+    workflow_id: Option<String>,
+    bytes_in: u64,
+    bytes_out: u64,
+    requests: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BandwidthStats {
+    // First day included, YYYY-MM-DD
+    since: String,
+    by_domain: Vec<Usage>,
+    by_day: Vec<Usage>,
+    by_run: Vec<RunUsage>,
+    month_bytes: u64,
+    monthly_cap_mb: Option<u64>,
+    // Background jobs are holding off for the rest of the month
+    capped: bool,
+}
+
+#[derive(Debug, Clone)]
+struct RunContext {
+    run_id: String,
+    workflow_id: Option<String>,
+}
+
+tokio::task_local! {
+    static RUN: RunContext;
+}
+
+#[derive(Default)]
+pub struct BandwidthState {
+    config: Mutex<BandwidthConfig>,
+    // "<month>:<scope>" caps already announced with `bandwidth-cap-reached`
+    announced: Mutex<HashSet<String>>,
+}
+
+impl BandwidthState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, BANDWIDTH_FILE)),
+            announced: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+// Attribute the traffic of `task` (but not of tasks it spawns) to a workflow run
+pub async fn in_run<F: Future>(run_id: &str, workflow_id: Option<&str>, task: F) -> F::Output {
+    let context = RunContext {
+        run_id: run_id.to_string(),
+        workflow_id: workflow_id.map(str::to_string),
+    };
+    RUN.scope(context, task).await
+}
+
+fn domain_of(url: &str) -> Option<String> {
+    let host = url::Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn this_month() -> String {
+    chrono::Local::now().format("%Y-%m").to_string()
+}
+
+// Sent plus received this month, optionally for one domain
+fn month_bytes(db: &Database, domain: Option<&str>) -> Result<u64, String> {
+    let pattern = format!("{}-%", this_month());
+    let total: i64 = db.with(|conn| {
+        conn.query_row(
+            "SELECT COALESCE(SUM(bytes_in + bytes_out), 0) FROM bandwidth
+             WHERE day LIKE ?1 AND (?2 IS NULL OR domain = ?2)",
+            params![pattern, domain],
+            |row| row.get(0),
+        )
+    })?;
+    Ok(total.max(0) as u64)
+}
+
+// One request to `url`
+pub fn record(app: &AppHandle, url: &str, sent: u64, received: u64) {
+    add(app, url, sent, received, 1);
+}
+
+// Body bytes read for a request already recorded without a Content-Length
+pub fn record_received(app: &AppHandle, url: &str, received: u64) {
+    add(app, url, 0, received, 0);
+}
+
+fn add(app: &AppHandle, url: &str, sent: u64, received: u64, requests: u64) {
+    let Some(domain) = domain_of(url) else { return };
+    let run = RUN.try_with(RunContext::clone).ok();
+    let (run_id, workflow_id) = match &run {
+        Some(run) => (run.run_id.as_str(), run.workflow_id.as_deref()),
+        None => ("", None),
+    };
+    let Some(db) = app.try_state::<Database>() else { return };
+    let result = db.with(|conn| {
+        conn.execute(
+            "INSERT INTO bandwidth (day, domain, run_id, workflow_id, bytes_in, bytes_out, requests)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(day, domain, run_id) DO UPDATE SET
+                 bytes_in = bytes_in + excluded.bytes_in,
+                 bytes_out = bytes_out + excluded.bytes_out,
+                 requests = requests + excluded.requests",
+            params![today(), domain, run_id, workflow_id, received as i64, sent as i64, requests as i64],
+        )
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to record bandwidth for {}: {}", domain, e);
+        return;
+    }
+    announce_caps(app, &db, &domain);
+}
+
+// Count a response by its Content-Length; bodies without one are counted by
+// the caller as they are read
+pub fn record_response(app: &AppHandle, response: &reqwest::Response, sent: u64) {
+    record(app, response.url().as_str(), sent, response.content_length().unwrap_or(0));
+}
+
+// Tell the frontend once per month and cap when a cap is reached
+fn announce_caps(app: &AppHandle, db: &Database, domain: &str) {
+    let state = app.state::<BandwidthState>();
+    let (total_cap, domain_cap) = {
+        let config = state.config.lock().unwrap();
+        (config.monthly_cap_mb, config.domain_caps_mb.get(domain).copied())
+    };
+    let checks = [("total", None, total_cap), (domain, Some(domain), domain_cap)];
+    for (scope, filter, cap) in checks {
+        let Some(cap) = cap else { continue };
+        let key = format!("{}:{}", this_month(), scope);
+        if state.announced.lock().unwrap().contains(&key) {
+            continue;
+        }
+        let Ok(used) = month_bytes(db, filter) else { continue };
+        if used >= cap * MEGABYTE {
+            state.announced.lock().unwrap().insert(key);
+            eprintln!("Monthly bandwidth cap of {} MB reached for {}", cap, scope);
+            let _ = app.emit_all(
+                "bandwidth-cap-reached",
+                json!({ "scope": scope, "cap_mb": cap, "used_bytes": used }),
+            );
+        }
+    }
+}
+
+// Whether this month's total cap has been reached
+pub fn over_cap(app: &AppHandle) -> bool {
+    let Some(state) = app.try_state::<BandwidthState>() else { return false };
+    let Some(cap) = state.config.lock().unwrap().monthly_cap_mb else { return false };
+    let Some(db) = app.try_state::<Database>() else { return false };
+    month_bytes(&db, None).map_or(false, |used| used >= cap * MEGABYTE)
+}
+
+// Whether background jobs should leave this URL's domain alone this month
+pub fn domain_over_cap(app: &AppHandle, url: &str) -> bool {
+    let Some(domain) = domain_of(url) else { return false };
+    let cap = app
+        .state::<BandwidthState>()
+        .config
+        .lock()
+        .unwrap()
+        .domain_caps_mb
+        .get(&domain)
+        .copied();
+    let Some(cap) = cap else { return false };
+    month_bytes(&app.state::<Database>(), Some(&domain)).map_or(false, |used| used >= cap * MEGABYTE)
+}
+
+// Event subscriber for webview and engine downloads
+pub fn on_event(app: &AppHandle, event: &AppEvent) {
+    if let AppEvent::DownloadCompleted { url, bytes: Some(bytes), .. } = event {
+        record(app, url, 0, *bytes);
+    }
+}
+
+fn usage_rows(db: &Database, column: &str, since: &str, order: &str) -> Result<Vec<Usage>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {0}, SUM(bytes_in), SUM(bytes_out), SUM(requests) FROM bandwidth
+             WHERE day >= ?1 GROUP BY {0} ORDER BY {1} LIMIT ?2",
+            column, order
+        ))?;
+        let rows = stmt.query_map(params![since, MAX_STATS_ROWS], |row| {
+            Ok(Usage {
+                key: row.get(0)?,
+                bytes_in: row.get::<_, i64>(1)?.max(0) as u64,
+                bytes_out: row.get::<_, i64>(2)?.max(0) as u64,
+                requests: row.get::<_, i64>(3)?.max(0) as u64,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+// Usage over the last `days` days (30 by default), largest domains and runs first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_bandwidth_stats(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    state: tauri::State<'_, BandwidthState>,
+    days: Option<u32>,
+) -> Result<BandwidthStats, String> {
+    let days = days.unwrap_or(DEFAULT_STATS_DAYS).max(1);
+    let since = (chrono::Local::now() - chrono::Duration::days(i64::from(days) - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let by_domain = usage_rows(&db, "domain", &since, "SUM(bytes_in + bytes_out) DESC")?;
+    let by_day = usage_rows(&db, "day", &since, "day")?;
+    let by_run = db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT run_id, MAX(workflow_id), SUM(bytes_in), SUM(bytes_out), SUM(requests) FROM bandwidth
+             WHERE day >= ?1 AND run_id != '' GROUP BY run_id
+             ORDER BY SUM(bytes_in + bytes_out) DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![since, MAX_STATS_ROWS], |row| {
+            Ok(RunUsage {
+                run_id: row.get(0)?,
+                workflow_id: row.get(1)?,
+                bytes_in: row.get::<_, i64>(2)?.max(0) as u64,
+                bytes_out: row.get::<_, i64>(3)?.max(0) as u64,
+                requests: row.get::<_, i64>(4)?.max(0) as u64,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+    Ok(BandwidthStats {
+        since,
+        by_domain,
+        by_day,
+        by_run,
+        month_bytes: month_bytes(&db, None)?,
+        monthly_cap_mb: state.config.lock().unwrap().monthly_cap_mb,
+        capped: over_cap(&app_handle),
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_bandwidth_config(state: tauri::State<'_, BandwidthState>) -> Result<BandwidthConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_bandwidth_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, BandwidthState>,
+    mut config: BandwidthConfig,
+) -> Result<(), String> {
+    if config.monthly_cap_mb == Some(0) || config.domain_caps_mb.values().any(|cap| *cap == 0) {
+        return Err("Caps must be at least 1 MB".to_string());
+    }
+    config.domain_caps_mb = config
+        .domain_caps_mb
+        .into_iter()
+        .map(|(domain, cap)| {
+            let domain = domain.trim().to_ascii_lowercase();
+            (domain.strip_prefix("www.").map(str::to_string).unwrap_or(domain), cap)
+        })
+        .collect();
+    storage::save(&app_handle, BANDWIDTH_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    // A raised cap may be reached again this month
+    state.announced.lock().unwrap().clear();
+    Ok(())
+}
@@ -0,0 +1,153 @@
+// Battery and power-source status
+// Background jobs (model inference, scheduled scrapes) check `should_defer_heavy_jobs`
+// so they can wait for AC power when the battery is low.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::storage;
+
+const BATTERY_FILE: &str = "battery-policy.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct BatteryPolicy {
+    // Defer heavy jobs when on battery and below this charge (0-100)
+    defer_below_percent: u8,
+    // Defer heavy jobs whenever on battery, regardless of charge
+    defer_on_battery: bool,
+}
+
+impl Default for BatteryPolicy {
+    fn default() -> Self {
+        Self {
+            defer_below_percent: 20,
+            defer_on_battery: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+pub struct BatteryStatus {
+    has_battery: bool,
+    on_battery: bool,
+    charging: bool,
+    percent: Option<f32>,
+    time_to_empty_secs: Option<u64>,
+    defer_heavy_jobs: bool,
+}
+
+#[derive(Default)]
+pub struct BatteryState {
+    policy: Mutex<BatteryPolicy>,
+    last: Mutex<BatteryStatus>,
+}
+
+impl BatteryState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            policy: Mutex::new(storage::load(app, BATTERY_FILE)),
+            last: Mutex::new(BatteryStatus::default()),
+        }
+    }
+}
+
+fn read_status(policy: &BatteryPolicy) -> Result<BatteryStatus, String> {
+    use starship_battery::State;
+
+    let manager = starship_battery::Manager::new().map_err(|e| e.to_string())?;
+    let batteries: Vec<_> = manager
+        .batteries()
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    if batteries.is_empty() {
+        return Ok(BatteryStatus::default());
+    }
+
+    // Aggregate multiple batteries by energy, as the OS indicator does
+    let energy: f32 = batteries.iter().map(|b| b.energy().value).sum();
+    let full: f32 = batteries.iter().map(|b| b.energy_full().value).sum();
+    let percent = if full > 0.0 { energy / full * 100.0 } else { 0.0 };
+    let on_battery = batteries.iter().any(|b| b.state() == State::Discharging);
+    let charging = batteries.iter().any(|b| b.state() == State::Charging);
+    let time_to_empty_secs = batteries
+        .iter()
+        .filter_map(|b| b.time_to_empty())
+        .map(|t| t.value as u64)
+        .max();
+
+    let defer_heavy_jobs = on_battery
+        && (policy.defer_on_battery || percent < f32::from(policy.defer_below_percent));
+
+    Ok(BatteryStatus {
+        has_battery: true,
+        on_battery,
+        charging,
+        percent: Some(percent),
+        time_to_empty_secs,
+        defer_heavy_jobs,
+    })
+}
+
+// Whether heavy background work should wait for better power conditions
+pub fn should_defer_heavy_jobs(app: &AppHandle) -> bool {
+    app.state::<BatteryState>().last.lock().unwrap().defer_heavy_jobs
+}
+
+fn refresh(app: &AppHandle) -> Result<BatteryStatus, String> {
+    let state = app.state::<BatteryState>();
+    let policy = state.policy.lock().unwrap().clone();
+    let status = read_status(&policy)?;
+
+    let changed = {
+        let mut last = state.last.lock().unwrap();
+        let changed = last.on_battery != status.on_battery
+            || last.charging != status.charging
+            || last.defer_heavy_jobs != status.defer_heavy_jobs
+            || last.percent.map(f32::round) != status.percent.map(f32::round);
+        *last = status.clone();
+        changed
+    };
+
+    if changed {
+        let _ = app.emit_all("power-changed", &status);
+    }
+    Ok(status)
+}
+
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        if let Err(e) = refresh(&app) {
+            eprintln!("Failed to read battery status: {}", e);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_battery_status(app_handle: AppHandle) -> Result<BatteryStatus, String> {
+    refresh(&app_handle)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_battery_policy(
+    app_handle: AppHandle,
+    state: tauri::State<'_, BatteryState>,
+    policy: BatteryPolicy,
+) -> Result<(), String> {
+    if policy.defer_below_percent > 100 {
+        return Err("defer_below_percent must be between 0 and 100".to_string());
+    }
+    storage::save(&app_handle, BATTERY_FILE, &policy)?;
+    *state.policy.lock().unwrap() = policy;
+    refresh(&app_handle).map(|_| ())
+}
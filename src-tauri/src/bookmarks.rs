@@ -0,0 +1,94 @@
+// Bookmarks stored in SQLite
+
+use rusqlite::{params, Row};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::db::Database;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS bookmarks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    folder TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS bookmarks_url ON bookmarks (url);
+";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Bookmark {
+    pub id: i64,
+    pub url: String,
+    pub title: String,
+    pub folder: String,
+    pub created_at: i64,
+}
+
+impl Bookmark {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            folder: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, url, title, folder, created_at FROM bookmarks";
+
+// Bookmarks whose URL or title contains the filter
+pub fn search(db: &Database, filter: &str, limit: u32) -> Result<Vec<Bookmark>, String> {
+    let pattern = format!("%{}%", filter.replace('%', "\\%").replace('_', "\\_"));
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE url LIKE ?1 ESCAPE '\\' OR title LIKE ?1 ESCAPE '\\'
+             ORDER BY created_at DESC LIMIT ?2",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![pattern, limit], Bookmark::from_row)?;
+        rows.collect()
+    })
+}
+
+pub fn all(db: &Database) -> Result<Vec<Bookmark>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!("{} ORDER BY folder, created_at", SELECT_COLUMNS))?;
+        let rows = stmt.query_map([], Bookmark::from_row)?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn add_bookmark(
+    db: tauri::State<'_, Database>,
+    url: String,
+    title: String,
+    folder: Option<String>,
+) -> Result<i64, String> {
+    url::Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    db.with(|conn| {
+        conn.execute(
+            "INSERT INTO bookmarks (url, title, folder, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![url, title, folder.unwrap_or_default(), chrono::Utc::now().timestamp()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_bookmark(db: tauri::State<'_, Database>, id: i64) -> Result<(), String> {
+    db.with(|conn| conn.execute("DELETE FROM bookmarks WHERE id = ?1", params![id]))?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_bookmarks(db: tauri::State<'_, Database>) -> Result<Vec<Bookmark>, String> {
+    all(&db)
+}
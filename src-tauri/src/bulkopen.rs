@@ -0,0 +1,179 @@
+// Opening many URLs at once
+// `open_url_batch` opens each URL of a list (typically pasted from a
+// spreadsheet) in its own window, optionally inside a container, keeping at
+// most `max_concurrent` pages loading at a time and pausing `delay_ms`
+// between starts so sites are not hit in a burst. Progress is emitted per URL
+// as `url-batch-progress`; the batch runs as a cancellable task.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window, WindowUrl};
+use tokio::sync::{oneshot, Semaphore};
+
+use crate::{containers, idle, kiosk, tasks, windowpool};
+
+const MAX_URLS: usize = 500;
+const MAX_CONCURRENT: usize = 10;
+const DEFAULT_CONCURRENT: usize = 3;
+const DEFAULT_DELAY_MS: u64 = 500;
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(default)]
+pub struct OpenBatchOptions {
+    max_concurrent: Option<usize>,
+    delay_ms: Option<u64>,
+    container: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UrlStatus {
+    Loaded { window_id: String },
+    TimedOut { window_id: String },
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct UrlResult {
+    index: usize,
+    url: String,
+    #[serde(flatten)]
+    status: UrlStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+struct BatchProgress {
+    batch_id: String,
+    total: usize,
+    result: UrlResult,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BatchReport {
+    batch_id: String,
+    results: Vec<UrlResult>,
+}
+
+#[derive(Default)]
+pub struct BulkOpenState {
+    // Window label -> waiter for its first page load
+    waiting: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+pub fn on_page_load(window: &Window) {
+    let waiter = window
+        .state::<BulkOpenState>()
+        .waiting
+        .lock()
+        .unwrap()
+        .remove(window.label());
+    if let Some(waiter) = waiter {
+        let _ = waiter.send(());
+    }
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<BulkOpenState>().waiting.lock().unwrap().remove(label);
+}
+
+fn parse_url(url: &str) -> Result<url::Url, String> {
+    let parsed = url::Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only http and https URLs can be opened".to_string());
+    }
+    Ok(parsed)
+}
+
+async fn open_one(app: &AppHandle, url: &str, container: Option<&str>) -> UrlStatus {
+    let url = match parse_url(url) {
+        Ok(url) => url,
+        Err(error) => return UrlStatus::Failed { error },
+    };
+    let window = match container {
+        Some(id) => containers::open_window(app, WindowUrl::External(url), id),
+        None => windowpool::build_window(app, WindowUrl::External(url), true),
+    };
+    let window = match window {
+        Ok(window) => window,
+        Err(error) => return UrlStatus::Failed { error },
+    };
+    let window_id = window.label().to_string();
+    let (tx, rx) = oneshot::channel();
+    let state = app.state::<BulkOpenState>();
+    state.waiting.lock().unwrap().insert(window_id.clone(), tx);
+    let loaded = tokio::time::timeout(LOAD_TIMEOUT, rx).await;
+    state.waiting.lock().unwrap().remove(&window_id);
+    match loaded {
+        Ok(Ok(())) => UrlStatus::Loaded { window_id },
+        Ok(Err(_)) => UrlStatus::Failed {
+            error: "Window closed before the page loaded".to_string(),
+        },
+        Err(_) => UrlStatus::TimedOut { window_id },
+    }
+}
+
+async fn open_batch(app: &AppHandle, batch_id: &str, urls: Vec<String>, options: OpenBatchOptions) -> Vec<UrlResult> {
+    let total = urls.len();
+    let limit = options.max_concurrent.unwrap_or(DEFAULT_CONCURRENT).clamp(1, MAX_CONCURRENT);
+    let delay = Duration::from_millis(options.delay_ms.unwrap_or(DEFAULT_DELAY_MS));
+    let semaphore = Arc::new(Semaphore::new(limit));
+    let mut handles = Vec::with_capacity(total);
+    for (index, url) in urls.into_iter().enumerate() {
+        if index > 0 && !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        let permit = semaphore.clone().acquire_owned().await.expect("semaphore is never closed");
+        let (app, batch_id, container) = (app.clone(), batch_id.to_string(), options.container.clone());
+        handles.push(tauri::async_runtime::spawn(async move {
+            let status = open_one(&app, &url, container.as_deref()).await;
+            drop(permit);
+            let result = UrlResult { index, url, status };
+            let _ = app.emit_all(
+                "url-batch-progress",
+                BatchProgress {
+                    batch_id,
+                    total,
+                    result: result.clone(),
+                },
+            );
+            result
+        }));
+    }
+    let mut results = Vec::with_capacity(total);
+    for handle in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => eprintln!("URL batch worker failed: {}", e),
+        }
+    }
+    results
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn open_url_batch(
+    app_handle: AppHandle,
+    urls: Vec<String>,
+    options: Option<OpenBatchOptions>,
+) -> Result<BatchReport, String> {
+    kiosk::ensure_inactive(&app_handle)?;
+    idle::ensure_unlocked(&app_handle)?;
+    let urls: Vec<String> = urls.into_iter().filter(|u| !u.trim().is_empty()).collect();
+    if urls.is_empty() {
+        return Err("No URLs to open".to_string());
+    }
+    if urls.len() > MAX_URLS {
+        return Err(format!("At most {} URLs can be opened at once", MAX_URLS));
+    }
+    let batch_id = format!("batch-{}", chrono::Utc::now().timestamp_millis());
+    let name = format!("Open {} URLs", urls.len());
+    let results = tasks::run(&app_handle, &name, async {
+        Ok(open_batch(&app_handle, &batch_id, urls, options.unwrap_or_default()).await)
+    })
+    .await?;
+    Ok(BatchReport { batch_id, results })
+}
@@ -0,0 +1,198 @@
+// Cache size reporting and selective clearing
+// Covers the webview HTTP caches of each profile/partition plus the app's own
+// caches. Downloaded models are never touched.
+
+use serde::Serialize;
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::{storage, tasks};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+// App-managed caches under the app cache directory
+pub const APP_CACHES: [&str; 4] = ["enrichment", "favicons", "thumbnails", "translations"];
+
+// Webview HTTP cache locations, relative to a profile's webview data directory
+#[cfg(target_os = "windows")]
+const WEBVIEW_CACHE_DIRS: [&str; 4] = [
+    "EBWebView/Default/Cache",
+    "EBWebView/Default/Code Cache",
+    "EBWebView/Default/GPUCache",
+    "EBWebView/Default/Service Worker/CacheStorage",
+];
+#[cfg(target_os = "macos")]
+const WEBVIEW_CACHE_DIRS: [&str; 2] = ["WebKit/NetworkCache", "WebKit/CacheStorage"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const WEBVIEW_CACHE_DIRS: [&str; 2] = ["WebKitCache", "CacheStorage"];
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ProfileCacheUsage {
+    profile_id: String,
+    http_cache_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AppCacheUsage {
+    name: String,
+    bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CacheUsage {
+    profiles: Vec<ProfileCacheUsage>,
+    app_caches: Vec<AppCacheUsage>,
+    total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ClearedCache {
+    freed_bytes: u64,
+    // Files the webview still had open; they are cleared on the next start
+    skipped_files: u64,
+}
+
+// Webview data directory of a profile; named partitions live under `partitions/`
+pub fn profile_data_dir(app: &AppHandle, profile_id: &str) -> Result<PathBuf, String> {
+    let base = if cfg!(target_os = "macos") {
+        app.path_resolver().app_cache_dir()
+    } else {
+        app.path_resolver().app_local_data_dir()
+    }
+    .ok_or_else(|| "App data directory unavailable".to_string())?;
+
+    if profile_id == DEFAULT_PROFILE {
+        Ok(base)
+    } else if profile_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(base.join("partitions").join(profile_id))
+    } else {
+        Err(format!("Invalid profile id: {}", profile_id))
+    }
+}
+
+fn list_profiles(app: &AppHandle) -> Vec<String> {
+    let mut profiles = vec![DEFAULT_PROFILE.to_string()];
+    if let Ok(base) = profile_data_dir(app, DEFAULT_PROFILE) {
+        if let Ok(entries) = std::fs::read_dir(base.join("partitions")) {
+            profiles.extend(
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|e| e.path().is_dir())
+                    .filter_map(|e| e.file_name().into_string().ok()),
+            );
+        }
+    }
+    profiles
+}
+
+fn webview_cache_paths(app: &AppHandle, profile_id: &str) -> Result<Vec<PathBuf>, String> {
+    let base = profile_data_dir(app, profile_id)?;
+    Ok(WEBVIEW_CACHE_DIRS.iter().map(|dir| base.join(dir)).collect())
+}
+
+// Delete everything below a directory, keeping the directory itself.
+// Returns (freed bytes, files that could not be removed).
+fn clear_dir(path: &Path) -> (u64, u64) {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return (0, 0);
+    };
+
+    let mut freed = 0;
+    let mut skipped = 0;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            let (f, s) = clear_dir(&path);
+            freed += f;
+            skipped += s;
+            let _ = std::fs::remove_dir(&path);
+        } else {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            match std::fs::remove_file(&path) {
+                Ok(()) => freed += size,
+                Err(_) => skipped += 1,
+            }
+        }
+    }
+    (freed, skipped)
+}
+
+fn usage(app_handle: &AppHandle) -> Result<CacheUsage, String> {
+    let mut profiles = Vec::new();
+    for profile_id in list_profiles(app_handle) {
+        let http_cache_bytes = webview_cache_paths(app_handle, &profile_id)?
+            .iter()
+            .map(|p| storage::dir_size(p))
+            .sum();
+        profiles.push(ProfileCacheUsage {
+            profile_id,
+            http_cache_bytes,
+        });
+    }
+
+    let app_caches: Vec<AppCacheUsage> = APP_CACHES
+        .iter()
+        .map(|name| AppCacheUsage {
+            name: name.to_string(),
+            bytes: storage::cache_dir(app_handle, name)
+                .map(|dir| storage::dir_size(&dir))
+                .unwrap_or(0),
+        })
+        .collect();
+
+    let total_bytes = profiles.iter().map(|p| p.http_cache_bytes).sum::<u64>()
+        + app_caches.iter().map(|c| c.bytes).sum::<u64>();
+
+    Ok(CacheUsage {
+        profiles,
+        app_caches,
+        total_bytes,
+    })
+}
+
+// Walking the cache directories can take a while; keep it off the async workers
+#[tauri::command]
+#[specta::specta]
+pub async fn get_cache_usage(app_handle: AppHandle) -> Result<CacheUsage, String> {
+    tasks::blocking(move || usage(&app_handle)).await
+}
+
+// Clear the HTTP cache of one profile (or all when None) and, optionally, the
+// app's own caches
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_cache(
+    app_handle: AppHandle,
+    profile_id: Option<String>,
+    include_app_caches: Option<bool>,
+) -> Result<ClearedCache, String> {
+    let profiles = match profile_id {
+        Some(id) => vec![id],
+        None => list_profiles(&app_handle),
+    };
+
+    let mut result = ClearedCache {
+        freed_bytes: 0,
+        skipped_files: 0,
+    };
+    let mut paths = Vec::new();
+    for profile in &profiles {
+        paths.extend(webview_cache_paths(&app_handle, profile)?);
+    }
+    if include_app_caches.unwrap_or(true) {
+        for name in APP_CACHES {
+            paths.push(storage::cache_dir(&app_handle, name)?);
+        }
+    }
+
+    tasks::blocking(move || {
+        for path in paths {
+            let (freed, skipped) = clear_dir(&path);
+            result.freed_bytes += freed;
+            result.skipped_files += skipped;
+        }
+        Ok(result)
+    })
+    .await
+}
@@ -0,0 +1,64 @@
+// Window screenshots
+// Webview engines expose no common capture API, so the OS window is captured
+// instead (this works while the window is occluded, but not while minimized).
+
+use image::RgbaImage;
+use tauri::Window;
+
+// Find the OS window backing a Tauri window by title and geometry
+fn find_os_window(window: &Window) -> Result<xcap::Window, String> {
+    let title = window.title().map_err(|e| e.to_string())?;
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+
+    xcap::Window::all()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|w| w.title() == title)
+        .min_by_key(|w| {
+            (w.x() - position.x).abs()
+                + (w.y() - position.y).abs()
+                + (w.width() as i32 - size.width as i32).abs()
+                + (w.height() as i32 - size.height as i32).abs()
+        })
+        .ok_or_else(|| "Window is not capturable".to_string())
+}
+
+// Capture the content area of a window (title bar and borders are cropped off)
+pub fn capture_window(window: &Window) -> Result<RgbaImage, String> {
+    if window.is_minimized().unwrap_or(false) {
+        return Err("Cannot capture a minimized window".to_string());
+    }
+    let os_window = find_os_window(window)?;
+    let mut image = os_window.capture_image().map_err(|e| e.to_string())?;
+
+    let outer = window.outer_position().map_err(|e| e.to_string())?;
+    let inner = window.inner_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let left = (inner.x - outer.x).max(0) as u32;
+    let top = (inner.y - outer.y).max(0) as u32;
+    let width = size.width.min(image.width().saturating_sub(left));
+    let height = size.height.min(image.height().saturating_sub(top));
+
+    Ok(image::imageops::crop(&mut image, left, top, width, height).to_image())
+}
+
+// Capture a rectangle (in physical pixels, relative to the content area)
+pub fn capture_region(window: &Window, x: u32, y: u32, width: u32, height: u32) -> Result<RgbaImage, String> {
+    let mut image = capture_window(window)?;
+    if x >= image.width() || y >= image.height() {
+        return Err("Region is outside the window".to_string());
+    }
+    let width = width.min(image.width() - x);
+    let height = height.min(image.height() - y);
+    Ok(image::imageops::crop(&mut image, x, y, width, height).to_image())
+}
+
+// Encode an image as PNG bytes
+pub fn to_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(png)
+}
@@ -0,0 +1,185 @@
+// Safety check before automated clicks
+// The workflow engine runs a `guard_click` action before clicking an element.
+// The element and its form are inspected in the page and matched against
+// heuristics for payments, deletions and outgoing messages. A risky click
+// goes ahead only if the workflow definition opts in to that kind of action
+// (`allow_risky_actions`) or the user confirms it in a native dialog; a
+// declined click fails the run. Every risky click is written to the audit log.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::audit::{self, AuditCategory};
+use crate::pagequery;
+use crate::workflow::RunReport;
+use crate::workflow_defs;
+
+const INSPECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Longest element text quoted in the confirmation dialog
+const MAX_LABEL_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskKind {
+    Payment,
+    Deletion,
+    SendMessage,
+}
+
+impl RiskKind {
+    fn describe(self) -> &'static str {
+        match self {
+            RiskKind::Payment => "a payment or purchase",
+            RiskKind::Deletion => "deleting something",
+            RiskKind::SendMessage => "sending a message",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardClickAction {
+    window_id: String,
+    selector: String,
+}
+
+// What the page reports about the element
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ElementInfo {
+    text: String,
+    tag: String,
+    href: String,
+    form_action: String,
+    // Lowercased name, id, autocomplete and type of the form's fields
+    form_fields: Vec<String>,
+}
+
+const PAYMENT_WORDS: [&str; 10] = [
+    "pay", "pay now", "buy", "buy now", "purchase", "place order", "checkout", "check out", "complete order",
+    "confirm payment",
+];
+const PAYMENT_FIELDS: [&str; 7] = ["cc-number", "cc-csc", "cc-exp", "cardnumber", "cvc", "cvv", "iban"];
+const PAYMENT_PATHS: [&str; 4] = ["checkout", "payment", "/pay", "billing"];
+const DELETION_WORDS: [&str; 9] = [
+    "delete", "remove", "erase", "destroy", "discard", "close account", "deactivate", "cancel subscription",
+    "empty trash",
+];
+const MESSAGE_WORDS: [&str; 6] = ["send", "reply", "reply all", "forward", "publish", "post"];
+const MESSAGE_FIELDS: [&str; 6] = ["to", "cc", "bcc", "recipient", "recipients", "subject"];
+
+const INSPECT_SCRIPT: &str = r#"
+var el = document.querySelector(SELECTOR);
+if (!el) throw new Error('No element matches ' + SELECTOR);
+var form = el.form || el.closest('form');
+var fields = [];
+if (form) {
+  Array.prototype.forEach.call(form.elements, function (f) {
+    [f.name, f.id, f.getAttribute('autocomplete'), f.type].forEach(function (v) { if (v) fields.push(String(v).toLowerCase()); });
+  });
+}
+return {
+  text: [el.innerText, el.value, el.getAttribute('aria-label'), el.title].filter(Boolean).join(' ').slice(0, 500),
+  tag: el.tagName.toLowerCase(),
+  href: el.href || '',
+  formAction: form ? (form.action || '') : '',
+  formFields: fields
+};
+"#;
+
+// Whole-word (or whole-phrase) match in lowercased text
+fn has_phrase(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + phrase.len()..].chars().next();
+        !before.map_or(false, char::is_alphanumeric) && !after.map_or(false, char::is_alphanumeric)
+    })
+}
+
+fn classify(info: &ElementInfo) -> Vec<RiskKind> {
+    let text = info.text.to_lowercase();
+    let target = format!("{} {}", info.href, info.form_action).to_lowercase();
+    let field = |names: &[&str]| info.form_fields.iter().any(|f| names.contains(&f.as_str()));
+    let mut risks = Vec::new();
+    if PAYMENT_WORDS.iter().any(|w| has_phrase(&text, w))
+        || field(&PAYMENT_FIELDS)
+        || (!info.form_action.is_empty() && PAYMENT_PATHS.iter().any(|p| target.contains(p)))
+    {
+        risks.push(RiskKind::Payment);
+    }
+    if DELETION_WORDS.iter().any(|w| has_phrase(&text, w)) {
+        risks.push(RiskKind::Deletion);
+    }
+    if MESSAGE_WORDS.iter().any(|w| has_phrase(&text, w)) && (field(&MESSAGE_FIELDS) || info.tag != "a") {
+        risks.push(RiskKind::SendMessage);
+    }
+    risks
+}
+
+fn label(info: &ElementInfo) -> String {
+    let text: String = info.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MAX_LABEL_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+fn ask(workflow: &str, element: &str, page: &str, risks: &[RiskKind]) -> bool {
+    let kinds: Vec<&str> = risks.iter().map(|r| r.describe()).collect();
+    let message = format!(
+        "Workflow \"{}\" is about to click \"{}\" on {}.\n\nThis looks like {}. Allow it?",
+        workflow,
+        element,
+        page,
+        kinds.join(" and ")
+    );
+    tauri::api::dialog::blocking::ask(None::<&Window>, "Confirm automated action", message)
+}
+
+pub async fn run_action(app: &AppHandle, action: &GuardClickAction, report: &RunReport) -> Result<Value, String> {
+    let window = app
+        .get_window(&action.window_id)
+        .ok_or_else(|| format!("Window not found: {}", action.window_id))?;
+    let selector = serde_json::to_string(&action.selector).map_err(|e| e.to_string())?;
+    let info = pagequery::run(app, &window, &INSPECT_SCRIPT.replace("SELECTOR", &selector), INSPECT_TIMEOUT).await?;
+    let info: ElementInfo = serde_json::from_value(info).map_err(|e| e.to_string())?;
+    let risks = classify(&info);
+    if risks.is_empty() {
+        return Ok(json!({ "allowed": true, "risks": risks }));
+    }
+
+    let page = window.url().map(|u| u.to_string()).unwrap_or_default();
+    let element = label(&info);
+    let opted_in = match &report.workflow_id {
+        Some(id) => workflow_defs::load(app, id)
+            .map(|definition| risks.iter().all(|r| definition.allows_risky_action(*r)))
+            .unwrap_or(false),
+        None => false,
+    };
+    let allowed = opted_in || {
+        let (workflow, element, page, kinds) = (report.workflow_name.clone(), element.clone(), page.clone(), risks.clone());
+        tauri::async_runtime::spawn_blocking(move || ask(&workflow, &element, &page, &kinds))
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    audit::record(
+        app,
+        AuditCategory::Automation,
+        if allowed { "workflow.click.allowed" } else { "workflow.click.declined" },
+        json!({
+            "run_id": report.run_id,
+            "workflow": report.workflow_name,
+            "page": page,
+            "element": element,
+            "risks": risks,
+            "opted_in": opted_in,
+        }),
+    );
+    if !allowed {
+        return Err(format!("Click on \"{}\" was declined", element));
+    }
+    Ok(json!({ "allowed": true, "risks": risks }))
+}
@@ -0,0 +1,316 @@
+// Clipboard history (opt-in)
+// While enabled, copied text is recorded in clipboard-history.json, newest
+// first, with pinned entries kept at the top and exempt from trimming. Text
+// that password managers mark as concealed is never recorded.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, ClipboardManager, Manager, Window};
+
+use crate::storage;
+
+const CONFIG_FILE: &str = "clipboard.json";
+const HISTORY_FILE: &str = "clipboard-history.json";
+const POLL_INTERVAL: Duration = Duration::from_millis(750);
+// Longer copies are skipped rather than truncated
+const MAX_ENTRY_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    enabled: bool,
+    // Unpinned entries kept
+    max_items: usize,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_items: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct ClipboardEntry {
+    text: String,
+    copied_at: i64,
+    pinned: bool,
+}
+
+// History entry as listed; `index` is what `paste_item` and friends take
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ClipboardItem {
+    index: usize,
+    text: String,
+    copied_at: i64,
+    pinned: bool,
+}
+
+#[derive(Default)]
+pub struct ClipboardState {
+    config: Mutex<ClipboardConfig>,
+    history: Mutex<Vec<ClipboardEntry>>,
+    // Last text seen on the clipboard, recorded or not
+    last_seen: Mutex<Option<String>>,
+}
+
+impl ClipboardState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+            history: Mutex::new(storage::load(app, HISTORY_FILE)),
+            last_seen: Mutex::new(None),
+        }
+    }
+}
+
+// Pinned first, then newest first; drop the oldest unpinned past `max_items`
+fn normalize(history: &mut Vec<ClipboardEntry>, max_items: usize) {
+    history.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.copied_at.cmp(&a.copied_at)));
+    let mut unpinned = 0;
+    history.retain(|e| {
+        if e.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= max_items
+    });
+}
+
+fn record(app: &AppHandle, text: String) -> Result<(), String> {
+    let state = app.state::<ClipboardState>();
+    let max_items = state.config.lock().unwrap().max_items;
+    let mut history = state.history.lock().unwrap();
+    let pinned = history.iter().any(|e| e.text == text && e.pinned);
+    history.retain(|e| e.text != text);
+    history.push(ClipboardEntry {
+        text,
+        copied_at: chrono::Utc::now().timestamp_millis(),
+        pinned,
+    });
+    normalize(&mut history, max_items);
+    storage::save(app, HISTORY_FILE, &*history)?;
+    let _ = app.emit_all("clipboard-history-changed", history.len());
+    Ok(())
+}
+
+fn poll(app: &AppHandle) {
+    let state = app.state::<ClipboardState>();
+    if !state.config.lock().unwrap().enabled {
+        return;
+    }
+    let Ok(Some(text)) = app.clipboard_manager().read_text() else { return };
+    {
+        let mut last_seen = state.last_seen.lock().unwrap();
+        if last_seen.as_deref() == Some(text.as_str()) {
+            return;
+        }
+        *last_seen = Some(text.clone());
+    }
+    if text.trim().is_empty() || text.len() > MAX_ENTRY_LEN || platform::is_concealed() {
+        return;
+    }
+    if let Err(e) = record(app, text) {
+        eprintln!("Failed to record clipboard entry: {}", e);
+    }
+}
+
+pub fn start_watcher(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        poll(&app);
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+fn save_history(app: &AppHandle, history: &[ClipboardEntry]) -> Result<(), String> {
+    storage::save(app, HISTORY_FILE, &history)?;
+    let _ = app.emit_all("clipboard-history-changed", history.len());
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_clipboard_config(state: tauri::State<'_, ClipboardState>) -> Result<ClipboardConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_clipboard_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ClipboardState>,
+    config: ClipboardConfig,
+) -> Result<(), String> {
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    // Whatever is on the clipboard when recording starts is not history
+    *state.last_seen.lock().unwrap() = app_handle.clipboard_manager().read_text().ok().flatten();
+    let mut history = state.history.lock().unwrap();
+    normalize(&mut history, config.max_items);
+    save_history(&app_handle, &history)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+// Entries matching `query` (case-insensitive), pinned first then newest
+#[tauri::command]
+#[specta::specta]
+pub async fn get_clipboard_history(
+    state: tauri::State<'_, ClipboardState>,
+    query: Option<String>,
+) -> Result<Vec<ClipboardItem>, String> {
+    let query = query.map(|q| q.to_lowercase()).filter(|q| !q.is_empty());
+    let history = state.history.lock().unwrap();
+    Ok(history
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| query.as_ref().map_or(true, |q| e.text.to_lowercase().contains(q)))
+        .map(|(index, e)| ClipboardItem {
+            index,
+            text: e.text.clone(),
+            copied_at: e.copied_at,
+            pinned: e.pinned,
+        })
+        .collect())
+}
+
+// Put an entry back on the clipboard and insert it at the focus of the
+// calling window
+#[tauri::command]
+#[specta::specta]
+pub async fn paste_item(
+    app_handle: AppHandle,
+    window: Window,
+    state: tauri::State<'_, ClipboardState>,
+    index: usize,
+) -> Result<(), String> {
+    let text = {
+        let history = state.history.lock().unwrap();
+        history.get(index).map(|e| e.text.clone()).ok_or("No such clipboard entry")?
+    };
+    *state.last_seen.lock().unwrap() = Some(text.clone());
+    app_handle
+        .clipboard_manager()
+        .write_text(text.clone())
+        .map_err(|e| e.to_string())?;
+    let literal = serde_json::to_string(&text).map_err(|e| e.to_string())?;
+    window
+        .eval(&format!("document.execCommand('insertText', false, {})", literal))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_clipboard_item_pinned(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ClipboardState>,
+    index: usize,
+    pinned: bool,
+) -> Result<(), String> {
+    let max_items = state.config.lock().unwrap().max_items;
+    let mut history = state.history.lock().unwrap();
+    history.get_mut(index).ok_or("No such clipboard entry")?.pinned = pinned;
+    normalize(&mut history, max_items);
+    save_history(&app_handle, &history)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_clipboard_item(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ClipboardState>,
+    index: usize,
+) -> Result<(), String> {
+    let mut history = state.history.lock().unwrap();
+    if index >= history.len() {
+        return Err("No such clipboard entry".to_string());
+    }
+    history.remove(index);
+    save_history(&app_handle, &history)
+}
+
+// Clears unpinned entries, or everything with `include_pinned`
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_clipboard_history(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ClipboardState>,
+    include_pinned: bool,
+) -> Result<(), String> {
+    let mut history = state.history.lock().unwrap();
+    history.retain(|e| e.pinned && !include_pinned);
+    save_history(&app_handle, &history)
+}
+
+// Whether the current clipboard content is flagged as a password by the
+// application that put it there
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::core::w;
+    use windows::Win32::System::DataExchange::{IsClipboardFormatAvailable, RegisterClipboardFormatW};
+
+    pub fn is_concealed() -> bool {
+        // Set by password managers (KeePass, 1Password, Bitwarden, ...)
+        unsafe {
+            let format = RegisterClipboardFormatW(w!("ExcludeClipboardContentFromMonitorProcessing"));
+            format != 0 && IsClipboardFormatAvailable(format).is_ok()
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use cocoa::base::{id, nil, NO};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    pub fn is_concealed() -> bool {
+        // nspasteboard.org convention for passwords
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let types: id = msg_send![pasteboard, types];
+            if types == nil {
+                return false;
+            }
+            let concealed = NSString::alloc(nil).init_str("org.nspasteboard.ConcealedType");
+            let found: cocoa::base::BOOL = msg_send![types, containsObject: concealed];
+            let _: () = msg_send![concealed, release];
+            found != NO
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    // KDE/KeePassXC hint; the value is "secret"
+    const PASSWORD_HINT: &str = "x-kde-passwordManagerHint";
+
+    fn targets() -> Option<String> {
+        let output = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            Command::new("wl-paste").arg("--list-types").output().ok()?
+        } else {
+            Command::new("xclip")
+                .args(["-selection", "clipboard", "-t", "TARGETS", "-o"])
+                .output()
+                .ok()?
+        };
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    pub fn is_concealed() -> bool {
+        targets().map_or(false, |targets| targets.lines().any(|t| t.trim() == PASSWORD_HINT))
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    pub fn is_concealed() -> bool {
+        false
+    }
+}
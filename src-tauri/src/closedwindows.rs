@@ -0,0 +1,169 @@
+// Recently closed windows and window cycling
+// Browser windows are recorded when the user asks to close them, with their
+// URL, container and geometry, and pushed onto a stack once they are actually
+// destroyed (kiosk mode can refuse the close). `reopen_closed_window` pops the
+// most recent one; `cycle_windows` moves focus through the visible windows.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Window, WindowUrl};
+
+use crate::{containers, idle, kiosk, windowpool};
+
+// Older entries are dropped
+const MAX_CLOSED: usize = 25;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ClosedWindow {
+    url: String,
+    container_id: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    closed_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CycleDirection {
+    Next,
+    Previous,
+}
+
+#[derive(Default)]
+pub struct ClosedWindowState {
+    // Window label -> snapshot taken on CloseRequested
+    pending: Mutex<HashMap<String, ClosedWindow>>,
+    // Most recently closed last
+    closed: Mutex<Vec<ClosedWindow>>,
+}
+
+// Called on CloseRequested, while the window still has its URL and geometry
+pub fn on_close_requested(window: &Window) {
+    // The main window only hides to the tray
+    if window.label() == "main" || !window.is_visible().unwrap_or(false) {
+        return;
+    }
+    let Ok(url) = window.url() else { return };
+    if !matches!(url.scheme(), "http" | "https") {
+        return;
+    }
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let app = window.app_handle();
+    let snapshot = ClosedWindow {
+        url: url.to_string(),
+        container_id: containers::window_container(&app, window.label()),
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        closed_at: chrono::Utc::now().timestamp_millis(),
+    };
+    app.state::<ClosedWindowState>()
+        .pending
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), snapshot);
+}
+
+// Called on Destroyed; only windows that really closed become reopenable
+pub fn forget_window(app: &AppHandle, label: &str) {
+    let state = app.state::<ClosedWindowState>();
+    let Some(snapshot) = state.pending.lock().unwrap().remove(label) else {
+        return;
+    };
+    let mut closed = state.closed.lock().unwrap();
+    if closed.len() >= MAX_CLOSED {
+        closed.remove(0);
+    }
+    closed.push(snapshot);
+}
+
+pub fn reopen(app: &AppHandle) -> Result<Option<ClosedWindow>, String> {
+    kiosk::ensure_inactive(app)?;
+    idle::ensure_unlocked(app)?;
+    let Some(entry) = app.state::<ClosedWindowState>().closed.lock().unwrap().pop() else {
+        return Ok(None);
+    };
+    let url: url::Url = entry.url.parse().map_err(|e| format!("Invalid URL: {}", e))?;
+    let window = match entry.container_id.as_deref() {
+        // The container may have been deleted since; open it without one then
+        Some(id) => containers::open_window(app, WindowUrl::External(url.clone()), id)
+            .or_else(|_| windowpool::build_window(app, WindowUrl::External(url), false))?,
+        None => windowpool::build_window(app, WindowUrl::External(url), false)?,
+    };
+    window
+        .set_size(PhysicalSize::new(entry.width, entry.height))
+        .map_err(|e| e.to_string())?;
+    // Skip the old position if that monitor is gone
+    let on_screen = window.available_monitors().unwrap_or_default().iter().any(|m| {
+        let (pos, size) = (m.position(), m.size());
+        entry.x >= pos.x
+            && entry.y >= pos.y
+            && entry.x < pos.x + size.width as i32
+            && entry.y < pos.y + size.height as i32
+    });
+    if on_screen {
+        window
+            .set_position(PhysicalPosition::new(entry.x, entry.y))
+            .map_err(|e| e.to_string())?;
+    }
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    Ok(Some(entry))
+}
+
+// Focus the next or previous visible window, in label order
+pub fn cycle(app: &AppHandle, direction: CycleDirection) -> Result<(), String> {
+    kiosk::ensure_inactive(app)?;
+    idle::ensure_unlocked(app)?;
+    let mut windows: Vec<Window> = app
+        .windows()
+        .into_values()
+        .filter(|w| w.is_visible().unwrap_or(false))
+        .collect();
+    if windows.len() < 2 {
+        return Ok(());
+    }
+    windows.sort_by(|a, b| a.label().cmp(b.label()));
+    let current = windows
+        .iter()
+        .position(|w| w.is_focused().unwrap_or(false))
+        .unwrap_or(0);
+    let target = match direction {
+        CycleDirection::Next => (current + 1) % windows.len(),
+        CycleDirection::Previous => (current + windows.len() - 1) % windows.len(),
+    };
+    let window = &windows[target];
+    if window.is_minimized().unwrap_or(false) {
+        window.unminimize().map_err(|e| e.to_string())?;
+    }
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+// Returns the reopened window's entry, or None if nothing was closed
+#[tauri::command]
+#[specta::specta]
+pub async fn reopen_closed_window(app_handle: AppHandle) -> Result<Option<ClosedWindow>, String> {
+    reopen(&app_handle)
+}
+
+// Most recently closed first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_closed_windows(
+    state: tauri::State<'_, ClosedWindowState>,
+) -> Result<Vec<ClosedWindow>, String> {
+    Ok(state.closed.lock().unwrap().iter().rev().cloned().collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cycle_windows(app_handle: AppHandle, direction: CycleDirection) -> Result<(), String> {
+    cycle(&app_handle, direction)
+}
@@ -0,0 +1,192 @@
+// Container windows
+// A container is a named partition (Work, Personal, Shopping, ...) with its
+// own webview data directory, so cookies, local storage and caches are not
+// shared with other containers or with ordinary windows. Windows are put in
+// a container when created; the container's name and color are available to
+// the frontend for labelling. WKWebView in this Tauri version ignores the
+// data directory, so on macOS containers only label windows.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window, WindowUrl};
+
+use crate::{storage, windowpool};
+
+const CONTAINERS_FILE: &str = "containers.json";
+const CONTAINERS_DIR: &str = "containers";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerColor {
+    Blue,
+    Turquoise,
+    Green,
+    Yellow,
+    Orange,
+    Red,
+    Pink,
+    Purple,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Container {
+    id: String,
+    name: String,
+    color: ContainerColor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ContainerList {
+    containers: Vec<Container>,
+}
+
+impl Default for ContainerList {
+    fn default() -> Self {
+        let container = |id: &str, name: &str, color| Container {
+            id: id.to_string(),
+            name: name.to_string(),
+            color,
+        };
+        Self {
+            containers: vec![
+                container("work", "Work", ContainerColor::Blue),
+                container("personal", "Personal", ContainerColor::Green),
+                container("shopping", "Shopping", ContainerColor::Pink),
+            ],
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ContainerState {
+    list: Mutex<ContainerList>,
+    // Window label -> container id
+    windows: Mutex<HashMap<String, String>>,
+}
+
+impl ContainerState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            list: Mutex::new(storage::load(app, CONTAINERS_FILE)),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+fn data_directory(app: &AppHandle, container_id: &str) -> Result<PathBuf, String> {
+    storage::data_path(app, &format!("{}/{}", CONTAINERS_DIR, container_id))
+}
+
+fn find(app: &AppHandle, container_id: &str) -> Result<Container, String> {
+    app.state::<ContainerState>()
+        .list
+        .lock()
+        .unwrap()
+        .containers
+        .iter()
+        .find(|c| c.id == container_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown container: {}", container_id))
+}
+
+// Container of an open window, if any
+pub fn window_container(app: &AppHandle, label: &str) -> Option<String> {
+    app.state::<ContainerState>().windows.lock().unwrap().get(label).cloned()
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<ContainerState>().windows.lock().unwrap().remove(label);
+}
+
+// Container windows are never pooled; each needs its own data directory
+pub fn open_window(app: &AppHandle, url: WindowUrl, container_id: &str) -> Result<Window, String> {
+    let container = find(app, container_id)?;
+    let window = windowpool::build_window_in(app, url, true, Some(data_directory(app, &container.id)?))?;
+    window
+        .set_title(&format!("MadEasy Browser — {}", container.name))
+        .map_err(|e| e.to_string())?;
+    app.state::<ContainerState>()
+        .windows
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), container.id.clone());
+    let _ = app.emit_all("window-container", (window.label(), &container));
+    Ok(window)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_containers(state: tauri::State<'_, ContainerState>) -> Result<Vec<Container>, String> {
+    Ok(state.list.lock().unwrap().containers.clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_container(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ContainerState>,
+    name: String,
+    color: ContainerColor,
+) -> Result<Container, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Container name cannot be empty".to_string());
+    }
+
+    let mut list = state.list.lock().unwrap();
+    let base: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut id = base.clone();
+    let mut n = 1;
+    while list.containers.iter().any(|c| c.id == id) {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+
+    let container = Container { id, name, color };
+    list.containers.push(container.clone());
+    storage::save(&app_handle, CONTAINERS_FILE, &*list)?;
+    Ok(container)
+}
+
+// Deletes the container's cookies and storage too
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_container(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ContainerState>,
+    container_id: String,
+) -> Result<(), String> {
+    if state.windows.lock().unwrap().values().any(|id| *id == container_id) {
+        return Err("Close the container's windows first".to_string());
+    }
+    {
+        let mut list = state.list.lock().unwrap();
+        let before = list.containers.len();
+        list.containers.retain(|c| c.id != container_id);
+        if list.containers.len() == before {
+            return Err(format!("Unknown container: {}", container_id));
+        }
+        storage::save(&app_handle, CONTAINERS_FILE, &*list)?;
+    }
+    let dir = data_directory(&app_handle, &container_id)?;
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_window_container(app_handle: AppHandle, window_id: String) -> Result<Option<Container>, String> {
+    match window_container(&app_handle, &window_id) {
+        Some(id) => find(&app_handle, &id).map(Some),
+        None => Ok(None),
+    }
+}
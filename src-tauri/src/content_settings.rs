@@ -0,0 +1,261 @@
+// Per-site content settings
+// JavaScript, images and popups can be turned off for individual origins,
+// per profile (content-settings.json), for lightweight or distraction-free
+// browsing. Where the engine allows it the webview settings are switched as
+// each top-level navigation starts: scripting and image loading on WebKitGTK,
+// scripting plus an image request filter on WebView2, and popup requests are
+// refused on both. New windows also get a script that stops `window.open` and
+// hides images; on macOS that script is all there is, so JavaScript can't be
+// turned off there.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{profiles, storage, windowpool};
+
+const SETTINGS_FILE: &str = "content-settings.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ContentSetting {
+    #[serde(default = "allowed")]
+    javascript: bool,
+    #[serde(default = "allowed")]
+    images: bool,
+    #[serde(default = "allowed")]
+    popups: bool,
+}
+
+fn allowed() -> bool {
+    true
+}
+
+impl Default for ContentSetting {
+    fn default() -> Self {
+        Self {
+            javascript: true,
+            images: true,
+            popups: true,
+        }
+    }
+}
+
+// Origin ("https://example.com") -> setting; origins not listed allow everything
+type SiteSettings = HashMap<String, ContentSetting>;
+
+#[derive(Default)]
+pub struct ContentSettingsState {
+    // Profile id -> settings, loaded on first use
+    profiles: Mutex<HashMap<String, SiteSettings>>,
+}
+
+fn settings(app: &AppHandle, profile_id: &str) -> SiteSettings {
+    let state = app.state::<ContentSettingsState>();
+    let mut cache = state.profiles.lock().unwrap();
+    cache
+        .entry(profile_id.to_string())
+        .or_insert_with(|| storage::load(app, &profiles::profile_file(profile_id, SETTINGS_FILE)))
+        .clone()
+}
+
+fn origin_of(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.origin().ascii_serialization())
+}
+
+// Setting for a page of the active profile
+pub fn setting_for(app: &AppHandle, url: &str) -> ContentSetting {
+    origin_of(url)
+        .and_then(|origin| settings(app, &profiles::active_profile(app)).get(&origin).copied())
+        .unwrap_or_default()
+}
+
+const CONTENT_SCRIPT: &str = r#"(function (SETTINGS) {
+  if (window.__MADEASY_CONTENT__) return;
+  window.__MADEASY_CONTENT__ = true;
+  var setting = SETTINGS[location.origin];
+  if (!setting) return;
+  if (setting.popups === false) {
+    window.open = function () { return null; };
+  }
+  if (setting.images === false) {
+    var style = document.createElement('style');
+    style.textContent = 'img, picture, video[poster], input[type=image] { visibility: hidden !important; } '
+      + '* { background-image: none !important; }';
+    (document.head || document.documentElement).appendChild(style);
+  }
+})"#;
+
+// Initialization script for new windows of the active profile
+pub fn initialization_script(app: &AppHandle) -> String {
+    let settings = settings(app, &profiles::active_profile(app));
+    format!(
+        "{}({});",
+        CONTENT_SCRIPT,
+        serde_json::to_string(&settings).unwrap_or_else(|_| "{}".to_string())
+    )
+}
+
+// Called on every window built by windowpool::build_window
+pub fn attach(app: &AppHandle, window: &Window) {
+    if let Err(e) = platform::install(app, window) {
+        eprintln!("Failed to install content settings hook: {}", e);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_content_settings(
+    app_handle: AppHandle,
+    profile_id: Option<String>,
+) -> Result<HashMap<String, ContentSetting>, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    Ok(settings(&app_handle, &profile))
+}
+
+// Allowing everything removes the origin's entry. Takes effect on the next
+// navigation; the injected script only changes for windows opened afterwards,
+// so pooled windows are rebuilt.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_content_setting(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ContentSettingsState>,
+    origin: String,
+    setting: ContentSetting,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let origin = origin_of(origin.trim()).ok_or_else(|| format!("Not an http(s) origin: {}", origin))?;
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut site_settings = settings(&app_handle, &profile);
+    if setting == ContentSetting::default() {
+        site_settings.remove(&origin);
+    } else {
+        site_settings.insert(origin, setting);
+    }
+    storage::save(&app_handle, &profiles::profile_file(&profile, SETTINGS_FILE), &site_settings)?;
+    state.profiles.lock().unwrap().insert(profile, site_settings);
+    windowpool::drain(&app_handle);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tauri::{AppHandle, Window};
+    use webview2_com::Microsoft::Web::WebView2::Win32::{ICoreWebView2_2, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_IMAGE};
+    use webview2_com::{NavigationStartingEventHandler, NewWindowRequestedEventHandler, WebResourceRequestedEventHandler};
+    use windows_webview2::core::{Interface, HSTRING, PWSTR};
+    use windows_webview2::Win32::System::Com::IStream;
+    use windows_webview2::Win32::System::WinRT::EventRegistrationToken;
+
+    pub fn install(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        window
+            .with_webview(move |webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let core = webview.controller().CoreWebView2()?;
+                    let environment = core.cast::<ICoreWebView2_2>()?.Environment()?;
+                    let images_blocked = Arc::new(AtomicBool::new(false));
+                    let popups_blocked = Arc::new(AtomicBool::new(false));
+                    let mut token = EventRegistrationToken::default();
+
+                    let (images, popups) = (images_blocked.clone(), popups_blocked.clone());
+                    let navigation = NavigationStartingEventHandler::create(Box::new(move |core, args| {
+                        let (Some(core), Some(args)) = (core, args) else { return Ok(()) };
+                        let mut uri = PWSTR::null();
+                        args.Uri(&mut uri)?;
+                        let setting = super::setting_for(&app, &webview2_com::take_pwstr(uri));
+                        core.Settings()?.SetIsScriptEnabled(setting.javascript)?;
+                        images.store(!setting.images, Ordering::Relaxed);
+                        popups.store(!setting.popups, Ordering::Relaxed);
+                        Ok(())
+                    }));
+                    core.add_NavigationStarting(&navigation, &mut token)?;
+
+                    core.AddWebResourceRequestedFilter(&HSTRING::from("*"), COREWEBVIEW2_WEB_RESOURCE_CONTEXT_IMAGE)?;
+                    let requests = WebResourceRequestedEventHandler::create(Box::new(move |_, args| {
+                        if let Some(args) = args {
+                            if images_blocked.load(Ordering::Relaxed) {
+                                let response = environment.CreateWebResourceResponse(
+                                    None::<&IStream>,
+                                    403,
+                                    &HSTRING::from("Blocked"),
+                                    &HSTRING::new(),
+                                )?;
+                                args.SetResponse(&response)?;
+                            }
+                        }
+                        Ok(())
+                    }));
+                    core.add_WebResourceRequested(&requests, &mut token)?;
+
+                    let new_windows = NewWindowRequestedEventHandler::create(Box::new(move |_, args| {
+                        if let Some(args) = args {
+                            if popups_blocked.load(Ordering::Relaxed) {
+                                args.SetHandled(true)?;
+                            }
+                        }
+                        Ok(())
+                    }));
+                    core.add_NewWindowRequested(&new_windows, &mut token)
+                })();
+                if let Err(e) = result {
+                    eprintln!("Failed to install content settings handlers: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tauri::{AppHandle, Window};
+    use webkit2gtk::{LoadEvent, PolicyDecisionExt, PolicyDecisionType, SettingsExt, WebViewExt};
+
+    pub fn install(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        window
+            .with_webview(move |webview| {
+                let view = webview.inner();
+                let popups_blocked = Rc::new(Cell::new(false));
+                let blocked = popups_blocked.clone();
+                // Only the main frame's loads are reported here
+                view.connect_load_changed(move |view, event| {
+                    if !matches!(event, LoadEvent::Started | LoadEvent::Redirected) {
+                        return;
+                    }
+                    let Some(uri) = view.uri() else { return };
+                    let setting = super::setting_for(&app, &uri);
+                    if let Some(settings) = WebViewExt::settings(view) {
+                        settings.set_enable_javascript(setting.javascript);
+                        settings.set_auto_load_images(setting.images);
+                    }
+                    blocked.set(!setting.popups);
+                });
+                view.connect_decide_policy(move |_, decision, kind| {
+                    if kind == PolicyDecisionType::NewWindowAction && popups_blocked.get() {
+                        decision.ignore();
+                        return true;
+                    }
+                    false
+                });
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    use tauri::{AppHandle, Window};
+
+    // WKWebView's navigation delegate belongs to wry; only the injected script applies
+    pub fn install(_app: &AppHandle, _window: &Window) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,348 @@
+// Custom context-menu items registered by the frontend and plugins
+// The injected listener reports what was right-clicked; Rust picks the matching
+// entries, asks the shell to show them and dispatches the click back with context.
+// Built-in items (owner "app") carry a message id as their title and are
+// handled here; "Ask AI about selection" hands the selected text to the chat.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{i18n, matching, pagequery, plugins};
+
+pub const BUILTIN_OWNER: &str = "app";
+const ASK_AI_ITEM: &str = "ask-ai-selection";
+const SELECTION_TIMEOUT: Duration = Duration::from_secs(3);
+// Longest selection passed to the chat
+const MAX_SELECTION_CHARS: usize = 8000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuContext {
+    Page,
+    Selection,
+    Link,
+    Image,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ContextMenuItem {
+    id: String,
+    title: String,
+    // Which kinds of targets the item applies to (empty = all)
+    #[serde(default)]
+    contexts: Vec<MenuContext>,
+    // Page URL patterns with `*` wildcards (empty = all pages)
+    #[serde(default)]
+    url_patterns: Vec<String>,
+    // Registering plugin id, if any
+    #[serde(default)]
+    owner: Option<String>,
+}
+
+// What the user right-clicked on, as reported by the injected listener
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ClickContext {
+    page_url: String,
+    #[serde(default)]
+    selection: Option<String>,
+    #[serde(default)]
+    link_url: Option<String>,
+    #[serde(default)]
+    image_url: Option<String>,
+    #[serde(default)]
+    x: f64,
+    #[serde(default)]
+    y: f64,
+}
+
+impl ClickContext {
+    fn kinds(&self) -> Vec<MenuContext> {
+        let mut kinds = vec![MenuContext::Page];
+        if self.selection.as_deref().map_or(false, |s| !s.trim().is_empty()) {
+            kinds.push(MenuContext::Selection);
+        }
+        if self.link_url.is_some() {
+            kinds.push(MenuContext::Link);
+        }
+        if self.image_url.is_some() {
+            kinds.push(MenuContext::Image);
+        }
+        kinds
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+struct MenuShowPayload {
+    items: Vec<ContextMenuItem>,
+    x: f64,
+    y: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MenuClickPayload {
+    pub item_id: String,
+    pub window_id: String,
+    pub owner: Option<String>,
+    pub context: ClickContext,
+}
+
+// Sent to the main window with `ai-ask-selection`; the chat panel adds it to
+// the active conversation
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SelectionQuestion {
+    window_id: String,
+    page_title: String,
+    page_url: String,
+    selection: String,
+    truncated: bool,
+}
+
+#[derive(Default)]
+pub struct ContextMenuState {
+    items: Mutex<Vec<ContextMenuItem>>,
+    // Last right-click context per window, consumed when an item is clicked
+    pending: Mutex<HashMap<String, ClickContext>>,
+}
+
+impl ContextMenuItem {
+    fn applies_to(&self, context: &ClickContext) -> bool {
+        let kinds = context.kinds();
+        let context_ok =
+            self.contexts.is_empty() || self.contexts.iter().any(|c| kinds.contains(c));
+        context_ok && matching::any_match(&self.url_patterns, &context.page_url)
+    }
+}
+
+const LISTENER_SCRIPT: &str = r#"(function () {
+  if (window.__MADEASY_CONTEXT_MENU__ || !window.__TAURI_INVOKE__) return;
+  window.__MADEASY_CONTEXT_MENU__ = true;
+  window.addEventListener('contextmenu', function (e) {
+    var link = e.target.closest && e.target.closest('a[href]');
+    var image = e.target.closest && e.target.closest('img[src]');
+    window.__TAURI_INVOKE__('context_menu_opened', { context: {
+      page_url: location.href,
+      selection: String(window.getSelection() || '') || null,
+      link_url: link ? link.href : null,
+      image_url: image ? image.src : null,
+      x: e.screenX, y: e.screenY
+    } });
+  });
+})();"#;
+
+// Install the context-menu listener into a freshly loaded page
+pub fn inject(window: &Window) {
+    let _ = window.eval(LISTENER_SCRIPT);
+}
+
+// Replace all items owned by a plugin
+pub fn register_owned(app: &AppHandle, owner: &str, owned: Vec<ContextMenuItem>) {
+    let state = app.state::<ContextMenuState>();
+    let mut items = state.items.lock().unwrap();
+    items.retain(|item| item.owner.as_deref() != Some(owner));
+    for mut item in owned {
+        if item.id.is_empty() || item.title.is_empty() {
+            continue;
+        }
+        item.id = format!("{}:{}", owner, item.id);
+        item.owner = Some(owner.to_string());
+        items.retain(|existing| existing.id != item.id);
+        items.push(item);
+    }
+}
+
+pub fn register_builtin(app: &AppHandle) {
+    register_owned(
+        app,
+        BUILTIN_OWNER,
+        vec![ContextMenuItem {
+            id: ASK_AI_ITEM.to_string(),
+            title: "menu-ask-ai-selection".to_string(),
+            contexts: vec![MenuContext::Selection],
+            url_patterns: Vec::new(),
+            owner: None,
+        }],
+    );
+}
+
+const SELECTION_SCRIPT: &str = r#"
+var selection = String(window.getSelection() || '');
+var active = document.activeElement;
+// Selections inside text fields aren't part of window.getSelection() in every engine
+if (!selection && active && typeof active.selectionStart === 'number' && active.type !== 'password') {
+  selection = active.value.substring(active.selectionStart, active.selectionEnd);
+}
+return { selection: selection, title: document.title, url: location.href };
+"#;
+
+// Read the window's selection and send it, with the page title and URL, to
+// the chat panel in the main window
+pub async fn ask_about_selection(app: &AppHandle, window: &Window) -> Result<SelectionQuestion, String> {
+    let page = pagequery::run(app, window, SELECTION_SCRIPT, SELECTION_TIMEOUT).await?;
+    let text = |key: &str| page.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let selection = text("selection").trim().to_string();
+    if selection.is_empty() {
+        return Err("Nothing is selected".to_string());
+    }
+    let (selection, truncated) = match selection.char_indices().nth(MAX_SELECTION_CHARS) {
+        Some((end, _)) => (selection[..end].to_string(), true),
+        None => (selection, false),
+    };
+    let question = SelectionQuestion {
+        window_id: window.label().to_string(),
+        page_title: text("title"),
+        page_url: text("url"),
+        selection,
+        truncated,
+    };
+    let main = app.get_window("main").ok_or("Main window not found")?;
+    let _ = main.show();
+    let _ = main.set_focus();
+    main.emit("ai-ask-selection", &question).map_err(|e| e.to_string())?;
+    Ok(question)
+}
+
+pub fn unregister_owned(app: &AppHandle, owner: &str) {
+    let state = app.state::<ContextMenuState>();
+    state
+        .items
+        .lock()
+        .unwrap()
+        .retain(|item| item.owner.as_deref() != Some(owner));
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn register_context_menu_item(
+    state: tauri::State<'_, ContextMenuState>,
+    item: ContextMenuItem,
+) -> Result<(), String> {
+    if item.id.is_empty() || item.title.is_empty() {
+        return Err("Context menu items need an id and a title".to_string());
+    }
+    // Ownership is only assigned through `register_owned`
+    let item = ContextMenuItem { owner: None, ..item };
+
+    let mut items = state.items.lock().unwrap();
+    if items.iter().any(|existing| existing.id == item.id && existing.owner.is_some()) {
+        return Err(format!("Context menu item {} belongs to another owner", item.id));
+    }
+    items.retain(|existing| existing.id != item.id);
+    items.push(item);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn unregister_context_menu_item(
+    state: tauri::State<'_, ContextMenuState>,
+    id: String,
+) -> Result<(), String> {
+    state
+        .items
+        .lock()
+        .unwrap()
+        .retain(|item| item.id != id || item.owner.is_some());
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_context_menu_items(
+    state: tauri::State<'_, ContextMenuState>,
+) -> Result<Vec<ContextMenuItem>, String> {
+    Ok(state.items.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn context_menu_opened(
+    window: Window,
+    state: tauri::State<'_, ContextMenuState>,
+    context: ClickContext,
+) -> Result<(), String> {
+    let items: Vec<ContextMenuItem> = state
+        .items
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|item| item.applies_to(&context))
+        .cloned()
+        .map(|mut item| {
+            if item.owner.as_deref() == Some(BUILTIN_OWNER) {
+                item.title = i18n::text(&window.app_handle(), &item.title);
+            }
+            item
+        })
+        .collect();
+
+    let payload = MenuShowPayload {
+        items,
+        x: context.x,
+        y: context.y,
+    };
+    state
+        .pending
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), context);
+
+    window
+        .emit("context-menu-show", payload)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn context_menu_item_clicked(
+    app_handle: AppHandle,
+    window: Window,
+    state: tauri::State<'_, ContextMenuState>,
+    item_id: String,
+) -> Result<(), String> {
+    let owner = state
+        .items
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|item| item.id == item_id)
+        .map(|item| item.owner.clone())
+        .ok_or_else(|| format!("Unknown context menu item: {}", item_id))?;
+    let context = state
+        .pending
+        .lock()
+        .unwrap()
+        .remove(window.label())
+        .unwrap_or_default();
+
+    let payload = MenuClickPayload {
+        item_id,
+        window_id: window.label().to_string(),
+        owner,
+        context,
+    };
+    match payload.owner.as_deref() {
+        Some(BUILTIN_OWNER) => {
+            if payload.item_id == format!("{}:{}", BUILTIN_OWNER, ASK_AI_ITEM) {
+                ask_about_selection(&app_handle, &window).await?;
+            }
+        }
+        Some(owner) => plugins::on_context_menu(&app_handle, owner, &payload),
+        None => {}
+    }
+    app_handle
+        .emit_all("context-menu-clicked", payload)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn ask_ai_about_selection(app_handle: AppHandle, window_id: String) -> Result<SelectionQuestion, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    ask_about_selection(&app_handle, &window).await
+}
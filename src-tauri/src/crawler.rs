@@ -0,0 +1,341 @@
+// Breadth-first site crawler
+// Walks a site from `start_url` and returns the pages as a tree (each page
+// under the page it was first linked from) with titles and status codes.
+// Politeness: robots.txt is honoured for our user agent, requests go out one
+// at a time with at least `MIN_DELAY` (or the site's Crawl-delay) between
+// them, and `crawl-progress` is emitted as pages come in.
+
+use reqwest::{Method, Url};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::linkcheck::{self, LinkKind};
+use crate::{bandwidth, tasks};
+
+const MIN_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(10);
+const MAX_PAGES: usize = 1000;
+const ROBOTS_AGENT: &str = "madeasybrowser";
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct CrawlRequest {
+    start_url: String,
+    #[serde(default = "default_max_pages")]
+    max_pages: usize,
+    #[serde(default = "default_same_origin")]
+    same_origin_only: bool,
+}
+
+fn default_max_pages() -> usize {
+    100
+}
+
+fn default_same_origin() -> bool {
+    true
+}
+
+// Workflow step: crawl and hand the pages on as rows for scraping
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CrawlAction {
+    pub start_url: String,
+    #[serde(default)]
+    pub max_pages: Option<usize>,
+    #[serde(default)]
+    pub same_origin_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CrawlNode {
+    url: String,
+    title: Option<String>,
+    status: Option<u16>,
+    error: Option<String>,
+    depth: u32,
+    children: Vec<CrawlNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CrawlResult {
+    root: CrawlNode,
+    pages: usize,
+    // Stopped at max_pages with pages still queued
+    truncated: bool,
+    // URLs robots.txt told us to skip
+    disallowed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+struct CrawlProgress {
+    start_url: String,
+    url: String,
+    crawled: usize,
+    queued: usize,
+}
+
+struct Page {
+    url: Url,
+    parent: Option<usize>,
+    depth: u32,
+    title: Option<String>,
+    status: Option<u16>,
+    error: Option<String>,
+}
+
+// Allow/Disallow rules of the robots.txt group that applies to us
+#[derive(Default)]
+struct Robots {
+    rules: Vec<(bool, String)>,
+    delay: Option<Duration>,
+}
+
+impl Robots {
+    fn parse(text: &str) -> Self {
+        let mut specific = Robots::default();
+        let mut wildcard = Robots::default();
+        let mut has_specific = false;
+        // Agents of the group being read; consecutive User-agent lines share a group
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let (key, value) = (key.trim().to_lowercase(), value.trim());
+            if key == "user-agent" {
+                if in_rules {
+                    agents.clear();
+                    in_rules = false;
+                }
+                agents.push(value.to_lowercase());
+                continue;
+            }
+            in_rules = true;
+            let targets: Vec<&mut Robots> = {
+                let mine = agents.iter().any(|a| !a.is_empty() && a != "*" && ROBOTS_AGENT.contains(a.as_str()));
+                let any = agents.iter().any(|a| a == "*");
+                has_specific |= mine;
+                let mut targets = Vec::new();
+                if mine {
+                    targets.push(&mut specific);
+                }
+                if any {
+                    targets.push(&mut wildcard);
+                }
+                targets
+            };
+            for robots in targets {
+                match key.as_str() {
+                    "allow" if !value.is_empty() => robots.rules.push((true, value.to_string())),
+                    "disallow" if !value.is_empty() => robots.rules.push((false, value.to_string())),
+                    "crawl-delay" => robots.delay = value.parse::<f64>().ok().map(Duration::from_secs_f64),
+                    _ => {}
+                }
+            }
+        }
+        if has_specific {
+            specific
+        } else {
+            wildcard
+        }
+    }
+
+    // Longest matching rule wins; `*` and a trailing `$` are supported
+    fn allows(&self, url: &Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| robots_match(pattern, &path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .map_or(true, |(allow, _)| *allow)
+    }
+}
+
+fn robots_match(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let Some(mut rest) = path.strip_prefix(parts[0]) else { return false };
+    for part in &parts[1..] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    match (anchored, parts.len()) {
+        (false, _) => true,
+        (true, 1) => rest.is_empty(),
+        // The last piece has to sit at the very end, not just somewhere after
+        (true, _) => path.ends_with(parts[parts.len() - 1]),
+    }
+}
+
+async fn load_robots(app: &AppHandle, client: &reqwest::Client, origin: &Url) -> Robots {
+    let Ok(url) = origin.join("/robots.txt") else { return Robots::default() };
+    match linkcheck::request(client, Method::GET, &url).await {
+        Ok((response, _)) if response.status().is_success() => {
+            let text = response.text().await.unwrap_or_default();
+            bandwidth::record(app, url.as_str(), 0, text.len() as u64);
+            Robots::parse(&text)
+        }
+        _ => Robots::default(),
+    }
+}
+
+fn title_of(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("title").unwrap();
+    let title = document.select(&selector).next()?.text().collect::<String>();
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+fn build_tree(pages: &[Page], index: usize) -> CrawlNode {
+    let page = &pages[index];
+    CrawlNode {
+        url: page.url.to_string(),
+        title: page.title.clone(),
+        status: page.status,
+        error: page.error.clone(),
+        depth: page.depth,
+        children: (0..pages.len())
+            .filter(|&i| pages[i].parent == Some(index))
+            .map(|i| build_tree(pages, i))
+            .collect(),
+    }
+}
+
+pub async fn crawl(app: &AppHandle, request: CrawlRequest) -> Result<CrawlResult, String> {
+    let start = Url::parse(&request.start_url).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(start.scheme(), "http" | "https") {
+        return Err("Only http(s) sites can be crawled".to_string());
+    }
+    let max_pages = request.max_pages.clamp(1, MAX_PAGES);
+    let client = linkcheck::client()?;
+
+    let robots = load_robots(app, &client, &start).await;
+    let delay = robots.delay.unwrap_or(MIN_DELAY).clamp(MIN_DELAY, MAX_DELAY);
+    let mut other_robots: Vec<(String, Robots)> = Vec::new();
+
+    let mut pages: Vec<Page> = Vec::new();
+    let mut disallowed = Vec::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start.clone(), None, 0)]);
+    while let Some((url, parent, depth)) = queue.pop_front() {
+        if pages.len() >= max_pages {
+            queue.push_front((url, parent, depth));
+            break;
+        }
+        let allowed = if url.origin() == start.origin() {
+            robots.allows(&url)
+        } else {
+            let origin = url.origin().ascii_serialization();
+            if !other_robots.iter().any(|(o, _)| *o == origin) {
+                other_robots.push((origin.clone(), load_robots(app, &client, &url).await));
+            }
+            other_robots.iter().find(|(o, _)| *o == origin).map_or(true, |(_, r)| r.allows(&url))
+        };
+        if !allowed {
+            disallowed.push(url.to_string());
+            continue;
+        }
+        if !pages.is_empty() {
+            tokio::time::sleep(delay).await;
+        }
+
+        let mut page = Page {
+            url: url.clone(),
+            parent,
+            depth,
+            title: None,
+            status: None,
+            error: None,
+        };
+        match linkcheck::request(&client, Method::GET, &url).await {
+            Ok((response, _)) => {
+                page.status = Some(response.status().as_u16());
+                let is_html = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map_or(false, |t| t.contains("html"));
+                if response.status().is_success() && is_html {
+                    let final_url = response.url().clone();
+                    let html = response.text().await.unwrap_or_default();
+                    bandwidth::record(app, final_url.as_str(), 0, html.len() as u64);
+                    page.title = title_of(&html);
+                    let index = pages.len();
+                    for (link, kind) in linkcheck::extract_links(&html, &final_url) {
+                        if kind != LinkKind::Anchor || (request.same_origin_only && link.origin() != start.origin()) {
+                            continue;
+                        }
+                        if seen.insert(link.clone()) {
+                            queue.push_back((link, Some(index), depth + 1));
+                        }
+                    }
+                }
+            }
+            Err(e) => page.error = Some(e),
+        }
+        pages.push(page);
+        let _ = app.emit_all(
+            "crawl-progress",
+            CrawlProgress {
+                start_url: start.to_string(),
+                url: url.to_string(),
+                crawled: pages.len(),
+                queued: queue.len(),
+            },
+        );
+    }
+
+    if pages.is_empty() {
+        return Err(format!("{} is disallowed by robots.txt", start));
+    }
+    Ok(CrawlResult {
+        root: build_tree(&pages, 0),
+        pages: pages.len(),
+        truncated: !queue.is_empty(),
+        disallowed,
+    })
+}
+
+fn flatten(node: &CrawlNode, rows: &mut Vec<Value>) {
+    rows.push(json!({
+        "url": node.url,
+        "title": node.title,
+        "status": node.status,
+        "depth": node.depth,
+    }));
+    for child in &node.children {
+        flatten(child, rows);
+    }
+}
+
+pub async fn run_action(app: &AppHandle, action: &CrawlAction) -> Result<Value, String> {
+    let request = CrawlRequest {
+        start_url: action.start_url.clone(),
+        max_pages: action.max_pages.unwrap_or_else(default_max_pages),
+        same_origin_only: action.same_origin_only.unwrap_or_else(default_same_origin),
+    };
+    let result = crawl(app, request).await?;
+    let mut rows = Vec::new();
+    flatten(&result.root, &mut rows);
+    Ok(json!({ "rows": rows, "truncated": result.truncated }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn crawl_site(app_handle: AppHandle, request: CrawlRequest) -> Result<CrawlResult, String> {
+    let name = format!("Crawl {}", request.start_url);
+    tasks::run(&app_handle, &name, crawl(&app_handle, request)).await
+}
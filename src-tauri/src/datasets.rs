@@ -0,0 +1,157 @@
+// Stored workflow datasets
+// The rows a workflow run produced, kept in SQLite so later runs can be
+// deduplicated against them. Datasets are keyed by run id.
+
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::db::Database;
+use crate::payloads::{self, PayloadRef};
+use crate::workflow::Row;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS datasets (
+    id TEXT PRIMARY KEY,
+    workflow_name TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    row_count INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS datasets_workflow ON datasets (workflow_name, created_at);
+CREATE TABLE IF NOT EXISTS dataset_rows (
+    dataset_id TEXT NOT NULL REFERENCES datasets(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (dataset_id, position)
+);
+";
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Dataset {
+    pub id: String,
+    pub workflow_name: String,
+    created_at: i64,
+    row_count: i64,
+}
+
+impl Dataset {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            workflow_name: row.get(1)?,
+            created_at: row.get(2)?,
+            row_count: row.get(3)?,
+        })
+    }
+}
+
+// Create or replace a dataset with the given rows
+pub fn save(db: &Database, id: &str, workflow_name: &str, rows: &[Row]) -> Result<(), String> {
+    let encoded = rows
+        .iter()
+        .map(|row| serde_json::to_string(row).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    db.with(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO datasets (id, workflow_name, created_at, row_count) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET row_count = excluded.row_count",
+            params![id, workflow_name, chrono::Utc::now().timestamp(), rows.len() as i64],
+        )?;
+        tx.execute("DELETE FROM dataset_rows WHERE dataset_id = ?1", params![id])?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO dataset_rows (dataset_id, position, data) VALUES (?1, ?2, ?3)")?;
+            for (position, data) in encoded.iter().enumerate() {
+                stmt.execute(params![id, position as i64, data])?;
+            }
+        }
+        tx.commit()
+    })
+}
+
+pub fn find(db: &Database, id: &str) -> Result<Option<Dataset>, String> {
+    db.with(|conn| {
+        conn.query_row(
+            "SELECT id, workflow_name, created_at, row_count FROM datasets WHERE id = ?1",
+            params![id],
+            Dataset::from_row,
+        )
+        .optional()
+    })
+}
+
+pub fn rows(db: &Database, id: &str) -> Result<Vec<Row>, String> {
+    let encoded: Vec<String> = db.with(|conn| {
+        let mut stmt = conn.prepare("SELECT data FROM dataset_rows WHERE dataset_id = ?1 ORDER BY position")?;
+        let rows = stmt.query_map(params![id], |row| row.get(0))?;
+        rows.collect()
+    })?;
+    encoded
+        .iter()
+        .map(|data| serde_json::from_str(data).map_err(|e| e.to_string()))
+        .collect()
+}
+
+// Rows of the workflow's datasets stored before the given one
+pub fn previous_rows(db: &Database, workflow_name: &str, dataset_id: &str) -> Result<Vec<Row>, String> {
+    let encoded: Vec<String> = db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT r.data FROM dataset_rows r JOIN datasets d ON d.id = r.dataset_id
+             WHERE d.workflow_name = ?1 AND d.id != ?2
+               AND d.created_at <= COALESCE((SELECT created_at FROM datasets WHERE id = ?2), d.created_at)
+             ORDER BY d.created_at, r.position",
+        )?;
+        let rows = stmt.query_map(params![workflow_name, dataset_id], |row| row.get(0))?;
+        rows.collect()
+    })?;
+    encoded
+        .iter()
+        .map(|data| serde_json::from_str(data).map_err(|e| e.to_string()))
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_datasets(db: tauri::State<'_, Database>, workflow_name: Option<String>) -> Result<Vec<Dataset>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, workflow_name, created_at, row_count FROM datasets
+             WHERE ?1 IS NULL OR workflow_name = ?1 ORDER BY created_at DESC",
+        )?;
+        let rows = stmt.query_map(params![workflow_name], Dataset::from_row)?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dataset_rows(db: tauri::State<'_, Database>, dataset_id: String) -> Result<Vec<Row>, String> {
+    rows(&db, &dataset_id)
+}
+
+// The rows as a JSON array over the payload channel, for datasets too large
+// to pass through an invoke response comfortably
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dataset_payload(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    dataset_id: String,
+) -> Result<PayloadRef, String> {
+    // Rows are stored as JSON already, so they can be joined without re-encoding
+    let encoded: Vec<String> = db.with(|conn| {
+        let mut stmt = conn.prepare("SELECT data FROM dataset_rows WHERE dataset_id = ?1 ORDER BY position")?;
+        let rows = stmt.query_map(params![dataset_id], |row| row.get(0))?;
+        rows.collect()
+    })?;
+    let json = format!("[{}]", encoded.join(","));
+    Ok(payloads::publish(&app_handle, json.into_bytes(), "application/json"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_dataset(db: tauri::State<'_, Database>, dataset_id: String) -> Result<(), String> {
+    db.with(|conn| conn.execute("DELETE FROM datasets WHERE id = ?1", params![dataset_id]))?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+// Shared SQLite database in the app data directory
+// Each subsystem contributes its schema; tables are created when the
+// connection is first opened
+
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+
+use crate::{
+    artifacts, audit, bandwidth, bookmarks, datasets, digest, downloads, feeds, history, notifications, pagemetrics,
+    readinglist, scanner, storage, tasks, visualdiff,
+};
+
+pub const DB_FILE: &str = "madeasy.db";
+
+pub struct Database {
+    path: PathBuf,
+    conn: OnceLock<Result<Mutex<Connection>, String>>,
+}
+
+fn connect(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+        .map_err(|e| e.to_string())?;
+
+    for schema in [
+        history::SCHEMA,
+        notifications::SCHEMA,
+        bookmarks::SCHEMA,
+        readinglist::SCHEMA,
+        feeds::SCHEMA,
+        digest::SCHEMA,
+        datasets::SCHEMA,
+        downloads::SCHEMA,
+        scanner::SCHEMA,
+        artifacts::SCHEMA,
+        audit::SCHEMA,
+        visualdiff::SCHEMA,
+        pagemetrics::SCHEMA,
+        bandwidth::SCHEMA,
+    ] {
+        conn.execute_batch(schema).map_err(|e| e.to_string())?;
+    }
+    Ok(conn)
+}
+
+impl Database {
+    // The connection is opened lazily, on first use or by `warm`
+    pub fn open(app: &AppHandle) -> Result<Self, String> {
+        Ok(Self {
+            path: storage::data_path(app, DB_FILE)?,
+            conn: OnceLock::new(),
+        })
+    }
+
+    fn connection(&self) -> Result<&Mutex<Connection>, String> {
+        self.conn
+            .get_or_init(|| connect(&self.path).map(Mutex::new))
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
+    // Open the connection and create tables ahead of the first query
+    pub fn warm(&self) -> Result<(), String> {
+        self.connection().map(|_| ())
+    }
+
+    // Fold the WAL back into the database file; a no-op if never opened
+    pub fn checkpoint(&self) -> Result<(), String> {
+        let Some(Ok(conn)) = self.conn.get() else {
+            return Ok(());
+        };
+        let conn = conn.lock().unwrap();
+        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+            .map_err(|e| e.to_string())
+    }
+
+    // Run a closure against the connection, mapping SQLite errors to strings
+    pub fn with<T>(&self, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        tasks::block_in_place(|| {
+            let conn = self.connection()?.lock().unwrap();
+            f(&conn).map_err(|e| e.to_string())
+        })
+    }
+}
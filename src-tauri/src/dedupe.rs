@@ -0,0 +1,222 @@
+// Deduplication of scraped rows
+// Rows are compared by exact key, fuzzy company/person name or web domain.
+// When a duplicate is found, fields the kept row lacks are merged in from it;
+// duplicates that add nothing are simply dropped. Runs can also be checked
+// against the workflow's previously stored datasets.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::datasets;
+use crate::db::Database;
+use crate::workflow::{self, Row, RunReport};
+
+// Legal-form suffixes ignored by fuzzy name matching
+const NAME_SUFFIXES: [&str; 14] = [
+    "as", "asa", "ab", "aps", "oy", "gmbh", "ag", "bv", "ltd", "limited", "inc", "llc", "plc", "sa",
+];
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DedupeStrategy {
+    // Rows are equal when all key fields are (case- and whitespace-insensitive)
+    ExactKey { fields: Vec<String> },
+    // Jaro-Winkler similarity of normalized names at or above the threshold
+    FuzzyName {
+        field: String,
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    // Same host (without "www.") in a URL or email field
+    Domain { field: String },
+}
+
+fn default_threshold() -> f64 {
+    0.92
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Duplicate {
+    // Index into the input rows
+    row: usize,
+    // Index of the row it duplicates; None when it matched a stored dataset
+    duplicate_of: Option<usize>,
+    merged_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DedupeReport {
+    total: usize,
+    kept: usize,
+    merged: usize,
+    dropped: usize,
+    duplicates: Vec<Duplicate>,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupeAction {
+    pub strategy: DedupeStrategy,
+    // Also drop rows already present in earlier runs of the workflow
+    #[serde(default)]
+    pub against_stored: bool,
+    // Store the result as this run's dataset
+    #[serde(default = "default_store")]
+    pub store: bool,
+}
+
+fn default_store() -> bool {
+    true
+}
+
+fn normalize_text(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn normalize_name(value: &str) -> String {
+    let cleaned: String = value
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    while words.len() > 1 && words.last().map_or(false, |w| NAME_SUFFIXES.contains(w)) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+fn domain(value: &str) -> Option<String> {
+    let value = value.trim().to_lowercase();
+    let host = match value.rsplit_once('@') {
+        Some((_, domain)) if !value.contains("://") => domain.to_string(),
+        _ => Url::parse(&value)
+            .or_else(|_| Url::parse(&format!("https://{}", value)))
+            .ok()?
+            .host_str()?
+            .to_string(),
+    };
+    let host = host.trim_start_matches("www.").to_string();
+    host.contains('.').then_some(host)
+}
+
+// Comparison key of a row; rows without one are never treated as duplicates
+fn key(row: &Row, strategy: &DedupeStrategy) -> Option<String> {
+    let text = |field: &str| workflow::cell_text(row.get(field));
+    let key = match strategy {
+        DedupeStrategy::ExactKey { fields } => {
+            let parts: Vec<String> = fields.iter().map(|f| normalize_text(&text(f))).collect();
+            if parts.iter().all(String::is_empty) {
+                return None;
+            }
+            parts.join("\u{1f}")
+        }
+        DedupeStrategy::FuzzyName { field, .. } => normalize_name(&text(field)),
+        DedupeStrategy::Domain { field } => domain(&text(field))?,
+    };
+    Some(key).filter(|k| !k.is_empty())
+}
+
+fn matches(a: &str, b: &str, strategy: &DedupeStrategy) -> bool {
+    match strategy {
+        DedupeStrategy::FuzzyName { threshold, .. } => a == b || strsim::jaro_winkler(a, b) >= *threshold,
+        _ => a == b,
+    }
+}
+
+fn is_blank(value: Option<&Value>) -> bool {
+    workflow::cell_text(value).trim().is_empty()
+}
+
+// Copy fields the kept row is missing; returns the names of merged fields
+fn merge(kept: &mut Row, duplicate: &Row) -> Vec<String> {
+    let mut merged = Vec::new();
+    for (field, value) in duplicate {
+        if is_blank(kept.get(field)) && !is_blank(Some(value)) {
+            kept.insert(field.clone(), value.clone());
+            merged.push(field.clone());
+        }
+    }
+    merged
+}
+
+pub fn dedupe(rows: Vec<Row>, strategy: &DedupeStrategy, stored: &[Row]) -> (Vec<Row>, DedupeReport) {
+    let stored_keys: Vec<String> = stored.iter().filter_map(|row| key(row, strategy)).collect();
+    let total = rows.len();
+    // (input index, key, row)
+    let mut kept: Vec<(usize, Option<String>, Row)> = Vec::new();
+    let mut duplicates = Vec::new();
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let Some(row_key) = key(&row, strategy) else {
+            kept.push((index, None, row));
+            continue;
+        };
+        if stored_keys.iter().any(|k| matches(k, &row_key, strategy)) {
+            duplicates.push(Duplicate {
+                row: index,
+                duplicate_of: None,
+                merged_fields: Vec::new(),
+            });
+            continue;
+        }
+        let existing = kept
+            .iter_mut()
+            .find(|(_, k, _)| k.as_deref().map_or(false, |k| matches(k, &row_key, strategy)));
+        match existing {
+            Some((kept_index, _, kept_row)) => duplicates.push(Duplicate {
+                row: index,
+                duplicate_of: Some(*kept_index),
+                merged_fields: merge(kept_row, &row),
+            }),
+            None => kept.push((index, Some(row_key), row)),
+        }
+    }
+
+    let merged = duplicates.iter().filter(|d| !d.merged_fields.is_empty()).count();
+    let report = DedupeReport {
+        total,
+        kept: kept.len(),
+        merged,
+        dropped: duplicates.len() - merged,
+        duplicates,
+    };
+    (kept.into_iter().map(|(_, _, row)| row).collect(), report)
+}
+
+pub async fn run_action(app: &AppHandle, action: &DedupeAction, report: &RunReport) -> Result<Value, String> {
+    let db = app.state::<Database>();
+    let stored = if action.against_stored {
+        datasets::previous_rows(&db, &report.workflow_name, &report.run_id)?
+    } else {
+        Vec::new()
+    };
+    let (rows, dedupe_report) = dedupe(report.rows.clone(), &action.strategy, &stored);
+    if action.store {
+        datasets::save(&db, &report.run_id, &report.workflow_name, &rows)?;
+    }
+    Ok(json!({ "rows": rows, "report": dedupe_report }))
+}
+
+// Deduplicate a stored dataset in place
+#[tauri::command]
+#[specta::specta]
+pub async fn dedupe_dataset(
+    db: tauri::State<'_, Database>,
+    dataset_id: String,
+    strategy: DedupeStrategy,
+    against_stored: Option<bool>,
+) -> Result<DedupeReport, String> {
+    let dataset = datasets::find(&db, &dataset_id)?.ok_or_else(|| format!("Dataset not found: {}", dataset_id))?;
+    let stored = if against_stored.unwrap_or(false) {
+        datasets::previous_rows(&db, &dataset.workflow_name, &dataset.id)?
+    } else {
+        Vec::new()
+    };
+    let (rows, report) = dedupe(datasets::rows(&db, &dataset.id)?, &strategy, &stored);
+    datasets::save(&db, &dataset.id, &dataset.workflow_name, &rows)?;
+    Ok(report)
+}
@@ -0,0 +1,100 @@
+// Web inspector access
+// Always available in debug builds; release builds need the "enabled in release"
+// flag so advanced users can opt in. The View menu item mirrors the current state.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::audit::{self, AuditCategory};
+use crate::{kiosk, storage};
+
+const DEVTOOLS_FILE: &str = "devtools.json";
+pub const MENU_ITEM_ID: &str = "toggle_devtools";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct DevtoolsConfig {
+    enabled_in_release: bool,
+}
+
+#[derive(Default)]
+pub struct DevtoolsState {
+    config: Mutex<DevtoolsConfig>,
+}
+
+impl DevtoolsState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, DEVTOOLS_FILE)),
+        }
+    }
+}
+
+// Never while in kiosk mode
+pub fn allowed(app: &AppHandle) -> bool {
+    if kiosk::active(app) {
+        return false;
+    }
+    cfg!(debug_assertions) || app.state::<DevtoolsState>().config.lock().unwrap().enabled_in_release
+}
+
+// Enable the View menu item when devtools are allowed and tick it while open
+pub fn refresh_menu(window: &Window) {
+    let item = window.menu_handle().get_item(MENU_ITEM_ID);
+    let _ = item.set_enabled(allowed(&window.app_handle()));
+    let _ = item.set_selected(window.is_devtools_open());
+}
+
+// Returns whether devtools are open afterwards
+pub fn toggle(window: &Window) -> Result<bool, String> {
+    if !allowed(&window.app_handle()) {
+        return Err("Developer tools are disabled in this build".to_string());
+    }
+    if window.is_devtools_open() {
+        window.close_devtools();
+    } else {
+        window.open_devtools();
+    }
+    let open = window.is_devtools_open();
+    refresh_menu(window);
+    Ok(open)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn toggle_devtools(app_handle: AppHandle, window_id: String) -> Result<bool, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    toggle(&window)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_devtools_config(state: tauri::State<'_, DevtoolsState>) -> Result<DevtoolsConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_devtools_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, DevtoolsState>,
+    config: DevtoolsConfig,
+) -> Result<(), String> {
+    storage::save(&app_handle, DEVTOOLS_FILE, &config)?;
+    audit::record(&app_handle, AuditCategory::Settings, "devtools.config", json!(config));
+    *state.config.lock().unwrap() = config;
+
+    for window in app_handle.windows().values() {
+        // Close any open inspectors when access is revoked
+        if !allowed(&app_handle) && window.is_devtools_open() {
+            window.close_devtools();
+        }
+        refresh_menu(window);
+    }
+    Ok(())
+}
@@ -0,0 +1,390 @@
+// Scheduled AI digests
+// A digest topic picks feeds and page monitors and an interval. When a topic
+// is due, the feed entries fetched and the monitor checks that found changes
+// since its last run are summarized by the AI provider into one report, which
+// is stored, shown as a single notification and optionally emailed. Topics
+// with nothing new are skipped without calling the AI.
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::ai::{self, AiTask};
+use crate::db::Database;
+use crate::notifications::{self, Notice, NotificationCategory};
+use crate::power::{self, GuardKind};
+use crate::{battery, email, idle, network, storage};
+
+const DIGESTS_FILE: &str = "digests.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const AI_TIMEOUT: Duration = Duration::from_secs(120);
+// Newest items first; older ones are only counted
+const MAX_ITEMS: usize = 80;
+const MAX_ITEM_CHARS: usize = 400;
+const MAX_INTERVAL_HOURS: u32 = 24 * 30;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS digests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    topic_id TEXT NOT NULL,
+    topic_name TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    item_count INTEGER NOT NULL,
+    summary TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS digests_topic ON digests (topic_id, created_at DESC);
+";
+
+const SYSTEM_PROMPT: &str = "You write a short digest of new items from news feeds and monitored web pages. \
+Group related items, lead with what matters most, mention sources by name and keep links. \
+Use plain text with short bullet points; no preamble.";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DigestTopic {
+    id: String,
+    name: String,
+    // Empty means every subscribed feed
+    #[serde(default)]
+    feed_ids: Vec<i64>,
+    #[serde(default)]
+    monitor_ids: Vec<String>,
+    // Extra guidance for the summary, e.g. "only security advisories"
+    #[serde(default)]
+    instructions: Option<String>,
+    interval_hours: u32,
+    #[serde(default = "default_notify")]
+    notify: bool,
+    #[serde(default)]
+    email_recipients: Vec<String>,
+    #[serde(default)]
+    enabled: bool,
+    // Unix millis; items newer than this go into the next digest
+    #[serde(default)]
+    last_run: Option<i64>,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Digest {
+    id: i64,
+    topic_id: String,
+    topic_name: String,
+    created_at: i64,
+    item_count: u32,
+    summary: String,
+}
+
+#[derive(Default)]
+pub struct DigestState {
+    topics: Mutex<Vec<DigestTopic>>,
+    // Stops the scheduler and `run_digest_now` from building the same digest twice
+    running: Mutex<Vec<String>>,
+}
+
+impl DigestState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            topics: Mutex::new(storage::load(app, DIGESTS_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+struct Item {
+    at: i64,
+    text: String,
+}
+
+fn clip(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MAX_ITEM_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+fn collect_items(app: &AppHandle, topic: &DigestTopic, since: i64) -> Result<Vec<Item>, String> {
+    let db = app.state::<Database>();
+    let mut items = db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT e.feed_id, f.title, e.title, e.url, e.summary, e.fetched_at
+             FROM feed_entries e JOIN feeds f ON f.id = e.feed_id
+             WHERE e.fetched_at > ?1 ORDER BY e.fetched_at DESC",
+        )?;
+        let rows = stmt.query_map(params![since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })?;
+        let mut items = Vec::new();
+        for row in rows {
+            let (feed_id, feed, title, url, summary, at) = row?;
+            if !topic.feed_ids.is_empty() && !topic.feed_ids.contains(&feed_id) {
+                continue;
+            }
+            let mut text = format!("[Feed: {}] {}", feed, title);
+            if let Some(url) = url {
+                text.push_str(&format!(" <{}>", url));
+            }
+            if !summary.is_empty() {
+                text.push_str(&format!(" — {}", clip(&summary)));
+            }
+            items.push(Item { at, text });
+        }
+        Ok(items)
+    })?;
+
+    if !topic.monitor_ids.is_empty() {
+        let checks = db.with(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT monitor_id, url, checked_at, changed_ratio FROM monitor_checks
+                 WHERE checked_at > ?1 AND changed_pixels > 0 ORDER BY checked_at DESC",
+            )?;
+            let rows = stmt.query_map(params![since], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, f64>(3)?,
+                ))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+        for (monitor_id, url, at, ratio) in checks {
+            if topic.monitor_ids.contains(&monitor_id) {
+                items.push(Item {
+                    at,
+                    text: format!(
+                        "[Page monitor: {}] {} changed ({:.1}% of the page)",
+                        monitor_id,
+                        url,
+                        ratio * 100.0
+                    ),
+                });
+            }
+        }
+    }
+    items.sort_by(|a, b| b.at.cmp(&a.at));
+    Ok(items)
+}
+
+async fn summarize(app: &AppHandle, topic: &DigestTopic, items: &[Item]) -> Result<String, String> {
+    let mut prompt = format!("Digest topic: {}\n", topic.name);
+    if let Some(instructions) = topic.instructions.as_deref().filter(|i| !i.trim().is_empty()) {
+        prompt.push_str(&format!("Focus: {}\n", instructions.trim()));
+    }
+    prompt.push_str(&format!("{} new items:\n", items.len()));
+    for item in items.iter().take(MAX_ITEMS) {
+        prompt.push_str("- ");
+        prompt.push_str(&item.text);
+        prompt.push('\n');
+    }
+    if items.len() > MAX_ITEMS {
+        prompt.push_str(&format!("({} older items not listed)\n", items.len() - MAX_ITEMS));
+    }
+    ai::complete(app, AiTask::Summary, SYSTEM_PROMPT, &prompt, AI_TIMEOUT).await
+}
+
+async fn deliver(app: &AppHandle, topic: &DigestTopic, digest: &Digest) {
+    if topic.notify {
+        let notice = Notice::new(NotificationCategory::Ai, topic.name.clone(), clip(&digest.summary));
+        let _ = notifications::notify(app, notice.owned_by("digest"));
+    }
+    if !topic.email_recipients.is_empty() {
+        let subject = format!("{} digest: {} new items", topic.name, digest.item_count);
+        let body = format!("{}\n\n{} items since the last digest.\n", digest.summary, digest.item_count);
+        if let Err(e) = email::send_text(app, &topic.email_recipients, &subject, body).await {
+            eprintln!("Failed to email digest {}: {}", topic.id, e);
+        }
+    }
+}
+
+fn set_last_run(app: &AppHandle, topic_id: &str, at: i64) -> Result<(), String> {
+    let state = app.state::<DigestState>();
+    let mut topics = state.topics.lock().unwrap();
+    if let Some(topic) = topics.iter_mut().find(|t| t.id == topic_id) {
+        topic.last_run = Some(at);
+    }
+    storage::save(app, DIGESTS_FILE, &*topics)
+}
+
+// Build and deliver one topic's digest; None when nothing is new
+async fn run_topic(app: &AppHandle, topic: &DigestTopic) -> Result<Option<Digest>, String> {
+    let started = chrono::Utc::now().timestamp_millis();
+    let items = collect_items(app, topic, topic.last_run.unwrap_or(0))?;
+    if items.is_empty() {
+        set_last_run(app, &topic.id, started)?;
+        return Ok(None);
+    }
+    let summary = summarize(app, topic, &items).await?;
+    let item_count = items.len() as u32;
+    let id = app.state::<Database>().with(|conn| {
+        conn.execute(
+            "INSERT INTO digests (topic_id, topic_name, created_at, item_count, summary) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![topic.id, topic.name, started, item_count, summary],
+        )?;
+        Ok(conn.last_insert_rowid())
+    })?;
+    // Items that arrive while the AI is working go into the next digest
+    set_last_run(app, &topic.id, started)?;
+    let digest = Digest {
+        id,
+        topic_id: topic.id.clone(),
+        topic_name: topic.name.clone(),
+        created_at: started,
+        item_count,
+        summary,
+    };
+    deliver(app, topic, &digest).await;
+    let _ = app.emit_all("digest-created", &digest);
+    Ok(Some(digest))
+}
+
+async fn run_guarded(app: &AppHandle, topic: &DigestTopic) -> Result<Option<Digest>, String> {
+    {
+        let state = app.state::<DigestState>();
+        let mut running = state.running.lock().unwrap();
+        if running.contains(&topic.id) {
+            return Err(format!("Digest {} is already being built", topic.name));
+        }
+        running.push(topic.id.clone());
+    }
+    let result = run_topic(app, topic).await;
+    app.state::<DigestState>().running.lock().unwrap().retain(|id| id != &topic.id);
+    result
+}
+
+fn due_topics(app: &AppHandle) -> Vec<DigestTopic> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let state = app.state::<DigestState>();
+    let topics = state.topics.lock().unwrap();
+    topics
+        .iter()
+        .filter(|t| t.enabled)
+        .filter(|t| t.last_run.map_or(true, |last| now - last >= i64::from(t.interval_hours) * 3_600_000))
+        .cloned()
+        .collect()
+}
+
+pub fn start_scheduler(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        // Same conditions as feed polling: wait for a usable connection, a present
+        // user and enough battery
+        if network::transfers_paused(&app) || idle::polling_paused(&app) || battery::should_defer_heavy_jobs(&app) {
+            continue;
+        }
+        for topic in due_topics(&app) {
+            let _awake = power::hold(&app, GuardKind::Digest, &topic.id);
+            if let Err(e) = tauri::async_runtime::block_on(run_guarded(&app, &topic)) {
+                eprintln!("Digest {} failed: {}", topic.id, e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_digest_topics(state: tauri::State<'_, DigestState>) -> Result<Vec<DigestTopic>, String> {
+    Ok(state.topics.lock().unwrap().clone())
+}
+
+// Adds or replaces a topic by id. A new topic starts collecting from now.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_digest_topic(
+    app_handle: AppHandle,
+    state: tauri::State<'_, DigestState>,
+    mut topic: DigestTopic,
+) -> Result<(), String> {
+    if topic.id.trim().is_empty() || topic.name.trim().is_empty() {
+        return Err("Digest topic needs an id and a name".to_string());
+    }
+    if topic.interval_hours == 0 || topic.interval_hours > MAX_INTERVAL_HOURS {
+        return Err(format!("Interval must be between 1 and {} hours", MAX_INTERVAL_HOURS));
+    }
+    if !topic.notify && topic.email_recipients.is_empty() {
+        return Err("Choose a notification or at least one email recipient".to_string());
+    }
+    let mut topics = state.topics.lock().unwrap();
+    let existing = topics.iter().position(|t| t.id == topic.id);
+    topic.last_run = match existing {
+        Some(index) => topics[index].last_run,
+        None => Some(chrono::Utc::now().timestamp_millis()),
+    };
+    match existing {
+        Some(index) => topics[index] = topic,
+        None => topics.push(topic),
+    }
+    storage::save(&app_handle, DIGESTS_FILE, &*topics)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_digest_topic(
+    app_handle: AppHandle,
+    state: tauri::State<'_, DigestState>,
+    topic_id: String,
+) -> Result<(), String> {
+    let mut topics = state.topics.lock().unwrap();
+    topics.retain(|t| t.id != topic_id);
+    storage::save(&app_handle, DIGESTS_FILE, &*topics)
+}
+
+// Build a topic's digest now, whether or not it is due
+#[tauri::command]
+#[specta::specta]
+pub async fn run_digest_now(
+    app_handle: AppHandle,
+    state: tauri::State<'_, DigestState>,
+    topic_id: String,
+) -> Result<Option<Digest>, String> {
+    let topic = state
+        .topics
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|t| t.id == topic_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown digest topic: {}", topic_id))?;
+    run_guarded(&app_handle, &topic).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_digests(
+    db: tauri::State<'_, Database>,
+    topic_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<Digest>, String> {
+    let limit = limit.unwrap_or(50).min(500);
+    db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, topic_id, topic_name, created_at, item_count, summary FROM digests
+             WHERE ?1 IS NULL OR topic_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![topic_id, limit], |row| {
+            Ok(Digest {
+                id: row.get(0)?,
+                topic_id: row.get(1)?,
+                topic_name: row.get(2)?,
+                created_at: row.get(3)?,
+                item_count: row.get(4)?,
+                summary: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
@@ -0,0 +1,211 @@
+// Focus-assist / do-not-disturb awareness
+// While the OS is in a focus mode, non-critical notifications are suppressed or
+// queued (they are always kept in the notification center); categories listed as
+// breakthrough are still shown.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::notifications::{self, Notice, NotificationCategory};
+use crate::{i18n, storage};
+
+const DND_FILE: &str = "dnd.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldMode {
+    // Drop the native popup; the entry stays in the notification center
+    Suppress,
+    // Show held notifications once the focus mode ends
+    Queue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct DndConfig {
+    respect_os: bool,
+    mode: HoldMode,
+    breakthrough: Vec<NotificationCategory>,
+}
+
+impl Default for DndConfig {
+    fn default() -> Self {
+        Self {
+            respect_os: true,
+            mode: HoldMode::Queue,
+            breakthrough: vec![NotificationCategory::WorkflowFailure],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DndState {
+    active: bool,
+    mode: HoldMode,
+    queued: usize,
+    breakthrough: Vec<NotificationCategory>,
+}
+
+#[derive(Default)]
+pub struct Dnd {
+    config: Mutex<DndConfig>,
+    active: Mutex<bool>,
+    queue: Mutex<Vec<(i64, Notice)>>,
+}
+
+impl Dnd {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, DND_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+// Decide whether a notification should skip the native popup; queued notices
+// are kept for delivery once the focus mode ends
+pub fn hold(app: &AppHandle, id: i64, notice: &Notice) -> bool {
+    let dnd = app.state::<Dnd>();
+    let config = dnd.config.lock().unwrap();
+    if !config.respect_os || !*dnd.active.lock().unwrap() {
+        return false;
+    }
+    if config.breakthrough.contains(&notice.category) {
+        return false;
+    }
+    if config.mode == HoldMode::Queue {
+        dnd.queue.lock().unwrap().push((id, notice.clone()));
+    }
+    true
+}
+
+// Show everything held while the focus mode was on, collapsing large backlogs
+fn flush_queue(app: &AppHandle) {
+    let queued = std::mem::take(&mut *app.state::<Dnd>().queue.lock().unwrap());
+    match queued.len() {
+        0 => {}
+        1..=3 => {
+            for (id, notice) in queued {
+                notifications::show_native(app, id, &notice);
+            }
+        }
+        count => {
+            let summary = Notice::new(
+                NotificationCategory::System,
+                i18n::text(app, "notice-dnd-summary-title"),
+                i18n::text_with(app, "notice-dnd-summary-body", &[("count", count.into())]),
+            );
+            if let Err(e) = notifications::notify(app, summary) {
+                eprintln!("Failed to show notification summary: {}", e);
+            }
+        }
+    }
+}
+
+// Poll the OS focus state and react to transitions
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let active = platform::is_dnd_active();
+        let changed = {
+            let dnd = app.state::<Dnd>();
+            let mut current = dnd.active.lock().unwrap();
+            let changed = *current != active;
+            *current = active;
+            changed
+        };
+
+        if changed {
+            let _ = app.emit_all("dnd-changed", active);
+            if !active {
+                flush_queue(&app);
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dnd_state(dnd: tauri::State<'_, Dnd>) -> Result<DndState, String> {
+    let config = dnd.config.lock().unwrap();
+    Ok(DndState {
+        active: *dnd.active.lock().unwrap(),
+        mode: config.mode,
+        queued: dnd.queue.lock().unwrap().len(),
+        breakthrough: config.breakthrough.clone(),
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_dnd_config(
+    app_handle: AppHandle,
+    dnd: tauri::State<'_, Dnd>,
+    config: DndConfig,
+) -> Result<(), String> {
+    storage::save(&app_handle, DND_FILE, &config)?;
+    *dnd.config.lock().unwrap() = config;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_ACCEPTS_NOTIFICATIONS, QUNS_APP,
+    };
+
+    // Quiet time, presentation mode, full-screen apps and Focus Assist all report
+    // something other than "accepts notifications"
+    pub fn is_dnd_active() -> bool {
+        match unsafe { SHQueryUserNotificationState() } {
+            Ok(state) => state != QUNS_ACCEPTS_NOTIFICATIONS && state != QUNS_APP,
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    // Focus modes (macOS 12+) record active assertions in this file
+    pub fn is_dnd_active() -> bool {
+        let Some(home) = std::env::var_os("HOME") else {
+            return false;
+        };
+        let path = std::path::Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json");
+        let Ok(bytes) = std::fs::read(path) else {
+            return false;
+        };
+        let Ok(json) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+            return false;
+        };
+
+        json["data"]
+            .as_array()
+            .map_or(false, |data| {
+                data.iter().any(|entry| {
+                    entry["storeAssertionRecords"]
+                        .as_array()
+                        .map_or(false, |records| !records.is_empty())
+                })
+            })
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    // GNOME exposes do-not-disturb as "show-banners = false"
+    pub fn is_dnd_active() -> bool {
+        Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+            .output()
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim() == "false")
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,817 @@
+// Download routing
+// Rules pick the folder a download is saved to by file type, source domain
+// or the workflow that triggered it (e.g. PDFs from the billing portal go to
+// ~/Documents/Invoices). The first rule whose conditions all match wins;
+// otherwise the default folder or the webview's own choice applies. When the
+// target name is taken, the rule's conflict strategy renames, overwrites or
+// skips. Webview downloads are routed natively where the engine allows it;
+// the frontend and workflow engine ask `resolve_download_path` for theirs.
+//
+// Every completed download is recorded with its SHA-256. A checksum or
+// signature published by the source is checked on arrival, executables get
+// the OS quarantine mark (see quarantine.rs), and `verify_download` lets
+// automation re-check a file against a known checksum before trusting it.
+// With a virus scanner configured (see scanner.rs) each download is scanned
+// too, and `open_download` only opens files that passed.
+
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::audit::{self, AuditCategory};
+use crate::db::Database;
+use crate::events::AppEvent;
+use crate::scanner::{self, ScanResult, ScanStatus};
+use crate::{quarantine, storage, tasks};
+
+const RULES_FILE: &str = "download-rules.json";
+// Highest " (n)" suffix tried before giving up on renaming
+const MAX_RENAME_ATTEMPTS: u32 = 999;
+const DEFAULT_LIST_LIMIT: u32 = 100;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS downloads (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL,
+    path TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    integrity TEXT NOT NULL,
+    quarantined INTEGER NOT NULL,
+    completed_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS downloads_completed ON downloads (completed_at);
+";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    // "report.csv" becomes "report (1).csv"
+    #[default]
+    Rename,
+    Overwrite,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DownloadRule {
+    name: String,
+    // Lowercase extensions without the dot; empty matches any
+    #[serde(default)]
+    extensions: Vec<String>,
+    // Source domains, subdomains included; empty matches any
+    #[serde(default)]
+    domains: Vec<String>,
+    // Only downloads made by this workflow
+    #[serde(default)]
+    workflow_id: Option<String>,
+    // Absolute, or starting with `~/`
+    folder: String,
+    #[serde(default)]
+    conflict: Option<ConflictStrategy>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct DownloadRules {
+    rules: Vec<DownloadRule>,
+    // For downloads no rule matches; None keeps the webview's default
+    default_folder: Option<String>,
+    conflict: ConflictStrategy,
+    // Base64 ed25519 public keys whose signatures over a download's SHA-256
+    // (lowercase hex) are trusted
+    trusted_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RoutedDownload {
+    // None when the download should be skipped
+    path: Option<String>,
+    rule: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Integrity {
+    // No checksum or signature to check against
+    Unverified,
+    Verified,
+    ChecksumMismatch,
+    InvalidSignature,
+}
+
+impl Integrity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Integrity::Unverified => "unverified",
+            Integrity::Verified => "verified",
+            Integrity::ChecksumMismatch => "checksum_mismatch",
+            Integrity::InvalidSignature => "invalid_signature",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "verified" => Integrity::Verified,
+            "checksum_mismatch" => Integrity::ChecksumMismatch,
+            "invalid_signature" => Integrity::InvalidSignature,
+            _ => Integrity::Unverified,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DownloadRecord {
+    id: i64,
+    url: String,
+    path: String,
+    size: i64,
+    sha256: String,
+    integrity: Integrity,
+    // Carries the OS quarantine mark
+    quarantined: bool,
+    completed_at: i64,
+    scan: Option<ScanResult>,
+}
+
+impl DownloadRecord {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            path: row.get(2)?,
+            size: row.get(3)?,
+            sha256: row.get(4)?,
+            integrity: Integrity::parse(&row.get::<_, String>(5)?),
+            quarantined: row.get(6)?,
+            completed_at: row.get(7)?,
+            scan: match row.get::<_, Option<String>>(8)? {
+                Some(status) => Some(ScanResult {
+                    status: ScanStatus::parse(&status),
+                    scanner: row.get(9)?,
+                    detail: row.get(10)?,
+                    scanned_at: row.get(11)?,
+                }),
+                None => None,
+            },
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "SELECT d.id, d.url, d.path, d.size, d.sha256, d.integrity, d.quarantined, d.completed_at,
+    s.status, s.scanner, s.detail, s.scanned_at
+    FROM downloads d LEFT JOIN download_scans s ON s.download_id = d.id";
+
+#[derive(Default)]
+pub struct DownloadState {
+    rules: Mutex<DownloadRules>,
+}
+
+impl DownloadState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            rules: Mutex::new(storage::load(app, RULES_FILE)),
+        }
+    }
+}
+
+fn expand_folder(folder: &str) -> Result<PathBuf, String> {
+    let path = match folder.strip_prefix("~/").or_else(|| (folder == "~").then_some("")) {
+        Some(rest) => tauri::api::path::home_dir()
+            .ok_or_else(|| "Home directory unavailable".to_string())?
+            .join(rest),
+        None => PathBuf::from(folder),
+    };
+    if path.is_absolute() {
+        Ok(path)
+    } else {
+        Err(format!("Download folder must be absolute: {}", folder))
+    }
+}
+
+impl DownloadRule {
+    fn matches(&self, host: Option<&str>, extension: Option<&str>, workflow_id: Option<&str>) -> bool {
+        let extension_ok = self.extensions.is_empty()
+            || extension.map_or(false, |ext| self.extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext)));
+        let domain_ok = self.domains.is_empty()
+            || host.map_or(false, |host| {
+                self.domains.iter().any(|d| {
+                    let d = d.to_lowercase();
+                    host == d || host.ends_with(&format!(".{}", d))
+                })
+            });
+        let workflow_ok = self.workflow_id.is_none() || self.workflow_id.as_deref() == workflow_id;
+        extension_ok && domain_ok && workflow_ok
+    }
+}
+
+// Keep only the final path component so a suggested name can't escape the folder
+fn clean_filename(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or("").trim();
+    if name.is_empty() || name == "." || name == ".." {
+        "download".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn resolve_conflict(path: PathBuf, strategy: ConflictStrategy) -> Option<PathBuf> {
+    if !path.exists() {
+        return Some(path);
+    }
+    match strategy {
+        ConflictStrategy::Overwrite => Some(path),
+        ConflictStrategy::Skip => None,
+        ConflictStrategy::Rename => {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+            let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            (1..=MAX_RENAME_ATTEMPTS)
+                .map(|n| parent.join(format!("{} ({}){}", stem, n, extension)))
+                .find(|candidate| !candidate.exists())
+        }
+    }
+}
+
+// Where a download should go. Ok(None) means no rule or default folder
+// applies and the caller keeps its own choice.
+pub fn route(
+    app: &AppHandle,
+    url: &str,
+    filename: &str,
+    workflow_id: Option<&str>,
+) -> Result<Option<RoutedDownload>, String> {
+    let rules = app.state::<DownloadState>().rules.lock().unwrap().clone();
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()));
+    let filename = clean_filename(filename);
+    let extension = Path::new(&filename)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase());
+
+    let matched = rules
+        .rules
+        .iter()
+        .find(|rule| rule.matches(host.as_deref(), extension.as_deref(), workflow_id));
+    let (folder, conflict, rule) = match (matched, &rules.default_folder) {
+        (Some(rule), _) => (rule.folder.clone(), rule.conflict.unwrap_or(rules.conflict), Some(rule.name.clone())),
+        (None, Some(folder)) => (folder.clone(), rules.conflict, None),
+        (None, None) => return Ok(None),
+    };
+    let folder = expand_folder(&folder)?;
+    std::fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+    let path = resolve_conflict(folder.join(filename), conflict);
+    Ok(Some(RoutedDownload {
+        path: path.map(|p| p.to_string_lossy().into_owned()),
+        rule,
+    }))
+}
+
+fn hash_file(path: &Path) -> Result<(String, u64), String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+fn signed_by_trusted_key(keys: &[String], sha256: &str, signature: &str) -> bool {
+    let Ok(signature) = base64::engine::general_purpose::STANDARD.decode(signature.trim()) else { return false };
+    let Ok(signature) = Signature::from_slice(&signature) else { return false };
+    keys.iter().any(|key| {
+        base64::engine::general_purpose::STANDARD
+            .decode(key.trim())
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+            .map_or(false, |key| key.verify(sha256.as_bytes(), &signature).is_ok())
+    })
+}
+
+fn check_integrity(app: &AppHandle, sha256: &str, expected: Option<&str>, signature: Option<&str>) -> Integrity {
+    if let Some(expected) = expected {
+        if !expected.trim().eq_ignore_ascii_case(sha256) {
+            return Integrity::ChecksumMismatch;
+        }
+    }
+    if let Some(signature) = signature {
+        let keys = app.state::<DownloadState>().rules.lock().unwrap().trusted_keys.clone();
+        if !signed_by_trusted_key(&keys, sha256, signature) {
+            return Integrity::InvalidSignature;
+        }
+    }
+    if expected.is_some() || signature.is_some() {
+        Integrity::Verified
+    } else {
+        Integrity::Unverified
+    }
+}
+
+// Hash, check and quarantine a finished download, then record it
+fn record(
+    app: &AppHandle,
+    url: &str,
+    path: &str,
+    expected: Option<&str>,
+    signature: Option<&str>,
+) -> Result<DownloadRecord, String> {
+    let file = Path::new(path);
+    let (sha256, size) = hash_file(file)?;
+    let integrity = check_integrity(app, &sha256, expected, signature);
+    let quarantined = quarantine::mark(file, url).unwrap_or_else(|e| {
+        eprintln!("Failed to quarantine {}: {}", path, e);
+        false
+    });
+    let completed_at = chrono::Utc::now().timestamp_millis();
+    let id = app.state::<Database>().with(|conn| {
+        conn.execute(
+            "INSERT INTO downloads (url, path, size, sha256, integrity, quarantined, completed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![url, path, size as i64, sha256, integrity.as_str(), quarantined, completed_at],
+        )?;
+        Ok(conn.last_insert_rowid())
+    })?;
+    if !matches!(integrity, Integrity::Verified | Integrity::Unverified) {
+        audit::record(
+            app,
+            AuditCategory::Automation,
+            "download.integrity_failed",
+            json!({ "url": url, "path": path, "sha256": sha256, "integrity": integrity }),
+        );
+    }
+    Ok(DownloadRecord {
+        id,
+        url: url.to_string(),
+        path: path.to_string(),
+        size: size as i64,
+        sha256,
+        integrity,
+        quarantined,
+        completed_at,
+        scan: None,
+    })
+}
+
+fn find_record(db: &Database, id: i64) -> Result<DownloadRecord, String> {
+    db.with(|conn| {
+        conn.query_row(&format!("{} WHERE d.id = ?1", SELECT_COLUMNS), params![id], DownloadRecord::from_row)
+            .optional()
+    })?
+    .ok_or_else(|| format!("Unknown download: {}", id))
+}
+
+// Event subscriber; hashing and scanning run off the publishing thread
+pub fn on_event(app: &AppHandle, event: &AppEvent) {
+    let AppEvent::DownloadCompleted { url, path, sha256, signature, .. } = event else { return };
+    let (app, url, path, sha256, signature) = (app.clone(), url.clone(), path.clone(), sha256.clone(), signature.clone());
+    tauri::async_runtime::spawn(async move {
+        let recorder = app.clone();
+        let file = path.clone();
+        let download =
+            tasks::blocking(move || record(&recorder, &url, &file, sha256.as_deref(), signature.as_deref())).await;
+        let download = match download {
+            Ok(download) => download,
+            Err(e) => {
+                eprintln!("Failed to record download {}: {}", path, e);
+                return;
+            }
+        };
+        let id = download.id;
+        let _ = app.emit_all("download-recorded", download);
+        if let Err(e) = scanner::scan(&app, id, Path::new(&path)).await {
+            eprintln!("Failed to scan download {}: {}", path, e);
+        }
+    });
+}
+
+// Called by the platform watchers when a webview download fails (not when
+// it is cancelled). Retrying opens the URL again in a new window.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn notify_failed(app: &AppHandle, url: &str) {
+    use crate::i18n;
+    use crate::notifications::{self, Notice, NotificationAction, NotificationCategory, BUTTON_OPEN};
+
+    let notice = Notice::new(
+        NotificationCategory::Download,
+        i18n::text(app, "notice-download-failed-title"),
+        i18n::text_with(app, "notice-download-failed-body", &[("url", url.into())]),
+    )
+    .with_action(NotificationAction::OpenUrl { url: url.to_string() })
+    .with_button(BUTTON_OPEN, i18n::text(app, "notice-download-retry"));
+    if let Err(e) = notifications::notify(app, notice) {
+        eprintln!("Failed to report failed download {}: {}", url, e);
+    }
+}
+
+// Called by network.rs when transfers pause or resume. Only WebView2 can pause
+// a download; WebKitGTK downloads keep running.
+pub fn set_paused(app: &AppHandle, paused: bool) {
+    if let Err(e) = platform::set_paused(app, paused) {
+        eprintln!("Failed to pause downloads: {}", e);
+    }
+}
+
+// Called on every window built by windowpool::build_window
+pub fn attach(app: &AppHandle, window: &Window) {
+    if let Err(e) = platform::install(app, window) {
+        eprintln!("Failed to install download routing: {}", e);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_download_rules(state: tauri::State<'_, DownloadState>) -> Result<DownloadRules, String> {
+    Ok(state.rules.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_download_rules(
+    app_handle: AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    rules: DownloadRules,
+) -> Result<(), String> {
+    for rule in &rules.rules {
+        if rule.name.trim().is_empty() {
+            return Err("Download rules need a name".to_string());
+        }
+        expand_folder(&rule.folder)?;
+    }
+    if let Some(folder) = &rules.default_folder {
+        expand_folder(folder)?;
+    }
+    storage::save(&app_handle, RULES_FILE, &rules)?;
+    *state.rules.lock().unwrap() = rules;
+    Ok(())
+}
+
+// For downloads saved by the frontend or the workflow engine
+#[tauri::command]
+#[specta::specta]
+pub async fn resolve_download_path(
+    app_handle: AppHandle,
+    url: String,
+    filename: String,
+    workflow_id: Option<String>,
+) -> Result<Option<RoutedDownload>, String> {
+    route(&app_handle, &url, &filename, workflow_id.as_deref())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_downloads(db: tauri::State<'_, Database>, limit: Option<u32>) -> Result<Vec<DownloadRecord>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!("{} ORDER BY d.completed_at DESC, d.id DESC LIMIT ?1", SELECT_COLUMNS))?;
+        let rows = stmt.query_map(params![limit.unwrap_or(DEFAULT_LIST_LIMIT)], DownloadRecord::from_row)?;
+        rows.collect()
+    })
+}
+
+// Re-hash the file now and compare it with a checksum the caller trusts
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_download(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    id: i64,
+    sha256: String,
+) -> Result<DownloadRecord, String> {
+    let mut download = find_record(&db, id)?;
+    let path = PathBuf::from(&download.path);
+    let (actual, size) = tasks::blocking(move || hash_file(&path)).await?;
+    download.integrity = if actual.eq_ignore_ascii_case(sha256.trim()) {
+        Integrity::Verified
+    } else {
+        Integrity::ChecksumMismatch
+    };
+    download.sha256 = actual;
+    download.size = size as i64;
+    db.with(|conn| {
+        conn.execute(
+            "UPDATE downloads SET sha256 = ?1, size = ?2, integrity = ?3 WHERE id = ?4",
+            params![download.sha256, download.size, download.integrity.as_str(), id],
+        )
+    })?;
+    audit::record(
+        &app_handle,
+        AuditCategory::Automation,
+        "download.verify",
+        json!({ "id": id, "path": download.path, "expected": sha256, "integrity": download.integrity }),
+    );
+    Ok(download)
+}
+
+// Scan again, e.g. after the scanner was configured or its signatures updated
+#[tauri::command]
+#[specta::specta]
+pub async fn scan_download(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    id: i64,
+) -> Result<DownloadRecord, String> {
+    let download = find_record(&db, id)?;
+    if scanner::scan(&app_handle, id, Path::new(&download.path)).await?.is_none() {
+        return Err("No virus scanner is configured".to_string());
+    }
+    find_record(&db, id)
+}
+
+// Open with the system's default application once the file is known to be safe
+#[tauri::command]
+#[specta::specta]
+pub async fn open_download(app_handle: AppHandle, db: tauri::State<'_, Database>, id: i64) -> Result<(), String> {
+    let download = find_record(&db, id)?;
+    if matches!(download.integrity, Integrity::ChecksumMismatch | Integrity::InvalidSignature) {
+        return Err("The download failed its integrity check".to_string());
+    }
+    if scanner::enabled(&app_handle) {
+        match download.scan.as_ref().map(|scan| scan.status) {
+            Some(ScanStatus::Clean) => {}
+            Some(ScanStatus::Infected) => return Err("The virus scan found a threat in this download".to_string()),
+            Some(ScanStatus::Pending) => return Err("The download is still being scanned".to_string()),
+            Some(ScanStatus::Failed) | None => return Err("The download has not passed a virus scan".to_string()),
+        }
+    }
+    tauri::api::shell::open(&tauri::api::shell::Scope::default(), &download.path, None).map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+    use tauri::{AppHandle, Window};
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2DownloadOperation, ICoreWebView2_4, COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON,
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_USER_CANCELED, COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_USER_PAUSED,
+        COREWEBVIEW2_DOWNLOAD_STATE,
+        COREWEBVIEW2_DOWNLOAD_STATE_COMPLETED, COREWEBVIEW2_DOWNLOAD_STATE_INTERRUPTED,
+        COREWEBVIEW2_DOWNLOAD_STATE_IN_PROGRESS,
+    };
+    use webview2_com::{DownloadStartingEventHandler, StateChangedEventHandler};
+    use windows_webview2::core::{Interface, HSTRING, PWSTR};
+    use windows_webview2::Win32::System::WinRT::EventRegistrationToken;
+
+    use crate::events::{self, AppEvent};
+    use crate::network;
+    use crate::power::{self, GuardKind};
+
+    thread_local! {
+        // Unfinished downloads and whether `set_paused` paused them; the
+        // webview only calls back on the UI thread
+        static ACTIVE: RefCell<Vec<(ICoreWebView2DownloadOperation, bool)>> = RefCell::new(Vec::new());
+    }
+
+    pub fn set_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+        app.run_on_main_thread(move || {
+            ACTIVE.with(|active| {
+                // Pausing raises StateChanged, which may touch the list
+                let operations = active.borrow().clone();
+                for (operation, paused_here) in operations {
+                    let result = unsafe {
+                        let mut state = COREWEBVIEW2_DOWNLOAD_STATE::default();
+                        let _ = operation.State(&mut state);
+                        if paused && state == COREWEBVIEW2_DOWNLOAD_STATE_IN_PROGRESS {
+                            operation.Pause()
+                        } else if !paused && paused_here {
+                            operation.Resume()
+                        } else {
+                            continue;
+                        }
+                    };
+                    if let Err(e) = result {
+                        eprintln!("Failed to pause or resume a download: {}", e);
+                        continue;
+                    }
+                    if let Some(entry) = active.borrow_mut().iter_mut().find(|(o, _)| *o == operation) {
+                        entry.1 = paused;
+                    }
+                }
+            })
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    // Publish DownloadCompleted once the operation finishes, or report it
+    // if it was interrupted for any reason but the user cancelling or a
+    // pause. The machine is kept awake while it is in progress.
+    unsafe fn watch(app: AppHandle, operation: &ICoreWebView2DownloadOperation) -> windows_webview2::core::Result<()> {
+        let awake = Mutex::new(Some(power::hold(&app, GuardKind::Download, "download")));
+        ACTIVE.with(|active| active.borrow_mut().push((operation.clone(), false)));
+        // Downloads started while transfers are paused wait like the others
+        let pause_now = network::transfers_paused(&app).then(|| app.clone());
+        let handler = StateChangedEventHandler::create(Box::new(move |operation, _| {
+            let Some(operation) = operation else { return Ok(()) };
+            let mut state = COREWEBVIEW2_DOWNLOAD_STATE::default();
+            operation.State(&mut state)?;
+            {
+                let mut awake = awake.lock().unwrap();
+                if state != COREWEBVIEW2_DOWNLOAD_STATE_IN_PROGRESS {
+                    awake.take();
+                } else if awake.is_none() {
+                    *awake = Some(power::hold(&app, GuardKind::Download, "download"));
+                }
+            }
+            if state == COREWEBVIEW2_DOWNLOAD_STATE_INTERRUPTED {
+                let mut reason = COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON::default();
+                operation.InterruptReason(&mut reason)?;
+                if reason == COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_USER_PAUSED {
+                    return Ok(());
+                }
+                ACTIVE.with(|active| active.borrow_mut().retain(|(o, _)| o != operation));
+                if reason != COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_USER_CANCELED {
+                    let mut uri = PWSTR::null();
+                    operation.Uri(&mut uri)?;
+                    super::notify_failed(&app, &webview2_com::take_pwstr(uri));
+                }
+                return Ok(());
+            }
+            if state != COREWEBVIEW2_DOWNLOAD_STATE_COMPLETED {
+                return Ok(());
+            }
+            ACTIVE.with(|active| active.borrow_mut().retain(|(o, _)| o != operation));
+            let (mut uri, mut path, mut bytes) = (PWSTR::null(), PWSTR::null(), 0i64);
+            operation.Uri(&mut uri)?;
+            operation.ResultFilePath(&mut path)?;
+            operation.BytesReceived(&mut bytes)?;
+            events::publish(
+                &app,
+                AppEvent::DownloadCompleted {
+                    url: webview2_com::take_pwstr(uri),
+                    path: webview2_com::take_pwstr(path),
+                    bytes: u64::try_from(bytes).ok(),
+                    sha256: None,
+                    signature: None,
+                },
+            );
+            Ok(())
+        }));
+        let mut token = EventRegistrationToken::default();
+        operation.add_StateChanged(&handler, &mut token)?;
+        if let Some(app) = pause_now {
+            super::set_paused(&app, true);
+        }
+        Ok(())
+    }
+
+    pub fn install(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        window
+            .with_webview(move |webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let core: ICoreWebView2_4 = webview.controller().CoreWebView2()?.cast()?;
+                    let handler = DownloadStartingEventHandler::create(Box::new(move |_, args| {
+                        let Some(args) = args else { return Ok(()) };
+                        let operation = args.DownloadOperation()?;
+                        let mut uri = PWSTR::null();
+                        operation.Uri(&mut uri)?;
+                        let mut suggested = PWSTR::null();
+                        args.ResultFilePath(&mut suggested)?;
+                        let suggested = webview2_com::take_pwstr(suggested);
+                        let filename = std::path::Path::new(&suggested)
+                            .file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        match super::route(&app, &webview2_com::take_pwstr(uri), &filename, None) {
+                            Ok(Some(routed)) => match routed.path {
+                                Some(path) => args.SetResultFilePath(&HSTRING::from(path.as_str()))?,
+                                None => return args.SetCancel(true),
+                            },
+                            Ok(None) => {}
+                            Err(e) => eprintln!("Failed to route download: {}", e),
+                        }
+                        watch(app.clone(), &operation)
+                    }));
+                    let mut token = EventRegistrationToken::default();
+                    core.add_DownloadStarting(&handler, &mut token)
+                })();
+                if let Err(e) = result {
+                    eprintln!("Failed to install download handler: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use gtk::glib::ObjectType;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Mutex;
+    use tauri::{AppHandle, Window};
+    use webkit2gtk::{Download, DownloadError, DownloadExt, URIRequestExt, WebContextExt, WebViewExt};
+
+    use crate::events::{self, AppEvent};
+    use crate::power::{self, GuardKind};
+
+    // Windows share a web context (and so its download signal) unless they
+    // have their own data directory, as container windows do
+    static CONTEXTS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+    // WebKitGTK has no way to pause a download
+    pub fn set_paused(_app: &AppHandle, _paused: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn source_url(download: &Download) -> String {
+        download
+            .request()
+            .and_then(|r| r.uri())
+            .map(|u| u.to_string())
+            .unwrap_or_default()
+    }
+
+    // Publish DownloadCompleted once the download finishes without failing;
+    // failures other than cancelling are reported. The machine is kept awake
+    // until `finished`, which follows failures too.
+    fn watch(app: AppHandle, download: &Download) {
+        let awake = Cell::new(Some(power::hold(&app, GuardKind::Download, "download")));
+        let failed = Rc::new(Cell::new(false));
+        let flag = failed.clone();
+        let reporter = app.clone();
+        download.connect_failed(move |download, error| {
+            flag.set(true);
+            if !error.matches(DownloadError::CancelledByUser) {
+                super::notify_failed(&reporter, &source_url(download));
+            }
+        });
+        download.connect_finished(move |download| {
+            awake.take();
+            let path = download
+                .destination()
+                .and_then(|uri| url::Url::parse(&uri).ok())
+                .and_then(|uri| uri.to_file_path().ok());
+            let Some(path) = path.filter(|_| !failed.get()) else { return };
+            events::publish(
+                &app,
+                AppEvent::DownloadCompleted {
+                    url: source_url(download),
+                    path: path.to_string_lossy().into_owned(),
+                    bytes: Some(download.received_data_length()),
+                    sha256: None,
+                    signature: None,
+                },
+            );
+        });
+    }
+
+    pub fn install(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        window
+            .with_webview(move |webview| {
+                let Some(context) = webview.inner().context() else { return };
+                {
+                    let mut contexts = CONTEXTS.lock().unwrap();
+                    let id = context.as_ptr() as usize;
+                    if contexts.contains(&id) {
+                        return;
+                    }
+                    contexts.push(id);
+                }
+                context.connect_download_started(move |_, download| {
+                    watch(app.clone(), download);
+                    let app = app.clone();
+                    download.connect_decide_destination(move |download, suggested| {
+                        match super::route(&app, &source_url(download), suggested, None) {
+                            Ok(Some(routed)) => match routed.path.and_then(|p| url::Url::from_file_path(p).ok()) {
+                                Some(destination) => {
+                                    download.set_allow_overwrite(true);
+                                    download.set_destination(destination.as_str());
+                                }
+                                None => download.cancel(),
+                            },
+                            Ok(None) => return false,
+                            Err(e) => {
+                                eprintln!("Failed to route download: {}", e);
+                                return false;
+                            }
+                        }
+                        true
+                    });
+                });
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    use tauri::{AppHandle, Window};
+
+    // WKWebView in this Tauri version does not download; the frontend
+    // publishes DownloadCompleted for its own downloads
+    pub fn install(_app: &AppHandle, _window: &Window) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn set_paused(_app: &AppHandle, _paused: bool) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+// Report emails over SMTP
+// The SMTP password lives in the keychain; connections always verify the
+// server certificate (implicit TLS or STARTTLS, never plaintext).
+
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::workflow::{self, RunReport};
+use crate::{secrets, storage};
+
+const SMTP_FILE: &str = "smtp.json";
+const PASSWORD_KEY: &str = "smtp-password";
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    // Implicit TLS, usually port 465
+    Tls,
+    // STARTTLS upgrade, usually port 587
+    StartTls,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SmtpConfig {
+    host: String,
+    port: u16,
+    security: SmtpSecurity,
+    username: String,
+    from_address: String,
+    #[serde(default)]
+    from_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SmtpStatus {
+    config: Option<SmtpConfig>,
+    has_password: bool,
+}
+
+#[derive(Default)]
+pub struct EmailState {
+    config: Mutex<Option<SmtpConfig>>,
+}
+
+impl EmailState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, SMTP_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailAction {
+    pub recipients: Vec<String>,
+    #[serde(default)]
+    pub subject: Option<String>,
+    #[serde(default = "default_attach_csv")]
+    pub attach_csv: bool,
+}
+
+fn default_attach_csv() -> bool {
+    true
+}
+
+fn transport(app: &AppHandle, config: &SmtpConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let password = secrets::require(PASSWORD_KEY)?;
+    audit::record(app, AuditCategory::Credential, "smtp.password", json!({ "host": config.host }));
+    let builder = match config.security {
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+        SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(builder
+        .port(config.port)
+        .credentials(Credentials::new(config.username.clone(), password))
+        .timeout(Some(SMTP_TIMEOUT))
+        .build())
+}
+
+fn configured(app: &AppHandle) -> Result<SmtpConfig, String> {
+    app.state::<EmailState>()
+        .config
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "SMTP is not configured".to_string())
+}
+
+fn mailbox(address: &str, name: Option<&str>) -> Result<Mailbox, String> {
+    let address = address.parse().map_err(|e| format!("Invalid email address {}: {}", address, e))?;
+    Ok(Mailbox::new(name.map(str::to_string), address))
+}
+
+fn summary_text(report: &RunReport) -> String {
+    let mut text = format!(
+        "Workflow: {}\nRun: {}\nStatus: {}\nRows: {}\n",
+        report.workflow_name,
+        report.run_id,
+        report.status,
+        report.rows.len()
+    );
+    if !report.summary.is_empty() {
+        text.push('\n');
+        text.push_str(&report.summary);
+        text.push('\n');
+    }
+    text
+}
+
+pub async fn send_report(
+    app: &AppHandle,
+    recipients: &[String],
+    subject: Option<&str>,
+    attach_csv: bool,
+    report: &RunReport,
+) -> Result<(), String> {
+    if recipients.is_empty() {
+        return Err("No recipients given".to_string());
+    }
+    let config = configured(app)?;
+
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from_address, config.from_name.as_deref())?)
+        .subject(subject.map(str::to_string).unwrap_or_else(|| {
+            format!("{}: {} ({})", report.workflow_name, report.status, report.run_id)
+        }));
+    for recipient in recipients {
+        builder = builder.to(mailbox(recipient, None)?);
+    }
+
+    let body = SinglePart::plain(summary_text(report));
+    let message = if attach_csv && !report.rows.is_empty() {
+        let csv = workflow::rows_to_csv(&report.rows)?;
+        let attachment = Attachment::new(format!("{}.csv", report.run_id))
+            .body(csv, ContentType::parse("text/csv").map_err(|e| e.to_string())?);
+        builder.multipart(MultiPart::mixed().singlepart(body).singlepart(attachment))
+    } else {
+        builder.singlepart(body)
+    }
+    .map_err(|e| e.to_string())?;
+
+    transport(app, &config)?
+        .send(message)
+        .await
+        .map_err(|e| e.to_string())?;
+    audit::record(
+        app,
+        AuditCategory::Export,
+        "email.send_report",
+        json!({ "run_id": report.run_id, "recipients": recipients, "rows": report.rows.len() }),
+    );
+    Ok(())
+}
+
+// Plain-text message with no attachment, e.g. a digest
+pub async fn send_text(app: &AppHandle, recipients: &[String], subject: &str, body: String) -> Result<(), String> {
+    if recipients.is_empty() {
+        return Err("No recipients given".to_string());
+    }
+    let config = configured(app)?;
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from_address, config.from_name.as_deref())?)
+        .subject(subject);
+    for recipient in recipients {
+        builder = builder.to(mailbox(recipient, None)?);
+    }
+    let message = builder.singlepart(SinglePart::plain(body)).map_err(|e| e.to_string())?;
+    transport(app, &config)?
+        .send(message)
+        .await
+        .map_err(|e| e.to_string())?;
+    audit::record(
+        app,
+        AuditCategory::Export,
+        "email.send_text",
+        json!({ "subject": subject, "recipients": recipients }),
+    );
+    Ok(())
+}
+
+pub async fn run_action(app: &AppHandle, action: &EmailAction, report: &RunReport) -> Result<Value, String> {
+    send_report(app, &action.recipients, action.subject.as_deref(), action.attach_csv, report).await?;
+    Ok(json!({ "sent_to": action.recipients }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_smtp_config(state: tauri::State<'_, EmailState>) -> Result<SmtpStatus, String> {
+    Ok(SmtpStatus {
+        config: state.config.lock().unwrap().clone(),
+        has_password: secrets::get(PASSWORD_KEY)?.is_some(),
+    })
+}
+
+// A missing password keeps the stored one
+#[tauri::command]
+#[specta::specta]
+pub async fn set_smtp_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, EmailState>,
+    config: SmtpConfig,
+    password: Option<String>,
+) -> Result<(), String> {
+    mailbox(&config.from_address, None)?;
+    if config.host.trim().is_empty() {
+        return Err("SMTP host cannot be empty".to_string());
+    }
+    let password_changed = password.is_some();
+    if let Some(password) = password {
+        secrets::set(PASSWORD_KEY, &password)?;
+    }
+    storage::save(&app_handle, SMTP_FILE, &Some(&config))?;
+    audit::record(
+        &app_handle,
+        AuditCategory::Settings,
+        "smtp.config",
+        json!({ "host": config.host, "password_changed": password_changed }),
+    );
+    *state.config.lock().unwrap() = Some(config);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn test_smtp_connection(app_handle: AppHandle) -> Result<(), String> {
+    let config = configured(&app_handle)?;
+    match transport(&app_handle, &config)?.test_connection().await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("SMTP server did not accept the connection".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn send_report_email(
+    app_handle: AppHandle,
+    recipients: Vec<String>,
+    subject: Option<String>,
+    report: RunReport,
+) -> Result<(), String> {
+    send_report(&app_handle, &recipients, subject.as_deref(), true, &report).await
+}
@@ -0,0 +1,363 @@
+// Device emulation
+// Makes a window look like a phone or tablet to the pages it loads: the
+// window is resized to the device's viewport, the webview's user agent is
+// replaced (so servers see it too), and an injected script reports the
+// device's pixel ratio, screen, platform and touch support and turns mouse
+// input into touch events. Clearing emulation restores size and user agent.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, LogicalSize, Manager, PhysicalSize, Window};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DevicePreset {
+    IphoneSe,
+    Iphone15,
+    Pixel8,
+    IpadAir,
+    IpadPro,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DeviceProfile {
+    preset: DevicePreset,
+    name: &'static str,
+    // CSS pixels, portrait
+    width: f64,
+    height: f64,
+    device_pixel_ratio: f64,
+    touch: bool,
+    platform: &'static str,
+    user_agent: &'static str,
+}
+
+const IOS_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+const IPADOS_UA: &str = "Mozilla/5.0 (iPad; CPU OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+const ANDROID_UA: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36";
+
+const PRESETS: [DevicePreset; 5] = [
+    DevicePreset::IphoneSe,
+    DevicePreset::Iphone15,
+    DevicePreset::Pixel8,
+    DevicePreset::IpadAir,
+    DevicePreset::IpadPro,
+];
+
+impl DevicePreset {
+    fn profile(self) -> DeviceProfile {
+        let (name, width, height, device_pixel_ratio, platform, user_agent) = match self {
+            DevicePreset::IphoneSe => ("iPhone SE", 375.0, 667.0, 2.0, "iPhone", IOS_UA),
+            DevicePreset::Iphone15 => ("iPhone 15", 393.0, 852.0, 3.0, "iPhone", IOS_UA),
+            DevicePreset::Pixel8 => ("Pixel 8", 412.0, 915.0, 2.625, "Linux armv8l", ANDROID_UA),
+            DevicePreset::IpadAir => ("iPad Air", 820.0, 1180.0, 2.0, "iPad", IPADOS_UA),
+            DevicePreset::IpadPro => ("iPad Pro 12.9\"", 1024.0, 1366.0, 2.0, "iPad", IPADOS_UA),
+        };
+        DeviceProfile {
+            preset: self,
+            name,
+            width,
+            height,
+            device_pixel_ratio,
+            touch: true,
+            platform,
+            user_agent,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ActiveEmulation {
+    preset: DevicePreset,
+    landscape: bool,
+}
+
+struct Emulated {
+    active: ActiveEmulation,
+    // Inner size before emulation started
+    original_size: PhysicalSize<u32>,
+}
+
+#[derive(Default)]
+pub struct EmulationState {
+    windows: Mutex<HashMap<String, Emulated>>,
+}
+
+fn emulation_script(profile: &DeviceProfile, landscape: bool) -> String {
+    let (width, height) = if landscape {
+        (profile.height, profile.width)
+    } else {
+        (profile.width, profile.height)
+    };
+    let device = serde_json::json!({
+        "width": width,
+        "height": height,
+        "dpr": profile.device_pixel_ratio,
+        "touch": profile.touch,
+        "platform": profile.platform,
+        "userAgent": profile.user_agent,
+    });
+    format!(
+        r#"(function (device) {{
+  function define(target, name, value) {{
+    try {{ Object.defineProperty(target, name, {{ get: function () {{ return value; }}, configurable: true }}); }} catch (e) {{}}
+  }}
+  define(window, 'devicePixelRatio', device.dpr);
+  define(Navigator.prototype, 'userAgent', device.userAgent);
+  define(Navigator.prototype, 'appVersion', device.userAgent.replace(/^Mozilla\//, ''));
+  define(Navigator.prototype, 'platform', device.platform);
+  define(Navigator.prototype, 'maxTouchPoints', device.touch ? 5 : 0);
+  define(Screen.prototype, 'width', device.width);
+  define(Screen.prototype, 'height', device.height);
+  define(Screen.prototype, 'availWidth', device.width);
+  define(Screen.prototype, 'availHeight', device.height);
+  if (window.__MADEASY_EMULATION__ || !device.touch) return;
+  window.__MADEASY_EMULATION__ = true;
+  if (!('ontouchstart' in window)) window.ontouchstart = null;
+  var matchMedia = window.matchMedia.bind(window);
+  window.matchMedia = function (query) {{
+    var coarse = /\((any-)?pointer:\s*coarse\)/.test(query), fine = /\((any-)?pointer:\s*fine\)/.test(query);
+    var hoverNone = /\((any-)?hover:\s*none\)/.test(query), hover = /\((any-)?hover:\s*hover\)/.test(query);
+    if (!coarse && !fine && !hoverNone && !hover) return matchMedia(query);
+    var list = matchMedia(query);
+    define(list, 'matches', coarse || hoverNone);
+    return list;
+  }};
+  // Mouse input becomes single-finger touch
+  var active = null;
+  function touch(type, e) {{
+    try {{
+      var point = new Touch({{ identifier: 1, target: active, clientX: e.clientX, clientY: e.clientY, pageX: e.pageX, pageY: e.pageY, screenX: e.screenX, screenY: e.screenY }});
+      var touches = type === 'touchend' ? [] : [point];
+      active.dispatchEvent(new TouchEvent(type, {{ bubbles: true, cancelable: true, composed: true, touches: touches, targetTouches: touches, changedTouches: [point] }}));
+    }} catch (err) {{}}
+  }}
+  window.addEventListener('mousedown', function (e) {{ if (e.isTrusted && e.button === 0) {{ active = e.target; touch('touchstart', e); }} }}, true);
+  window.addEventListener('mousemove', function (e) {{ if (e.isTrusted && active) touch('touchmove', e); }}, true);
+  window.addEventListener('mouseup', function (e) {{ if (e.isTrusted && active) {{ touch('touchend', e); active = null; }} }}, true);
+}})({});"#,
+        device
+    )
+}
+
+fn find_window(app: &AppHandle, window_id: &str) -> Result<Window, String> {
+    app.get_window(window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))
+}
+
+pub fn on_page_load(window: &Window) {
+    let active = window
+        .state::<EmulationState>()
+        .windows
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .map(|e| e.active.clone());
+    if let Some(active) = active {
+        let _ = window.eval(&emulation_script(&active.preset.profile(), active.landscape));
+    }
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<EmulationState>().windows.lock().unwrap().remove(label);
+    platform::forget_window(label);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_device_presets() -> Result<Vec<DeviceProfile>, String> {
+    Ok(PRESETS.iter().map(|p| p.profile()).collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_device_emulation(
+    state: tauri::State<'_, EmulationState>,
+    window_id: String,
+) -> Result<Option<ActiveEmulation>, String> {
+    Ok(state.windows.lock().unwrap().get(&window_id).map(|e| e.active.clone()))
+}
+
+// The page is reloaded so the server sees the new user agent
+#[tauri::command]
+#[specta::specta]
+pub async fn emulate_device(
+    app_handle: AppHandle,
+    state: tauri::State<'_, EmulationState>,
+    window_id: String,
+    preset: DevicePreset,
+    landscape: Option<bool>,
+) -> Result<DeviceProfile, String> {
+    if window_id == "main" {
+        return Err("The main window cannot emulate a device".to_string());
+    }
+    let window = find_window(&app_handle, &window_id)?;
+    let profile = preset.profile();
+    let landscape = landscape.unwrap_or(false);
+    let (width, height) = if landscape {
+        (profile.height, profile.width)
+    } else {
+        (profile.width, profile.height)
+    };
+
+    let original_size = match state.windows.lock().unwrap().get(&window_id) {
+        Some(existing) => existing.original_size,
+        None => window.inner_size().map_err(|e| e.to_string())?,
+    };
+    platform::set_user_agent(&window, Some(profile.user_agent))?;
+    window
+        .set_size(LogicalSize::new(width, height))
+        .map_err(|e| e.to_string())?;
+    state.windows.lock().unwrap().insert(
+        window_id,
+        Emulated {
+            active: ActiveEmulation { preset, landscape },
+            original_size,
+        },
+    );
+    window.eval("location.reload()").map_err(|e| e.to_string())?;
+    Ok(profile)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_device_emulation(
+    app_handle: AppHandle,
+    state: tauri::State<'_, EmulationState>,
+    window_id: String,
+) -> Result<(), String> {
+    let window = find_window(&app_handle, &window_id)?;
+    let Some(emulated) = state.windows.lock().unwrap().remove(&window_id) else {
+        return Ok(());
+    };
+    platform::set_user_agent(&window, None)?;
+    window.set_size(emulated.original_size).map_err(|e| e.to_string())?;
+    window.eval("location.reload()").map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tauri::Window;
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Settings2;
+    use windows_webview2::core::{Interface, HSTRING, PWSTR};
+
+    // Each window's own user agent, to put back when emulation ends
+    static ORIGINAL: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+    pub fn set_user_agent(window: &Window, user_agent: Option<&'static str>) -> Result<(), String> {
+        let label = window.label().to_string();
+        window
+            .with_webview(move |webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let settings: ICoreWebView2Settings2 = webview.controller().CoreWebView2()?.Settings()?.cast()?;
+                    let mut original = ORIGINAL.lock().unwrap();
+                    let original = original.get_or_insert_with(HashMap::new);
+                    let value = match user_agent {
+                        Some(user_agent) => {
+                            if !original.contains_key(&label) {
+                                let mut current = PWSTR::null();
+                                settings.UserAgent(&mut current)?;
+                                original.insert(label, webview2_com::take_pwstr(current));
+                            }
+                            user_agent.to_string()
+                        }
+                        None => match original.remove(&label) {
+                            Some(value) => value,
+                            None => return Ok(()),
+                        },
+                    };
+                    settings.SetUserAgent(&HSTRING::from(value.as_str()))
+                })();
+                if let Err(e) = result {
+                    eprintln!("Failed to set user agent: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn forget_window(label: &str) {
+        if let Some(original) = ORIGINAL.lock().unwrap().as_mut() {
+            original.remove(label);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::{msg_send, sel, sel_impl};
+    use tauri::Window;
+
+    // A nil customUserAgent restores WKWebView's default
+    pub fn set_user_agent(window: &Window, user_agent: Option<&'static str>) -> Result<(), String> {
+        window
+            .with_webview(move |webview| unsafe {
+                let view = webview.inner() as id;
+                let value: id = match user_agent {
+                    Some(user_agent) => NSString::alloc(nil).init_str(user_agent),
+                    None => nil,
+                };
+                let _: () = msg_send![view, setCustomUserAgent: value];
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn forget_window(_label: &str) {}
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tauri::Window;
+    use webkit2gtk::{SettingsExt, WebViewExt};
+
+    static ORIGINAL: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+    pub fn set_user_agent(window: &Window, user_agent: Option<&'static str>) -> Result<(), String> {
+        let label = window.label().to_string();
+        window
+            .with_webview(move |webview| {
+                let Some(settings) = webview.inner().settings() else { return };
+                let mut original = ORIGINAL.lock().unwrap();
+                let original = original.get_or_insert_with(HashMap::new);
+                match user_agent {
+                    Some(user_agent) => {
+                        if !original.contains_key(&label) {
+                            let current = settings.user_agent().map(|s| s.to_string()).unwrap_or_default();
+                            original.insert(label, current);
+                        }
+                        settings.set_user_agent(Some(user_agent));
+                    }
+                    None => {
+                        if let Some(value) = original.remove(&label) {
+                            settings.set_user_agent(Some(value.as_str()).filter(|v| !v.is_empty()));
+                        }
+                    }
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn forget_window(label: &str) {
+        if let Some(original) = ORIGINAL.lock().unwrap().as_mut() {
+            original.remove(label);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use tauri::Window;
+
+    pub fn set_user_agent(_window: &Window, _user_agent: Option<&'static str>) -> Result<(), String> {
+        Err("Device emulation is not supported on this platform".to_string())
+    }
+
+    pub fn forget_window(_label: &str) {}
+}
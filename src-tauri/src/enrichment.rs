@@ -0,0 +1,381 @@
+// Company-registry enrichment for lead rows
+// Looks companies up by org/VAT number (or by name where the registry supports
+// it) in the configured public registries and appends legal name, org number,
+// address and status. Results, including misses, are cached on disk and each
+// registry is rate limited.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::{outbound, storage};
+use crate::workflow::{self, Row};
+
+const CONFIG_FILE: &str = "enrichment.json";
+const CACHE_NAME: &str = "enrichment";
+const BRREG_API: &str = "https://data.brreg.no/enhetsregisteret/api/enheter";
+const VIES_API: &str = "https://ec.europa.eu/taxation_customs/vies/rest-api/ms";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Registry {
+    // Brønnøysund Register Centre (Norway)
+    Brreg,
+    // EU VAT Information Exchange System
+    Vies,
+}
+
+impl Registry {
+    fn name(self) -> &'static str {
+        match self {
+            Registry::Brreg => "brreg",
+            Registry::Vies => "vies",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EnrichmentConfig {
+    // Queried in order; the first match wins
+    registries: Vec<Registry>,
+    // Minimum delay between two requests to the same registry
+    min_interval_ms: u64,
+    cache_days: i64,
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            registries: vec![Registry::Brreg, Registry::Vies],
+            min_interval_ms: 250,
+            cache_days: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Company {
+    registry: Registry,
+    org_number: String,
+    legal_name: String,
+    address: String,
+    // "active", "bankrupt", "dissolving", "deleted" or "invalid"
+    status: String,
+}
+
+#[derive(Serialize, Deserialize, Type)]
+struct CacheEntry {
+    fetched_at: i64,
+    company: Option<Company>,
+}
+
+pub struct EnrichmentState {
+    config: Mutex<EnrichmentConfig>,
+    // Held across requests so concurrent lookups queue up behind the limiter
+    last_request: tokio::sync::Mutex<HashMap<Registry, Instant>>,
+}
+
+impl EnrichmentState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+            last_request: tokio::sync::Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichAction {
+    #[serde(default = "default_name_field")]
+    pub name_field: String,
+    #[serde(default = "default_org_number_field")]
+    pub org_number_field: String,
+}
+
+fn default_name_field() -> String {
+    "company_name".to_string()
+}
+
+fn default_org_number_field() -> String {
+    "org_number".to_string()
+}
+
+enum Query {
+    Number(String),
+    Name(String),
+}
+
+impl Query {
+    fn cache_key(&self, registry: Registry) -> String {
+        match self {
+            Query::Number(number) => format!("{}\nnumber\n{}", registry.name(), number),
+            Query::Name(name) => format!("{}\nname\n{}", registry.name(), name.to_lowercase()),
+        }
+    }
+}
+
+fn compact(value: &str) -> String {
+    value.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_uppercase()
+}
+
+// Norwegian org numbers are 9 digits, optionally written as "NO 123 456 789 MVA"
+fn norwegian_org_number(value: &str) -> Option<String> {
+    let compact = compact(value);
+    let digits = compact.trim_start_matches("NO").trim_end_matches("MVA");
+    Some(digits.to_string()).filter(|d| d.len() == 9 && d.chars().all(|c| c.is_ascii_digit()))
+}
+
+// EU VAT numbers carry their country prefix, e.g. "SE556677889901"
+fn eu_vat_number(value: &str) -> Option<(String, String)> {
+    let compact = compact(value);
+    if compact.len() < 4 {
+        return None;
+    }
+    let (country, number) = compact.split_at(2);
+    let valid = country.chars().all(|c| c.is_ascii_alphabetic())
+        && country != "NO"
+        && number.chars().any(|c| c.is_ascii_digit());
+    valid.then(|| (country.to_string(), number.to_string()))
+}
+
+fn join_address(parts: &[&str]) -> String {
+    parts.iter().map(|p| p.trim()).filter(|p| !p.is_empty()).collect::<Vec<_>>().join(", ")
+}
+
+fn brreg_company(entity: &Value) -> Option<Company> {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    let address = entity
+        .get("forretningsadresse")
+        .or_else(|| entity.get("postadresse"))
+        .map(|a| {
+            let mut parts: Vec<String> = a["adresse"]
+                .as_array()
+                .map(|lines| lines.iter().map(text).collect())
+                .unwrap_or_default();
+            parts.push(format!("{} {}", text(&a["postnummer"]), text(&a["poststed"])));
+            parts.push(text(&a["land"]));
+            join_address(&parts.iter().map(String::as_str).collect::<Vec<_>>())
+        })
+        .unwrap_or_default();
+    let status = if entity["slettedato"].is_string() {
+        "deleted"
+    } else if entity["konkurs"].as_bool() == Some(true) {
+        "bankrupt"
+    } else if entity["underAvvikling"].as_bool() == Some(true)
+        || entity["underTvangsavviklingEllerTvangsopplosning"].as_bool() == Some(true)
+    {
+        "dissolving"
+    } else {
+        "active"
+    };
+
+    Some(Company {
+        registry: Registry::Brreg,
+        org_number: entity["organisasjonsnummer"].as_str()?.to_string(),
+        legal_name: entity["navn"].as_str()?.to_string(),
+        address,
+        status: status.to_string(),
+    })
+}
+
+async fn lookup_brreg(app: &AppHandle, client: &reqwest::Client, query: &Query) -> Result<Option<Company>, String> {
+    let request = match query {
+        Query::Number(number) => match norwegian_org_number(number) {
+            Some(number) => client.get(format!("{}/{}", BRREG_API, number)),
+            None => return Ok(None),
+        },
+        Query::Name(name) => client.get(BRREG_API).query(&[("navn", name.as_str()), ("size", "1")]),
+    };
+    let response = outbound::send(app, Registry::Brreg.name(), request).await?;
+    // 410 Gone: the entity was removed from the register
+    if matches!(response.status().as_u16(), 404 | 410) {
+        return Ok(None);
+    }
+    let body: Value = response
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(match query {
+        Query::Number(_) => brreg_company(&body),
+        Query::Name(_) => body["_embedded"]["enheter"].get(0).and_then(brreg_company),
+    })
+}
+
+async fn lookup_vies(app: &AppHandle, client: &reqwest::Client, query: &Query) -> Result<Option<Company>, String> {
+    // VIES has no name search
+    let Query::Number(number) = query else { return Ok(None) };
+    let Some((country, number)) = eu_vat_number(number) else { return Ok(None) };
+
+    let request = client.get(format!("{}/{}/vat/{}", VIES_API, country, number));
+    let body: Value = outbound::send(app, Registry::Vies.name(), request)
+        .await?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(error) = body["userError"].as_str().filter(|e| *e != "VALID" && *e != "INVALID") {
+        return Err(format!("VIES lookup failed: {}", error));
+    }
+
+    let valid = body["isValid"].as_bool().unwrap_or(false);
+    // Member states may withhold name and address; they come back as "---"
+    let field = |key: &str| body[key].as_str().filter(|v| *v != "---").unwrap_or_default().to_string();
+    if !valid && field("name").is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Company {
+        registry: Registry::Vies,
+        org_number: format!("{}{}", country, number),
+        legal_name: field("name"),
+        address: join_address(&field("address").lines().collect::<Vec<_>>()),
+        status: if valid { "active" } else { "invalid" }.to_string(),
+    }))
+}
+
+fn cache_path(app: &AppHandle, key: &str) -> Result<PathBuf, String> {
+    let key = hex::encode(Sha256::digest(key.as_bytes()));
+    Ok(storage::cache_dir(app, CACHE_NAME)?.join(format!("{}.json", key)))
+}
+
+fn cached(app: &AppHandle, key: &str, max_age_days: i64) -> Option<Option<Company>> {
+    let bytes = std::fs::read(cache_path(app, key).ok()?).ok()?;
+    let entry: CacheEntry = serde_json::from_slice(&bytes).ok()?;
+    let age = chrono::Utc::now().timestamp() - entry.fetched_at;
+    (age < max_age_days * 86_400).then_some(entry.company)
+}
+
+fn store(app: &AppHandle, key: &str, company: &Option<Company>) -> Result<(), String> {
+    let entry = CacheEntry {
+        fetched_at: chrono::Utc::now().timestamp(),
+        company: company.clone(),
+    };
+    let bytes = serde_json::to_vec(&entry).map_err(|e| e.to_string())?;
+    std::fs::write(cache_path(app, key)?, bytes).map_err(|e| e.to_string())
+}
+
+async fn lookup(app: &AppHandle, client: &reqwest::Client, query: &Query) -> Result<Option<Company>, String> {
+    let config = app.state::<EnrichmentState>().config.lock().unwrap().clone();
+    let interval = Duration::from_millis(config.min_interval_ms);
+
+    for registry in config.registries {
+        let key = query.cache_key(registry);
+        if let Some(hit) = cached(app, &key, config.cache_days) {
+            if hit.is_some() {
+                return Ok(hit);
+            }
+            continue;
+        }
+
+        let company = {
+            let state = app.state::<EnrichmentState>();
+            let mut last_request = state.last_request.lock().await;
+            if let Some(elapsed) = last_request.get(&registry).map(Instant::elapsed) {
+                if elapsed < interval {
+                    tokio::time::sleep(interval - elapsed).await;
+                }
+            }
+            let result = match registry {
+                Registry::Brreg => lookup_brreg(app, client, query).await,
+                Registry::Vies => lookup_vies(app, client, query).await,
+            };
+            last_request.insert(registry, Instant::now());
+            result?
+        };
+
+        store(app, &key, &company)?;
+        if company.is_some() {
+            return Ok(company);
+        }
+    }
+    Ok(None)
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(20))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+pub async fn enrich(app: &AppHandle, rows: Vec<Row>, action: &EnrichAction) -> Result<Value, String> {
+    let client = client()?;
+    let total = rows.len();
+    let mut matched = 0;
+    let mut enriched = Vec::with_capacity(total);
+
+    for mut row in rows {
+        let number = workflow::cell_text(row.get(&action.org_number_field));
+        let name = workflow::cell_text(row.get(&action.name_field));
+        let query = if !number.trim().is_empty() {
+            Query::Number(number.trim().to_string())
+        } else if !name.trim().is_empty() {
+            Query::Name(name.trim().to_string())
+        } else {
+            enriched.push(row);
+            continue;
+        };
+
+        if let Some(company) = lookup(app, &client, &query).await? {
+            matched += 1;
+            row.insert("legal_name".to_string(), json!(company.legal_name));
+            row.insert("org_number".to_string(), json!(company.org_number));
+            row.insert("address".to_string(), json!(company.address));
+            row.insert("company_status".to_string(), json!(company.status));
+            row.insert("registry".to_string(), json!(company.registry));
+        }
+        enriched.push(row);
+    }
+
+    Ok(json!({
+        "rows": enriched,
+        "summary": { "total": total, "matched": matched },
+    }))
+}
+
+pub async fn run_action(app: &AppHandle, action: &EnrichAction, rows: &[Row]) -> Result<Value, String> {
+    enrich(app, rows.to_vec(), action).await
+}
+
+// Look up a single company; queries with six or more digits are treated as org/VAT numbers
+#[tauri::command]
+#[specta::specta]
+pub async fn lookup_company(app_handle: AppHandle, query: String) -> Result<Option<Company>, String> {
+    let query = query.trim();
+    let query = if query.chars().filter(|c| c.is_ascii_digit()).count() >= 6 {
+        Query::Number(query.to_string())
+    } else {
+        Query::Name(query.to_string())
+    };
+    lookup(&app_handle, &client()?, &query).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_enrichment_config(state: tauri::State<'_, EnrichmentState>) -> Result<EnrichmentConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_enrichment_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, EnrichmentState>,
+    config: EnrichmentConfig,
+) -> Result<(), String> {
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    audit::record(&app_handle, AuditCategory::Settings, "enrichment.config", json!(config));
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
@@ -0,0 +1,160 @@
+// Named server environments (local, staging, production, ...)
+// Each environment has its own server URL and optional credentials; the
+// credential secret lives in the keychain. Switching checks that the new
+// server answers before the app (and the main window) move over to it.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::{load_app_config, secrets, server, storage, AppConfig, APP_CONFIG_FILE};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EnvironmentAuth {
+    #[default]
+    None,
+    // Secret is the token
+    Bearer,
+    // Secret is the password
+    Basic { username: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Environment {
+    name: String,
+    server_url: String,
+    #[serde(default)]
+    auth: EnvironmentAuth,
+}
+
+// Configs from before environments existed become a single "local" one
+pub fn migrate(config: &mut AppConfig) {
+    if config.environments.is_empty() {
+        config.environments.push(Environment {
+            name: "local".to_string(),
+            server_url: config.server_url.clone(),
+            auth: EnvironmentAuth::None,
+        });
+    }
+    if find(config, &config.active_environment).is_err() {
+        config.active_environment = config.environments[0].name.clone();
+    }
+}
+
+// Keep `server_url` pointing at the active environment
+pub fn sync_server_url(config: &mut AppConfig) -> Result<(), String> {
+    validate(&config.environments, &config.active_environment)?;
+    config.server_url = find(config, &config.active_environment)?.server_url.clone();
+    Ok(())
+}
+
+fn secret_key(name: &str) -> String {
+    format!("environment:{}", name)
+}
+
+pub fn validate(environments: &[Environment], active: &str) -> Result<(), String> {
+    let mut names = Vec::new();
+    for environment in environments {
+        let name = environment.name.trim();
+        if name.is_empty() {
+            return Err("Environment name is required".to_string());
+        }
+        if names.contains(&name) {
+            return Err(format!("Duplicate environment: {}", name));
+        }
+        names.push(name);
+        let url = url::Url::parse(&environment.server_url)
+            .map_err(|e| format!("Invalid server URL for {}: {}", name, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Server URL for {} must use http or https", name));
+        }
+    }
+    if !names.contains(&active) {
+        return Err(format!("Unknown active environment: {}", active));
+    }
+    Ok(())
+}
+
+pub fn find<'a>(config: &'a AppConfig, name: &str) -> Result<&'a Environment, String> {
+    config
+        .environments
+        .iter()
+        .find(|e| e.name == name)
+        .ok_or_else(|| format!("Unknown environment: {}", name))
+}
+
+fn origin(url: &str) -> Option<String> {
+    url::Url::parse(url).ok().map(|u| u.origin().ascii_serialization())
+}
+
+// Switch to another environment once its server responds. The main window
+// follows when it is showing the previous server.
+#[tauri::command]
+#[specta::specta]
+pub async fn switch_environment(app_handle: AppHandle, name: String) -> Result<(), String> {
+    let mut config = load_app_config(&app_handle);
+    let target = find(&config, &name)?.server_url.clone();
+    if !server::responds(&target).await {
+        return Err(format!("{} ({}) is not responding", name, target));
+    }
+
+    let previous = std::mem::replace(&mut config.server_url, target.clone());
+    config.active_environment = name.clone();
+    storage::save(&app_handle, APP_CONFIG_FILE, &config)?;
+    server::set_url(&app_handle, &target);
+
+    if let Some(window) = app_handle.get_window("main") {
+        let on_previous = window
+            .url()
+            .ok()
+            .map_or(false, |u| origin(u.as_str()) == origin(&previous));
+        if on_previous {
+            window
+                .eval(&format!("window.location.replace({})", serde_json::to_string(&target).unwrap_or_default()))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    app_handle
+        .emit_all("environment-changed", json!({ "name": name, "server_url": target }))
+        .map_err(|e| e.to_string())
+}
+
+// Store (or clear, with None) the token/password for an environment
+#[tauri::command]
+#[specta::specta]
+pub async fn set_environment_secret(app_handle: AppHandle, name: String, secret: Option<String>) -> Result<(), String> {
+    let config = load_app_config(&app_handle);
+    find(&config, &name)?;
+    match secret.filter(|s| !s.is_empty()) {
+        Some(secret) => secrets::set(&secret_key(&name), &secret),
+        None => secrets::delete(&secret_key(&name)),
+    }
+}
+
+// Authorization header value for requests to the active environment's server
+#[tauri::command]
+#[specta::specta]
+pub async fn get_environment_auth_header(app_handle: AppHandle) -> Result<Option<String>, String> {
+    let config = load_app_config(&app_handle);
+    let environment = find(&config, &config.active_environment)?;
+    let header = match &environment.auth {
+        EnvironmentAuth::None => return Ok(None),
+        EnvironmentAuth::Bearer => format!("Bearer {}", secrets::require(&secret_key(&environment.name))?),
+        EnvironmentAuth::Basic { username } => {
+            use base64::Engine;
+            let password = secrets::require(&secret_key(&environment.name))?;
+            let encoded = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+            format!("Basic {}", encoded)
+        }
+    };
+    audit::record(
+        &app_handle,
+        AuditCategory::Credential,
+        "environment.auth",
+        json!({ "environment": environment.name }),
+    );
+    Ok(Some(header))
+}
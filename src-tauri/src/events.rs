@@ -0,0 +1,112 @@
+// Typed application events
+// Subsystems publish AppEvents here instead of emitting ad-hoc strings;
+// subscribers registered at setup are called in order, then the event is
+// serialized once and forwarded to every window under its kebab-case name
+// (e.g. `navigation-finished`) with a `type` tag.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AppEvent {
+    NavigationFinished {
+        window_id: String,
+        url: String,
+    },
+    DownloadCompleted {
+        url: String,
+        path: String,
+        bytes: Option<u64>,
+        // Expected checksum (hex) and base64 ed25519 signature of it, when
+        // the source publishes them
+        #[serde(default)]
+        sha256: Option<String>,
+        #[serde(default)]
+        signature: Option<String>,
+    },
+    WorkflowFailed {
+        run_id: String,
+        workflow_name: String,
+        error: String,
+    },
+    AiTokenReceived {
+        request_id: String,
+        token: String,
+    },
+    NavigationBlocked {
+        window_id: String,
+        url: String,
+        reason: String,
+    },
+}
+
+impl AppEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::NavigationFinished { .. } => "navigation-finished",
+            AppEvent::DownloadCompleted { .. } => "download-completed",
+            AppEvent::WorkflowFailed { .. } => "workflow-failed",
+            AppEvent::AiTokenReceived { .. } => "ai-token-received",
+            AppEvent::NavigationBlocked { .. } => "navigation-blocked",
+        }
+    }
+}
+
+// TypeScript for the event payloads plus a name -> payload map for typed listeners
+#[cfg(debug_assertions)]
+pub fn typescript(config: &specta::ts::ExportConfiguration) -> Result<String, String> {
+    // (event name, `type` tag); keep in step with AppEvent::name
+    const EVENTS: [(&str, &str); 5] = [
+        ("navigation-finished", "navigation_finished"),
+        ("download-completed", "download_completed"),
+        ("workflow-failed", "workflow_failed"),
+        ("ai-token-received", "ai_token_received"),
+        ("navigation-blocked", "navigation_blocked"),
+    ];
+    let payload = specta::ts::export::<AppEvent>(config).map_err(|e| e.to_string())?;
+    let map: String = EVENTS
+        .iter()
+        .map(|(name, tag)| format!("  \"{}\": Extract<AppEvent, {{ type: \"{}\" }}>;\n", name, tag))
+        .collect();
+    Ok(format!(
+        "// Generated by src-tauri on debug runs; do not edit\n\n{}\n\nexport type AppEventMap = {{\n{}}};\n",
+        payload, map
+    ))
+}
+
+pub type Subscriber = fn(&AppHandle, &AppEvent);
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+pub fn subscribe(app: &AppHandle, subscriber: Subscriber) {
+    app.state::<EventBus>().subscribers.lock().unwrap().push(subscriber);
+}
+
+pub fn publish(app: &AppHandle, event: AppEvent) {
+    // Copied out so a subscriber can publish without deadlocking
+    let subscribers = app.state::<EventBus>().subscribers.lock().unwrap().clone();
+    for subscriber in subscribers {
+        subscriber(app, &event);
+    }
+    let _ = app.emit_all(event.name(), &event);
+}
+
+// Events that happen on the frontend or in the workflow engine; the others
+// only originate in the backend
+#[tauri::command]
+#[specta::specta]
+pub async fn publish_app_event(app_handle: AppHandle, event: AppEvent) -> Result<(), String> {
+    match event {
+        AppEvent::DownloadCompleted { .. } | AppEvent::WorkflowFailed { .. } => {
+            publish(&app_handle, event);
+            Ok(())
+        }
+        other => Err(format!("{} can only be published by the backend", other.name())),
+    }
+}
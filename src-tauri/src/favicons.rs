@@ -0,0 +1,176 @@
+// Favicon caching service
+// Icons are fetched once per origin, normalized to 32x32 PNG (SVG is kept as-is)
+// and served from the app cache as data URLs. Origins come from page content,
+// so requests go through linkpreview's public-address check on every hop.
+
+use base64::Engine;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::{linkpreview, storage, tasks};
+
+const CACHE_NAME: &str = "favicons";
+const ICON_SIZE: u32 = 32;
+const MAX_ICON_BYTES: usize = 512 * 1024;
+// Icon links are in the head; the rest of the page is not needed
+const MAX_PAGE_BYTES: usize = 256 * 1024;
+// Refetch icons (and retry origins without one) after a week
+const TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct CacheMeta {
+    fetched_at: i64,
+    mime: Option<String>,
+}
+
+fn cache_key(origin: &str) -> String {
+    hex::encode(Sha256::digest(origin.as_bytes()))
+}
+
+fn paths(app: &AppHandle, origin: &str) -> Result<(PathBuf, PathBuf), String> {
+    let dir = storage::cache_dir(app, CACHE_NAME)?;
+    let key = cache_key(origin);
+    Ok((dir.join(format!("{}.icon", key)), dir.join(format!("{}.json", key))))
+}
+
+fn normalize_origin(origin: &str) -> Result<Url, String> {
+    let url = Url::parse(origin).map_err(|e| format!("Invalid origin: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Unsupported scheme: {}", url.scheme()));
+    }
+    Url::parse(&url.origin().ascii_serialization()).map_err(|e| e.to_string())
+}
+
+// Candidate icon URLs declared by the page, best first, then /favicon.ico
+async fn candidate_urls(app: &AppHandle, origin: &Url) -> Vec<Url> {
+    let mut candidates = Vec::new();
+
+    if let Ok((_, response)) = linkpreview::get_public(app, origin, "text/html,application/xhtml+xml").await {
+        if let Some((body, _)) = linkpreview::read_up_to(app, response, MAX_PAGE_BYTES).await {
+            let body = String::from_utf8_lossy(&body);
+            let document = Html::parse_document(&body);
+            let selector = Selector::parse("link[rel][href]").unwrap();
+            let mut declared: Vec<(u32, Url)> = document
+                .select(&selector)
+                .filter_map(|link| {
+                    let rel = link.value().attr("rel")?.to_lowercase();
+                    let rank = match rel.as_str() {
+                        "icon" | "shortcut icon" => 0,
+                        "apple-touch-icon" => 1,
+                        _ => return None,
+                    };
+                    let href = origin.join(link.value().attr("href")?).ok()?;
+                    Some((rank, href))
+                })
+                .collect();
+            declared.sort_by_key(|(rank, _)| *rank);
+            candidates.extend(declared.into_iter().map(|(_, url)| url));
+        }
+    }
+
+    if let Ok(fallback) = origin.join("/favicon.ico") {
+        candidates.push(fallback);
+    }
+    candidates
+}
+
+// Convert fetched bytes to (mime, bytes): SVG passes through, bitmaps become PNG
+fn normalize_icon(bytes: &[u8], content_type: Option<&str>) -> Option<(String, Vec<u8>)> {
+    let looks_svg = content_type.map_or(false, |t| t.contains("svg"))
+        || bytes.starts_with(b"<svg")
+        || bytes.starts_with(b"<?xml");
+    if looks_svg {
+        return Some(("image/svg+xml".to_string(), bytes.to_vec()));
+    }
+
+    let image = image::load_from_memory(bytes).ok()?;
+    let resized = image.resize(ICON_SIZE, ICON_SIZE, image::imageops::FilterType::Lanczos3);
+    let mut png = Vec::new();
+    resized
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+        .ok()?;
+    Some(("image/png".to_string(), png))
+}
+
+async fn fetch_icon(app: &AppHandle, origin: &Url) -> Option<(String, Vec<u8>)> {
+    for url in candidate_urls(app, origin).await {
+        let Ok((_, response)) = linkpreview::get_public(app, &url, "image/*").await else {
+            continue;
+        };
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        // Oversized icons are skipped without reading them whole
+        let Some((bytes, true)) = linkpreview::read_up_to(app, response, MAX_ICON_BYTES).await else {
+            continue;
+        };
+        if let Some(icon) = normalize_icon(&bytes, content_type.as_deref()) {
+            return Some(icon);
+        }
+    }
+    None
+}
+
+fn to_data_url(mime: &str, bytes: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+// Cached icon bytes for an origin, fetching when missing or stale
+pub async fn icon_for(app: &AppHandle, origin: &str) -> Result<Option<(String, Vec<u8>)>, String> {
+    let origin = normalize_origin(origin)?;
+    let (icon_path, meta_path) = paths(app, origin.as_str())?;
+    let now = chrono::Utc::now().timestamp();
+
+    let (icon_file, meta_file) = (icon_path.clone(), meta_path.clone());
+    let cached = tasks::blocking(move || {
+        let meta: Option<CacheMeta> = std::fs::read(&meta_file)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+        Ok(meta.filter(|m| now - m.fetched_at < TTL_SECS).map(|meta| match meta.mime {
+            Some(mime) => std::fs::read(&icon_file).ok().map(|bytes| (mime, bytes)),
+            // Known to have no icon; don't hammer the server again
+            None => None,
+        }))
+    })
+    .await?;
+    if let Some(icon) = cached {
+        return Ok(icon);
+    }
+
+    let icon = fetch_icon(app, &origin).await;
+    let meta = CacheMeta {
+        fetched_at: now,
+        mime: icon.as_ref().map(|(mime, _)| mime.clone()),
+    };
+    let bytes = icon.as_ref().map(|(_, bytes)| bytes.clone());
+    tasks::blocking(move || {
+        if let Some(bytes) = bytes {
+            std::fs::write(&icon_path, bytes).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&meta_path, serde_json::to_vec(&meta).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())
+    })
+    .await?;
+
+    Ok(icon)
+}
+
+// Favicon for an origin as a data URL, or None when the site has no usable icon
+#[tauri::command]
+#[specta::specta]
+pub async fn get_favicon(app_handle: AppHandle, origin: String) -> Result<Option<String>, String> {
+    Ok(icon_for(&app_handle, &origin)
+        .await?
+        .map(|(mime, bytes)| to_data_url(&mime, &bytes)))
+}
@@ -0,0 +1,413 @@
+// RSS/Atom feeds
+// Pages advertise feeds with <link rel="alternate">; an injected script reports
+// them so the shell can offer a subscribe button. Subscribed feeds are polled in
+// the background with conditional requests and new entries are stored in SQLite.
+
+use reqwest::header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{StatusCode, Url};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::db::Database;
+use crate::{bandwidth, battery, i18n, idle, network};
+use crate::notifications::{self, Notice, NotificationAction, NotificationCategory};
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS feeds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL DEFAULT '',
+    site_url TEXT,
+    etag TEXT,
+    last_modified TEXT,
+    last_polled INTEGER,
+    last_error TEXT
+);
+CREATE TABLE IF NOT EXISTS feed_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    feed_id INTEGER NOT NULL REFERENCES feeds (id) ON DELETE CASCADE,
+    guid TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    url TEXT,
+    summary TEXT NOT NULL DEFAULT '',
+    published INTEGER,
+    fetched_at INTEGER NOT NULL,
+    read INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (feed_id, guid)
+);
+CREATE INDEX IF NOT EXISTS feed_entries_published ON feed_entries (published DESC);
+";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const SUMMARY_CHARS: usize = 500;
+
+const DETECT_SCRIPT: &str = r#"(function () {
+  if (!window.__TAURI_INVOKE__ || !/^https?:/.test(location.protocol)) return;
+  var links = document.querySelectorAll(
+    'link[rel~="alternate"][type="application/rss+xml"], link[rel~="alternate"][type="application/atom+xml"]'
+  );
+  var feeds = Array.prototype.map.call(links, function (l) {
+    return { url: l.href, title: l.title || document.title };
+  });
+  if (feeds.length) window.__TAURI_INVOKE__('feeds_detected', { feeds: feeds });
+})();"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DetectedFeed {
+    url: String,
+    title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+struct FeedsDetected {
+    window_id: String,
+    feeds: Vec<DetectedFeed>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Feed {
+    id: i64,
+    url: String,
+    title: String,
+    site_url: Option<String>,
+    last_polled: Option<i64>,
+    last_error: Option<String>,
+    unread: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FeedEntry {
+    id: i64,
+    feed_id: i64,
+    title: String,
+    url: Option<String>,
+    summary: String,
+    published: Option<i64>,
+    read: bool,
+}
+
+impl FeedEntry {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            feed_id: row.get(1)?,
+            title: row.get(2)?,
+            url: row.get(3)?,
+            summary: row.get(4)?,
+            published: row.get(5)?,
+            read: row.get(6)?,
+        })
+    }
+}
+
+struct PollTarget {
+    id: i64,
+    url: String,
+    title: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub fn inject(window: &Window) {
+    let _ = window.eval(DETECT_SCRIPT);
+}
+
+fn strip_tags(html: &str) -> String {
+    let text = scraper::Html::parse_fragment(html).root_element().text().collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(SUMMARY_CHARS) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text,
+    }
+}
+
+// Fetch and store one feed; returns (feed title, new entry titles and links)
+async fn poll_feed(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    db: &Database,
+    target: &PollTarget,
+) -> Result<(String, Vec<(String, Option<String>)>), String> {
+    let mut request = client.get(&target.url);
+    if let Some(etag) = &target.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(modified) = &target.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, modified);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    bandwidth::record(app, &target.url, 0, 0);
+    let now = chrono::Utc::now().timestamp();
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        db.with(|conn| {
+            conn.execute(
+                "UPDATE feeds SET last_polled = ?2, last_error = NULL WHERE id = ?1",
+                params![target.id, now],
+            )
+        })?;
+        return Ok((target.title.clone(), Vec::new()));
+    }
+    let response = response.error_for_status().map_err(|e| e.to_string())?;
+    let header = |name: HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    bandwidth::record_received(app, &target.url, body.len() as u64);
+    let feed = feed_rs::parser::parse(&body[..]).map_err(|e| format!("Not a valid feed: {}", e))?;
+
+    let title = feed
+        .title
+        .map(|t| t.content)
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| target.title.clone());
+    let site_url = feed.links.first().map(|l| l.href.clone());
+
+    db.with(|conn| {
+        conn.execute(
+            "UPDATE feeds SET title = ?2, site_url = ?3, etag = ?4, last_modified = ?5,
+                 last_polled = ?6, last_error = NULL
+             WHERE id = ?1",
+            params![target.id, title, site_url, etag, last_modified, now],
+        )?;
+
+        let mut new_entries = Vec::new();
+        for entry in &feed.entries {
+            let entry_title = entry.title.as_ref().map(|t| t.content.clone()).unwrap_or_default();
+            let link = entry.links.first().map(|l| l.href.clone());
+            let summary = entry
+                .summary
+                .as_ref()
+                .map(|s| s.content.clone())
+                .or_else(|| entry.content.as_ref().and_then(|c| c.body.clone()))
+                .map(|s| strip_tags(&s))
+                .unwrap_or_default();
+            let published = entry.published.or(entry.updated).map(|d| d.timestamp());
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO feed_entries
+                     (feed_id, guid, title, url, summary, published, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![target.id, entry.id, entry_title, link, summary, published, now],
+            )?;
+            if inserted > 0 {
+                new_entries.push((entry_title, link));
+            }
+        }
+        Ok((title, new_entries))
+    })
+}
+
+fn notify_new_entries(app: &AppHandle, feed_title: &str, entries: &[(String, Option<String>)]) {
+    let notice = match entries {
+        [] => return,
+        [(title, link)] => {
+            let notice = Notice::new(NotificationCategory::Feed, feed_title, title.clone());
+            match link {
+                Some(url) => notice.with_action(NotificationAction::OpenUrl { url: url.clone() }),
+                None => notice,
+            }
+        }
+        _ => Notice::new(
+            NotificationCategory::Feed,
+            feed_title,
+            i18n::text_with(app, "notice-feed-new-items", &[("count", entries.len().into())]),
+        ),
+    };
+    let _ = notifications::notify(app, notice.owned_by("feeds"));
+}
+
+pub async fn poll_all(app: &AppHandle, notify: bool) -> Result<(), String> {
+    let db = app.state::<Database>();
+    let targets = db.with(|conn| {
+        let mut stmt = conn.prepare("SELECT id, url, title, etag, last_modified FROM feeds")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(PollTarget {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                title: row.get(2)?,
+                etag: row.get(3)?,
+                last_modified: row.get(4)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut changed = false;
+    for target in targets {
+        // Feeds on a domain over its monthly cap wait for next month
+        if bandwidth::domain_over_cap(app, &target.url) {
+            continue;
+        }
+        match poll_feed(app, &client, &db, &target).await {
+            Ok((title, entries)) => {
+                changed |= !entries.is_empty();
+                if notify {
+                    notify_new_entries(app, &title, &entries);
+                }
+            }
+            Err(e) => {
+                db.with(|conn| {
+                    conn.execute("UPDATE feeds SET last_error = ?2 WHERE id = ?1", params![target.id, e])
+                })?;
+            }
+        }
+    }
+    if changed {
+        let _ = app.emit_all("feeds-updated", ());
+    }
+    Ok(())
+}
+
+pub fn start_poller(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        // Metered or offline connections, an idle user or a low battery wait for the next round
+        if !network::transfers_paused(&app) && !idle::polling_paused(&app) && !battery::should_defer_heavy_jobs(&app) {
+            if let Err(e) = tauri::async_runtime::block_on(poll_all(&app, true)) {
+                eprintln!("Feed polling failed: {}", e);
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+// Called by DETECT_SCRIPT
+#[tauri::command]
+#[specta::specta]
+pub async fn feeds_detected(window: Window, feeds: Vec<DetectedFeed>) -> Result<(), String> {
+    window
+        .emit_all(
+            "feeds-detected",
+            FeedsDetected {
+                window_id: window.label().to_string(),
+                feeds,
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn subscribe_feed(app_handle: AppHandle, db: tauri::State<'_, Database>, url: String) -> Result<i64, String> {
+    let url = Url::parse(&url).map_err(|e| format!("Invalid feed URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Unsupported scheme: {}", url.scheme()));
+    }
+    let existing: Option<i64> = db.with(|conn| {
+        conn.query_row("SELECT id FROM feeds WHERE url = ?1", params![url.as_str()], |row| row.get(0))
+            .optional()
+    })?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    let id = db.with(|conn| {
+        conn.execute("INSERT INTO feeds (url) VALUES (?1)", params![url.as_str()])?;
+        Ok(conn.last_insert_rowid())
+    })?;
+
+    // Fetch right away so the feed is validated and filled without notifying
+    // about every existing entry
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let target = PollTarget {
+        id,
+        url: url.to_string(),
+        title: String::new(),
+        etag: None,
+        last_modified: None,
+    };
+    if let Err(e) = poll_feed(&app_handle, &client, &db, &target).await {
+        db.with(|conn| conn.execute("DELETE FROM feeds WHERE id = ?1", params![id]))?;
+        return Err(e);
+    }
+    let _ = app_handle.emit_all("feeds-updated", ());
+    Ok(id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn unsubscribe_feed(app_handle: AppHandle, db: tauri::State<'_, Database>, feed_id: i64) -> Result<(), String> {
+    db.with(|conn| conn.execute("DELETE FROM feeds WHERE id = ?1", params![feed_id]))?;
+    let _ = app_handle.emit_all("feeds-updated", ());
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_feeds(db: tauri::State<'_, Database>) -> Result<Vec<Feed>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.url, f.title, f.site_url, f.last_polled, f.last_error,
+                    (SELECT COUNT(*) FROM feed_entries e WHERE e.feed_id = f.id AND e.read = 0)
+             FROM feeds f ORDER BY f.title COLLATE NOCASE",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Feed {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                title: row.get(2)?,
+                site_url: row.get(3)?,
+                last_polled: row.get(4)?,
+                last_error: row.get(5)?,
+                unread: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_feed_entries(
+    db: tauri::State<'_, Database>,
+    feed_id: Option<i64>,
+    unread_only: Option<bool>,
+    limit: Option<u32>,
+) -> Result<Vec<FeedEntry>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, feed_id, title, url, summary, published, read FROM feed_entries
+             WHERE (?1 IS NULL OR feed_id = ?1) AND (?2 = 0 OR read = 0)
+             ORDER BY COALESCE(published, fetched_at) DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![feed_id, unread_only.unwrap_or(false), limit.unwrap_or(200)],
+            FeedEntry::from_row,
+        )?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn mark_feed_entry_read(db: tauri::State<'_, Database>, entry_id: i64, read: Option<bool>) -> Result<(), String> {
+    db.with(|conn| {
+        conn.execute(
+            "UPDATE feed_entries SET read = ?2 WHERE id = ?1",
+            params![entry_id, read.unwrap_or(true)],
+        )
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn refresh_feeds(app_handle: AppHandle) -> Result<(), String> {
+    poll_all(&app_handle, true).await
+}
@@ -0,0 +1,189 @@
+// Find in page
+// Tauri 1 has no find API, so matches are highlighted by an injected script.
+// The script reports back through `report_find_result`, which completes the
+// waiting command so the shell gets counts synchronously.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+use tokio::sync::oneshot;
+
+const RESULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+const FIND_SCRIPT: &str = r#"(function () {
+  if (window.__MADEASY_FIND__) return;
+  var state = { marks: [], index: -1 };
+  var CURRENT = 'background:#ff9632;color:#000', OTHER = 'background:#ffff00;color:#000';
+
+  function report() {
+    window.__TAURI_INVOKE__ && window.__TAURI_INVOKE__('report_find_result', {
+      matches: state.marks.length, activeIndex: state.index
+    });
+  }
+  function clear() {
+    state.marks.forEach(function (mark) {
+      var parent = mark.parentNode;
+      if (!parent) return;
+      parent.replaceChild(document.createTextNode(mark.textContent), mark);
+      parent.normalize();
+    });
+    state.marks = [];
+    state.index = -1;
+  }
+  function activate(i) {
+    if (!state.marks.length) return;
+    if (state.index >= 0) state.marks[state.index].style.cssText = OTHER;
+    state.index = (i + state.marks.length) % state.marks.length;
+    var mark = state.marks[state.index];
+    mark.style.cssText = CURRENT;
+    mark.scrollIntoView({ block: 'center', inline: 'nearest' });
+  }
+  function escape(s) { return s.replace(/[.*+?^${}()|[\]\\]/g, '\\$&'); }
+
+  window.__MADEASY_FIND__ = {
+    find: function (query, options) {
+      clear();
+      if (query) {
+        var source = escape(query);
+        if (options.wholeWord) source = '\\b' + source + '\\b';
+        var re = new RegExp(source, options.caseSensitive ? 'g' : 'gi');
+        var walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT, {
+          acceptNode: function (node) {
+            var p = node.parentElement;
+            if (!p || /^(SCRIPT|STYLE|NOSCRIPT|TEXTAREA)$/.test(p.tagName)) return NodeFilter.FILTER_REJECT;
+            return NodeFilter.FILTER_ACCEPT;
+          }
+        });
+        var nodes = [];
+        while (walker.nextNode()) nodes.push(walker.currentNode);
+        nodes.forEach(function (node) {
+          var text = node.nodeValue, match, last = 0, parts = [];
+          re.lastIndex = 0;
+          while ((match = re.exec(text)) && match[0].length) {
+            parts.push(document.createTextNode(text.slice(last, match.index)));
+            var mark = document.createElement('mark');
+            mark.setAttribute('data-madeasy-find', '');
+            mark.style.cssText = OTHER;
+            mark.textContent = match[0];
+            parts.push(mark);
+            state.marks.push(mark);
+            last = match.index + match[0].length;
+          }
+          if (!parts.length) return;
+          parts.push(document.createTextNode(text.slice(last)));
+          var frag = document.createDocumentFragment();
+          parts.forEach(function (p) { frag.appendChild(p); });
+          node.parentNode.replaceChild(frag, node);
+        });
+        activate(options.backwards ? -1 : 0);
+      }
+      report();
+    },
+    next: function (forward) {
+      activate(state.index + (forward ? 1 : -1));
+      report();
+    },
+    stop: function () { clear(); }
+  };
+})();"#;
+
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FindOptions {
+    case_sensitive: bool,
+    whole_word: bool,
+    // Start at the last match instead of the first
+    backwards: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FindResult {
+    matches: u32,
+    // Zero-based index of the highlighted match, -1 when there are none
+    active_index: i32,
+}
+
+#[derive(Default)]
+pub struct FindState {
+    pending: Mutex<HashMap<String, oneshot::Sender<FindResult>>>,
+}
+
+fn window_by_id(app: &AppHandle, window_id: &str) -> Result<Window, String> {
+    app.get_window(window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))
+}
+
+// Run a find script in the window and wait for it to report the result
+async fn run(app: &AppHandle, window: &Window, call: String) -> Result<FindResult, String> {
+    let (tx, rx) = oneshot::channel();
+    app.state::<FindState>()
+        .pending
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), tx);
+
+    window
+        .eval(&format!("{}\nwindow.__MADEASY_FIND__.{};", FIND_SCRIPT, call))
+        .map_err(|e| e.to_string())?;
+
+    match tokio::time::timeout(RESULT_TIMEOUT, rx).await {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(_)) => Err("Find request was superseded".to_string()),
+        Err(_) => Err("Page did not respond to find request".to_string()),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn find_in_page(
+    app_handle: AppHandle,
+    window_id: String,
+    query: String,
+    options: Option<FindOptions>,
+) -> Result<FindResult, String> {
+    let window = window_by_id(&app_handle, &window_id)?;
+    let options = options.unwrap_or_default();
+    let call = format!(
+        "find({}, {{ caseSensitive: {}, wholeWord: {}, backwards: {} }})",
+        serde_json::to_string(&query).map_err(|e| e.to_string())?,
+        options.case_sensitive,
+        options.whole_word,
+        options.backwards
+    );
+    run(&app_handle, &window, call).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn find_next(app_handle: AppHandle, window_id: String, forward: Option<bool>) -> Result<FindResult, String> {
+    let window = window_by_id(&app_handle, &window_id)?;
+    run(&app_handle, &window, format!("next({})", forward.unwrap_or(true))).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_find(app_handle: AppHandle, window_id: String) -> Result<(), String> {
+    let window = window_by_id(&app_handle, &window_id)?;
+    app_handle.state::<FindState>().pending.lock().unwrap().remove(&window_id);
+    window
+        .eval("window.__MADEASY_FIND__ && window.__MADEASY_FIND__.stop();")
+        .map_err(|e| e.to_string())
+}
+
+// Called by the injected script
+#[tauri::command]
+#[specta::specta]
+pub async fn report_find_result(
+    window: Window,
+    state: tauri::State<'_, FindState>,
+    matches: u32,
+    active_index: i32,
+) -> Result<(), String> {
+    if let Some(tx) = state.pending.lock().unwrap().remove(window.label()) {
+        let _ = tx.send(FindResult { matches, active_index });
+    }
+    Ok(())
+}
@@ -0,0 +1,350 @@
+// Fingerprinting resistance
+// With the mode on for a profile, new windows get a script that adds faint,
+// stable noise to canvas, audio and WebGL readouts, reports a generic WebGL
+// renderer, limits font probing to a common set, and pins hardware details
+// and other navigator properties to fixed values. Noise and values derive
+// from a random per-profile seed combined with the window's partition (its
+// container, if any), so they stay the same within a partition across
+// sessions but differ between partitions. The page counts how often each
+// surface is read, for `get_fingerprint_report`.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{containers, pagequery, profiles, storage};
+
+const SETTINGS_FILE: &str = "fingerprint.json";
+const DEFAULT_PARTITION: &str = "default";
+const REPORT_TIMEOUT: Duration = Duration::from_secs(5);
+// Fonts `document.fonts.check` admits to having
+const COMMON_FONTS: [&str; 12] = [
+    "Arial", "Helvetica", "Times New Roman", "Times", "Courier New", "Courier", "Verdana", "Georgia", "Tahoma",
+    "Trebuchet MS", "serif", "sans-serif",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct FingerprintSettings {
+    enabled: bool,
+    canvas: bool,
+    audio: bool,
+    webgl: bool,
+    fonts: bool,
+    hardware: bool,
+    freeze_navigator: bool,
+}
+
+impl Default for FingerprintSettings {
+    // Everything on once the mode is enabled
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            canvas: true,
+            audio: true,
+            webgl: true,
+            fonts: true,
+            hardware: true,
+            freeze_navigator: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct FingerprintFile {
+    #[serde(flatten)]
+    settings: FingerprintSettings,
+    // Created on first save
+    seed: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SpoofedValue {
+    surface: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FingerprintReport {
+    enabled: bool,
+    partition: String,
+    spoofed: Vec<SpoofedValue>,
+    // Surface -> times the page read it since loading
+    reads: HashMap<String, u32>,
+}
+
+// Values a page in one partition sees
+struct Persona {
+    noise_seed: u32,
+    hardware_concurrency: u32,
+    device_memory: u32,
+}
+
+fn load(app: &AppHandle, profile_id: &str) -> FingerprintFile {
+    storage::load(app, &profiles::profile_file(profile_id, SETTINGS_FILE))
+}
+
+fn persona(seed: &str, partition: &str) -> Persona {
+    let digest = Sha256::digest(format!("{}:{}", seed, partition).as_bytes());
+    Persona {
+        noise_seed: u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]]),
+        hardware_concurrency: [4, 8][(digest[4] % 2) as usize],
+        device_memory: [4, 8][(digest[5] % 2) as usize],
+    }
+}
+
+fn spoofed_values(settings: &FingerprintSettings, persona: &Persona) -> Vec<SpoofedValue> {
+    let value = |surface: &str, value: String| SpoofedValue {
+        surface: surface.to_string(),
+        value,
+    };
+    let mut spoofed = Vec::new();
+    if settings.canvas {
+        spoofed.push(value("canvas", format!("pixel noise, seed {:08x}", persona.noise_seed)));
+    }
+    if settings.audio {
+        spoofed.push(value("audio", "sample noise".to_string()));
+    }
+    if settings.webgl {
+        spoofed.push(value("webgl", "vendor \"Google Inc.\", renderer \"ANGLE (Generic GPU)\", pixel noise".to_string()));
+    }
+    if settings.fonts {
+        spoofed.push(value("fonts", COMMON_FONTS.join(", ")));
+    }
+    if settings.hardware {
+        spoofed.push(value("hardwareConcurrency", persona.hardware_concurrency.to_string()));
+        spoofed.push(value("deviceMemory", persona.device_memory.to_string()));
+    }
+    if settings.freeze_navigator {
+        spoofed.push(value("navigator", "no plugins, doNotTrack unset, webdriver false, frozen".to_string()));
+    }
+    spoofed
+}
+
+const FINGERPRINT_SCRIPT: &str = r#"(function (CONFIG) {
+  if (window.__MADEASY_FINGERPRINT__) return;
+  Object.defineProperty(window, '__MADEASY_FINGERPRINT__', { value: true });
+  var reads = {};
+  function hit(surface) { reads[surface] = (reads[surface] || 0) + 1; }
+  Object.defineProperty(window, '__MADEASY_FINGERPRINT_READS__', { value: function () { return reads; } });
+  // mulberry32, so noise is the same for the same data
+  function random(seed) {
+    return function () {
+      seed = (seed + 0x6D2B79F5) | 0;
+      var t = Math.imul(seed ^ (seed >>> 15), 1 | seed);
+      t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+      return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+    };
+  }
+  function noisePixels(data, width) {
+    var next = random(CONFIG.seed ^ width);
+    for (var i = 0; i < data.length; i += 4) {
+      if (next() < 0.05) data[i] = data[i] ^ 1;
+    }
+  }
+  function define(target, name, getter) {
+    Object.defineProperty(target, name, { get: getter, configurable: !CONFIG.freeze, enumerable: true });
+  }
+
+  if (CONFIG.canvas) {
+    var getImageData = CanvasRenderingContext2D.prototype.getImageData;
+    CanvasRenderingContext2D.prototype.getImageData = function () {
+      hit('canvas');
+      var image = getImageData.apply(this, arguments);
+      noisePixels(image.data, image.width);
+      return image;
+    };
+    var noisyCopy = function (canvas) {
+      var copy = document.createElement('canvas');
+      copy.width = canvas.width;
+      copy.height = canvas.height;
+      var ctx = copy.getContext('2d');
+      if (!ctx || !canvas.width || !canvas.height) return canvas;
+      ctx.drawImage(canvas, 0, 0);
+      var image = getImageData.call(ctx, 0, 0, copy.width, copy.height);
+      noisePixels(image.data, image.width);
+      ctx.putImageData(image, 0, 0);
+      return copy;
+    };
+    var toDataURL = HTMLCanvasElement.prototype.toDataURL;
+    HTMLCanvasElement.prototype.toDataURL = function () {
+      hit('canvas');
+      return toDataURL.apply(noisyCopy(this), arguments);
+    };
+    var toBlob = HTMLCanvasElement.prototype.toBlob;
+    HTMLCanvasElement.prototype.toBlob = function () {
+      hit('canvas');
+      return toBlob.apply(noisyCopy(this), arguments);
+    };
+  }
+
+  if (CONFIG.audio && window.AudioBuffer) {
+    var getChannelData = AudioBuffer.prototype.getChannelData;
+    var noised = new WeakSet();
+    AudioBuffer.prototype.getChannelData = function () {
+      hit('audio');
+      var data = getChannelData.apply(this, arguments);
+      if (!noised.has(data)) {
+        noised.add(data);
+        var next = random(CONFIG.seed);
+        for (var i = 0; i < data.length; i += 100) data[i] += (next() - 0.5) * 1e-7;
+      }
+      return data;
+    };
+    if (window.AnalyserNode) {
+      var getFloatFrequencyData = AnalyserNode.prototype.getFloatFrequencyData;
+      AnalyserNode.prototype.getFloatFrequencyData = function (array) {
+        hit('audio');
+        getFloatFrequencyData.apply(this, arguments);
+        var next = random(CONFIG.seed);
+        for (var i = 0; i < array.length; i++) array[i] += (next() - 0.5) * 1e-4;
+      };
+    }
+  }
+
+  if (CONFIG.webgl) {
+    [window.WebGLRenderingContext, window.WebGL2RenderingContext].forEach(function (Context) {
+      if (!Context) return;
+      var getParameter = Context.prototype.getParameter;
+      Context.prototype.getParameter = function (name) {
+        if (name === 0x9245 || name === 0x9246) hit('webgl');
+        if (name === 0x9245) return 'Google Inc.';
+        if (name === 0x9246) return 'ANGLE (Generic GPU)';
+        return getParameter.apply(this, arguments);
+      };
+      var readPixels = Context.prototype.readPixels;
+      Context.prototype.readPixels = function (x, y, width, height, format, type, pixels) {
+        hit('webgl');
+        readPixels.apply(this, arguments);
+        if (pixels && pixels.length) noisePixels(pixels, width);
+      };
+    });
+  }
+
+  if (CONFIG.fonts) {
+    if (document.fonts && document.fonts.check) {
+      var check = document.fonts.check.bind(document.fonts);
+      var allowed = CONFIG.fonts.map(function (f) { return f.toLowerCase(); });
+      document.fonts.check = function (font) {
+        hit('fonts');
+        var families = String(font).replace(/^.*?\d+\w*\s+/, '').split(',');
+        var known = families.every(function (f) { return allowed.indexOf(f.trim().replace(/["']/g, '').toLowerCase()) >= 0; });
+        return known && check.apply(null, arguments);
+      };
+    }
+    if (window.queryLocalFonts) {
+      window.queryLocalFonts = function () {
+        hit('fonts');
+        return Promise.reject(new DOMException('Not allowed', 'NotAllowedError'));
+      };
+    }
+  }
+
+  if (CONFIG.hardware) {
+    define(Navigator.prototype, 'hardwareConcurrency', function () { hit('hardwareConcurrency'); return CONFIG.hardwareConcurrency; });
+    define(Navigator.prototype, 'deviceMemory', function () { hit('deviceMemory'); return CONFIG.deviceMemory; });
+  }
+
+  if (CONFIG.freeze) {
+    var plugins = Object.freeze(Object.create(PluginArray.prototype, { length: { value: 0 } }));
+    var mimeTypes = Object.freeze(Object.create(MimeTypeArray.prototype, { length: { value: 0 } }));
+    var languages = Object.freeze(navigator.languages.slice());
+    define(Navigator.prototype, 'plugins', function () { hit('navigator'); return plugins; });
+    define(Navigator.prototype, 'mimeTypes', function () { hit('navigator'); return mimeTypes; });
+    define(Navigator.prototype, 'doNotTrack', function () { hit('navigator'); return null; });
+    define(Navigator.prototype, 'webdriver', function () { hit('navigator'); return false; });
+    define(Navigator.prototype, 'languages', function () { hit('navigator'); return languages; });
+  }
+})"#;
+
+// Initialization script for new windows of the active profile.
+// `data_directory` is the window's own data directory, which marks its partition.
+pub fn initialization_script(app: &AppHandle, data_directory: Option<&Path>) -> String {
+    let file = load(app, &profiles::active_profile(app));
+    let settings = &file.settings;
+    let Some(seed) = file.seed.as_deref().filter(|_| settings.enabled) else {
+        return String::new();
+    };
+    let partition = data_directory
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| DEFAULT_PARTITION.to_string());
+    let persona = persona(seed, &partition);
+    let config = serde_json::json!({
+        "seed": persona.noise_seed,
+        "canvas": settings.canvas,
+        "audio": settings.audio,
+        "webgl": settings.webgl,
+        "fonts": if settings.fonts { Some(COMMON_FONTS) } else { None },
+        "hardware": settings.hardware,
+        "hardwareConcurrency": persona.hardware_concurrency,
+        "deviceMemory": persona.device_memory,
+        "freeze": settings.freeze_navigator,
+    });
+    format!("{}({});", FINGERPRINT_SCRIPT, config)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_fingerprint_settings(
+    app_handle: AppHandle,
+    profile_id: Option<String>,
+) -> Result<FingerprintSettings, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    Ok(load(&app_handle, &profile).settings)
+}
+
+// Applies to windows opened afterwards
+#[tauri::command]
+#[specta::specta]
+pub async fn set_fingerprint_settings(
+    app_handle: AppHandle,
+    settings: FingerprintSettings,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut file = load(&app_handle, &profile);
+    if file.seed.is_none() {
+        let mut bytes = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        file.seed = Some(hex::encode(bytes));
+    }
+    file.settings = settings;
+    storage::save(&app_handle, &profiles::profile_file(&profile, SETTINGS_FILE), &file)
+}
+
+// What a window is shown instead of the real values, and how often the page
+// has read each surface. A window opened before the mode was enabled
+// reports no reads.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_fingerprint_report(app_handle: AppHandle, window_id: String) -> Result<FingerprintReport, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    let file = load(&app_handle, &profiles::active_profile(&app_handle));
+    let partition = containers::window_container(&app_handle, &window_id).unwrap_or_else(|| DEFAULT_PARTITION.to_string());
+    let spoofed = match file.seed.as_deref().filter(|_| file.settings.enabled) {
+        Some(seed) => spoofed_values(&file.settings, &persona(seed, &partition)),
+        None => Vec::new(),
+    };
+    let reads = pagequery::run(
+        &app_handle,
+        &window,
+        "return window.__MADEASY_FINGERPRINT_READS__ ? window.__MADEASY_FINGERPRINT_READS__() : {};",
+        REPORT_TIMEOUT,
+    )
+    .await?;
+    Ok(FingerprintReport {
+        enabled: file.settings.enabled,
+        partition,
+        spoofed,
+        reads: serde_json::from_value(reads).map_err(|e| e.to_string())?,
+    })
+}
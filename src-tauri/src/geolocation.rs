@@ -0,0 +1,156 @@
+// Per-window geolocation override
+// The page's navigator.geolocation is wrapped so that, while an override is
+// set for its window, getCurrentPosition and watchPosition answer with the
+// configured coordinates without a permission prompt. The wrapper is injected
+// on every page load; clearing the override hands calls back to the real API.
+// Overrides last until the window closes.
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+const DEFAULT_ACCURACY: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+pub struct GeolocationOverride {
+    latitude: f64,
+    longitude: f64,
+    // Meters
+    accuracy: f64,
+}
+
+#[derive(Default)]
+pub struct GeolocationState {
+    overrides: Mutex<HashMap<String, GeolocationOverride>>,
+}
+
+const WRAPPER_SCRIPT: &str = r#"(function () {
+  if (window.__MADEASY_GEO__ || !navigator.geolocation) return;
+  var geo = navigator.geolocation, watches = {}, nextWatch = 1;
+  var original = {
+    get: geo.getCurrentPosition.bind(geo),
+    watch: geo.watchPosition.bind(geo),
+    clear: geo.clearWatch.bind(geo)
+  };
+  var state = window.__MADEASY_GEO__ = { position: null };
+  function position() {
+    var p = state.position;
+    return {
+      coords: { latitude: p.latitude, longitude: p.longitude, accuracy: p.accuracy, altitude: null, altitudeAccuracy: null, heading: null, speed: null },
+      timestamp: Date.now()
+    };
+  }
+  geo.getCurrentPosition = function (success, error, options) {
+    if (!state.position) return original.get(success, error, options);
+    setTimeout(function () { success(position()); }, 0);
+  };
+  geo.watchPosition = function (success, error, options) {
+    var id = nextWatch++;
+    watches[id] = { success: success, error: error, options: options, real: state.position ? null : original.watch(success, error, options) };
+    if (state.position) setTimeout(function () { success(position()); }, 0);
+    return id;
+  };
+  geo.clearWatch = function (id) {
+    var watch = watches[id];
+    if (watch && watch.real !== null) original.clear(watch.real);
+    delete watches[id];
+  };
+  // Move running watches between the override and the real API
+  state.set = function (p) {
+    state.position = p;
+    Object.keys(watches).forEach(function (id) {
+      var w = watches[id];
+      if (p) {
+        if (w.real !== null) { original.clear(w.real); w.real = null; }
+        w.success(position());
+      } else if (w.real === null) {
+        w.real = original.watch(w.success, w.error, w.options);
+      }
+    });
+  };
+})();"#;
+
+fn update_script(value: Option<GeolocationOverride>) -> String {
+    let position = match value {
+        Some(o) => format!(
+            "{{ latitude: {}, longitude: {}, accuracy: {} }}",
+            o.latitude, o.longitude, o.accuracy
+        ),
+        None => "null".to_string(),
+    };
+    format!("{}\nif (window.__MADEASY_GEO__) window.__MADEASY_GEO__.set({});", WRAPPER_SCRIPT, position)
+}
+
+pub fn on_page_load(window: &Window) {
+    let value = window
+        .state::<GeolocationState>()
+        .overrides
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .copied();
+    if value.is_some() {
+        let _ = window.eval(&update_script(value));
+    }
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<GeolocationState>().overrides.lock().unwrap().remove(label);
+}
+
+fn find_window(app: &AppHandle, window_id: &str) -> Result<Window, String> {
+    app.get_window(window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_geolocation_override(
+    app_handle: AppHandle,
+    state: tauri::State<'_, GeolocationState>,
+    window_id: String,
+    lat: f64,
+    lon: f64,
+    accuracy: Option<f64>,
+) -> Result<(), String> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err("Latitude must be within ±90 and longitude within ±180".to_string());
+    }
+    let accuracy = accuracy.unwrap_or(DEFAULT_ACCURACY);
+    if !(accuracy.is_finite() && accuracy > 0.0) {
+        return Err("Accuracy must be a positive number of meters".to_string());
+    }
+    let window = find_window(&app_handle, &window_id)?;
+    let value = GeolocationOverride {
+        latitude: lat,
+        longitude: lon,
+        accuracy,
+    };
+    state.overrides.lock().unwrap().insert(window_id, value);
+    window.eval(&update_script(Some(value))).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_geolocation_override(
+    app_handle: AppHandle,
+    state: tauri::State<'_, GeolocationState>,
+    window_id: String,
+) -> Result<(), String> {
+    let window = find_window(&app_handle, &window_id)?;
+    if state.overrides.lock().unwrap().remove(&window_id).is_some() {
+        window.eval(&update_script(None)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_geolocation_override(
+    state: tauri::State<'_, GeolocationState>,
+    window_id: String,
+) -> Result<Option<GeolocationOverride>, String> {
+    Ok(state.overrides.lock().unwrap().get(&window_id).copied())
+}
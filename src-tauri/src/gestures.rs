@@ -0,0 +1,230 @@
+// Right-button mouse gesture recognition
+// An injected listener reports the pointer trail; Rust turns it into a direction
+// sequence and runs the configured action
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::storage;
+
+const GESTURES_FILE: &str = "gestures.json";
+
+// Minimum travel in CSS pixels before a movement counts as a stroke
+const STROKE_THRESHOLD: f64 = 24.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GestureAction {
+    Back,
+    Forward,
+    Reload,
+    CloseWindow,
+    NewWindow,
+    ScrollTop,
+    ScrollBottom,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct GestureConfig {
+    enabled: bool,
+    show_trail: bool,
+    // Direction sequence (e.g. "L", "DR") to action
+    bindings: BTreeMap<String, GestureAction>,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        let bindings = [
+            ("L", GestureAction::Back),
+            ("R", GestureAction::Forward),
+            ("UD", GestureAction::Reload),
+            ("DR", GestureAction::CloseWindow),
+            ("DL", GestureAction::NewWindow),
+            ("U", GestureAction::ScrollTop),
+            ("D", GestureAction::ScrollBottom),
+        ]
+        .into_iter()
+        .map(|(gesture, action)| (gesture.to_string(), action))
+        .collect();
+
+        Self {
+            enabled: false,
+            show_trail: true,
+            bindings,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GestureState {
+    config: Mutex<GestureConfig>,
+}
+
+impl GestureState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, GESTURES_FILE)),
+        }
+    }
+}
+
+// Turn a pointer trail into a sequence of U/D/L/R strokes, collapsing repeats
+fn recognize(points: &[(f64, f64)]) -> String {
+    let mut gesture = String::new();
+    let Some(&(mut anchor_x, mut anchor_y)) = points.first() else {
+        return gesture;
+    };
+
+    for &(x, y) in &points[1..] {
+        let (dx, dy) = (x - anchor_x, y - anchor_y);
+        if dx.abs() < STROKE_THRESHOLD && dy.abs() < STROKE_THRESHOLD {
+            continue;
+        }
+        let direction = if dx.abs() > dy.abs() {
+            if dx > 0.0 { 'R' } else { 'L' }
+        } else if dy > 0.0 {
+            'D'
+        } else {
+            'U'
+        };
+        if !gesture.ends_with(direction) {
+            gesture.push(direction);
+        }
+        anchor_x = x;
+        anchor_y = y;
+    }
+
+    gesture
+}
+
+fn run_action(window: &Window, action: GestureAction) -> Result<(), String> {
+    match action {
+        GestureAction::Back => window.eval("history.back()"),
+        GestureAction::Forward => window.eval("history.forward()"),
+        GestureAction::Reload => window.eval("location.reload()"),
+        GestureAction::ScrollTop => window.eval("window.scrollTo({ top: 0, behavior: 'smooth' })"),
+        GestureAction::ScrollBottom => window.eval(
+            "window.scrollTo({ top: document.documentElement.scrollHeight, behavior: 'smooth' })",
+        ),
+        GestureAction::CloseWindow => window.close(),
+        GestureAction::NewWindow => {
+            tauri::async_runtime::spawn(crate::create_new_window(window.app_handle(), None, None));
+            Ok(())
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+// Listener script injected into every page; reports right-button drags to Rust
+fn listener_script(config: &GestureConfig) -> String {
+    format!(
+        r#"(function () {{
+  if (window.__MADEASY_GESTURES__) {{ window.__MADEASY_GESTURES__.configure({enabled}, {trail}); return; }}
+  var state = {{ enabled: {enabled}, trail: {trail}, points: null, canvas: null, suppress: false }};
+  function drawTrail() {{
+    if (!state.trail || !state.points || state.points.length < 2) return;
+    if (!state.canvas) {{
+      state.canvas = document.createElement('canvas');
+      state.canvas.width = window.innerWidth; state.canvas.height = window.innerHeight;
+      state.canvas.style.cssText = 'position:fixed;inset:0;pointer-events:none;z-index:2147483647';
+      document.documentElement.appendChild(state.canvas);
+    }}
+    var ctx = state.canvas.getContext('2d'), p = state.points;
+    ctx.strokeStyle = '#3b82f6'; ctx.lineWidth = 3;
+    ctx.beginPath(); ctx.moveTo(p[p.length - 2][0], p[p.length - 2][1]); ctx.lineTo(p[p.length - 1][0], p[p.length - 1][1]); ctx.stroke();
+  }}
+  function clearTrail() {{ if (state.canvas) {{ state.canvas.remove(); state.canvas = null; }} }}
+  window.addEventListener('mousedown', function (e) {{
+    if (state.enabled && e.button === 2) state.points = [[e.clientX, e.clientY]];
+  }}, true);
+  window.addEventListener('mousemove', function (e) {{
+    if (!state.points) return;
+    state.points.push([e.clientX, e.clientY]);
+    drawTrail();
+  }}, true);
+  window.addEventListener('mouseup', function (e) {{
+    if (!state.points || e.button !== 2) return;
+    var points = state.points; state.points = null; clearTrail();
+    // Jitter during a plain right-click isn't a stroke and keeps the context menu
+    var moved = points.some(function (p) {{
+      return Math.abs(p[0] - points[0][0]) >= {threshold} || Math.abs(p[1] - points[0][1]) >= {threshold};
+    }});
+    if (moved && window.__TAURI_INVOKE__) {{
+      state.suppress = true;
+      window.__TAURI_INVOKE__('gesture_performed', {{ points: points }});
+    }}
+  }}, true);
+  window.addEventListener('contextmenu', function (e) {{
+    if (state.suppress) {{ e.preventDefault(); e.stopImmediatePropagation(); state.suppress = false; }}
+  }}, true);
+  window.__MADEASY_GESTURES__ = {{ configure: function (enabled, trail) {{ state.enabled = enabled; state.trail = trail; }} }};
+}})();"#,
+        enabled = config.enabled,
+        trail = config.show_trail,
+        threshold = STROKE_THRESHOLD,
+    )
+}
+
+// Install the gesture listener into a freshly loaded page
+pub fn inject(window: &Window) {
+    let script = listener_script(&window.state::<GestureState>().config.lock().unwrap());
+    let _ = window.eval(&script);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_gesture_config(state: tauri::State<'_, GestureState>) -> Result<GestureConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_gesture_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, GestureState>,
+    config: GestureConfig,
+) -> Result<(), String> {
+    if let Some(gesture) = config
+        .bindings
+        .keys()
+        .find(|g| g.is_empty() || !g.chars().all(|c| "UDLR".contains(c)))
+    {
+        return Err(format!("Invalid gesture: {:?}", gesture));
+    }
+
+    storage::save(&app_handle, GESTURES_FILE, &config)?;
+
+    // Reconfigure listeners already running in open windows
+    let script = listener_script(&config);
+    for window in app_handle.windows().values() {
+        let _ = window.eval(&script);
+    }
+
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn gesture_performed(
+    window: Window,
+    state: tauri::State<'_, GestureState>,
+    points: Vec<(f64, f64)>,
+) -> Result<Option<GestureAction>, String> {
+    let action = {
+        let config = state.config.lock().unwrap();
+        if !config.enabled {
+            return Ok(None);
+        }
+        config.bindings.get(&recognize(&points)).copied()
+    };
+
+    if let Some(action) = action {
+        run_action(&window, action)?;
+    }
+    Ok(action)
+}
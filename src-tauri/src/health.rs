@@ -0,0 +1,149 @@
+// Backend health checks
+// Pings the server URL in the background and emits `backend-status` on every
+// change and failed retry. Retries back off exponentially while the server is
+// down. If it is unreachable at startup the main window shows a bundled
+// offline page (served over `offline://`) instead of a blank error screen,
+// and returns to where it was once the server answers.
+
+use serde::Serialize;
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::http::{Request, Response, ResponseBuilder};
+use tauri::{AppHandle, Manager};
+
+use crate::server;
+
+pub const OFFLINE_SCHEME: &str = "offline";
+const OFFLINE_PAGE: &str = include_str!("../assets/offline.html");
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const MIN_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BackendStatus {
+    online: bool,
+    url: String,
+    // Consecutive failed checks
+    failures: u32,
+    checked_at: i64,
+    next_check_ms: u64,
+}
+
+#[derive(Default)]
+pub struct HealthState {
+    status: Mutex<Option<BackendStatus>>,
+    // Where the main window was before the offline page replaced it
+    fallback_from: Mutex<Option<String>>,
+    // Wakes the loop early for `check_backend_now`
+    wake: tokio::sync::Notify,
+}
+
+fn offline_url() -> String {
+    // WebView2 only serves custom schemes through the https://<scheme>.localhost form
+    if cfg!(windows) {
+        format!("https://{}.localhost/", OFFLINE_SCHEME)
+    } else {
+        format!("{}://localhost/", OFFLINE_SCHEME)
+    }
+}
+
+pub fn serve_offline_page(_app: &AppHandle, _request: &Request) -> Result<Response, Box<dyn std::error::Error>> {
+    ResponseBuilder::new()
+        .mimetype("text/html")
+        .status(200)
+        .body(OFFLINE_PAGE.as_bytes().to_vec())
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    (MIN_RETRY * 2u32.saturating_pow(failures.saturating_sub(1))).min(MAX_RETRY)
+}
+
+fn show_fallback(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else { return };
+    let Ok(current) = window.url() else { return };
+    if current.scheme() == OFFLINE_SCHEME || current.as_str() == offline_url() {
+        return;
+    }
+    *app.state::<HealthState>().fallback_from.lock().unwrap() = Some(current.to_string());
+    let target = serde_json::to_string(&offline_url()).unwrap_or_default();
+    let _ = window.eval(&format!("window.location.replace({})", target));
+}
+
+fn leave_fallback(app: &AppHandle) {
+    let Some(previous) = app.state::<HealthState>().fallback_from.lock().unwrap().take() else {
+        return;
+    };
+    if let Some(window) = app.get_window("main") {
+        let target = serde_json::to_string(&previous).unwrap_or_default();
+        let _ = window.eval(&format!("window.location.replace({})", target));
+    }
+}
+
+// Returns whether the server answered and when to check next
+async fn check(app: &AppHandle) -> (bool, Duration) {
+    let url = server::status(app).url;
+    let online = server::responds(&url).await;
+
+    let state = app.state::<HealthState>();
+    let (status, changed) = {
+        let mut current = state.status.lock().unwrap();
+        let failures = match (&*current, online) {
+            (_, true) => 0,
+            (Some(previous), false) => previous.failures + 1,
+            (None, false) => 1,
+        };
+        let next = if online { CHECK_INTERVAL } else { retry_delay(failures) };
+        let changed = current.as_ref().map_or(true, |c| c.online != online);
+        let status = BackendStatus {
+            online,
+            url,
+            failures,
+            checked_at: chrono::Utc::now().timestamp(),
+            next_check_ms: next.as_millis() as u64,
+        };
+        *current = Some(status.clone());
+        (status, changed)
+    };
+
+    if changed || !online {
+        let _ = app.emit_all("backend-status", &status);
+    }
+    (online, Duration::from_millis(status.next_check_ms))
+}
+
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<HealthState>();
+        let mut ever_online = false;
+        loop {
+            let (online, delay) = check(&app).await;
+            if online {
+                ever_online = true;
+                leave_fallback(&app);
+            } else if !ever_online && !server::is_starting(&app) {
+                // Unreachable at startup and the sidecar (if any) has given up
+                show_fallback(&app);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = state.wake.notified() => {}
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn backend_status(state: tauri::State<'_, HealthState>) -> Result<Option<BackendStatus>, String> {
+    Ok(state.status.lock().unwrap().clone())
+}
+
+// Retry immediately instead of waiting for the next backoff step
+#[tauri::command]
+#[specta::specta]
+pub async fn check_backend_now(state: tauri::State<'_, HealthState>) -> Result<(), String> {
+    state.wake.notify_one();
+    Ok(())
+}
@@ -0,0 +1,261 @@
+// Window hibernation
+// Windows that stay hidden or unfocused for `idle_minutes` have their page
+// unloaded (navigated to about:blank) to free the renderer's memory. The URL
+// and scroll position are kept and the page comes back when the window is
+// focused again. Sites matching an exclusion pattern are never suspended.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Window};
+
+use crate::events::AppEvent;
+use crate::{matching, pagequery, storage};
+
+const CONFIG_FILE: &str = "hibernation.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+const BLANK_URL: &str = "about:blank";
+
+const SNAPSHOT_SCRIPT: &str = "return { title: document.title, x: window.scrollX, y: window.scrollY };";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct HibernationConfig {
+    enabled: bool,
+    idle_minutes: u64,
+    // Wildcard URL patterns, e.g. "https://mail.example.com/*"
+    exclusions: Vec<String>,
+}
+
+impl Default for HibernationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_minutes: 30,
+            exclusions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HibernatedWindow {
+    window_id: String,
+    url: String,
+    title: Option<String>,
+    scroll_x: f64,
+    scroll_y: f64,
+    hibernated_at: i64,
+}
+
+#[derive(Default)]
+pub struct HibernationState {
+    config: Mutex<HibernationConfig>,
+    inactive_since: Mutex<HashMap<String, Instant>>,
+    hibernated: Mutex<HashMap<String, HibernatedWindow>>,
+    // Windows navigating back to their page, waiting to have the scroll position restored
+    restoring: Mutex<HashMap<String, HibernatedWindow>>,
+}
+
+impl HibernationState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+fn is_excluded(config: &HibernationConfig, url: &str) -> bool {
+    config.exclusions.iter().any(|p| matching::wildcard_match(p, url))
+}
+
+fn page_url(window: &Window) -> Option<String> {
+    let url = window.url().ok()?.to_string();
+    (url.starts_with("http://") || url.starts_with("https://")).then_some(url)
+}
+
+pub async fn hibernate(app: &AppHandle, window: &Window) -> Result<(), String> {
+    let state = app.state::<HibernationState>();
+    let label = window.label().to_string();
+    if state.hibernated.lock().unwrap().contains_key(&label) {
+        return Ok(());
+    }
+    let url = page_url(window).ok_or("Only web pages can be hibernated")?;
+    if is_excluded(&state.config.lock().unwrap(), &url) {
+        return Err(format!("{} is excluded from hibernation", url));
+    }
+
+    // An unresponsive page still gets unloaded, just without its scroll position
+    let snapshot = pagequery::run(app, window, SNAPSHOT_SCRIPT, QUERY_TIMEOUT)
+        .await
+        .unwrap_or(Value::Null);
+    let entry = HibernatedWindow {
+        window_id: label.clone(),
+        url,
+        title: snapshot["title"].as_str().filter(|t| !t.is_empty()).map(str::to_string),
+        scroll_x: snapshot["x"].as_f64().unwrap_or(0.0),
+        scroll_y: snapshot["y"].as_f64().unwrap_or(0.0),
+        hibernated_at: chrono::Utc::now().timestamp_millis(),
+    };
+
+    window
+        .eval(&format!("location.replace({})", serde_json::to_string(&BLANK_URL).unwrap_or_default()))
+        .map_err(|e| e.to_string())?;
+    state.hibernated.lock().unwrap().insert(label, entry.clone());
+    let _ = app.emit_all("window-hibernated", entry);
+    Ok(())
+}
+
+fn restore(app: &AppHandle, window: &Window) -> Result<(), String> {
+    let state = app.state::<HibernationState>();
+    let Some(entry) = state.hibernated.lock().unwrap().remove(window.label()) else {
+        return Ok(());
+    };
+    window
+        .eval(&format!("location.replace({})", serde_json::to_string(&entry.url).unwrap_or_default()))
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit_all("window-restored", &entry);
+    state.restoring.lock().unwrap().insert(entry.window_id.clone(), entry);
+    Ok(())
+}
+
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        let state = app.state::<HibernationState>();
+        let config = state.config.lock().unwrap().clone();
+        if !config.enabled {
+            continue;
+        }
+        let idle = Duration::from_secs(config.idle_minutes.max(1) * 60);
+        let now = Instant::now();
+        let windows = app.windows();
+
+        let mut due = Vec::new();
+        {
+            let mut inactive = state.inactive_since.lock().unwrap();
+            let hibernated = state.hibernated.lock().unwrap();
+            inactive.retain(|label, _| windows.contains_key(label));
+            for (label, window) in &windows {
+                // The main window hosts the app shell
+                if label == "main" || hibernated.contains_key(label) {
+                    continue;
+                }
+                if window.is_focused().unwrap_or(false) {
+                    inactive.remove(label);
+                    continue;
+                }
+                let since = *inactive.entry(label.clone()).or_insert(now);
+                let candidate = page_url(window).map_or(false, |url| !is_excluded(&config, &url));
+                if candidate && now.duration_since(since) >= idle {
+                    due.push(window.clone());
+                }
+            }
+        }
+
+        for window in due {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = hibernate(&app, &window).await {
+                    eprintln!("Failed to hibernate {}: {}", window.label(), e);
+                }
+            });
+        }
+    });
+}
+
+pub fn on_focus_changed(window: &Window, focused: bool) {
+    let app = window.app_handle();
+    let state = app.state::<HibernationState>();
+    if !focused {
+        state
+            .inactive_since
+            .lock()
+            .unwrap()
+            .insert(window.label().to_string(), Instant::now());
+        return;
+    }
+    state.inactive_since.lock().unwrap().remove(window.label());
+    if let Err(e) = restore(&app, window) {
+        eprintln!("Failed to restore {}: {}", window.label(), e);
+    }
+}
+
+// Subscribed to the event bus; puts the scroll position back once a restored page has loaded
+pub fn on_event(app: &AppHandle, event: &AppEvent) {
+    let AppEvent::NavigationFinished { window_id, url } = event else { return };
+    if url == BLANK_URL {
+        return;
+    }
+    let Some(window) = app.get_window(window_id) else { return };
+    let state = app.state::<HibernationState>();
+    let Some(entry) = state.restoring.lock().unwrap().remove(window.label()) else {
+        return;
+    };
+    if entry.scroll_x > 0.0 || entry.scroll_y > 0.0 {
+        let _ = window.eval(&format!("window.scrollTo({}, {})", entry.scroll_x, entry.scroll_y));
+    }
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    let state = app.state::<HibernationState>();
+    state.inactive_since.lock().unwrap().remove(label);
+    state.hibernated.lock().unwrap().remove(label);
+    state.restoring.lock().unwrap().remove(label);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_hibernation_config(state: tauri::State<'_, HibernationState>) -> Result<HibernationConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_hibernation_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, HibernationState>,
+    config: HibernationConfig,
+) -> Result<(), String> {
+    if config.idle_minutes == 0 {
+        return Err("idle_minutes must be at least 1".to_string());
+    }
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_hibernated_windows(state: tauri::State<'_, HibernationState>) -> Result<Vec<HibernatedWindow>, String> {
+    let mut windows: Vec<HibernatedWindow> = state.hibernated.lock().unwrap().values().cloned().collect();
+    windows.sort_by_key(|w| w.hibernated_at);
+    Ok(windows)
+}
+
+// Suspend a window now, regardless of how long it has been idle
+#[tauri::command]
+#[specta::specta]
+pub async fn hibernate_window(app_handle: AppHandle, window_id: String) -> Result<(), String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    if window_id == "main" {
+        return Err("The main window cannot be hibernated".to_string());
+    }
+    hibernate(&app_handle, &window).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_hibernated_window(app_handle: AppHandle, window_id: String) -> Result<(), String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    restore(&app_handle, &window)
+}
@@ -0,0 +1,129 @@
+// Browsing history
+// Visits are reported by a script injected on page load and stored in SQLite.
+// The page only supplies its title; the URL is the window's own.
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Window};
+
+use crate::db::Database;
+use crate::jumplist;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    url TEXT PRIMARY KEY,
+    title TEXT NOT NULL DEFAULT '',
+    visit_count INTEGER NOT NULL DEFAULT 0,
+    last_visit INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS history_last_visit ON history (last_visit DESC);
+";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HistoryEntry {
+    pub url: String,
+    pub title: String,
+    pub visit_count: u32,
+    pub last_visit: i64,
+}
+
+const REPORT_SCRIPT: &str = r#"(function () {
+  if (window.__TAURI_INVOKE__ && /^https?:/.test(location.protocol)) {
+    window.__TAURI_INVOKE__('record_history_visit', { title: document.title });
+  }
+})();"#;
+
+// Ask a freshly loaded page to report itself to the history
+pub fn inject(window: &Window) {
+    let _ = window.eval(REPORT_SCRIPT);
+}
+
+pub fn recent(db: &Database, limit: u32) -> Result<Vec<HistoryEntry>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT url, title, visit_count, last_visit FROM history
+             ORDER BY last_visit DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(HistoryEntry {
+                url: row.get(0)?,
+                title: row.get(1)?,
+                visit_count: row.get(2)?,
+                last_visit: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+// Frecency: visit count weighted by how recently the page was last visited
+const FRECENCY: &str = "visit_count * CASE
+        WHEN ?1 - last_visit < 4 * 86400 THEN 100
+        WHEN ?1 - last_visit < 14 * 86400 THEN 70
+        WHEN ?1 - last_visit < 31 * 86400 THEN 50
+        WHEN ?1 - last_visit < 90 * 86400 THEN 30
+        ELSE 10
+    END";
+
+// Most relevant entries by frecency, optionally restricted to URLs or titles
+// containing `filter`
+pub fn frecency_ranked(db: &Database, filter: Option<&str>, limit: u32) -> Result<Vec<HistoryEntry>, String> {
+    let now = chrono::Utc::now().timestamp();
+    let pattern = filter.map(|f| format!("%{}%", f.replace('%', "\\%").replace('_', "\\_")));
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT url, title, visit_count, last_visit FROM history
+             WHERE ?2 IS NULL OR url LIKE ?2 ESCAPE '\\' OR title LIKE ?2 ESCAPE '\\'
+             ORDER BY {} DESC LIMIT ?3",
+            FRECENCY
+        ))?;
+        let rows = stmt.query_map(params![now, pattern, limit], |row| {
+            Ok(HistoryEntry {
+                url: row.get(0)?,
+                title: row.get(1)?,
+                visit_count: row.get(2)?,
+                last_visit: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn record_history_visit(
+    app_handle: AppHandle,
+    window: Window,
+    db: tauri::State<'_, Database>,
+    title: String,
+) -> Result<(), String> {
+    let url = window.url().map_err(|e| e.to_string())?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Ok(());
+    }
+    let url = url.to_string();
+    let now = chrono::Utc::now().timestamp();
+    db.with(|conn| {
+        conn.execute(
+            "INSERT INTO history (url, title, visit_count, last_visit) VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT(url) DO UPDATE SET
+                title = excluded.title,
+                visit_count = visit_count + 1,
+                last_visit = excluded.last_visit",
+            params![url, title, now],
+        )
+    })?;
+
+    jumplist::refresh(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_recent_history(
+    db: tauri::State<'_, Database>,
+    limit: Option<u32>,
+) -> Result<Vec<HistoryEntry>, String> {
+    recent(&db, limit.unwrap_or(50))
+}
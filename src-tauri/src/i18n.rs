@@ -0,0 +1,198 @@
+// Localization of backend UI text
+// Menu items, tray entries and notifications are looked up by message id in
+// the Fluent resources under locales/, bundled at compile time. The locale
+// follows the system unless the user picks one; missing messages fall back to
+// English. Changing the locale rebuilds the tray and retitles window menus
+// (submenu titles of already open windows follow on the next start).
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use unic_langid::LanguageIdentifier;
+
+use crate::{devtools, jumplist, share, storage, tray};
+
+const LOCALE_FILE: &str = "locale.json";
+pub const DEFAULT_LOCALE: &str = "en";
+
+const RESOURCES: [(&str, &str); 5] = [
+    ("en", include_str!("../locales/en.ftl")),
+    ("nb", include_str!("../locales/nb.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+    ("fr", include_str!("../locales/fr.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+// Window menu items that carry translated titles, by menu item id
+const MENU_ITEMS: [(&str, &str); 10] = [
+    ("new_window", "menu-new-window"),
+    ("reopen_closed_window", "menu-reopen-closed-window"),
+    (share::MENU_ITEM_ID, "menu-share-page"),
+    ("settings", "menu-settings"),
+    ("close", "menu-close"),
+    ("quit", "menu-quit"),
+    (devtools::MENU_ITEM_ID, "menu-devtools"),
+    ("next_window", "menu-next-window"),
+    ("previous_window", "menu-previous-window"),
+    ("about", "menu-about"),
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct LocaleConfig {
+    // None follows the system locale
+    locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LocaleInfo {
+    locale: String,
+    preference: Option<String>,
+    available: Vec<String>,
+}
+
+pub struct I18nState {
+    config: Mutex<LocaleConfig>,
+    locale: Mutex<String>,
+}
+
+impl I18nState {
+    pub fn load(app: &AppHandle) -> Self {
+        let config: LocaleConfig = storage::load(app, LOCALE_FILE);
+        Self {
+            locale: Mutex::new(resolve(config.locale.as_deref())),
+            config: Mutex::new(config),
+        }
+    }
+}
+
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        RESOURCES
+            .iter()
+            .map(|&(code, source)| {
+                let langid: LanguageIdentifier = code.parse().expect("invalid bundled locale");
+                let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+                // Bidi isolation marks would show up literally in native menus
+                bundle.set_use_isolating(false);
+                let resource = FluentResource::try_new(source.to_string()).expect("invalid bundled Fluent resource");
+                bundle.add_resource(resource).expect("duplicate Fluent message");
+                (code, bundle)
+            })
+            .collect()
+    })
+}
+
+// Map a BCP 47 tag such as "nb-NO" or "de_DE.UTF-8" to a bundled locale
+fn supported(tag: &str) -> Option<&'static str> {
+    let language = tag.split(['-', '_', '.']).next()?.to_lowercase();
+    let language = match language.as_str() {
+        "no" | "nn" => "nb",
+        other => other,
+    };
+    RESOURCES.iter().map(|(code, _)| *code).find(|code| *code == language)
+}
+
+fn resolve(preference: Option<&str>) -> String {
+    preference
+        .and_then(supported)
+        .or_else(|| sys_locale::get_locale().as_deref().and_then(supported))
+        .unwrap_or(DEFAULT_LOCALE)
+        .to_string()
+}
+
+// Locale for UI built before the app is set up (the application menu)
+pub fn initial_locale(config: &tauri::Config) -> String {
+    let preference = tauri::api::path::app_data_dir(config)
+        .and_then(|dir| std::fs::read(dir.join(LOCALE_FILE)).ok())
+        .and_then(|bytes| serde_json::from_slice::<LocaleConfig>(&bytes).ok())
+        .and_then(|config| config.locale);
+    resolve(preference.as_deref())
+}
+
+pub fn current(app: &AppHandle) -> String {
+    app.state::<I18nState>().locale.lock().unwrap().clone()
+}
+
+fn format(locale: &str, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = bundles().get(locale)?;
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        eprintln!("Failed to format {} ({}): {:?}", id, locale, errors);
+    }
+    Some(text.into_owned())
+}
+
+pub fn lookup(locale: &str, id: &str, args: &[(&str, FluentValue)]) -> String {
+    let args = (!args.is_empty()).then(|| {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        fluent_args
+    });
+    format(locale, id, args.as_ref())
+        .or_else(|| format(DEFAULT_LOCALE, id, args.as_ref()))
+        .unwrap_or_else(|| id.to_string())
+}
+
+pub fn text(app: &AppHandle, id: &str) -> String {
+    lookup(&current(app), id, &[])
+}
+
+pub fn text_with(app: &AppHandle, id: &str, args: &[(&str, FluentValue)]) -> String {
+    lookup(&current(app), id, args)
+}
+
+fn apply(app: &AppHandle) {
+    if let Err(e) = tray::refresh(app) {
+        eprintln!("Failed to rebuild tray menu: {}", e);
+    }
+    jumplist::refresh(app);
+    for window in app.windows().values() {
+        let menu = window.menu_handle();
+        for (item_id, message_id) in MENU_ITEMS {
+            // Windows without the application menu have no such items
+            let _ = menu.get_item(item_id).set_title(text(app, message_id));
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_locale(state: tauri::State<'_, I18nState>) -> Result<LocaleInfo, String> {
+    Ok(LocaleInfo {
+        locale: state.locale.lock().unwrap().clone(),
+        preference: state.config.lock().unwrap().locale.clone(),
+        available: RESOURCES.iter().map(|(code, _)| code.to_string()).collect(),
+    })
+}
+
+// `None` returns to following the system locale
+#[tauri::command]
+#[specta::specta]
+pub async fn set_locale(
+    app_handle: AppHandle,
+    state: tauri::State<'_, I18nState>,
+    locale: Option<String>,
+) -> Result<String, String> {
+    if let Some(tag) = &locale {
+        supported(tag).ok_or_else(|| format!("Unsupported locale: {}", tag))?;
+    }
+    let config = LocaleConfig { locale };
+    storage::save(&app_handle, LOCALE_FILE, &config)?;
+    let resolved = resolve(config.locale.as_deref());
+    *state.config.lock().unwrap() = config;
+    *state.locale.lock().unwrap() = resolved.clone();
+
+    apply(&app_handle);
+    let _ = app_handle.emit_all("locale-changed", &resolved);
+    Ok(resolved)
+}
@@ -0,0 +1,357 @@
+// Idle detection
+// The OS reports how long the user has been away from keyboard and mouse.
+// After the configured number of idle minutes the app can lock (browser
+// windows are hidden and the main window shows the lock screen until
+// `unlock_app`), pause background polling such as feed refreshes, and
+// hibernate hidden windows. Each action fires once per idle period; polling
+// resumes as soon as the user is back, the lock stays until unlocked.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::{hibernation, kiosk, secrets, storage, tasks};
+
+const IDLE_FILE: &str = "idle.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const WRONG_PIN_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct IdleConfig {
+    // Each action is off when unset
+    lock_after_minutes: Option<u64>,
+    pause_polling_after_minutes: Option<u64>,
+    suspend_hidden_after_minutes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct IdleFile {
+    #[serde(flatten)]
+    config: IdleConfig,
+    // Argon2 PHC string; without a PIN unlocking only needs the user to come back
+    lock_pin_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct IdleStatus {
+    idle_secs: u64,
+    locked: bool,
+    polling_paused: bool,
+    lock_pin_set: bool,
+}
+
+// What has been done in the current idle period
+#[derive(Debug, Default)]
+struct Applied {
+    polling_paused: bool,
+    hidden_suspended: bool,
+}
+
+#[derive(Default)]
+pub struct IdleState {
+    file: Mutex<IdleFile>,
+    applied: Mutex<Applied>,
+    // Windows hidden by the lock, shown again on unlock
+    locked: Mutex<Option<Vec<String>>>,
+}
+
+impl IdleState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            file: Mutex::new(storage::load(app, IDLE_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+// Seconds since the last keyboard or mouse input; 0 if the OS can't tell
+pub fn idle_secs() -> u64 {
+    platform::idle_time().map_or(0, |idle| idle.as_secs())
+}
+
+pub fn polling_paused(app: &AppHandle) -> bool {
+    app.state::<IdleState>().applied.lock().unwrap().polling_paused
+}
+
+pub fn locked(app: &AppHandle) -> bool {
+    app.state::<IdleState>().locked.lock().unwrap().is_some()
+}
+
+pub fn ensure_unlocked(app: &AppHandle) -> Result<(), String> {
+    if locked(app) {
+        Err("The app is locked".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn lock(app: &AppHandle) {
+    // Kiosk mode has its own exit PIN and must stay on screen
+    if kiosk::active(app) {
+        return;
+    }
+    let state = app.state::<IdleState>();
+    let mut locked = state.locked.lock().unwrap();
+    if locked.is_some() {
+        return;
+    }
+    let mut hidden = Vec::new();
+    for (label, window) in app.windows() {
+        if label != "main" && window.is_visible().unwrap_or(false) && window.hide().is_ok() {
+            hidden.push(label);
+        }
+    }
+    *locked = Some(hidden);
+    if let Some(main) = app.get_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+    }
+    let _ = app.emit_all("app-locked", ());
+}
+
+fn suspend_hidden(app: &AppHandle) {
+    for (label, window) in app.windows() {
+        if label == "main" || window.is_visible().unwrap_or(true) {
+            continue;
+        }
+        let is_page = window
+            .url()
+            .map_or(false, |url| matches!(url.scheme(), "http" | "https"));
+        if !is_page {
+            continue;
+        }
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = hibernation::hibernate(&app, &window).await {
+                eprintln!("Failed to hibernate {}: {}", window.label(), e);
+            }
+        });
+    }
+}
+
+fn check(app: &AppHandle, idle: Duration) {
+    let state = app.state::<IdleState>();
+    let config = state.file.lock().unwrap().config.clone();
+    let reached = |minutes: Option<u64>| minutes.map_or(false, |m| idle >= Duration::from_secs(m.max(1) * 60));
+
+    let mut applied = state.applied.lock().unwrap();
+    // Input within the last poll means the user is back
+    if idle < POLL_INTERVAL {
+        if applied.polling_paused {
+            let _ = app.emit_all("idle-changed", false);
+        }
+        *applied = Applied::default();
+        return;
+    }
+    if reached(config.pause_polling_after_minutes) && !applied.polling_paused {
+        applied.polling_paused = true;
+        let _ = app.emit_all("idle-changed", true);
+    }
+    if reached(config.suspend_hidden_after_minutes) && !applied.hidden_suspended {
+        applied.hidden_suspended = true;
+        suspend_hidden(app);
+    }
+    drop(applied);
+    if reached(config.lock_after_minutes) {
+        lock(app);
+    }
+}
+
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if let Some(idle) = platform::idle_time() {
+            check(&app, idle);
+        }
+    });
+}
+
+async fn verify_pin(hash: String, pin: String) -> Result<bool, String> {
+    tasks::blocking(move || Ok(secrets::pin_matches(&hash, &pin))).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_idle_time(app_handle: AppHandle, state: tauri::State<'_, IdleState>) -> Result<IdleStatus, String> {
+    Ok(IdleStatus {
+        idle_secs: idle_secs(),
+        locked: locked(&app_handle),
+        polling_paused: polling_paused(&app_handle),
+        lock_pin_set: state.file.lock().unwrap().lock_pin_hash.is_some(),
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_idle_config(state: tauri::State<'_, IdleState>) -> Result<IdleConfig, String> {
+    Ok(state.file.lock().unwrap().config.clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_idle_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, IdleState>,
+    config: IdleConfig,
+) -> Result<(), String> {
+    let mut file = state.file.lock().unwrap();
+    file.config = config;
+    storage::save(&app_handle, IDLE_FILE, &*file)
+}
+
+// No `pin` removes the PIN; changing or removing an existing one requires it
+#[tauri::command]
+#[specta::specta]
+pub async fn set_app_lock_pin(
+    app_handle: AppHandle,
+    state: tauri::State<'_, IdleState>,
+    pin: Option<String>,
+    current_pin: Option<String>,
+) -> Result<(), String> {
+    ensure_unlocked(&app_handle)?;
+    if let Some(pin) = &pin {
+        secrets::validate_pin(pin)?;
+    }
+    let existing = state.file.lock().unwrap().lock_pin_hash.clone();
+    if let Some(hash) = existing {
+        if !verify_pin(hash, current_pin.unwrap_or_default()).await? {
+            return Err("Current PIN is incorrect".to_string());
+        }
+    }
+    let hash = match pin {
+        Some(pin) => Some(tasks::blocking(move || secrets::hash_pin(&pin)).await?),
+        None => None,
+    };
+    let mut file = state.file.lock().unwrap();
+    file.lock_pin_hash = hash;
+    storage::save(&app_handle, IDLE_FILE, &*file)?;
+    audit::record(
+        &app_handle,
+        AuditCategory::Credential,
+        "app_lock.pin_set",
+        json!({ "enabled": file.lock_pin_hash.is_some() }),
+    );
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn lock_app(app_handle: AppHandle) -> Result<(), String> {
+    kiosk::ensure_inactive(&app_handle)?;
+    lock(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_app(
+    app_handle: AppHandle,
+    state: tauri::State<'_, IdleState>,
+    pin: Option<String>,
+) -> Result<(), String> {
+    if !locked(&app_handle) {
+        return Ok(());
+    }
+    let hash = state.file.lock().unwrap().lock_pin_hash.clone();
+    if let Some(hash) = hash {
+        if !verify_pin(hash, pin.unwrap_or_default()).await? {
+            audit::record(&app_handle, AuditCategory::Credential, "app_lock.unlock_denied", json!({}));
+            tokio::time::sleep(WRONG_PIN_DELAY).await;
+            return Err("Incorrect PIN".to_string());
+        }
+    }
+    let hidden = state.locked.lock().unwrap().take().unwrap_or_default();
+    for label in hidden {
+        if let Some(window) = app_handle.get_window(&label) {
+            let _ = window.show();
+        }
+    }
+    let _ = app_handle.emit_all("app-unlocked", ());
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::time::Duration;
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub fn idle_time() -> Option<Duration> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return None;
+        }
+        // Both tick counts wrap after 49 days
+        let millis = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+        Some(Duration::from_millis(u64::from(millis)))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+    use std::time::Duration;
+
+    // IOHIDSystem keeps the time since the last input event, in nanoseconds
+    pub fn idle_time() -> Option<Duration> {
+        let output = Command::new("ioreg").args(["-c", "IOHIDSystem", "-d", "4"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let nanos = text
+            .lines()
+            .find(|line| line.contains("\"HIDIdleTime\""))?
+            .rsplit('=')
+            .next()?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(Duration::from_nanos(nanos))
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+    use std::time::Duration;
+
+    // GNOME's idle monitor, then xprintidle for other X11 desktops
+    pub fn idle_time() -> Option<Duration> {
+        let mutter = Command::new("gdbus")
+            .args([
+                "call",
+                "--session",
+                "--dest",
+                "org.gnome.Mutter.IdleMonitor",
+                "--object-path",
+                "/org/gnome/Mutter/IdleMonitor/Core",
+                "--method",
+                "org.gnome.Mutter.IdleMonitor.GetIdletime",
+            ])
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .and_then(|out| {
+                // "(uint64 12345,)"
+                let text = String::from_utf8_lossy(&out.stdout).to_string();
+                let value = text.split_whitespace().nth(1)?;
+                value.trim_end_matches(|c: char| !c.is_ascii_digit()).parse::<u64>().ok()
+            });
+        let millis = mutter.or_else(|| {
+            Command::new("xprintidle")
+                .output()
+                .ok()
+                .filter(|out| out.status.success())
+                .and_then(|out| String::from_utf8_lossy(&out.stdout).trim().parse::<u64>().ok())
+        })?;
+        Some(Duration::from_millis(millis))
+    }
+}
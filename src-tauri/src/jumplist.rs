@@ -0,0 +1,273 @@
+// Windows taskbar jump list and macOS dock menu
+// Shows recent URLs from history, pinned workflows and a "New private window" task.
+// Entries relaunch the app with arguments handled by the single-instance handler.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::launch::LaunchAction;
+use crate::{history, i18n, storage};
+
+const PINNED_FILE: &str = "pinned-workflows.json";
+const MAX_RECENT: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PinnedWorkflow {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpItem {
+    title: String,
+    action: LaunchAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpCategory {
+    name: String,
+    items: Vec<JumpItem>,
+}
+
+#[derive(Default)]
+pub struct JumpListState {
+    pinned: Mutex<Vec<PinnedWorkflow>>,
+    // Last applied list, so history updates only touch the OS when something changed
+    applied: Mutex<Vec<JumpCategory>>,
+}
+
+impl JumpListState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            pinned: Mutex::new(storage::load(app, PINNED_FILE)),
+            applied: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+fn build(app: &AppHandle) -> Vec<JumpCategory> {
+    let recent = history::recent(&app.state::<Database>(), MAX_RECENT).unwrap_or_default();
+    let pinned = app.state::<JumpListState>().pinned.lock().unwrap().clone();
+
+    let mut categories = vec![JumpCategory {
+        name: i18n::text(app, "jumplist-tasks"),
+        items: vec![JumpItem {
+            title: i18n::text(app, "jumplist-new-private-window"),
+            action: LaunchAction::NewPrivateWindow,
+        }],
+    }];
+
+    if !pinned.is_empty() {
+        categories.push(JumpCategory {
+            name: i18n::text(app, "jumplist-pinned-workflows"),
+            items: pinned
+                .into_iter()
+                .map(|w| JumpItem {
+                    title: w.name,
+                    action: LaunchAction::RunWorkflow(w.id),
+                })
+                .collect(),
+        });
+    }
+
+    if !recent.is_empty() {
+        categories.push(JumpCategory {
+            name: i18n::text(app, "jumplist-recent"),
+            items: recent
+                .into_iter()
+                .map(|entry| JumpItem {
+                    title: if entry.title.is_empty() { entry.url.clone() } else { entry.title },
+                    action: LaunchAction::OpenUrl(entry.url),
+                })
+                .collect(),
+        });
+    }
+
+    categories
+}
+
+// Rebuild the jump list / dock menu if its contents changed
+pub fn refresh(app: &AppHandle) {
+    let categories = build(app);
+    {
+        let mut applied = app.state::<JumpListState>().applied.lock().unwrap();
+        if *applied == categories {
+            return;
+        }
+        *applied = categories.clone();
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = platform::apply(&app, &categories) {
+            eprintln!("Failed to update jump list: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_pinned_workflows(
+    app_handle: AppHandle,
+    state: tauri::State<'_, JumpListState>,
+    workflows: Vec<PinnedWorkflow>,
+) -> Result<(), String> {
+    storage::save(&app_handle, PINNED_FILE, &workflows)?;
+    *state.pinned.lock().unwrap() = workflows;
+    refresh(&app_handle);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::JumpCategory;
+    use tauri::AppHandle;
+    use windows::core::{Interface, HSTRING, PROPVARIANT};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IObjectArray,
+        IObjectCollection, IShellLinkW, ShellLink,
+    };
+
+    pub fn apply(_app: &AppHandle, categories: &[JumpCategory]) -> Result<(), String> {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+
+        unsafe {
+            // Already-initialized threads return S_FALSE or RPC_E_CHANGED_MODE; both are fine
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+            let list: ICustomDestinationList =
+                CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)
+                    .map_err(|e| e.to_string())?;
+            let mut max_slots = 0u32;
+            let _removed: IObjectArray = list.BeginList(&mut max_slots).map_err(|e| e.to_string())?;
+
+            for (index, category) in categories.iter().enumerate() {
+                let collection: IObjectCollection =
+                    CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)
+                        .map_err(|e| e.to_string())?;
+
+                for item in &category.items {
+                    let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+                        .map_err(|e| e.to_string())?;
+                    let args = item
+                        .action
+                        .to_args()
+                        .iter()
+                        .map(|a| format!("\"{}\"", a.replace('"', "")))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    link.SetPath(&HSTRING::from(exe.as_os_str())).map_err(|e| e.to_string())?;
+                    link.SetArguments(&HSTRING::from(args)).map_err(|e| e.to_string())?;
+
+                    let store: IPropertyStore = link.cast().map_err(|e| e.to_string())?;
+                    store
+                        .SetValue(&PKEY_Title, &PROPVARIANT::from(item.title.as_str()))
+                        .map_err(|e| e.to_string())?;
+                    store.Commit().map_err(|e| e.to_string())?;
+
+                    collection.AddObject(&link).map_err(|e| e.to_string())?;
+                }
+
+                let array: IObjectArray = collection.cast().map_err(|e| e.to_string())?;
+                // The first category holds the fixed tasks
+                if index == 0 {
+                    list.AddUserTasks(&array).map_err(|e| e.to_string())?;
+                } else {
+                    list.AppendCategory(&HSTRING::from(category.name.as_str()), &array)
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+
+            list.CommitList().map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{JumpCategory, JumpItem};
+    use cocoa::appkit::NSApp;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{class_addMethod, Class, Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::{Mutex, Once, OnceLock};
+    use tauri::AppHandle;
+
+    static ITEMS: Mutex<Vec<JumpItem>> = Mutex::new(Vec::new());
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+    static INSTALL: Once = Once::new();
+
+    // NSApplicationDelegate applicationDockMenu: - rebuilt from ITEMS on every open
+    extern "C" fn dock_menu(_this: &Object, _sel: Sel, _sender: id) -> id {
+        unsafe {
+            let menu: id = msg_send![class!(NSMenu), new];
+            let target: id = msg_send![class!(MadEasyDockTarget), new];
+            for (tag, item) in ITEMS.lock().unwrap().iter().enumerate() {
+                let title = NSString::alloc(nil).init_str(&item.title);
+                let key = NSString::alloc(nil).init_str("");
+                let entry: id = msg_send![class!(NSMenuItem), alloc];
+                let entry: id = msg_send![entry, initWithTitle: title action: sel!(dockItemClicked:) keyEquivalent: key];
+                let _: () = msg_send![entry, setTag: tag as isize];
+                let _: () = msg_send![entry, setTarget: target];
+                let _: () = msg_send![menu, addItem: entry];
+            }
+            let _: id = msg_send![menu, autorelease];
+            menu
+        }
+    }
+
+    extern "C" fn item_clicked(_this: &Object, _sel: Sel, sender: id) {
+        let tag: isize = unsafe { msg_send![sender, tag] };
+        let item = ITEMS.lock().unwrap().get(tag as usize).cloned();
+        if let (Some(item), Some(app)) = (item, APP.get()) {
+            crate::launch::dispatch(app, item.action);
+        }
+    }
+
+    unsafe fn install() {
+        let mut decl = ClassDecl::new("MadEasyDockTarget", class!(NSObject)).unwrap();
+        decl.add_method(
+            sel!(dockItemClicked:),
+            item_clicked as extern "C" fn(&Object, Sel, id),
+        );
+        decl.register();
+
+        // Add the dock menu hook to the delegate class installed by the windowing layer
+        let delegate: id = msg_send![NSApp(), delegate];
+        let delegate_class: *const Class = msg_send![delegate, class];
+        class_addMethod(
+            delegate_class as *mut Class,
+            sel!(applicationDockMenu:),
+            std::mem::transmute(dock_menu as extern "C" fn(&Object, Sel, id) -> id),
+            b"@@:@\0".as_ptr() as *const _,
+        );
+    }
+
+    pub fn apply(app: &AppHandle, categories: &[JumpCategory]) -> Result<(), String> {
+        let _ = APP.set(app.clone());
+        // Dock menus are flat; categories are concatenated
+        *ITEMS.lock().unwrap() = categories.iter().flat_map(|c| c.items.clone()).collect();
+        app.run_on_main_thread(|| INSTALL.call_once(|| unsafe { install() }))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::JumpCategory;
+    use tauri::AppHandle;
+
+    pub fn apply(_app: &AppHandle, _categories: &[JumpCategory]) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+// Kiosk mode for shared terminals
+// One fullscreen window without menu or devtools shows the kiosk URL; other
+// windows are hidden, the tray menu is emptied, and new windows, external
+// URLs and downloads are refused. Navigation is limited to the kiosk URL's
+// origin plus the configured allowed origins (enforced by the webview where
+// the engine allows it, and by a page script everywhere). Leaving kiosk mode
+// takes the admin PIN, which must be set before kiosk mode can start.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window, WindowBuilder, WindowUrl};
+
+use crate::audit::{self, AuditCategory};
+use crate::{secrets, storage, tasks, tray};
+
+const KIOSK_FILE: &str = "kiosk.json";
+pub const WINDOW_LABEL: &str = "kiosk";
+// Slows down guessing at the exit prompt
+const WRONG_PIN_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct KioskConfig {
+    // Origins such as "https://example.com", besides the kiosk URL's own
+    allowed_origins: Vec<String>,
+    // Argon2 PHC string of the admin PIN
+    pin_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct KioskStatus {
+    active: bool,
+    url: Option<String>,
+    allowed_origins: Vec<String>,
+    pin_set: bool,
+}
+
+#[derive(Debug, Clone)]
+struct KioskSession {
+    url: url::Url,
+    origins: Vec<String>,
+}
+
+impl KioskSession {
+    fn allows(&self, target: &str) -> bool {
+        if target == "about:blank" {
+            return true;
+        }
+        match url::Url::parse(target) {
+            Ok(url) => {
+                let origin = url.origin().ascii_serialization();
+                self.origins.iter().any(|allowed| *allowed == origin)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct KioskState {
+    config: Mutex<KioskConfig>,
+    session: Mutex<Option<KioskSession>>,
+    // Held through each exit attempt, wrong-PIN delay included, so parallel
+    // calls can't skip the delay
+    exit_attempt: tokio::sync::Mutex<()>,
+}
+
+impl KioskState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, KIOSK_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn active(app: &AppHandle) -> bool {
+    app.try_state::<KioskState>()
+        .map_or(false, |state| state.session.lock().unwrap().is_some())
+}
+
+// For commands that would open windows or leave the kiosk
+pub fn ensure_inactive(app: &AppHandle) -> Result<(), String> {
+    if active(app) {
+        Err("Not available in kiosk mode".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn session(app: &AppHandle) -> Option<KioskSession> {
+    app.try_state::<KioskState>()
+        .and_then(|state| state.session.lock().unwrap().clone())
+}
+
+fn normalize_origin(origin: &str) -> Result<String, String> {
+    let url = url::Url::parse(origin).map_err(|_| format!("Invalid origin: {}", origin))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Invalid origin: {}", origin));
+    }
+    Ok(url.origin().ascii_serialization())
+}
+
+async fn verify_pin(hash: String, pin: String) -> Result<bool, String> {
+    tasks::blocking(move || Ok(secrets::pin_matches(&hash, &pin))).await
+}
+
+// Sends the page home when it ends up on a foreign origin and keeps popups
+// inside the kiosk window
+fn guard_script(session: &KioskSession) -> String {
+    format!(
+        r#"(function (home, origins) {{
+  if (location.href !== 'about:blank' && origins.indexOf(location.origin) < 0) {{ location.replace(home); return; }}
+  if (window.__MADEASY_KIOSK__) return;
+  window.__MADEASY_KIOSK__ = true;
+  window.open = function () {{ return null; }};
+  document.addEventListener('click', function (e) {{
+    var link = e.target && e.target.closest ? e.target.closest('a[href]') : null;
+    if (!link) return;
+    var target;
+    try {{ target = new URL(link.href, location.href); }} catch (err) {{ return; }}
+    if (link.hasAttribute('download') || origins.indexOf(target.origin) < 0) {{ e.preventDefault(); e.stopPropagation(); return; }}
+    if (link.target && link.target !== '_self') {{ e.preventDefault(); location.assign(target.href); }}
+  }}, true);
+}})({}, {});"#,
+        serde_json::to_string(session.url.as_str()).unwrap_or_default(),
+        serde_json::to_string(&session.origins).unwrap_or_default(),
+    )
+}
+
+pub fn on_page_load(window: &Window) {
+    if window.label() != WINDOW_LABEL {
+        return;
+    }
+    if let Some(session) = session(&window.app_handle()) {
+        let _ = window.eval(&guard_script(&session));
+    }
+}
+
+// Starts kiosk mode on `url`; used by the command and by `--kiosk <url>`
+pub fn enter(app: &AppHandle, url: &str, extra_origins: Option<Vec<String>>) -> Result<(), String> {
+    let url = url::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Kiosk URL must be http or https".to_string());
+    }
+    let state = app.state::<KioskState>();
+    let config = state.config.lock().unwrap().clone();
+    if config.pin_hash.is_none() {
+        return Err("Set an admin PIN before entering kiosk mode".to_string());
+    }
+    let mut origins = vec![url.origin().ascii_serialization()];
+    for origin in extra_origins.unwrap_or(config.allowed_origins) {
+        let origin = normalize_origin(&origin)?;
+        if !origins.contains(&origin) {
+            origins.push(origin);
+        }
+    }
+    let session = KioskSession { url: url.clone(), origins };
+    {
+        let mut current = state.session.lock().unwrap();
+        if current.is_some() {
+            return Err("Kiosk mode is already active".to_string());
+        }
+        *current = Some(session.clone());
+    }
+
+    let window = match WindowBuilder::new(app, WINDOW_LABEL, WindowUrl::External(url.clone()))
+        .title("MadEasy Browser")
+        .fullscreen(true)
+        .decorations(false)
+        .always_on_top(true)
+        .build()
+    {
+        Ok(window) => window,
+        Err(e) => {
+            *state.session.lock().unwrap() = None;
+            return Err(e.to_string());
+        }
+    };
+    let app_handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+            if active(&app_handle) {
+                api.prevent_close();
+            }
+        }
+    });
+    if let Err(e) = platform::lock_down(app, &window) {
+        eprintln!("Failed to restrict kiosk webview: {}", e);
+    }
+    for (label, other) in app.windows() {
+        if label != WINDOW_LABEL {
+            other.close_devtools();
+            let _ = other.hide();
+        }
+    }
+    if let Err(e) = app.tray_handle().set_menu(tauri::SystemTrayMenu::new()) {
+        eprintln!("Failed to clear tray menu: {}", e);
+    }
+    let _ = window.set_focus();
+    audit::record(
+        app,
+        AuditCategory::Settings,
+        "kiosk.enter",
+        json!({ "url": session.url.as_str(), "origins": session.origins }),
+    );
+    Ok(())
+}
+
+// The webview asks before each navigation on platforms with a native hook
+fn navigation_allowed(app: &AppHandle, target: &str) -> bool {
+    session(app).map_or(true, |session| session.allows(target))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_kiosk_status(state: tauri::State<'_, KioskState>) -> Result<KioskStatus, String> {
+    let config = state.config.lock().unwrap().clone();
+    let session = state.session.lock().unwrap().clone();
+    Ok(KioskStatus {
+        active: session.is_some(),
+        url: session.as_ref().map(|s| s.url.to_string()),
+        allowed_origins: session.map(|s| s.origins).unwrap_or(config.allowed_origins),
+        pin_set: config.pin_hash.is_some(),
+    })
+}
+
+// Default allowed origins for `--kiosk` launches
+#[tauri::command]
+#[specta::specta]
+pub async fn set_kiosk_allowed_origins(
+    app_handle: AppHandle,
+    state: tauri::State<'_, KioskState>,
+    origins: Vec<String>,
+) -> Result<(), String> {
+    ensure_inactive(&app_handle)?;
+    let origins = origins.iter().map(|o| normalize_origin(o)).collect::<Result<Vec<_>, _>>()?;
+    let mut config = state.config.lock().unwrap();
+    config.allowed_origins = origins;
+    storage::save(&app_handle, KIOSK_FILE, &*config)
+}
+
+// Changing an existing PIN requires the current one
+#[tauri::command]
+#[specta::specta]
+pub async fn set_kiosk_pin(
+    app_handle: AppHandle,
+    state: tauri::State<'_, KioskState>,
+    pin: String,
+    current_pin: Option<String>,
+) -> Result<(), String> {
+    ensure_inactive(&app_handle)?;
+    secrets::validate_pin(&pin)?;
+    let existing = state.config.lock().unwrap().pin_hash.clone();
+    if let Some(hash) = existing {
+        let current = current_pin.unwrap_or_default();
+        if !verify_pin(hash, current).await? {
+            return Err("Current PIN is incorrect".to_string());
+        }
+    }
+    let hash = tasks::blocking(move || secrets::hash_pin(&pin)).await?;
+    let mut config = state.config.lock().unwrap();
+    config.pin_hash = Some(hash);
+    storage::save(&app_handle, KIOSK_FILE, &*config)?;
+    audit::record(&app_handle, AuditCategory::Credential, "kiosk.pin_set", json!({}));
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn enter_kiosk_mode(
+    app_handle: AppHandle,
+    url: String,
+    allowed_origins: Option<Vec<String>>,
+) -> Result<(), String> {
+    enter(&app_handle, &url, allowed_origins)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn exit_kiosk_mode(
+    app_handle: AppHandle,
+    state: tauri::State<'_, KioskState>,
+    pin: String,
+) -> Result<(), String> {
+    let _attempt = state.exit_attempt.lock().await;
+    if !active(&app_handle) {
+        return Ok(());
+    }
+    let hash = state.config.lock().unwrap().pin_hash.clone().unwrap_or_default();
+    if !verify_pin(hash, pin).await? {
+        audit::record(&app_handle, AuditCategory::Credential, "kiosk.exit_denied", json!({}));
+        tokio::time::sleep(WRONG_PIN_DELAY).await;
+        return Err("Incorrect PIN".to_string());
+    }
+    *state.session.lock().unwrap() = None;
+    if let Some(window) = app_handle.get_window(WINDOW_LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    if let Some(main) = app_handle.get_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+    }
+    if let Err(e) = tray::refresh(&app_handle) {
+        eprintln!("Failed to rebuild tray menu: {}", e);
+    }
+    audit::record(&app_handle, AuditCategory::Settings, "kiosk.exit", json!({}));
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::{AppHandle, Window};
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_4;
+    use webview2_com::{DownloadStartingEventHandler, NavigationStartingEventHandler, NewWindowRequestedEventHandler};
+    use windows_webview2::core::{Interface, PWSTR};
+    use windows_webview2::Win32::System::WinRT::EventRegistrationToken;
+
+    // Cancels foreign navigations, popups and every download
+    pub fn lock_down(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        window
+            .with_webview(move |webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let core: ICoreWebView2_4 = webview.controller().CoreWebView2()?.cast()?;
+                    let mut token = EventRegistrationToken::default();
+                    let navigation = NavigationStartingEventHandler::create(Box::new(move |_, args| {
+                        if let Some(args) = args {
+                            let mut uri = PWSTR::null();
+                            args.Uri(&mut uri)?;
+                            if !super::navigation_allowed(&app, &webview2_com::take_pwstr(uri)) {
+                                args.SetCancel(true)?;
+                            }
+                        }
+                        Ok(())
+                    }));
+                    core.add_NavigationStarting(&navigation, &mut token)?;
+                    let popups = NewWindowRequestedEventHandler::create(Box::new(|_, args| {
+                        if let Some(args) = args {
+                            args.SetHandled(true)?;
+                        }
+                        Ok(())
+                    }));
+                    core.add_NewWindowRequested(&popups, &mut token)?;
+                    let downloads = DownloadStartingEventHandler::create(Box::new(|_, args| {
+                        if let Some(args) = args {
+                            args.SetCancel(true)?;
+                        }
+                        Ok(())
+                    }));
+                    core.add_DownloadStarting(&downloads, &mut token)
+                })();
+                if let Err(e) = result {
+                    eprintln!("Failed to install kiosk handlers: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use gtk::glib::Cast;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tauri::{AppHandle, Window};
+    use webkit2gtk::{
+        DownloadExt, NavigationPolicyDecision, NavigationPolicyDecisionExt, PolicyDecisionExt, PolicyDecisionType,
+        URIRequestExt, WebContextExt, WebViewExt,
+    };
+
+    // The web context (and so its download signal) is shared by all windows
+    static DOWNLOAD_HOOK: AtomicBool = AtomicBool::new(false);
+
+    pub fn lock_down(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        window
+            .with_webview(move |webview| {
+                let view = webview.inner();
+                let navigation_app = app.clone();
+                view.connect_decide_policy(move |_, decision, kind| {
+                    match kind {
+                        PolicyDecisionType::NewWindowAction => decision.ignore(),
+                        PolicyDecisionType::NavigationAction => {
+                            let uri = decision
+                                .downcast_ref::<NavigationPolicyDecision>()
+                                .and_then(|d| d.navigation_action())
+                                .and_then(|mut action| action.request())
+                                .and_then(|request| request.uri())
+                                .map(|uri| uri.to_string())
+                                .unwrap_or_default();
+                            if super::navigation_allowed(&navigation_app, &uri) {
+                                return false;
+                            }
+                            decision.ignore();
+                        }
+                        _ => return false,
+                    }
+                    true
+                });
+                if !DOWNLOAD_HOOK.swap(true, Ordering::SeqCst) {
+                    if let Some(context) = view.context() {
+                        context.connect_download_started(move |_, download| {
+                            if super::active(&app) {
+                                download.cancel();
+                            }
+                        });
+                    }
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    use tauri::{AppHandle, Window};
+
+    // WKWebView in this Tauri version does not download; navigation relies on the page script
+    pub fn lock_down(_app: &AppHandle, _window: &Window) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+// Command-line activation
+// Jump list / dock entries relaunch the executable with these arguments; the
+// single-instance handler forwards them to the running instance
+
+use tauri::{AppHandle, Manager};
+
+use crate::kiosk;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchAction {
+    OpenUrl(String),
+    NewPrivateWindow,
+    RunWorkflow(String),
+    Kiosk(String),
+}
+
+impl LaunchAction {
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            LaunchAction::OpenUrl(url) => vec!["--open-url".to_string(), url.clone()],
+            LaunchAction::NewPrivateWindow => vec!["--new-private-window".to_string()],
+            LaunchAction::RunWorkflow(id) => vec!["--run-workflow".to_string(), id.clone()],
+            LaunchAction::Kiosk(url) => vec!["--kiosk".to_string(), url.clone()],
+        }
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Vec<LaunchAction> {
+    let mut actions = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--open-url" => {
+                if let Some(url) = iter.next() {
+                    actions.push(LaunchAction::OpenUrl(url.clone()));
+                }
+            }
+            "--new-private-window" => actions.push(LaunchAction::NewPrivateWindow),
+            "--run-workflow" => {
+                if let Some(id) = iter.next() {
+                    actions.push(LaunchAction::RunWorkflow(id.clone()));
+                }
+            }
+            "--kiosk" => {
+                if let Some(url) = iter.next() {
+                    actions.push(LaunchAction::Kiosk(url.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+    actions
+}
+
+pub fn dispatch(app: &AppHandle, action: LaunchAction) {
+    match action {
+        LaunchAction::OpenUrl(url) => {
+            tauri::async_runtime::spawn(crate::create_new_window(app.clone(), Some(url), None));
+        }
+        LaunchAction::NewPrivateWindow => {
+            let _ = app.emit_all("open-private-window", ());
+        }
+        LaunchAction::RunWorkflow(id) => {
+            let _ = app.emit_all("run-workflow-requested", id);
+        }
+        LaunchAction::Kiosk(url) => {
+            if let Err(e) = kiosk::enter(app, &url, None) {
+                eprintln!("Failed to enter kiosk mode: {}", e);
+            }
+        }
+    }
+}
+
+// Handle the arguments of this or a second instance; with no actions, just
+// bring the main window to the front. A running kiosk ignores them.
+pub fn handle_args(app: &AppHandle, args: &[String]) {
+    if kiosk::active(app) {
+        if let Some(window) = app.get_window(kiosk::WINDOW_LABEL) {
+            let _ = window.set_focus();
+        }
+        return;
+    }
+    let actions = parse_args(args);
+    if actions.is_empty() {
+        if let Some(window) = app.get_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+    for action in actions {
+        dispatch(app, action);
+    }
+}
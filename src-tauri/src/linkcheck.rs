@@ -0,0 +1,347 @@
+// Broken link checker
+// Collects the links (anchors plus linked resources) of a page, optionally
+// following same-origin pages up to `depth` levels, and checks each target
+// concurrently. Redirects are followed by hand so every hop is reported.
+// HEAD is tried first; servers that reject it get a GET.
+
+use reqwest::{Method, StatusCode, Url};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::{bandwidth, tasks};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REDIRECTS: usize = 10;
+const MAX_DEPTH: u32 = 3;
+const USER_AGENT: &str = "MadEasyBrowser-LinkChecker/3.0";
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(default)]
+pub struct LinkCheckOptions {
+    // 0 checks the page's own links; each level also checks the links of
+    // same-origin pages found on the previous one
+    depth: u32,
+    max_links: usize,
+    concurrency: usize,
+}
+
+impl Default for LinkCheckOptions {
+    fn default() -> Self {
+        Self {
+            depth: 0,
+            max_links: 500,
+            concurrency: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    Anchor,
+    Image,
+    Script,
+    Stylesheet,
+    Frame,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RedirectHop {
+    url: String,
+    status: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LinkReport {
+    url: String,
+    kind: LinkKind,
+    // Pages the link was found on
+    found_on: Vec<String>,
+    status: Option<u16>,
+    ok: bool,
+    redirects: Vec<RedirectHop>,
+    final_url: Option<String>,
+    error: Option<String>,
+    elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LinkCheckResult {
+    pages: Vec<String>,
+    checked: usize,
+    broken: usize,
+    // Links beyond `max_links` that were not checked
+    skipped: usize,
+    links: Vec<LinkReport>,
+}
+
+pub fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// Absolute http(s) URL without its fragment
+pub fn normalize(base: &Url, href: &str) -> Option<Url> {
+    let mut url = base.join(href.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url)
+}
+
+// Links on a page in document order, without duplicates
+pub fn extract_links(html: &str, base: &Url) -> Vec<(Url, LinkKind)> {
+    let document = Html::parse_document(html);
+    // <base href> changes how relative links resolve
+    let base = Selector::parse("base[href]")
+        .ok()
+        .and_then(|s| document.select(&s).next())
+        .and_then(|b| b.value().attr("href").and_then(|href| base.join(href).ok()))
+        .unwrap_or_else(|| base.clone());
+    let sources = [
+        ("a[href], area[href]", "href", LinkKind::Anchor),
+        ("img[src]", "src", LinkKind::Image),
+        ("script[src]", "src", LinkKind::Script),
+        ("link[rel~='stylesheet'][href]", "href", LinkKind::Stylesheet),
+        ("iframe[src], frame[src]", "src", LinkKind::Frame),
+    ];
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for (selector, attr, kind) in sources {
+        let selector = Selector::parse(selector).unwrap();
+        for element in document.select(&selector) {
+            let Some(url) = element.value().attr(attr).and_then(|v| normalize(&base, v)) else {
+                continue;
+            };
+            if seen.insert(url.clone()) {
+                links.push((url, kind));
+            }
+        }
+    }
+    links
+}
+
+// Send a request following redirects manually; returns the final response
+// status, the hops taken and the final URL
+pub async fn request(
+    client: &reqwest::Client,
+    method: Method,
+    url: &Url,
+) -> Result<(reqwest::Response, Vec<RedirectHop>), String> {
+    let mut current = url.clone();
+    let mut hops = Vec::new();
+    loop {
+        let response = client
+            .request(method.clone(), current.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        if !status.is_redirection() || status == StatusCode::NOT_MODIFIED {
+            return Ok((response, hops));
+        }
+        if hops.len() >= MAX_REDIRECTS {
+            return Err("Too many redirects".to_string());
+        }
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or("Redirect without a location")?;
+        let next = current.join(location).map_err(|e| e.to_string())?;
+        hops.push(RedirectHop {
+            url: current.to_string(),
+            status: status.as_u16(),
+        });
+        if hops.iter().any(|h| h.url == next.as_str()) {
+            return Err("Redirect loop".to_string());
+        }
+        current = next;
+    }
+}
+
+// A request whose body is never read
+async fn probe(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    method: Method,
+    url: &Url,
+) -> Result<(reqwest::Response, Vec<RedirectHop>), String> {
+    let result = request(client, method, url).await;
+    if let Ok((response, _)) = &result {
+        bandwidth::record(app, response.url().as_str(), 0, 0);
+    }
+    result
+}
+
+async fn check(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    url: &Url,
+    kind: LinkKind,
+    found_on: Vec<String>,
+) -> LinkReport {
+    let started = Instant::now();
+    let mut result = probe(app, client, Method::HEAD, url).await;
+    // Plenty of servers answer HEAD with an error they'd never give a GET
+    let retry = match &result {
+        Ok((response, _)) => matches!(response.status().as_u16(), 403 | 404 | 405 | 501),
+        Err(_) => true,
+    };
+    if retry {
+        if let Ok(fallback) = probe(app, client, Method::GET, url).await {
+            result = Ok(fallback);
+        }
+    }
+    let mut report = LinkReport {
+        url: url.to_string(),
+        kind,
+        found_on,
+        status: None,
+        ok: false,
+        redirects: Vec::new(),
+        final_url: None,
+        error: None,
+        elapsed_ms: 0,
+    };
+    match result {
+        Ok((response, hops)) => {
+            report.status = Some(response.status().as_u16());
+            report.ok = response.status().is_success();
+            report.final_url = Some(response.url().to_string());
+            report.redirects = hops;
+        }
+        Err(e) => report.error = Some(e),
+    }
+    report.elapsed_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+pub async fn fetch_html(app: &AppHandle, client: &reqwest::Client, url: &Url) -> Result<(Url, String), String> {
+    let (response, _) = request(client, Method::GET, url).await?;
+    let final_url = response.url().clone();
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |t| t.contains("html"));
+    if !is_html {
+        return Err(format!("{} is not an HTML page", url));
+    }
+    let html = response.text().await.map_err(|e| e.to_string())?;
+    bandwidth::record(app, final_url.as_str(), 0, html.len() as u64);
+    Ok((final_url, html))
+}
+
+pub async fn check_links_from(
+    app: AppHandle,
+    start: Url,
+    options: LinkCheckOptions,
+) -> Result<LinkCheckResult, String> {
+    let client = client()?;
+    let depth = options.depth.min(MAX_DEPTH);
+
+    // Collect links breadth-first, remembering where each was found
+    let mut found: HashMap<Url, (LinkKind, Vec<String>)> = HashMap::new();
+    let mut order: Vec<Url> = Vec::new();
+    let mut pages = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([(start.clone(), 0)]);
+    while let Some((page, level)) = queue.pop_front() {
+        if !visited.insert(page.clone()) {
+            continue;
+        }
+        let html = match fetch_html(&app, &client, &page).await {
+            Ok((_, html)) => html,
+            // The start page has to load; deeper pages just show up as broken links
+            Err(e) if level == 0 => return Err(e),
+            Err(_) => continue,
+        };
+        pages.push(page.to_string());
+        for (url, kind) in extract_links(&html, &page) {
+            let entry = found.entry(url.clone()).or_insert_with(|| {
+                order.push(url.clone());
+                (kind, Vec::new())
+            });
+            if !entry.1.contains(&page.to_string()) {
+                entry.1.push(page.to_string());
+            }
+            if level < depth && kind == LinkKind::Anchor && url.origin() == start.origin() {
+                queue.push_back((url, level + 1));
+            }
+        }
+    }
+
+    let skipped = order.len().saturating_sub(options.max_links);
+    order.truncate(options.max_links);
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.clamp(1, 32)));
+    let mut tasks = JoinSet::new();
+    for (index, url) in order.into_iter().enumerate() {
+        let (kind, found_on) = found.remove(&url).unwrap_or((LinkKind::Anchor, Vec::new()));
+        let app = app.clone();
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (index, check(&app, &client, &url, kind, found_on).await)
+        });
+    }
+    let mut links = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(report) = joined {
+            links.push(report);
+        }
+    }
+    links.sort_by_key(|(index, _)| *index);
+    let links: Vec<LinkReport> = links.into_iter().map(|(_, report)| report).collect();
+
+    Ok(LinkCheckResult {
+        pages,
+        checked: links.len(),
+        broken: links.iter().filter(|l| !l.ok).count(),
+        skipped,
+        links,
+    })
+}
+
+// Check the links of a window's current page or of `url`
+#[tauri::command]
+#[specta::specta]
+pub async fn check_links(
+    app_handle: AppHandle,
+    window_id: Option<String>,
+    url: Option<String>,
+    options: Option<LinkCheckOptions>,
+) -> Result<LinkCheckResult, String> {
+    let start = match (window_id, url) {
+        (_, Some(url)) => url,
+        (Some(window_id), None) => app_handle
+            .get_window(&window_id)
+            .ok_or_else(|| format!("Window not found: {}", window_id))?
+            .url()
+            .map_err(|e| e.to_string())?
+            .to_string(),
+        (None, None) => return Err("Provide a window id or a URL".to_string()),
+    };
+    let start = Url::parse(&start).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(start.scheme(), "http" | "https") {
+        return Err("Only http(s) pages can be checked".to_string());
+    }
+    let name = format!("Check links on {}", start);
+    tasks::run(&app_handle, &name, check_links_from(app_handle.clone(), start, options.unwrap_or_default())).await
+}
@@ -0,0 +1,267 @@
+// Link previews from OpenGraph / Twitter-card metadata
+// Pages are fetched here rather than in the webview so previews never run page
+// scripts or send cookies. Since the URL comes from arbitrary page content,
+// only public http(s) addresses are fetched: every hop of a redirect chain is
+// resolved and checked, and the request is pinned to the checked address so a
+// second DNS answer cannot point it somewhere internal. Results (including
+// "no preview") are cached on disk for a day.
+
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::{bandwidth, storage};
+
+const CACHE_NAME: &str = "link-previews";
+const TTL_SECS: i64 = 24 * 60 * 60;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(8);
+const MAX_REDIRECTS: usize = 5;
+// Metadata lives in <head>; no need to read whole pages
+const MAX_HTML_BYTES: usize = 512 * 1024;
+const MAX_TEXT_CHARS: usize = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LinkPreview {
+    url: String,
+    // After redirects
+    final_url: String,
+    title: Option<String>,
+    description: Option<String>,
+    image: Option<String>,
+    site_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct CacheEntry {
+    fetched_at: i64,
+    preview: Option<LinkPreview>,
+}
+
+fn cache_path(app: &AppHandle, url: &str) -> Result<PathBuf, String> {
+    let dir = storage::cache_dir(app, CACHE_NAME)?;
+    Ok(dir.join(format!("{}.json", hex::encode(Sha256::digest(url.as_bytes())))))
+}
+
+fn write_cache(app: &AppHandle, url: &str, preview: Option<&LinkPreview>) -> Result<(), String> {
+    let entry = CacheEntry {
+        fetched_at: chrono::Utc::now().timestamp(),
+        preview: preview.cloned(),
+    };
+    let bytes = serde_json::to_vec(&entry).map_err(|e| e.to_string())?;
+    std::fs::write(cache_path(app, url)?, bytes).map_err(|e| e.to_string())
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                // Carrier-grade NAT and benchmarking ranges
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (18..20).contains(&b))
+                || a == 0
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+// Resolve the URL's host and return a public address to connect to
+async fn checked_address(url: &Url) -> Result<SocketAddr, String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Unsupported scheme: {}", url.scheme()));
+    }
+    let host = url.host_str().ok_or("URL has no host")?;
+    let port = url.port_or_known_default().ok_or("URL has no port")?;
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_matches(|c| c == '[' || c == ']'), port))
+        .await
+        .map_err(|e| format!("Could not resolve {}: {}", host, e))?
+        .collect();
+    if addresses.is_empty() || !addresses.iter().all(|a| is_public(a.ip())) {
+        return Err(format!("{} is not a public address", host));
+    }
+    Ok(addresses[0])
+}
+
+async fn read_limited(app: &AppHandle, mut response: reqwest::Response) -> Result<String, String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_HTML_BYTES {
+            body.truncate(MAX_HTML_BYTES);
+            break;
+        }
+    }
+    bandwidth::record_received(app, response.url().as_str(), body.len() as u64);
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+// Read the body up to `limit` bytes; the flag says whether it was complete.
+// Used for favicons and reading list pages and images.
+pub async fn read_up_to(app: &AppHandle, mut response: reqwest::Response, limit: usize) -> Option<(Vec<u8>, bool)> {
+    if response.content_length().map_or(false, |len| len > limit as u64) {
+        return Some((Vec::new(), false));
+    }
+    let mut body = Vec::new();
+    let mut complete = true;
+    while let Some(chunk) = response.chunk().await.ok()? {
+        body.extend_from_slice(&chunk);
+        if body.len() > limit {
+            complete = false;
+            break;
+        }
+    }
+    bandwidth::record_received(app, response.url().as_str(), body.len() as u64);
+    body.truncate(limit);
+    Some((body, complete))
+}
+
+// GET a public URL, following redirects by hand so each hop gets checked.
+// Returns the final URL and its successful response; also used for favicons.
+pub async fn get_public(app: &AppHandle, url: &Url, accept: &str) -> Result<(Url, reqwest::Response), String> {
+    let mut current = url.clone();
+    for _ in 0..=MAX_REDIRECTS {
+        let address = checked_address(&current).await?;
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve(current.host_str().unwrap_or_default(), address)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let response = client
+            .get(current.clone())
+            .header(reqwest::header::ACCEPT, accept)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        bandwidth::record(app, current.as_str(), 0, 0);
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or("Redirect without a location")?;
+            current = current.join(location).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(format!("Fetching {} failed: {}", current, response.status()));
+        }
+        return Ok((current, response));
+    }
+    Err("Too many redirects".to_string())
+}
+
+async fn fetch_html(app: &AppHandle, url: &Url) -> Result<(Url, String), String> {
+    let (current, response) = get_public(app, url, "text/html,application/xhtml+xml").await?;
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |t| t.contains("html"));
+    if !is_html {
+        return Err("Not an HTML page".to_string());
+    }
+    Ok((current, read_limited(app, response).await?))
+}
+
+fn clip(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    Some(match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text,
+    })
+}
+
+// First non-empty `content` among the meta tags, in order of preference
+fn meta(document: &Html, keys: &[&str]) -> Option<String> {
+    let selector = Selector::parse("meta[content]").unwrap();
+    keys.iter().find_map(|key| {
+        document.select(&selector).find_map(|element| {
+            let element = element.value();
+            let name = element.attr("property").or_else(|| element.attr("name"))?;
+            if !name.eq_ignore_ascii_case(key) {
+                return None;
+            }
+            clip(element.attr("content")?)
+        })
+    })
+}
+
+pub fn parse(url: &str, final_url: &Url, html: &str) -> Option<LinkPreview> {
+    let document = Html::parse_document(html);
+    let title = meta(&document, &["og:title", "twitter:title"]).or_else(|| {
+        let selector = Selector::parse("title").unwrap();
+        document.select(&selector).next().and_then(|t| clip(&t.text().collect::<String>()))
+    });
+    let description = meta(&document, &["og:description", "twitter:description", "description"]);
+    let image = meta(&document, &["og:image:secure_url", "og:image", "twitter:image", "twitter:image:src"])
+        .and_then(|src| final_url.join(&src).ok())
+        .filter(|src| matches!(src.scheme(), "http" | "https"))
+        .map(|src| src.to_string());
+    let site_name = meta(&document, &["og:site_name"]);
+    if title.is_none() && description.is_none() && image.is_none() {
+        return None;
+    }
+    Some(LinkPreview {
+        url: url.to_string(),
+        final_url: final_url.to_string(),
+        title,
+        description,
+        image,
+        site_name: site_name.or_else(|| final_url.host_str().map(str::to_string)),
+    })
+}
+
+// Cache a preview from HTML that was fetched elsewhere (e.g. the reading list)
+pub fn remember(app: &AppHandle, url: &Url, html: &str) {
+    let preview = parse(url.as_str(), url, html);
+    if preview.is_some() {
+        let _ = write_cache(app, url.as_str(), preview.as_ref());
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_link_preview(app_handle: AppHandle, url: String) -> Result<Option<LinkPreview>, String> {
+    let parsed = Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let path = cache_path(&app_handle, parsed.as_str())?;
+    let cached: Option<CacheEntry> = std::fs::read(&path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    if let Some(entry) = cached.filter(|e| chrono::Utc::now().timestamp() - e.fetched_at < TTL_SECS) {
+        return Ok(entry.preview);
+    }
+
+    let (final_url, html) = fetch_html(&app_handle, &parsed).await?;
+    let preview = parse(parsed.as_str(), &final_url, &html);
+    write_cache(&app_handle, parsed.as_str(), preview.as_ref())?;
+    Ok(preview)
+}
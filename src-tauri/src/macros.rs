@@ -0,0 +1,314 @@
+// Keyboard and mouse macros
+// While a window is recording, an injected listener reports trusted keyboard
+// and mouse input to `macro_input` and Rust timestamps each step. Playback
+// replays the steps into the page as synthetic events with the recorded
+// delays (scaled by `speed`). Keystrokes in password fields are never
+// recorded. Macros are stored per profile.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{profiles, storage, tasks};
+
+const MACROS_FILE: &str = "macros.json";
+const MAX_STEPS: usize = 10_000;
+const MAX_REPEAT: u32 = 1_000;
+// Longest pause kept between two steps; idle time beyond this is dropped
+const MAX_DELAY_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MacroInput {
+    KeyDown { key: String, code: String, modifiers: Modifiers },
+    KeyUp { key: String, code: String, modifiers: Modifiers },
+    MouseDown { x: f64, y: f64, button: u8 },
+    MouseUp { x: f64, y: f64, button: u8 },
+    Click { x: f64, y: f64, button: u8 },
+    MouseMove { x: f64, y: f64 },
+    Wheel { x: f64, y: f64, delta_x: f64, delta_y: f64 },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct Modifiers {
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MacroStep {
+    // Time since the previous step
+    delay_ms: u64,
+    input: MacroInput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MacroDefinition {
+    id: String,
+    name: String,
+    // Page the recording started on
+    start_url: Option<String>,
+    created_at: i64,
+    steps: Vec<MacroStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PlaybackOptions {
+    repeat: u32,
+    // 2.0 plays twice as fast
+    speed: f64,
+}
+
+impl Default for PlaybackOptions {
+    fn default() -> Self {
+        Self { repeat: 1, speed: 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+struct MacroLibrary {
+    macros: Vec<MacroDefinition>,
+}
+
+struct Recording {
+    start_url: Option<String>,
+    last_input: Instant,
+    steps: Vec<MacroStep>,
+}
+
+#[derive(Default)]
+pub struct MacroState {
+    recordings: Mutex<HashMap<String, Recording>>,
+}
+
+fn load_macros(app: &AppHandle, profile_id: &str) -> MacroLibrary {
+    storage::load(app, &profiles::profile_file(profile_id, MACROS_FILE))
+}
+
+fn save_macros(app: &AppHandle, profile_id: &str, library: &MacroLibrary) -> Result<(), String> {
+    storage::save(app, &profiles::profile_file(profile_id, MACROS_FILE), library)
+}
+
+fn find_window(app: &AppHandle, window_id: &str) -> Result<Window, String> {
+    app.get_window(window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))
+}
+
+// Reports trusted input only, so synthetic events from playback are not re-recorded
+const RECORDER_SCRIPT: &str = r#"(function () {
+  if (window.__MADEASY_MACRO_RECORDER__) return;
+  window.__MADEASY_MACRO_RECORDER__ = true;
+  var lastMove = 0;
+  function send(input) { if (window.__TAURI_INVOKE__) window.__TAURI_INVOKE__('macro_input', { input: input }); }
+  function modifiers(e) { return { ctrl: e.ctrlKey, shift: e.shiftKey, alt: e.altKey, meta: e.metaKey }; }
+  function key(type) {
+    return function (e) {
+      if (!e.isTrusted || (e.target && e.target.type === 'password')) return;
+      send({ type: type, key: e.key, code: e.code, modifiers: modifiers(e) });
+    };
+  }
+  function mouse(type) {
+    return function (e) {
+      if (e.isTrusted) send({ type: type, x: e.clientX, y: e.clientY, button: e.button });
+    };
+  }
+  window.addEventListener('keydown', key('key_down'), true);
+  window.addEventListener('keyup', key('key_up'), true);
+  window.addEventListener('mousedown', mouse('mouse_down'), true);
+  window.addEventListener('mouseup', mouse('mouse_up'), true);
+  window.addEventListener('click', mouse('click'), true);
+  window.addEventListener('mousemove', function (e) {
+    var now = Date.now();
+    if (!e.isTrusted || now - lastMove < 50) return;
+    lastMove = now;
+    send({ type: 'mouse_move', x: e.clientX, y: e.clientY });
+  }, true);
+  window.addEventListener('wheel', function (e) {
+    if (e.isTrusted) send({ type: 'wheel', x: e.clientX, y: e.clientY, delta_x: e.deltaX, delta_y: e.deltaY });
+  }, { capture: true, passive: true });
+})();"#;
+
+// Synthetic events don't type into fields, so printable keys are inserted
+// into the focused editable element explicitly
+const PLAYER_SCRIPT: &str = r#"if (!window.__MADEASY_MACRO_PLAY__) window.__MADEASY_MACRO_PLAY__ = function (input) {
+  var opts = { bubbles: true, cancelable: true, composed: true };
+  if (input.type === 'key_down' || input.type === 'key_up') {
+    var m = input.modifiers, target = document.activeElement || document.body;
+    var init = Object.assign({ key: input.key, code: input.code, ctrlKey: m.ctrl, shiftKey: m.shift, altKey: m.alt, metaKey: m.meta }, opts);
+    var proceed = target.dispatchEvent(new KeyboardEvent(input.type === 'key_down' ? 'keydown' : 'keyup', init));
+    var editable = target.isContentEditable || /^(INPUT|TEXTAREA)$/.test(target.tagName);
+    if (proceed && editable && input.type === 'key_down' && !m.ctrl && !m.meta && !m.alt) {
+      if (input.key.length === 1) document.execCommand('insertText', false, input.key);
+      else if (input.key === 'Backspace') document.execCommand('delete');
+      else if (input.key === 'Enter' && target.form && target.tagName === 'INPUT') target.form.requestSubmit();
+    }
+    return;
+  }
+  var el = document.elementFromPoint(input.x, input.y) || document.body;
+  var mouseInit = Object.assign({ clientX: input.x, clientY: input.y, button: input.button || 0 }, opts);
+  if (input.type === 'mouse_move') el.dispatchEvent(new MouseEvent('mousemove', mouseInit));
+  else if (input.type === 'mouse_down') { el.dispatchEvent(new MouseEvent('mousedown', mouseInit)); if (el.focus) el.focus(); }
+  else if (input.type === 'mouse_up') el.dispatchEvent(new MouseEvent('mouseup', mouseInit));
+  else if (input.type === 'click') el.dispatchEvent(new MouseEvent('click', mouseInit));
+  else if (input.type === 'wheel') {
+    el.dispatchEvent(new WheelEvent('wheel', Object.assign({ deltaX: input.delta_x, deltaY: input.delta_y }, mouseInit)));
+    window.scrollBy(input.delta_x, input.delta_y);
+  }
+};"#;
+
+// Re-install the recorder after a recording window navigates
+pub fn inject(window: &Window) {
+    let recording = window
+        .state::<MacroState>()
+        .recordings
+        .lock()
+        .unwrap()
+        .contains_key(window.label());
+    if recording {
+        let _ = window.eval(RECORDER_SCRIPT);
+    }
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<MacroState>().recordings.lock().unwrap().remove(label);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn record_macro(app_handle: AppHandle, state: tauri::State<'_, MacroState>, window_id: String) -> Result<(), String> {
+    let window = find_window(&app_handle, &window_id)?;
+    {
+        let mut recordings = state.recordings.lock().unwrap();
+        if recordings.contains_key(&window_id) {
+            return Err(format!("{} is already recording", window_id));
+        }
+        recordings.insert(
+            window_id,
+            Recording {
+                start_url: window.url().ok().map(|u| u.to_string()),
+                last_input: Instant::now(),
+                steps: Vec::new(),
+            },
+        );
+    }
+    window.eval(RECORDER_SCRIPT).map_err(|e| e.to_string())
+}
+
+// Called by the injected recorder
+#[tauri::command]
+#[specta::specta]
+pub async fn macro_input(window: Window, state: tauri::State<'_, MacroState>, input: MacroInput) -> Result<(), String> {
+    let mut recordings = state.recordings.lock().unwrap();
+    let Some(recording) = recordings.get_mut(window.label()) else {
+        return Ok(());
+    };
+    if recording.steps.len() >= MAX_STEPS {
+        return Err(format!("Macros are limited to {} steps", MAX_STEPS));
+    }
+    let now = Instant::now();
+    let delay_ms = if recording.steps.is_empty() {
+        0
+    } else {
+        (now.duration_since(recording.last_input).as_millis() as u64).min(MAX_DELAY_MS)
+    };
+    recording.last_input = now;
+    recording.steps.push(MacroStep { delay_ms, input });
+    Ok(())
+}
+
+// Stop recording and save the macro; nothing is saved when no input was captured
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_macro_recording(
+    app_handle: AppHandle,
+    state: tauri::State<'_, MacroState>,
+    window_id: String,
+    name: String,
+    profile_id: Option<String>,
+) -> Result<Option<MacroDefinition>, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let recording = state
+        .recordings
+        .lock()
+        .unwrap()
+        .remove(&window_id)
+        .ok_or_else(|| format!("{} is not recording", window_id))?;
+    if recording.steps.is_empty() {
+        return Ok(None);
+    }
+
+    let created_at = chrono::Utc::now().timestamp_millis();
+    let definition = MacroDefinition {
+        id: format!("macro_{}", created_at),
+        name: if name.trim().is_empty() { "Untitled macro".to_string() } else { name.trim().to_string() },
+        start_url: recording.start_url,
+        created_at,
+        steps: recording.steps,
+    };
+    let mut library = load_macros(&app_handle, &profile);
+    library.macros.push(definition.clone());
+    save_macros(&app_handle, &profile, &library)?;
+    Ok(Some(definition))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_macros(app_handle: AppHandle, profile_id: Option<String>) -> Result<Vec<MacroDefinition>, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    Ok(load_macros(&app_handle, &profile).macros)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_macro(app_handle: AppHandle, macro_id: String, profile_id: Option<String>) -> Result<(), String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut library = load_macros(&app_handle, &profile);
+    library.macros.retain(|m| m.id != macro_id);
+    save_macros(&app_handle, &profile, &library)
+}
+
+// Replays into `window_id`; runs as a task so `cancel_task` stops it
+#[tauri::command]
+#[specta::specta]
+pub async fn play_macro(
+    app_handle: AppHandle,
+    id: String,
+    window_id: String,
+    options: Option<PlaybackOptions>,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    if !(options.speed > 0.0 && options.speed <= 100.0) {
+        return Err("speed must be between 0 and 100".to_string());
+    }
+    if options.repeat == 0 || options.repeat > MAX_REPEAT {
+        return Err(format!("repeat must be between 1 and {}", MAX_REPEAT));
+    }
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let definition = load_macros(&app_handle, &profile)
+        .macros
+        .into_iter()
+        .find(|m| m.id == id)
+        .ok_or_else(|| format!("Macro not found: {}", id))?;
+    let window = find_window(&app_handle, &window_id)?;
+
+    let playback = async {
+        for _ in 0..options.repeat {
+            for step in &definition.steps {
+                tokio::time::sleep(Duration::from_secs_f64(step.delay_ms as f64 / 1000.0 / options.speed)).await;
+                let input = serde_json::to_string(&step.input).map_err(|e| e.to_string())?;
+                window
+                    .eval(&format!("{}\nwindow.__MADEASY_MACRO_PLAY__({});", PLAYER_SCRIPT, input))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    };
+    tasks::run(&app_handle, &format!("Macro {}", definition.name), playback).await
+}
@@ -7,18 +7,136 @@
 )]
 
 use tauri::{
-    CustomMenuItem, Manager, Menu, MenuItem, Submenu, Window, WindowBuilder, WindowUrl,
-    SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem
+    CustomMenuItem, Manager, Menu, MenuItem, Submenu, Window, WindowUrl,
+    SystemTrayEvent
 };
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize, Deserialize)]
+mod a11y;
+mod ai;
+mod appscheme;
+mod artifacts;
+mod audit;
+mod backup;
+mod bandwidth;
+mod battery;
+mod bookmarks;
+mod bulkopen;
+mod cache;
+mod capture;
+mod click_guard;
+mod clipboard;
+mod closedwindows;
+mod containers;
+mod content_settings;
+mod contextmenu;
+mod crawler;
+mod datasets;
+mod db;
+mod dedupe;
+mod devtools;
+mod digest;
+mod favicons;
+mod feeds;
+mod find;
+mod fingerprint;
+mod dnd;
+mod downloads;
+mod email;
+mod emulation;
+mod enrichment;
+mod environments;
+mod events;
+mod geolocation;
+mod gestures;
+mod health;
+mod hibernation;
+mod history;
+mod i18n;
+mod idle;
+mod jumplist;
+mod kiosk;
+mod launch;
+mod linkcheck;
+mod linkpreview;
+mod macros;
+mod matching;
+mod monitors;
+mod network;
+mod notifications;
+mod oauth;
+mod offline_cache;
+mod omnibox;
+mod outbound;
+mod pagebridge;
+mod pageerrors;
+mod pagemetrics;
+mod pagequery;
+mod payloads;
+mod plugin_registry;
+mod plugins;
+mod policy;
+mod power;
+mod print;
+mod profile_archive;
+mod profiles;
+mod quarantine;
+mod readability;
+mod readinglist;
+mod regional;
+mod resources;
+mod scripting;
+mod search;
+mod s3;
+mod safemode;
+mod scanner;
+mod secrets;
+mod seo;
+mod server;
+mod share;
+mod shortcuts;
+mod shutdown;
+mod speeddial;
+mod spellcheck;
+mod startup;
+mod storage;
+mod taskmanager;
+mod tasks;
+mod theme;
+mod thumbnails;
+mod translation;
+mod totp;
+mod tray;
+mod upload;
+mod urlcleaner;
+mod userscripts;
+mod validation;
+mod vault;
+mod vision;
+mod visualdiff;
+mod windowpool;
+mod workflow;
+mod workflow_defs;
+mod workflow_gen;
+mod workflow_git;
+mod workspaces;
+mod zoom;
+
+const APP_CONFIG_FILE: &str = "app-config.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
 struct AppConfig {
+    // Effective URL, mirrors the active environment
     server_url: String,
+    environments: Vec<environments::Environment>,
+    active_environment: String,
     window_width: f64,
     window_height: f64,
     auto_start: bool,
+    // "light", "dark", "system" or an imported theme id
     theme: String,
 }
 
@@ -26,6 +144,8 @@ impl Default for AppConfig {
     fn default() -> Self {
         Self {
             server_url: "http://localhost:5000".to_string(),
+            environments: Vec::new(),
+            active_environment: "local".to_string(),
             window_width: 1400.0,
             window_height: 900.0,
             auto_start: false,
@@ -34,27 +154,48 @@ impl Default for AppConfig {
     }
 }
 
+fn load_app_config(app: &tauri::AppHandle) -> AppConfig {
+    let mut config: AppConfig = storage::load(app, APP_CONFIG_FILE);
+    environments::migrate(&mut config);
+    config
+}
+
 // Tauri commands (callable from frontend)
 #[tauri::command]
-async fn get_app_config() -> Result<AppConfig, String> {
-    // Load config from file or return default
-    Ok(AppConfig::default())
+#[specta::specta]
+async fn get_app_config(app_handle: tauri::AppHandle) -> Result<AppConfig, String> {
+    Ok(load_app_config(&app_handle))
 }
 
 #[tauri::command]
-async fn save_app_config(config: AppConfig) -> Result<(), String> {
-    // Save config to file
-    println!("Saving config: {:?}", config);
+#[specta::specta]
+async fn save_app_config(app_handle: tauri::AppHandle, mut config: AppConfig) -> Result<(), String> {
+    // Use switch_environment to change the active server
+    let current = load_app_config(&app_handle);
+    config.active_environment = current.active_environment;
+    environments::sync_server_url(&mut config)?;
+    let url_changed = config.server_url != current.server_url;
+    if url_changed && !server::responds(&config.server_url).await {
+        return Err(format!("{} is not responding", config.server_url));
+    }
+    storage::save(&app_handle, APP_CONFIG_FILE, &config)?;
+    if url_changed {
+        server::set_url(&app_handle, &config.server_url);
+    }
+    theme::apply(&app_handle, &config.theme)?;
     Ok(())
 }
 
 #[tauri::command]
-async fn open_external_url(url: String) -> Result<(), String> {
+#[specta::specta]
+async fn open_external_url(app_handle: tauri::AppHandle, url: String) -> Result<(), String> {
+    kiosk::ensure_inactive(&app_handle)?;
     tauri::api::shell::open(&tauri::api::shell::Scope::default(), url, None)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
+#[specta::specta]
 async fn get_system_info() -> Result<HashMap<String, String>, String> {
     let mut info = HashMap::new();
     
@@ -66,55 +207,77 @@ async fn get_system_info() -> Result<HashMap<String, String>, String> {
 }
 
 #[tauri::command]
-async fn create_new_window(app_handle: tauri::AppHandle, url: Option<String>) -> Result<(), String> {
+#[specta::specta]
+pub(crate) async fn create_new_window(
+    app_handle: tauri::AppHandle,
+    url: Option<String>,
+    container_id: Option<String>,
+) -> Result<(), String> {
+    kiosk::ensure_inactive(&app_handle)?;
+    idle::ensure_unlocked(&app_handle)?;
+    let url: Option<url::Url> = url
+        .map(|u| urlcleaner::clean(&app_handle, &u).parse())
+        .transpose()
+        .map_err(|e| format!("Invalid URL: {}", e))?;
+
+    if let Some(container_id) = container_id {
+        let window_url = match url {
+            Some(u) => WindowUrl::External(u),
+            None => WindowUrl::App("index.html".into()),
+        };
+        containers::open_window(&app_handle, window_url, &container_id)?;
+        return Ok(());
+    }
+
+    // Prefer a pre-warmed window; it already has the app shell loaded
+    if let Some(window) = windowpool::take(&app_handle) {
+        if let Some(url) = &url {
+            window
+                .eval(&format!("location.replace({})", serde_json::to_string(url.as_str()).unwrap_or_default()))
+                .map_err(|e| e.to_string())?;
+        }
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        windowpool::refill(&app_handle);
+        return Ok(());
+    }
+
     let window_url = match url {
-        Some(u) => WindowUrl::External(u.parse().map_err(|e| format!("Invalid URL: {}", e))?),
+        Some(u) => WindowUrl::External(u),
         None => WindowUrl::App("index.html".into()),
     };
-    
-    WindowBuilder::new(
-        &app_handle,
-        format!("window_{}", chrono::Utc::now().timestamp()),
-        window_url,
-    )
-    .title("MadEasy Browser")
-    .inner_size(1200.0, 800.0)
-    .min_inner_size(800.0, 600.0)
-    .build()
-    .map_err(|e| e.to_string())?;
-    
+    windowpool::build_window(&app_handle, window_url, true)?;
+    windowpool::refill(&app_handle);
     Ok(())
 }
 
 #[tauri::command]
+#[specta::specta]
 async fn minimize_to_tray(window: Window) -> Result<(), String> {
     window.hide().map_err(|e| e.to_string())?;
     Ok(())
 }
 
-#[tauri::command]
-async fn show_notification(title: String, body: String) -> Result<(), String> {
-    tauri::api::notification::Notification::new("com.madeasy.browser")
-        .title(&title)
-        .body(&body)
-        .show()
-        .map_err(|e| e.to_string())?;
-    
-    Ok(())
-}
-
 // Create application menu
-fn create_menu() -> Menu {
-    let quit = CustomMenuItem::new("quit".to_string(), "Quit");
-    let close = CustomMenuItem::new("close".to_string(), "Close");
-    let new_window = CustomMenuItem::new("new_window".to_string(), "New Window");
-    let about = CustomMenuItem::new("about".to_string(), "About");
-    let settings = CustomMenuItem::new("settings".to_string(), "Settings");
+pub(crate) fn create_menu(locale: &str) -> Menu {
+    let t = |id| i18n::lookup(locale, id, &[]);
+    let quit = CustomMenuItem::new("quit".to_string(), t("menu-quit"));
+    let close = CustomMenuItem::new("close".to_string(), t("menu-close"));
+    let new_window = CustomMenuItem::new("new_window".to_string(), t("menu-new-window"));
+    let reopen_closed =
+        CustomMenuItem::new("reopen_closed_window".to_string(), t("menu-reopen-closed-window"))
+            .accelerator(shortcuts::MENU_REOPEN_CLOSED_WINDOW);
+    let share_page = CustomMenuItem::new(share::MENU_ITEM_ID.to_string(), t("menu-share-page"));
+    let about = CustomMenuItem::new("about".to_string(), t("menu-about"));
+    let settings = CustomMenuItem::new("settings".to_string(), t("menu-settings"));
     
     let submenu = Submenu::new(
-        "File",
+        t("menu-file"),
         Menu::new()
             .add_item(new_window)
+            .add_item(reopen_closed)
+            .add_native_item(MenuItem::Separator)
+            .add_item(share_page)
             .add_native_item(MenuItem::Separator)
             .add_item(settings)
             .add_native_item(MenuItem::Separator)
@@ -122,33 +285,38 @@ fn create_menu() -> Menu {
             .add_item(quit),
     );
     
-    let help_submenu = Submenu::new("Help", Menu::new().add_item(about));
+    let devtools = CustomMenuItem::new(devtools::MENU_ITEM_ID.to_string(), t("menu-devtools"));
+    let view_submenu = Submenu::new(t("menu-view"), Menu::new().add_item(devtools));
+    
+    let next_window = CustomMenuItem::new("next_window".to_string(), t("menu-next-window"))
+        .accelerator(shortcuts::MENU_NEXT_WINDOW);
+    let previous_window = CustomMenuItem::new("previous_window".to_string(), t("menu-previous-window"))
+        .accelerator(shortcuts::MENU_PREVIOUS_WINDOW);
+    let window_submenu = Submenu::new(
+        t("menu-window"),
+        Menu::new().add_item(next_window).add_item(previous_window),
+    );
+
+    let help_submenu = Submenu::new(t("menu-help"), Menu::new().add_item(about));
     
     Menu::new()
         .add_submenu(submenu)
+        .add_submenu(view_submenu)
+        .add_submenu(window_submenu)
         .add_submenu(help_submenu)
 }
 
-// Create system tray
-fn create_system_tray() -> SystemTray {
-    let quit = CustomMenuItem::new("quit".to_string(), "Quit");
-    let hide = CustomMenuItem::new("hide".to_string(), "Hide");
-    let show = CustomMenuItem::new("show".to_string(), "Show");
-    let new_window = CustomMenuItem::new("new_window".to_string(), "New Window");
-    
-    let tray_menu = SystemTrayMenu::new()
-        .add_item(show)
-        .add_item(hide)
-        .add_native_item(SystemTrayMenuItem::Separator)
-        .add_item(new_window)
-        .add_native_item(SystemTrayMenuItem::Separator)
-        .add_item(quit);
-    
-    SystemTray::new().with_menu(tray_menu)
-}
-
 // Handle system tray events
 fn handle_system_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
+    // The tray menu is empty in kiosk mode; clicks must not reveal other windows
+    if kiosk::active(app) {
+        return;
+    }
+    // While locked the main window shows the lock screen; only quitting works
+    let quit = matches!(&event, SystemTrayEvent::MenuItemClick { id, .. } if id == "quit");
+    if idle::locked(app) && !quit {
+        return;
+    }
     match event {
         SystemTrayEvent::LeftClick {
             position: _,
@@ -161,7 +329,7 @@ fn handle_system_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
         }
         SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
             "quit" => {
-                std::process::exit(0);
+                shutdown::request(app, false);
             }
             "hide" => {
                 let window = app.get_window("main").unwrap();
@@ -173,9 +341,9 @@ fn handle_system_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
                 window.set_focus().unwrap();
             }
             "new_window" => {
-                let _ = create_new_window(app.clone(), None);
+                tauri::async_runtime::spawn(create_new_window(app.clone(), None, None));
             }
-            _ => {}
+            other => tray::dispatch_click(app, other),
         },
         _ => {}
     }
@@ -183,22 +351,60 @@ fn handle_system_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
 
 // Handle menu events
 fn handle_menu_event(event: tauri::WindowMenuEvent) {
+    let app = event.window().app_handle();
+    if kiosk::active(&app) || (idle::locked(&app) && event.menu_item_id() != "quit") {
+        return;
+    }
     match event.menu_item_id() {
         "quit" => {
-            std::process::exit(0);
+            shutdown::request(&event.window().app_handle(), false);
         }
         "close" => {
             event.window().close().unwrap();
         }
         "new_window" => {
-            let _ = create_new_window(event.window().app_handle(), None);
+            tauri::async_runtime::spawn(create_new_window(event.window().app_handle(), None, None));
+        }
+        "reopen_closed_window" => {
+            if let Err(e) = closedwindows::reopen(&event.window().app_handle()) {
+                eprintln!("{}", e);
+            }
+        }
+        share::MENU_ITEM_ID => {
+            let window = event.window().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = share::share_page(window.app_handle(), window).await {
+                    eprintln!("Failed to share page: {}", e);
+                }
+            });
+        }
+        "next_window" | "previous_window" => {
+            let direction = if event.menu_item_id() == "next_window" {
+                closedwindows::CycleDirection::Next
+            } else {
+                closedwindows::CycleDirection::Previous
+            };
+            if let Err(e) = closedwindows::cycle(&event.window().app_handle(), direction) {
+                eprintln!("{}", e);
+            }
         }
         "about" => {
-            let _ = show_notification(
-                "About MadEasy Browser".to_string(),
-                "MadEasy Browser v3.0.0\nBuilt with Tauri and Rust".to_string(),
+            let app = event.window().app_handle();
+            let version = app.package_info().version.to_string();
+            let _ = notifications::notify(
+                &app,
+                notifications::Notice::new(
+                    notifications::NotificationCategory::System,
+                    i18n::text(&app, "notice-about-title"),
+                    i18n::text_with(&app, "notice-about-body", &[("version", version.into())]),
+                ),
             );
         }
+        devtools::MENU_ITEM_ID => {
+            if let Err(e) = devtools::toggle(event.window()) {
+                eprintln!("{}", e);
+            }
+        }
         "settings" => {
             // Open settings window or navigate to settings page
             println!("Settings clicked");
@@ -207,14 +413,218 @@ fn handle_menu_event(event: tauri::WindowMenuEvent) {
     }
 }
 
+// Handle events for every window
+fn handle_window_event(event: tauri::GlobalWindowEvent) {
+    let window = event.window();
+    match event.event() {
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+            monitors::remember_placement(window);
+        }
+        tauri::WindowEvent::CloseRequested { .. } => {
+            monitors::save_placements(&window.app_handle());
+            closedwindows::on_close_requested(window);
+        }
+        tauri::WindowEvent::Focused(focused) => {
+            hibernation::on_focus_changed(window, *focused);
+        }
+        tauri::WindowEvent::Destroyed => {
+            monitors::save_placements(&window.app_handle());
+            resources::forget_window(&window.app_handle(), window.label());
+            hibernation::forget_window(&window.app_handle(), window.label());
+            windowpool::forget_window(&window.app_handle(), window.label());
+            macros::forget_window(&window.app_handle(), window.label());
+            geolocation::forget_window(&window.app_handle(), window.label());
+            emulation::forget_window(&window.app_handle(), window.label());
+            containers::forget_window(&window.app_handle(), window.label());
+            bulkopen::forget_window(&window.app_handle(), window.label());
+            workspaces::forget_window(&window.app_handle(), window.label());
+            pageerrors::forget_window(&window.app_handle(), window.label());
+            closedwindows::forget_window(&window.app_handle(), window.label());
+        }
+        tauri::WindowEvent::ThemeChanged(_) => {
+            theme::system_theme_changed(&window.app_handle());
+        }
+        _ => {}
+    }
+}
+
+// Inject page-level helpers after every navigation
+fn handle_page_load(window: Window, payload: tauri::PageLoadPayload) {
+    pagebridge::on_page_load(&window, payload.url());
+    urlcleaner::inject(&window);
+    gestures::inject(&window);
+    macros::inject(&window);
+    contextmenu::inject(&window);
+    pageerrors::inject(&window);
+    history::inject(&window);
+    spellcheck::inject(&window);
+    feeds::inject(&window);
+    thumbnails::on_page_load(&window, payload.url());
+    zoom::on_page_load(&window, payload.url());
+    plugins::on_page_load(&window, payload.url());
+    userscripts::inject(&window, payload.url());
+    theme::on_page_load(&window);
+    offline_cache::on_page_load(&window, payload.url());
+    pagemetrics::on_page_load(&window, payload.url());
+    geolocation::on_page_load(&window);
+    emulation::on_page_load(&window);
+    kiosk::on_page_load(&window);
+    policy::on_page_load(&window, payload.url());
+    bulkopen::on_page_load(&window);
+    safemode::on_page_load(&window, payload.url());
+    events::publish(
+        &window.app_handle(),
+        events::AppEvent::NavigationFinished {
+            window_id: window.label().to_string(),
+            url: payload.url().to_string(),
+        },
+    );
+}
+
 // Application setup
 fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    // Heavier subsystems are deferred until after first paint; see startup.rs
+    app.manage(startup::StartupState::default());
+    app.manage(events::EventBus::default());
+    events::subscribe(&app.handle(), startup::on_event);
+    app.manage(tasks::TaskRegistry::default());
+    app.manage(shutdown::ShutdownState::default());
+    app.manage(payloads::PayloadStore::default());
+    // Decides whether plugins, userscripts and imported themes load at all
+    app.manage(safemode::detect(&app.handle()));
+    startup::phase(&app.handle(), "pending restore", || backup::apply_pending_restore(&app.handle()));
+    startup::phase(&app.handle(), "window state", || {
+        app.manage(i18n::I18nState::load(&app.handle()));
+        app.manage(monitors::PlacementState::load(&app.handle()));
+        app.manage(shortcuts::ShortcutState::load(&app.handle()));
+        shortcuts::register_all(&app.handle());
+        app.manage(gestures::GestureState::load(&app.handle()));
+        app.manage(contextmenu::ContextMenuState::default());
+        contextmenu::register_builtin(&app.handle());
+        app.manage(pagebridge::PageBridgeState::default());
+        app.manage(pageerrors::PageErrorState::default());
+        app.manage(closedwindows::ClosedWindowState::default());
+        app.manage(tray::TrayState::default());
+    });
+    startup::phase(&app.handle(), "storage", || -> Result<(), String> {
+        app.manage(db::Database::open(&app.handle())?);
+        app.manage(notifications::NotificationHandlers::default());
+        share::register(&app.handle());
+        app.manage(backup::BackupState::load(&app.handle()));
+        app.manage(bandwidth::BandwidthState::load(&app.handle()));
+        events::subscribe(&app.handle(), bandwidth::on_event);
+        events::subscribe(&app.handle(), workflow::on_event);
+        backup::start_scheduler(&app.handle());
+        Ok(())
+    })?;
+    startup::phase(&app.handle(), "system monitors", || {
+        app.manage(dnd::Dnd::load(&app.handle()));
+        dnd::start_monitor(&app.handle());
+        app.manage(power::PowerGuards::default());
+        events::subscribe(&app.handle(), power::on_event);
+        app.manage(battery::BatteryState::load(&app.handle()));
+        battery::start_monitor(&app.handle());
+        app.manage(network::NetworkState::load(&app.handle()));
+        network::start_monitor(&app.handle());
+        app.manage(resources::ResourceMonitor::default());
+        resources::start_monitor(&app.handle());
+        app.manage(taskmanager::TaskManager::default());
+        taskmanager::start_monitor(&app.handle());
+        app.manage(hibernation::HibernationState::load(&app.handle()));
+        hibernation::start_monitor(&app.handle());
+        events::subscribe(&app.handle(), hibernation::on_event);
+        app.manage(idle::IdleState::load(&app.handle()));
+        idle::start_monitor(&app.handle());
+    });
+    startup::phase(&app.handle(), "browsing state", || {
+        app.manage(thumbnails::ThumbnailState::load(&app.handle()));
+        app.manage(speeddial::SpeedDialState::load(&app.handle()));
+        app.manage(profiles::ProfileState::load(&app.handle()));
+        app.manage(jumplist::JumpListState::load(&app.handle()));
+        app.manage(outbound::OutboundState::load(&app.handle()));
+        app.manage(ai::AiState::load(&app.handle()));
+        app.manage(omnibox::OmniboxState::load(&app.handle()));
+        app.manage(translation::TranslationState::load(&app.handle()));
+        spellcheck::apply(&app.handle());
+        app.manage(find::FindState::default());
+        app.manage(pagequery::PageQueryState::default());
+        app.manage(macros::MacroState::default());
+        app.manage(geolocation::GeolocationState::default());
+        app.manage(emulation::EmulationState::default());
+        app.manage(kiosk::KioskState::load(&app.handle()));
+        app.manage(policy::PolicyState::load());
+        app.manage(containers::ContainerState::load(&app.handle()));
+        app.manage(bulkopen::BulkOpenState::default());
+        app.manage(workspaces::WorkspaceState::load(&app.handle()));
+        app.manage(downloads::DownloadState::load(&app.handle()));
+        app.manage(scanner::ScannerState::load(&app.handle()));
+        app.manage(content_settings::ContentSettingsState::default());
+        events::subscribe(&app.handle(), downloads::on_event);
+        app.manage(pagemetrics::PageMetricsState::load(&app.handle()));
+        app.manage(zoom::ZoomState::load(&app.handle()));
+        app.manage(devtools::DevtoolsState::load(&app.handle()));
+        feeds::start_poller(&app.handle());
+    });
+    startup::phase(&app.handle(), "integrations", || {
+        app.manage(email::EmailState::load(&app.handle()));
+        app.manage(upload::UploadState::load(&app.handle()));
+        app.manage(s3::S3State::load(&app.handle()));
+        app.manage(enrichment::EnrichmentState::load(&app.handle()));
+        app.manage(artifacts::ArtifactState::load(&app.handle()));
+        app.manage(vault::VaultState::load(&app.handle()));
+        app.manage(totp::TotpState::load(&app.handle()));
+        app.manage(workflow_git::WorkflowGitState::load(&app.handle()));
+        app.manage(digest::DigestState::load(&app.handle()));
+        digest::start_scheduler(&app.handle());
+    });
+    startup::phase(&app.handle(), "extensions", || {
+        app.manage(plugins::PluginState::default());
+        app.manage(plugin_registry::RegistryState::load(&app.handle()));
+        app.manage(userscripts::UserscriptState::load(&app.handle()));
+        app.manage(urlcleaner::CleanerState::load(&app.handle()));
+        app.manage(windowpool::WindowPoolState::load(&app.handle()));
+    });
+    let config = startup::phase(&app.handle(), "theme", || {
+        app.manage(theme::ThemeState::load(&app.handle()));
+        let config = load_app_config(&app.handle());
+        if let Err(e) = theme::apply(&app.handle(), &config.theme) {
+            eprintln!("Failed to apply theme: {}", e);
+        }
+        theme::start_watcher(&app.handle());
+        config
+    });
+    startup::phase(&app.handle(), "server", || {
+        app.manage(server::ServerState::new(&config.server_url));
+        server::start(&app.handle());
+        app.manage(health::HealthState::default());
+        app.manage(offline_cache::OfflineCacheState::load(&app.handle()));
+        health::start_monitor(&app.handle());
+    });
+    startup::phase(&app.handle(), "clipboard", || {
+        app.manage(clipboard::ClipboardState::load(&app.handle()));
+        clipboard::start_watcher(&app.handle());
+    });
+    for window in app.windows().values() {
+        devtools::refresh_menu(window);
+    }
+    
     // Get the main window
     let main_window = app.get_window("main").unwrap();
     
     // Set window properties
     main_window.set_title("MadEasy Browser")?;
     
+    // Put the window back on the monitor it was last used on
+    if let Err(e) = monitors::restore_placement(&main_window) {
+        eprintln!("Failed to restore window placement: {}", e);
+    }
+    
+    // Handle jump list / dock arguments passed to the first instance
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for action in launch::parse_args(&args) {
+        launch::dispatch(&app.handle(), action);
+    }
+    
     // Setup window event handlers
     let window = main_window.clone();
     main_window.on_window_event(move |event| match event {
@@ -226,27 +636,391 @@ fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         _ => {}
     });
     
+    startup::finish_setup(&app.handle());
     Ok(())
 }
 
+// Every command the frontend can invoke. The same list feeds the invoke
+// handler and the generated TypeScript bindings, so they cannot drift apart.
+macro_rules! commands {
+    ($($generate:tt)+) => {
+        $($generate)+![
+            get_app_config,
+            save_app_config,
+            open_external_url,
+            get_system_info,
+            create_new_window,
+            minimize_to_tray,
+            monitors::list_monitors,
+            monitors::move_window_to_monitor,
+            shortcuts::list_shortcuts,
+            shortcuts::set_shortcut,
+            gestures::get_gesture_config,
+            gestures::set_gesture_config,
+            gestures::gesture_performed,
+            contextmenu::register_context_menu_item,
+            contextmenu::unregister_context_menu_item,
+            contextmenu::list_context_menu_items,
+            contextmenu::context_menu_opened,
+            contextmenu::context_menu_item_clicked,
+            tray::update_tray_menu,
+            tray::set_tray_badge,
+            tray::set_tray_status,
+            history::record_history_visit,
+            history::get_recent_history,
+            jumplist::set_pinned_workflows,
+            notifications::show_notification,
+            notifications::list_notifications,
+            notifications::mark_notification_read,
+            notifications::mark_all_notifications_read,
+            notifications::clear_notifications,
+            notifications::activate_notification,
+            notifications::press_notification_button,
+            dnd::get_dnd_state,
+            dnd::set_dnd_config,
+            power::get_power_guards,
+            power::acquire_power_guard,
+            power::release_power_guard,
+            battery::get_battery_status,
+            battery::set_battery_policy,
+            network::get_network_status,
+            network::set_network_policy,
+            resources::get_resource_usage,
+            resources::report_window_memory,
+            taskmanager::webview_pong,
+            taskmanager::list_window_processes,
+            taskmanager::kill_window_process,
+            cache::get_cache_usage,
+            cache::clear_cache,
+            favicons::get_favicon,
+            thumbnails::get_page_thumbnail,
+            thumbnails::set_thumbnail_settings,
+            speeddial::get_speed_dial,
+            speeddial::update_speed_dial,
+            speeddial::set_speed_dial_background,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::switch_profile,
+            search::list_search_engines,
+            search::save_search_engine,
+            search::delete_search_engine,
+            search::set_default_search_engine,
+            search::resolve_search,
+            bookmarks::add_bookmark,
+            bookmarks::remove_bookmark,
+            bookmarks::list_bookmarks,
+            omnibox::get_omnibox_suggestions,
+            omnibox::get_omnibox_settings,
+            omnibox::set_omnibox_settings,
+            ai::get_ai_config,
+            ai::set_ai_config,
+            ai::stream_ai_completion,
+            translation::translate_text,
+            translation::translate_page,
+            translation::restore_page_translation,
+            translation::submit_page_text,
+            translation::get_translation_config,
+            translation::set_translation_config,
+            spellcheck::get_spellcheck_config,
+            spellcheck::set_spellcheck_config,
+            spellcheck::list_dictionary_words,
+            spellcheck::add_dictionary_word,
+            spellcheck::remove_dictionary_word,
+            find::find_in_page,
+            find::find_next,
+            find::stop_find,
+            find::report_find_result,
+            zoom::get_zoom,
+            zoom::set_zoom,
+            zoom::reset_zoom_all,
+            print::list_printers,
+            print::print_page,
+            devtools::toggle_devtools,
+            devtools::get_devtools_config,
+            devtools::set_devtools_config,
+            readinglist::save_reading_item,
+            readinglist::list_reading_items,
+            readinglist::open_reading_item,
+            readinglist::set_reading_item_read,
+            readinglist::set_reading_item_tags,
+            readinglist::delete_reading_item,
+            feeds::feeds_detected,
+            feeds::subscribe_feed,
+            feeds::unsubscribe_feed,
+            feeds::list_feeds,
+            feeds::list_feed_entries,
+            feeds::mark_feed_entry_read,
+            feeds::refresh_feeds,
+            workflow::run_workflow_action,
+            email::get_smtp_config,
+            email::set_smtp_config,
+            email::test_smtp_connection,
+            email::send_report_email,
+            upload::list_upload_targets,
+            upload::save_upload_target,
+            upload::delete_upload_target,
+            upload::test_sftp_connection,
+            upload::upload_files,
+            s3::get_s3_config,
+            s3::set_s3_config,
+            s3::upload_to_s3,
+            validation::validate_rows,
+            enrichment::lookup_company,
+            enrichment::get_enrichment_config,
+            enrichment::set_enrichment_config,
+            datasets::list_datasets,
+            datasets::get_dataset_rows,
+            datasets::get_dataset_payload,
+            datasets::delete_dataset,
+            dedupe::dedupe_dataset,
+            artifacts::save_artifact,
+            artifacts::list_artifacts,
+            artifacts::open_artifact,
+            artifacts::prune_artifacts,
+            artifacts::get_retention_rules,
+            artifacts::set_retention_rules,
+            audit::query_audit_log,
+            audit::verify_audit_log,
+            vault::list_vault_secrets,
+            vault::set_vault_secret,
+            vault::delete_vault_secret,
+            workflow_defs::list_workflows,
+            workflow_defs::get_workflow,
+            workflow_defs::save_workflow,
+            workflow_defs::delete_workflow,
+            workflow_defs::run_workflow,
+            workflow_git::get_workflow_git_config,
+            workflow_git::set_workflow_git_config,
+            workflow_git::list_workflow_versions,
+            workflow_git::diff_workflow_versions,
+            workflow_git::rollback_workflow,
+            visualdiff::check_monitor_visual,
+            visualdiff::list_monitor_checks,
+            visualdiff::get_monitor_diff,
+            vision::extract_with_vision,
+            plugins::list_plugins,
+            plugins::install_plugin,
+            plugins::enable_plugin,
+            plugins::set_plugin_permissions,
+            plugins::uninstall_plugin,
+            plugins::run_plugin_command,
+            plugin_registry::get_plugin_registry_config,
+            plugin_registry::set_plugin_registry_config,
+            plugin_registry::search_plugin_registry,
+            plugin_registry::check_plugin_updates,
+            plugin_registry::install_plugin_from_registry,
+            userscripts::list_userscripts,
+            userscripts::save_userscript,
+            userscripts::import_userscript,
+            userscripts::set_userscript_enabled,
+            userscripts::delete_userscript,
+            theme::get_theme,
+            theme::list_themes,
+            theme::import_theme,
+            theme::delete_theme,
+            server::server_status,
+            server::restart_server,
+            health::backend_status,
+            health::check_backend_now,
+            offline_cache::get_offline_cache_config,
+            offline_cache::set_offline_cache_config,
+            offline_cache::get_offline_copy,
+            environments::switch_environment,
+            environments::set_environment_secret,
+            environments::get_environment_auth_header,
+            oauth::start_oauth_flow,
+            oauth::get_oauth_grant,
+            oauth::delete_oauth_token,
+            totp::list_totp_accounts,
+            totp::add_totp_account,
+            totp::delete_totp_account,
+            totp::get_totp_code,
+            clipboard::get_clipboard_config,
+            clipboard::set_clipboard_config,
+            clipboard::get_clipboard_history,
+            clipboard::paste_item,
+            clipboard::set_clipboard_item_pinned,
+            clipboard::delete_clipboard_item,
+            clipboard::clear_clipboard_history,
+            urlcleaner::clean_url,
+            urlcleaner::get_url_cleaner_config,
+            urlcleaner::set_url_cleaner_config,
+            urlcleaner::update_url_cleaner_rules,
+            linkpreview::fetch_link_preview,
+            linkcheck::check_links,
+            crawler::crawl_site,
+            pagequery::report_page_query,
+            seo::audit_seo,
+            a11y::audit_accessibility,
+            pagemetrics::get_page_metrics,
+            pagemetrics::get_page_metrics_config,
+            pagemetrics::set_page_metrics_config,
+            pagemetrics::list_network_log,
+            pagemetrics::clear_network_log,
+            hibernation::get_hibernation_config,
+            hibernation::set_hibernation_config,
+            hibernation::list_hibernated_windows,
+            hibernation::hibernate_window,
+            hibernation::restore_hibernated_window,
+            startup::get_startup_report,
+            tasks::list_tasks,
+            tasks::cancel_task,
+            windowpool::get_window_pool_config,
+            windowpool::set_window_pool_config,
+            payloads::release_payload,
+            events::publish_app_event,
+            scripting::list_scripts,
+            scripting::get_script,
+            scripting::save_script,
+            scripting::delete_script,
+            scripting::run_script,
+            macros::record_macro,
+            macros::macro_input,
+            macros::stop_macro_recording,
+            macros::list_macros,
+            macros::delete_macro,
+            macros::play_macro,
+            backup::get_backup_config,
+            backup::set_backup_config,
+            backup::set_backup_passphrase,
+            backup::has_backup_passphrase,
+            backup::create_backup,
+            backup::list_backups,
+            backup::verify_backup,
+            backup::restore_backup,
+            profile_archive::export_profile_archive,
+            profile_archive::import_profile_archive,
+            i18n::get_locale,
+            i18n::set_locale,
+            geolocation::set_geolocation_override,
+            geolocation::clear_geolocation_override,
+            geolocation::get_geolocation_override,
+            regional::get_regional_overrides,
+            regional::set_regional_overrides,
+            emulation::list_device_presets,
+            emulation::get_device_emulation,
+            emulation::emulate_device,
+            emulation::clear_device_emulation,
+            kiosk::get_kiosk_status,
+            kiosk::set_kiosk_allowed_origins,
+            kiosk::set_kiosk_pin,
+            kiosk::enter_kiosk_mode,
+            kiosk::exit_kiosk_mode,
+            shutdown::shutdown,
+            policy::get_navigation_policy,
+            policy::set_navigation_policy,
+            policy::set_policy_pin,
+            policy::bypass_navigation_policy,
+            containers::list_containers,
+            containers::create_container,
+            containers::delete_container,
+            containers::get_window_container,
+            bulkopen::open_url_batch,
+            workspaces::list_workspaces,
+            workspaces::create_workspace,
+            workspaces::delete_workspace,
+            workspaces::add_window_to_workspace,
+            workspaces::remove_window_from_workspace,
+            workspaces::save_workspace,
+            workspaces::restore_workspace,
+            workspaces::switch_workspace,
+            downloads::get_download_rules,
+            downloads::set_download_rules,
+            downloads::resolve_download_path,
+            downloads::list_downloads,
+            downloads::verify_download,
+            downloads::scan_download,
+            downloads::open_download,
+            scanner::get_scanner_config,
+            scanner::set_scanner_config,
+            content_settings::get_content_settings,
+            content_settings::set_content_setting,
+            fingerprint::get_fingerprint_settings,
+            fingerprint::set_fingerprint_settings,
+            fingerprint::get_fingerprint_report,
+            idle::get_idle_time,
+            idle::get_idle_config,
+            idle::set_idle_config,
+            idle::set_app_lock_pin,
+            idle::lock_app,
+            idle::unlock_app,
+            vision::ask_about_region,
+            workflow_gen::generate_workflow_from_prompt,
+            digest::list_digest_topics,
+            digest::save_digest_topic,
+            digest::delete_digest_topic,
+            digest::run_digest_now,
+            digest::list_digests,
+            contextmenu::ask_ai_about_selection,
+            outbound::get_outbound_config,
+            outbound::set_outbound_config,
+            outbound::clear_outbound_cache,
+            ai::get_ai_routing,
+            ai::set_ai_routing,
+            ai::get_ai_usage,
+            ai::reset_ai_usage,
+            pageerrors::report_page_errors,
+            pageerrors::get_page_errors,
+            pageerrors::clear_page_errors,
+            closedwindows::reopen_closed_window,
+            closedwindows::list_closed_windows,
+            closedwindows::cycle_windows,
+            safemode::get_safe_mode_status,
+            safemode::reset_subsystem,
+            safemode::exit_safe_mode,
+            bandwidth::get_bandwidth_stats,
+            bandwidth::get_bandwidth_config,
+            bandwidth::set_bandwidth_config,
+            share::share_content
+        ]
+    };
+}
+
+// Regenerate the frontend's TypeScript bindings on debug runs
+#[cfg(debug_assertions)]
+fn export_bindings() {
+    const COMMAND_BINDINGS: &str = "../client/src/types/tauri-commands.ts";
+    const EVENT_BINDINGS: &str = "../client/src/types/tauri-events.ts";
+    // Timestamps and sizes are i64/u64; they fit in a JS number in practice
+    let config = specta::ts::ExportConfiguration::default().bigint(specta::ts::BigIntExportBehavior::Number);
+    if let Err(e) = tauri_specta::ts::export_with_cfg(commands!(specta::collect_types), config.clone(), COMMAND_BINDINGS) {
+        eprintln!("Failed to export command bindings: {}", e);
+    }
+    let events = events::typescript(&config).and_then(|ts| std::fs::write(EVENT_BINDINGS, ts).map_err(|e| e.to_string()));
+    if let Err(e) = events {
+        eprintln!("Failed to export event bindings: {}", e);
+    }
+}
+
 fn main() {
+    #[cfg(debug_assertions)]
+    export_bindings();
+
     let context = tauri::generate_context!();
+    let locale = i18n::initial_locale(context.config());
     
     tauri::Builder::default()
-        .menu(create_menu())
-        .system_tray(create_system_tray())
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            launch::handle_args(app, &argv);
+        }))
+        .menu(create_menu(&locale))
+        .system_tray(tray::create_system_tray(&locale))
         .on_system_tray_event(handle_system_tray_event)
         .on_menu_event(handle_menu_event)
+        .on_window_event(handle_window_event)
+        .on_page_load(handle_page_load)
+        .register_uri_scheme_protocol(appscheme::APP_SCHEME, appscheme::serve)
+        .register_uri_scheme_protocol(health::OFFLINE_SCHEME, health::serve_offline_page)
+        .register_uri_scheme_protocol(offline_cache::CACHE_SCHEME, offline_cache::serve)
+        .register_uri_scheme_protocol(payloads::PAYLOAD_SCHEME, payloads::serve)
+        .register_uri_scheme_protocol(policy::BLOCKED_SCHEME, policy::serve_blocked_page)
         .setup(setup_app)
-        .invoke_handler(tauri::generate_handler![
-            get_app_config,
-            save_app_config,
-            open_external_url,
-            get_system_info,
-            create_new_window,
-            minimize_to_tray,
-            show_notification
-        ])
-        .run(context)
-        .expect("error while running tauri application");
+        .invoke_handler(pagebridge::guard(commands!(tauri::generate_handler)))
+        .build(context)
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                server::shutdown(app);
+            }
+        });
 }
\ No newline at end of file
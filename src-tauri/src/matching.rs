@@ -0,0 +1,53 @@
+// Wildcard URL pattern matching shared by menus, scripts and policies
+
+// Match text against a pattern where `*` matches any run of characters (case-insensitive)
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let mut rest = text.as_str();
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+
+    true
+}
+
+// True when any of the patterns match; an empty list matches everything
+pub fn any_match(patterns: &[String], text: &str) -> bool {
+    patterns.is_empty() || patterns.iter().any(|p| wildcard_match(p, text))
+}
+
+// The same match as a JavaScript regular expression, for checks that run in
+// page scripts before Rust sees the URL; use it with the `i` flag
+pub fn wildcard_js_regex(pattern: &str) -> String {
+    let escaped: String = pattern
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            c if "\\^$.|?+()[]{}/".contains(c) => format!("\\{}", c),
+            c => c.to_string(),
+        })
+        .collect();
+    format!("^{}$", escaped)
+}
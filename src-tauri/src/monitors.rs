@@ -0,0 +1,237 @@
+// Multi-monitor aware window placement
+// Remembers which monitor each window lived on and restores it on the next launch
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Window};
+
+use crate::storage;
+
+const PLACEMENTS_FILE: &str = "window-placements.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MonitorInfo {
+    index: usize,
+    name: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+    is_primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct SavedPlacement {
+    monitor: Option<String>,
+    // Offset relative to the monitor origin, so placements survive monitor rearrangement
+    offset_x: i32,
+    offset_y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Default)]
+pub struct PlacementState {
+    placements: Mutex<HashMap<String, SavedPlacement>>,
+}
+
+impl PlacementState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            placements: Mutex::new(storage::load(app, PLACEMENTS_FILE)),
+        }
+    }
+
+    fn persist(&self, app: &AppHandle) -> Result<(), String> {
+        let placements = self.placements.lock().unwrap();
+        storage::save(app, PLACEMENTS_FILE, &*placements)
+    }
+}
+
+fn monitor_info(index: usize, monitor: &Monitor, primary: Option<&Monitor>) -> MonitorInfo {
+    MonitorInfo {
+        index,
+        name: monitor.name().cloned(),
+        x: monitor.position().x,
+        y: monitor.position().y,
+        width: monitor.size().width,
+        height: monitor.size().height,
+        scale_factor: monitor.scale_factor(),
+        is_primary: primary.map_or(false, |p| same_monitor(p, monitor)),
+    }
+}
+
+fn same_monitor(a: &Monitor, b: &Monitor) -> bool {
+    a.name() == b.name() && a.position() == b.position()
+}
+
+// Find the monitor containing the given point, if any
+fn monitor_at(monitors: &[Monitor], point: PhysicalPosition<i32>) -> Option<&Monitor> {
+    monitors.iter().find(|m| {
+        let pos = m.position();
+        let size = m.size();
+        point.x >= pos.x
+            && point.y >= pos.y
+            && point.x < pos.x + size.width as i32
+            && point.y < pos.y + size.height as i32
+    })
+}
+
+// Place a window on a monitor at the given offset, clamped so it stays fully visible
+fn place_on_monitor(
+    window: &Window,
+    monitor: &Monitor,
+    offset: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+) -> Result<(), String> {
+    let origin = monitor.position();
+    let bounds = monitor.size();
+    let width = size.width.min(bounds.width);
+    let height = size.height.min(bounds.height);
+    let max_x = (bounds.width - width) as i32;
+    let max_y = (bounds.height - height) as i32;
+
+    window
+        .set_size(PhysicalSize::new(width, height))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(
+            origin.x + offset.x.clamp(0, max_x),
+            origin.y + offset.y.clamp(0, max_y),
+        ))
+        .map_err(|e| e.to_string())
+}
+
+// Centre a window on a monitor, used when its saved monitor is no longer connected
+fn center_on_monitor(window: &Window, monitor: &Monitor, size: PhysicalSize<u32>) -> Result<(), String> {
+    let bounds = monitor.size();
+    let offset = PhysicalPosition::new(
+        (bounds.width.saturating_sub(size.width) / 2) as i32,
+        (bounds.height.saturating_sub(size.height) / 2) as i32,
+    );
+    place_on_monitor(window, monitor, offset, size)
+}
+
+// Record the current monitor and geometry of a window (in memory only)
+pub fn remember_placement(window: &Window) {
+    let state = window.state::<PlacementState>();
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    // Minimized windows report bogus coordinates on some platforms
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let monitors = window.available_monitors().unwrap_or_default();
+    let monitor = monitor_at(&monitors, position);
+    let origin = monitor.map(|m| *m.position()).unwrap_or(PhysicalPosition::new(0, 0));
+
+    state.placements.lock().unwrap().insert(
+        window.label().to_string(),
+        SavedPlacement {
+            monitor: monitor.and_then(|m| m.name().cloned()),
+            offset_x: position.x - origin.x,
+            offset_y: position.y - origin.y,
+            width: size.width,
+            height: size.height,
+        },
+    );
+}
+
+// Flush remembered placements to disk
+pub fn save_placements(app: &AppHandle) {
+    if let Err(e) = app.state::<PlacementState>().persist(app) {
+        eprintln!("Failed to save window placements: {}", e);
+    }
+}
+
+// Recovery: forget all saved placements so windows open at their defaults
+pub fn reset_placements(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<PlacementState>();
+    state.placements.lock().unwrap().clear();
+    state.persist(app)
+}
+
+// Restore a window onto the monitor it was last seen on, falling back to the
+// primary monitor when that monitor has been disconnected
+pub fn restore_placement(window: &Window) -> Result<(), String> {
+    let saved = window
+        .state::<PlacementState>()
+        .placements
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .cloned();
+    let Some(saved) = saved else {
+        return Ok(());
+    };
+
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let size = PhysicalSize::new(saved.width, saved.height);
+    let target = saved
+        .monitor
+        .as_ref()
+        .and_then(|name| monitors.iter().find(|m| m.name() == Some(name)));
+
+    match target {
+        Some(monitor) => place_on_monitor(
+            window,
+            monitor,
+            PhysicalPosition::new(saved.offset_x, saved.offset_y),
+            size,
+        ),
+        None => match window.primary_monitor().map_err(|e| e.to_string())? {
+            Some(primary) => center_on_monitor(window, &primary, size),
+            None => Ok(()),
+        },
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_monitors(window: Window) -> Result<Vec<MonitorInfo>, String> {
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let primary = window.primary_monitor().map_err(|e| e.to_string())?;
+
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| monitor_info(index, monitor, primary.as_ref()))
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn move_window_to_monitor(
+    app_handle: AppHandle,
+    window_id: String,
+    monitor_index: usize,
+) -> Result<(), String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let target = monitors
+        .get(monitor_index)
+        .ok_or_else(|| format!("Monitor {} is not connected", monitor_index))?;
+
+    // Keep the window at the same relative offset on the new monitor
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let offset = match monitor_at(&monitors, position) {
+        Some(current) => PhysicalPosition::new(
+            position.x - current.position().x,
+            position.y - current.position().y,
+        ),
+        None => PhysicalPosition::new(0, 0),
+    };
+
+    place_on_monitor(&window, target, offset, size)?;
+    remember_placement(&window);
+    save_placements(&app_handle);
+    Ok(())
+}
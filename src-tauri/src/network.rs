@@ -0,0 +1,183 @@
+// Network connectivity monitoring
+// Emits `network-changed { online, interface, metered }` and pauses transfers
+// while offline or on a metered connection: webview downloads are paused and
+// resumed where the engine allows it (WebView2, see downloads.rs), background
+// jobs check `transfers_paused`, and the frontend gets `transfers-paused` /
+// `transfers-resumed` for its own downloads and sync.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{bandwidth, downloads, storage};
+
+const NETWORK_FILE: &str = "network-policy.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct NetworkPolicy {
+    pause_on_metered: bool,
+}
+
+impl Default for NetworkPolicy {
+    fn default() -> Self {
+        Self {
+            pause_on_metered: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Type)]
+pub struct NetworkStatus {
+    online: bool,
+    interface: Option<String>,
+    metered: bool,
+    transfers_paused: bool,
+}
+
+#[derive(Default)]
+pub struct NetworkState {
+    policy: Mutex<NetworkPolicy>,
+    last: Mutex<Option<NetworkStatus>>,
+}
+
+impl NetworkState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            policy: Mutex::new(storage::load(app, NETWORK_FILE)),
+            last: Mutex::new(None),
+        }
+    }
+}
+
+fn read_status(policy: &NetworkPolicy) -> NetworkStatus {
+    let interface = netdev::get_default_interface()
+        .ok()
+        .filter(|iface| iface.is_up() && iface.gateway.is_some());
+    let online = interface.is_some();
+    let name = interface.map(|iface| iface.friendly_name.unwrap_or(iface.name));
+    let metered = online && platform::is_metered(name.as_deref());
+
+    NetworkStatus {
+        online,
+        interface: name,
+        metered,
+        transfers_paused: !online || (metered && policy.pause_on_metered),
+    }
+}
+
+// Whether downloads and sync should hold off right now; also true once the
+// monthly bandwidth cap is reached
+pub fn transfers_paused(app: &AppHandle) -> bool {
+    let paused = app
+        .state::<NetworkState>()
+        .last
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(false, |status| status.transfers_paused);
+    paused || bandwidth::over_cap(app)
+}
+
+// Assumes online until the first poll says otherwise
+pub fn is_online(app: &AppHandle) -> bool {
+    app.state::<NetworkState>()
+        .last
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(true, |status| status.online)
+}
+
+fn refresh(app: &AppHandle) -> NetworkStatus {
+    let state = app.state::<NetworkState>();
+    let status = read_status(&state.policy.lock().unwrap());
+
+    let previous = state.last.lock().unwrap().replace(status.clone());
+    if previous.as_ref() != Some(&status) {
+        let _ = app.emit_all("network-changed", &status);
+
+        let was_paused = previous.map_or(false, |p| p.transfers_paused);
+        if status.transfers_paused && !was_paused {
+            downloads::set_paused(app, true);
+            let _ = app.emit_all("transfers-paused", &status);
+        } else if !status.transfers_paused && was_paused {
+            downloads::set_paused(app, false);
+            let _ = app.emit_all("transfers-resumed", &status);
+        }
+    }
+    status
+}
+
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        refresh(&app);
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_network_status(app_handle: AppHandle) -> Result<NetworkStatus, String> {
+    Ok(refresh(&app_handle))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_network_policy(
+    app_handle: AppHandle,
+    state: tauri::State<'_, NetworkState>,
+    policy: NetworkPolicy,
+) -> Result<(), String> {
+    storage::save(&app_handle, NETWORK_FILE, &policy)?;
+    *state.policy.lock().unwrap() = policy;
+    refresh(&app_handle);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+    pub fn is_metered(_interface: Option<&str>) -> bool {
+        let cost = NetworkInformation::GetInternetConnectionProfile()
+            .and_then(|profile| profile.GetConnectionCost())
+            .and_then(|cost| cost.NetworkCostType());
+        matches!(cost, Ok(kind) if kind == NetworkCostType::Fixed || kind == NetworkCostType::Variable)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    // macOS has no synchronous metered flag outside NWPathMonitor; treat a
+    // phone hotspot interface (Personal Hotspot shows up as bridge/iPhone USB) as metered
+    pub fn is_metered(interface: Option<&str>) -> bool {
+        interface.map_or(false, |name| name.contains("iPhone") || name.starts_with("bridge"))
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    // NetworkManager reports "yes" / "yes (guessed)" for metered devices
+    pub fn is_metered(interface: Option<&str>) -> bool {
+        let Some(interface) = interface else {
+            return false;
+        };
+        Command::new("nmcli")
+            .args(["-t", "-f", "GENERAL.METERED", "dev", "show", interface])
+            .output()
+            .map(|out| {
+                String::from_utf8_lossy(&out.stdout)
+                    .split(':')
+                    .nth(1)
+                    .map_or(false, |value| value.trim().starts_with("yes"))
+            })
+            .unwrap_or(false)
+    }
+}
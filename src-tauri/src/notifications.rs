@@ -0,0 +1,520 @@
+// Notification center
+// Every notification is stored in SQLite with a category, read state and an
+// optional click-through action, then shown through the OS. Action buttons
+// pressed on the native notification are routed to the owning subsystem.
+
+use rusqlite::{params, Row};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::{dnd, idle, kiosk};
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS notifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    action TEXT,
+    owner TEXT,
+    buttons TEXT NOT NULL DEFAULT '[]',
+    read INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS notifications_created ON notifications (created_at DESC);
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    General,
+    Download,
+    Workflow,
+    WorkflowFailure,
+    Ai,
+    Feed,
+    System,
+}
+
+impl NotificationCategory {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotificationCategory::General => "general",
+            NotificationCategory::Download => "download",
+            NotificationCategory::Workflow => "workflow",
+            NotificationCategory::WorkflowFailure => "workflow_failure",
+            NotificationCategory::Ai => "ai",
+            NotificationCategory::Feed => "feed",
+            NotificationCategory::System => "system",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "download" => NotificationCategory::Download,
+            "workflow" => NotificationCategory::Workflow,
+            "workflow_failure" => NotificationCategory::WorkflowFailure,
+            "ai" => NotificationCategory::Ai,
+            "feed" => NotificationCategory::Feed,
+            "system" => NotificationCategory::System,
+            _ => NotificationCategory::General,
+        }
+    }
+}
+
+// What happens when a notification is clicked in the notification center
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationAction {
+    OpenUrl { url: String },
+    FocusWindow { window_id: String },
+    OpenWorkflowRun { run_id: String },
+}
+
+// A button shown on the native notification ("Retry download", "Open page", ...)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct NotificationButton {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StoredNotification {
+    pub id: i64,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    pub action: Option<NotificationAction>,
+    // Subsystem that receives button presses (e.g. "downloads")
+    pub owner: Option<String>,
+    pub buttons: Vec<NotificationButton>,
+    pub read: bool,
+    pub created_at: i64,
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, category, title, body, action, owner, buttons, read, created_at FROM notifications";
+
+impl StoredNotification {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let category: String = row.get(1)?;
+        let action: Option<String> = row.get(4)?;
+        let buttons: String = row.get(6)?;
+        Ok(Self {
+            id: row.get(0)?,
+            category: NotificationCategory::parse(&category),
+            title: row.get(2)?,
+            body: row.get(3)?,
+            action: action.and_then(|a| serde_json::from_str(&a).ok()),
+            owner: row.get(5)?,
+            buttons: serde_json::from_str(&buttons).unwrap_or_default(),
+            read: row.get(7)?,
+            created_at: row.get(8)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notice {
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    pub action: Option<NotificationAction>,
+    pub owner: Option<String>,
+    pub buttons: Vec<NotificationButton>,
+}
+
+impl Notice {
+    pub fn new(category: NotificationCategory, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            category,
+            title: title.into(),
+            body: body.into(),
+            action: None,
+            owner: None,
+            buttons: Vec::new(),
+        }
+    }
+
+    pub fn with_action(mut self, action: NotificationAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn with_button(mut self, id: impl Into<String>, label: impl Into<String>) -> Self {
+        self.buttons.push(NotificationButton {
+            id: id.into(),
+            label: label.into(),
+        });
+        self
+    }
+
+    pub fn owned_by(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+}
+
+// Button ids with built-in behaviour; anything else goes to the owner
+pub const BUTTON_DISMISS: &str = "dismiss";
+pub const BUTTON_OPEN: &str = "open";
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ButtonPress {
+    pub notification: StoredNotification,
+    pub button_id: String,
+}
+
+type ButtonHandler = Arc<dyn Fn(&AppHandle, &ButtonPress) + Send + Sync>;
+
+// Subsystems register a handler for the notifications they own
+#[derive(Default)]
+pub struct NotificationHandlers {
+    handlers: Mutex<HashMap<String, ButtonHandler>>,
+}
+
+pub fn register_button_handler(
+    app: &AppHandle,
+    owner: &str,
+    handler: impl Fn(&AppHandle, &ButtonPress) + Send + Sync + 'static,
+) {
+    app.state::<NotificationHandlers>()
+        .handlers
+        .lock()
+        .unwrap()
+        .insert(owner.to_string(), Arc::new(handler));
+}
+
+// Store a notification, show it through the OS and tell open windows about it
+pub fn notify(app: &AppHandle, notice: Notice) -> Result<i64, String> {
+    let action = notice
+        .action
+        .as_ref()
+        .map(|a| serde_json::to_string(a).map_err(|e| e.to_string()))
+        .transpose()?;
+    let created_at = chrono::Utc::now().timestamp();
+
+    let buttons = serde_json::to_string(&notice.buttons).map_err(|e| e.to_string())?;
+
+    let db = app.state::<Database>();
+    let id = db.with(|conn| {
+        conn.execute(
+            "INSERT INTO notifications (category, title, body, action, owner, buttons, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                notice.category.as_str(),
+                notice.title,
+                notice.body,
+                action,
+                notice.owner,
+                buttons,
+                created_at
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    })?;
+
+    // Focus modes hold back the popup; the entry is in the center either way
+    if !dnd::hold(app, id, &notice) {
+        show_native(app, id, &notice);
+    }
+
+    let _ = app.emit_all("notification-added", id);
+    Ok(id)
+}
+
+// Show the OS popup for a stored notification
+pub fn show_native(app: &AppHandle, id: i64, notice: &Notice) {
+    if notice.buttons.is_empty() {
+        let result = tauri::api::notification::Notification::new(&app.config().tauri.bundle.identifier)
+            .title(&notice.title)
+            .body(&notice.body)
+            .show();
+        if let Err(e) = result {
+            eprintln!("Failed to show notification: {}", e);
+        }
+    } else {
+        platform::show_with_buttons(app, id, notice);
+    }
+}
+
+fn load(app: &AppHandle, id: i64) -> Result<StoredNotification, String> {
+    app.state::<Database>().with(|conn| {
+        conn.query_row(
+            &format!("{} WHERE id = ?1", SELECT_COLUMNS),
+            params![id],
+            StoredNotification::from_row,
+        )
+    })
+}
+
+// Deliver a button press from the native notification to its owner
+pub fn handle_button(app: &AppHandle, id: i64, button_id: &str) -> Result<(), String> {
+    app.state::<Database>().with(|conn| {
+        conn.execute("UPDATE notifications SET read = 1 WHERE id = ?1", params![id])
+    })?;
+    let notification = load(app, id)?;
+
+    match (button_id, notification.action.clone()) {
+        (BUTTON_DISMISS, _) => Ok(()),
+        (BUTTON_OPEN, Some(action)) => run_action(app, action),
+        _ => {
+            let press = ButtonPress {
+                notification,
+                button_id: button_id.to_string(),
+            };
+            let handler = press.notification.owner.as_ref().and_then(|owner| {
+                app.state::<NotificationHandlers>()
+                    .handlers
+                    .lock()
+                    .unwrap()
+                    .get(owner)
+                    .cloned()
+            });
+            match handler {
+                Some(handler) => handler(app, &press),
+                // Owners living in the frontend listen for this event
+                None => {
+                    let _ = app.emit_all("notification-action", &press);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn run_action(app: &AppHandle, action: NotificationAction) -> Result<(), String> {
+    // Every action opens or reveals something outside the kiosk window
+    kiosk::ensure_inactive(app)?;
+    idle::ensure_unlocked(app)?;
+    match action {
+        NotificationAction::OpenUrl { url } => {
+            tauri::async_runtime::spawn(crate::create_new_window(app.clone(), Some(url), None));
+            Ok(())
+        }
+        NotificationAction::FocusWindow { window_id } => {
+            let window = app
+                .get_window(&window_id)
+                .ok_or_else(|| format!("Window not found: {}", window_id))?;
+            window.show().map_err(|e| e.to_string())?;
+            window.set_focus().map_err(|e| e.to_string())
+        }
+        NotificationAction::OpenWorkflowRun { run_id } => app
+            .emit_all("open-workflow-run", run_id)
+            .map_err(|e| e.to_string()),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn show_notification(
+    app_handle: AppHandle,
+    title: String,
+    body: String,
+    category: Option<NotificationCategory>,
+    action: Option<NotificationAction>,
+    buttons: Option<Vec<NotificationButton>>,
+) -> Result<i64, String> {
+    // Owners are only assigned through `owned_by`; presses on these buttons
+    // reach the frontend as `notification-action` events
+    let mut notice = Notice::new(category.unwrap_or(NotificationCategory::General), title, body);
+    notice.action = action;
+    notice.buttons = buttons.unwrap_or_default();
+    notify(&app_handle, notice)
+}
+
+// Button presses from notification UIs rendered by the frontend
+#[tauri::command]
+#[specta::specta]
+pub async fn press_notification_button(
+    app_handle: AppHandle,
+    id: i64,
+    button_id: String,
+) -> Result<(), String> {
+    handle_button(&app_handle, id, &button_id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_notifications(
+    db: tauri::State<'_, Database>,
+    category: Option<NotificationCategory>,
+    unread_only: Option<bool>,
+    limit: Option<u32>,
+) -> Result<Vec<StoredNotification>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE (?1 IS NULL OR category = ?1) AND (?2 = 0 OR read = 0)
+             ORDER BY created_at DESC, id DESC LIMIT ?3",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                category.map(|c| c.as_str()),
+                unread_only.unwrap_or(false),
+                limit.unwrap_or(100)
+            ],
+            StoredNotification::from_row,
+        )?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn mark_notification_read(
+    db: tauri::State<'_, Database>,
+    id: i64,
+    read: Option<bool>,
+) -> Result<(), String> {
+    db.with(|conn| {
+        conn.execute(
+            "UPDATE notifications SET read = ?1 WHERE id = ?2",
+            params![read.unwrap_or(true), id],
+        )
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn mark_all_notifications_read(db: tauri::State<'_, Database>) -> Result<(), String> {
+    db.with(|conn| conn.execute("UPDATE notifications SET read = 1", []))?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_notifications(db: tauri::State<'_, Database>) -> Result<(), String> {
+    db.with(|conn| conn.execute("DELETE FROM notifications", []))?;
+    Ok(())
+}
+
+// Click-through from the notification center: mark read and run the action
+#[tauri::command]
+#[specta::specta]
+pub async fn activate_notification(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    id: i64,
+) -> Result<(), String> {
+    db.with(|conn| conn.execute("UPDATE notifications SET read = 1 WHERE id = ?1", params![id]))?;
+    let notification = load(&app_handle, id)?;
+
+    match notification.action {
+        Some(action) => run_action(&app_handle, action),
+        None => Ok(()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{handle_button, Notice};
+    use tauri::AppHandle;
+    use tauri_winrt_notification::Toast;
+
+    pub fn show_with_buttons(app: &AppHandle, id: i64, notice: &Notice) {
+        // Attributed to the app's AUMID, which the installer sets to the bundle identifier
+        let mut toast = Toast::new(&app.config().tauri.bundle.identifier)
+            .title(&notice.title)
+            .text1(&notice.body);
+        for button in &notice.buttons {
+            toast = toast.add_button(&button.label, &button.id);
+        }
+
+        let app = app.clone();
+        let result = toast
+            .on_activated(move |action| {
+                if let Some(button_id) = action {
+                    if let Err(e) = handle_button(&app, id, &button_id) {
+                        eprintln!("Notification action failed: {}", e);
+                    }
+                }
+                Ok(())
+            })
+            .show();
+        if let Err(e) = result {
+            eprintln!("Failed to show notification: {}", e);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{handle_button, Notice, BUTTON_DISMISS};
+    use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+    use tauri::AppHandle;
+
+    pub fn show_with_buttons(app: &AppHandle, id: i64, notice: &Notice) {
+        let app = app.clone();
+        let notice = notice.clone();
+
+        // The call blocks until the user responds
+        std::thread::spawn(move || {
+            let labels: Vec<&str> = notice.buttons.iter().map(|b| b.label.as_str()).collect();
+            let main_button = match labels.as_slice() {
+                [single] => MainButton::SingleAction(single),
+                many => MainButton::DropdownActions("Actions", many),
+            };
+            let response = mac_notification_sys::send_notification(
+                &notice.title,
+                None,
+                &notice.body,
+                Some(Notification::new().main_button(main_button).close_button("Dismiss")),
+            );
+
+            let button_id = match response {
+                Ok(NotificationResponse::ActionButton(label)) => notice
+                    .buttons
+                    .iter()
+                    .find(|b| b.label == label)
+                    .map(|b| b.id.clone()),
+                Ok(NotificationResponse::CloseButton(_)) => Some(BUTTON_DISMISS.to_string()),
+                _ => None,
+            };
+            if let Some(button_id) = button_id {
+                if let Err(e) = handle_button(&app, id, &button_id) {
+                    eprintln!("Notification action failed: {}", e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::{handle_button, Notice};
+    use tauri::AppHandle;
+
+    pub fn show_with_buttons(app: &AppHandle, id: i64, notice: &Notice) {
+        let mut notification = notify_rust::Notification::new();
+        notification.summary(&notice.title).body(&notice.body);
+        for button in &notice.buttons {
+            notification.action(&button.id, &button.label);
+        }
+
+        let handle = match notification.show() {
+            Ok(handle) => handle,
+            Err(e) => {
+                eprintln!("Failed to show notification: {}", e);
+                return;
+            }
+        };
+
+        // wait_for_action blocks until the notification is acted on or closed
+        let app = app.clone();
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| {
+                if action != "__closed" {
+                    if let Err(e) = handle_button(&app, id, action) {
+                        eprintln!("Notification action failed: {}", e);
+                    }
+                }
+            });
+        });
+    }
+}
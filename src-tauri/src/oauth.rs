@@ -0,0 +1,308 @@
+// OAuth 2.0 authorization code flow with PKCE for desktop integrations
+// A temporary listener on 127.0.0.1 receives the redirect, the code is
+// exchanged here (the verifier never reaches the webview) and the tokens go
+// to the keychain under `oauth:<key>`. Integrations call `access_token`,
+// which refreshes expired tokens.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WindowBuilder, WindowUrl};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::audit::{self, AuditCategory};
+use crate::secrets;
+
+const CALLBACK_PATH: &str = "/callback";
+const FLOW_TIMEOUT: Duration = Duration::from_secs(300);
+const POPUP_LABEL: &str = "oauth-popup";
+// Refresh this long before the token actually expires
+const EXPIRY_MARGIN_SECS: i64 = 60;
+const DONE_PAGE: &str = "<!doctype html><html><body style=\"font-family:sans-serif;text-align:center;padding-top:4em\">\
+<h2>Signed in</h2><p>You can close this window and return to MadEasy Browser.</p></body></html>";
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct OAuthRequest {
+    auth_url: String,
+    token_url: String,
+    client_id: String,
+    #[serde(default)]
+    scopes: Vec<String>,
+    // Keychain name for the tokens, e.g. "sheets"; defaults to the client id
+    #[serde(default)]
+    key: Option<String>,
+    // Sign in inside an app window instead of the system browser
+    #[serde(default)]
+    popup: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct StoredToken {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<i64>,
+    scopes: Vec<String>,
+    token_url: String,
+    client_id: String,
+}
+
+// What the frontend gets back; the tokens themselves stay in Rust
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct OAuthGrant {
+    key: String,
+    scopes: Vec<String>,
+    expires_at: Option<i64>,
+    refreshable: bool,
+}
+
+#[derive(Debug, Deserialize, Type)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+    scope: Option<String>,
+}
+
+fn secret_key(key: &str) -> String {
+    format!("oauth:{}", key)
+}
+
+fn random_string(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn authorize_url(request: &OAuthRequest, redirect_uri: &str, state: &str, challenge: &str) -> Result<String, String> {
+    let mut url = url::Url::parse(&request.auth_url).map_err(|e| format!("Invalid auth URL: {}", e))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &request.client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("scope", &request.scopes.join(" "))
+        .append_pair("state", state)
+        .append_pair("code_challenge", challenge)
+        .append_pair("code_challenge_method", "S256");
+    Ok(url.to_string())
+}
+
+// Read one HTTP request and return its query parameters if it hit the callback
+async fn read_callback(stream: &mut TcpStream) -> Option<Vec<(String, String)>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < 16 * 1024 {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let head = String::from_utf8_lossy(&buf);
+    let target = head.lines().next()?.split_whitespace().nth(1)?;
+    let url = url::Url::parse(&format!("http://127.0.0.1{}", target)).ok()?;
+    if url.path() != CALLBACK_PATH {
+        return None;
+    }
+    Some(url.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect())
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+// Wait for the browser to come back with `code` for our `state`
+async fn wait_for_code(listener: TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        let Some(params) = read_callback(&mut stream).await else {
+            // favicon.ico and the like
+            respond(&mut stream, "404 Not Found", "").await;
+            continue;
+        };
+        let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+
+        if param("state").as_deref() != Some(state) {
+            respond(&mut stream, "400 Bad Request", "State mismatch").await;
+            return Err("OAuth state mismatch".to_string());
+        }
+        if let Some(error) = param("error") {
+            let description = param("error_description").unwrap_or_default();
+            respond(&mut stream, "200 OK", "Sign-in was cancelled. You can close this window.").await;
+            return Err(format!("Authorization failed: {} {}", error, description).trim_end().to_string());
+        }
+        let code = param("code").ok_or("No authorization code in callback")?;
+        respond(&mut stream, "200 OK", DONE_PAGE).await;
+        return Ok(code);
+    }
+}
+
+async fn request_token(token_url: &str, form: &[(&str, &str)]) -> Result<TokenResponse, String> {
+    let response = reqwest::Client::new()
+        .post(token_url)
+        .header("Accept", "application/json")
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Token request failed ({}): {}", status, body));
+    }
+    response.json().await.map_err(|e| format!("Invalid token response: {}", e))
+}
+
+fn store(key: &str, token: &StoredToken) -> Result<(), String> {
+    let value = serde_json::to_string(token).map_err(|e| e.to_string())?;
+    secrets::set(&secret_key(key), &value)
+}
+
+fn to_stored(response: TokenResponse, previous: Option<&StoredToken>, token_url: &str, client_id: &str, requested: &[String]) -> StoredToken {
+    StoredToken {
+        access_token: response.access_token,
+        // Refresh responses may omit the refresh token, keeping the old one valid
+        refresh_token: response
+            .refresh_token
+            .or_else(|| previous.and_then(|p| p.refresh_token.clone())),
+        expires_at: response.expires_in.map(|s| chrono::Utc::now().timestamp() + s),
+        scopes: response
+            .scope
+            .map(|s| s.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_else(|| requested.to_vec()),
+        token_url: token_url.to_string(),
+        client_id: client_id.to_string(),
+    }
+}
+
+fn grant(key: &str, token: &StoredToken) -> OAuthGrant {
+    OAuthGrant {
+        key: key.to_string(),
+        scopes: token.scopes.clone(),
+        expires_at: token.expires_at,
+        refreshable: token.refresh_token.is_some(),
+    }
+}
+
+fn load(key: &str) -> Result<StoredToken, String> {
+    let value = secrets::get(&secret_key(key))?.ok_or_else(|| format!("Not signed in to {}", key))?;
+    serde_json::from_str(&value).map_err(|e| e.to_string())
+}
+
+// Valid access token for `key`, refreshed first when it has expired
+pub async fn access_token(key: &str) -> Result<String, String> {
+    let token = load(key)?;
+    let expired = token
+        .expires_at
+        .map_or(false, |t| chrono::Utc::now().timestamp() >= t - EXPIRY_MARGIN_SECS);
+    if !expired {
+        return Ok(token.access_token);
+    }
+    let refresh_token = token
+        .refresh_token
+        .clone()
+        .ok_or_else(|| format!("Session for {} expired, sign in again", key))?;
+    let response = request_token(
+        &token.token_url,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+            ("client_id", &token.client_id),
+        ],
+    )
+    .await?;
+    let refreshed = to_stored(response, Some(&token), &token.token_url, &token.client_id, &token.scopes);
+    store(key, &refreshed)?;
+    Ok(refreshed.access_token)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn start_oauth_flow(app_handle: AppHandle, request: OAuthRequest) -> Result<OAuthGrant, String> {
+    let key = request.key.clone().unwrap_or_else(|| request.client_id.clone());
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.map_err(|e| e.to_string())?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+
+    let verifier = random_string(64);
+    let state = random_string(16);
+    let url = authorize_url(&request, &redirect_uri, &state, &code_challenge(&verifier))?;
+
+    if request.popup {
+        if let Some(existing) = app_handle.get_window(POPUP_LABEL) {
+            let _ = existing.close();
+        }
+        WindowBuilder::new(
+            &app_handle,
+            POPUP_LABEL,
+            WindowUrl::External(url.parse().map_err(|e| format!("Invalid URL: {}", e))?),
+        )
+        .title("Sign in")
+        .inner_size(520.0, 720.0)
+        .build()
+        .map_err(|e| e.to_string())?;
+    } else {
+        tauri::api::shell::open(&tauri::api::shell::Scope::default(), &url, None).map_err(|e| e.to_string())?;
+    }
+
+    let code = tokio::time::timeout(FLOW_TIMEOUT, wait_for_code(listener, &state)).await;
+    if let Some(popup) = app_handle.get_window(POPUP_LABEL) {
+        let _ = popup.close();
+    }
+    let code = code.map_err(|_| "Timed out waiting for sign-in".to_string())??;
+
+    let response = request_token(
+        &request.token_url,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+            ("client_id", &request.client_id),
+            ("code_verifier", &verifier),
+        ],
+    )
+    .await?;
+    let token = to_stored(response, None, &request.token_url, &request.client_id, &request.scopes);
+    store(&key, &token)?;
+
+    audit::record(
+        &app_handle,
+        AuditCategory::Credential,
+        "oauth.authorize",
+        json!({ "key": key, "scopes": token.scopes }),
+    );
+    Ok(grant(&key, &token))
+}
+
+// Sign-in state for `key`, refreshing the token if needed. Fails when the
+// user has to sign in again.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_oauth_grant(key: String) -> Result<OAuthGrant, String> {
+    access_token(&key).await?;
+    Ok(grant(&key, &load(&key)?))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_oauth_token(app_handle: AppHandle, key: String) -> Result<(), String> {
+    secrets::delete(&secret_key(&key))?;
+    audit::record(&app_handle, AuditCategory::Credential, "oauth.delete", json!({ "key": key }));
+    Ok(())
+}
@@ -0,0 +1,155 @@
+// Offline browsing from the article store
+// When enabled and the network is down, navigations to pages that were saved
+// to the reading list are redirected to `cache://` and answered from the
+// stored article. Every cached page served emits `offline-page-served` with
+// the original URL and its age so the frontend can show how fresh it is.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::http::{Request, Response, ResponseBuilder};
+use tauri::{AppHandle, Manager, Window};
+
+use crate::db::Database;
+use crate::{network, readinglist, storage};
+
+pub const CACHE_SCHEME: &str = "cache";
+const CONFIG_FILE: &str = "offline-cache.json";
+// Age thresholds for the freshness indicator
+const FRESH_SECS: i64 = 24 * 3600;
+const STALE_SECS: i64 = 7 * 24 * 3600;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct OfflineCacheConfig {
+    enabled: bool,
+}
+
+#[derive(Default)]
+pub struct OfflineCacheState {
+    config: Mutex<OfflineCacheConfig>,
+}
+
+impl OfflineCacheState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Freshness {
+    Fresh,
+    Stale,
+    Old,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CachedPage {
+    url: String,
+    cache_url: String,
+    saved_at: i64,
+    age_secs: i64,
+    freshness: Freshness,
+}
+
+impl CachedPage {
+    fn new(url: &str, saved_at: i64) -> Self {
+        let age_secs = (chrono::Utc::now().timestamp() - saved_at).max(0);
+        let freshness = match age_secs {
+            age if age < FRESH_SECS => Freshness::Fresh,
+            age if age < STALE_SECS => Freshness::Stale,
+            _ => Freshness::Old,
+        };
+        Self {
+            url: url.to_string(),
+            cache_url: cache_url(url),
+            saved_at,
+            age_secs,
+            freshness,
+        }
+    }
+}
+
+fn enabled(app: &AppHandle) -> bool {
+    app.state::<OfflineCacheState>().config.lock().unwrap().enabled
+}
+
+fn cache_url(url: &str) -> String {
+    let encoded: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+    // WebView2 only serves custom schemes through the https://<scheme>.localhost form
+    if cfg!(windows) {
+        format!("https://{}.localhost/?url={}", CACHE_SCHEME, encoded)
+    } else {
+        format!("{}://localhost/?url={}", CACHE_SCHEME, encoded)
+    }
+}
+
+fn original_url(request: &Request) -> Option<String> {
+    let uri = url::Url::parse(request.uri()).ok()?;
+    let url = uri.query_pairs().find(|(key, _)| key == "url")?.1.into_owned();
+    Some(url)
+}
+
+fn lookup(app: &AppHandle, url: &str) -> Result<Option<(CachedPage, String)>, String> {
+    let db = app.state::<Database>();
+    Ok(readinglist::offline_copy(app, &db, url)?.map(|(saved_at, html)| (CachedPage::new(url, saved_at), html)))
+}
+
+pub fn serve(app: &AppHandle, request: &Request) -> Result<Response, Box<dyn std::error::Error>> {
+    let found = original_url(request).and_then(|url| lookup(app, &url).ok().flatten());
+    let Some((page, html)) = found else {
+        return ResponseBuilder::new()
+            .mimetype("text/plain")
+            .status(404)
+            .body(b"No offline copy of this page".to_vec());
+    };
+    let _ = app.emit_all("offline-page-served", &page);
+    ResponseBuilder::new()
+        .mimetype("text/html")
+        .header("X-Cache-Saved-At", page.saved_at.to_string())
+        .status(200)
+        .body(html.into_bytes())
+}
+
+// Swap a failed navigation for the stored copy while offline
+pub fn on_page_load(window: &Window, url: &str) {
+    let app = window.app_handle();
+    if !enabled(&app) || network::is_online(&app) || !(url.starts_with("http://") || url.starts_with("https://")) {
+        return;
+    }
+    if let Ok(Some((page, _))) = lookup(&app, url) {
+        let target = serde_json::to_string(&page.cache_url).unwrap_or_default();
+        let _ = window.eval(&format!("window.location.replace({})", target));
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_offline_cache_config(
+    state: tauri::State<'_, OfflineCacheState>,
+) -> Result<OfflineCacheConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_offline_cache_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, OfflineCacheState>,
+    config: OfflineCacheConfig,
+) -> Result<(), String> {
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+// Whether a stored copy exists and how old it is, for showing an indicator
+// before navigating
+#[tauri::command]
+#[specta::specta]
+pub async fn get_offline_copy(app_handle: AppHandle, url: String) -> Result<Option<CachedPage>, String> {
+    Ok(lookup(&app_handle, &url)?.map(|(page, _)| page))
+}
@@ -0,0 +1,406 @@
+// Omnibox suggestions
+// Local sources (open windows, bookmarks, history) are merged and returned
+// synchronously; search-engine and AI suggestions arrive afterwards as
+// `omnibox-remote-suggestions` events so typing never waits on the network.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::ai::{self, AiTask};
+use crate::db::Database;
+use crate::search::{self, ResolvedInput};
+use crate::{bandwidth, bookmarks, history, profiles, storage};
+
+const OMNIBOX_FILE: &str = "omnibox.json";
+const MAX_SUGGESTIONS: usize = 8;
+const REMOTE_TIMEOUT: Duration = Duration::from_millis(800);
+const AI_DEBOUNCE: Duration = Duration::from_millis(400);
+const AI_TIMEOUT: Duration = Duration::from_secs(8);
+const QUESTION_WORDS: &[&str] = &[
+    "who", "what", "when", "where", "why", "how", "which", "is", "are", "can", "does", "do", "should",
+];
+
+const AI_SYSTEM_PROMPT: &str = "You help a web browser's address bar. \
+The user typed a query. Reply with JSON only: {\"answer\": string|null, \"url\": string|null}. \
+\"answer\" is a one-sentence direct answer if the query is a factual question, otherwise null. \
+\"url\" is the single most likely website the user wants to visit, otherwise null.";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct OmniboxSettings {
+    // Send question-like input to the AI provider (opt-in)
+    ai_suggestions: bool,
+    search_suggestions: bool,
+}
+
+impl Default for OmniboxSettings {
+    fn default() -> Self {
+        Self {
+            ai_suggestions: false,
+            search_suggestions: true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct OmniboxState {
+    settings: Mutex<OmniboxSettings>,
+    // Latest query number per window, used to debounce AI requests
+    generations: Mutex<HashMap<String, u64>>,
+}
+
+impl OmniboxState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            settings: Mutex::new(storage::load(app, OMNIBOX_FILE)),
+            generations: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Type)]
+struct AiReply {
+    answer: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    // What the user typed, resolved to a URL or a search
+    Input,
+    OpenWindow,
+    Bookmark,
+    History,
+    Search,
+    Ai,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub title: String,
+    pub url: String,
+    pub score: f64,
+    // Window to switch to for open-window suggestions
+    pub window_id: Option<String>,
+    // Extra label shown next to the suggestion (e.g. "AI answer")
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+struct RemoteSuggestions {
+    prefix: String,
+    suggestions: Vec<Suggestion>,
+}
+
+// Compare URLs ignoring scheme, "www." and trailing slashes
+fn dedup_key(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    url.trim_end_matches('/').to_lowercase()
+}
+
+// Boost matches at the start of the host or title
+fn match_boost(prefix: &str, url: &str, title: &str) -> f64 {
+    let prefix = prefix.to_lowercase();
+    let key = dedup_key(url);
+    if key.starts_with(&prefix) {
+        2.0
+    } else if title.to_lowercase().starts_with(&prefix) {
+        1.5
+    } else {
+        1.0
+    }
+}
+
+fn input_suggestion(app: &AppHandle, prefix: &str) -> Option<Suggestion> {
+    let engines = search::load_engines(app, &profiles::active_profile(app));
+    let resolved = search::resolve(&engines, prefix).ok()?;
+    let (title, url) = match resolved {
+        ResolvedInput::Url { url } => (url.clone(), url),
+        ResolvedInput::Search { url, terms, engine_id } => {
+            let engine = engines.engines.iter().find(|e| e.id == engine_id)?;
+            (format!("Search {} for \"{}\"", engine.name, terms), url)
+        }
+    };
+    Some(Suggestion {
+        kind: SuggestionKind::Input,
+        title,
+        url,
+        score: f64::MAX,
+        window_id: None,
+        label: None,
+    })
+}
+
+pub fn local_suggestions(app: &AppHandle, prefix: &str) -> Result<Vec<Suggestion>, String> {
+    let db = app.state::<Database>();
+    let needle = prefix.to_lowercase();
+    let mut merged: HashMap<String, Suggestion> = HashMap::new();
+
+    let mut add = |suggestion: Suggestion| {
+        let key = dedup_key(&suggestion.url);
+        match merged.get_mut(&key) {
+            // Keep the more actionable kind but accumulate relevance
+            Some(existing) => {
+                existing.score += suggestion.score;
+                if suggestion.kind == SuggestionKind::OpenWindow {
+                    existing.kind = suggestion.kind;
+                    existing.window_id = suggestion.window_id;
+                } else if existing.kind == SuggestionKind::History {
+                    existing.kind = suggestion.kind;
+                }
+            }
+            None => {
+                merged.insert(key, suggestion);
+            }
+        }
+    };
+
+    for (label, window) in app.windows() {
+        let Ok(url) = window.url() else { continue };
+        let url = url.to_string();
+        if url.to_lowercase().contains(&needle) {
+            add(Suggestion {
+                kind: SuggestionKind::OpenWindow,
+                title: window.title().unwrap_or_else(|_| url.clone()),
+                score: 500.0 * match_boost(prefix, &url, ""),
+                url,
+                window_id: Some(label),
+                label: Some("Switch to window".to_string()),
+            });
+        }
+    }
+
+    for bookmark in bookmarks::search(&db, prefix, 20)? {
+        add(Suggestion {
+            kind: SuggestionKind::Bookmark,
+            score: 300.0 * match_boost(prefix, &bookmark.url, &bookmark.title),
+            title: bookmark.title,
+            url: bookmark.url,
+            window_id: None,
+            label: None,
+        });
+    }
+
+    // History rows come back ranked by frecency; convert rank to a score
+    let entries = history::frecency_ranked(&db, Some(prefix), 20)?;
+    let count = entries.len() as f64;
+    for (rank, entry) in entries.into_iter().enumerate() {
+        add(Suggestion {
+            kind: SuggestionKind::History,
+            score: (count - rank as f64) * 10.0 * match_boost(prefix, &entry.url, &entry.title),
+            title: if entry.title.is_empty() { entry.url.clone() } else { entry.title },
+            url: entry.url,
+            window_id: None,
+            label: None,
+        });
+    }
+
+    let mut suggestions: Vec<Suggestion> = merged.into_values().collect();
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    suggestions.truncate(MAX_SUGGESTIONS - 1);
+
+    if let Some(input) = input_suggestion(app, prefix) {
+        suggestions.retain(|s| dedup_key(&s.url) != dedup_key(&input.url));
+        suggestions.insert(0, input);
+    }
+    Ok(suggestions)
+}
+
+// Query the default engine's suggest endpoint (OpenSearch JSON: [query, [terms...]])
+async fn remote_suggestions(app: &AppHandle, prefix: &str) -> Result<Vec<Suggestion>, String> {
+    let engines = search::load_engines(app, &profiles::active_profile(app));
+    let Some(engine) = engines.engines.iter().find(|e| e.id == engines.default_engine) else {
+        return Ok(Vec::new());
+    };
+    let Some(suggest_url) = &engine.suggest_url else {
+        return Ok(Vec::new());
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(REMOTE_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = search::expand_template(suggest_url, prefix);
+    let bytes = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    bandwidth::record(app, &url, 0, bytes.len() as u64);
+    let body: serde_json::Value = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
+
+    Ok(body[1]
+        .as_array()
+        .map(|terms| {
+            terms
+                .iter()
+                .filter_map(|t| t.as_str())
+                .take(4)
+                .enumerate()
+                .map(|(i, term)| Suggestion {
+                    kind: SuggestionKind::Search,
+                    title: term.to_string(),
+                    url: search::expand_template(&engine.url_template, term),
+                    score: 100.0 - i as f64,
+                    window_id: None,
+                    label: Some(engine.name.clone()),
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// Questions and longer free-form phrases are worth an AI lookup; URLs and
+// keyword searches are not
+fn is_question_like(engines: &search::SearchEngines, input: &str) -> bool {
+    if search::looks_like_url(input) {
+        return false;
+    }
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let first = words.first().map(|w| w.to_lowercase()).unwrap_or_default();
+    if words.len() > 1 && engines.engines.iter().any(|e| e.keyword.eq_ignore_ascii_case(&first)) {
+        return false;
+    }
+    input.ends_with('?') || (words.len() >= 2 && QUESTION_WORDS.contains(&first.as_str())) || words.len() >= 5
+}
+
+async fn ai_suggestions(app: &AppHandle, prefix: &str) -> Result<Vec<Suggestion>, String> {
+    let reply = ai::complete(app, AiTask::Chat, AI_SYSTEM_PROMPT, prefix, AI_TIMEOUT).await?;
+    // Models sometimes wrap JSON in a code fence
+    let json = reply
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```");
+    let reply: AiReply = serde_json::from_str(json.trim()).map_err(|e| e.to_string())?;
+
+    let mut suggestions = Vec::new();
+    if let Some(answer) = reply.answer.filter(|a| !a.trim().is_empty()) {
+        // Selecting the answer runs a normal search for the query
+        let engines = search::load_engines(app, &profiles::active_profile(app));
+        let url = match search::resolve(&engines, prefix)? {
+            ResolvedInput::Url { url } | ResolvedInput::Search { url, .. } => url,
+        };
+        suggestions.push(Suggestion {
+            kind: SuggestionKind::Ai,
+            title: answer.trim().to_string(),
+            url,
+            score: 200.0,
+            window_id: None,
+            label: Some("AI answer".to_string()),
+        });
+    }
+    if let Some(url) = reply.url.and_then(|u| url::Url::parse(&u).ok()) {
+        if matches!(url.scheme(), "http" | "https") {
+            suggestions.push(Suggestion {
+                kind: SuggestionKind::Ai,
+                title: url.host_str().unwrap_or_default().to_string(),
+                url: url.to_string(),
+                score: 150.0,
+                window_id: None,
+                label: Some("AI suggested site".to_string()),
+            });
+        }
+    }
+    Ok(suggestions)
+}
+
+// Ask the AI provider once the user stops typing for AI_DEBOUNCE
+fn schedule_ai_suggestions(app: AppHandle, window: Window, prefix: String) {
+    let generation = {
+        let state = app.state::<OmniboxState>();
+        let mut generations = state.generations.lock().unwrap();
+        let generation = generations.entry(window.label().to_string()).or_insert(0);
+        *generation += 1;
+        *generation
+    };
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(AI_DEBOUNCE).await;
+        let current = app
+            .state::<OmniboxState>()
+            .generations
+            .lock()
+            .unwrap()
+            .get(window.label())
+            .copied();
+        if current != Some(generation) {
+            return;
+        }
+        match ai_suggestions(&app, &prefix).await {
+            Ok(suggestions) if !suggestions.is_empty() => {
+                let _ = window.emit(
+                    "omnibox-remote-suggestions",
+                    RemoteSuggestions { prefix, suggestions },
+                );
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("AI omnibox suggestions failed: {}", e),
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_omnibox_suggestions(
+    app_handle: AppHandle,
+    window: Window,
+    state: tauri::State<'_, OmniboxState>,
+    prefix: String,
+) -> Result<Vec<Suggestion>, String> {
+    let prefix = prefix.trim().to_string();
+    if prefix.is_empty() {
+        return Ok(Vec::new());
+    }
+    let suggestions = local_suggestions(&app_handle, &prefix)?;
+    let settings = state.settings.lock().unwrap().clone();
+
+    if settings.ai_suggestions && ai::is_configured(&app_handle) {
+        let engines = search::load_engines(&app_handle, &profiles::active_profile(&app_handle));
+        if is_question_like(&engines, &prefix) {
+            schedule_ai_suggestions(app_handle.clone(), window.clone(), prefix.clone());
+        }
+    }
+
+    // Search suggestions follow asynchronously, unless the input is a URL
+    if settings.search_suggestions && !search::looks_like_url(&prefix) {
+        tauri::async_runtime::spawn(async move {
+            if let Ok(suggestions) = remote_suggestions(&app_handle, &prefix).await {
+                let _ = window.emit(
+                    "omnibox-remote-suggestions",
+                    RemoteSuggestions { prefix, suggestions },
+                );
+            }
+        });
+    }
+
+    Ok(suggestions)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_omnibox_settings(state: tauri::State<'_, OmniboxState>) -> Result<OmniboxSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_omnibox_settings(
+    app_handle: AppHandle,
+    state: tauri::State<'_, OmniboxState>,
+    settings: OmniboxSettings,
+) -> Result<(), String> {
+    storage::save(&app_handle, OMNIBOX_FILE, &settings)?;
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
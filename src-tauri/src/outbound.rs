@@ -0,0 +1,275 @@
+// Shared client for outbound API calls
+// AI, translation and registry requests go through here so a burst from a
+// workflow can't exceed a provider's quota: each provider has its own
+// request rate, transient failures (connection errors, 429, 5xx) are retried
+// with exponential backoff plus jitter, honouring Retry-After, and responses
+// of idempotent calls can be served from a short-lived in-memory cache.
+
+use rand::Rng;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::{bandwidth, storage};
+
+const OUTBOUND_FILE: &str = "outbound.json";
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// Longest Retry-After we are willing to wait for
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+const MAX_CACHE_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ProviderLimits {
+    requests_per_minute: u32,
+    max_retries: u32,
+    // How long cached responses are reused; 0 disables caching
+    #[serde(default)]
+    cache_ttl_secs: u64,
+}
+
+impl Default for ProviderLimits {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 60,
+            max_retries: 2,
+            cache_ttl_secs: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct OutboundConfig {
+    // Provider name ("ai", "translation", "brreg", ...) -> limits
+    providers: HashMap<String, ProviderLimits>,
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        let limits = |requests_per_minute, max_retries, cache_ttl_secs| ProviderLimits {
+            requests_per_minute,
+            max_retries,
+            cache_ttl_secs,
+        };
+        Self {
+            providers: HashMap::from([
+                ("ai".to_string(), limits(60, 3, 0)),
+                ("translation".to_string(), limits(30, 3, 3600)),
+                ("brreg".to_string(), limits(120, 2, 0)),
+                ("vies".to_string(), limits(30, 2, 0)),
+            ]),
+        }
+    }
+}
+
+struct CachedResponse {
+    stored: Instant,
+    ttl: Duration,
+    body: Value,
+}
+
+#[derive(Default)]
+pub struct OutboundState {
+    config: Mutex<OutboundConfig>,
+    // Provider -> earliest time the next request may start
+    next_slot: Mutex<HashMap<String, Instant>>,
+    cache: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl OutboundState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, OUTBOUND_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+fn limits(app: &AppHandle, provider: &str) -> ProviderLimits {
+    let state = app.state::<OutboundState>();
+    let config = state.config.lock().unwrap();
+    // "ai:backup" falls back to the limits for "ai"
+    let family = provider.split(':').next().unwrap_or(provider);
+    config
+        .providers
+        .get(provider)
+        .or_else(|| config.providers.get(family))
+        .cloned()
+        .unwrap_or_default()
+}
+
+// Reserve the provider's next request slot and wait for it
+async fn throttle(app: &AppHandle, provider: &str, limits: &ProviderLimits) {
+    let spacing = Duration::from_secs(60) / limits.requests_per_minute.max(1);
+    let start = {
+        let state = app.state::<OutboundState>();
+        let mut slots = state.next_slot.lock().unwrap();
+        let now = Instant::now();
+        let start = slots.get(provider).copied().filter(|next| *next > now).unwrap_or(now);
+        slots.insert(provider.to_string(), start + spacing);
+        start
+    };
+    tokio::time::sleep_until(start.into()).await;
+}
+
+fn backoff(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    if let Some(wait) = retry_after {
+        return wait.min(MAX_RETRY_AFTER);
+    }
+    let base = BASE_BACKOFF.saturating_mul(1 << attempt.min(10)).min(MAX_BACKOFF);
+    // Jitter keeps clients that failed together from retrying together
+    let jitter = rand::thread_rng().gen_range(0..=base.as_millis() as u64);
+    base / 2 + Duration::from_millis(jitter / 2)
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+pub fn retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// Size of a buffered request body; streamed bodies count as 0
+fn body_len(request: &RequestBuilder) -> u64 {
+    request
+        .try_clone()
+        .and_then(|r| r.build().ok())
+        .and_then(|r| r.body().and_then(|b| b.as_bytes()).map(|b| b.len() as u64))
+        .unwrap_or(0)
+}
+
+// Send a request within the provider's rate limit, retrying transient
+// failures. The final response is returned whatever its status, for callers
+// that treat e.g. 404 as an answer. Requests with streaming bodies can't be
+// cloned and are sent once.
+pub async fn send(app: &AppHandle, provider: &str, request: RequestBuilder) -> Result<Response, String> {
+    let limits = limits(app, provider);
+    let sent = body_len(&request);
+    let mut attempt = 0;
+    loop {
+        throttle(app, provider, &limits).await;
+        let current = if attempt < limits.max_retries { request.try_clone() } else { None };
+        let Some(current) = current else {
+            let response = request.send().await.map_err(|e| e.to_string())?;
+            bandwidth::record_response(app, &response, sent);
+            return Ok(response);
+        };
+        let result = current.send().await;
+        if let Ok(response) = &result {
+            bandwidth::record_response(app, response, sent);
+        }
+        match result {
+            Ok(response) if retryable(response.status()) => {
+                tokio::time::sleep(backoff(attempt, retry_after(&response))).await;
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                tokio::time::sleep(backoff(attempt, None)).await;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+        attempt += 1;
+    }
+}
+
+// Rate limited but without retries, for callers with somewhere else to go
+pub async fn send_once(app: &AppHandle, provider: &str, request: RequestBuilder) -> Result<Response, String> {
+    throttle(app, provider, &limits(app, provider)).await;
+    let sent = body_len(&request);
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    bandwidth::record_response(app, &response, sent);
+    Ok(response)
+}
+
+// Method, URL and body identify a call; credentials in headers are left out
+fn cache_key(provider: &str, request: &RequestBuilder) -> Option<String> {
+    let built = request.try_clone()?.build().ok()?;
+    let mut hasher = Sha256::new();
+    hasher.update(provider.as_bytes());
+    hasher.update(built.method().as_str().as_bytes());
+    hasher.update(built.url().as_str().as_bytes());
+    if let Some(body) = built.body() {
+        hasher.update(body.as_bytes()?);
+    }
+    Some(hex::encode(hasher.finalize()))
+}
+
+// `send` for idempotent JSON calls; identical requests are answered from the
+// cache while the provider's cache_ttl_secs allows
+pub async fn json(app: &AppHandle, provider: &str, request: RequestBuilder) -> Result<Value, String> {
+    let ttl = Duration::from_secs(limits(app, provider).cache_ttl_secs);
+    let key = (!ttl.is_zero()).then(|| cache_key(provider, &request)).flatten();
+    if let Some(key) = &key {
+        let state = app.state::<OutboundState>();
+        let cache = state.cache.lock().unwrap();
+        if let Some(hit) = cache.get(key).filter(|hit| hit.stored.elapsed() < hit.ttl) {
+            return Ok(hit.body.clone());
+        }
+    }
+
+    let body: Value = send(app, provider, request)
+        .await?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(key) = key {
+        let state = app.state::<OutboundState>();
+        let mut cache = state.cache.lock().unwrap();
+        cache.retain(|_, entry| entry.stored.elapsed() < entry.ttl);
+        if cache.len() >= MAX_CACHE_ENTRIES {
+            if let Some(oldest) = cache.iter().min_by_key(|(_, e)| e.stored).map(|(k, _)| k.clone()) {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(
+            key,
+            CachedResponse {
+                stored: Instant::now(),
+                ttl,
+                body: body.clone(),
+            },
+        );
+    }
+    Ok(body)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_outbound_config(state: tauri::State<'_, OutboundState>) -> Result<OutboundConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_outbound_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, OutboundState>,
+    config: OutboundConfig,
+) -> Result<(), String> {
+    if let Some((name, _)) = config.providers.iter().find(|(_, l)| l.requests_per_minute == 0) {
+        return Err(format!("{} needs at least one request per minute", name));
+    }
+    storage::save(&app_handle, OUTBOUND_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_outbound_cache(state: tauri::State<'_, OutboundState>) -> Result<(), String> {
+    state.cache.lock().unwrap().clear();
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// Callbacks from web pages
+// Scripts injected into browser windows report back through a handful of
+// commands (pings, gestures, context menus, page queries, ...). Tauri only
+// accepts invokes from remote pages whose domain has an IPC scope for the
+// window, and such a scope opens every app command. So each page load grants
+// its domain to the window, and `guard` wraps the invoke handler to reject
+// everything but CALLBACKS from remote pages.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tauri::scope::ipc::RemoteDomainAccessScope;
+use tauri::utils::config::AppUrl;
+use tauri::{AppHandle, Invoke, Manager, Window, WindowUrl};
+
+use crate::{appscheme, health, offline_cache, payloads, policy};
+
+// The only commands remote pages may invoke
+const CALLBACKS: [&str; 11] = [
+    "webview_pong",
+    "gesture_performed",
+    "context_menu_opened",
+    "report_page_query",
+    "report_window_memory",
+    "report_page_errors",
+    "report_find_result",
+    "record_history_visit",
+    "feeds_detected",
+    "submit_page_text",
+    "macro_input",
+];
+
+#[derive(Default)]
+pub struct PageBridgeState {
+    // (window label, domain) pairs with a remote IPC scope
+    granted: Mutex<HashSet<(String, String)>>,
+}
+
+// The bundled frontend ("tauri") and the registered custom schemes
+const APP_SCHEMES: [&str; 6] = [
+    "tauri",
+    appscheme::APP_SCHEME,
+    health::OFFLINE_SCHEME,
+    offline_cache::CACHE_SCHEME,
+    payloads::PAYLOAD_SCHEME,
+    policy::BLOCKED_SCHEME,
+];
+
+// http(s) origins of pages the app serves itself: custom schemes as Windows
+// serves them (exactly https://<scheme>.localhost, so local dev servers on
+// other *.localhost hosts or ports stay remote) and the dev server
+pub fn app_origins(app: &AppHandle) -> Vec<String> {
+    let mut origins: Vec<String> = APP_SCHEMES
+        .iter()
+        .map(|scheme| format!("https://{}.localhost", scheme))
+        .collect();
+    if let AppUrl::Url(WindowUrl::External(dev)) = &app.config().build.dev_path {
+        if cfg!(debug_assertions) {
+            origins.push(dev.origin().ascii_serialization());
+        }
+    }
+    origins
+}
+
+// Pages the app serves itself: everything outside http(s) plus `app_origins`
+pub fn is_app_page(app: &AppHandle, url: &url::Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return true;
+    }
+    app_origins(app).contains(&url.origin().ascii_serialization())
+}
+
+// Whether the injected scripts in `window` can reach the callbacks
+pub fn can_invoke(window: &Window) -> bool {
+    let Ok(url) = window.url() else { return false };
+    match url.scheme() {
+        "http" | "https" => is_app_page(&window.app_handle(), &url) || url.domain().is_some(),
+        "about" | "data" | "blob" | "file" => false,
+        _ => true,
+    }
+}
+
+// Called from `handle_page_load`
+pub fn on_page_load(window: &Window, url: &str) {
+    let app = window.app_handle();
+    let Ok(url) = url::Url::parse(url) else { return };
+    if is_app_page(&app, &url) {
+        return;
+    }
+    let Some(domain) = url.domain() else { return };
+    let key = (window.label().to_string(), domain.to_string());
+    if app.state::<PageBridgeState>().granted.lock().unwrap().insert(key) {
+        app.ipc_scope()
+            .configure_remote_access(RemoteDomainAccessScope::new(domain).add_window(window.label()));
+    }
+}
+
+fn allows(invoke: &Invoke) -> bool {
+    let window = invoke.message.window();
+    let remote = match window.url() {
+        Ok(url) => !is_app_page(&window.app_handle(), &url),
+        Err(_) => true,
+    };
+    !remote || CALLBACKS.contains(&invoke.message.command())
+}
+
+// Wraps the generated invoke handler
+pub fn guard(handler: impl Fn(Invoke) + Send + Sync + 'static) -> impl Fn(Invoke) + Send + Sync + 'static {
+    move |invoke| {
+        if allows(&invoke) {
+            handler(invoke);
+        } else {
+            let command = invoke.message.command().to_string();
+            invoke
+                .resolver
+                .reject(format!("{} can't be invoked from a web page", command));
+        }
+    }
+}
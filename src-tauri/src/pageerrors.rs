@@ -0,0 +1,149 @@
+// Page errors
+// A bridge in each page reports console.error calls, uncaught errors and
+// unhandled promise rejections. Each one is written to the app log with the
+// window and URL, and the most recent are kept per window so a "the page
+// broke" report can be looked into with `get_page_errors`.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+// Errors kept per window; older ones are dropped
+const MAX_ERRORS: usize = 200;
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+// Installed as an initialization script so errors during load are caught, and
+// again after each load for windows not built by the pool. Reports are
+// batched, identical messages are only counted, and a page sends at most 100.
+pub const BRIDGE_SCRIPT: &str = r#"(function () {
+  if (window.__MADEASY_PAGE_ERRORS__) return;
+  window.__MADEASY_PAGE_ERRORS__ = true;
+  var queue = [], seen = {}, sent = 0, timer = null;
+  function text(value) {
+    if (value instanceof Error) return value.stack || String(value);
+    if (typeof value === 'string') return value;
+    try { return JSON.stringify(value); } catch (e) { return String(value); }
+  }
+  function flush() {
+    timer = null;
+    if (!window.__TAURI_INVOKE__) { timer = setTimeout(flush, 1000); return; }
+    var batch = queue.splice(0);
+    if (batch.length) window.__TAURI_INVOKE__('report_page_errors', { errors: batch });
+  }
+  function report(kind, message, source, line, column) {
+    var key = kind + message;
+    if (seen[key]) { seen[key].count++; return; }
+    if (sent >= 100) return;
+    sent++;
+    seen[key] = { kind: kind, message: message, url: location.href, source: source || null,
+      line: line || null, column: column || null, count: 1, at: Date.now() };
+    queue.push(seen[key]);
+    if (!timer) timer = setTimeout(flush, 500);
+  }
+  var consoleError = console.error;
+  console.error = function () {
+    report('console', Array.prototype.map.call(arguments, text).join(' '));
+    return consoleError.apply(console, arguments);
+  };
+  window.addEventListener('error', function (e) {
+    if (e.error || e.message) report('uncaught', e.error ? text(e.error) : e.message, e.filename, e.lineno, e.colno);
+  });
+  window.addEventListener('unhandledrejection', function (e) {
+    report('unhandled_rejection', text(e.reason));
+  });
+})();"#;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PageErrorKind {
+    Console,
+    Uncaught,
+    UnhandledRejection,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PageError {
+    kind: PageErrorKind,
+    message: String,
+    url: String,
+    source: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    // Repeats of the same message before the batch was sent
+    count: u32,
+    at: i64,
+}
+
+#[derive(Default)]
+pub struct PageErrorState {
+    // Window label -> most recent errors, oldest first
+    errors: Mutex<HashMap<String, VecDeque<PageError>>>,
+}
+
+pub fn inject(window: &Window) {
+    let _ = window.eval(BRIDGE_SCRIPT);
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<PageErrorState>().errors.lock().unwrap().remove(label);
+}
+
+fn clip(message: &str) -> String {
+    match message.char_indices().nth(MAX_MESSAGE_CHARS) {
+        Some((end, _)) => format!("{}…", &message[..end]),
+        None => message.to_string(),
+    }
+}
+
+// Called by BRIDGE_SCRIPT
+#[tauri::command]
+#[specta::specta]
+pub async fn report_page_errors(
+    window: Window,
+    state: tauri::State<'_, PageErrorState>,
+    errors: Vec<PageError>,
+) -> Result<(), String> {
+    let mut all = state.errors.lock().unwrap();
+    let kept = all.entry(window.label().to_string()).or_default();
+    for mut error in errors {
+        error.message = clip(&error.message);
+        let location = match (&error.source, error.line) {
+            (Some(source), Some(line)) => format!(" ({}:{})", source, line),
+            _ => String::new(),
+        };
+        eprintln!(
+            "[page error] window={} url={} kind={:?} count={}: {}{}",
+            window.label(),
+            error.url,
+            error.kind,
+            error.count,
+            error.message,
+            location
+        );
+        if kept.len() >= MAX_ERRORS {
+            kept.pop_front();
+        }
+        kept.push_back(error);
+    }
+    Ok(())
+}
+
+// Newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_page_errors(state: tauri::State<'_, PageErrorState>, window_id: String) -> Result<Vec<PageError>, String> {
+    let all = state.errors.lock().unwrap();
+    Ok(all
+        .get(&window_id)
+        .map(|kept| kept.iter().rev().cloned().collect())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_page_errors(state: tauri::State<'_, PageErrorState>, window_id: String) -> Result<(), String> {
+    state.errors.lock().unwrap().remove(&window_id);
+    Ok(())
+}
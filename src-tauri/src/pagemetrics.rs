@@ -0,0 +1,339 @@
+// Page performance metrics
+// An observer injected on every page load buffers long tasks, LCP and layout
+// shifts; `get_page_metrics` reads them together with navigation and resource
+// timing and aggregates everything here. With `record_navigations` on, a
+// summary of each page load is written to the `network_log` table.
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::db::Database;
+use crate::{pagequery, storage};
+
+const CONFIG_FILE: &str = "page-metrics.json";
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+// Give late long tasks and LCP candidates time to arrive before recording
+const RECORD_DELAY: Duration = Duration::from_secs(3);
+// Main-thread time beyond this per long task counts as blocking
+const BLOCKING_THRESHOLD_MS: f64 = 50.0;
+const MAX_LONG_TASKS: usize = 20;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS network_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    ttfb_ms REAL,
+    dom_content_loaded_ms REAL,
+    load_ms REAL,
+    lcp_ms REAL,
+    cls REAL NOT NULL DEFAULT 0,
+    resource_count INTEGER NOT NULL DEFAULT 0,
+    transfer_bytes INTEGER NOT NULL DEFAULT 0,
+    long_tasks INTEGER NOT NULL DEFAULT 0,
+    total_blocking_ms REAL NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS network_log_url ON network_log (url, recorded_at);
+";
+
+const OBSERVER_SCRIPT: &str = r#"(function() {
+  if (window.__MADEASY_PERF__ || typeof PerformanceObserver === 'undefined') return;
+  var perf = window.__MADEASY_PERF__ = { longTasks: [], lcp: null, cls: 0 };
+  var observe = function(type, handle) {
+    try { new PerformanceObserver(function(list) { list.getEntries().forEach(handle); }).observe({ type: type, buffered: true }); } catch (e) {}
+  };
+  observe('longtask', function(e) { if (perf.longTasks.length < 500) perf.longTasks.push({ start: e.startTime, duration: e.duration }); });
+  observe('largest-contentful-paint', function(e) { perf.lcp = e.startTime; });
+  observe('layout-shift', function(e) { if (!e.hadRecentInput) perf.cls += e.value; });
+})();"#;
+
+const COLLECT_SCRIPT: &str = r#"
+var nav = performance.getEntriesByType('navigation')[0] || null;
+var perf = window.__MADEASY_PERF__ || { longTasks: [], lcp: null, cls: 0 };
+return {
+  url: location.href,
+  navigation: nav && {
+    ttfb: nav.responseStart - nav.startTime,
+    domContentLoaded: nav.domContentLoadedEventEnd - nav.startTime,
+    load: nav.loadEventEnd > 0 ? nav.loadEventEnd - nav.startTime : null,
+    dns: nav.domainLookupEnd - nav.domainLookupStart,
+    connect: nav.connectEnd - nav.connectStart,
+    transferSize: nav.transferSize || 0,
+    type: nav.type
+  },
+  resources: performance.getEntriesByType('resource').slice(0, 2000).map(function(r) {
+    return { type: r.initiatorType, transferSize: r.transferSize || 0, bodySize: r.decodedBodySize || 0, duration: r.duration };
+  }),
+  longTasks: perf.longTasks,
+  lcp: perf.lcp,
+  cls: perf.cls
+};
+"#;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct PageMetricsConfig {
+    record_navigations: bool,
+}
+
+#[derive(Default)]
+pub struct PageMetricsState {
+    config: Mutex<PageMetricsConfig>,
+}
+
+impl PageMetricsState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct NavigationTiming {
+    ttfb: f64,
+    dom_content_loaded: f64,
+    load: Option<f64>,
+    dns: f64,
+    connect: f64,
+    transfer_size: u64,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+struct ResourceEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    transfer_size: u64,
+    body_size: u64,
+    duration: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LongTask {
+    start: f64,
+    duration: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+struct RawMetrics {
+    url: String,
+    navigation: Option<NavigationTiming>,
+    resources: Vec<ResourceEntry>,
+    long_tasks: Vec<LongTask>,
+    lcp: Option<f64>,
+    cls: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct ResourceSummary {
+    count: usize,
+    transfer_bytes: u64,
+    body_bytes: u64,
+    slowest_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PageMetrics {
+    url: String,
+    navigation: Option<NavigationTiming>,
+    lcp_ms: Option<f64>,
+    cls: f64,
+    resources: ResourceSummary,
+    // Keyed by initiator type (script, img, css, fetch, ...)
+    resources_by_type: BTreeMap<String, ResourceSummary>,
+    long_task_count: usize,
+    total_blocking_ms: f64,
+    // Longest first
+    long_tasks: Vec<LongTask>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct NetworkLogEntry {
+    id: i64,
+    url: String,
+    recorded_at: i64,
+    ttfb_ms: Option<f64>,
+    dom_content_loaded_ms: Option<f64>,
+    load_ms: Option<f64>,
+    lcp_ms: Option<f64>,
+    cls: f64,
+    resource_count: i64,
+    transfer_bytes: i64,
+    long_tasks: i64,
+    total_blocking_ms: f64,
+}
+
+impl NetworkLogEntry {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            recorded_at: row.get(2)?,
+            ttfb_ms: row.get(3)?,
+            dom_content_loaded_ms: row.get(4)?,
+            load_ms: row.get(5)?,
+            lcp_ms: row.get(6)?,
+            cls: row.get(7)?,
+            resource_count: row.get(8)?,
+            transfer_bytes: row.get(9)?,
+            long_tasks: row.get(10)?,
+            total_blocking_ms: row.get(11)?,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, url, recorded_at, ttfb_ms, dom_content_loaded_ms, load_ms, lcp_ms, cls,
+    resource_count, transfer_bytes, long_tasks, total_blocking_ms FROM network_log";
+
+impl ResourceSummary {
+    fn add(&mut self, entry: &ResourceEntry) {
+        self.count += 1;
+        self.transfer_bytes += entry.transfer_size;
+        self.body_bytes += entry.body_size;
+        self.slowest_ms = self.slowest_ms.max(entry.duration);
+    }
+}
+
+fn aggregate(raw: RawMetrics) -> PageMetrics {
+    let mut resources = ResourceSummary::default();
+    let mut resources_by_type: BTreeMap<String, ResourceSummary> = BTreeMap::new();
+    for entry in &raw.resources {
+        resources.add(entry);
+        resources_by_type.entry(entry.kind.clone()).or_default().add(entry);
+    }
+    let total_blocking_ms = raw
+        .long_tasks
+        .iter()
+        .map(|t| (t.duration - BLOCKING_THRESHOLD_MS).max(0.0))
+        .sum();
+    let long_task_count = raw.long_tasks.len();
+    let mut long_tasks = raw.long_tasks;
+    long_tasks.sort_by(|a, b| b.duration.total_cmp(&a.duration));
+    long_tasks.truncate(MAX_LONG_TASKS);
+
+    PageMetrics {
+        url: raw.url,
+        navigation: raw.navigation,
+        lcp_ms: raw.lcp,
+        cls: raw.cls,
+        resources,
+        resources_by_type,
+        long_task_count,
+        total_blocking_ms,
+        long_tasks,
+    }
+}
+
+async fn collect(app: &AppHandle, window: &Window) -> Result<PageMetrics, String> {
+    let raw = pagequery::run(app, window, COLLECT_SCRIPT, QUERY_TIMEOUT).await?;
+    let raw: RawMetrics = serde_json::from_value(raw).map_err(|e| e.to_string())?;
+    Ok(aggregate(raw))
+}
+
+fn record(db: &Database, metrics: &PageMetrics) -> Result<(), String> {
+    let navigation = metrics.navigation.as_ref();
+    db.with(|conn| {
+        conn.execute(
+            "INSERT INTO network_log (url, recorded_at, ttfb_ms, dom_content_loaded_ms, load_ms, lcp_ms, cls,
+                 resource_count, transfer_bytes, long_tasks, total_blocking_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                metrics.url,
+                chrono::Utc::now().timestamp(),
+                navigation.map(|n| n.ttfb),
+                navigation.map(|n| n.dom_content_loaded),
+                navigation.and_then(|n| n.load),
+                metrics.lcp_ms,
+                metrics.cls,
+                metrics.resources.count as i64,
+                (metrics.resources.transfer_bytes + navigation.map_or(0, |n| n.transfer_size)) as i64,
+                metrics.long_task_count as i64,
+                metrics.total_blocking_ms,
+            ],
+        )
+    })?;
+    Ok(())
+}
+
+pub fn on_page_load(window: &Window, url: &str) {
+    let _ = window.eval(OBSERVER_SCRIPT);
+    let app = window.app_handle();
+    let recording = app.state::<PageMetricsState>().config.lock().unwrap().record_navigations;
+    if !recording || !(url.starts_with("http://") || url.starts_with("https://")) {
+        return;
+    }
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RECORD_DELAY).await;
+        let result = match collect(&app, &window).await {
+            Ok(metrics) => record(&app.state::<Database>(), &metrics),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to record page metrics: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_page_metrics(app_handle: AppHandle, window_id: String) -> Result<PageMetrics, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    collect(&app_handle, &window).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_page_metrics_config(state: tauri::State<'_, PageMetricsState>) -> Result<PageMetricsConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_page_metrics_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, PageMetricsState>,
+    config: PageMetricsConfig,
+) -> Result<(), String> {
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+// Recorded page loads, newest first, optionally for one URL
+#[tauri::command]
+#[specta::specta]
+pub async fn list_network_log(
+    db: tauri::State<'_, Database>,
+    url: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<NetworkLogEntry>, String> {
+    let limit = limit.unwrap_or(100).min(1000);
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE (?1 IS NULL OR url = ?1) ORDER BY recorded_at DESC, id DESC LIMIT ?2",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![url, limit], NetworkLogEntry::from_row)?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_network_log(db: tauri::State<'_, Database>) -> Result<(), String> {
+    db.with(|conn| conn.execute("DELETE FROM network_log", []))?;
+    Ok(())
+}
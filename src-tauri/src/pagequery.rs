@@ -0,0 +1,84 @@
+// Run a script in a page and wait for its result
+// Tauri 1's `eval` is fire-and-forget, so the script reports back through
+// `report_page_query`. Pending queries are keyed by window label and a random
+// id, so a page can only answer queries that were sent to it.
+
+use rand::RngCore;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+use tokio::sync::oneshot;
+
+type Reply = Result<Value, String>;
+
+#[derive(Default)]
+pub struct PageQueryState {
+    pending: Mutex<HashMap<String, oneshot::Sender<Reply>>>,
+}
+
+fn key(window: &Window, id: &str) -> String {
+    format!("{}:{}", window.label(), id)
+}
+
+// `body` is a function body; its return value (or the value of a returned
+// promise) must be JSON-serializable
+pub async fn run(app: &AppHandle, window: &Window, body: &str, timeout: Duration) -> Reply {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let id = hex::encode(bytes);
+    let (tx, rx) = oneshot::channel();
+    let state = app.state::<PageQueryState>();
+    state.pending.lock().unwrap().insert(key(window, &id), tx);
+
+    let script = format!(
+        r#"(function() {{
+  var report = function(result, error) {{
+    window.__TAURI_INVOKE__ && window.__TAURI_INVOKE__('report_page_query', {{
+      id: {id:?}, result: result === undefined ? null : result, error: error || null
+    }});
+  }};
+  try {{
+    Promise.resolve((function() {{
+{body}
+    }})()).then(function(r) {{ report(r); }}, function(e) {{ report(null, String(e)); }});
+  }} catch (e) {{
+    report(null, String(e));
+  }}
+}})();"#,
+        id = id,
+        body = body
+    );
+    if let Err(e) = window.eval(&script) {
+        state.pending.lock().unwrap().remove(&key(window, &id));
+        return Err(e.to_string());
+    }
+
+    let reply = tokio::time::timeout(timeout, rx).await;
+    state.pending.lock().unwrap().remove(&key(window, &id));
+    match reply {
+        Ok(Ok(reply)) => reply,
+        Ok(Err(_)) => Err("Page query was dropped".to_string()),
+        Err(_) => Err("Page did not respond".to_string()),
+    }
+}
+
+// Called by the script injected by `run`
+#[tauri::command]
+#[specta::specta]
+pub async fn report_page_query(
+    window: Window,
+    state: tauri::State<'_, PageQueryState>,
+    id: String,
+    result: Value,
+    error: Option<String>,
+) -> Result<(), String> {
+    if let Some(tx) = state.pending.lock().unwrap().remove(&key(&window, &id)) {
+        let _ = tx.send(match error {
+            Some(error) => Err(error),
+            None => Ok(result),
+        });
+    }
+    Ok(())
+}
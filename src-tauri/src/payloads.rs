@@ -0,0 +1,154 @@
+// Binary payload channel
+// Large results (screenshots, datasets) are handed to the frontend as a
+// `payload://` URL instead of being base64-encoded into an invoke response.
+// The frontend fetches the URL (or uses it as an <img> src); Range requests
+// are supported so big payloads can be read in chunks. Payloads expire after
+// PAYLOAD_TTL, and the oldest are dropped once MAX_TOTAL_BYTES is exceeded.
+
+use rand::RngCore;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::http::{Request, Response, ResponseBuilder};
+use tauri::{AppHandle, Manager};
+
+pub const PAYLOAD_SCHEME: &str = "payload";
+const PAYLOAD_TTL: Duration = Duration::from_secs(10 * 60);
+const MAX_TOTAL_BYTES: usize = 512 * 1024 * 1024;
+
+struct Payload {
+    bytes: Arc<Vec<u8>>,
+    mime: String,
+    created: Instant,
+}
+
+#[derive(Default)]
+pub struct PayloadStore {
+    payloads: Mutex<HashMap<String, Payload>>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PayloadRef {
+    id: String,
+    url: String,
+    mime: String,
+    size: usize,
+}
+
+fn payload_url(id: &str) -> String {
+    // WebView2 only serves custom schemes through the https://<scheme>.localhost form
+    if cfg!(windows) {
+        format!("https://{}.localhost/{}", PAYLOAD_SCHEME, id)
+    } else {
+        format!("{}://localhost/{}", PAYLOAD_SCHEME, id)
+    }
+}
+
+// Drop expired payloads, then the oldest until `incoming` more bytes fit
+fn evict(payloads: &mut HashMap<String, Payload>, incoming: usize) {
+    payloads.retain(|_, p| p.created.elapsed() < PAYLOAD_TTL);
+    let mut total: usize = payloads.values().map(|p| p.bytes.len()).sum();
+    while total + incoming > MAX_TOTAL_BYTES {
+        let Some(oldest) = payloads.iter().min_by_key(|(_, p)| p.created).map(|(id, _)| id.clone()) else {
+            break;
+        };
+        if let Some(removed) = payloads.remove(&oldest) {
+            total -= removed.bytes.len();
+        }
+    }
+}
+
+pub fn publish(app: &AppHandle, bytes: Vec<u8>, mime: &str) -> PayloadRef {
+    let mut raw = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut raw);
+    let id = hex::encode(raw);
+    let size = bytes.len();
+
+    let store = app.state::<PayloadStore>();
+    let mut payloads = store.payloads.lock().unwrap();
+    evict(&mut payloads, size);
+    payloads.insert(
+        id.clone(),
+        Payload {
+            bytes: Arc::new(bytes),
+            mime: mime.to_string(),
+            created: Instant::now(),
+        },
+    );
+    PayloadRef {
+        url: payload_url(&id),
+        id,
+        mime: mime.to_string(),
+        size,
+    }
+}
+
+// "bytes=start-end", "bytes=start-" or "bytes=-suffix"; None when unsatisfiable
+fn parse_range(header: &str, len: usize) -> Option<(usize, usize)> {
+    let spec = header.trim().strip_prefix("bytes=")?;
+    // Multiple ranges are not supported; serve the first
+    let (start, end) = spec.split(',').next()?.trim().split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: usize = suffix.parse().ok()?;
+            (len.saturating_sub(suffix), len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => (start.parse().ok()?, end.parse::<usize>().ok()?.min(len.checked_sub(1)?)),
+    };
+    (start <= end && start < len).then_some((start, end))
+}
+
+pub fn serve(app: &AppHandle, request: &Request) -> Result<Response, Box<dyn std::error::Error>> {
+    let id = url::Url::parse(request.uri())
+        .ok()
+        .map(|uri| uri.path().trim_start_matches('/').to_string())
+        .unwrap_or_default();
+    let found = {
+        let store = app.state::<PayloadStore>();
+        let payloads = store.payloads.lock().unwrap();
+        payloads
+            .get(&id)
+            .filter(|p| p.created.elapsed() < PAYLOAD_TTL)
+            .map(|p| (p.bytes.clone(), p.mime.clone()))
+    };
+    let Some((bytes, mime)) = found else {
+        return ResponseBuilder::new()
+            .mimetype("text/plain")
+            .header("Access-Control-Allow-Origin", "*")
+            .status(404)
+            .body(b"Payload not found or expired".to_vec());
+    };
+
+    let builder = ResponseBuilder::new()
+        .mimetype(&mime)
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Access-Control-Expose-Headers", "Content-Range, Content-Length")
+        .header("Accept-Ranges", "bytes");
+    let range = request
+        .headers()
+        .get("range")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| parse_range(v, bytes.len()));
+    match range {
+        None => builder.status(200).body(bytes.to_vec()),
+        Some(None) => builder
+            .header("Content-Range", format!("bytes */{}", bytes.len()))
+            .status(416)
+            .body(Vec::new()),
+        Some(Some((start, end))) => builder
+            .header("Content-Range", format!("bytes {}-{}/{}", start, end, bytes.len()))
+            .status(206)
+            .body(bytes[start..=end].to_vec()),
+    }
+}
+
+// Free a payload as soon as the frontend is done with it
+#[tauri::command]
+#[specta::specta]
+pub async fn release_payload(store: tauri::State<'_, PayloadStore>, id: String) -> Result<(), String> {
+    store.payloads.lock().unwrap().remove(&id);
+    Ok(())
+}
@@ -0,0 +1,239 @@
+// Plugin marketplace client
+// Talks to a configurable registry over HTTP:
+//   GET <url>/plugins?q=<query>            -> [RegistryEntry]
+//   GET <url>/plugins/<id>/<version>       -> PackageRelease ("latest" for the newest)
+// A release carries the raw manifest, a module URL, the module's SHA-256 and
+// an Ed25519 signature over "<manifest>\n<sha256>" made with the registry's
+// key. Nothing is installed unless the checksum and signature both verify
+// against the public key configured here.
+
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::plugins::{self, PluginInfo};
+use crate::{bandwidth, storage};
+
+const REGISTRY_FILE: &str = "plugin-registry.json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct RegistryConfig {
+    url: String,
+    // Base64 Ed25519 public key releases must be signed with
+    public_key: String,
+}
+
+#[derive(Default)]
+pub struct RegistryState {
+    config: Mutex<RegistryConfig>,
+}
+
+impl RegistryState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, REGISTRY_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RegistryEntry {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    latest_version: String,
+    // Filled in locally
+    #[serde(default)]
+    installed_version: Option<String>,
+    #[serde(default)]
+    update_available: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+struct PackageRelease {
+    version: String,
+    // plugin.json exactly as signed
+    manifest: String,
+    module_url: String,
+    sha256: String,
+    signature: String,
+}
+
+fn config(app: &AppHandle) -> Result<RegistryConfig, String> {
+    let config = app.state::<RegistryState>().config.lock().unwrap().clone();
+    if config.url.trim().is_empty() {
+        return Err("No plugin registry configured".to_string());
+    }
+    Ok(config)
+}
+
+fn endpoint(config: &RegistryConfig, path: &str) -> String {
+    format!("{}/{}", config.url.trim_end_matches('/'), path)
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+fn is_newer(candidate: &str, installed: &str) -> bool {
+    match (parse_version(candidate), parse_version(installed)) {
+        (Some(candidate), Some(installed)) => candidate > installed,
+        _ => false,
+    }
+}
+
+fn verifying_key(config: &RegistryConfig) -> Result<VerifyingKey, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(config.public_key.trim())
+        .map_err(|_| "Registry public key is not valid base64".to_string())?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| "Registry public key must be 32 bytes".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+fn verify(config: &RegistryConfig, release: &PackageRelease, module: &[u8]) -> Result<(), String> {
+    let digest = hex::encode(Sha256::digest(module));
+    if !digest.eq_ignore_ascii_case(release.sha256.trim()) {
+        return Err("Plugin checksum mismatch".to_string());
+    }
+    let signature = base64::engine::general_purpose::STANDARD
+        .decode(release.signature.trim())
+        .map_err(|_| "Plugin signature is not valid base64".to_string())?;
+    let signature = Signature::from_slice(&signature).map_err(|e| e.to_string())?;
+    let message = format!("{}\n{}", release.manifest, release.sha256.trim().to_lowercase());
+    verifying_key(config)?
+        .verify(message.as_bytes(), &signature)
+        .map_err(|_| "Plugin signature is invalid".to_string())
+}
+
+// Send a request and read its whole body, counting the traffic
+async fn fetch(app: &AppHandle, request: reqwest::RequestBuilder) -> Result<Vec<u8>, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+    let url = response.url().to_string();
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    bandwidth::record(app, &url, 0, body.len() as u64);
+    Ok(body.to_vec())
+}
+
+async fn fetch_entries(app: &AppHandle, config: &RegistryConfig, query: &str) -> Result<Vec<RegistryEntry>, String> {
+    let request = reqwest::Client::new()
+        .get(endpoint(config, "plugins"))
+        .query(&[("q", query)])
+        .timeout(REQUEST_TIMEOUT);
+    let body = fetch(app, request).await?;
+    serde_json::from_slice(&body).map_err(|e| format!("Invalid registry response: {}", e))
+}
+
+fn mark_installed(app: &AppHandle, entries: &mut [RegistryEntry]) {
+    let installed = plugins::installed_versions(app);
+    for entry in entries {
+        entry.installed_version = installed.get(&entry.id).cloned();
+        entry.update_available = entry
+            .installed_version
+            .as_deref()
+            .map_or(false, |version| is_newer(&entry.latest_version, version));
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_plugin_registry_config(state: tauri::State<'_, RegistryState>) -> Result<RegistryConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_plugin_registry_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, RegistryState>,
+    config: RegistryConfig,
+) -> Result<(), String> {
+    if !config.url.is_empty() {
+        let url = url::Url::parse(&config.url).map_err(|e| format!("Invalid registry URL: {}", e))?;
+        if url.scheme() != "https" {
+            return Err("The plugin registry must use HTTPS".to_string());
+        }
+        verifying_key(&config)?;
+    }
+    storage::save(&app_handle, REGISTRY_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn search_plugin_registry(app_handle: AppHandle, query: String) -> Result<Vec<RegistryEntry>, String> {
+    let config = config(&app_handle)?;
+    let mut entries = fetch_entries(&app_handle, &config, query.trim()).await?;
+    mark_installed(&app_handle, &mut entries);
+    Ok(entries)
+}
+
+// Installed plugins with a newer release in the registry
+#[tauri::command]
+#[specta::specta]
+pub async fn check_plugin_updates(app_handle: AppHandle) -> Result<Vec<RegistryEntry>, String> {
+    let config = config(&app_handle)?;
+    let installed = plugins::installed_versions(&app_handle);
+    let mut updates = Vec::new();
+    for (id, version) in installed {
+        let mut entries = fetch_entries(&app_handle, &config, &id).await?;
+        entries.retain(|e| e.id == id && is_newer(&e.latest_version, &version));
+        updates.extend(entries);
+    }
+    mark_installed(&app_handle, &mut updates);
+    Ok(updates)
+}
+
+// Download, verify and install a release; `version` defaults to the latest.
+// Installing over an existing plugin updates it in place.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_plugin_from_registry(
+    app_handle: AppHandle,
+    id: String,
+    version: Option<String>,
+) -> Result<PluginInfo, String> {
+    let config = config(&app_handle)?;
+    let version = version.unwrap_or_else(|| "latest".to_string());
+    let client = reqwest::Client::new();
+    let request = client
+        .get(endpoint(
+            &config,
+            &format!("plugins/{}/{}", urlencode(&id), urlencode(&version)),
+        ))
+        .timeout(REQUEST_TIMEOUT);
+    let release: PackageRelease = serde_json::from_slice(&fetch(&app_handle, request).await?)
+        .map_err(|e| format!("Invalid registry response: {}", e))?;
+
+    let module = fetch(&app_handle, client.get(&release.module_url).timeout(DOWNLOAD_TIMEOUT)).await?;
+    verify(&config, &release, &module)?;
+
+    // The signed manifest must describe what was asked for
+    let manifest: serde_json::Value =
+        serde_json::from_str(&release.manifest).map_err(|e| format!("Invalid manifest: {}", e))?;
+    if manifest["id"].as_str() != Some(id.as_str()) || manifest["version"].as_str() != Some(release.version.as_str()) {
+        return Err("Registry release does not match the requested plugin".to_string());
+    }
+    plugins::install(&app_handle, release.manifest.as_bytes(), &module).await
+}
+
+fn urlencode(segment: &str) -> String {
+    url::form_urlencoded::byte_serialize(segment.as_bytes()).collect()
+}
@@ -0,0 +1,876 @@
+// Sandboxed WASM plugins
+// Plugins are installed into `<app data>/plugins/<id>/` (a `plugin.json`
+// manifest plus the module) and run in wasmtime without WASI, with fuel and
+// memory limits on every call. Anything beyond the sandbox goes through host
+// functions that check the capabilities the user granted at install time.
+//
+// Host interface, all payloads UTF-8:
+//   exports  memory, alloc(len: i32) -> i32
+//            on_navigation(ptr, len) -> i64     {window_id, url}
+//            on_context_menu(ptr, len) -> i64   the context-menu click payload
+//            run_command(ptr, len) -> i64       {command, args}
+//   imports  madeasy.log(ptr, len)              write to the app log
+//            madeasy.emit(ptr, len)             emit `plugin-event` to the frontend
+//            madeasy.http_get(ptr, len) -> i64  URL; needs a matching network host
+//            madeasy.read_file(ptr, len) -> i64 path; needs a granted directory
+//            madeasy.history_search(ptr, len) -> i64   query; needs history_read
+//            madeasy.ai_complete(ptr, len) -> i64      {system, prompt}; needs ai
+// Hooks are optional and take JSON. They return 0 for no reply, or
+// (ptr << 32 | len) of a JSON reply in plugin memory. Host functions that
+// return i64 reply the same way with {"ok": ...} or {"error": "..."}.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+use wasmtime::{Caller, Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::audit::{self, AuditCategory};
+use crate::contextmenu::{self, ContextMenuItem, MenuClickPayload};
+use crate::db::Database;
+use crate::ai::{self, AiTask};
+use crate::{bandwidth, history, matching, safemode, storage};
+
+const PLUGINS_DIR: &str = "plugins";
+const PLUGINS_FILE: &str = "plugins.json";
+const MANIFEST_FILE: &str = "plugin.json";
+// Instruction budget per hook call
+const CALL_FUEL: u64 = 50_000_000;
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+const HTTP_MAX_REDIRECTS: usize = 10;
+// Longer response bodies are truncated
+const HTTP_MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+const AI_TIMEOUT: Duration = Duration::from_secs(60);
+const HISTORY_LIMIT: u32 = 50;
+
+// Capabilities a plugin asks for in its manifest and the user grants
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct Permissions {
+    // Host patterns with `*` wildcards, e.g. "api.example.com", "*.example.org"
+    network_hosts: Vec<String>,
+    // Absolute directories the plugin may read from
+    filesystem_paths: Vec<String>,
+    history_read: bool,
+    ai: bool,
+}
+
+impl Permissions {
+    fn is_empty(&self) -> bool {
+        self == &Permissions::default()
+    }
+
+    fn is_subset_of(&self, other: &Permissions) -> bool {
+        self.network_hosts.iter().all(|h| other.network_hosts.contains(h))
+            && self.filesystem_paths.iter().all(|p| other.filesystem_paths.contains(p))
+            && (!self.history_read || other.history_read)
+            && (!self.ai || other.ai)
+    }
+
+    // Only the parts of `self` that are also in `requested`
+    fn restrict_to(&self, requested: &Permissions) -> Permissions {
+        Permissions {
+            network_hosts: self
+                .network_hosts
+                .iter()
+                .filter(|h| requested.network_hosts.contains(h))
+                .cloned()
+                .collect(),
+            filesystem_paths: self
+                .filesystem_paths
+                .iter()
+                .filter(|p| requested.filesystem_paths.contains(p))
+                .cloned()
+                .collect(),
+            history_read: self.history_read && requested.history_read,
+            ai: self.ai && requested.ai,
+        }
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .network_hosts
+            .iter()
+            .map(|h| format!("Connect to {}", h))
+            .collect();
+        lines.extend(self.filesystem_paths.iter().map(|p| format!("Read files in {}", p)));
+        if self.history_read {
+            lines.push("Read your browsing history".to_string());
+        }
+        if self.ai {
+            lines.push("Use your AI provider".to_string());
+        }
+        lines
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for host in &self.network_hosts {
+            let valid = !host.is_empty()
+                && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '*'))
+                && host.chars().any(|c| c != '*' && c != '.');
+            if !valid {
+                return Err(format!("Invalid network host: {}", host));
+            }
+        }
+        for path in &self.filesystem_paths {
+            if !Path::new(path).is_absolute() {
+                return Err(format!("Filesystem path must be absolute: {}", path));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_url(&self, url: &str) -> Result<(), String> {
+        let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+        }
+        let host = parsed.host_str().unwrap_or_default();
+        if self.network_hosts.iter().any(|pattern| matching::wildcard_match(pattern, host)) {
+            Ok(())
+        } else {
+            Err(format!("Permission denied: network access to {}", host))
+        }
+    }
+
+    // Resolves symlinks and `..` before comparing, so granted directories
+    // cannot be escaped
+    fn check_path(&self, path: &str) -> Result<PathBuf, String> {
+        let resolved = std::fs::canonicalize(path).map_err(|e| format!("{}: {}", path, e))?;
+        let allowed = self
+            .filesystem_paths
+            .iter()
+            .filter_map(|dir| std::fs::canonicalize(dir).ok())
+            .any(|dir| resolved.starts_with(dir));
+        if allowed {
+            Ok(resolved)
+        } else {
+            Err(format!("Permission denied: reading {}", path))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PluginManifest {
+    id: String,
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "default_module")]
+    module: String,
+    #[serde(default)]
+    permissions: Permissions,
+    // Registered while the plugin is enabled; clicks go to `on_context_menu`
+    #[serde(default)]
+    context_menu_items: Vec<ContextMenuItem>,
+}
+
+fn default_module() -> String {
+    "plugin.wasm".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct PluginRecord {
+    id: String,
+    enabled: bool,
+    #[serde(default)]
+    granted: Permissions,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PluginInfo {
+    id: String,
+    name: String,
+    version: String,
+    description: String,
+    enabled: bool,
+    loaded: bool,
+    requested: Permissions,
+    granted: Permissions,
+    last_error: Option<String>,
+}
+
+struct HostState {
+    app: AppHandle,
+    plugin_id: String,
+    granted: Permissions,
+    limits: StoreLimits,
+}
+
+struct Running {
+    store: Store<HostState>,
+    instance: Instance,
+}
+
+struct Plugin {
+    manifest: PluginManifest,
+    enabled: bool,
+    granted: Permissions,
+    running: Option<Arc<Mutex<Running>>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+// A running plugin's instance, cloned out of the map so hooks run without
+// holding the plugins lock
+struct Hooks {
+    running: Arc<Mutex<Running>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl Plugin {
+    fn hooks(&self) -> Option<Hooks> {
+        Some(Hooks {
+            running: self.running.clone()?,
+            last_error: self.last_error.clone(),
+        })
+    }
+
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            id: self.manifest.id.clone(),
+            name: self.manifest.name.clone(),
+            version: self.manifest.version.clone(),
+            description: self.manifest.description.clone(),
+            enabled: self.enabled,
+            loaded: self.running.is_some(),
+            requested: self.manifest.permissions.clone(),
+            granted: self.granted.clone(),
+            last_error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+pub struct PluginState {
+    engine: Engine,
+    plugins: Mutex<HashMap<String, Plugin>>,
+    // Set once `load_installed` has run
+    loaded: AtomicBool,
+}
+
+// Installed plugins are loaded later by `load_installed`
+impl Default for PluginState {
+    fn default() -> Self {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Self {
+            engine: Engine::new(&config).expect("wasmtime engine"),
+            plugins: Mutex::new(HashMap::new()),
+            loaded: AtomicBool::new(false),
+        }
+    }
+}
+
+// Load installed plugins and start the enabled ones. Compiling modules is slow,
+// so this runs on the deferred startup thread without holding the lock.
+pub fn load_installed(app: &AppHandle) {
+    let state = app.state::<PluginState>();
+    let records: Vec<PluginRecord> = storage::load(app, PLUGINS_FILE);
+    let mut loaded = Vec::new();
+    for record in records {
+        let manifest = match plugin_dir(app, &record.id).and_then(|dir| read_manifest(&dir)) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("Skipping plugin {}: {}", record.id, e);
+                continue;
+            }
+        };
+        let mut plugin = Plugin {
+            granted: record.granted.restrict_to(&manifest.permissions),
+            manifest,
+            enabled: record.enabled,
+            running: None,
+            last_error: Arc::new(Mutex::new(None)),
+        };
+        // Safe mode lists plugins without running them
+        if plugin.enabled && !safemode::active(app) {
+            start(app, &state.engine, &mut plugin);
+        }
+        loaded.push((record.id, plugin));
+    }
+
+    // A plugin installed while these were loading takes precedence
+    let mut plugins = state.plugins.lock().unwrap();
+    for (id, plugin) in loaded {
+        plugins.entry(id).or_insert(plugin);
+    }
+    state.loaded.store(true, Ordering::SeqCst);
+}
+
+// Recovery: stop and disable every plugin, returning how many were enabled
+pub fn disable_all(app: &AppHandle) -> Result<usize, String> {
+    let state = app.state::<PluginState>();
+    let mut plugins = state.plugins.lock().unwrap();
+    let mut disabled = 0;
+    for plugin in plugins.values_mut().filter(|p| p.enabled) {
+        plugin.enabled = false;
+        stop(app, plugin);
+        disabled += 1;
+    }
+    save_records(app, &plugins)?;
+    Ok(disabled)
+}
+
+fn plugins_root(app: &AppHandle) -> PathBuf {
+    app.path_resolver()
+        .app_data_dir()
+        .unwrap_or_default()
+        .join(PLUGINS_DIR)
+}
+
+// Ids start with a letter or digit, which also rules out "." and ".."
+fn valid_id(id: &str) -> bool {
+    id.len() <= 64
+        && id.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'))
+}
+
+fn plugin_dir(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    if !valid_id(id) {
+        return Err(format!("Invalid plugin id: {}", id));
+    }
+    let root = plugins_root(app);
+    let dir = root.join(id);
+    // Once the directory exists, make sure links don't lead out of the plugins root
+    if let (Ok(root), Ok(real)) = (root.canonicalize(), dir.canonicalize()) {
+        if real == root || !real.starts_with(&root) {
+            return Err(format!("Plugin directory of {} is outside the plugins folder", id));
+        }
+    }
+    Ok(dir)
+}
+
+fn validate_manifest(manifest: &PluginManifest) -> Result<(), String> {
+    if !valid_id(&manifest.id) {
+        return Err(format!("Invalid plugin id: {}", manifest.id));
+    }
+    if manifest.name.trim().is_empty() {
+        return Err("Plugin name is required".to_string());
+    }
+    let valid_version = manifest.version.split('.').count() == 3
+        && manifest
+            .version
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if !valid_version {
+        return Err(format!("Version must look like 1.2.3: {}", manifest.version));
+    }
+    if manifest.module.contains('/') || manifest.module.contains('\\') || manifest.module.starts_with('.') {
+        return Err(format!("Invalid module file name: {}", manifest.module));
+    }
+    manifest.permissions.validate()
+}
+
+fn parse_manifest(bytes: &[u8]) -> Result<PluginManifest, String> {
+    let manifest: PluginManifest = serde_json::from_slice(bytes).map_err(|e| format!("Invalid manifest: {}", e))?;
+    validate_manifest(&manifest)?;
+    Ok(manifest)
+}
+
+fn read_manifest(dir: &Path) -> Result<PluginManifest, String> {
+    let bytes = std::fs::read(dir.join(MANIFEST_FILE)).map_err(|e| format!("Cannot read manifest: {}", e))?;
+    parse_manifest(&bytes)
+}
+
+fn read_memory(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let memory = caller.get_export("memory")?.into_memory()?;
+    let data = memory.data(&caller);
+    let start = usize::try_from(ptr).ok()?;
+    let end = start.checked_add(usize::try_from(len).ok()?)?;
+    data.get(start..end).map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+}
+
+// Copy a JSON reply into plugin memory via its `alloc`; 0 if that fails
+fn write_reply(caller: &mut Caller<'_, HostState>, value: &Value) -> i64 {
+    let bytes = value.to_string().into_bytes();
+    let Ok(len) = i32::try_from(bytes.len()) else { return 0 };
+    let Some(alloc) = caller.get_export("alloc").and_then(|e| e.into_func()) else { return 0 };
+    let Ok(alloc) = alloc.typed::<i32, i32>(&*caller) else { return 0 };
+    let Ok(ptr) = alloc.call(&mut *caller, len) else { return 0 };
+    let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) else { return 0 };
+    if memory.write(&mut *caller, ptr as u32 as usize, &bytes).is_err() {
+        return 0;
+    }
+    ((ptr as u32 as i64) << 32) | len as i64
+}
+
+// Run a capability-checked host function and hand its result back to the plugin
+fn host_call(
+    mut caller: Caller<'_, HostState>,
+    ptr: i32,
+    len: i32,
+    handler: impl FnOnce(&HostState, &str) -> Result<Value, String>,
+) -> i64 {
+    let result = match read_memory(&mut caller, ptr, len) {
+        Some(input) => handler(caller.data(), &input),
+        None => Err("Invalid memory range".to_string()),
+    };
+    let reply = match result {
+        Ok(value) => json!({ "ok": value }),
+        Err(e) => {
+            eprintln!("[plugin {}] {}", caller.data().plugin_id, e);
+            json!({ "error": e })
+        }
+    };
+    write_reply(&mut caller, &reply)
+}
+
+// Redirects are followed by hand so every hop must match a granted host
+fn http_get(state: &HostState, url: &str) -> Result<Value, String> {
+    state.granted.check_url(url)?;
+    tauri::async_runtime::block_on(async {
+        let client = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| e.to_string())?;
+        let mut current = url::Url::parse(url).map_err(|e| e.to_string())?;
+        for _ in 0..=HTTP_MAX_REDIRECTS {
+            let mut response = client.get(current.clone()).send().await.map_err(|e| e.to_string())?;
+            bandwidth::record(&state.app, current.as_str(), 0, 0);
+            if response.status().is_redirection() {
+                if let Some(location) = response.headers().get(reqwest::header::LOCATION) {
+                    let location = location.to_str().map_err(|e| e.to_string())?;
+                    current = current.join(location).map_err(|e| e.to_string())?;
+                    state.granted.check_url(current.as_str())?;
+                    continue;
+                }
+            }
+            let status = response.status().as_u16();
+            let mut body = Vec::new();
+            while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+                body.extend_from_slice(&chunk);
+                if body.len() >= HTTP_MAX_BODY_BYTES {
+                    body.truncate(HTTP_MAX_BODY_BYTES);
+                    break;
+                }
+            }
+            bandwidth::record_received(&state.app, current.as_str(), body.len() as u64);
+            let body = String::from_utf8_lossy(&body);
+            return Ok(json!({ "status": status, "body": body }));
+        }
+        Err("Too many redirects".to_string())
+    })
+}
+
+fn read_file(state: &HostState, path: &str) -> Result<Value, String> {
+    let path = state.granted.check_path(path)?;
+    std::fs::read_to_string(path).map(Value::String).map_err(|e| e.to_string())
+}
+
+fn history_search(state: &HostState, query: &str) -> Result<Value, String> {
+    if !state.granted.history_read {
+        return Err("Permission denied: history access".to_string());
+    }
+    let db = state.app.state::<Database>();
+    let query = Some(query.trim()).filter(|q| !q.is_empty());
+    let entries = history::frecency_ranked(&db, query, HISTORY_LIMIT)?;
+    serde_json::to_value(entries).map_err(|e| e.to_string())
+}
+
+fn ai_complete(state: &HostState, input: &str) -> Result<Value, String> {
+    if !state.granted.ai {
+        return Err("Permission denied: AI access".to_string());
+    }
+    let request: Value = serde_json::from_str(input).map_err(|e| format!("Invalid request: {}", e))?;
+    let prompt = request["prompt"].as_str().ok_or_else(|| "Missing prompt".to_string())?;
+    let system = request["system"].as_str().unwrap_or_default();
+    let reply = ai::complete(&state.app, AiTask::Chat, system, prompt, AI_TIMEOUT);
+    tauri::async_runtime::block_on(reply).map(Value::String)
+}
+
+fn linker(engine: &Engine) -> Result<Linker<HostState>, String> {
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap("madeasy", "log", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            if let Some(message) = read_memory(&mut caller, ptr, len) {
+                eprintln!("[plugin {}] {}", caller.data().plugin_id, message);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    linker
+        .func_wrap("madeasy", "emit", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            if let Some(text) = read_memory(&mut caller, ptr, len) {
+                let payload: Value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+                let state = caller.data();
+                let _ = state
+                    .app
+                    .emit_all("plugin-event", json!({ "plugin_id": state.plugin_id, "payload": payload }));
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    linker
+        .func_wrap("madeasy", "http_get", |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            host_call(caller, ptr, len, http_get)
+        })
+        .map_err(|e| e.to_string())?;
+    linker
+        .func_wrap("madeasy", "read_file", |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            host_call(caller, ptr, len, read_file)
+        })
+        .map_err(|e| e.to_string())?;
+    linker
+        .func_wrap("madeasy", "history_search", |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            host_call(caller, ptr, len, history_search)
+        })
+        .map_err(|e| e.to_string())?;
+    linker
+        .func_wrap("madeasy", "ai_complete", |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            host_call(caller, ptr, len, ai_complete)
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(linker)
+}
+
+fn instantiate(app: &AppHandle, engine: &Engine, plugin: &Plugin) -> Result<Running, String> {
+    let manifest = &plugin.manifest;
+    let path = plugin_dir(app, &manifest.id)?.join(&manifest.module);
+    let module = Module::from_file(engine, &path).map_err(|e| e.to_string())?;
+    let mut store = Store::new(
+        engine,
+        HostState {
+            app: app.clone(),
+            plugin_id: manifest.id.clone(),
+            granted: plugin.granted.clone(),
+            limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).build(),
+        },
+    );
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(CALL_FUEL).map_err(|e| e.to_string())?;
+    let instance = linker(engine)?
+        .instantiate(&mut store, &module)
+        .map_err(|e| e.to_string())?;
+    if instance.get_memory(&mut store, "memory").is_none() {
+        return Err("Plugin does not export its memory".to_string());
+    }
+    Ok(Running { store, instance })
+}
+
+fn start(app: &AppHandle, engine: &Engine, plugin: &mut Plugin) {
+    match instantiate(app, engine, plugin) {
+        Ok(running) => {
+            plugin.running = Some(Arc::new(Mutex::new(running)));
+            *plugin.last_error.lock().unwrap() = None;
+            contextmenu::register_owned(app, &plugin.manifest.id, plugin.manifest.context_menu_items.clone());
+        }
+        Err(e) => *plugin.last_error.lock().unwrap() = Some(e),
+    }
+}
+
+fn stop(app: &AppHandle, plugin: &mut Plugin) {
+    plugin.running = None;
+    contextmenu::unregister_owned(app, &plugin.manifest.id);
+}
+
+// Call a hook with a JSON argument; Ok(None) when the plugin doesn't implement it.
+// Host functions block on async work, so this must not run on the async runtime.
+fn call_hook(running: &mut Running, hook: &str, input: &Value) -> Result<Option<Value>, String> {
+    let Running { store, instance } = running;
+    let Ok(func) = instance.get_typed_func::<(i32, i32), i64>(&mut *store, hook) else {
+        return Ok(None);
+    };
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&mut *store, "alloc")
+        .map_err(|_| "Plugin does not export alloc".to_string())?;
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| "Plugin does not export its memory".to_string())?;
+    store.set_fuel(CALL_FUEL).map_err(|e| e.to_string())?;
+
+    let bytes = input.to_string().into_bytes();
+    let len = i32::try_from(bytes.len()).map_err(|_| "Hook input too large".to_string())?;
+    let ptr = alloc.call(&mut *store, len).map_err(|e| e.to_string())?;
+    memory
+        .write(&mut *store, ptr as u32 as usize, &bytes)
+        .map_err(|e| e.to_string())?;
+
+    let reply = func.call(&mut *store, (ptr, len)).map_err(|e| e.to_string())?;
+    if reply == 0 {
+        return Ok(None);
+    }
+    let (ptr, len) = ((reply >> 32) as u32 as usize, reply as u32 as usize);
+    let data = memory
+        .data(&*store)
+        .get(ptr..ptr.saturating_add(len))
+        .ok_or_else(|| "Plugin reply is out of bounds".to_string())?;
+    serde_json::from_slice(data)
+        .map(Some)
+        .map_err(|e| format!("Plugin reply is not valid JSON: {}", e))
+}
+
+// Run a hook on one plugin, recording failures on the plugin
+fn dispatch(hooks: &Hooks, hook: &str, input: &Value) -> Result<Option<Value>, String> {
+    let result = call_hook(&mut hooks.running.lock().unwrap(), hook, input);
+    if let Err(e) = &result {
+        *hooks.last_error.lock().unwrap() = Some(format!("{}: {}", hook, e));
+    }
+    result
+}
+
+// Hooks of a running plugin, taken under the plugins lock only briefly
+fn running_hooks(app: &AppHandle, plugin_id: &str) -> Option<Hooks> {
+    app.state::<PluginState>().plugins.lock().unwrap().get(plugin_id)?.hooks()
+}
+
+// Notify running plugins of a finished navigation, off the UI thread
+pub fn on_page_load(window: &Window, url: &str) {
+    let app = window.app_handle();
+    let input = json!({ "window_id": window.label(), "url": url });
+    std::thread::spawn(move || {
+        let running: Vec<Hooks> = {
+            let state = app.state::<PluginState>();
+            let plugins = state.plugins.lock().unwrap();
+            plugins.values().filter_map(Plugin::hooks).collect()
+        };
+        for hooks in running {
+            let _ = dispatch(&hooks, "on_navigation", &input);
+        }
+    });
+}
+
+pub fn on_context_menu(app: &AppHandle, plugin_id: &str, payload: &MenuClickPayload) {
+    let Ok(input) = serde_json::to_value(payload) else { return };
+    let app = app.clone();
+    let plugin_id = plugin_id.to_string();
+    std::thread::spawn(move || {
+        if let Some(hooks) = running_hooks(&app, &plugin_id) {
+            let _ = dispatch(&hooks, "on_context_menu", &input);
+        }
+    });
+}
+
+fn save_records(app: &AppHandle, plugins: &HashMap<String, Plugin>) -> Result<(), String> {
+    let mut records: Vec<PluginRecord> = plugins
+        .values()
+        .map(|p| PluginRecord {
+            id: p.manifest.id.clone(),
+            enabled: p.enabled,
+            granted: p.granted.clone(),
+        })
+        .collect();
+    // Until `load_installed` has run, keep the records it hasn't got to yet
+    if !app.state::<PluginState>().loaded.load(Ordering::SeqCst) {
+        let stored: Vec<PluginRecord> = storage::load(app, PLUGINS_FILE);
+        records.extend(stored.into_iter().filter(|r| !plugins.contains_key(&r.id)));
+    }
+    records.sort_by(|a, b| a.id.cmp(&b.id));
+    storage::save(app, PLUGINS_FILE, &records)
+}
+
+// Native consent dialog listing what the plugin asks for
+fn ask_consent(manifest: &PluginManifest) -> bool {
+    let message = format!(
+        "{} {} requests permission to:\n\n- {}\n\nAllow?",
+        manifest.name,
+        manifest.version,
+        manifest.permissions.describe().join("\n- ")
+    );
+    tauri::api::dialog::blocking::ask(None::<&Window>, "Install plugin", message)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_plugins(state: tauri::State<'_, PluginState>) -> Result<Vec<PluginInfo>, String> {
+    let mut plugins: Vec<PluginInfo> = state.plugins.lock().unwrap().values().map(Plugin::info).collect();
+    plugins.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    Ok(plugins)
+}
+
+// Installed plugin ids and versions, for update checks
+pub fn installed_versions(app: &AppHandle) -> HashMap<String, String> {
+    let state = app.state::<PluginState>();
+    let plugins = state.plugins.lock().unwrap();
+    plugins
+        .values()
+        .map(|p| (p.manifest.id.clone(), p.manifest.version.clone()))
+        .collect()
+}
+
+// Install (or update) a plugin from its manifest and module. New plugins start
+// disabled; updates keep their enabled state. The user is asked to consent to
+// the requested permissions unless an update asks for nothing new.
+pub async fn install(app: &AppHandle, manifest_bytes: &[u8], wasm: &[u8]) -> Result<PluginInfo, String> {
+    let state = app.state::<PluginState>();
+    let manifest = parse_manifest(manifest_bytes)?;
+    // Compile once up front so broken modules are rejected at install time
+    Module::new(&state.engine, wasm).map_err(|e| format!("Invalid WASM module: {}", e))?;
+
+    let previous = state
+        .plugins
+        .lock()
+        .unwrap()
+        .get(&manifest.id)
+        .map(|p| (p.manifest.permissions.clone(), p.granted.clone()));
+    let granted = match previous {
+        Some((requested, granted)) if manifest.permissions.is_subset_of(&requested) => {
+            granted.restrict_to(&manifest.permissions)
+        }
+        _ if manifest.permissions.is_empty() => Permissions::default(),
+        _ => {
+            let prompt = manifest.clone();
+            let allowed = tauri::async_runtime::spawn_blocking(move || ask_consent(&prompt))
+                .await
+                .map_err(|e| e.to_string())?;
+            if !allowed {
+                return Err("Plugin installation cancelled".to_string());
+            }
+            audit::record(
+                app,
+                AuditCategory::Settings,
+                "plugin.permissions.grant",
+                json!({ "plugin": manifest.id, "permissions": manifest.permissions }),
+            );
+            manifest.permissions.clone()
+        }
+    };
+
+    std::fs::create_dir_all(plugin_dir(app, &manifest.id)?).map_err(|e| e.to_string())?;
+    // Checked again now that the directory exists
+    let dir = plugin_dir(app, &manifest.id)?;
+    std::fs::write(dir.join(&manifest.module), wasm).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(MANIFEST_FILE), manifest_bytes).map_err(|e| e.to_string())?;
+
+    let mut plugins = state.plugins.lock().unwrap();
+    let enabled = match plugins.remove(&manifest.id) {
+        Some(mut previous) => {
+            stop(app, &mut previous);
+            previous.enabled
+        }
+        None => false,
+    };
+    let mut plugin = Plugin {
+        manifest,
+        enabled,
+        granted,
+        running: None,
+        last_error: Arc::new(Mutex::new(None)),
+    };
+    if enabled {
+        start(app, &state.engine, &mut plugin);
+    }
+    let info = plugin.info();
+    plugins.insert(info.id.clone(), plugin);
+    save_records(app, &plugins)?;
+    Ok(info)
+}
+
+// Install from a local directory containing plugin.json and the module
+#[tauri::command]
+#[specta::specta]
+pub async fn install_plugin(app_handle: AppHandle, path: String) -> Result<PluginInfo, String> {
+    let source = PathBuf::from(&path);
+    let manifest_bytes =
+        std::fs::read(source.join(MANIFEST_FILE)).map_err(|e| format!("Cannot read manifest: {}", e))?;
+    let manifest = parse_manifest(&manifest_bytes)?;
+    let wasm = std::fs::read(source.join(&manifest.module)).map_err(|e| format!("Cannot read module: {}", e))?;
+    install(&app_handle, &manifest_bytes, &wasm).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn enable_plugin(
+    app_handle: AppHandle,
+    state: tauri::State<'_, PluginState>,
+    plugin_id: String,
+    enabled: bool,
+) -> Result<PluginInfo, String> {
+    let mut plugins = state.plugins.lock().unwrap();
+    let plugin = plugins
+        .get_mut(&plugin_id)
+        .ok_or_else(|| format!("Unknown plugin: {}", plugin_id))?;
+    plugin.enabled = enabled;
+    if enabled && plugin.running.is_none() {
+        start(&app_handle, &state.engine, plugin);
+    } else if !enabled {
+        stop(&app_handle, plugin);
+    }
+    let info = plugin.info();
+    save_records(&app_handle, &plugins)?;
+    Ok(info)
+}
+
+// Revoke (part of) a plugin's granted permissions. Granting more than was
+// consented to requires reinstalling.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_plugin_permissions(
+    app_handle: AppHandle,
+    state: tauri::State<'_, PluginState>,
+    plugin_id: String,
+    granted: Permissions,
+) -> Result<PluginInfo, String> {
+    let mut plugins = state.plugins.lock().unwrap();
+    let plugin = plugins
+        .get_mut(&plugin_id)
+        .ok_or_else(|| format!("Unknown plugin: {}", plugin_id))?;
+    if !granted.is_subset_of(&plugin.granted) {
+        return Err("Permissions can only be revoked here".to_string());
+    }
+    plugin.granted = granted;
+    // Restart so the running instance picks up the narrower grant
+    if plugin.running.is_some() {
+        stop(&app_handle, plugin);
+        start(&app_handle, &state.engine, plugin);
+    }
+    audit::record(
+        &app_handle,
+        AuditCategory::Settings,
+        "plugin.permissions.revoke",
+        json!({ "plugin": plugin_id, "permissions": plugin.granted }),
+    );
+    let info = plugin.info();
+    save_records(&app_handle, &plugins)?;
+    Ok(info)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn uninstall_plugin(
+    app_handle: AppHandle,
+    state: tauri::State<'_, PluginState>,
+    plugin_id: String,
+) -> Result<(), String> {
+    let mut plugins = state.plugins.lock().unwrap();
+    let mut plugin = plugins
+        .remove(&plugin_id)
+        .ok_or_else(|| format!("Unknown plugin: {}", plugin_id))?;
+    stop(&app_handle, &mut plugin);
+    let dir = plugin_dir(&app_handle, &plugin_id)?;
+    if dir.exists() {
+        std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    save_records(&app_handle, &plugins)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn run_plugin_command(
+    app_handle: AppHandle,
+    plugin_id: String,
+    command: String,
+    args: Option<Value>,
+) -> Result<Option<Value>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let hooks = {
+            let state = app_handle.state::<PluginState>();
+            let plugins = state.plugins.lock().unwrap();
+            let plugin = plugins
+                .get(&plugin_id)
+                .ok_or_else(|| format!("Unknown plugin: {}", plugin_id))?;
+            plugin
+                .hooks()
+                .ok_or_else(|| format!("Plugin {} is not running", plugin_id))?
+        };
+        dispatch(&hooks, "run_command", &json!({ "command": command, "args": args }))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,483 @@
+// Navigation policy
+// Every http(s) navigation in browser windows is checked against the active
+// profile's allow and deny rules: an allow rule wins over a deny rule, and
+// URLs matching neither get the default action (deny for parental-style
+// allow lists). Rules name a domain (subdomains included), a wildcard pattern
+// over host and path, or a built-in category. Blocked navigations are
+// cancelled by the webview where the engine allows it (and caught on page
+// load otherwise), replaced by the blocked page and published as a
+// `navigation-blocked` event. A PIN unlocks a host for a limited time.
+//
+// An administrator can install a managed policy file (see `managed_path`);
+// it then applies to every profile and cannot be changed from the app.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::http::{Request, Response, ResponseBuilder};
+use tauri::{AppHandle, Manager, Window};
+
+use crate::audit::{self, AuditCategory};
+use crate::events::{self, AppEvent};
+use crate::{matching, profiles, secrets, storage, tasks};
+
+const POLICY_FILE: &str = "policy.json";
+pub const BLOCKED_SCHEME: &str = "blocked";
+const BLOCKED_PAGE: &str = include_str!("../assets/blocked.html");
+const MAX_BYPASS_MINUTES: u32 = 24 * 60;
+const DEFAULT_BYPASS_MINUTES: u32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Social,
+    Video,
+    Gaming,
+    Gambling,
+    Adult,
+    Shopping,
+}
+
+impl Category {
+    fn domains(self) -> &'static [&'static str] {
+        match self {
+            Category::Social => &[
+                "facebook.com", "instagram.com", "tiktok.com", "twitter.com", "x.com", "snapchat.com", "reddit.com",
+                "pinterest.com", "tumblr.com", "threads.net",
+            ],
+            Category::Video => &["youtube.com", "twitch.tv", "vimeo.com", "netflix.com", "dailymotion.com"],
+            Category::Gaming => &["roblox.com", "steampowered.com", "epicgames.com", "miniclip.com", "poki.com"],
+            Category::Gambling => &[
+                "bet365.com", "pokerstars.com", "draftkings.com", "fanduel.com", "unibet.com", "betway.com",
+            ],
+            Category::Adult => &["pornhub.com", "xvideos.com", "xnxx.com", "xhamster.com", "onlyfans.com"],
+            Category::Shopping => &["amazon.com", "ebay.com", "aliexpress.com", "temu.com", "etsy.com"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyRule {
+    // Matches the domain and its subdomains
+    Domain { domain: String },
+    // `*` matches anything; patterns without a `/` apply to the host only
+    Wildcard { pattern: String },
+    Category { category: Category },
+}
+
+impl PolicyRule {
+    fn matches(&self, host: &str, path: &str) -> bool {
+        match self {
+            PolicyRule::Domain { domain } => domain_matches(host, domain),
+            // Host and path are compared case-insensitively, like the pattern
+            PolicyRule::Wildcard { pattern } => {
+                if pattern.contains('/') {
+                    matching::wildcard_match(pattern, &format!("{}{}", host, path))
+                } else {
+                    matching::wildcard_match(pattern, host)
+                }
+            }
+            PolicyRule::Category { category } => category.domains().iter().any(|d| domain_matches(host, d)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            PolicyRule::Domain { domain } => domain.clone(),
+            PolicyRule::Wildcard { pattern } => pattern.clone(),
+            PolicyRule::Category { category } => format!("{:?} category", category),
+        }
+    }
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim_start_matches("*.").to_lowercase();
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    #[default]
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct NavigationPolicy {
+    // For URLs no rule matches; `deny` turns `allow` into an allow list
+    default_action: PolicyAction,
+    allow: Vec<PolicyRule>,
+    deny: Vec<PolicyRule>,
+}
+
+// On disk, both per profile and in the managed file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct PolicyFile {
+    #[serde(flatten)]
+    policy: NavigationPolicy,
+    // Argon2 PHC string of the bypass PIN; no PIN means no bypass
+    bypass_pin_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PolicyStatus {
+    policy: NavigationPolicy,
+    managed: bool,
+    pin_set: bool,
+}
+
+#[derive(Default)]
+pub struct PolicyState {
+    managed: Option<PolicyFile>,
+    // Profile id -> policy, loaded on first use
+    profiles: Mutex<HashMap<String, PolicyFile>>,
+    // (profile id, host) -> expiry in ms
+    bypasses: Mutex<HashMap<(String, String), i64>>,
+}
+
+impl PolicyState {
+    pub fn load() -> Self {
+        let managed = managed_path().and_then(|path| {
+            let bytes = std::fs::read(&path).ok()?;
+            match serde_json::from_slice(&bytes) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    // A broken managed policy must not silently open everything up
+                    eprintln!("Invalid managed policy {}: {}", path.display(), e);
+                    Some(PolicyFile {
+                        policy: NavigationPolicy {
+                            default_action: PolicyAction::Deny,
+                            ..Default::default()
+                        },
+                        bypass_pin_hash: None,
+                    })
+                }
+            }
+        });
+        Self {
+            managed,
+            ..Default::default()
+        }
+    }
+}
+
+// Where administrators put the managed policy
+fn managed_path() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        std::env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join("MadEasy").join(POLICY_FILE))
+    } else if cfg!(target_os = "macos") {
+        Some(PathBuf::from("/Library/Application Support/MadEasy").join(POLICY_FILE))
+    } else {
+        Some(PathBuf::from("/etc/madeasy").join(POLICY_FILE))
+    }
+}
+
+fn policy_file(app: &AppHandle, profile_id: &str) -> PolicyFile {
+    let state = app.state::<PolicyState>();
+    if let Some(managed) = &state.managed {
+        return managed.clone();
+    }
+    let mut cache = state.profiles.lock().unwrap();
+    cache
+        .entry(profile_id.to_string())
+        .or_insert_with(|| storage::load(app, &profiles::profile_file(profile_id, POLICY_FILE)))
+        .clone()
+}
+
+fn save_policy_file(app: &AppHandle, profile_id: &str, file: PolicyFile) -> Result<(), String> {
+    storage::save(app, &profiles::profile_file(profile_id, POLICY_FILE), &file)?;
+    app.state::<PolicyState>()
+        .profiles
+        .lock()
+        .unwrap()
+        .insert(profile_id.to_string(), file);
+    Ok(())
+}
+
+fn ensure_unmanaged(app: &AppHandle) -> Result<(), String> {
+    if app.state::<PolicyState>().managed.is_some() {
+        Err("The navigation policy is managed by your administrator".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+// Why the URL is blocked for the active profile, or None when it may load
+pub fn check(app: &AppHandle, url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+    let profile = profiles::active_profile(app);
+
+    let now = chrono::Utc::now().timestamp_millis();
+    {
+        let mut bypasses = app.state::<PolicyState>().bypasses.lock().unwrap();
+        bypasses.retain(|_, expires_at| *expires_at > now);
+        if bypasses.contains_key(&(profile.clone(), host.clone())) {
+            return None;
+        }
+    }
+
+    let policy = policy_file(app, &profile).policy;
+    if policy.allow.iter().any(|rule| rule.matches(&host, url.path())) {
+        return None;
+    }
+    if let Some(rule) = policy.deny.iter().find(|rule| rule.matches(&host, url.path())) {
+        return Some(format!("Blocked by rule: {}", rule.describe()));
+    }
+    match policy.default_action {
+        PolicyAction::Allow => None,
+        PolicyAction::Deny => Some("Not on the list of allowed sites".to_string()),
+    }
+}
+
+fn blocked_url(url: &str, reason: &str) -> String {
+    let base = if cfg!(any(target_os = "windows", target_os = "android")) {
+        format!("https://{}.localhost/", BLOCKED_SCHEME)
+    } else {
+        format!("{}://localhost/", BLOCKED_SCHEME)
+    };
+    let mut blocked = url::Url::parse(&base).expect("valid blocked page URL");
+    blocked.query_pairs_mut().append_pair("url", url).append_pair("reason", reason);
+    blocked.to_string()
+}
+
+pub fn serve_blocked_page(_app: &AppHandle, _request: &Request) -> Result<Response, Box<dyn std::error::Error>> {
+    ResponseBuilder::new()
+        .mimetype("text/html")
+        .status(200)
+        .body(BLOCKED_PAGE.as_bytes().to_vec())
+}
+
+// Shared by the native hooks and the page-load fallback; returns the page to
+// show instead when the navigation is blocked
+fn on_navigation(app: &AppHandle, window_id: &str, url: &str) -> Option<String> {
+    let reason = check(app, url)?;
+    events::publish(
+        app,
+        AppEvent::NavigationBlocked {
+            window_id: window_id.to_string(),
+            url: url.to_string(),
+            reason: reason.clone(),
+        },
+    );
+    Some(blocked_url(url, &reason))
+}
+
+// Called on every window built by windowpool::build_window
+pub fn attach(app: &AppHandle, window: &Window) {
+    if let Err(e) = platform::install(app, window) {
+        eprintln!("Failed to install navigation policy hook: {}", e);
+    }
+}
+
+// Catches navigations the webview could not veto
+pub fn on_page_load(window: &Window, url: &str) {
+    // The app shell is served over http in development
+    if window.label() == "main" {
+        return;
+    }
+    if let Some(blocked) = on_navigation(&window.app_handle(), window.label(), url) {
+        let target = serde_json::to_string(&blocked).unwrap_or_default();
+        let _ = window.eval(&format!("window.location.replace({})", target));
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_navigation_policy(app_handle: AppHandle, profile_id: Option<String>) -> Result<PolicyStatus, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let file = policy_file(&app_handle, &profile);
+    Ok(PolicyStatus {
+        policy: file.policy,
+        managed: app_handle.state::<PolicyState>().managed.is_some(),
+        pin_set: file.bypass_pin_hash.is_some(),
+    })
+}
+
+// Changing the rules of a PIN-protected policy takes the PIN
+#[tauri::command]
+#[specta::specta]
+pub async fn set_navigation_policy(
+    app_handle: AppHandle,
+    policy: NavigationPolicy,
+    pin: Option<String>,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    ensure_unmanaged(&app_handle)?;
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut file = policy_file(&app_handle, &profile);
+    verify(&file, pin).await?;
+    file.policy = policy;
+    save_policy_file(&app_handle, &profile, file)?;
+    audit::record(&app_handle, AuditCategory::Settings, "policy.update", json!({ "profile": profile }));
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_policy_pin(
+    app_handle: AppHandle,
+    pin: String,
+    current_pin: Option<String>,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    ensure_unmanaged(&app_handle)?;
+    secrets::validate_pin(&pin)?;
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut file = policy_file(&app_handle, &profile);
+    verify(&file, current_pin).await?;
+    file.bypass_pin_hash = Some(tasks::blocking(move || secrets::hash_pin(&pin)).await?);
+    save_policy_file(&app_handle, &profile, file)?;
+    audit::record(&app_handle, AuditCategory::Credential, "policy.pin_set", json!({ "profile": profile }));
+    Ok(())
+}
+
+// Passes when no PIN is set
+async fn verify(file: &PolicyFile, pin: Option<String>) -> Result<(), String> {
+    let Some(hash) = file.bypass_pin_hash.clone() else {
+        return Ok(());
+    };
+    let pin = pin.unwrap_or_default();
+    if tasks::blocking(move || Ok(secrets::pin_matches(&hash, &pin))).await? {
+        Ok(())
+    } else {
+        Err("Incorrect PIN".to_string())
+    }
+}
+
+// Lets the URL's host load for `minutes` in the active profile
+#[tauri::command]
+#[specta::specta]
+pub async fn bypass_navigation_policy(
+    app_handle: AppHandle,
+    url: String,
+    pin: String,
+    minutes: Option<u32>,
+) -> Result<(), String> {
+    let host = url::Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_end_matches('.').to_lowercase()))
+        .ok_or_else(|| format!("Invalid URL: {}", url))?;
+    let minutes = minutes.unwrap_or(DEFAULT_BYPASS_MINUTES).clamp(1, MAX_BYPASS_MINUTES);
+    let profile = profiles::active_profile(&app_handle);
+    let file = policy_file(&app_handle, &profile);
+    if file.bypass_pin_hash.is_none() {
+        return Err("No bypass PIN is set".to_string());
+    }
+    if let Err(e) = verify(&file, Some(pin)).await {
+        audit::record(&app_handle, AuditCategory::Credential, "policy.bypass_denied", json!({ "host": host }));
+        return Err(e);
+    }
+    let expires_at = chrono::Utc::now().timestamp_millis() + i64::from(minutes) * 60_000;
+    app_handle
+        .state::<PolicyState>()
+        .bypasses
+        .lock()
+        .unwrap()
+        .insert((profile.clone(), host.clone()), expires_at);
+    audit::record(
+        &app_handle,
+        AuditCategory::Settings,
+        "policy.bypass",
+        json!({ "profile": profile, "host": host, "minutes": minutes }),
+    );
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::{AppHandle, Window};
+    use webview2_com::NavigationStartingEventHandler;
+    use windows_webview2::core::{HSTRING, PWSTR};
+    use windows_webview2::Win32::System::WinRT::EventRegistrationToken;
+
+    pub fn install(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        let label = window.label().to_string();
+        window
+            .with_webview(move |webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let core = webview.controller().CoreWebView2()?;
+                    let target = core.clone();
+                    let handler = NavigationStartingEventHandler::create(Box::new(move |_, args| {
+                        if let Some(args) = args {
+                            let mut uri = PWSTR::null();
+                            args.Uri(&mut uri)?;
+                            if let Some(blocked) = super::on_navigation(&app, &label, &webview2_com::take_pwstr(uri)) {
+                                args.SetCancel(true)?;
+                                target.Navigate(&HSTRING::from(blocked.as_str()))?;
+                            }
+                        }
+                        Ok(())
+                    }));
+                    let mut token = EventRegistrationToken::default();
+                    core.add_NavigationStarting(&handler, &mut token)
+                })();
+                if let Err(e) = result {
+                    eprintln!("Failed to install navigation handler: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use gtk::glib::Cast;
+    use tauri::{AppHandle, Window};
+    use webkit2gtk::{
+        NavigationPolicyDecision, NavigationPolicyDecisionExt, PolicyDecisionExt, PolicyDecisionType, URIRequestExt,
+        WebViewExt,
+    };
+
+    pub fn install(app: &AppHandle, window: &Window) -> Result<(), String> {
+        let app = app.clone();
+        let label = window.label().to_string();
+        window
+            .with_webview(move |webview| {
+                webview.inner().connect_decide_policy(move |view, decision, kind| {
+                    if kind != PolicyDecisionType::NavigationAction {
+                        return false;
+                    }
+                    let Some(uri) = decision
+                        .downcast_ref::<NavigationPolicyDecision>()
+                        .and_then(|d| d.navigation_action())
+                        .and_then(|mut action| action.request())
+                        .and_then(|request| request.uri())
+                    else {
+                        return false;
+                    };
+                    match super::on_navigation(&app, &label, &uri) {
+                        Some(blocked) => {
+                            decision.ignore();
+                            view.load_uri(&blocked);
+                            true
+                        }
+                        None => false,
+                    }
+                });
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    use tauri::{AppHandle, Window};
+
+    // WKWebView's navigation delegate belongs to wry; blocked pages are
+    // replaced on page load instead
+    pub fn install(_app: &AppHandle, _window: &Window) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+// Keep the machine awake while downloads, workflow runs or scheduled jobs are active
+// Guards are reference counted: the OS inhibitor is taken when the first guard is
+// acquired and released with the last one. Work started in the backend holds a
+// `Held` for its lifetime; the frontend uses the acquire/release commands.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::events::AppEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardKind {
+    Download,
+    Workflow,
+    Backup,
+    Digest,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PowerGuard {
+    id: u64,
+    kind: GuardKind,
+    reason: String,
+    started_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PowerGuardStatus {
+    inhibiting: bool,
+    guards: Vec<PowerGuard>,
+}
+
+// Guards and the inhibitor share one lock so acquire and release can't
+// interleave between "last guard gone" and dropping the inhibitor
+#[derive(Default)]
+struct Guards {
+    active: BTreeMap<u64, PowerGuard>,
+    next_id: u64,
+    inhibitor: Option<platform::Inhibitor>,
+}
+
+#[derive(Default)]
+pub struct PowerGuards {
+    guards: Mutex<Guards>,
+}
+
+pub fn acquire(app: &AppHandle, kind: GuardKind, reason: &str) -> Result<u64, String> {
+    let state = app.state::<PowerGuards>();
+    let mut guards = state.guards.lock().unwrap();
+    if guards.inhibitor.is_none() {
+        guards.inhibitor = Some(platform::Inhibitor::new("MadEasy Browser is downloading or running workflows")?);
+    }
+
+    guards.next_id += 1;
+    let id = guards.next_id;
+    guards.active.insert(
+        id,
+        PowerGuard {
+            id,
+            kind,
+            reason: reason.to_string(),
+            started_at: chrono::Utc::now().timestamp(),
+        },
+    );
+    Ok(id)
+}
+
+pub fn release(app: &AppHandle, id: u64) {
+    let state = app.state::<PowerGuards>();
+    let mut guards = state.guards.lock().unwrap();
+    guards.active.remove(&id);
+    if guards.active.is_empty() {
+        // Dropping the inhibitor lets the system sleep again
+        guards.inhibitor.take();
+    }
+}
+
+// Released when dropped
+pub struct Held {
+    app: AppHandle,
+    id: Option<u64>,
+}
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            release(&self.app, id);
+        }
+    }
+}
+
+// Failing to inhibit sleep never stops the job itself
+pub fn hold(app: &AppHandle, kind: GuardKind, reason: &str) -> Held {
+    let id = match acquire(app, kind, reason) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            eprintln!("Failed to keep the system awake for {}: {}", reason, e);
+            None
+        }
+    };
+    Held { app: app.clone(), id }
+}
+
+// Workflow guards use the run id as their reason
+pub fn release_run(app: &AppHandle, run_id: &str) {
+    let ids: Vec<u64> = app
+        .state::<PowerGuards>()
+        .guards
+        .lock()
+        .unwrap()
+        .active
+        .values()
+        .filter(|g| g.kind == GuardKind::Workflow && g.reason == run_id)
+        .map(|g| g.id)
+        .collect();
+    for id in ids {
+        release(app, id);
+    }
+}
+
+// Event subscriber; a failed run no longer needs its guard
+pub fn on_event(app: &AppHandle, event: &AppEvent) {
+    if let AppEvent::WorkflowFailed { run_id, .. } = event {
+        release_run(app, run_id);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_power_guards(state: tauri::State<'_, PowerGuards>) -> Result<PowerGuardStatus, String> {
+    let guards = state.guards.lock().unwrap();
+    Ok(PowerGuardStatus {
+        inhibiting: guards.inhibitor.is_some(),
+        guards: guards.active.values().cloned().collect(),
+    })
+}
+
+// For downloads and workflow runs driven by the frontend
+#[tauri::command]
+#[specta::specta]
+pub async fn acquire_power_guard(
+    app_handle: AppHandle,
+    kind: GuardKind,
+    reason: String,
+) -> Result<u64, String> {
+    acquire(&app_handle, kind, &reason)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn release_power_guard(app_handle: AppHandle, id: u64) -> Result<(), String> {
+    release(&app_handle, id);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::sync::mpsc;
+    use windows::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_SYSTEM_REQUIRED,
+    };
+
+    // SetThreadExecutionState is per-thread, so a dedicated thread holds the request
+    pub struct Inhibitor {
+        stop: mpsc::Sender<()>,
+    }
+
+    impl Inhibitor {
+        pub fn new(_reason: &str) -> Result<Self, String> {
+            let (stop, stopped) = mpsc::channel::<()>();
+            std::thread::spawn(move || unsafe {
+                SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED);
+                let _ = stopped.recv();
+                SetThreadExecutionState(ES_CONTINUOUS);
+            });
+            Ok(Self { stop })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            let _ = self.stop.send(());
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+
+    const K_IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            assertion_id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(assertion_id: u32) -> i32;
+    }
+
+    pub struct Inhibitor {
+        assertion_id: u32,
+    }
+
+    impl Inhibitor {
+        pub fn new(reason: &str) -> Result<Self, String> {
+            let kind = CFString::new("PreventUserIdleSystemSleep");
+            let name = CFString::new(reason);
+            let mut assertion_id = 0u32;
+            let result = unsafe {
+                IOPMAssertionCreateWithName(
+                    kind.as_concrete_TypeRef(),
+                    K_IOPM_ASSERTION_LEVEL_ON,
+                    name.as_concrete_TypeRef(),
+                    &mut assertion_id,
+                )
+            };
+            if result != 0 {
+                return Err(format!("IOPMAssertionCreateWithName failed: {}", result));
+            }
+            Ok(Self { assertion_id })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            unsafe {
+                IOPMAssertionRelease(self.assertion_id);
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::{Child, ChildStdin, Command, Stdio};
+
+    // systemd-inhibit holds the lock for as long as its child process runs.
+    // The child is `cat` on a pipe: closing the pipe ends it, so no process is
+    // left behind the way killing systemd-inhibit would orphan `sleep`.
+    pub struct Inhibitor {
+        child: Child,
+        stdin: Option<ChildStdin>,
+    }
+
+    impl Inhibitor {
+        pub fn new(reason: &str) -> Result<Self, String> {
+            let mut child = Command::new("systemd-inhibit")
+                .args([
+                    "--what=idle:sleep",
+                    "--who=MadEasy Browser",
+                    &format!("--why={}", reason),
+                    "--mode=block",
+                    "cat",
+                ])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| format!("Failed to start systemd-inhibit: {}", e))?;
+            let stdin = child.stdin.take();
+            Ok(Self { child, stdin })
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            self.stdin.take();
+            let _ = self.child.wait();
+        }
+    }
+}
@@ -0,0 +1,298 @@
+// Printing
+// Interactive printing opens the engine's print dialog; silent printing sends
+// the page straight to a printer with the given options (used by workflows).
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use crate::tasks;
+
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(default)]
+pub struct PrintOptions {
+    // Printer name from `list_printers`; the system default when omitted
+    printer: Option<String>,
+    copies: Option<u32>,
+    // e.g. "1-3,5"
+    page_range: Option<String>,
+    silent: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Printer {
+    name: String,
+    is_default: bool,
+}
+
+// Parse "1-3,5" into inclusive (start, end) pairs, 1-based
+fn parse_page_range(range: &str) -> Result<Vec<(u32, u32)>, String> {
+    let invalid = || format!("Invalid page range: {}", range);
+    range
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+            let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+            if start == 0 || end < start {
+                return Err(invalid());
+            }
+            Ok((start, end))
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_printers() -> Result<Vec<Printer>, String> {
+    tasks::blocking(platform::list_printers).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn print_page(
+    app_handle: AppHandle,
+    window_id: String,
+    options: Option<PrintOptions>,
+) -> Result<(), String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    let options = options.unwrap_or_default();
+
+    if !options.silent {
+        return window.print().map_err(|e| e.to_string());
+    }
+
+    let ranges = match &options.page_range {
+        Some(range) => parse_page_range(range)?,
+        None => Vec::new(),
+    };
+    if let Some(printer) = &options.printer {
+        if !tasks::blocking(platform::list_printers).await?.iter().any(|p| &p.name == printer) {
+            return Err(format!("Unknown printer: {}", printer));
+        }
+    }
+    platform::print_silent(
+        &window,
+        options.printer.as_deref(),
+        options.copies.unwrap_or(1).clamp(1, 999),
+        &ranges,
+    )
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::Printer;
+    use tauri::Window;
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2Environment6, ICoreWebView2PrintSettings2, ICoreWebView2_16, ICoreWebView2_2,
+    };
+    use webview2_com::PrintCompletedHandler;
+    use windows::core::PWSTR;
+    use windows::Win32::Graphics::Printing::{
+        EnumPrintersW, GetDefaultPrinterW, PRINTER_ENUM_CONNECTIONS, PRINTER_ENUM_LOCAL, PRINTER_INFO_4W,
+    };
+    use windows_webview2::core::{Interface, HSTRING};
+
+    fn default_printer() -> Option<String> {
+        unsafe {
+            let mut len = 0u32;
+            let _ = GetDefaultPrinterW(PWSTR::null(), &mut len);
+            let mut buf = vec![0u16; len as usize];
+            GetDefaultPrinterW(PWSTR(buf.as_mut_ptr()), &mut len).as_bool().then(|| {
+                String::from_utf16_lossy(&buf[..len.saturating_sub(1) as usize])
+            })
+        }
+    }
+
+    pub fn list_printers() -> Result<Vec<Printer>, String> {
+        let default = default_printer();
+        let flags = PRINTER_ENUM_LOCAL | PRINTER_ENUM_CONNECTIONS;
+        unsafe {
+            let (mut needed, mut count) = (0u32, 0u32);
+            let _ = EnumPrintersW(flags, None, 4, None, &mut needed, &mut count);
+            let mut buf = vec![0u8; needed as usize];
+            EnumPrintersW(flags, None, 4, Some(&mut buf), &mut needed, &mut count).map_err(|e| e.to_string())?;
+
+            let infos = std::slice::from_raw_parts(buf.as_ptr() as *const PRINTER_INFO_4W, count as usize);
+            Ok(infos
+                .iter()
+                .filter_map(|info| info.pPrinterName.to_string().ok())
+                .map(|name| Printer {
+                    is_default: default.as_deref() == Some(name.as_str()),
+                    name,
+                })
+                .collect())
+        }
+    }
+
+    // ICoreWebView2_16::Print with explicit print settings
+    pub fn print_silent(window: &Window, printer: Option<&str>, copies: u32, ranges: &[(u32, u32)]) -> Result<(), String> {
+        let printer = printer.map(str::to_string);
+        let ranges = ranges
+            .iter()
+            .map(|(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        window
+            .with_webview(move |webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let core = webview.controller().CoreWebView2()?;
+                    let environment: ICoreWebView2Environment6 =
+                        core.cast::<ICoreWebView2_2>()?.Environment()?.cast()?;
+                    let settings = environment.CreatePrintSettings()?;
+                    let settings: ICoreWebView2PrintSettings2 = settings.cast()?;
+                    settings.SetCopies(copies as i32)?;
+                    if let Some(printer) = &printer {
+                        settings.SetPrinterName(&HSTRING::from(printer.as_str()))?;
+                    }
+                    if !ranges.is_empty() {
+                        settings.SetPageRanges(&HSTRING::from(ranges.as_str()))?;
+                    }
+                    let handler = PrintCompletedHandler::create(Box::new(|_result, _status| Ok(())));
+                    core.cast::<ICoreWebView2_16>()?.Print(&settings.cast()?, &handler)
+                })();
+                if let Err(e) = result {
+                    eprintln!("Silent print failed: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod cups {
+    use super::Printer;
+    use std::process::Command;
+
+    // Printer names and the default destination from CUPS
+    pub fn list_printers() -> Result<Vec<Printer>, String> {
+        let output = Command::new("lpstat")
+            .arg("-e")
+            .output()
+            .map_err(|e| format!("lpstat is not available: {}", e))?;
+        let default = Command::new("lpstat")
+            .arg("-d")
+            .output()
+            .ok()
+            .and_then(|o| {
+                let text = String::from_utf8_lossy(&o.stdout).to_string();
+                text.split_once(':').map(|(_, name)| name.trim().to_string())
+            });
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|name| Printer {
+                is_default: default.as_deref() == Some(name),
+                name: name.to_string(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub use super::cups::list_printers;
+    use cocoa::base::{id, nil, NO};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::Window;
+
+    // WKWebView print operation with the panels suppressed (macOS 11+)
+    pub fn print_silent(window: &Window, printer: Option<&str>, copies: u32, ranges: &[(u32, u32)]) -> Result<(), String> {
+        if ranges.len() > 1 {
+            return Err("Only a single page range is supported on macOS".to_string());
+        }
+        let printer = printer.map(str::to_string);
+        let range = ranges.first().copied();
+
+        window
+            .with_webview(move |webview| unsafe {
+                let view = webview.inner() as id;
+                let shared: id = msg_send![class!(NSPrintInfo), sharedPrintInfo];
+                let info: id = msg_send![shared, copy];
+                let dict: id = msg_send![info, dictionary];
+
+                if let Some(name) = &printer {
+                    let name = NSString::alloc(nil).init_str(name);
+                    let device: id = msg_send![class!(NSPrinter), printerWithName: name];
+                    if device != nil {
+                        let _: () = msg_send![info, setPrinter: device];
+                    }
+                }
+                let set = |key: &str, value: id| {
+                    let key = NSString::alloc(nil).init_str(key);
+                    let _: () = msg_send![dict, setObject: value forKey: key];
+                };
+                set("NSCopies", msg_send![class!(NSNumber), numberWithUnsignedInt: copies]);
+                if let Some((first, last)) = range {
+                    set("NSAllPages", msg_send![class!(NSNumber), numberWithBool: NO]);
+                    set("NSFirstPage", msg_send![class!(NSNumber), numberWithUnsignedInt: first]);
+                    set("NSLastPage", msg_send![class!(NSNumber), numberWithUnsignedInt: last]);
+                }
+
+                let operation: id = msg_send![view, printOperationWithPrintInfo: info];
+                let _: () = msg_send![operation, setShowsPrintPanel: NO];
+                let _: () = msg_send![operation, setShowsProgressPanel: NO];
+                let ns_window: id = msg_send![view, window];
+                let _: () = msg_send![operation, runOperationModalForWindow: ns_window
+                                                  delegate: nil
+                                                  didRunSelector: nil
+                                                  contextInfo: nil];
+                let _: () = msg_send![info, release];
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    pub use super::cups::list_printers;
+    use gtk::{PageRange, PrintPages, PrintSettings};
+    use tauri::Window;
+
+    // WebKitGTK print operation run without a dialog
+    pub fn print_silent(window: &Window, printer: Option<&str>, copies: u32, ranges: &[(u32, u32)]) -> Result<(), String> {
+        let printer = printer.map(str::to_string);
+        let ranges: Vec<(u32, u32)> = ranges.to_vec();
+
+        window
+            .with_webview(move |webview| {
+                let settings = PrintSettings::new();
+                if let Some(printer) = &printer {
+                    settings.set_printer(printer);
+                }
+                settings.set_n_copies(copies as i32);
+                if !ranges.is_empty() {
+                    // GTK page ranges are 0-based
+                    let ranges: Vec<PageRange> = ranges
+                        .iter()
+                        .map(|(start, end)| PageRange::new(*start as i32 - 1, *end as i32 - 1))
+                        .collect();
+                    settings.set_print_pages(PrintPages::Ranges);
+                    settings.set_page_ranges(&ranges);
+                }
+
+                let operation = webkit2gtk::PrintOperation::new(webview.inner());
+                webkit2gtk::PrintOperationExt::set_print_settings(&operation, &settings);
+                webkit2gtk::PrintOperationExt::print(&operation);
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    pub use super::cups::list_printers;
+    use tauri::Window;
+
+    pub fn print_silent(_window: &Window, _printer: Option<&str>, _copies: u32, _ranges: &[(u32, u32)]) -> Result<(), String> {
+        Err("Silent printing is not supported on this platform".to_string())
+    }
+}
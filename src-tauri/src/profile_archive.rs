@@ -0,0 +1,54 @@
+// Portable profile archives
+// Everything a backup contains (see backup.rs), encrypted under a passphrase
+// chosen for the export, so it can be imported on another machine. The key is
+// derived like a backup key, from the passphrase and a random salt stored in
+// the file header. Imports are staged like a restore.
+
+use rand::RngCore;
+use serde_json::json;
+use tauri::AppHandle;
+
+use crate::audit::{self, AuditCategory};
+use crate::backup::{self, derive_key, RestoreReport, MIN_PASSPHRASE_LEN, SALT_LEN};
+use crate::tasks;
+
+const MAGIC: &[u8] = b"MEPROFILE1";
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_profile_archive(app_handle: AppHandle, path: String, passphrase: String) -> Result<u64, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    tasks::blocking(move || {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = derive_key(&passphrase, &salt)?;
+        let sealed = backup::seal(&key, &backup::build_archive(&app_handle)?)?;
+        let bytes = [MAGIC, &salt[..], &sealed[..]].concat();
+        std::fs::write(&path, &bytes).map_err(|e| e.to_string())?;
+        audit::record(&app_handle, AuditCategory::Export, "profile.export", json!({ "path": path }));
+        Ok(bytes.len() as u64)
+    })
+    .await
+}
+
+// The imported data replaces the current data on the next start
+#[tauri::command]
+#[specta::specta]
+pub async fn import_profile_archive(app_handle: AppHandle, path: String, passphrase: String) -> Result<RestoreReport, String> {
+    tasks::blocking(move || {
+        let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+        let rest = bytes.strip_prefix(MAGIC).ok_or("Not a profile archive")?;
+        if rest.len() < SALT_LEN {
+            return Err("Profile archive is truncated".to_string());
+        }
+        let (salt, sealed) = rest.split_at(SALT_LEN);
+        let archive = backup::open(&derive_key(&passphrase, salt)?, sealed)
+            .map_err(|_| "Wrong passphrase or damaged archive".to_string())?;
+        let report = backup::stage_restore(&app_handle, archive)?;
+        audit::record(&app_handle, AuditCategory::Settings, "profile.import", json!({ "path": path }));
+        Ok(report)
+    })
+    .await
+}
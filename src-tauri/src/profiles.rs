@@ -0,0 +1,128 @@
+// User profiles
+// Per-profile settings live under `profiles/<id>/` in the app data directory
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::{spellcheck, storage, windowpool};
+
+const PROFILES_FILE: &str = "profiles.json";
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct ProfileList {
+    profiles: Vec<Profile>,
+    active: String,
+}
+
+impl Default for ProfileList {
+    fn default() -> Self {
+        Self {
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE.to_string(),
+                name: "Default".to_string(),
+            }],
+            active: DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ProfileState {
+    list: Mutex<ProfileList>,
+}
+
+impl ProfileState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            list: Mutex::new(storage::load(app, PROFILES_FILE)),
+        }
+    }
+}
+
+// Relative path of a per-profile file, for use with `storage`
+pub fn profile_file(profile_id: &str, file: &str) -> String {
+    format!("profiles/{}/{}", profile_id, file)
+}
+
+pub fn active_profile(app: &AppHandle) -> String {
+    app.state::<ProfileState>().list.lock().unwrap().active.clone()
+}
+
+// Resolve an optional profile argument to a known profile id
+pub fn resolve(app: &AppHandle, profile_id: Option<String>) -> Result<String, String> {
+    let list = app.state::<ProfileState>().list.lock().unwrap();
+    match profile_id {
+        None => Ok(list.active.clone()),
+        Some(id) if list.profiles.iter().any(|p| p.id == id) => Ok(id),
+        Some(id) => Err(format!("Unknown profile: {}", id)),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_profiles(state: tauri::State<'_, ProfileState>) -> Result<(Vec<Profile>, String), String> {
+    let list = state.list.lock().unwrap();
+    Ok((list.profiles.clone(), list.active.clone()))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_profile(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ProfileState>,
+    name: String,
+) -> Result<Profile, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+
+    let mut list = state.list.lock().unwrap();
+    let base: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut id = base.clone();
+    let mut n = 1;
+    while list.profiles.iter().any(|p| p.id == id) {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+
+    let profile = Profile { id, name };
+    list.profiles.push(profile.clone());
+    storage::save(&app_handle, PROFILES_FILE, &*list)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn switch_profile(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ProfileState>,
+    profile_id: String,
+) -> Result<(), String> {
+    {
+        let mut list = state.list.lock().unwrap();
+        if !list.profiles.iter().any(|p| p.id == profile_id) {
+            return Err(format!("Unknown profile: {}", profile_id));
+        }
+        list.active = profile_id.clone();
+        storage::save(&app_handle, PROFILES_FILE, &*list)?;
+    }
+    spellcheck::apply(&app_handle);
+    windowpool::drain(&app_handle);
+    app_handle
+        .emit_all("profile-changed", profile_id)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,254 @@
+// Article extraction
+// A small readability-style heuristic: paragraphs score their parent (and half
+// their grandparent) by text length, the best-scoring container is taken as the
+// article body and flattened into simple blocks.
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+const MIN_PARAGRAPH_CHARS: usize = 25;
+const EXCERPT_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Block {
+    Heading { level: u8, text: String },
+    Paragraph { text: String },
+    Quote { text: String },
+    Code { text: String },
+    ListItem { text: String },
+    Image { src: String, alt: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Article {
+    pub title: String,
+    pub byline: Option<String>,
+    pub blocks: Vec<Block>,
+}
+
+impl Article {
+    pub fn text(&self) -> String {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Heading { text, .. }
+                | Block::Paragraph { text }
+                | Block::Quote { text }
+                | Block::Code { text }
+                | Block::ListItem { text } => Some(text.as_str()),
+                Block::Image { .. } => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    pub fn excerpt(&self) -> String {
+        let text = self
+            .blocks
+            .iter()
+            .find_map(|block| match block {
+                Block::Paragraph { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        match text.char_indices().nth(EXCERPT_CHARS) {
+            Some((i, _)) => format!("{}…", text[..i].trim_end()),
+            None => text.to_string(),
+        }
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.text().split_whitespace().count()
+    }
+
+    // Render as minimal HTML; `image_src` maps each image's src for display
+    pub fn to_html(&self, image_src: impl Fn(&str) -> Option<String>) -> String {
+        let mut html = format!("<h1>{}</h1>\n", escape(&self.title));
+        if let Some(byline) = &self.byline {
+            html.push_str(&format!("<p class=\"byline\">{}</p>\n", escape(byline)));
+        }
+        let mut in_list = false;
+        for block in &self.blocks {
+            let is_item = matches!(block, Block::ListItem { .. });
+            if is_item != in_list {
+                html.push_str(if is_item { "<ul>\n" } else { "</ul>\n" });
+                in_list = is_item;
+            }
+            match block {
+                Block::Heading { level, text } => {
+                    html.push_str(&format!("<h{0}>{1}</h{0}>\n", level, escape(text)))
+                }
+                Block::Paragraph { text } => html.push_str(&format!("<p>{}</p>\n", escape(text))),
+                Block::Quote { text } => html.push_str(&format!("<blockquote>{}</blockquote>\n", escape(text))),
+                Block::Code { text } => html.push_str(&format!("<pre>{}</pre>\n", escape(text))),
+                Block::ListItem { text } => html.push_str(&format!("<li>{}</li>\n", escape(text))),
+                Block::Image { src, alt } => {
+                    if let Some(src) = image_src(src) {
+                        html.push_str(&format!("<img src=\"{}\" alt=\"{}\">\n", escape(&src), escape(alt)));
+                    }
+                }
+            }
+        }
+        if in_list {
+            html.push_str("</ul>\n");
+        }
+        html
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn clean_text(element: ElementRef) -> String {
+    element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .find_map(|m| m.value().attr("content"))
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document.select(&selector).map(clean_text).find(|t| !t.is_empty())
+}
+
+// Whether an element is page chrome rather than content
+fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+    if matches!(value.name(), "nav" | "header" | "footer" | "aside" | "form" | "script" | "style" | "noscript") {
+        return true;
+    }
+    let hints = format!(
+        "{} {}",
+        value.attr("class").unwrap_or_default(),
+        value.attr("id").unwrap_or_default()
+    )
+    .to_lowercase();
+    ["comment", "sidebar", "footer", "share", "promo", "advert", "related", "newsletter"]
+        .iter()
+        .any(|hint| hints.contains(hint))
+}
+
+fn best_container(document: &Html) -> Option<ElementRef<'_>> {
+    let paragraphs = Selector::parse("p").unwrap();
+    let mut scores: HashMap<ego_tree::NodeId, f64> = HashMap::new();
+
+    for paragraph in document.select(&paragraphs) {
+        let length = clean_text(paragraph).len();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        // Commas are a cheap signal of prose rather than navigation
+        let score = 1.0 + length.min(300) as f64 / 100.0 + clean_text(paragraph).matches(',').count() as f64;
+        let parent = paragraph.parent().and_then(ElementRef::wrap);
+        if let Some(parent) = parent {
+            *scores.entry(parent.id()).or_default() += score;
+            if let Some(grandparent) = parent.parent().and_then(ElementRef::wrap) {
+                *scores.entry(grandparent.id()).or_default() += score / 2.0;
+            }
+        }
+    }
+
+    scores
+        .into_iter()
+        .filter_map(|(id, score)| document.tree.get(id).and_then(ElementRef::wrap).map(|e| (e, score)))
+        .filter(|(element, _)| !is_boilerplate(*element))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(element, _)| element)
+}
+
+fn collect_blocks(element: ElementRef, base: &Url, blocks: &mut Vec<Block>) {
+    for child in element.children() {
+        let Some(child) = ElementRef::wrap(child) else {
+            continue;
+        };
+        if is_boilerplate(child) {
+            continue;
+        }
+        let name = child.value().name();
+        let text = || clean_text(child);
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = text();
+                if !text.is_empty() {
+                    let level = name[1..].parse().unwrap_or(2);
+                    blocks.push(Block::Heading { level, text });
+                }
+            }
+            "p" => {
+                let text = text();
+                if !text.is_empty() {
+                    blocks.push(Block::Paragraph { text });
+                }
+                collect_images(child, base, blocks);
+            }
+            "blockquote" => blocks.push(Block::Quote { text: text() }),
+            "pre" => blocks.push(Block::Code {
+                text: child.text().collect(),
+            }),
+            "li" => blocks.push(Block::ListItem { text: text() }),
+            "img" => collect_images(child, base, blocks),
+            _ => collect_blocks(child, base, blocks),
+        }
+    }
+}
+
+fn image_block(img: ElementRef, base: &Url) -> Option<Block> {
+    let value = img.value();
+    // Lazy-loading pages keep the real source in a data attribute
+    let src = value
+        .attr("data-src")
+        .or_else(|| value.attr("src"))
+        .filter(|s| !s.starts_with("data:"))?;
+    let src = base.join(src).ok()?;
+    Some(Block::Image {
+        src: src.to_string(),
+        alt: value.attr("alt").unwrap_or_default().to_string(),
+    })
+}
+
+fn collect_images(element: ElementRef, base: &Url, blocks: &mut Vec<Block>) {
+    if element.value().name() == "img" {
+        blocks.extend(image_block(element, base));
+        return;
+    }
+    for node in element.descendants() {
+        if let (Some(img), Node::Element(e)) = (ElementRef::wrap(node), node.value()) {
+            if e.name() == "img" {
+                blocks.extend(image_block(img, base));
+            }
+        }
+    }
+}
+
+pub fn extract(html: &str, url: &Url) -> Result<Article, String> {
+    let document = Html::parse_document(html);
+    let title = meta_content(&document, "meta[property='og:title']")
+        .or_else(|| first_text(&document, "title"))
+        .or_else(|| first_text(&document, "h1"))
+        .unwrap_or_else(|| url.to_string());
+    let byline = meta_content(&document, "meta[name='author']").or_else(|| first_text(&document, "[rel='author']"));
+
+    let container = best_container(&document).ok_or_else(|| "No article content found on the page".to_string())?;
+    let mut blocks = Vec::new();
+    collect_blocks(container, url, &mut blocks);
+    blocks.retain(|block| match block {
+        Block::Quote { text } | Block::ListItem { text } | Block::Code { text } => !text.trim().is_empty(),
+        _ => true,
+    });
+
+    Ok(Article { title, byline, blocks })
+}
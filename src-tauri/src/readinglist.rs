@@ -0,0 +1,317 @@
+// Reading list with offline articles
+// Saved pages are reduced to their article (text + images) and stored under
+// `readinglist/<id>/` in the app data directory so they can be read offline.
+
+use base64::Engine;
+use reqwest::Url;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::linkpreview;
+use crate::readability::{self, Article, Block};
+use crate::storage;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS reading_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    excerpt TEXT NOT NULL DEFAULT '',
+    word_count INTEGER NOT NULL DEFAULT 0,
+    saved_at INTEGER NOT NULL,
+    read_at INTEGER,
+    tags TEXT NOT NULL DEFAULT '[]'
+);
+";
+
+const MAX_IMAGES: usize = 40;
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+// Longer pages are cut off; the article is usually near the top
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ReadingItem {
+    id: i64,
+    url: String,
+    title: String,
+    excerpt: String,
+    word_count: u32,
+    saved_at: i64,
+    read: bool,
+    tags: Vec<String>,
+}
+
+impl ReadingItem {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let tags: String = row.get(7)?;
+        Ok(Self {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            excerpt: row.get(3)?,
+            word_count: row.get(4)?,
+            saved_at: row.get(5)?,
+            read: row.get::<_, Option<i64>>(6)?.is_some(),
+            tags: serde_json::from_str(&tags).unwrap_or_default(),
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, url, title, excerpt, word_count, saved_at, read_at, tags FROM reading_items";
+
+// Article plus the local file name of each downloaded image, keyed by source URL
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+struct StoredArticle {
+    article: Article,
+    images: HashMap<String, (String, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ReadingArticle {
+    item: ReadingItem,
+    // Self-contained HTML with images inlined as data URLs
+    html: String,
+}
+
+fn item_dir(id: i64) -> String {
+    format!("readinglist/{}", id)
+}
+
+fn get_item(db: &Database, id: i64) -> Result<ReadingItem, String> {
+    db.with(|conn| conn.query_row(&format!("{} WHERE id = ?1", SELECT_COLUMNS), params![id], ReadingItem::from_row))
+        .map_err(|_| format!("Reading list item not found: {}", id))
+}
+
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// Download article images; returns source URL -> (file name, mime). Sources
+// come from page content, so only public addresses are fetched.
+async fn download_images(
+    app: &AppHandle,
+    id: i64,
+    article: &Article,
+) -> HashMap<String, (String, String)> {
+    let mut images = HashMap::new();
+    let sources = article.blocks.iter().filter_map(|block| match block {
+        Block::Image { src, .. } => Some(src.clone()),
+        _ => None,
+    });
+
+    for (index, src) in sources.take(MAX_IMAGES).enumerate() {
+        let Ok(url) = Url::parse(&src) else { continue };
+        let Ok((_, response)) = linkpreview::get_public(app, &url, "image/*").await else {
+            continue;
+        };
+        let mime = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        if !mime.starts_with("image/") {
+            continue;
+        }
+        let Some((bytes, true)) = linkpreview::read_up_to(app, response, MAX_IMAGE_BYTES).await else {
+            continue;
+        };
+        let file = format!("image-{}", index);
+        let Ok(path) = storage::data_path(app, &format!("{}/{}", item_dir(id), file)) else {
+            continue;
+        };
+        if std::fs::write(path, &bytes).is_ok() {
+            images.insert(src, (file, mime));
+        }
+    }
+    images
+}
+
+// Save a page. `html` lets the frontend pass the rendered page (e.g. behind a
+// login); otherwise the URL is fetched.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_reading_item(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    url: String,
+    html: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<ReadingItem, String> {
+    let page_url = Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let html = match html {
+        Some(html) => html,
+        None => {
+            let (_, response) = linkpreview::get_public(&app_handle, &page_url, "text/html,application/xhtml+xml")
+                .await?;
+            let (body, _) = linkpreview::read_up_to(&app_handle, response, MAX_PAGE_BYTES)
+                .await
+                .ok_or("Failed to read the page")?;
+            String::from_utf8_lossy(&body).into_owned()
+        }
+    };
+    let article = readability::extract(&html, &page_url)?;
+    // Hovering the saved link later shouldn't need another fetch
+    linkpreview::remember(&app_handle, &page_url, &html);
+    let tags = serde_json::to_string(&normalize_tags(tags.unwrap_or_default())).map_err(|e| e.to_string())?;
+
+    // Saving the same URL again refreshes the article and keeps its id
+    let id = db.with(|conn| {
+        conn.query_row(
+            "INSERT INTO reading_items (url, title, excerpt, word_count, saved_at, tags)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(url) DO UPDATE SET title = excluded.title, excerpt = excluded.excerpt,
+                 word_count = excluded.word_count, saved_at = excluded.saved_at
+             RETURNING id",
+            params![
+                url,
+                article.title,
+                article.excerpt(),
+                article.word_count() as i64,
+                chrono::Utc::now().timestamp(),
+                tags
+            ],
+            |row| row.get(0),
+        )
+    })?;
+
+    let images = download_images(&app_handle, id, &article).await;
+    storage::save(
+        &app_handle,
+        &format!("{}/article.json", item_dir(id)),
+        &StoredArticle { article, images },
+    )?;
+
+    let _ = app_handle.emit_all("reading-list-changed", ());
+    get_item(&db, id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_reading_items(
+    db: tauri::State<'_, Database>,
+    unread_only: Option<bool>,
+    tag: Option<String>,
+) -> Result<Vec<ReadingItem>, String> {
+    let items = db.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE ?1 = 0 OR read_at IS NULL ORDER BY saved_at DESC",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![unread_only.unwrap_or(false)], ReadingItem::from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    let tag = tag.map(|t| t.trim().to_lowercase());
+    Ok(items
+        .into_iter()
+        .filter(|item| tag.as_ref().map_or(true, |t| item.tags.contains(t)))
+        .collect())
+}
+
+// Self-contained HTML for a stored article
+pub fn render(app: &AppHandle, id: i64) -> Result<String, String> {
+    let stored: Option<StoredArticle> = storage::load(app, &format!("{}/article.json", item_dir(id)));
+    let stored = stored.ok_or_else(|| "The saved article is missing; save the page again".to_string())?;
+
+    Ok(stored.article.to_html(|src| {
+        let (file, mime) = stored.images.get(src)?;
+        let path = storage::data_path(app, &format!("{}/{}", item_dir(id), file)).ok()?;
+        let bytes = std::fs::read(path).ok()?;
+        Some(format!(
+            "data:{};base64,{}",
+            mime,
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        ))
+    }))
+}
+
+// Stored copy of a page for offline browsing: (saved_at, html)
+pub fn offline_copy(app: &AppHandle, db: &Database, url: &str) -> Result<Option<(i64, String)>, String> {
+    let item = db.with(|conn| {
+        conn.query_row(&format!("{} WHERE url = ?1", SELECT_COLUMNS), params![url], ReadingItem::from_row)
+            .optional()
+    })?;
+    match item {
+        Some(item) => Ok(Some((item.saved_at, render(app, item.id)?))),
+        None => Ok(None),
+    }
+}
+
+// Load the stored article for offline reading and mark it read
+#[tauri::command]
+#[specta::specta]
+pub async fn open_reading_item(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    id: i64,
+) -> Result<ReadingArticle, String> {
+    let html = render(&app_handle, id)?;
+
+    db.with(|conn| {
+        conn.execute(
+            "UPDATE reading_items SET read_at = ?2 WHERE id = ?1 AND read_at IS NULL",
+            params![id, chrono::Utc::now().timestamp()],
+        )
+    })?;
+    Ok(ReadingArticle {
+        item: get_item(&db, id)?,
+        html,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_reading_item_read(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    id: i64,
+    read: bool,
+) -> Result<(), String> {
+    let read_at = read.then(|| chrono::Utc::now().timestamp());
+    db.with(|conn| conn.execute("UPDATE reading_items SET read_at = ?2 WHERE id = ?1", params![id, read_at]))?;
+    let _ = app_handle.emit_all("reading-list-changed", ());
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_reading_item_tags(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    id: i64,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let tags = serde_json::to_string(&normalize_tags(tags)).map_err(|e| e.to_string())?;
+    db.with(|conn| conn.execute("UPDATE reading_items SET tags = ?2 WHERE id = ?1", params![id, tags]))?;
+    let _ = app_handle.emit_all("reading-list-changed", ());
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_reading_item(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    id: i64,
+) -> Result<(), String> {
+    db.with(|conn| conn.execute("DELETE FROM reading_items WHERE id = ?1", params![id]))?;
+    let dir = storage::data_path(&app_handle, &item_dir(id))?;
+    if dir.exists() {
+        std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let _ = app_handle.emit_all("reading-list-changed", ());
+    Ok(())
+}
@@ -0,0 +1,260 @@
+// Timezone, locale and Accept-Language overrides per profile
+// Automation against geo-sensitive sites should not depend on the host's
+// settings. New windows get an initialization script that makes Intl, Date
+// and navigator.language report the profile's timezone and locale, and the
+// Accept-Language header is set on the webview where the engine allows it.
+// Local-time Date setters and parsing of local date strings still use the
+// host timezone. Windows that are already open keep their settings.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Window};
+
+use crate::{profiles, storage};
+
+const CONFIG_FILE: &str = "regional.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct RegionalOverrides {
+    // IANA name, e.g. "Europe/Oslo"
+    timezone: Option<String>,
+    // BCP 47 tag used for navigator.language and Intl defaults
+    locale: Option<String>,
+    // Header value, e.g. "nb-NO,nb;q=0.9,en;q=0.8"; derived from `locale` when unset
+    accept_language: Option<String>,
+}
+
+impl RegionalOverrides {
+    fn header(&self) -> Option<String> {
+        self.accept_language.clone().or_else(|| {
+            let locale = self.locale.as_ref()?;
+            match locale.split_once('-') {
+                Some((language, _)) => Some(format!("{},{};q=0.9", locale, language)),
+                None => Some(locale.clone()),
+            }
+        })
+    }
+}
+
+fn load(app: &AppHandle, profile_id: &str) -> RegionalOverrides {
+    storage::load(app, &profiles::profile_file(profile_id, CONFIG_FILE))
+}
+
+fn valid_locale(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= 35
+        && tag.split('-').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+fn valid_timezone(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'))
+}
+
+fn valid_header(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 256
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | ',' | ';' | '=' | '.' | '*' | ' '))
+}
+
+const OVERRIDE_SCRIPT: &str = r#"(function (TZ, LOCALE) {
+  if (window.__MADEASY_REGIONAL__) return;
+  window.__MADEASY_REGIONAL__ = true;
+  var DateTimeFormat = Intl.DateTimeFormat;
+  if (TZ) {
+    try { new DateTimeFormat('en-US', { timeZone: TZ }); } catch (e) { TZ = null; }
+  }
+  function withDefaults(locales, options, timeZone) {
+    var opts = Object.assign({}, options);
+    if (timeZone && TZ && !opts.timeZone) opts.timeZone = TZ;
+    return [locales === undefined && LOCALE ? LOCALE : locales, opts];
+  }
+  ['DateTimeFormat', 'NumberFormat', 'Collator', 'PluralRules', 'RelativeTimeFormat', 'ListFormat'].forEach(function (name) {
+    var Original = Intl[name];
+    if (!Original) return;
+    var Wrapped = function (locales, options) {
+      var args = withDefaults(locales, options, name === 'DateTimeFormat');
+      return new Original(args[0], args[1]);
+    };
+    Wrapped.prototype = Original.prototype;
+    Wrapped.supportedLocalesOf = Original.supportedLocalesOf;
+    Intl[name] = Wrapped;
+  });
+  ['toLocaleString', 'toLocaleDateString', 'toLocaleTimeString'].forEach(function (name) {
+    var original = Date.prototype[name];
+    Date.prototype[name] = function (locales, options) {
+      var args = withDefaults(locales, options, true);
+      return original.call(this, args[0], args[1]);
+    };
+  });
+  var numberToLocale = Number.prototype.toLocaleString;
+  Number.prototype.toLocaleString = function (locales, options) {
+    var args = withDefaults(locales, options, false);
+    return numberToLocale.call(this, args[0], args[1]);
+  };
+  if (LOCALE) {
+    var languages = Object.freeze([LOCALE].concat(LOCALE.indexOf('-') > 0 ? [LOCALE.split('-')[0]] : []));
+    Object.defineProperty(Navigator.prototype, 'language', { get: function () { return LOCALE; }, configurable: true });
+    Object.defineProperty(Navigator.prototype, 'languages', { get: function () { return languages; }, configurable: true });
+  }
+  if (!TZ) return;
+  var parts = new DateTimeFormat('en-US', {
+    timeZone: TZ, hourCycle: 'h23', year: 'numeric', month: 'numeric', day: 'numeric', hour: 'numeric', minute: 'numeric', second: 'numeric'
+  });
+  var utcTime = Date.prototype.getTime;
+  // Minutes behind UTC, like getTimezoneOffset
+  function offset(date) {
+    var time = utcTime.call(date);
+    if (isNaN(time)) return NaN;
+    var p = {};
+    parts.formatToParts(new Date(time)).forEach(function (part) { p[part.type] = +part.value; });
+    var local = Date.UTC(p.year, p.month - 1, p.day, p.hour % 24, p.minute, p.second);
+    return Math.round((Math.floor(time / 1000) * 1000 - local) / 60000);
+  }
+  function shifted(date) { return new Date(utcTime.call(date) - offset(date) * 60000); }
+  Date.prototype.getTimezoneOffset = function () { return offset(this); };
+  [['getFullYear', 'getUTCFullYear'], ['getMonth', 'getUTCMonth'], ['getDate', 'getUTCDate'], ['getDay', 'getUTCDay'],
+   ['getHours', 'getUTCHours'], ['getMinutes', 'getUTCMinutes'], ['getSeconds', 'getUTCSeconds']].forEach(function (pair) {
+    var utc = Date.prototype[pair[1]];
+    Date.prototype[pair[0]] = function () { return utc.call(shifted(this)); };
+  });
+  function pad(n) { return (n < 10 ? '0' : '') + n; }
+  function zone(date) {
+    var minutes = -offset(date), sign = minutes < 0 ? '-' : '+';
+    minutes = Math.abs(minutes);
+    return 'GMT' + sign + pad(Math.floor(minutes / 60)) + pad(minutes % 60);
+  }
+  var dateToString = Date.prototype.toDateString;
+  Date.prototype.toDateString = function () { return isNaN(utcTime.call(this)) ? dateToString.call(this) : shifted(this).toUTCString().replace(/^(\w+), (\d+) (\w+) (\d+).*$/, '$1 $3 $2 $4'); };
+  Date.prototype.toTimeString = function () {
+    if (isNaN(utcTime.call(this))) return 'Invalid Date';
+    return pad(this.getHours()) + ':' + pad(this.getMinutes()) + ':' + pad(this.getSeconds()) + ' ' + zone(this);
+  };
+  Date.prototype.toString = function () {
+    if (isNaN(utcTime.call(this))) return 'Invalid Date';
+    return this.toDateString() + ' ' + this.toTimeString();
+  };
+})"#;
+
+// Initialization script for new windows of the active profile
+pub fn initialization_script(app: &AppHandle) -> String {
+    let overrides = load(app, &profiles::active_profile(app));
+    format!(
+        "{}({}, {});",
+        OVERRIDE_SCRIPT,
+        serde_json::to_string(&overrides.timezone).unwrap_or_else(|_| "null".to_string()),
+        serde_json::to_string(&overrides.locale).unwrap_or_else(|_| "null".to_string()),
+    )
+}
+
+// Called on every window built by windowpool::build_window
+pub fn apply(app: &AppHandle, window: &Window) {
+    let Some(header) = load(app, &profiles::active_profile(app)).header() else {
+        return;
+    };
+    if let Err(e) = platform::set_accept_language(window, &header) {
+        eprintln!("Failed to set Accept-Language: {}", e);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_regional_overrides(
+    app_handle: AppHandle,
+    profile_id: Option<String>,
+) -> Result<RegionalOverrides, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    Ok(load(&app_handle, &profile))
+}
+
+// Applies to windows opened afterwards
+#[tauri::command]
+#[specta::specta]
+pub async fn set_regional_overrides(
+    app_handle: AppHandle,
+    overrides: RegionalOverrides,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(timezone) = overrides.timezone.as_deref().filter(|t| !valid_timezone(t)) {
+        return Err(format!("Invalid timezone: {}", timezone));
+    }
+    if let Some(locale) = overrides.locale.as_deref().filter(|l| !valid_locale(l)) {
+        return Err(format!("Invalid locale: {}", locale));
+    }
+    if let Some(header) = overrides.accept_language.as_deref().filter(|h| !valid_header(h)) {
+        return Err(format!("Invalid Accept-Language value: {}", header));
+    }
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    storage::save(&app_handle, &profiles::profile_file(&profile, CONFIG_FILE), &overrides)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::Window;
+    use webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL;
+    use webview2_com::WebResourceRequestedEventHandler;
+    use windows_webview2::core::HSTRING;
+    use windows_webview2::Win32::System::WinRT::EventRegistrationToken;
+
+    // WebView2 has no language setting per webview, so every request gets the header
+    pub fn set_accept_language(window: &Window, header: &str) -> Result<(), String> {
+        let header = header.to_string();
+        window
+            .with_webview(move |webview| unsafe {
+                let result = (|| -> windows_webview2::core::Result<()> {
+                    let core = webview.controller().CoreWebView2()?;
+                    core.AddWebResourceRequestedFilter(&HSTRING::from("*"), COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL)?;
+                    let handler = WebResourceRequestedEventHandler::create(Box::new(move |_, args| {
+                        if let Some(args) = args {
+                            args.Request()?
+                                .Headers()?
+                                .SetHeader(&HSTRING::from("Accept-Language"), &HSTRING::from(header.as_str()))?;
+                        }
+                        Ok(())
+                    }));
+                    let mut token = EventRegistrationToken::default();
+                    core.add_WebResourceRequested(&handler, &mut token)
+                })();
+                if let Err(e) = result {
+                    eprintln!("Failed to install Accept-Language handler: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use tauri::Window;
+    use webkit2gtk::{WebContextExt, WebViewExt};
+
+    // Preferred languages are per web context, which all windows share
+    pub fn set_accept_language(window: &Window, header: &str) -> Result<(), String> {
+        let languages: Vec<String> = header
+            .split(',')
+            .filter_map(|part| part.split(';').next())
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty() && tag != "*")
+            .collect();
+        window
+            .with_webview(move |webview| {
+                if let Some(context) = webview.inner().context() {
+                    let languages: Vec<&str> = languages.iter().map(String::as_str).collect();
+                    context.set_preferred_languages(&languages);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    use tauri::Window;
+
+    // WKWebView always sends the system's preferred languages
+    pub fn set_accept_language(_window: &Window, _header: &str) -> Result<(), String> {
+        Err("Accept-Language overrides are not supported on this platform".to_string())
+    }
+}
@@ -0,0 +1,220 @@
+// Resource usage of the app process, its webview helper processes and windows
+// Process figures come from the OS; per-window JS heap sizes are reported by each
+// page (where the engine exposes performance.memory).
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, RefreshKind, System};
+use tauri::{AppHandle, Manager, Window};
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessKind {
+    Main,
+    Webview,
+    Gpu,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    pub kind: ProcessKind,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub handles: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WindowUsage {
+    pub window_id: String,
+    pub url: Option<String>,
+    pub visible: bool,
+    pub js_heap_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ResourceReport {
+    pub total_cpu_percent: f32,
+    pub total_memory_bytes: u64,
+    pub processes: Vec<ProcessUsage>,
+    pub windows: Vec<WindowUsage>,
+}
+
+#[derive(Default)]
+pub struct ResourceMonitor {
+    system: Mutex<Option<System>>,
+    // Latest heap size and URL reported by each window
+    window_reports: Mutex<HashMap<String, (Option<u64>, String)>>,
+}
+
+const MEMORY_REPORT_SCRIPT: &str = r#"(function () {
+  if (!window.__TAURI_INVOKE__) return;
+  var heap = performance.memory ? performance.memory.usedJSHeapSize : null;
+  window.__TAURI_INVOKE__('report_window_memory', { url: location.href, jsHeapBytes: heap });
+})();"#;
+
+fn classify(name: &str, is_main: bool) -> ProcessKind {
+    let name = name.to_lowercase();
+    if is_main {
+        ProcessKind::Main
+    } else if name.contains("gpu") {
+        ProcessKind::Gpu
+    } else if name.contains("webview") || name.contains("webkit") || name.contains("webcontent") {
+        ProcessKind::Webview
+    } else {
+        ProcessKind::Other
+    }
+}
+
+// The app process plus every process descended from it
+fn app_processes(system: &System) -> Vec<ProcessUsage> {
+    let root = Pid::from_u32(std::process::id());
+    let mut family = vec![root];
+    let mut i = 0;
+    while i < family.len() {
+        let parent = family[i];
+        family.extend(
+            system
+                .processes()
+                .iter()
+                .filter(|(_, p)| p.parent() == Some(parent))
+                .map(|(pid, _)| *pid),
+        );
+        i += 1;
+    }
+
+    family
+        .into_iter()
+        .filter_map(|pid| system.process(pid).map(|p| (pid, p)))
+        .map(|(pid, process)| ProcessUsage {
+            pid: pid.as_u32(),
+            name: process.name().to_string(),
+            kind: classify(process.name(), pid == root),
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+            handles: platform::handle_count(pid.as_u32()),
+        })
+        .collect()
+}
+
+pub fn collect(app: &AppHandle) -> ResourceReport {
+    let monitor = app.state::<ResourceMonitor>();
+
+    let processes = {
+        let mut system = monitor.system.lock().unwrap();
+        let system = system.get_or_insert_with(|| {
+            System::new_with_specifics(
+                RefreshKind::new().with_processes(ProcessRefreshKind::everything()),
+            )
+        });
+        system.refresh_processes();
+        app_processes(system)
+    };
+
+    let reports = monitor.window_reports.lock().unwrap();
+    let windows = app
+        .windows()
+        .into_iter()
+        .map(|(label, window)| {
+            let report = reports.get(&label);
+            WindowUsage {
+                url: report.map(|(_, url)| url.clone()),
+                js_heap_bytes: report.and_then(|(heap, _)| *heap),
+                visible: window.is_visible().unwrap_or(false),
+                window_id: label,
+            }
+        })
+        .collect();
+
+    ResourceReport {
+        total_cpu_percent: processes.iter().map(|p| p.cpu_percent).sum(),
+        total_memory_bytes: processes.iter().map(|p| p.memory_bytes).sum(),
+        processes,
+        windows,
+    }
+}
+
+// Periodically ask windows for their heap size and broadcast a `resource-report`
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        for window in app.windows().values() {
+            let _ = window.eval(MEMORY_REPORT_SCRIPT);
+        }
+        std::thread::sleep(REPORT_INTERVAL);
+        let _ = app.emit_all("resource-report", collect(&app));
+    });
+}
+
+// Forget reports for windows that have closed
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<ResourceMonitor>()
+        .window_reports
+        .lock()
+        .unwrap()
+        .remove(label);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_resource_usage(app_handle: AppHandle) -> Result<ResourceReport, String> {
+    Ok(collect(&app_handle))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn report_window_memory(
+    window: Window,
+    monitor: tauri::State<'_, ResourceMonitor>,
+    url: String,
+    js_heap_bytes: Option<u64>,
+) -> Result<(), String> {
+    monitor
+        .window_reports
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), (js_heap_bytes, url));
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        GetProcessHandleCount, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    pub fn handle_count(pid: u32) -> Option<u64> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut count = 0u32;
+            let result = GetProcessHandleCount(process, &mut count);
+            let _ = CloseHandle(process);
+            result.ok().map(|_| u64::from(count))
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    // Open file descriptors are the closest equivalent of handles
+    pub fn handle_count(pid: u32) -> Option<u64> {
+        std::fs::read_dir(format!("/proc/{}/fd", pid))
+            .ok()
+            .map(|entries| entries.count() as u64)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub fn handle_count(_pid: u32) -> Option<u64> {
+        None
+    }
+}
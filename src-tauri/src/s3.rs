@@ -0,0 +1,178 @@
+// Export uploads to S3 and S3-compatible object storage (MinIO, R2, ...)
+// The secret access key lives in the keychain. Large files are streamed as
+// multipart uploads by `put_object_stream`.
+
+use s3::creds::Credentials;
+use s3::{Bucket, Region};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::{bandwidth, secrets, storage};
+
+const S3_FILE: &str = "s3.json";
+const SECRET_KEY: &str = "s3-secret-access-key";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct S3Config {
+    // Custom endpoint for S3-compatible services; AWS when empty
+    #[serde(default)]
+    endpoint: Option<String>,
+    region: String,
+    access_key_id: String,
+    // MinIO and most self-hosted services need path-style addressing
+    #[serde(default)]
+    path_style: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct S3Status {
+    config: Option<S3Config>,
+    has_secret: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct S3Upload {
+    bucket: String,
+    key: String,
+    size: u64,
+}
+
+#[derive(Default)]
+pub struct S3State {
+    config: Mutex<Option<S3Config>>,
+}
+
+impl S3State {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, S3_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct S3UploadAction {
+    pub bucket: String,
+    pub key: String,
+    pub path: String,
+}
+
+fn configured(app: &AppHandle) -> Result<S3Config, String> {
+    app.state::<S3State>()
+        .config
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "S3 storage is not configured".to_string())
+}
+
+fn region(config: &S3Config) -> Result<Region, String> {
+    match config.endpoint.as_deref().filter(|e| !e.trim().is_empty()) {
+        Some(endpoint) => Ok(Region::Custom {
+            region: config.region.clone(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+        }),
+        None => config.region.parse().map_err(|e| format!("Invalid region {}: {}", config.region, e)),
+    }
+}
+
+fn bucket(config: &S3Config, name: &str) -> Result<Bucket, String> {
+    let secret = secrets::require(SECRET_KEY)?;
+    let credentials = Credentials::new(Some(&config.access_key_id), Some(&secret), None, None, None)
+        .map_err(|e| e.to_string())?;
+    let bucket = Bucket::new(name, region(config)?, credentials).map_err(|e| e.to_string())?;
+    Ok(if config.path_style {
+        bucket.with_path_style()
+    } else {
+        bucket
+    })
+}
+
+pub async fn upload(app: &AppHandle, bucket_name: &str, key: &str, path: &str) -> Result<S3Upload, String> {
+    let key = key.trim_start_matches('/');
+    if bucket_name.is_empty() || key.is_empty() {
+        return Err("Bucket and key cannot be empty".to_string());
+    }
+    let path = Path::new(path);
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+        .len();
+
+    let bucket = bucket(&configured(app)?, bucket_name)?;
+    audit::record(app, AuditCategory::Credential, "s3.secret_access_key", json!({ "bucket": bucket_name }));
+    let mut file = tokio::fs::File::open(path).await.map_err(|e| e.to_string())?;
+    let status = bucket
+        .put_object_stream(&mut file, key)
+        .await
+        .map_err(|e| e.to_string())?;
+    bandwidth::record(app, &bucket.url(), size, 0);
+    if !(200..300).contains(&status) {
+        return Err(format!("Upload to s3://{}/{} failed with status {}", bucket_name, key, status));
+    }
+
+    audit::record(
+        app,
+        AuditCategory::Export,
+        "s3.upload",
+        json!({ "bucket": bucket_name, "key": key, "size": size }),
+    );
+    Ok(S3Upload {
+        bucket: bucket_name.to_string(),
+        key: key.to_string(),
+        size,
+    })
+}
+
+pub async fn run_action(app: &AppHandle, action: &S3UploadAction) -> Result<Value, String> {
+    let uploaded = upload(app, &action.bucket, &action.key, &action.path).await?;
+    Ok(json!(uploaded))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_s3_config(state: tauri::State<'_, S3State>) -> Result<S3Status, String> {
+    Ok(S3Status {
+        config: state.config.lock().unwrap().clone(),
+        has_secret: secrets::get(SECRET_KEY)?.is_some(),
+    })
+}
+
+// A missing secret keeps the stored one
+#[tauri::command]
+#[specta::specta]
+pub async fn set_s3_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, S3State>,
+    config: S3Config,
+    secret_access_key: Option<String>,
+) -> Result<(), String> {
+    if config.access_key_id.trim().is_empty() {
+        return Err("Access key id cannot be empty".to_string());
+    }
+    region(&config)?;
+    let secret_changed = secret_access_key.is_some();
+    if let Some(secret) = secret_access_key {
+        secrets::set(SECRET_KEY, &secret)?;
+    }
+    storage::save(&app_handle, S3_FILE, &Some(&config))?;
+    audit::record(
+        &app_handle,
+        AuditCategory::Settings,
+        "s3.config",
+        json!({ "endpoint": config.endpoint, "region": config.region, "secret_changed": secret_changed }),
+    );
+    *state.config.lock().unwrap() = Some(config);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn upload_to_s3(app_handle: AppHandle, bucket: String, key: String, path: String) -> Result<S3Upload, String> {
+    upload(&app_handle, &bucket, &key, &path).await
+}
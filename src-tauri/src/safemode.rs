@@ -0,0 +1,162 @@
+// Crash-loop detection and safe mode
+// Every launch bumps a counter in startup-crashes.json that is cleared once
+// the app has run for STABLE_AFTER past startup or quits cleanly. A counter
+// already at CRASH_THRESHOLD means the last launches died early, so this one
+// starts in safe mode: plugins are not started, userscripts are not injected,
+// imported themes are replaced by "system" and the server keeps workflow
+// schedules paused. The main window shows the recovery page instead of the
+// app, where subsystems can be reset before restarting normally. Launching
+// with `--safe-mode` forces it.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{appscheme, load_app_config, monitors, plugins, storage, userscripts, APP_CONFIG_FILE};
+
+const CRASHES_FILE: &str = "startup-crashes.json";
+const CRASH_THRESHOLD: u32 = 3;
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+const SAFE_MODE_ARG: &str = "--safe-mode";
+// Set on the server process in safe mode
+pub const SERVER_ENV: &str = "MADEASY_SAFE_MODE";
+const RECOVERY_PATH: &str = "/recovery";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CrashRecord {
+    // Launches since the app last ran stably
+    unfinished_starts: u32,
+    last_start: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Plugins,
+    Userscripts,
+    Theme,
+    WindowPlacements,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SafeModeStatus {
+    active: bool,
+    // Started with --safe-mode rather than after crashes
+    forced: bool,
+    // Launches that ended before the app ran stably
+    crashes: u32,
+    threshold: u32,
+}
+
+pub struct SafeModeState {
+    active: bool,
+    forced: bool,
+    crashes: u32,
+}
+
+// Called first thing in `setup_app`, before anything that checks `active`
+pub fn detect(app: &AppHandle) -> SafeModeState {
+    let mut record: CrashRecord = storage::load(app, CRASHES_FILE);
+    let crashes = record.unfinished_starts;
+    record.unfinished_starts = crashes.saturating_add(1);
+    record.last_start = Some(chrono::Utc::now().timestamp_millis());
+    if let Err(e) = storage::save(app, CRASHES_FILE, &record) {
+        eprintln!("Failed to record startup: {}", e);
+    }
+
+    let forced = std::env::args().skip(1).any(|arg| arg == SAFE_MODE_ARG);
+    let active = forced || crashes >= CRASH_THRESHOLD;
+    if active {
+        eprintln!(
+            "Starting in safe mode ({})",
+            if forced { "requested".to_string() } else { format!("{} unfinished starts", crashes) }
+        );
+    }
+    SafeModeState { active, forced, crashes }
+}
+
+pub fn active(app: &AppHandle) -> bool {
+    app.try_state::<SafeModeState>().map_or(false, |state| state.active)
+}
+
+fn clear(app: &AppHandle) -> Result<(), String> {
+    storage::save(app, CRASHES_FILE, &CrashRecord::default())
+}
+
+// Called once deferred startup has finished. Safe mode keeps the count until
+// the user leaves it from the recovery page.
+pub fn on_ready(app: &AppHandle) {
+    if active(app) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(STABLE_AFTER);
+        if let Err(e) = clear(&app) {
+            eprintln!("Failed to clear startup crash count: {}", e);
+        }
+    });
+}
+
+// Called during a graceful shutdown
+pub fn on_clean_exit(app: &AppHandle) {
+    if !active(app) {
+        let _ = clear(app);
+    }
+}
+
+// Keeps the main window on the recovery page while in safe mode
+pub fn on_page_load(window: &Window, url: &str) {
+    if window.label() != "main" || !active(&window.app_handle()) {
+        return;
+    }
+    let recovery = appscheme::app_url(RECOVERY_PATH);
+    if !url.starts_with(&recovery) {
+        let target = serde_json::to_string(&recovery).unwrap_or_default();
+        let _ = window.eval(&format!("location.replace({})", target));
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_safe_mode_status(state: tauri::State<'_, SafeModeState>) -> Result<SafeModeStatus, String> {
+    Ok(SafeModeStatus {
+        active: state.active,
+        forced: state.forced,
+        crashes: state.crashes,
+        threshold: CRASH_THRESHOLD,
+    })
+}
+
+// Returns a short description of what was reset
+#[tauri::command]
+#[specta::specta]
+pub async fn reset_subsystem(app_handle: AppHandle, subsystem: Subsystem) -> Result<String, String> {
+    let message = match subsystem {
+        Subsystem::Plugins => format!("Disabled {} plugin(s)", plugins::disable_all(&app_handle)?),
+        Subsystem::Userscripts => format!("Disabled {} userscript(s)", userscripts::disable_all(&app_handle)?),
+        Subsystem::Theme => {
+            let mut config = load_app_config(&app_handle);
+            config.theme = "system".to_string();
+            storage::save(&app_handle, APP_CONFIG_FILE, &config)?;
+            "Theme set to follow the system".to_string()
+        }
+        Subsystem::WindowPlacements => {
+            monitors::reset_placements(&app_handle)?;
+            "Window positions forgotten".to_string()
+        }
+    };
+    eprintln!("Safe mode reset {:?}: {}", subsystem, message);
+    Ok(message)
+}
+
+// Clear the crash count and restart without safe mode
+#[tauri::command]
+#[specta::specta]
+pub async fn exit_safe_mode(app_handle: AppHandle) -> Result<(), String> {
+    clear(&app_handle)?;
+    app_handle.restart();
+    Ok(())
+}
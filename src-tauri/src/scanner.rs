@@ -0,0 +1,249 @@
+// Virus scanning of completed downloads
+// When a scanner is configured, every recorded download is handed to it:
+// Microsoft Defender's command-line scanner on Windows, or a user-supplied
+// command (e.g. `clamscan --no-summary {path}`) anywhere. The result is kept
+// next to the download record, and `open_download` refuses files whose scan
+// has not passed.
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::storage;
+
+const SCANNER_FILE: &str = "scanner.json";
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+// Longest scanner output kept with a result
+const MAX_DETAIL_CHARS: usize = 2000;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS download_scans (
+    download_id INTEGER PRIMARY KEY REFERENCES downloads (id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    scanner TEXT NOT NULL,
+    detail TEXT,
+    scanned_at INTEGER NOT NULL
+);
+";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScannerKind {
+    #[default]
+    None,
+    // MpCmdRun.exe; Windows only
+    Defender,
+    Command {
+        program: String,
+        // `{path}` is replaced with the file to scan
+        args: Vec<String>,
+        // Exit codes meaning a threat was found; other non-zero codes are errors
+        #[serde(default = "default_infected_codes")]
+        infected_exit_codes: Vec<i32>,
+    },
+}
+
+// clamscan and most scanners exit with 1 on a detection
+fn default_infected_codes() -> Vec<i32> {
+    vec![1]
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ScannerConfig {
+    scanner: ScannerKind,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanStatus {
+    Pending,
+    Clean,
+    Infected,
+    // The scanner could not be run or reported an error
+    Failed,
+}
+
+impl ScanStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ScanStatus::Pending => "pending",
+            ScanStatus::Clean => "clean",
+            ScanStatus::Infected => "infected",
+            ScanStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "pending" => ScanStatus::Pending,
+            "clean" => ScanStatus::Clean,
+            "infected" => ScanStatus::Infected,
+            _ => ScanStatus::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ScanResult {
+    pub status: ScanStatus,
+    pub scanner: String,
+    pub detail: Option<String>,
+    pub scanned_at: i64,
+}
+
+#[derive(Default)]
+pub struct ScannerState {
+    config: Mutex<ScannerConfig>,
+}
+
+impl ScannerState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, SCANNER_FILE)),
+        }
+    }
+}
+
+pub fn enabled(app: &AppHandle) -> bool {
+    !matches!(app.state::<ScannerState>().config.lock().unwrap().scanner, ScannerKind::None)
+}
+
+fn defender_path() -> String {
+    let program_files = std::env::var("ProgramFiles").unwrap_or_else(|_| "C:\\Program Files".to_string());
+    format!("{}\\Windows Defender\\MpCmdRun.exe", program_files)
+}
+
+fn truncate(output: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(output).trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+    Some(match text.char_indices().nth(MAX_DETAIL_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    })
+}
+
+async fn run_scanner(config: &ScannerConfig, path: &Path) -> (ScanStatus, String, Option<String>) {
+    let (name, program, args, infected_codes) = match &config.scanner {
+        ScannerKind::None => return (ScanStatus::Clean, "none".to_string(), None),
+        ScannerKind::Defender => (
+            "defender".to_string(),
+            defender_path(),
+            vec![
+                "-Scan".to_string(),
+                "-ScanType".to_string(),
+                "3".to_string(),
+                "-File".to_string(),
+                path.to_string_lossy().into_owned(),
+                "-DisableRemediation".to_string(),
+            ],
+            // MpCmdRun exits with 2 when malware is found
+            vec![2],
+        ),
+        ScannerKind::Command {
+            program,
+            args,
+            infected_exit_codes,
+        } => (
+            program.clone(),
+            program.clone(),
+            args.iter()
+                .map(|a| a.replace("{path}", &path.to_string_lossy()))
+                .collect(),
+            infected_exit_codes.clone(),
+        ),
+    };
+    let timeout = Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let output = tokio::process::Command::new(&program)
+        .args(&args)
+        .kill_on_drop(true)
+        .output();
+    let output = match tokio::time::timeout(timeout, output).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return (ScanStatus::Failed, name, Some(format!("Cannot run {}: {}", program, e))),
+        Err(_) => return (ScanStatus::Failed, name, Some("Scan timed out".to_string())),
+    };
+    let detail = truncate(&output.stdout).or_else(|| truncate(&output.stderr));
+    let status = match output.status.code() {
+        Some(0) => ScanStatus::Clean,
+        Some(code) if infected_codes.contains(&code) => ScanStatus::Infected,
+        _ => ScanStatus::Failed,
+    };
+    (status, name, detail)
+}
+
+fn store(app: &AppHandle, download_id: i64, result: &ScanResult) -> Result<(), String> {
+    app.state::<Database>().with(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO download_scans (download_id, status, scanner, detail, scanned_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![download_id, result.status.as_str(), result.scanner, result.detail, result.scanned_at],
+        )
+        .map(|_| ())
+    })
+}
+
+// Scan a recorded download and store the outcome; None when no scanner is set
+pub async fn scan(app: &AppHandle, download_id: i64, path: &Path) -> Result<Option<ScanResult>, String> {
+    let config = app.state::<ScannerState>().config.lock().unwrap().clone();
+    if matches!(config.scanner, ScannerKind::None) {
+        return Ok(None);
+    }
+    let pending = ScanResult {
+        status: ScanStatus::Pending,
+        scanner: String::new(),
+        detail: None,
+        scanned_at: chrono::Utc::now().timestamp_millis(),
+    };
+    store(app, download_id, &pending)?;
+    let (status, scanner, detail) = run_scanner(&config, path).await;
+    let result = ScanResult {
+        status,
+        scanner,
+        detail,
+        scanned_at: chrono::Utc::now().timestamp_millis(),
+    };
+    store(app, download_id, &result)?;
+    let _ = app.emit_all("download-scanned", (download_id, &result));
+    Ok(Some(result))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_scanner_config(state: tauri::State<'_, ScannerState>) -> Result<ScannerConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_scanner_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ScannerState>,
+    config: ScannerConfig,
+) -> Result<(), String> {
+    match &config.scanner {
+        ScannerKind::Defender if !cfg!(target_os = "windows") => {
+            return Err("Microsoft Defender is only available on Windows".to_string())
+        }
+        ScannerKind::Command { program, args, .. } => {
+            if program.trim().is_empty() {
+                return Err("Scanner program cannot be empty".to_string());
+            }
+            if !args.iter().any(|a| a.contains("{path}")) {
+                return Err("Scanner arguments must include {path}".to_string());
+            }
+        }
+        _ => {}
+    }
+    storage::save(&app_handle, SCANNER_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
@@ -0,0 +1,273 @@
+// User automation scripts
+// Rhai scripts for automations that outgrow the workflow DSL. Scripts live in
+// the app-data "scripts" folder (or are passed inline) and only see the
+// bindings registered in `register`: opening and navigating windows, querying
+// pages, exporting CSV/JSON into "script-exports" and notifications. There is
+// no file, process or module access, and runs are capped in operations and
+// size and can be stopped with `cancel_task`.
+
+use rhai::{Dynamic, Engine, EvalAltResult};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Window, WindowUrl};
+
+use crate::artifacts::safe_name;
+use crate::audit::{self, AuditCategory};
+use crate::notifications::{self, Notice, NotificationCategory};
+use crate::{pagequery, tasks, windowpool, workflow};
+
+const SCRIPTS_DIR: &str = "scripts";
+const EXPORTS_DIR: &str = "script-exports";
+const SCRIPT_EXTENSION: &str = "rhai";
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_SLEEP_MS: i64 = 60_000;
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ScriptInfo {
+    name: String,
+    size: u64,
+    modified: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct RunScriptRequest {
+    // Name of a script in the library, or inline source
+    path: Option<String>,
+    source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ScriptResult {
+    // Lines written with print() and debug()
+    output: Vec<String>,
+    result: Value,
+    duration_ms: u64,
+}
+
+fn scripts_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    data_dir(app, SCRIPTS_DIR)
+}
+
+fn data_dir(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "App data directory unavailable".to_string())?
+        .join(name);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+// Script names are plain file stems so a name can never leave the library
+fn script_path(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let name = name.strip_suffix(".rhai").unwrap_or(name);
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if !valid {
+        return Err(format!("Invalid script name: {}", name));
+    }
+    Ok(scripts_dir(app)?.join(format!("{}.{}", name, SCRIPT_EXTENSION)))
+}
+
+fn script_error(message: impl Into<String>) -> Box<EvalAltResult> {
+    message.into().into()
+}
+
+fn find_window(app: &AppHandle, window_id: &str) -> Result<Window, Box<EvalAltResult>> {
+    app.get_window(window_id)
+        .ok_or_else(|| script_error(format!("Window not found: {}", window_id)))
+}
+
+fn web_url(url: &str) -> Result<url::Url, Box<EvalAltResult>> {
+    let parsed = url::Url::parse(url).map_err(|e| script_error(format!("Invalid URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(script_error("Scripts can only open http and https URLs"));
+    }
+    Ok(parsed)
+}
+
+fn query(app: &AppHandle, window_id: &str, body: String) -> Result<Dynamic, Box<EvalAltResult>> {
+    let window = find_window(app, window_id)?;
+    let value = tauri::async_runtime::block_on(pagequery::run(app, &window, &body, QUERY_TIMEOUT)).map_err(script_error)?;
+    rhai::serde::to_dynamic(value)
+}
+
+fn write_export(app: &AppHandle, name: &str, extension: &str, bytes: Vec<u8>) -> Result<String, Box<EvalAltResult>> {
+    let dir = data_dir(app, EXPORTS_DIR).map_err(script_error)?;
+    let path = dir.join(format!("{}.{}", safe_name(name), extension));
+    std::fs::write(&path, bytes).map_err(|e| script_error(e.to_string()))?;
+    let path = path.to_string_lossy().to_string();
+    audit::record(app, AuditCategory::Export, "script.export", json!({ "path": path }));
+    Ok(path)
+}
+
+fn register(engine: &mut Engine, app: &AppHandle) {
+    let handle = app.clone();
+    engine.register_fn("open_window", move |url: &str| -> Result<String, Box<EvalAltResult>> {
+        let window = windowpool::build_window(&handle, WindowUrl::External(web_url(url)?), true).map_err(script_error)?;
+        Ok(window.label().to_string())
+    });
+
+    let handle = app.clone();
+    engine.register_fn("navigate", move |window_id: &str, url: &str| -> Result<(), Box<EvalAltResult>> {
+        let url = web_url(url)?;
+        find_window(&handle, window_id)?
+            .eval(&format!("location.href = {}", serde_json::to_string(url.as_str()).unwrap_or_default()))
+            .map_err(|e| script_error(e.to_string()))
+    });
+
+    let handle = app.clone();
+    engine.register_fn("current_url", move |window_id: &str| -> Result<String, Box<EvalAltResult>> {
+        let window = find_window(&handle, window_id)?;
+        window.url().map(|u| u.to_string()).map_err(|e| script_error(e.to_string()))
+    });
+
+    let handle = app.clone();
+    engine.register_fn("query_text", move |window_id: &str, selector: &str| {
+        let body = format!(
+            "return Array.from(document.querySelectorAll({:?}), e => e.textContent.trim());",
+            selector
+        );
+        query(&handle, window_id, body)
+    });
+
+    let handle = app.clone();
+    engine.register_fn("query_attr", move |window_id: &str, selector: &str, attribute: &str| {
+        let body = format!(
+            "return Array.from(document.querySelectorAll({:?}), e => e.getAttribute({:?}));",
+            selector, attribute
+        );
+        query(&handle, window_id, body)
+    });
+
+    engine.register_fn("sleep", |ms: i64| {
+        std::thread::sleep(Duration::from_millis(ms.clamp(0, MAX_SLEEP_MS) as u64));
+    });
+
+    let handle = app.clone();
+    engine.register_fn("export_csv", move |name: &str, rows: rhai::Array| -> Result<String, Box<EvalAltResult>> {
+        let rows: Vec<workflow::Row> = rhai::serde::from_dynamic(&Dynamic::from_array(rows))?;
+        let csv = workflow::rows_to_csv(&rows).map_err(script_error)?;
+        write_export(&handle, name, "csv", csv)
+    });
+
+    let handle = app.clone();
+    engine.register_fn("export_json", move |name: &str, value: Dynamic| -> Result<String, Box<EvalAltResult>> {
+        let value: Value = rhai::serde::from_dynamic(&value)?;
+        let json = serde_json::to_vec_pretty(&value).map_err(|e| script_error(e.to_string()))?;
+        write_export(&handle, name, "json", json)
+    });
+
+    let handle = app.clone();
+    engine.register_fn("notify", move |title: &str, body: &str| -> Result<(), Box<EvalAltResult>> {
+        let notice = Notice::new(NotificationCategory::Workflow, title, body).owned_by("scripting");
+        notifications::notify(&handle, notice).map(|_| ()).map_err(script_error)
+    });
+}
+
+fn build_engine(app: &AppHandle, output: Arc<Mutex<Vec<String>>>, stop: Arc<AtomicBool>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(16 * 1024 * 1024);
+    engine.set_max_array_size(100_000);
+    engine.set_max_map_size(10_000);
+    engine.on_progress(move |_| stop.load(Ordering::Relaxed).then(|| Dynamic::from("cancelled")));
+
+    let printed = output.clone();
+    engine.on_print(move |line| printed.lock().unwrap().push(line.to_string()));
+    engine.on_debug(move |line, _, position| output.lock().unwrap().push(format!("[{}] {}", position, line)));
+    register(&mut engine, app);
+    engine
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_scripts(app_handle: AppHandle) -> Result<Vec<ScriptInfo>, String> {
+    let mut scripts = Vec::new();
+    for entry in std::fs::read_dir(scripts_dir(&app_handle)?).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if path.extension().map_or(true, |e| e != SCRIPT_EXTENSION) {
+            continue;
+        }
+        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        scripts.push(ScriptInfo {
+            name: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified: metadata
+                .modified()
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp_millis()),
+        });
+    }
+    scripts.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    Ok(scripts)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_script(app_handle: AppHandle, name: String) -> Result<String, String> {
+    std::fs::read_to_string(script_path(&app_handle, &name)?).map_err(|e| e.to_string())
+}
+
+// Scripts are compiled before saving so syntax errors show up in the editor
+#[tauri::command]
+#[specta::specta]
+pub async fn save_script(app_handle: AppHandle, name: String, source: String) -> Result<(), String> {
+    let path = script_path(&app_handle, &name)?;
+    Engine::new().compile(&source).map_err(|e| e.to_string())?;
+    std::fs::write(path, source).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_script(app_handle: AppHandle, name: String) -> Result<(), String> {
+    let path = script_path(&app_handle, &name)?;
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn run_script(app_handle: AppHandle, request: RunScriptRequest) -> Result<ScriptResult, String> {
+    let (name, source) = match (request.path, request.source) {
+        (Some(path), None) => {
+            let source = std::fs::read_to_string(script_path(&app_handle, &path)?).map_err(|e| e.to_string())?;
+            (path, source)
+        }
+        (None, Some(source)) => ("inline".to_string(), source),
+        _ => return Err("Provide either a script path or source".to_string()),
+    };
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let stop = Arc::new(AtomicBool::new(false));
+    let started = Instant::now();
+    let (app, printed, stopped) = (app_handle.clone(), output.clone(), stop.clone());
+    let task = tasks::blocking(move || {
+        let engine = build_engine(&app, printed, stopped);
+        let result = engine.eval::<Dynamic>(&source).map_err(|e| e.to_string())?;
+        rhai::serde::from_dynamic::<Value>(&result).map_err(|e| e.to_string())
+    });
+    let result = tasks::run(&app_handle, &format!("Script {}", name), task).await;
+    // A cancelled script keeps running on the blocking pool until its next operation
+    stop.store(true, Ordering::Relaxed);
+
+    let output = output.lock().unwrap().clone();
+    Ok(ScriptResult {
+        output,
+        result: result?,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,239 @@
+// Search engines with keyword shortcuts
+// "g rust tauri" searches Google, "w Oslo" searches Wikipedia; plain input goes to
+// the profile's default engine and URL-like input is navigated to directly.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::{profiles, storage};
+
+const ENGINES_FILE: &str = "search-engines.json";
+const TERMS_PLACEHOLDER: &str = "{searchTerms}";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SearchEngine {
+    pub id: String,
+    pub name: String,
+    pub keyword: String,
+    // URL with a {searchTerms} placeholder
+    pub url_template: String,
+    #[serde(default)]
+    pub suggest_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SearchEngines {
+    pub engines: Vec<SearchEngine>,
+    pub default_engine: String,
+}
+
+impl Default for SearchEngines {
+    fn default() -> Self {
+        let engine = |id: &str, name: &str, keyword: &str, url: &str, suggest: Option<&str>| SearchEngine {
+            id: id.to_string(),
+            name: name.to_string(),
+            keyword: keyword.to_string(),
+            url_template: url.to_string(),
+            suggest_url: suggest.map(str::to_string),
+        };
+        Self {
+            engines: vec![
+                engine(
+                    "google",
+                    "Google",
+                    "g",
+                    "https://www.google.com/search?q={searchTerms}",
+                    Some("https://suggestqueries.google.com/complete/search?client=firefox&q={searchTerms}"),
+                ),
+                engine(
+                    "duckduckgo",
+                    "DuckDuckGo",
+                    "d",
+                    "https://duckduckgo.com/?q={searchTerms}",
+                    Some("https://duckduckgo.com/ac/?type=list&q={searchTerms}"),
+                ),
+                engine("bing", "Bing", "b", "https://www.bing.com/search?q={searchTerms}", None),
+                engine(
+                    "wikipedia",
+                    "Wikipedia",
+                    "w",
+                    "https://en.wikipedia.org/wiki/Special:Search?search={searchTerms}",
+                    Some("https://en.wikipedia.org/w/api.php?action=opensearch&search={searchTerms}"),
+                ),
+            ],
+            default_engine: "google".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResolvedInput {
+    Url { url: String },
+    Search { url: String, engine_id: String, terms: String },
+}
+
+pub fn load_engines(app: &AppHandle, profile_id: &str) -> SearchEngines {
+    storage::load(app, &profiles::profile_file(profile_id, ENGINES_FILE))
+}
+
+fn save_engines(app: &AppHandle, profile_id: &str, engines: &SearchEngines) -> Result<(), String> {
+    storage::save(app, &profiles::profile_file(profile_id, ENGINES_FILE), engines)
+}
+
+pub fn expand_template(template: &str, terms: &str) -> String {
+    let encoded: String = url::form_urlencoded::byte_serialize(terms.as_bytes()).collect();
+    template.replace(TERMS_PLACEHOLDER, &encoded)
+}
+
+// Whether input should be treated as an address rather than search terms
+pub fn looks_like_url(input: &str) -> bool {
+    if input.contains(char::is_whitespace) {
+        return false;
+    }
+    if input.contains("://") {
+        return url::Url::parse(input).is_ok();
+    }
+    let host = input.split(['/', '?', '#']).next().unwrap_or_default();
+    host == "localhost"
+        || host.starts_with("localhost:")
+        || (host.contains('.') && !host.starts_with('.') && !host.ends_with('.'))
+}
+
+pub fn resolve(engines: &SearchEngines, query: &str) -> Result<ResolvedInput, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err("Empty query".to_string());
+    }
+
+    // Keyword search: "<keyword> <terms>"
+    if let Some((keyword, terms)) = query.split_once(char::is_whitespace) {
+        if let Some(engine) = engines.engines.iter().find(|e| e.keyword.eq_ignore_ascii_case(keyword)) {
+            let terms = terms.trim();
+            return Ok(ResolvedInput::Search {
+                url: expand_template(&engine.url_template, terms),
+                engine_id: engine.id.clone(),
+                terms: terms.to_string(),
+            });
+        }
+    }
+
+    if looks_like_url(query) {
+        let url = if query.contains("://") {
+            query.to_string()
+        } else {
+            format!("https://{}", query)
+        };
+        return Ok(ResolvedInput::Url { url });
+    }
+
+    let engine = engines
+        .engines
+        .iter()
+        .find(|e| e.id == engines.default_engine)
+        .or_else(|| engines.engines.first())
+        .ok_or_else(|| "No search engines configured".to_string())?;
+    Ok(ResolvedInput::Search {
+        url: expand_template(&engine.url_template, query),
+        engine_id: engine.id.clone(),
+        terms: query.to_string(),
+    })
+}
+
+fn validate(engine: &SearchEngine) -> Result<(), String> {
+    if engine.id.is_empty() || engine.name.trim().is_empty() {
+        return Err("Search engines need an id and a name".to_string());
+    }
+    if engine.keyword.is_empty() || engine.keyword.contains(char::is_whitespace) {
+        return Err("Keywords must be a single word".to_string());
+    }
+    for template in std::iter::once(&engine.url_template).chain(engine.suggest_url.as_ref()) {
+        if !template.contains(TERMS_PLACEHOLDER) {
+            return Err(format!("URL must contain {}: {}", TERMS_PLACEHOLDER, template));
+        }
+        url::Url::parse(&expand_template(template, "test"))
+            .map_err(|e| format!("Invalid URL template {}: {}", template, e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_search_engines(
+    app_handle: AppHandle,
+    profile_id: Option<String>,
+) -> Result<SearchEngines, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    Ok(load_engines(&app_handle, &profile))
+}
+
+// Create or update an engine (matched by id)
+#[tauri::command]
+#[specta::specta]
+pub async fn save_search_engine(
+    app_handle: AppHandle,
+    engine: SearchEngine,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    validate(&engine)?;
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut engines = load_engines(&app_handle, &profile);
+
+    if let Some(other) = engines
+        .engines
+        .iter()
+        .find(|e| e.id != engine.id && e.keyword.eq_ignore_ascii_case(&engine.keyword))
+    {
+        return Err(format!("Keyword \"{}\" is already used by {}", engine.keyword, other.name));
+    }
+
+    match engines.engines.iter_mut().find(|e| e.id == engine.id) {
+        Some(existing) => *existing = engine,
+        None => engines.engines.push(engine),
+    }
+    save_engines(&app_handle, &profile, &engines)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_search_engine(
+    app_handle: AppHandle,
+    engine_id: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut engines = load_engines(&app_handle, &profile);
+    if engines.default_engine == engine_id {
+        return Err("Cannot delete the default search engine".to_string());
+    }
+    engines.engines.retain(|e| e.id != engine_id);
+    save_engines(&app_handle, &profile, &engines)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_default_search_engine(
+    app_handle: AppHandle,
+    engine_id: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut engines = load_engines(&app_handle, &profile);
+    if !engines.engines.iter().any(|e| e.id == engine_id) {
+        return Err(format!("Unknown search engine: {}", engine_id));
+    }
+    engines.default_engine = engine_id;
+    save_engines(&app_handle, &profile, &engines)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn resolve_search(
+    app_handle: AppHandle,
+    query: String,
+    profile_id: Option<String>,
+) -> Result<ResolvedInput, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    resolve(&load_engines(&app_handle, &profile), &query)
+}
@@ -0,0 +1,61 @@
+// Credentials in the OS keychain (Windows Credential Manager, macOS Keychain,
+// Secret Service on Linux). Settings files only ever store the key name, or
+// for admin PINs an Argon2 hash.
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+const SERVICE: &str = "com.madeasy.browser";
+
+fn entry(key: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, key).map_err(|e| e.to_string())
+}
+
+pub fn set(key: &str, secret: &str) -> Result<(), String> {
+    entry(key)?.set_password(secret).map_err(|e| e.to_string())
+}
+
+pub fn get(key: &str) -> Result<Option<String>, String> {
+    match entry(key)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Like `get`, but a missing secret is an error
+pub fn require(key: &str) -> Result<String, String> {
+    get(key)?.ok_or_else(|| format!("No credentials stored for {}", key))
+}
+
+pub fn delete(key: &str) -> Result<(), String> {
+    match entry(key)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+const MIN_PIN_LEN: usize = 4;
+const MAX_PIN_LEN: usize = 12;
+
+pub fn validate_pin(pin: &str) -> Result<(), String> {
+    if (MIN_PIN_LEN..=MAX_PIN_LEN).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(format!("PIN must be {} to {} digits", MIN_PIN_LEN, MAX_PIN_LEN))
+    }
+}
+
+pub fn hash_pin(pin: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut rand::rngs::OsRng);
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| e.to_string())
+}
+
+pub fn pin_matches(hash: &str, pin: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(pin.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
@@ -0,0 +1,325 @@
+// On-page SEO audit
+// The page reports the facts (title, meta tags, headings, images, structured
+// data, ...) and the checks and scoring happen here, so the rules stay in one
+// place. The `X-Robots-Tag` header is read with a separate HEAD request.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{bandwidth, pagequery};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+const TITLE_LEN: (usize, usize) = (10, 60);
+const DESCRIPTION_LEN: (usize, usize) = (50, 160);
+
+const FACTS_SCRIPT: &str = r#"
+var meta = function(name) {
+  var el = document.querySelector('meta[name="' + name + '" i], meta[property="' + name + '" i]');
+  return el ? el.getAttribute('content') : null;
+};
+var canonical = document.querySelector('link[rel="canonical"]');
+var images = Array.prototype.slice.call(document.images);
+var types = [];
+document.querySelectorAll('script[type="application/ld+json"]').forEach(function(s) {
+  try {
+    var data = JSON.parse(s.textContent);
+    (Array.isArray(data) ? data : (data['@graph'] || [data])).forEach(function(item) {
+      var type = item && item['@type'];
+      if (type) types = types.concat([].concat(type).map(String));
+    });
+  } catch (e) { types.push('invalid'); }
+});
+return {
+  url: location.href,
+  title: document.title || null,
+  description: meta('description'),
+  canonical: canonical ? canonical.href : null,
+  robots: meta('robots'),
+  lang: document.documentElement.getAttribute('lang'),
+  viewport: meta('viewport'),
+  ogTitle: meta('og:title'),
+  ogImage: meta('og:image'),
+  headings: Array.prototype.map.call(document.querySelectorAll('h1, h2, h3, h4, h5, h6'), function(h) {
+    return { level: Number(h.tagName[1]), text: h.textContent.trim().slice(0, 200) };
+  }),
+  images: images.length,
+  imagesWithoutAlt: images.filter(function(img) { return !img.hasAttribute('alt'); }).length,
+  jsonLdTypes: types,
+  microdata: !!document.querySelector('[itemscope]')
+};
+"#;
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+struct PageFacts {
+    url: String,
+    title: Option<String>,
+    description: Option<String>,
+    canonical: Option<String>,
+    robots: Option<String>,
+    lang: Option<String>,
+    viewport: Option<String>,
+    og_title: Option<String>,
+    og_image: Option<String>,
+    headings: Vec<Heading>,
+    images: usize,
+    images_without_alt: usize,
+    json_ld_types: Vec<String>,
+    microdata: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Heading {
+    level: u8,
+    text: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SeoCheck {
+    id: &'static str,
+    passed: bool,
+    severity: Severity,
+    message: String,
+    // Share of the score this check is worth
+    weight: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SeoReport {
+    url: String,
+    // 0-100, weighted share of passed checks
+    score: u32,
+    checks: Vec<SeoCheck>,
+    title: Option<String>,
+    description: Option<String>,
+    canonical: Option<String>,
+    robots: Vec<String>,
+    headings: Vec<Heading>,
+    structured_data: Vec<String>,
+}
+
+fn check(id: &'static str, passed: bool, severity: Severity, weight: u32, message: String) -> SeoCheck {
+    SeoCheck {
+        id,
+        passed,
+        severity,
+        message,
+        weight,
+    }
+}
+
+fn length_check(
+    id: &'static str,
+    label: &str,
+    value: Option<&str>,
+    (min, max): (usize, usize),
+    weight: u32,
+) -> SeoCheck {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => check(id, false, Severity::Error, weight, format!("The page has no {}", label)),
+        Some(v) => {
+            let len = v.chars().count();
+            let passed = (min..=max).contains(&len);
+            let message = if passed {
+                format!("{} length is {} characters", label, len)
+            } else {
+                format!("{} is {} characters; aim for {}-{}", label, len, min, max)
+            };
+            check(id, passed, Severity::Warning, weight, message)
+        }
+    }
+}
+
+// Skipped levels, e.g. an h4 directly after an h2
+fn heading_problems(headings: &[Heading]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut previous = 0;
+    for heading in headings {
+        if previous > 0 && heading.level > previous + 1 {
+            problems.push(format!("h{} follows h{} (\"{}\")", heading.level, previous, heading.text));
+        }
+        previous = heading.level;
+    }
+    problems
+}
+
+async fn robots_header(app: &AppHandle, url: &str) -> Option<String> {
+    let response = reqwest::Client::new()
+        .head(url)
+        .timeout(HEADER_TIMEOUT)
+        .send()
+        .await
+        .ok()?;
+    bandwidth::record(app, url, 0, 0);
+    let values: Vec<String> = response
+        .headers()
+        .get_all("x-robots-tag")
+        .iter()
+        .filter_map(|v| v.to_str().ok().map(str::to_string))
+        .collect();
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
+fn evaluate(facts: PageFacts, header: Option<String>) -> SeoReport {
+    let mut checks = vec![
+        length_check("title", "Title", facts.title.as_deref(), TITLE_LEN, 15),
+        length_check("meta_description", "Meta description", facts.description.as_deref(), DESCRIPTION_LEN, 10),
+    ];
+
+    let canonical_ok = facts
+        .canonical
+        .as_deref()
+        .and_then(|c| url::Url::parse(c).ok())
+        .is_some();
+    checks.push(check(
+        "canonical",
+        canonical_ok,
+        Severity::Warning,
+        10,
+        match &facts.canonical {
+            Some(c) if canonical_ok => format!("Canonical URL is {}", c),
+            Some(c) => format!("Canonical URL is invalid: {}", c),
+            None => "No canonical link".to_string(),
+        },
+    ));
+
+    let h1s = facts.headings.iter().filter(|h| h.level == 1).count();
+    checks.push(check(
+        "single_h1",
+        h1s == 1,
+        if h1s == 0 { Severity::Error } else { Severity::Warning },
+        10,
+        format!("The page has {} h1 heading(s)", h1s),
+    ));
+    let problems = heading_problems(&facts.headings);
+    checks.push(check(
+        "heading_order",
+        problems.is_empty(),
+        Severity::Warning,
+        5,
+        if problems.is_empty() {
+            "Heading levels are in order".to_string()
+        } else {
+            format!("Skipped heading levels: {}", problems.join("; "))
+        },
+    ));
+
+    checks.push(check(
+        "image_alt",
+        facts.images_without_alt == 0,
+        Severity::Warning,
+        10,
+        format!("{} of {} images have no alt attribute", facts.images_without_alt, facts.images),
+    ));
+
+    let structured: Vec<String> = facts.json_ld_types.iter().filter(|t| *t != "invalid").cloned().collect();
+    let invalid_json_ld = facts.json_ld_types.iter().any(|t| t == "invalid");
+    checks.push(check(
+        "structured_data",
+        (!structured.is_empty() || facts.microdata) && !invalid_json_ld,
+        if invalid_json_ld { Severity::Error } else { Severity::Info },
+        10,
+        if invalid_json_ld {
+            "A JSON-LD block could not be parsed".to_string()
+        } else if structured.is_empty() && !facts.microdata {
+            "No structured data (JSON-LD or microdata)".to_string()
+        } else {
+            format!("Structured data: {}", if structured.is_empty() { "microdata".to_string() } else { structured.join(", ") })
+        },
+    ));
+
+    let robots: Vec<String> = facts
+        .robots
+        .iter()
+        .chain(header.iter())
+        .flat_map(|r| r.split(','))
+        .map(|d| d.trim().to_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect();
+    let noindex = robots.iter().any(|d| d == "noindex" || d == "none");
+    checks.push(check(
+        "indexable",
+        !noindex,
+        Severity::Error,
+        15,
+        if noindex {
+            "Robots directives keep the page out of search results".to_string()
+        } else {
+            "The page can be indexed".to_string()
+        },
+    ));
+
+    checks.push(check(
+        "lang",
+        facts.lang.as_deref().map_or(false, |l| !l.trim().is_empty()),
+        Severity::Warning,
+        5,
+        match &facts.lang {
+            Some(lang) if !lang.trim().is_empty() => format!("Document language is {}", lang),
+            _ => "The html element has no lang attribute".to_string(),
+        },
+    ));
+    checks.push(check(
+        "viewport",
+        facts.viewport.is_some(),
+        Severity::Warning,
+        5,
+        if facts.viewport.is_some() {
+            "A viewport is set for mobile".to_string()
+        } else {
+            "No viewport meta tag; the page may not be mobile friendly".to_string()
+        },
+    ));
+    checks.push(check(
+        "open_graph",
+        facts.og_title.is_some() && facts.og_image.is_some(),
+        Severity::Info,
+        5,
+        if facts.og_title.is_some() && facts.og_image.is_some() {
+            "OpenGraph title and image are set".to_string()
+        } else {
+            "OpenGraph title or image is missing; shared links get a poor preview".to_string()
+        },
+    ));
+
+    let total: u32 = checks.iter().map(|c| c.weight).sum();
+    let passed: u32 = checks.iter().filter(|c| c.passed).map(|c| c.weight).sum();
+    SeoReport {
+        url: facts.url,
+        score: (passed * 100 + total / 2) / total.max(1),
+        checks,
+        title: facts.title,
+        description: facts.description,
+        canonical: facts.canonical,
+        robots,
+        headings: facts.headings,
+        structured_data: structured,
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn audit_seo(app_handle: AppHandle, window_id: String) -> Result<SeoReport, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    let facts = pagequery::run(&app_handle, &window, FACTS_SCRIPT, QUERY_TIMEOUT).await?;
+    let facts: PageFacts = serde_json::from_value(facts).map_err(|e| e.to_string())?;
+    let header = if facts.url.starts_with("http") {
+        robots_header(&app_handle, &facts.url).await
+    } else {
+        None
+    };
+    Ok(evaluate(facts, header))
+}
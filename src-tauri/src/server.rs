@@ -0,0 +1,319 @@
+// Bundled backend server lifecycle
+// The frontend talks to a backend at `server_url`. When nothing answers there
+// and the URL points at this machine, the bundled server is started as a
+// sidecar, watched, restarted with backoff when it crashes and killed when
+// the app exits. A server that is already running (e.g. in development) is
+// used as-is.
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::api::process::{Command, CommandChild, CommandEvent};
+use tauri::{AppHandle, Manager};
+
+use crate::i18n;
+use crate::notifications::{self, Notice, NotificationCategory};
+use crate::safemode;
+
+const SIDECAR: &str = "madeasy-server";
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const READY_POLL: Duration = Duration::from_millis(250);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+// Consecutive crashes before giving up
+const MAX_RESTARTS: u32 = 5;
+// A run at least this long resets the crash counter
+const STABLE_AFTER_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerPhase {
+    Stopped,
+    Starting,
+    Running,
+    // Someone else's server is answering at the URL
+    External,
+    Crashed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ServerStatus {
+    phase: ServerPhase,
+    url: String,
+    pid: Option<u32>,
+    restarts: u32,
+    started_at: Option<i64>,
+    last_error: Option<String>,
+}
+
+pub struct ServerState {
+    status: Mutex<ServerStatus>,
+    child: Mutex<Option<CommandChild>>,
+    // Bumped on every spawn so exits of replaced processes are ignored
+    generation: AtomicU64,
+    shutting_down: AtomicBool,
+}
+
+impl ServerState {
+    pub fn new(url: &str) -> Self {
+        Self {
+            status: Mutex::new(ServerStatus {
+                phase: ServerPhase::Stopped,
+                url: url.trim_end_matches('/').to_string(),
+                pid: None,
+                restarts: 0,
+                started_at: None,
+                last_error: None,
+            }),
+            child: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            shutting_down: AtomicBool::new(false),
+        }
+    }
+}
+
+pub fn status(app: &AppHandle) -> ServerStatus {
+    app.state::<ServerState>().status.lock().unwrap().clone()
+}
+
+pub fn url(app: &AppHandle) -> String {
+    app.state::<ServerState>().status.lock().unwrap().url.clone()
+}
+
+pub fn is_starting(app: &AppHandle) -> bool {
+    status(app).phase == ServerPhase::Starting
+}
+
+fn update(app: &AppHandle, change: impl FnOnce(&mut ServerStatus)) {
+    let status = {
+        let state = app.state::<ServerState>();
+        let mut status = state.status.lock().unwrap();
+        change(&mut status);
+        status.clone()
+    };
+    let _ = app.emit_all("server-status", status);
+}
+
+fn is_local(url: &str) -> bool {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| matches!(h, "localhost" | "127.0.0.1" | "::1" | "[::1]")))
+        .unwrap_or(false)
+}
+
+fn port_of(url: &str) -> Option<u16> {
+    url::Url::parse(url).ok()?.port_or_known_default()
+}
+
+// Any HTTP response counts; only connection failures mean "not up"
+pub async fn responds(url: &str) -> bool {
+    reqwest::Client::new()
+        .get(url)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .is_ok()
+}
+
+async fn wait_ready(url: &str) -> bool {
+    let deadline = tokio::time::Instant::now() + READY_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        if responds(url).await {
+            return true;
+        }
+        tokio::time::sleep(READY_POLL).await;
+    }
+    false
+}
+
+fn kill_child(app: &AppHandle) {
+    let state = app.state::<ServerState>();
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    if let Some(child) = state.child.lock().unwrap().take() {
+        let _ = child.kill();
+    }
+}
+
+fn spawn_sidecar(app: &AppHandle, url: &str) -> Result<u32, String> {
+    let port = port_of(url).ok_or_else(|| format!("No port in server URL: {}", url))?;
+    let mut env = HashMap::from([("PORT".to_string(), port.to_string())]);
+    // The server leaves workflow schedules paused in safe mode
+    if safemode::active(app) {
+        env.insert(safemode::SERVER_ENV.to_string(), "1".to_string());
+    }
+    let (mut events, child) = Command::new_sidecar(SIDECAR)
+        .map_err(|e| e.to_string())?
+        .args(["--port", &port.to_string()])
+        .envs(env)
+        .spawn()
+        .map_err(|e| format!("Failed to start the server: {}", e))?;
+    let pid = child.pid();
+
+    let state = app.state::<ServerState>();
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    *state.child.lock().unwrap() = Some(child);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = events.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => println!("[server] {}", line.trim_end()),
+                CommandEvent::Stderr(line) => eprintln!("[server] {}", line.trim_end()),
+                CommandEvent::Terminated(payload) => {
+                    on_exit(&app, generation, payload.code);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+    Ok(pid)
+}
+
+// Restart a crashed server with exponential backoff, up to MAX_RESTARTS in a row
+fn on_exit(app: &AppHandle, generation: u64, code: Option<i32>) {
+    let state = app.state::<ServerState>();
+    if state.shutting_down.load(Ordering::SeqCst) || state.generation.load(Ordering::SeqCst) != generation {
+        return;
+    }
+    state.child.lock().unwrap().take();
+
+    let now = chrono::Utc::now().timestamp();
+    let mut restarts = 0;
+    update(app, |status| {
+        let stable = status.started_at.map_or(false, |t| now - t >= STABLE_AFTER_SECS);
+        status.restarts = if stable { 1 } else { status.restarts + 1 };
+        restarts = status.restarts;
+        status.phase = ServerPhase::Crashed;
+        status.pid = None;
+        status.last_error = Some(match code {
+            Some(code) => format!("Server exited with code {}", code),
+            None => "Server was terminated".to_string(),
+        });
+    });
+
+    if restarts > MAX_RESTARTS {
+        update(app, |status| status.phase = ServerPhase::Failed);
+        let _ = notifications::notify(
+            app,
+            Notice::new(
+                NotificationCategory::System,
+                i18n::text(app, "notice-server-stopped-title"),
+                i18n::text(app, "notice-server-stopped-body"),
+            )
+            .owned_by("server"),
+        );
+        return;
+    }
+    let delay = Duration::from_secs(1 << (restarts - 1).min(5));
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        launch(&app).await;
+    });
+}
+
+// Use a running server at the URL or start the bundled one and wait for it
+pub async fn launch(app: &AppHandle) {
+    let url = status(app).url;
+    update(app, |status| {
+        status.phase = ServerPhase::Starting;
+        status.last_error = None;
+    });
+
+    if responds(&url).await {
+        update(app, |status| status.phase = ServerPhase::External);
+        return;
+    }
+    if !is_local(&url) {
+        update(app, |status| {
+            status.phase = ServerPhase::Failed;
+            status.last_error = Some(format!("{} is not reachable", url));
+        });
+        return;
+    }
+
+    let pid = match spawn_sidecar(app, &url) {
+        Ok(pid) => pid,
+        Err(e) => {
+            update(app, |status| {
+                status.phase = ServerPhase::Failed;
+                status.last_error = Some(e);
+            });
+            return;
+        }
+    };
+    update(app, |status| status.pid = Some(pid));
+
+    if wait_ready(&url).await {
+        update(app, |status| {
+            status.phase = ServerPhase::Running;
+            status.started_at = Some(chrono::Utc::now().timestamp());
+        });
+    } else {
+        kill_child(app);
+        update(app, |status| {
+            status.phase = ServerPhase::Failed;
+            status.pid = None;
+            status.last_error = Some("Server did not become ready in time".to_string());
+        });
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { launch(&app).await });
+}
+
+// Point at a different server (environment switch). A sidecar started for
+// the old URL is stopped; the new URL gets the usual launch treatment.
+pub fn set_url(app: &AppHandle, url: &str) {
+    kill_child(app);
+    update(app, |status| {
+        status.phase = ServerPhase::Stopped;
+        status.url = url.trim_end_matches('/').to_string();
+        status.pid = None;
+        status.restarts = 0;
+        status.started_at = None;
+        status.last_error = None;
+    });
+    start(app);
+}
+
+// Stop the sidecar when the app exits
+pub fn shutdown(app: &AppHandle) {
+    app.state::<ServerState>().shutting_down.store(true, Ordering::SeqCst);
+    kill_child(app);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn server_status(app_handle: AppHandle) -> Result<ServerStatus, String> {
+    Ok(status(&app_handle))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn restart_server(app_handle: AppHandle) -> Result<ServerStatus, String> {
+    let managed = app_handle.state::<ServerState>().child.lock().unwrap().is_some();
+    kill_child(&app_handle);
+    // Give the old process time to release the port
+    let url = status(&app_handle).url;
+    for _ in 0..20 {
+        if !managed || !responds(&url).await {
+            break;
+        }
+        tokio::time::sleep(READY_POLL).await;
+    }
+    update(&app_handle, |status| {
+        status.phase = ServerPhase::Stopped;
+        status.pid = None;
+        status.restarts = 0;
+        status.started_at = None;
+    });
+    launch(&app_handle).await;
+    Ok(status(&app_handle))
+}
@@ -0,0 +1,290 @@
+// Native share sheet
+// Pages, files (screenshots, exported reports) and text are handed to the
+// Windows Share UI or the macOS sharing service picker, anchored to a window.
+// The File menu shares the current page. Notifications created with owner
+// OWNER and a BUTTON_SHARE button (e.g. workflow completions from the
+// frontend) share the run's saved artifacts, the linked URL or the body text.
+// Linux has no system share sheet.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::notifications::{self, ButtonPress, NotificationAction};
+use crate::{artifacts, i18n, kiosk, pagequery};
+
+// Notifications owned by this module get BUTTON_SHARE handled here
+pub const OWNER: &str = "share";
+pub const BUTTON_SHARE: &str = "share";
+pub const MENU_ITEM_ID: &str = "share_page";
+const PAGE_SCRIPT: &str = "return { title: document.title, url: location.href };";
+const PAGE_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_TEXT_CHARS: usize = 100_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShareContent {
+    Url { url: String, title: Option<String> },
+    File { path: String },
+    Text { text: String, title: Option<String> },
+}
+
+// What the platform share sheet is given
+pub struct Share {
+    title: String,
+    url: Option<String>,
+    text: Option<String>,
+    files: Vec<PathBuf>,
+}
+
+impl Share {
+    fn files(title: String, files: Vec<PathBuf>) -> Self {
+        Self {
+            title,
+            url: None,
+            text: None,
+            files,
+        }
+    }
+}
+
+fn prepare(content: ShareContent) -> Result<Share, String> {
+    match content {
+        ShareContent::Url { url, title } => {
+            let parsed = url::Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+            if !matches!(parsed.scheme(), "http" | "https" | "mailto") {
+                return Err(format!("{} links can't be shared", parsed.scheme()));
+            }
+            Ok(Share {
+                title: title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| url.clone()),
+                url: Some(url),
+                text: None,
+                files: Vec::new(),
+            })
+        }
+        ShareContent::File { path } => {
+            let path = PathBuf::from(path);
+            if !path.is_file() {
+                return Err(format!("Not a file: {}", path.display()));
+            }
+            let title = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            Ok(Share::files(title, vec![path]))
+        }
+        ShareContent::Text { text, title } => {
+            if text.trim().is_empty() {
+                return Err("Nothing to share".to_string());
+            }
+            if text.chars().count() > MAX_TEXT_CHARS {
+                return Err(format!("Text is longer than {} characters", MAX_TEXT_CHARS));
+            }
+            Ok(Share {
+                title: title.unwrap_or_default(),
+                url: None,
+                text: Some(text),
+                files: Vec::new(),
+            })
+        }
+    }
+}
+
+// The share sheet needs a visible window to attach to
+fn anchor(app: &AppHandle, window_id: Option<&str>) -> Result<Window, String> {
+    let window = match window_id {
+        Some(id) => app.get_window(id).ok_or_else(|| format!("Window not found: {}", id))?,
+        None => app
+            .windows()
+            .into_values()
+            .find(|w| w.is_focused().unwrap_or(false))
+            .or_else(|| app.get_window("main"))
+            .ok_or("No window to share from")?,
+    };
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    Ok(window)
+}
+
+pub fn share(app: &AppHandle, window_id: Option<&str>, share: Share) -> Result<(), String> {
+    kiosk::ensure_inactive(app)?;
+    let window = anchor(app, window_id)?;
+    platform::show(app, &window, share)
+}
+
+// Share the page shown in `window`; used by the window menu
+pub async fn share_page(app: AppHandle, window: Window) -> Result<(), String> {
+    let page = pagequery::run(&app, &window, PAGE_SCRIPT, PAGE_TIMEOUT).await?;
+    let text = |key: &str| page.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let content = ShareContent::Url {
+        url: text("url").unwrap_or_default(),
+        title: text("title"),
+    };
+    share(&app, Some(window.label()), prepare(content)?)
+}
+
+// BUTTON_SHARE on a notification owned by OWNER
+fn on_button(app: &AppHandle, press: &ButtonPress) {
+    if press.button_id != BUTTON_SHARE {
+        return;
+    }
+    let title = press.notification.title.clone();
+    let result = match press.notification.action.clone() {
+        Some(NotificationAction::OpenWorkflowRun { run_id }) => {
+            artifacts::run_paths(app, &run_id).and_then(|files| {
+                if files.is_empty() {
+                    return Err(i18n::text(app, "share-no-artifacts"));
+                }
+                share(app, None, Share::files(title, files))
+            })
+        }
+        Some(NotificationAction::OpenUrl { url }) => {
+            prepare(ShareContent::Url { url, title: Some(title) }).and_then(|s| share(app, None, s))
+        }
+        _ => prepare(ShareContent::Text {
+            text: press.notification.body.clone(),
+            title: Some(title),
+        })
+        .and_then(|s| share(app, None, s)),
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to share from notification: {}", e);
+    }
+}
+
+// Called once the notification handlers are managed
+pub fn register(app: &AppHandle) {
+    notifications::register_button_handler(app, OWNER, on_button);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn share_content(
+    app_handle: AppHandle,
+    window_id: Option<String>,
+    content: ShareContent,
+) -> Result<(), String> {
+    share(&app_handle, window_id.as_deref(), prepare(content)?)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::Share;
+    use std::sync::Mutex;
+    use tauri::{AppHandle, Window};
+    use windows::core::{factory, Interface, HSTRING};
+    use windows::ApplicationModel::DataTransfer::{DataRequestedEventArgs, DataTransferManager};
+    use windows::Foundation::Collections::IIterable;
+    use windows::Foundation::{TypedEventHandler, Uri};
+    use windows::Storage::{IStorageItem, StorageFile};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::IDataTransferManagerInterop;
+
+    // Window handle -> DataRequested registration of the last share from it
+    static HANDLERS: Mutex<Vec<(isize, i64)>> = Mutex::new(Vec::new());
+
+    pub fn show(app: &AppHandle, window: &Window, share: Share) -> Result<(), String> {
+        let hwnd = window.hwnd().map_err(|e| e.to_string())?.0 as isize;
+        // Resolved here rather than in DataRequested, which must answer quickly
+        let files = share
+            .files
+            .iter()
+            .map(|path| {
+                StorageFile::GetFileFromPathAsync(&HSTRING::from(path.as_os_str()))?
+                    .get()?
+                    .cast::<IStorageItem>()
+            })
+            .collect::<windows::core::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?;
+
+        app.run_on_main_thread(move || unsafe {
+            let result = (|| -> windows::core::Result<()> {
+                let handle = HWND(hwnd as _);
+                let interop = factory::<DataTransferManager, IDataTransferManagerInterop>()?;
+                let manager: DataTransferManager = interop.GetForWindow(handle)?;
+                let mut handlers = HANDLERS.lock().unwrap();
+                if let Some(index) = handlers.iter().position(|(h, _)| *h == hwnd) {
+                    let (_, token) = handlers.remove(index);
+                    // Stale if the handle now belongs to another window
+                    let _ = manager.RemoveDataRequested(token);
+                }
+                let token = manager.DataRequested(&TypedEventHandler::new(
+                    move |_, args: &Option<DataRequestedEventArgs>| {
+                        let Some(args) = args else { return Ok(()) };
+                        let data = args.Request()?.Data()?;
+                        data.Properties()?.SetTitle(&HSTRING::from(share.title.as_str()))?;
+                        if let Some(url) = &share.url {
+                            data.SetWebLink(&Uri::CreateUri(&HSTRING::from(url.as_str()))?)?;
+                        }
+                        if let Some(text) = &share.text {
+                            data.SetText(&HSTRING::from(text.as_str()))?;
+                        }
+                        if !files.is_empty() {
+                            let items = IIterable::<IStorageItem>::try_from(
+                                files.iter().cloned().map(Some).collect::<Vec<_>>(),
+                            )?;
+                            data.SetStorageItems(&items, true)?;
+                        }
+                        Ok(())
+                    },
+                ))?;
+                handlers.push((hwnd, token));
+                interop.ShowShareUIForWindow(handle)
+            })();
+            if let Err(e) = result {
+                eprintln!("Failed to show the share sheet: {}", e);
+            }
+        })
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Share;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::{NSArray, NSPoint, NSRect, NSSize, NSString};
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::{AppHandle, Window};
+
+    // NSMinYEdge: below the anchor rect
+    const PREFERRED_EDGE: u64 = 1;
+
+    // NSSharingServicePicker anchored to the top of the web view
+    pub fn show(_app: &AppHandle, window: &Window, share: Share) -> Result<(), String> {
+        window
+            .with_webview(move |webview| unsafe {
+                let view = webview.inner() as id;
+                let mut items: Vec<id> = Vec::new();
+                if let Some(url) = &share.url {
+                    let url: id = msg_send![class!(NSURL), URLWithString: NSString::alloc(nil).init_str(url)];
+                    if url != nil {
+                        items.push(url);
+                    }
+                }
+                if let Some(text) = &share.text {
+                    items.push(NSString::alloc(nil).init_str(text));
+                }
+                for path in &share.files {
+                    let path = NSString::alloc(nil).init_str(&path.to_string_lossy());
+                    items.push(msg_send![class!(NSURL), fileURLWithPath: path]);
+                }
+                let items = NSArray::arrayWithObjects(nil, &items);
+                let picker: id = msg_send![class!(NSSharingServicePicker), alloc];
+                let picker: id = msg_send![picker, initWithItems: items];
+                let bounds: NSRect = msg_send![view, bounds];
+                let anchor = NSRect::new(NSPoint::new(bounds.size.width / 2.0, 0.0), NSSize::new(1.0, 1.0));
+                let _: () = msg_send![picker, showRelativeToRect: anchor ofView: view preferredEdge: PREFERRED_EDGE];
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::Share;
+    use tauri::{AppHandle, Window};
+
+    pub fn show(_app: &AppHandle, _window: &Window, _share: Share) -> Result<(), String> {
+        Err("Sharing is not supported on this platform".to_string())
+    }
+}
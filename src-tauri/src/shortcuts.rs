@@ -0,0 +1,226 @@
+// User-definable global keyboard shortcuts
+// Bindings are persisted in the app data directory and registered with the OS on startup
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, GlobalShortcutManager, Manager};
+
+use crate::closedwindows::{self, CycleDirection};
+use crate::{idle, kiosk, storage};
+
+const SHORTCUTS_FILE: &str = "shortcuts.json";
+
+// Accelerators of the application menu (see `create_menu`); global bindings
+// would shadow them in every window
+pub const MENU_REOPEN_CLOSED_WINDOW: &str = "CmdOrCtrl+Shift+T";
+pub const MENU_NEXT_WINDOW: &str = "CmdOrCtrl+Alt+Right";
+pub const MENU_PREVIOUS_WINDOW: &str = "CmdOrCtrl+Alt+Left";
+const MENU_ACCELERATORS: [&str; 3] = [MENU_REOPEN_CLOSED_WINDOW, MENU_NEXT_WINDOW, MENU_PREVIOUS_WINDOW];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    NewWindow,
+    Screenshot,
+    ToggleTray,
+    SummonAi,
+    ReopenClosedWindow,
+    NextWindow,
+    PreviousWindow,
+}
+
+impl ShortcutAction {
+    const ALL: [ShortcutAction; 7] = [
+        ShortcutAction::NewWindow,
+        ShortcutAction::Screenshot,
+        ShortcutAction::ToggleTray,
+        ShortcutAction::SummonAi,
+        ShortcutAction::ReopenClosedWindow,
+        ShortcutAction::NextWindow,
+        ShortcutAction::PreviousWindow,
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ShortcutBinding {
+    action: ShortcutAction,
+    accelerator: Option<String>,
+    registered: bool,
+}
+
+#[derive(Default)]
+pub struct ShortcutState {
+    bindings: Mutex<BTreeMap<ShortcutAction, Option<String>>>,
+}
+
+impl ShortcutState {
+    pub fn load(app: &AppHandle) -> Self {
+        let mut bindings: BTreeMap<ShortcutAction, Option<String>> =
+            storage::load(app, SHORTCUTS_FILE);
+        // Global shortcuts take their keys from every other application, so
+        // none is bound until the user picks one
+        for action in ShortcutAction::ALL {
+            bindings.entry(action).or_insert(None);
+        }
+        Self {
+            bindings: Mutex::new(bindings),
+        }
+    }
+}
+
+// Canonical form of an accelerator so "shift+ctrl+a" and "Ctrl+Shift+A" compare equal
+fn normalize(accelerator: &str) -> String {
+    let mut parts: Vec<String> = accelerator
+        .split('+')
+        .map(|p| p.trim().to_uppercase())
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.as_str() {
+            "CONTROL" => "CTRL".to_string(),
+            "COMMAND" | "SUPER" => "CMD".to_string(),
+            "CMDORCTRL" | "COMMANDORCONTROL" if cfg!(target_os = "macos") => "CMD".to_string(),
+            "CMDORCTRL" | "COMMANDORCONTROL" => "CTRL".to_string(),
+            "OPTION" => "ALT".to_string(),
+            _ => p,
+        })
+        .collect();
+    let key = parts.pop().unwrap_or_default();
+    parts.sort();
+    parts.push(key);
+    parts.join("+")
+}
+
+// Run the action bound to a shortcut. Global shortcuts stay registered in
+// kiosk mode and while the app is locked but do nothing, since most of them
+// reveal other windows.
+fn trigger(app: &AppHandle, action: ShortcutAction) {
+    if kiosk::active(app) || idle::locked(app) {
+        return;
+    }
+    match action {
+        ShortcutAction::NewWindow => {
+            tauri::async_runtime::spawn(crate::create_new_window(app.clone(), None, None));
+        }
+        ShortcutAction::ReopenClosedWindow => {
+            if let Err(e) = closedwindows::reopen(app) {
+                eprintln!("{}", e);
+            }
+        }
+        ShortcutAction::NextWindow | ShortcutAction::PreviousWindow => {
+            let direction = if action == ShortcutAction::NextWindow {
+                CycleDirection::Next
+            } else {
+                CycleDirection::Previous
+            };
+            if let Err(e) = closedwindows::cycle(app, direction) {
+                eprintln!("{}", e);
+            }
+        }
+        ShortcutAction::ToggleTray => {
+            if let Some(window) = app.get_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+        }
+        // Screenshot and AI panel are handled by the frontend
+        ShortcutAction::Screenshot | ShortcutAction::SummonAi => {
+            if let Some(window) = app.get_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit_all("shortcut-triggered", action);
+        }
+    }
+}
+
+fn register(app: &AppHandle, action: ShortcutAction, accelerator: &str) -> Result<(), String> {
+    let handle = app.clone();
+    app.global_shortcut_manager()
+        .register(accelerator, move || trigger(&handle, action))
+        .map_err(|e| format!("Could not register {}: {}", accelerator, e))
+}
+
+// Register all persisted shortcuts with the OS
+pub fn register_all(app: &AppHandle) {
+    let bindings = app.state::<ShortcutState>().bindings.lock().unwrap().clone();
+    for (action, accelerator) in bindings {
+        if let Some(accelerator) = accelerator {
+            if let Err(e) = register(app, action, &accelerator) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_shortcuts(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ShortcutState>,
+) -> Result<Vec<ShortcutBinding>, String> {
+    let manager = app_handle.global_shortcut_manager();
+    let bindings = state.bindings.lock().unwrap();
+
+    Ok(bindings
+        .iter()
+        .map(|(action, accelerator)| ShortcutBinding {
+            action: *action,
+            accelerator: accelerator.clone(),
+            registered: accelerator
+                .as_deref()
+                .map_or(false, |a| manager.is_registered(a).unwrap_or(false)),
+        })
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_shortcut(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ShortcutState>,
+    action: ShortcutAction,
+    accelerator: Option<String>,
+) -> Result<(), String> {
+    let previous = {
+        let bindings = state.bindings.lock().unwrap();
+
+        // Conflict with another action's binding
+        if let Some(new) = accelerator.as_deref() {
+            let normalized = normalize(new);
+            if let Some((other, _)) = bindings.iter().find(|(other, existing)| {
+                **other != action && existing.as_deref().map(normalize) == Some(normalized.clone())
+            }) {
+                return Err(format!("{} is already bound to {:?}", new, other));
+            }
+            if MENU_ACCELERATORS.iter().any(|menu| normalize(menu) == normalized) {
+                return Err(format!("{} is already used by the application menu", new));
+            }
+        }
+
+        bindings.get(&action).cloned().flatten()
+    };
+
+    let mut manager = app_handle.global_shortcut_manager();
+    if let Some(old) = previous.as_deref() {
+        manager.unregister(old).map_err(|e| e.to_string())?;
+    }
+
+    // Conflict with a shortcut owned by another application: restore the old binding
+    if let Some(new) = accelerator.as_deref() {
+        if let Err(e) = register(&app_handle, action, new) {
+            if let Some(old) = previous.as_deref() {
+                let _ = register(&app_handle, action, old);
+            }
+            return Err(e);
+        }
+    }
+
+    let mut bindings = state.bindings.lock().unwrap();
+    bindings.insert(action, accelerator);
+    storage::save(&app_handle, SHORTCUTS_FILE, &*bindings)
+}
@@ -0,0 +1,74 @@
+// Graceful shutdown
+// Quitting from the tray, the menu or the frontend goes through here instead
+// of exiting on the spot: windows are told with a `shutdown-started` event so
+// the frontend can save its session, registered tasks (workflow runs, crawls)
+// get a grace period to finish before they are cancelled, then window
+// placements and the database are flushed and the managed server is stopped.
+// A forced shutdown skips the wait but still flushes.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::{kiosk, monitors, safemode, server, tasks};
+
+// How long running tasks may take to finish on their own
+const GRACE_PERIOD: Duration = Duration::from_secs(10);
+// After cancelling, how long to wait for tasks to unwind
+const CANCEL_PERIOD: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Default)]
+pub struct ShutdownState {
+    started: AtomicBool,
+}
+
+async fn wait_for_tasks(app: &AppHandle, limit: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + limit;
+    while tasks::running(app) > 0 {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    true
+}
+
+fn persist(app: &AppHandle) {
+    monitors::save_placements(app);
+    if let Some(db) = app.try_state::<Database>() {
+        if let Err(e) = db.checkpoint() {
+            eprintln!("Failed to checkpoint database: {}", e);
+        }
+    }
+    server::shutdown(app);
+    safemode::on_clean_exit(app);
+}
+
+async fn run(app: AppHandle, force: bool) {
+    let _ = app.emit_all("shutdown-started", force);
+    if !force && !wait_for_tasks(&app, GRACE_PERIOD).await {
+        eprintln!("{} task(s) still running at shutdown; cancelling", tasks::running(&app));
+    }
+    tasks::cancel_all(&app);
+    wait_for_tasks(&app, CANCEL_PERIOD).await;
+    tasks::block_in_place(|| persist(&app));
+    app.exit(0);
+}
+
+// Starts the shutdown sequence; later requests while it runs are ignored
+pub fn request(app: &AppHandle, force: bool) {
+    if app.state::<ShutdownState>().started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tauri::async_runtime::spawn(run(app.clone(), force));
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn shutdown(app_handle: AppHandle, force: bool) -> Result<(), String> {
+    kiosk::ensure_inactive(&app_handle)?;
+    request(&app_handle, force);
+    Ok(())
+}
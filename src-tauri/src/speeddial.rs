@@ -0,0 +1,213 @@
+// Speed dial / new-tab page data
+// Pinned tiles come first in the user's order; remaining slots are filled with
+// the most-visited sites from history (by frecency).
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::db::Database;
+use crate::{history, storage};
+
+const SPEED_DIAL_FILE: &str = "speeddial.json";
+const BACKGROUND_FILE: &str = "speeddial-background";
+const MAX_BACKGROUND_BYTES: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PinnedTile {
+    url: String,
+    title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Background {
+    None,
+    Color { color: String },
+    // Image copied into the app data directory
+    Image { mime: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct SpeedDialConfig {
+    pinned: Vec<PinnedTile>,
+    // Most-visited sites the user removed from the page
+    hidden: Vec<String>,
+    background: Background,
+    max_tiles: usize,
+}
+
+impl Default for SpeedDialConfig {
+    fn default() -> Self {
+        Self {
+            pinned: Vec::new(),
+            hidden: Vec::new(),
+            background: Background::None,
+            max_tiles: 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Tile {
+    url: String,
+    title: String,
+    pinned: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SpeedDial {
+    tiles: Vec<Tile>,
+    background: Background,
+    // Data URL for image backgrounds
+    background_image: Option<String>,
+    config: SpeedDialConfig,
+}
+
+#[derive(Default)]
+pub struct SpeedDialState {
+    config: Mutex<SpeedDialConfig>,
+}
+
+impl SpeedDialState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, SPEED_DIAL_FILE)),
+        }
+    }
+}
+
+fn mime_for(path: &Path) -> Result<&'static str, String> {
+    match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+        Some("png") => Ok("image/png"),
+        Some("jpg") | Some("jpeg") => Ok("image/jpeg"),
+        Some("webp") => Ok("image/webp"),
+        Some("gif") => Ok("image/gif"),
+        _ => Err("Background must be a PNG, JPEG, WebP or GIF image".to_string()),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_speed_dial(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    state: tauri::State<'_, SpeedDialState>,
+) -> Result<SpeedDial, String> {
+    let config = state.config.lock().unwrap().clone();
+
+    let mut seen: HashSet<String> = config.hidden.iter().cloned().collect();
+    let mut tiles: Vec<Tile> = config
+        .pinned
+        .iter()
+        .filter(|tile| seen.insert(tile.url.clone()))
+        .map(|tile| Tile {
+            url: tile.url.clone(),
+            title: tile.title.clone(),
+            pinned: true,
+        })
+        .collect();
+
+    if tiles.len() < config.max_tiles {
+        // Over-fetch to make up for hidden and already pinned entries
+        let candidates = history::frecency_ranked(&db, None, (config.max_tiles * 3) as u32)?;
+        for entry in candidates {
+            if tiles.len() >= config.max_tiles {
+                break;
+            }
+            if seen.insert(entry.url.clone()) {
+                tiles.push(Tile {
+                    title: if entry.title.is_empty() { entry.url.clone() } else { entry.title },
+                    url: entry.url,
+                    pinned: false,
+                });
+            }
+        }
+    }
+
+    let background_image = match &config.background {
+        Background::Image { mime } => std::fs::read(storage::data_path(&app_handle, BACKGROUND_FILE)?)
+            .ok()
+            .map(|bytes| {
+                format!(
+                    "data:{};base64,{}",
+                    mime,
+                    base64::engine::general_purpose::STANDARD.encode(bytes)
+                )
+            }),
+        _ => None,
+    };
+
+    Ok(SpeedDial {
+        tiles,
+        background: config.background.clone(),
+        background_image,
+        config,
+    })
+}
+
+// Replace the pinned tiles (in display order), hidden sites and settings.
+// Image backgrounds are set separately with `set_speed_dial_background`.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_speed_dial(
+    app_handle: AppHandle,
+    state: tauri::State<'_, SpeedDialState>,
+    config: SpeedDialConfig,
+) -> Result<(), String> {
+    if config.max_tiles == 0 || config.max_tiles > 48 {
+        return Err("max_tiles must be between 1 and 48".to_string());
+    }
+    if let Some(tile) = config
+        .pinned
+        .iter()
+        .find(|t| reqwest::Url::parse(&t.url).is_err())
+    {
+        return Err(format!("Invalid tile URL: {}", tile.url));
+    }
+
+    let mut current = state.config.lock().unwrap();
+    let background = match (&config.background, &current.background) {
+        // Keep the stored image; the frontend can't supply its contents here
+        (Background::Image { .. }, existing @ Background::Image { .. }) => existing.clone(),
+        (Background::Image { .. }, _) => {
+            return Err("Use set_speed_dial_background to set an image".to_string())
+        }
+        (other, _) => other.clone(),
+    };
+    let config = SpeedDialConfig { background, ..config };
+
+    storage::save(&app_handle, SPEED_DIAL_FILE, &config)?;
+    *current = config;
+    Ok(())
+}
+
+// Copy an image into the app data directory and use it as the background
+#[tauri::command]
+#[specta::specta]
+pub async fn set_speed_dial_background(
+    app_handle: AppHandle,
+    state: tauri::State<'_, SpeedDialState>,
+    path: String,
+) -> Result<(), String> {
+    let path = Path::new(&path);
+    let mime = mime_for(path)?;
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_BACKGROUND_BYTES {
+        return Err("Background image is larger than 10 MB".to_string());
+    }
+
+    std::fs::copy(path, storage::data_path(&app_handle, BACKGROUND_FILE)?)
+        .map_err(|e| e.to_string())?;
+
+    let mut config = state.config.lock().unwrap();
+    config.background = Background::Image {
+        mime: mime.to_string(),
+    };
+    storage::save(&app_handle, SPEED_DIAL_FILE, &*config)
+}
@@ -0,0 +1,309 @@
+// Spellcheck languages and custom dictionary, per profile
+// Each webview engine uses a different spellchecker: WebView2 uses the Windows
+// spell checking service, WKWebView uses NSSpellChecker and WebKitGTK uses Enchant.
+// Custom words are added to the matching user dictionary.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{profiles, storage};
+
+const CONFIG_FILE: &str = "spellcheck.json";
+const DICTIONARY_FILE: &str = "custom-dictionary.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct SpellcheckConfig {
+    enabled: bool,
+    // BCP 47 tags, e.g. "en-US", "nb-NO"
+    languages: Vec<String>,
+}
+
+impl Default for SpellcheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            languages: vec!["en-US".to_string()],
+        }
+    }
+}
+
+fn load_config(app: &AppHandle, profile_id: &str) -> SpellcheckConfig {
+    storage::load(app, &profiles::profile_file(profile_id, CONFIG_FILE))
+}
+
+fn load_dictionary(app: &AppHandle, profile_id: &str) -> Vec<String> {
+    storage::load(app, &profiles::profile_file(profile_id, DICTIONARY_FILE))
+}
+
+// Enable or disable spellchecking of editable fields on the page
+pub fn inject(window: &Window) {
+    let config = load_config(&window.app_handle(), &profiles::active_profile(&window.app_handle()));
+    let _ = window.eval(&format!(
+        "document.documentElement && (document.documentElement.spellcheck = {});",
+        config.enabled
+    ));
+}
+
+// Push the active profile's settings to the engine and all open windows
+pub fn apply(app: &AppHandle) {
+    let profile = profiles::active_profile(app);
+    let config = load_config(app, &profile);
+    for window in app.windows().values() {
+        if let Err(e) = platform::set_languages(window, config.enabled, &config.languages) {
+            eprintln!("Failed to apply spellcheck languages: {}", e);
+        }
+        inject(window);
+    }
+    for word in load_dictionary(app, &profile) {
+        let _ = platform::add_word(&config.languages, &word);
+    }
+}
+
+fn valid_language(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= 35
+        && tag.split('-').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_spellcheck_config(
+    app_handle: AppHandle,
+    profile_id: Option<String>,
+) -> Result<SpellcheckConfig, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    Ok(load_config(&app_handle, &profile))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_spellcheck_config(
+    app_handle: AppHandle,
+    config: SpellcheckConfig,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(tag) = config.languages.iter().find(|tag| !valid_language(tag)) {
+        return Err(format!("Invalid language tag: {}", tag));
+    }
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    storage::save(&app_handle, &profiles::profile_file(&profile, CONFIG_FILE), &config)?;
+    if profile == profiles::active_profile(&app_handle) {
+        apply(&app_handle);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_dictionary_words(app_handle: AppHandle, profile_id: Option<String>) -> Result<Vec<String>, String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    Ok(load_dictionary(&app_handle, &profile))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn add_dictionary_word(
+    app_handle: AppHandle,
+    word: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let word = word.trim().to_string();
+    if word.is_empty() || word.contains(char::is_whitespace) {
+        return Err("Dictionary entries must be a single word".to_string());
+    }
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut words = load_dictionary(&app_handle, &profile);
+    if words.contains(&word) {
+        return Ok(());
+    }
+    words.push(word.clone());
+    words.sort_by_key(|w| w.to_lowercase());
+    storage::save(&app_handle, &profiles::profile_file(&profile, DICTIONARY_FILE), &words)?;
+
+    if profile == profiles::active_profile(&app_handle) {
+        platform::add_word(&load_config(&app_handle, &profile).languages, &word)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_dictionary_word(
+    app_handle: AppHandle,
+    word: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let profile = profiles::resolve(&app_handle, profile_id)?;
+    let mut words = load_dictionary(&app_handle, &profile);
+    words.retain(|w| w != &word);
+    storage::save(&app_handle, &profiles::profile_file(&profile, DICTIONARY_FILE), &words)?;
+    platform::remove_word(&load_config(&app_handle, &profile).languages, &word)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::Window;
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::Globalization::{ISpellChecker, ISpellChecker2, ISpellCheckerFactory, SpellCheckerFactory};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+
+    // WebView2 checks in the user's Windows input languages; there is no
+    // per-webview language setting to change at runtime
+    pub fn set_languages(_window: &Window, _enabled: bool, _languages: &[String]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn checkers(languages: &[String]) -> Result<Vec<ISpellChecker>, String> {
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            let factory: ISpellCheckerFactory =
+                CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_INPROC_SERVER).map_err(|e| e.to_string())?;
+            let mut checkers = Vec::new();
+            for language in languages {
+                let tag = HSTRING::from(language.as_str());
+                if factory.IsSupported(&tag).map(|s| s.as_bool()).unwrap_or(false) {
+                    checkers.push(factory.CreateSpellChecker(&tag).map_err(|e| e.to_string())?);
+                }
+            }
+            Ok(checkers)
+        }
+    }
+
+    pub fn add_word(languages: &[String], word: &str) -> Result<(), String> {
+        for checker in checkers(languages)? {
+            unsafe { checker.Add(&HSTRING::from(word)) }.map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_word(languages: &[String], word: &str) -> Result<(), String> {
+        for checker in checkers(languages)? {
+            let checker: ISpellChecker2 = checker.cast().map_err(|e| e.to_string())?;
+            unsafe { checker.Remove(&HSTRING::from(word)) }.map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use cocoa::base::{id, nil, YES};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::Window;
+
+    // NSSpellChecker is app-wide; with several languages let it detect the language
+    pub fn set_languages(_window: &Window, _enabled: bool, languages: &[String]) -> Result<(), String> {
+        unsafe {
+            let checker: id = msg_send![class!(NSSpellChecker), sharedSpellChecker];
+            if let [language] = languages {
+                let language = NSString::alloc(nil).init_str(&language.replace('-', "_"));
+                let _: bool = msg_send![checker, setLanguage: language];
+            } else {
+                let _: () = msg_send![checker, setAutomaticallyIdentifiesLanguages: YES];
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_word(_languages: &[String], word: &str) -> Result<(), String> {
+        unsafe {
+            let checker: id = msg_send![class!(NSSpellChecker), sharedSpellChecker];
+            let word = NSString::alloc(nil).init_str(word);
+            let _: () = msg_send![checker, learnWord: word];
+        }
+        Ok(())
+    }
+
+    pub fn remove_word(_languages: &[String], word: &str) -> Result<(), String> {
+        unsafe {
+            let checker: id = msg_send![class!(NSSpellChecker), sharedSpellChecker];
+            let word = NSString::alloc(nil).init_str(word);
+            let _: () = msg_send![checker, unlearnWord: word];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::PathBuf;
+    use tauri::Window;
+    use webkit2gtk::{WebContextExt, WebViewExt};
+
+    fn enchant_tag(language: &str) -> String {
+        language.replace('-', "_")
+    }
+
+    pub fn set_languages(window: &Window, enabled: bool, languages: &[String]) -> Result<(), String> {
+        let languages: Vec<String> = languages.iter().map(|l| enchant_tag(l)).collect();
+        window
+            .with_webview(move |webview| {
+                if let Some(context) = webview.inner().context() {
+                    let tags: Vec<&str> = languages.iter().map(String::as_str).collect();
+                    context.set_spell_checking_enabled(enabled);
+                    context.set_spell_checking_languages(&tags);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    // Enchant's personal word lists: $XDG_CONFIG_HOME/enchant/<lang>.dic
+    fn word_list(language: &str) -> Option<PathBuf> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config.join("enchant").join(format!("{}.dic", enchant_tag(language))))
+    }
+
+    fn update_word_list(languages: &[String], edit: impl Fn(&mut Vec<String>)) -> Result<(), String> {
+        for path in languages.iter().filter_map(|l| word_list(l)) {
+            let mut words: Vec<String> = std::fs::read_to_string(&path)
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string)
+                .collect();
+            edit(&mut words);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            let mut contents = words.join("\n");
+            contents.push('\n');
+            std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    pub fn add_word(languages: &[String], word: &str) -> Result<(), String> {
+        update_word_list(languages, |words| {
+            if !words.iter().any(|w| w == word) {
+                words.push(word.to_string());
+            }
+        })
+    }
+
+    pub fn remove_word(languages: &[String], word: &str) -> Result<(), String> {
+        update_word_list(languages, |words| words.retain(|w| w != word))
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use tauri::Window;
+
+    pub fn set_languages(_window: &Window, _enabled: bool, _languages: &[String]) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn add_word(_languages: &[String], _word: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn remove_word(_languages: &[String], _word: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
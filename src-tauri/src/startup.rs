@@ -0,0 +1,143 @@
+// Startup phases and timings
+// `setup_app` only does what the first window needs; heavier work (opening the
+// database, compiling plugins, verifying the audit log, the jump list) runs on
+// a background thread once the main window has painted, or after
+// FIRST_PAINT_WAIT if it never reports, and the window pool is filled last.
+// Every phase is timed for `get_startup_report`.
+
+use serde::Serialize;
+use specta::Type;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::events::AppEvent;
+use crate::{audit, jumplist, plugins, safemode, windowpool};
+
+const FIRST_PAINT_WAIT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PhaseTiming {
+    name: &'static str,
+    // Ran in the background after first paint
+    deferred: bool,
+    start_ms: u64,
+    duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct StartupReport {
+    // Milliseconds since setup began
+    setup_ms: Option<u64>,
+    first_paint_ms: Option<u64>,
+    ready_ms: Option<u64>,
+    phases: Vec<PhaseTiming>,
+}
+
+pub struct StartupState {
+    started: Instant,
+    phases: Mutex<Vec<PhaseTiming>>,
+    setup_ms: Mutex<Option<u64>>,
+    first_paint_ms: Mutex<Option<u64>>,
+    painted: Condvar,
+    ready_ms: Mutex<Option<u64>>,
+}
+
+impl Default for StartupState {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            phases: Mutex::new(Vec::new()),
+            setup_ms: Mutex::new(None),
+            first_paint_ms: Mutex::new(None),
+            painted: Condvar::new(),
+            ready_ms: Mutex::new(None),
+        }
+    }
+}
+
+impl StartupState {
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    fn report(&self) -> StartupReport {
+        StartupReport {
+            setup_ms: *self.setup_ms.lock().unwrap(),
+            first_paint_ms: *self.first_paint_ms.lock().unwrap(),
+            ready_ms: *self.ready_ms.lock().unwrap(),
+            phases: self.phases.lock().unwrap().clone(),
+        }
+    }
+}
+
+fn timed<T>(app: &AppHandle, name: &'static str, deferred: bool, f: impl FnOnce() -> T) -> T {
+    let state = app.state::<StartupState>();
+    let start_ms = state.elapsed_ms();
+    let begun = Instant::now();
+    let result = f();
+    state.phases.lock().unwrap().push(PhaseTiming {
+        name,
+        deferred,
+        start_ms,
+        duration_ms: begun.elapsed().as_millis() as u64,
+    });
+    result
+}
+
+pub fn phase<T>(app: &AppHandle, name: &'static str, f: impl FnOnce() -> T) -> T {
+    timed(app, name, false, f)
+}
+
+fn deferred(app: &AppHandle) {
+    timed(app, "database", true, || {
+        if let Err(e) = app.state::<Database>().warm() {
+            eprintln!("Failed to open database: {}", e);
+        }
+    });
+    timed(app, "plugins", true, || plugins::load_installed(app));
+    timed(app, "audit log", true, || audit::verify_on_startup(app));
+    timed(app, "jump list", true, || jumplist::refresh(app));
+    windowpool::refill(app);
+}
+
+// Called at the end of `setup_app`
+pub fn finish_setup(app: &AppHandle) {
+    let state = app.state::<StartupState>();
+    *state.setup_ms.lock().unwrap() = Some(state.elapsed_ms());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let state = app.state::<StartupState>();
+        {
+            let painted = state.first_paint_ms.lock().unwrap();
+            let _ = state
+                .painted
+                .wait_timeout_while(painted, FIRST_PAINT_WAIT, |painted| painted.is_none());
+        }
+        deferred(&app);
+        *state.ready_ms.lock().unwrap() = Some(state.elapsed_ms());
+        safemode::on_ready(&app);
+        let _ = app.emit_all("startup-complete", state.report());
+    });
+}
+
+// Subscribed to the event bus; the main window's first load counts as first paint
+pub fn on_event(app: &AppHandle, event: &AppEvent) {
+    if !matches!(event, AppEvent::NavigationFinished { window_id, .. } if window_id == "main") {
+        return;
+    }
+    let state = app.state::<StartupState>();
+    let mut first_paint = state.first_paint_ms.lock().unwrap();
+    if first_paint.is_none() {
+        *first_paint = Some(state.elapsed_ms());
+        state.painted.notify_all();
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_startup_report(state: tauri::State<'_, StartupState>) -> Result<StartupReport, String> {
+    Ok(state.report())
+}
@@ -0,0 +1,69 @@
+// JSON persistence helpers for state kept in the app data directory
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::tasks;
+
+// Resolve a file inside the app data directory (`file` may include subdirectories),
+// creating its parent directory if needed
+pub fn data_path(app: &AppHandle, file: &str) -> Result<PathBuf, String> {
+    let path = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "App data directory unavailable".to_string())?
+        .join(file);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+// Load a JSON file, falling back to the default value when missing or unreadable
+pub fn load<T: DeserializeOwned + Default>(app: &AppHandle, file: &str) -> T {
+    tasks::block_in_place(|| {
+        data_path(app, file)
+            .ok()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    })
+}
+
+// Write a JSON file atomically (write to temp file, then rename)
+pub fn save<T: Serialize>(app: &AppHandle, file: &str, value: &T) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    tasks::block_in_place(|| {
+        let path = data_path(app, file)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+    })
+}
+
+// Resolve a subdirectory of the app cache directory, creating it if needed
+pub fn cache_dir(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path_resolver()
+        .app_cache_dir()
+        .ok_or_else(|| "App cache directory unavailable".to_string())?
+        .join(name);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+// Total size in bytes of all files below a path (missing paths count as empty)
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
@@ -0,0 +1,215 @@
+// Task manager: hang detection and terminating stuck webviews
+// Each window is pinged periodically; a window that stops answering is reported
+// as unresponsive and can be killed without restarting the browser.
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Window, WindowUrl};
+
+use crate::resources::{self, ProcessUsage};
+use crate::{containers, pagebridge, windowpool};
+
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+const HANG_THRESHOLD: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WindowTask {
+    window_id: String,
+    url: Option<String>,
+    visible: bool,
+    responsive: bool,
+    last_response_ms: Option<u64>,
+    js_heap_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TaskList {
+    windows: Vec<WindowTask>,
+    processes: Vec<ProcessUsage>,
+}
+
+#[derive(Default)]
+struct PingState {
+    last_pong: Option<Instant>,
+    first_ping: Option<Instant>,
+    unresponsive: bool,
+}
+
+#[derive(Default)]
+pub struct TaskManager {
+    pings: Mutex<HashMap<String, PingState>>,
+}
+
+const PING_SCRIPT: &str =
+    "window.__TAURI_INVOKE__ && window.__TAURI_INVOKE__('webview_pong');";
+
+fn is_unresponsive(state: &PingState, now: Instant) -> bool {
+    let since = state.last_pong.or(state.first_ping);
+    since.map_or(false, |t| now.duration_since(t) > HANG_THRESHOLD)
+}
+
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let now = Instant::now();
+        let windows = app.windows();
+        let manager = app.state::<TaskManager>();
+
+        let mut changes = Vec::new();
+        {
+            let mut pings = manager.pings.lock().unwrap();
+            pings.retain(|label, _| windows.contains_key(label));
+
+            for (label, window) in &windows {
+                // Hidden windows may be throttled by the engine, and pages that
+                // can't invoke would never answer; don't flag either
+                if !window.is_visible().unwrap_or(false) || !pagebridge::can_invoke(window) {
+                    if let Some(state) = pings.remove(label) {
+                        if state.unresponsive {
+                            changes.push((label.clone(), false));
+                        }
+                    }
+                    continue;
+                }
+                let state = pings.entry(label.clone()).or_default();
+                state.first_ping.get_or_insert(now);
+                let _ = window.eval(PING_SCRIPT);
+
+                let hung = is_unresponsive(state, now);
+                if hung != state.unresponsive {
+                    state.unresponsive = hung;
+                    changes.push((label.clone(), hung));
+                }
+            }
+        }
+
+        for (label, hung) in changes {
+            let event = if hung { "window-unresponsive" } else { "window-responsive" };
+            let _ = app.emit_all(event, label);
+        }
+        std::thread::sleep(PING_INTERVAL);
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn webview_pong(window: Window, manager: tauri::State<'_, TaskManager>) -> Result<(), String> {
+    let mut pings = manager.pings.lock().unwrap();
+    let state = pings.entry(window.label().to_string()).or_default();
+    state.last_pong = Some(Instant::now());
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_window_processes(
+    app_handle: AppHandle,
+    manager: tauri::State<'_, TaskManager>,
+) -> Result<TaskList, String> {
+    let report = resources::collect(&app_handle);
+    let now = Instant::now();
+    let pings = manager.pings.lock().unwrap();
+
+    let windows = report
+        .windows
+        .into_iter()
+        .map(|usage| {
+            let ping = pings.get(&usage.window_id);
+            WindowTask {
+                responsive: ping.map_or(true, |p| !p.unresponsive),
+                last_response_ms: ping
+                    .and_then(|p| p.last_pong)
+                    .map(|t| now.duration_since(t).as_millis() as u64),
+                url: usage.url,
+                visible: usage.visible,
+                js_heap_bytes: usage.js_heap_bytes,
+                window_id: usage.window_id,
+            }
+        })
+        .collect();
+
+    Ok(TaskList {
+        windows,
+        processes: report.processes,
+    })
+}
+
+// Kill a window's web content. WebKitGTK can terminate the per-view web process
+// directly; elsewhere the webview is destroyed and recreated at the same URL and
+// geometry, which tears down its renderer.
+#[tauri::command]
+#[specta::specta]
+pub async fn kill_window_process(
+    app_handle: AppHandle,
+    manager: tauri::State<'_, TaskManager>,
+    window_id: String,
+) -> Result<(), String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    manager.pings.lock().unwrap().remove(&window_id);
+
+    if platform::terminate_web_process(&window)? {
+        return Ok(());
+    }
+
+    let url = resources::collect(&app_handle)
+        .windows
+        .into_iter()
+        .find(|w| w.window_id == window_id)
+        .and_then(|w| w.url);
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+
+    // The main window hosts the app shell; reload it in place rather than closing it
+    if window_id == "main" {
+        return window.eval("location.reload()").map_err(|e| e.to_string());
+    }
+    let container = containers::window_container(&app_handle, &window_id);
+    window.close().map_err(|e| e.to_string())?;
+
+    let window_url = match url {
+        Some(u) => WindowUrl::External(u.parse().map_err(|e| format!("Invalid URL: {}", e))?),
+        None => WindowUrl::App("index.html".into()),
+    };
+    let replacement = match container {
+        Some(container_id) => containers::open_window(&app_handle, window_url, &container_id)?,
+        None => windowpool::build_window(&app_handle, window_url, true)?,
+    };
+    replacement
+        .set_size(PhysicalSize::new(size.width, size.height))
+        .map_err(|e| e.to_string())?;
+    replacement
+        .set_position(PhysicalPosition::new(position.x, position.y))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use tauri::Window;
+    use webkit2gtk::WebViewExt;
+
+    pub fn terminate_web_process(window: &Window) -> Result<bool, String> {
+        window
+            .with_webview(|webview| {
+                let view = webview.inner();
+                view.terminate_web_process();
+                // A terminated view shows nothing until reloaded
+                view.reload();
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use tauri::Window;
+
+    pub fn terminate_web_process(_window: &Window) -> Result<bool, String> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,124 @@
+// Background work and cancellable tasks
+// Commands run on the async runtime's worker threads, so disk, SQLite and
+// process calls must not block them: short ones go through `block_in_place`,
+// longer ones onto the blocking pool with `blocking`. Long-running operations
+// (crawls, link checks) are wrapped in `run`, which registers a TaskHandle
+// that `cancel_task` can stop.
+
+use rand::RngCore;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+pub struct TaskHandle {
+    name: String,
+    started_at: i64,
+    cancel: Notify,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TaskInfo {
+    id: String,
+    name: String,
+    started_at: i64,
+}
+
+#[derive(Default)]
+pub struct TaskRegistry {
+    tasks: Mutex<HashMap<String, Arc<TaskHandle>>>,
+}
+
+// Run a short blocking call in place; the runtime moves its other tasks off
+// this worker meanwhile. Outside the runtime (setup, event handlers) it just
+// runs the call.
+pub fn block_in_place<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+// Run slow blocking work (directory walks, external processes) on the blocking pool
+pub async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Run a long operation as a registered task; it ends with an error when cancelled
+pub async fn run<T>(app: &AppHandle, name: &str, task: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let id = hex::encode(bytes);
+    let handle = Arc::new(TaskHandle {
+        name: name.to_string(),
+        started_at: chrono::Utc::now().timestamp_millis(),
+        cancel: Notify::new(),
+    });
+    let registry = app.state::<TaskRegistry>();
+    registry.tasks.lock().unwrap().insert(id.clone(), handle.clone());
+    let _ = app.emit_all(
+        "task-started",
+        TaskInfo {
+            id: id.clone(),
+            name: handle.name.clone(),
+            started_at: handle.started_at,
+        },
+    );
+
+    let result = tokio::select! {
+        result = task => result,
+        _ = handle.cancel.notified() => Err(format!("{} was cancelled", name)),
+    };
+    registry.tasks.lock().unwrap().remove(&id);
+    let _ = app.emit_all("task-finished", &id);
+    result
+}
+
+// Number of registered tasks still running
+pub fn running(app: &AppHandle) -> usize {
+    app.state::<TaskRegistry>().tasks.lock().unwrap().len()
+}
+
+pub fn cancel_all(app: &AppHandle) {
+    for handle in app.state::<TaskRegistry>().tasks.lock().unwrap().values() {
+        handle.cancel.notify_one();
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_tasks(registry: tauri::State<'_, TaskRegistry>) -> Result<Vec<TaskInfo>, String> {
+    let mut tasks: Vec<TaskInfo> = registry
+        .tasks
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(id, handle)| TaskInfo {
+            id: id.clone(),
+            name: handle.name.clone(),
+            started_at: handle.started_at,
+        })
+        .collect();
+    tasks.sort_by_key(|t| t.started_at);
+    Ok(tasks)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_task(registry: tauri::State<'_, TaskRegistry>, task_id: String) -> Result<(), String> {
+    let tasks = registry.tasks.lock().unwrap();
+    let handle = tasks
+        .get(&task_id)
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+    // notify_one stores a permit, so a cancel that lands before the task
+    // starts waiting is not lost
+    handle.cancel.notify_one();
+    Ok(())
+}
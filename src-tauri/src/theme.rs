@@ -0,0 +1,542 @@
+// Light/dark/system theming and importable theme packages
+// The `theme` setting is "light", "dark", "system" or the id of an imported
+// theme package. Native window chrome is forced to the resolved appearance
+// (or left to follow the OS for "system"), the tray glyph follows the theme's
+// icon variant, and the frontend gets the palette via `theme-changed`.
+// Native watchers report OS light/dark switches as `system-theme-changed`.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{safemode, storage};
+use crate::tray::{self, IconVariant};
+
+const THEMES_FILE: &str = "themes.json";
+const BUILTIN_THEMES: [&str; 3] = ["light", "dark", "system"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ThemePackage {
+    id: String,
+    name: String,
+    // Native chrome appearance the palette is designed for
+    base: Appearance,
+    // CSS custom property name (without `--`) to colour
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+    #[serde(default)]
+    accent: Option<String>,
+    #[serde(default)]
+    tray_icon: IconVariant,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ResolvedTheme {
+    theme: String,
+    appearance: Appearance,
+    follows_system: bool,
+    colors: BTreeMap<String, String>,
+    accent: Option<String>,
+    tray_icon: IconVariant,
+}
+
+#[derive(Default)]
+pub struct ThemeState {
+    themes: Mutex<Vec<ThemePackage>>,
+    // Last applied theme, None until `apply` runs at startup
+    current: Mutex<Option<ResolvedTheme>>,
+    // Last seen OS appearance, to drop duplicate change notifications
+    system: Mutex<Option<Appearance>>,
+}
+
+impl ThemeState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            themes: Mutex::new(storage::load(app, THEMES_FILE)),
+            current: Mutex::new(None),
+            system: Mutex::new(Some(platform::system_appearance())),
+        }
+    }
+}
+
+fn current_theme(app: &AppHandle) -> String {
+    let state = app.state::<ThemeState>();
+    let current = state.current.lock().unwrap();
+    current.as_ref().map_or_else(|| "system".to_string(), |c| c.theme.clone())
+}
+
+// Accept the colour syntaxes the frontend can use directly in CSS
+fn valid_color(color: &str) -> bool {
+    let color = color.trim();
+    let hex = color
+        .strip_prefix('#')
+        .map_or(false, |h| matches!(h.len(), 3 | 4 | 6 | 8) && h.chars().all(|c| c.is_ascii_hexdigit()));
+    let function = ["rgb(", "rgba(", "hsl(", "hsla("].iter().any(|f| color.starts_with(f))
+        && color.ends_with(')')
+        && color
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || " ,.%()/-".contains(c));
+    hex || function
+}
+
+fn validate(package: &ThemePackage) -> Result<(), String> {
+    let valid_id = !package.id.is_empty()
+        && package
+            .id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid_id || BUILTIN_THEMES.contains(&package.id.as_str()) {
+        return Err(format!("Invalid theme id: {}", package.id));
+    }
+    if package.name.trim().is_empty() {
+        return Err("Theme name is required".to_string());
+    }
+    for (name, color) in &package.colors {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid colour name: {}", name));
+        }
+        if !valid_color(color) {
+            return Err(format!("Invalid colour for {}: {}", name, color));
+        }
+    }
+    if let Some(accent) = package.accent.as_deref().filter(|a| !valid_color(a)) {
+        return Err(format!("Invalid accent colour: {}", accent));
+    }
+    Ok(())
+}
+
+fn resolve(app: &AppHandle, theme: &str) -> ResolvedTheme {
+    let builtin = |appearance: Appearance, follows_system: bool| ResolvedTheme {
+        theme: theme.to_string(),
+        appearance,
+        follows_system,
+        colors: BTreeMap::new(),
+        accent: None,
+        tray_icon: IconVariant::Color,
+    };
+    match theme {
+        "light" => builtin(Appearance::Light, false),
+        "dark" => builtin(Appearance::Dark, false),
+        "system" => {
+            // Monochrome glyph that contrasts with the OS taskbar/menu bar
+            let appearance = platform::system_appearance();
+            ResolvedTheme {
+                tray_icon: match appearance {
+                    Appearance::Light => IconVariant::Dark,
+                    Appearance::Dark => IconVariant::Light,
+                },
+                ..builtin(appearance, true)
+            }
+        }
+        id => {
+            let themes = app.state::<ThemeState>().themes.lock().unwrap().clone();
+            match themes.into_iter().find(|t| t.id == id) {
+                Some(package) => ResolvedTheme {
+                    theme: theme.to_string(),
+                    appearance: package.base,
+                    follows_system: false,
+                    colors: package.colors,
+                    accent: package.accent,
+                    tray_icon: package.tray_icon,
+                },
+                // A deleted custom theme falls back to following the OS
+                None => ResolvedTheme {
+                    theme: "system".to_string(),
+                    ..builtin(platform::system_appearance(), true)
+                },
+            }
+        }
+    }
+}
+
+fn apply_to_window(window: &Window, resolved: &ResolvedTheme) {
+    let forced = (!resolved.follows_system).then_some(resolved.appearance);
+    if let Err(e) = platform::set_window_appearance(window, forced) {
+        eprintln!("Failed to apply theme to {}: {}", window.label(), e);
+    }
+}
+
+// Apply a theme setting to all windows, the tray and the frontend
+pub fn apply(app: &AppHandle, theme: &str) -> Result<ResolvedTheme, String> {
+    // Imported themes are not applied in safe mode
+    let theme = if safemode::active(app) && !BUILTIN_THEMES.contains(&theme) {
+        "system"
+    } else {
+        theme
+    };
+    let resolved = resolve(app, theme);
+    *app.state::<ThemeState>().current.lock().unwrap() = Some(resolved.clone());
+    for window in app.windows().values() {
+        apply_to_window(window, &resolved);
+    }
+    tray::set_variant(app, resolved.tray_icon)?;
+    app.emit_all("theme-changed", &resolved).map_err(|e| e.to_string())?;
+    Ok(resolved)
+}
+
+// Windows opened after the theme was applied pick it up on their first load
+pub fn on_page_load(window: &Window) {
+    let current = window.state::<ThemeState>().current.lock().unwrap().clone();
+    if let Some(resolved) = current {
+        apply_to_window(window, &resolved);
+    }
+}
+
+// The OS may have switched appearance: tell the frontend and re-skin windows
+// and the tray while following it
+pub fn system_theme_changed(app: &AppHandle) {
+    let appearance = platform::system_appearance();
+    {
+        let state = app.state::<ThemeState>();
+        let mut last = state.system.lock().unwrap();
+        if *last == Some(appearance) {
+            return;
+        }
+        *last = Some(appearance);
+    }
+    let _ = app.emit_all("system-theme-changed", json!({ "appearance": appearance }));
+    if current_theme(app) == "system" {
+        if let Err(e) = apply(app, "system") {
+            eprintln!("Failed to follow system theme: {}", e);
+        }
+    }
+}
+
+// Must be called on the main thread (the macOS observer is registered there)
+pub fn start_watcher(app: &AppHandle) {
+    platform::watch(app);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_theme(app_handle: AppHandle) -> Result<ResolvedTheme, String> {
+    Ok(resolve(&app_handle, &current_theme(&app_handle)))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_themes(state: tauri::State<'_, ThemeState>) -> Result<Vec<ThemePackage>, String> {
+    Ok(state.themes.lock().unwrap().clone())
+}
+
+// Import a theme package (JSON); re-importing the same id replaces it
+#[tauri::command]
+#[specta::specta]
+pub async fn import_theme(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ThemeState>,
+    path: String,
+) -> Result<ThemePackage, String> {
+    let bytes = std::fs::read(&path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let package: ThemePackage = serde_json::from_slice(&bytes).map_err(|e| format!("Invalid theme: {}", e))?;
+    validate(&package)?;
+    {
+        let mut themes = state.themes.lock().unwrap();
+        themes.retain(|t| t.id != package.id);
+        themes.push(package.clone());
+        storage::save(&app_handle, THEMES_FILE, &*themes)?;
+    }
+    let current = current_theme(&app_handle);
+    if current == package.id {
+        apply(&app_handle, &current)?;
+    }
+    Ok(package)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_theme(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ThemeState>,
+    theme_id: String,
+) -> Result<(), String> {
+    {
+        let mut themes = state.themes.lock().unwrap();
+        themes.retain(|t| t.id != theme_id);
+        storage::save(&app_handle, THEMES_FILE, &*themes)?;
+    }
+    if current_theme(&app_handle) == theme_id {
+        apply(&app_handle, "system")?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::Appearance;
+    use tauri::Window;
+    use windows::core::w;
+    use windows::Win32::Foundation::{BOOL, HWND};
+    use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_USE_IMMERSIVE_DARK_MODE};
+    use tauri::AppHandle;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegGetValueW, RegNotifyChangeKeyValue, RegOpenKeyExW, HKEY, HKEY_CURRENT_USER, KEY_NOTIFY,
+        REG_NOTIFY_CHANGE_LAST_SET, RRF_RT_REG_DWORD,
+    };
+
+    const PERSONALIZE_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize");
+
+    pub fn system_appearance() -> Appearance {
+        let mut value: u32 = 1;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                PERSONALIZE_KEY,
+                w!("AppsUseLightTheme"),
+                RRF_RT_REG_DWORD,
+                None,
+                Some(&mut value as *mut u32 as *mut _),
+                Some(&mut size),
+            )
+        };
+        if result.is_ok() && value == 0 {
+            Appearance::Dark
+        } else {
+            Appearance::Light
+        }
+    }
+
+    // Dark title bar via DWM; "follow the OS" uses the current system setting
+    pub fn set_window_appearance(window: &Window, forced: Option<Appearance>) -> Result<(), String> {
+        let dark = BOOL::from(forced.unwrap_or_else(system_appearance) == Appearance::Dark);
+        let hwnd = HWND(window.hwnd().map_err(|e| e.to_string())?.0 as _);
+        unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_USE_IMMERSIVE_DARK_MODE,
+                &dark as *const BOOL as *const _,
+                std::mem::size_of::<BOOL>() as u32,
+            )
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    // Block on registry change notifications for the Personalize key
+    pub fn watch(app: &AppHandle) {
+        let app = app.clone();
+        std::thread::spawn(move || unsafe {
+            let mut key = HKEY::default();
+            if RegOpenKeyExW(HKEY_CURRENT_USER, PERSONALIZE_KEY, 0, KEY_NOTIFY, &mut key).is_err() {
+                eprintln!("Failed to watch the system theme");
+                return;
+            }
+            while RegNotifyChangeKeyValue(key, false, REG_NOTIFY_CHANGE_LAST_SET, HANDLE::default(), false).is_ok() {
+                super::system_theme_changed(&app);
+            }
+            let _ = RegCloseKey(key);
+        });
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Appearance;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::OnceLock;
+    use tauri::{AppHandle, Window};
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    pub fn system_appearance() -> Appearance {
+        unsafe {
+            let defaults: id = msg_send![class!(NSUserDefaults), standardUserDefaults];
+            let key = NSString::alloc(nil).init_str("AppleInterfaceStyle");
+            let style: id = msg_send![defaults, stringForKey: key];
+            if style != nil {
+                Appearance::Dark
+            } else {
+                Appearance::Light
+            }
+        }
+    }
+
+    // NSWindow.appearance; nil follows the system
+    pub fn set_window_appearance(window: &Window, forced: Option<Appearance>) -> Result<(), String> {
+        window
+            .with_webview(move |webview| unsafe {
+                let ns_window = webview.ns_window() as id;
+                let appearance: id = match forced {
+                    Some(appearance) => {
+                        let name = match appearance {
+                            Appearance::Light => "NSAppearanceNameAqua",
+                            Appearance::Dark => "NSAppearanceNameDarkAqua",
+                        };
+                        let name = NSString::alloc(nil).init_str(name);
+                        msg_send![class!(NSAppearance), appearanceNamed: name]
+                    }
+                    None => nil,
+                };
+                let _: () = msg_send![ns_window, setAppearance: appearance];
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    extern "C" fn theme_changed(_this: &Object, _cmd: Sel, _notification: id) {
+        if let Some(app) = APP.get() {
+            super::system_theme_changed(app);
+        }
+    }
+
+    // Observe AppleInterfaceThemeChangedNotification on the distributed center
+    pub fn watch(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+        unsafe {
+            let Some(mut decl) = ClassDecl::new("MadEasyThemeObserver", class!(NSObject)) else {
+                return;
+            };
+            decl.add_method(
+                sel!(themeChanged:),
+                theme_changed as extern "C" fn(&Object, Sel, id),
+            );
+            let observer: id = msg_send![decl.register(), new];
+            let center: id = msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+            let name = NSString::alloc(nil).init_str("AppleInterfaceThemeChangedNotification");
+            let _: () = msg_send![center, addObserver: observer selector: sel!(themeChanged:) name: name object: nil];
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::Appearance;
+    use gtk::prelude::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use tauri::{AppHandle, Window};
+
+    const PORTAL_ARGS: [&str; 6] = [
+        "--session",
+        "--dest",
+        "org.freedesktop.portal.Desktop",
+        "--object-path",
+        "/org/freedesktop/portal/desktop",
+        "--method",
+    ];
+
+    // freedesktop appearance setting: 0 = no preference, 1 = dark, 2 = light
+    fn portal_color_scheme() -> Option<u32> {
+        let output = Command::new("gdbus")
+            .arg("call")
+            .args(&PORTAL_ARGS)
+            .args(["org.freedesktop.portal.Settings.Read", "org.freedesktop.appearance", "color-scheme"])
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let value = text.split("uint32").nth(1)?;
+        value
+            .trim()
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    fn gsettings(key: &str) -> Option<String> {
+        let output = Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", key])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().trim_matches('\'').to_lowercase())
+    }
+
+    pub fn system_appearance() -> Appearance {
+        let dark = match portal_color_scheme() {
+            Some(1) => true,
+            Some(2) => false,
+            _ => {
+                gsettings("color-scheme").map_or(false, |s| s == "prefer-dark")
+                    || gsettings("gtk-theme").map_or(false, |s| s.contains("dark"))
+            }
+        };
+        if dark {
+            Appearance::Dark
+        } else {
+            Appearance::Light
+        }
+    }
+
+    // GTK's dark-variant preference is process-wide
+    pub fn set_window_appearance(window: &Window, forced: Option<Appearance>) -> Result<(), String> {
+        let dark = forced.unwrap_or_else(system_appearance) == Appearance::Dark;
+        window
+            .with_webview(move |_| {
+                if let Some(settings) = gtk::Settings::default() {
+                    settings.set_gtk_application_prefer_dark_theme(dark);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    // Follow the settings portal's SettingChanged signal, falling back to
+    // GNOME's gsettings where no portal is running
+    pub fn watch(app: &AppHandle) {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let portal = Command::new("gdbus")
+                .args(["monitor", "--session", "--dest", "org.freedesktop.portal.Desktop"])
+                .args(["--object-path", "/org/freedesktop/portal/desktop"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn();
+            let gsettings = || {
+                Command::new("gsettings")
+                    .args(["monitor", "org.gnome.desktop.interface"])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+            };
+            let mut child = match portal.or_else(|_| gsettings()) {
+                Ok(child) => child,
+                Err(e) => {
+                    eprintln!("Failed to watch the system theme: {}", e);
+                    return;
+                }
+            };
+            let Some(stdout) = child.stdout.take() else { return };
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if line.contains("color-scheme") || line.contains("gtk-theme") {
+                    super::system_theme_changed(&app);
+                }
+            }
+            let _ = child.wait();
+        });
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use super::Appearance;
+    use tauri::{AppHandle, Window};
+
+    pub fn system_appearance() -> Appearance {
+        Appearance::Light
+    }
+
+    pub fn set_window_appearance(_window: &Window, _forced: Option<Appearance>) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn watch(_app: &AppHandle) {}
+}
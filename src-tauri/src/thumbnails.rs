@@ -0,0 +1,180 @@
+// Page thumbnails for the new-tab page and history
+// Pages are captured shortly after they finish loading (or on demand), scaled
+// down and kept in a size-limited LRU disk cache.
+
+use base64::Engine;
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{capture, resources, storage};
+
+const CACHE_NAME: &str = "thumbnails";
+const SETTINGS_FILE: &str = "thumbnails.json";
+const THUMB_WIDTH: u32 = 320;
+const THUMB_HEIGHT: u32 = 200;
+// Let the page settle (fonts, images) before capturing
+const CAPTURE_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ThumbnailSettings {
+    capture_on_navigation: bool,
+    max_cache_bytes: u64,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self {
+            capture_on_navigation: true,
+            max_cache_bytes: 50 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ThumbnailState {
+    settings: Mutex<ThumbnailSettings>,
+}
+
+impl ThumbnailState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            settings: Mutex::new(storage::load(app, SETTINGS_FILE)),
+        }
+    }
+}
+
+fn thumbnail_path(app: &AppHandle, url: &str) -> Result<PathBuf, String> {
+    let key = hex::encode(Sha256::digest(url.as_bytes()));
+    Ok(storage::cache_dir(app, CACHE_NAME)?.join(format!("{}.jpg", key)))
+}
+
+fn is_capturable(url: &str) -> bool {
+    (url.starts_with("http://") || url.starts_with("https://"))
+        && !url.starts_with("http://localhost")
+        && !url.starts_with("http://127.0.0.1")
+}
+
+// Capture, scale (cover-crop to the thumbnail aspect) and store a thumbnail
+pub fn capture(app: &AppHandle, window: &Window, url: &str) -> Result<Vec<u8>, String> {
+    let screenshot = capture::capture_window(window)?;
+    let scaled = image::DynamicImage::ImageRgba8(screenshot)
+        .resize_to_fill(THUMB_WIDTH, THUMB_HEIGHT, FilterType::Triangle)
+        .to_rgb8();
+
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 80)
+        .encode_image(&scaled)
+        .map_err(|e| e.to_string())?;
+
+    std::fs::write(thumbnail_path(app, url)?, &jpeg).map_err(|e| e.to_string())?;
+    evict(app)?;
+    Ok(jpeg)
+}
+
+// Remove least recently used thumbnails until the cache is within its limit.
+// File modification time doubles as the last-access time.
+fn evict(app: &AppHandle) -> Result<(), String> {
+    let limit = app.state::<ThumbnailState>().settings.lock().unwrap().max_cache_bytes;
+    let dir = storage::cache_dir(app, CACHE_NAME)?;
+
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some((meta.modified().ok()?, meta.len(), entry.path()))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    files.sort_by_key(|(modified, _, _)| *modified);
+
+    for (_, size, path) in files {
+        if total <= limit {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total -= size;
+        }
+    }
+    Ok(())
+}
+
+// Schedule a capture after a page finishes loading
+pub fn on_page_load(window: &Window, url: &str) {
+    let app = window.app_handle();
+    let enabled = app
+        .state::<ThumbnailState>()
+        .settings
+        .lock()
+        .unwrap()
+        .capture_on_navigation;
+    if !enabled || !is_capturable(url) || !window.is_visible().unwrap_or(false) {
+        return;
+    }
+
+    let window = window.clone();
+    let url = url.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(CAPTURE_DELAY).await;
+        if let Err(e) = capture(&app, &window, &url) {
+            eprintln!("Thumbnail capture failed for {}: {}", url, e);
+        }
+    });
+}
+
+// Stored thumbnail for a URL, without capturing one
+pub fn cached(app: &AppHandle, url: &str) -> Option<Vec<u8>> {
+    std::fs::read(thumbnail_path(app, url).ok()?).ok()
+}
+
+// Thumbnail for a URL as a data URL; captured on demand if a window is showing it
+#[tauri::command]
+#[specta::specta]
+pub async fn get_page_thumbnail(app_handle: AppHandle, url: String) -> Result<Option<String>, String> {
+    let path = thumbnail_path(&app_handle, &url)?;
+
+    let jpeg = match std::fs::read(&path) {
+        Ok(bytes) => {
+            // Mark as recently used for LRU eviction
+            let _ = filetime::set_file_mtime(&path, filetime::FileTime::now());
+            Some(bytes)
+        }
+        Err(_) => {
+            let showing = resources::collect(&app_handle)
+                .windows
+                .into_iter()
+                .find(|w| w.visible && w.url.as_deref() == Some(url.as_str()))
+                .and_then(|w| app_handle.get_window(&w.window_id));
+            match showing {
+                Some(window) => Some(capture(&app_handle, &window, &url)?),
+                None => None,
+            }
+        }
+    };
+
+    Ok(jpeg.map(|bytes| {
+        format!(
+            "data:image/jpeg;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        )
+    }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_thumbnail_settings(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ThumbnailState>,
+    settings: ThumbnailSettings,
+) -> Result<(), String> {
+    storage::save(&app_handle, SETTINGS_FILE, &settings)?;
+    *state.settings.lock().unwrap() = settings;
+    evict(&app_handle)
+}
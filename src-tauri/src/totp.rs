@@ -0,0 +1,264 @@
+// TOTP authenticator (RFC 6238) for saved logins with two-factor auth
+// Shared secrets live in the keychain under `totp:<account>`; totp.json only
+// keeps the parameters needed to compute codes. Every code handed out, to the
+// frontend or to a workflow via `{{totp:<account>}}`, needs the user's OK in
+// a native dialog first and is audited.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::audit::{self, AuditCategory};
+use crate::{secrets, storage};
+
+const TOTP_FILE: &str = "totp.json";
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u64 = 30;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TotpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TotpAccount {
+    account: String,
+    issuer: Option<String>,
+    #[serde(default)]
+    algorithm: TotpAlgorithm,
+    digits: u32,
+    period: u64,
+    created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TotpCode {
+    pub code: String,
+    // Seconds until the code rolls over
+    expires_in: u64,
+}
+
+#[derive(Default)]
+pub struct TotpState {
+    accounts: Mutex<Vec<TotpAccount>>,
+}
+
+impl TotpState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            accounts: Mutex::new(storage::load(app, TOTP_FILE)),
+        }
+    }
+}
+
+fn secret_key(account: &str) -> String {
+    format!("totp:{}", account)
+}
+
+// Keychain entries of every account's shared secret, for backups
+pub fn keychain_keys(app: &AppHandle) -> Vec<String> {
+    let accounts = app.state::<TotpState>().accounts.lock().unwrap();
+    accounts.iter().map(|a| secret_key(&a.account)).collect()
+}
+
+// RFC 4648 base32, as used by authenticator apps; spaces, dashes and padding ignored
+fn base32_decode(input: &str) -> Result<Vec<u8>, String> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut bits: u64 = 0;
+    let mut count = 0;
+    let mut out = Vec::new();
+    for c in input.chars().filter(|c| !matches!(c, ' ' | '-' | '=')) {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase() as u8)
+            .ok_or_else(|| format!("Invalid character in TOTP secret: {}", c))?;
+        bits = (bits << 5) | value as u64;
+        count += 5;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+    if out.is_empty() {
+        return Err("TOTP secret is empty".to_string());
+    }
+    Ok(out)
+}
+
+fn hmac_digest(algorithm: TotpAlgorithm, key: &[u8], message: &[u8]) -> Vec<u8> {
+    fn run<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+        let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(message);
+        mac.finalize().into_bytes().to_vec()
+    }
+    match algorithm {
+        TotpAlgorithm::Sha1 => run::<Hmac<sha1::Sha1>>(key, message),
+        TotpAlgorithm::Sha256 => run::<Hmac<sha2::Sha256>>(key, message),
+        TotpAlgorithm::Sha512 => run::<Hmac<sha2::Sha512>>(key, message),
+    }
+}
+
+fn generate(account: &TotpAccount, secret: &[u8], now: u64) -> TotpCode {
+    let counter = now / account.period;
+    let digest = hmac_digest(account.algorithm, secret, &counter.to_be_bytes());
+    // Dynamic truncation (RFC 4226 section 5.3)
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([digest[offset] & 0x7f, digest[offset + 1], digest[offset + 2], digest[offset + 3]]);
+    let code = value % 10u32.pow(account.digits);
+    TotpCode {
+        code: format!("{:0width$}", code, width = account.digits as usize),
+        expires_in: account.period - now % account.period,
+    }
+}
+
+// otpauth://totp/Issuer:account?secret=...&issuer=...&digits=6&period=30&algorithm=SHA1
+fn parse_uri(uri: &str) -> Result<(TotpAccount, String), String> {
+    let url = url::Url::parse(uri).map_err(|e| format!("Invalid otpauth URI: {}", e))?;
+    if url.scheme() != "otpauth" || url.host_str() != Some("totp") {
+        return Err("Only otpauth://totp URIs are supported".to_string());
+    }
+    let label = percent_decode(url.path().trim_start_matches('/'));
+    let (label_issuer, account) = match label.split_once(':') {
+        Some((issuer, account)) => (Some(issuer.trim().to_string()), account.trim().to_string()),
+        None => (None, label.trim().to_string()),
+    };
+    let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned());
+    let secret = param("secret").ok_or("otpauth URI has no secret")?;
+    let algorithm = match param("algorithm").as_deref().map(str::to_ascii_uppercase).as_deref() {
+        None | Some("SHA1") => TotpAlgorithm::Sha1,
+        Some("SHA256") => TotpAlgorithm::Sha256,
+        Some("SHA512") => TotpAlgorithm::Sha512,
+        Some(other) => return Err(format!("Unsupported TOTP algorithm: {}", other)),
+    };
+    let account = TotpAccount {
+        account,
+        issuer: param("issuer").or(label_issuer),
+        algorithm,
+        digits: param("digits").and_then(|d| d.parse().ok()).unwrap_or(DEFAULT_DIGITS),
+        period: param("period").and_then(|p| p.parse().ok()).unwrap_or(DEFAULT_PERIOD),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    Ok((account, secret))
+}
+
+fn percent_decode(text: &str) -> String {
+    url::form_urlencoded::parse(format!("x={}", text.replace('+', "%2B")).as_bytes())
+        .next()
+        .map(|(_, v)| v.into_owned())
+        .unwrap_or_default()
+}
+
+fn validate(account: &TotpAccount, secret: &str) -> Result<(), String> {
+    if account.account.is_empty() {
+        return Err("Account name is required".to_string());
+    }
+    if !(6..=8).contains(&account.digits) {
+        return Err("TOTP codes must have 6 to 8 digits".to_string());
+    }
+    if account.period == 0 {
+        return Err("TOTP period must be positive".to_string());
+    }
+    base32_decode(secret).map(|_| ())
+}
+
+// Current code for `account`, after the user allowed `requester` to use it.
+// Blocks on a native dialog, so never call this on the main thread.
+pub fn code_with_consent(app: &AppHandle, account: &str, requester: &str) -> Result<TotpCode, String> {
+    let entry = app
+        .state::<TotpState>()
+        .accounts
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|a| a.account == account)
+        .cloned()
+        .ok_or_else(|| format!("Unknown TOTP account: {}", account))?;
+    let name = match &entry.issuer {
+        Some(issuer) => format!("{} ({})", entry.account, issuer),
+        None => entry.account.clone(),
+    };
+    let message = format!("{} wants the two-factor code for {}.\n\nAllow?", requester, name);
+    if !tauri::api::dialog::blocking::ask(None::<&Window>, "Two-factor code", message) {
+        audit::record(app, AuditCategory::Credential, "totp.denied", json!({ "account": account, "requester": requester }));
+        return Err("Two-factor code request was declined".to_string());
+    }
+
+    let secret = base32_decode(&secrets::require(&secret_key(account))?)?;
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    audit::record(app, AuditCategory::Credential, "totp.code", json!({ "account": account, "requester": requester }));
+    Ok(generate(&entry, &secret, now))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_totp_accounts(state: tauri::State<'_, TotpState>) -> Result<Vec<TotpAccount>, String> {
+    Ok(state.accounts.lock().unwrap().clone())
+}
+
+// Add from an otpauth:// URI (QR code contents) or a bare base32 secret
+#[tauri::command]
+#[specta::specta]
+pub async fn add_totp_account(
+    app_handle: AppHandle,
+    state: tauri::State<'_, TotpState>,
+    uri: Option<String>,
+    account: Option<String>,
+    secret: Option<String>,
+) -> Result<TotpAccount, String> {
+    let (entry, secret) = match (uri, account, secret) {
+        (Some(uri), _, _) => parse_uri(&uri)?,
+        (None, Some(account), Some(secret)) => (
+            TotpAccount {
+                account,
+                issuer: None,
+                algorithm: TotpAlgorithm::Sha1,
+                digits: DEFAULT_DIGITS,
+                period: DEFAULT_PERIOD,
+                created_at: chrono::Utc::now().timestamp(),
+            },
+            secret,
+        ),
+        _ => return Err("Provide an otpauth URI or an account and secret".to_string()),
+    };
+    validate(&entry, &secret)?;
+    secrets::set(&secret_key(&entry.account), &secret)?;
+    audit::record(&app_handle, AuditCategory::Settings, "totp.add", json!({ "account": entry.account }));
+
+    let mut accounts = state.accounts.lock().unwrap();
+    accounts.retain(|a| a.account != entry.account);
+    accounts.push(entry.clone());
+    storage::save(&app_handle, TOTP_FILE, &*accounts)?;
+    Ok(entry)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_totp_account(
+    app_handle: AppHandle,
+    state: tauri::State<'_, TotpState>,
+    account: String,
+) -> Result<(), String> {
+    secrets::delete(&secret_key(&account))?;
+    audit::record(&app_handle, AuditCategory::Settings, "totp.delete", json!({ "account": account }));
+
+    let mut accounts = state.accounts.lock().unwrap();
+    accounts.retain(|a| a.account != account);
+    storage::save(&app_handle, TOTP_FILE, &*accounts)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_totp_code(window: Window, account: String) -> Result<TotpCode, String> {
+    let app = window.app_handle();
+    let requester = window.title().unwrap_or_else(|_| window.label().to_string());
+    tauri::async_runtime::spawn_blocking(move || code_with_consent(&app, &account, &requester))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,363 @@
+// Page and text translation
+// Providers: DeepL, Google Cloud Translation, or a local LibreTranslate-compatible
+// server. Page text is collected by an injected script, translated in chunks
+// and written back; translations are cached per URL + target language.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::audit::{self, AuditCategory};
+use crate::{outbound, storage};
+
+const CONFIG_FILE: &str = "translation.json";
+const CACHE_NAME: &str = "translations";
+// Per-request limits; DeepL and Google both cap request sizes around here
+const MAX_CHUNK_CHARS: usize = 4500;
+const MAX_CHUNK_SEGMENTS: usize = 50;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Collects visible text nodes and hands them to `submit_page_text`
+const COLLECT_SCRIPT: &str = r#"(function (targetLang) {
+  if (!window.__TAURI_INVOKE__) return;
+  var skip = { SCRIPT: 1, STYLE: 1, NOSCRIPT: 1, CODE: 1, PRE: 1, TEXTAREA: 1 };
+  var walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT, {
+    acceptNode: function (node) {
+      var parent = node.parentElement;
+      if (!parent || skip[parent.tagName] || parent.isContentEditable) return NodeFilter.FILTER_REJECT;
+      return node.nodeValue.trim() ? NodeFilter.FILTER_ACCEPT : NodeFilter.FILTER_REJECT;
+    }
+  });
+  var nodes = [];
+  while (walker.nextNode()) nodes.push(walker.currentNode);
+  window.__MADEASY_TRANSLATION_NODES__ = nodes;
+  window.__MADEASY_TRANSLATION_ORIGINALS__ = window.__MADEASY_TRANSLATION_ORIGINALS__ ||
+    nodes.map(function (n) { return n.nodeValue; });
+  window.__TAURI_INVOKE__('submit_page_text', {
+    url: location.href,
+    targetLang: targetLang,
+    texts: nodes.map(function (n) { return n.nodeValue.trim(); })
+  });
+})"#;
+
+const APPLY_SCRIPT: &str = r#"(function (translations) {
+  var nodes = window.__MADEASY_TRANSLATION_NODES__ || [];
+  translations.forEach(function (text, i) {
+    if (nodes[i] && text) {
+      var value = nodes[i].nodeValue;
+      var lead = value.match(/^\s*/)[0], trail = value.match(/\s*$/)[0];
+      nodes[i].nodeValue = lead + text + trail;
+    }
+  });
+})"#;
+
+const RESTORE_SCRIPT: &str = r#"(function () {
+  var nodes = window.__MADEASY_TRANSLATION_NODES__ || [];
+  var originals = window.__MADEASY_TRANSLATION_ORIGINALS__ || [];
+  nodes.forEach(function (n, i) { if (originals[i] !== undefined) n.nodeValue = originals[i]; });
+  window.__MADEASY_TRANSLATION_ORIGINALS__ = null;
+})();"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum TranslationConfig {
+    Deepl { api_key: String },
+    Google { api_key: String },
+    // LibreTranslate-compatible server, e.g. http://localhost:5000
+    Local { endpoint: String },
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        TranslationConfig::Local {
+            endpoint: "http://localhost:5000".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TranslationState {
+    config: Mutex<TranslationConfig>,
+}
+
+impl TranslationState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+struct PageTranslated {
+    window_id: String,
+    url: String,
+    target_lang: String,
+    segments: usize,
+    cached_segments: usize,
+}
+
+// Group segments into requests within the provider limits
+fn chunk_segments(segments: &[String]) -> Vec<Vec<String>> {
+    let mut chunks: Vec<Vec<String>> = Vec::new();
+    let mut size = 0;
+    for segment in segments {
+        let start_new = match chunks.last() {
+            None => true,
+            Some(chunk) => chunk.len() >= MAX_CHUNK_SEGMENTS || size + segment.len() > MAX_CHUNK_CHARS,
+        };
+        if start_new {
+            chunks.push(Vec::new());
+            size = 0;
+        }
+        size += segment.len();
+        chunks.last_mut().unwrap().push(segment.clone());
+    }
+    chunks
+}
+
+// Split a line at spaces so no piece exceeds MAX_CHUNK_CHARS
+fn split_line(line: &str) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while rest.len() > MAX_CHUNK_CHARS {
+        let mut cut = MAX_CHUNK_CHARS;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let cut = rest[..cut].rfind(' ').map(|i| i + 1).unwrap_or(cut);
+        pieces.push(rest[..cut].to_string());
+        rest = &rest[cut..];
+    }
+    pieces.push(rest.to_string());
+    pieces
+}
+
+async fn request(
+    app: &AppHandle,
+    config: &TranslationConfig,
+    texts: &[String],
+    target_lang: &str,
+) -> Result<Vec<String>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let (request, pointer) = match config {
+        TranslationConfig::Deepl { api_key } => {
+            // Free-tier keys end in ":fx" and use a separate host
+            let host = if api_key.ends_with(":fx") { "api-free.deepl.com" } else { "api.deepl.com" };
+            let request = client
+                .post(format!("https://{}/v2/translate", host))
+                .header("Authorization", format!("DeepL-Auth-Key {}", api_key))
+                .json(&json!({ "text": texts, "target_lang": target_lang.to_uppercase() }));
+            (request, ("/translations", "text"))
+        }
+        TranslationConfig::Google { api_key } => {
+            let request = client
+                .post("https://translation.googleapis.com/language/translate/v2")
+                .query(&[("key", api_key)])
+                .json(&json!({ "q": texts, "target": target_lang, "format": "text" }));
+            (request, ("/data/translations", "translatedText"))
+        }
+        TranslationConfig::Local { endpoint } => {
+            let request = client
+                .post(format!("{}/translate", endpoint.trim_end_matches('/')))
+                .json(&json!({ "q": texts, "source": "auto", "target": target_lang, "format": "text" }));
+            (request, ("/translatedText", ""))
+        }
+    };
+
+    let body = outbound::json(app, "translation", request).await?;
+
+    let (list, field) = pointer;
+    let items = body
+        .pointer(list)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Unexpected response from translation provider".to_string())?;
+    let translations: Vec<String> = items
+        .iter()
+        .map(|item| {
+            let value = if field.is_empty() { item } else { &item[field] };
+            value.as_str().unwrap_or_default().to_string()
+        })
+        .collect();
+
+    if translations.len() != texts.len() {
+        return Err("Translation provider returned the wrong number of segments".to_string());
+    }
+    Ok(translations)
+}
+
+pub async fn translate_segments(app: &AppHandle, segments: &[String], target_lang: &str) -> Result<Vec<String>, String> {
+    let config = app.state::<TranslationState>().config.lock().unwrap().clone();
+    let mut translated = Vec::with_capacity(segments.len());
+    for chunk in chunk_segments(segments) {
+        translated.extend(request(app, &config, &chunk, target_lang).await?);
+    }
+    Ok(translated)
+}
+
+fn cache_path(app: &AppHandle, url: &str, target_lang: &str) -> Result<PathBuf, String> {
+    let key = hex::encode(Sha256::digest(format!("{}\n{}", url, target_lang).as_bytes()));
+    Ok(storage::cache_dir(app, CACHE_NAME)?.join(format!("{}.json", key)))
+}
+
+fn load_cache(app: &AppHandle, url: &str, target_lang: &str) -> HashMap<String, String> {
+    cache_path(app, url, target_lang)
+        .ok()
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn validate_lang(target_lang: &str) -> Result<(), String> {
+    let valid = (2..=7).contains(&target_lang.len())
+        && target_lang.chars().all(|c| c.is_ascii_alphabetic() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid language code: {}", target_lang))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn translate_text(app_handle: AppHandle, text: String, target_lang: String) -> Result<String, String> {
+    validate_lang(&target_lang)?;
+    let lines: Vec<Vec<String>> = text.lines().map(split_line).collect();
+    // Blank lines are kept as-is rather than sent to the provider
+    let segments: Vec<String> = lines
+        .iter()
+        .flatten()
+        .filter(|p| !p.trim().is_empty())
+        .cloned()
+        .collect();
+    let mut translated = translate_segments(&app_handle, &segments, &target_lang).await?.into_iter();
+
+    let lines: Vec<String> = lines
+        .iter()
+        .map(|pieces| {
+            pieces
+                .iter()
+                .map(|piece| {
+                    if piece.trim().is_empty() {
+                        piece.clone()
+                    } else {
+                        translated.next().unwrap_or_default()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+// Start translating a window; results are applied to the page and announced
+// with a `page-translated` event
+#[tauri::command]
+#[specta::specta]
+pub async fn translate_page(app_handle: AppHandle, window_id: String, target_lang: String) -> Result<(), String> {
+    validate_lang(&target_lang)?;
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    window
+        .eval(&format!(
+            "{}({});",
+            COLLECT_SCRIPT,
+            serde_json::to_string(&target_lang).unwrap_or_default()
+        ))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_page_translation(app_handle: AppHandle, window_id: String) -> Result<(), String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    window.eval(RESTORE_SCRIPT).map_err(|e| e.to_string())
+}
+
+// Called by COLLECT_SCRIPT with the page's text segments
+#[tauri::command]
+#[specta::specta]
+pub async fn submit_page_text(
+    app_handle: AppHandle,
+    window: Window,
+    url: String,
+    target_lang: String,
+    texts: Vec<String>,
+) -> Result<(), String> {
+    validate_lang(&target_lang)?;
+    let mut cache = load_cache(&app_handle, &url, &target_lang);
+    let cached_segments = texts.iter().filter(|t| cache.contains_key(*t)).count();
+
+    let mut missing: Vec<String> = texts.iter().filter(|t| !cache.contains_key(*t)).cloned().collect();
+    missing.sort();
+    missing.dedup();
+    if !missing.is_empty() {
+        let translated = translate_segments(&app_handle, &missing, &target_lang).await?;
+        cache.extend(missing.into_iter().zip(translated));
+        let bytes = serde_json::to_vec(&cache).map_err(|e| e.to_string())?;
+        std::fs::write(cache_path(&app_handle, &url, &target_lang)?, bytes).map_err(|e| e.to_string())?;
+    }
+
+    let translations: Vec<&str> = texts
+        .iter()
+        .map(|t| cache.get(t).map(String::as_str).unwrap_or_default())
+        .collect();
+    let translations = serde_json::to_string(&translations).map_err(|e| e.to_string())?;
+    window
+        .eval(&format!("{}({});", APPLY_SCRIPT, translations))
+        .map_err(|e| e.to_string())?;
+
+    app_handle
+        .emit_all(
+            "page-translated",
+            PageTranslated {
+                window_id: window.label().to_string(),
+                url,
+                target_lang,
+                segments: texts.len(),
+                cached_segments,
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_translation_config(state: tauri::State<'_, TranslationState>) -> Result<TranslationConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_translation_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, TranslationState>,
+    config: TranslationConfig,
+) -> Result<(), String> {
+    if let TranslationConfig::Local { endpoint } = &config {
+        url::Url::parse(endpoint).map_err(|e| format!("Invalid endpoint: {}", e))?;
+    }
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    let provider = match &config {
+        TranslationConfig::Deepl { .. } => "deepl",
+        TranslationConfig::Google { .. } => "google",
+        TranslationConfig::Local { .. } => "local",
+    };
+    audit::record(&app_handle, AuditCategory::Settings, "translation.config", json!({ "provider": provider }));
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
@@ -0,0 +1,374 @@
+// System tray menu
+// The fixed entries (show/hide/new window/quit) wrap a dynamic section managed by the
+// frontend: recent workflows, active downloads, profile switcher and so on
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, CustomMenuItem, Icon, Manager, SystemTray, SystemTrayMenu, SystemTrayMenuItem,
+    SystemTraySubmenu,
+};
+
+use crate::{i18n, kiosk};
+
+// Ids reserved for the fixed entries handled in main.rs
+const BUILTIN_IDS: [&str; 4] = ["show", "hide", "new_window", "quit"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TrayItem {
+    Item {
+        id: String,
+        title: String,
+        #[serde(default = "default_true")]
+        enabled: bool,
+        #[serde(default)]
+        checked: bool,
+    },
+    Separator,
+    Submenu {
+        title: String,
+        items: Vec<TrayItem>,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayStatus {
+    #[default]
+    Idle,
+    WorkflowRunning,
+    DownloadActive,
+    AiResponding,
+}
+
+impl TrayStatus {
+    // Message id of the tooltip
+    fn tooltip(self) -> &'static str {
+        match self {
+            TrayStatus::Idle => "tray-tooltip",
+            TrayStatus::WorkflowRunning => "tray-tooltip-workflow",
+            TrayStatus::DownloadActive => "tray-tooltip-download",
+            TrayStatus::AiResponding => "tray-tooltip-ai",
+        }
+    }
+
+    // Indicator dot colour, or None for the plain icon
+    fn color(self) -> Option<[u8; 3]> {
+        match self {
+            TrayStatus::Idle => None,
+            TrayStatus::WorkflowRunning => Some([59, 130, 246]),
+            TrayStatus::DownloadActive => Some([34, 197, 94]),
+            TrayStatus::AiResponding => Some([168, 85, 247]),
+        }
+    }
+}
+
+// Tray glyph style, chosen by the active theme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum IconVariant {
+    // The full-colour app icon
+    #[default]
+    Color,
+    // White glyph for dark taskbars/panels
+    Light,
+    // Black glyph for light taskbars/panels
+    Dark,
+}
+
+#[derive(Default)]
+pub struct TrayState {
+    items: Mutex<Vec<TrayItem>>,
+    badge: Mutex<u32>,
+    status: Mutex<TrayStatus>,
+    variant: Mutex<IconVariant>,
+}
+
+fn collect_ids<'a>(items: &'a [TrayItem], ids: &mut Vec<&'a str>) {
+    for item in items {
+        match item {
+            TrayItem::Item { id, .. } => ids.push(id),
+            TrayItem::Submenu { items, .. } => collect_ids(items, ids),
+            TrayItem::Separator => {}
+        }
+    }
+}
+
+fn validate(items: &[TrayItem]) -> Result<(), String> {
+    let mut ids = Vec::new();
+    collect_ids(items, &mut ids);
+
+    let mut seen = HashSet::new();
+    for id in ids {
+        if BUILTIN_IDS.contains(&id) {
+            return Err(format!("Tray item id is reserved: {}", id));
+        }
+        if !seen.insert(id) {
+            return Err(format!("Duplicate tray item id: {}", id));
+        }
+    }
+    Ok(())
+}
+
+fn append_items(mut menu: SystemTrayMenu, items: &[TrayItem]) -> SystemTrayMenu {
+    for item in items {
+        menu = match item {
+            TrayItem::Item {
+                id,
+                title,
+                enabled,
+                checked,
+            } => {
+                let mut entry = CustomMenuItem::new(id.clone(), title.clone());
+                if !enabled {
+                    entry = entry.disabled();
+                }
+                if *checked {
+                    entry = entry.selected();
+                }
+                menu.add_item(entry)
+            }
+            TrayItem::Separator => menu.add_native_item(SystemTrayMenuItem::Separator),
+            TrayItem::Submenu { title, items } => menu.add_submenu(SystemTraySubmenu::new(
+                title.clone(),
+                append_items(SystemTrayMenu::new(), items),
+            )),
+        };
+    }
+    menu
+}
+
+// Build the full tray menu around the given dynamic items
+fn build_menu(locale: &str, items: &[TrayItem]) -> SystemTrayMenu {
+    let t = |id| i18n::lookup(locale, id, &[]);
+    let quit = CustomMenuItem::new("quit".to_string(), t("tray-quit"));
+    let hide = CustomMenuItem::new("hide".to_string(), t("tray-hide"));
+    let show = CustomMenuItem::new("show".to_string(), t("tray-show"));
+    let new_window = CustomMenuItem::new("new_window".to_string(), t("tray-new-window"));
+
+    let mut menu = SystemTrayMenu::new()
+        .add_item(show)
+        .add_item(hide)
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(new_window)
+        .add_native_item(SystemTrayMenuItem::Separator);
+
+    if !items.is_empty() {
+        menu = append_items(menu, items).add_native_item(SystemTrayMenuItem::Separator);
+    }
+
+    menu.add_item(quit)
+}
+
+// Create system tray
+pub fn create_system_tray(locale: &str) -> SystemTray {
+    SystemTray::new().with_menu(build_menu(locale, &[]))
+}
+
+// Rebuild the tray menu from the current dynamic items and re-translate the tooltip.
+// Kiosk mode keeps the menu empty; the items show up again once it ends.
+pub fn refresh(app: &AppHandle) -> Result<(), String> {
+    if kiosk::active(app) {
+        return Ok(());
+    }
+    let state = app.state::<TrayState>();
+    let items = state.items.lock().unwrap().clone();
+    let status = *state.status.lock().unwrap();
+    let tray = app.tray_handle();
+    tray.set_menu(build_menu(&i18n::current(app), &items))
+        .map_err(|e| e.to_string())?;
+    tray.set_tooltip(&i18n::text(app, status.tooltip()))
+        .map_err(|e| e.to_string())
+}
+
+// Route a click on a dynamic item back to the frontend
+pub fn dispatch_click(app: &AppHandle, id: &str) {
+    let _ = app.emit_all("tray-item-clicked", id);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_tray_menu(
+    app_handle: AppHandle,
+    state: tauri::State<'_, TrayState>,
+    items: Vec<TrayItem>,
+) -> Result<(), String> {
+    validate(&items)?;
+    *state.items.lock().unwrap() = items;
+    refresh(&app_handle)
+}
+
+const BASE_ICON: &[u8] = include_bytes!("../icons/32x32.png");
+const BADGE_COLOR: [u8; 3] = [220, 38, 38];
+
+// 3x5 bitmap glyphs for the badge count, one row per u8 (low three bits):
+// the digits 0-9, then PLUS for counts above 9
+const GLYPHS: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b010, 0b111, 0b010, 0b000],
+];
+const PLUS: usize = 10;
+
+struct Canvas {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl Canvas {
+    fn put(&mut self, x: i32, y: i32, color: [u8; 3], alpha: u8) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+        self.rgba[i..i + 3].copy_from_slice(&color);
+        self.rgba[i + 3] = alpha;
+    }
+
+    fn circle(&mut self, cx: i32, cy: i32, r: i32, color: [u8; 3]) {
+        for y in cy - r..=cy + r {
+            for x in cx - r..=cx + r {
+                if (x - cx).pow(2) + (y - cy).pow(2) <= r * r {
+                    self.put(x, y, color, 255);
+                }
+            }
+        }
+    }
+
+    fn glyph(&mut self, left: i32, top: i32, glyph: usize, scale: i32, color: [u8; 3]) {
+        for (row, bits) in GLYPHS[glyph].iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        self.put(left + col * scale + dx, top + row as i32 * scale + dy, color, 255);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Compose the tray icon with a status dot and (where drawn) a count badge.
+// Template images on macOS must be monochrome, so colours collapse to black there
+// and the count is shown as the tray title instead.
+fn render_icon(count: u32, status: TrayStatus, variant: IconVariant, template: bool) -> Result<Icon, String> {
+    let base = image::load_from_memory(BASE_ICON)
+        .map_err(|e| e.to_string())?
+        .to_rgba8();
+    let (width, height) = base.dimensions();
+    let mut canvas = Canvas {
+        rgba: base.into_raw(),
+        width,
+        height,
+    };
+    let tint = |color: [u8; 3]| if template { [0, 0, 0] } else { color };
+
+    let glyph = match variant {
+        _ if template => Some([0, 0, 0]),
+        IconVariant::Color => None,
+        IconVariant::Light => Some([255, 255, 255]),
+        IconVariant::Dark => Some([0, 0, 0]),
+    };
+    if let Some(glyph) = glyph {
+        for pixel in canvas.rgba.chunks_mut(4) {
+            pixel[..3].copy_from_slice(&glyph);
+        }
+    }
+
+    let size = width.min(height) as i32;
+    if let Some(color) = status.color() {
+        let r = size / 6;
+        canvas.circle(r + 1, size - r - 2, r, tint(color));
+    }
+
+    if count > 0 && !template {
+        let r = size * 7 / 20;
+        let (cx, cy) = (size - r - 1, r + 1);
+        canvas.circle(cx, cy, r, BADGE_COLOR);
+        let scale = (size / 16).max(1);
+        let top = cy - scale * 5 / 2;
+        if count > 9 {
+            // "9+", with a one-column gap
+            canvas.glyph(cx - scale * 7 / 2, top, 9, scale, [255, 255, 255]);
+            canvas.glyph(cx + scale / 2, top, PLUS, scale, [255, 255, 255]);
+        } else {
+            canvas.glyph(cx - scale * 3 / 2, top, count as usize, scale, [255, 255, 255]);
+        }
+    }
+
+    Ok(Icon::Rgba {
+        rgba: canvas.rgba,
+        width,
+        height,
+    })
+}
+
+// Redraw the tray icon from the current badge count and status
+fn apply_icon(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<TrayState>();
+    let count = *state.badge.lock().unwrap();
+    let status = *state.status.lock().unwrap();
+    let variant = *state.variant.lock().unwrap();
+    let template = cfg!(target_os = "macos");
+    let tray = app.tray_handle();
+
+    tray.set_icon(render_icon(count, status, variant, template)?)
+        .map_err(|e| e.to_string())?;
+    tray.set_tooltip(&i18n::text(app, status.tooltip())).map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "macos")]
+    {
+        tray.set_icon_as_template(true).map_err(|e| e.to_string())?;
+        let title = if count > 0 { count.to_string() } else { String::new() };
+        tray.set_title(&title).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// Update the status variant of the tray icon (used by downloads, workflows and AI)
+pub fn set_status(app: &AppHandle, status: TrayStatus) -> Result<(), String> {
+    *app.state::<TrayState>().status.lock().unwrap() = status;
+    apply_icon(app)
+}
+
+pub fn set_variant(app: &AppHandle, variant: IconVariant) -> Result<(), String> {
+    *app.state::<TrayState>().variant.lock().unwrap() = variant;
+    apply_icon(app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_tray_badge(
+    app_handle: AppHandle,
+    state: tauri::State<'_, TrayState>,
+    count: u32,
+) -> Result<(), String> {
+    *state.badge.lock().unwrap() = count;
+    apply_icon(&app_handle)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_tray_status(app_handle: AppHandle, status: TrayStatus) -> Result<(), String> {
+    set_status(&app_handle, status)
+}
@@ -0,0 +1,350 @@
+// Export uploads to SFTP and FTPS servers
+// SFTP servers must be pinned by host key fingerprint (`test_sftp_connection`
+// reports it); FTPS relies on normal certificate verification. Passwords are
+// kept in the keychain. Transient network failures are retried with backoff.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::{bandwidth, secrets, storage};
+
+const TARGETS_FILE: &str = "upload-targets.json";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    Sftp,
+    Ftps,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct UploadTarget {
+    id: String,
+    name: String,
+    protocol: Protocol,
+    host: String,
+    port: u16,
+    username: String,
+    #[serde(default)]
+    remote_dir: String,
+    // OpenSSH-style "SHA256:..." fingerprint the SFTP server must present
+    #[serde(default)]
+    host_key_sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ConnectionTest {
+    host_key_sha256: Option<String>,
+    // Whether the presented key matched the pinned one
+    pinned: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadAction {
+    pub target_id: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct UploadState {
+    targets: Mutex<Vec<UploadTarget>>,
+}
+
+impl UploadState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            targets: Mutex::new(storage::load(app, TARGETS_FILE)),
+        }
+    }
+}
+
+// An upload failure and whether retrying may help
+struct Failure {
+    message: String,
+    transient: bool,
+}
+
+impl Failure {
+    fn fatal(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            transient: false,
+        }
+    }
+
+    fn transient(message: impl ToString) -> Self {
+        Self {
+            message: message.to_string(),
+            transient: true,
+        }
+    }
+}
+
+fn password_key(target_id: &str) -> String {
+    format!("upload-{}", target_id)
+}
+
+fn connect_tcp(host: &str, port: u16) -> Result<TcpStream, Failure> {
+    let address = (host, port)
+        .to_socket_addrs()
+        .map_err(Failure::transient)?
+        .next()
+        .ok_or_else(|| Failure::fatal(format!("Could not resolve {}", host)))?;
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(Failure::transient)?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT * 4)).map_err(Failure::transient)?;
+    Ok(stream)
+}
+
+fn remote_path(target: &UploadTarget, file_name: &str) -> String {
+    let dir = target.remote_dir.trim_end_matches('/');
+    if dir.is_empty() {
+        file_name.to_string()
+    } else {
+        format!("{}/{}", dir, file_name)
+    }
+}
+
+fn sftp_session(target: &UploadTarget, password: &str) -> Result<(ssh2::Session, String), Failure> {
+    let mut session = ssh2::Session::new().map_err(|e| Failure::fatal(e.to_string()))?;
+    session.set_tcp_stream(connect_tcp(&target.host, target.port)?);
+    session.handshake().map_err(Failure::transient)?;
+
+    let hash = session
+        .host_key_hash(ssh2::HashType::Sha256)
+        .ok_or_else(|| Failure::fatal("Server did not present a host key"))?;
+    let fingerprint = format!(
+        "SHA256:{}",
+        base64::engine::general_purpose::STANDARD_NO_PAD.encode(hash)
+    );
+
+    match &target.host_key_sha256 {
+        Some(pinned) if pinned == &fingerprint => {}
+        Some(_) => {
+            return Err(Failure::fatal(format!(
+                "Host key mismatch for {}: server presented {}",
+                target.host, fingerprint
+            )))
+        }
+        None => return Err(Failure::fatal("SFTP target has no pinned host key; test the connection first")),
+    }
+
+    session
+        .userauth_password(&target.username, password)
+        .map_err(|e| Failure::fatal(format!("Authentication failed: {}", e)))?;
+    Ok((session, fingerprint))
+}
+
+fn upload_sftp(target: &UploadTarget, password: &str, files: &[PathBuf]) -> Result<(), Failure> {
+    let (session, _) = sftp_session(target, password)?;
+    let sftp = session.sftp().map_err(Failure::transient)?;
+    for file in files {
+        let name = file.file_name().and_then(|n| n.to_str()).unwrap_or("export");
+        let mut local = std::fs::File::open(file).map_err(|e| Failure::fatal(e.to_string()))?;
+        let mut remote = sftp
+            .create(Path::new(&remote_path(target, name)))
+            .map_err(|e| Failure::fatal(format!("Cannot create {}: {}", name, e)))?;
+        std::io::copy(&mut local, &mut remote).map_err(Failure::transient)?;
+    }
+    Ok(())
+}
+
+fn upload_ftps(target: &UploadTarget, password: &str, files: &[PathBuf]) -> Result<(), Failure> {
+    use suppaftp::native_tls::TlsConnector;
+    use suppaftp::types::FileType;
+    use suppaftp::{NativeTlsConnector, NativeTlsFtpStream};
+
+    let stream = NativeTlsFtpStream::connect_timeout(
+        (target.host.as_str(), target.port)
+            .to_socket_addrs()
+            .map_err(Failure::transient)?
+            .next()
+            .ok_or_else(|| Failure::fatal(format!("Could not resolve {}", target.host)))?,
+        CONNECT_TIMEOUT,
+    )
+    .map_err(Failure::transient)?;
+    let connector = TlsConnector::new().map_err(|e| Failure::fatal(e.to_string()))?;
+    let mut ftp = stream
+        .into_secure(NativeTlsConnector::from(connector), &target.host)
+        .map_err(|e| Failure::fatal(format!("TLS negotiation failed: {}", e)))?;
+    ftp.login(&target.username, password)
+        .map_err(|e| Failure::fatal(format!("Authentication failed: {}", e)))?;
+    if !target.remote_dir.is_empty() {
+        ftp.cwd(&target.remote_dir).map_err(|e| Failure::fatal(e.to_string()))?;
+    }
+    ftp.transfer_type(FileType::Binary).map_err(Failure::transient)?;
+
+    for file in files {
+        let name = file.file_name().and_then(|n| n.to_str()).unwrap_or("export");
+        let mut local = std::fs::File::open(file).map_err(|e| Failure::fatal(e.to_string()))?;
+        ftp.put_file(name, &mut local).map_err(Failure::transient)?;
+    }
+    let _ = ftp.quit();
+    Ok(())
+}
+
+fn find_target(app: &AppHandle, target_id: &str) -> Result<UploadTarget, String> {
+    app.state::<UploadState>()
+        .targets
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|t| t.id == target_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown upload target: {}", target_id))
+}
+
+pub async fn upload(app: &AppHandle, target_id: &str, paths: &[String]) -> Result<(), String> {
+    let target = find_target(app, target_id)?;
+    let password = secrets::require(&password_key(&target.id))?;
+    audit::record(app, AuditCategory::Credential, "upload.password", json!({ "target": target.id }));
+    let files: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    if let Some(missing) = files.iter().find(|f| !f.is_file()) {
+        return Err(format!("File not found: {}", missing.display()));
+    }
+
+    let mut attempt = 1;
+    loop {
+        let (target, password, files) = (target.clone(), password.clone(), files.clone());
+        let result = tauri::async_runtime::spawn_blocking(move || match target.protocol {
+            Protocol::Sftp => upload_sftp(&target, &password, &files),
+            Protocol::Ftps => upload_ftps(&target, &password, &files),
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+        match result {
+            Ok(()) => {
+                let scheme = match target.protocol {
+                    Protocol::Sftp => "sftp",
+                    Protocol::Ftps => "ftps",
+                };
+                let server = format!("{}://{}", scheme, target.host);
+                for file in &files {
+                    let size = std::fs::metadata(file).map_or(0, |m| m.len());
+                    bandwidth::record(app, &server, size, 0);
+                }
+                audit::record(
+                    app,
+                    AuditCategory::Export,
+                    "upload.files",
+                    json!({ "target": target.id, "host": target.host, "paths": paths }),
+                );
+                return Ok(());
+            }
+            Err(failure) if failure.transient && attempt < MAX_ATTEMPTS => {
+                tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+            }
+            Err(failure) => return Err(failure.message),
+        }
+    }
+}
+
+pub async fn run_action(app: &AppHandle, action: &UploadAction) -> Result<Value, String> {
+    upload(app, &action.target_id, &action.paths).await?;
+    Ok(json!({ "uploaded": action.paths }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_upload_targets(state: tauri::State<'_, UploadState>) -> Result<Vec<UploadTarget>, String> {
+    Ok(state.targets.lock().unwrap().clone())
+}
+
+// Create or update a target (matched by id); a missing password keeps the stored one
+#[tauri::command]
+#[specta::specta]
+pub async fn save_upload_target(
+    app_handle: AppHandle,
+    state: tauri::State<'_, UploadState>,
+    target: UploadTarget,
+    password: Option<String>,
+) -> Result<(), String> {
+    if target.id.is_empty() || target.host.trim().is_empty() || target.username.is_empty() {
+        return Err("Upload targets need an id, host and username".to_string());
+    }
+    audit::record(
+        &app_handle,
+        AuditCategory::Settings,
+        "upload.save_target",
+        json!({ "target": target.id, "host": target.host, "password_changed": password.is_some() }),
+    );
+    if let Some(password) = password {
+        secrets::set(&password_key(&target.id), &password)?;
+    }
+    let mut targets = state.targets.lock().unwrap();
+    match targets.iter_mut().find(|t| t.id == target.id) {
+        Some(existing) => *existing = target,
+        None => targets.push(target),
+    }
+    storage::save(&app_handle, TARGETS_FILE, &*targets)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_upload_target(
+    app_handle: AppHandle,
+    state: tauri::State<'_, UploadState>,
+    target_id: String,
+) -> Result<(), String> {
+    secrets::delete(&password_key(&target_id))?;
+    audit::record(&app_handle, AuditCategory::Settings, "upload.delete_target", json!({ "target": target_id }));
+    let mut targets = state.targets.lock().unwrap();
+    targets.retain(|t| t.id != target_id);
+    storage::save(&app_handle, TARGETS_FILE, &*targets)
+}
+
+// Connect to an SFTP target and report its host key fingerprint. Authentication
+// is only attempted once the fingerprint matches the pinned one.
+#[tauri::command]
+#[specta::specta]
+pub async fn test_sftp_connection(app_handle: AppHandle, target_id: String) -> Result<ConnectionTest, String> {
+    let target = find_target(&app_handle, &target_id)?;
+    if target.protocol != Protocol::Sftp {
+        return Err("Not an SFTP target".to_string());
+    }
+    let password = secrets::get(&password_key(&target.id))?;
+    if password.is_some() {
+        audit::record(&app_handle, AuditCategory::Credential, "upload.password", json!({ "target": target.id }));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut session = ssh2::Session::new().map_err(|e| e.to_string())?;
+        session.set_tcp_stream(connect_tcp(&target.host, target.port).map_err(|f| f.message)?);
+        session.handshake().map_err(|e| e.to_string())?;
+        let fingerprint = session.host_key_hash(ssh2::HashType::Sha256).map(|hash| {
+            format!("SHA256:{}", base64::engine::general_purpose::STANDARD_NO_PAD.encode(hash))
+        });
+        let pinned = target.host_key_sha256.is_some() && target.host_key_sha256 == fingerprint;
+        drop(session);
+
+        if pinned {
+            let password = password.ok_or_else(|| "No password stored for this target".to_string())?;
+            sftp_session(&target, &password).map_err(|f| f.message)?;
+        }
+        Ok(ConnectionTest {
+            host_key_sha256: fingerprint,
+            pinned,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn upload_files(app_handle: AppHandle, target_id: String, paths: Vec<String>) -> Result<(), String> {
+    upload(&app_handle, &target_id, &paths).await
+}
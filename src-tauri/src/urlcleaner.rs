@@ -0,0 +1,268 @@
+// Tracking-parameter removal
+// Query parameters matching the rule list (utm_*, fbclid, gclid, ...) are
+// stripped from links before they are followed and from the address of every
+// loaded page, so they neither reach the server nor end up in history. Rules
+// can be refreshed from a JSON list at `rules_url`; URLs matching an exception
+// pattern are left alone. `clean_url` is also what the frontend uses when
+// copying links.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{bandwidth, matching, storage, windowpool};
+
+const CONFIG_FILE: &str = "url-cleaner.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CleanerRule {
+    // Parameter name, `*` wildcards allowed
+    param: String,
+    // Page URL patterns the rule is limited to (empty = all sites)
+    #[serde(default)]
+    sites: Vec<String>,
+}
+
+impl CleanerRule {
+    fn new(param: &str, sites: &[&str]) -> Self {
+        Self {
+            param: param.to_string(),
+            sites: sites.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+fn default_rules() -> Vec<CleanerRule> {
+    let mut rules: Vec<CleanerRule> = [
+        "utm_*", "fbclid", "gclid", "gclsrc", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "twclid", "ttclid",
+        "igshid", "mc_cid", "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "li_fat_id", "oly_anon_id", "oly_enc_id",
+        "vero_id", "_openstat", "__s",
+    ]
+    .iter()
+    .map(|param| CleanerRule::new(param, &[]))
+    .collect();
+    rules.push(CleanerRule::new("ref_src", &["*://twitter.com/*", "*://x.com/*"]));
+    rules.push(CleanerRule::new("si", &["*://open.spotify.com/*", "*://youtu.be/*"]));
+    rules.push(CleanerRule::new("pd_rd_*", &["*://*.amazon.*/*"]));
+    rules.push(CleanerRule::new("pf_rd_*", &["*://*.amazon.*/*"]));
+    rules
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct CleanerConfig {
+    enabled: bool,
+    rules: Vec<CleanerRule>,
+    // URL patterns never cleaned, e.g. sites that break without their parameters
+    exceptions: Vec<String>,
+    // JSON list of rules, either `[...]` or `{"rules": [...]}`
+    rules_url: Option<String>,
+    rules_updated_at: Option<i64>,
+}
+
+impl Default for CleanerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: default_rules(),
+            exceptions: Vec::new(),
+            rules_url: None,
+            rules_updated_at: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CleanerState {
+    config: Mutex<CleanerConfig>,
+}
+
+impl CleanerState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+        }
+    }
+}
+
+#[derive(Deserialize, Type)]
+#[serde(untagged)]
+enum RuleList {
+    Bare(Vec<CleanerRule>),
+    Wrapped { rules: Vec<CleanerRule> },
+}
+
+fn clean_with(config: &CleanerConfig, url: &str) -> String {
+    if !config.enabled || config.exceptions.iter().any(|p| matching::wildcard_match(p, url)) {
+        return url.to_string();
+    }
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.to_string();
+    };
+    let Some(query) = parsed.query().map(str::to_string) else {
+        return url.to_string();
+    };
+    let rules: Vec<&CleanerRule> = config
+        .rules
+        .iter()
+        .filter(|r| matching::any_match(&r.sites, url))
+        .collect();
+    // Kept parameters stay exactly as written; re-serializing decoded pairs
+    // would turn `%20` into `+` and `?flag` into `?flag=`
+    let segments: Vec<&str> = query.split('&').collect();
+    let kept: Vec<&str> = segments
+        .iter()
+        .copied()
+        .filter(|segment| {
+            let Some((name, _)) = url::form_urlencoded::parse(segment.as_bytes()).next() else {
+                return true;
+            };
+            !rules.iter().any(|r| matching::wildcard_match(&r.param, &name))
+        })
+        .collect();
+    if kept.len() == segments.len() {
+        return url.to_string();
+    }
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.set_query(Some(&kept.join("&")));
+    }
+    parsed.to_string()
+}
+
+pub fn clean(app: &AppHandle, url: &str) -> String {
+    clean_with(&app.state::<CleanerState>().config.lock().unwrap(), url)
+}
+
+// Page-side cleaner: fixes the current address before page scripts read it
+// and rewrites links as they are clicked
+pub fn script(app: &AppHandle) -> String {
+    let config = app.state::<CleanerState>().config.lock().unwrap().clone();
+    if !config.enabled {
+        return String::new();
+    }
+    let rules: Vec<serde_json::Value> = config
+        .rules
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "param": matching::wildcard_js_regex(&r.param),
+                "sites": r.sites.iter().map(|s| matching::wildcard_js_regex(s)).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let exceptions: Vec<String> = config.exceptions.iter().map(|p| matching::wildcard_js_regex(p)).collect();
+    format!(
+        r#"(function() {{
+  if (window.__madeasyUrlCleaner) return;
+  window.__madeasyUrlCleaner = true;
+  var rules = {rules};
+  var exceptions = {exceptions};
+  var test = function(p, s) {{ return new RegExp(p, 'i').test(s); }};
+  var clean = function(href) {{
+    var url;
+    try {{ url = new URL(href, location.href); }} catch (e) {{ return href; }}
+    if (!url.search || exceptions.some(function(p) {{ return test(p, url.href); }})) return href;
+    var active = rules.filter(function(r) {{ return !r.sites.length || r.sites.some(function(p) {{ return test(p, url.href); }}); }});
+    var segments = url.search.slice(1).split('&');
+    var kept = segments.filter(function(segment) {{
+      var name = segment.split('=')[0].replace(/\+/g, ' ');
+      try {{ name = decodeURIComponent(name); }} catch (e) {{}}
+      return !active.some(function(r) {{ return test(r.param, name); }});
+    }});
+    if (kept.length === segments.length) return href;
+    url.search = kept.join('&');
+    return url.href;
+  }};
+  var current = clean(location.href);
+  if (current !== location.href) history.replaceState(history.state, '', current);
+  var rewrite = function(event) {{
+    var link = event.target.closest && event.target.closest('a[href]');
+    if (link && /^https?:/.test(link.href)) {{
+      var cleaned = clean(link.href);
+      if (cleaned !== link.href) link.href = cleaned;
+    }}
+  }};
+  document.addEventListener('mousedown', rewrite, true);
+  document.addEventListener('click', rewrite, true);
+  document.addEventListener('auxclick', rewrite, true);
+}})();"#,
+        rules = serde_json::Value::from(rules),
+        exceptions = serde_json::Value::from(exceptions),
+    )
+}
+
+pub fn inject(window: &Window) {
+    let script = script(&window.app_handle());
+    if !script.is_empty() {
+        let _ = window.eval(&script);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clean_url(app_handle: AppHandle, url: String) -> Result<String, String> {
+    Ok(clean(&app_handle, &url))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_url_cleaner_config(state: tauri::State<'_, CleanerState>) -> Result<CleanerConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_url_cleaner_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, CleanerState>,
+    config: CleanerConfig,
+) -> Result<(), String> {
+    if config.rules.iter().any(|r| r.param.trim().is_empty()) {
+        return Err("Rule parameter names cannot be empty".to_string());
+    }
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    windowpool::drain(&app_handle);
+    Ok(())
+}
+
+// Replace the rules with the list published at `rules_url`
+#[tauri::command]
+#[specta::specta]
+pub async fn update_url_cleaner_rules(
+    app_handle: AppHandle,
+    state: tauri::State<'_, CleanerState>,
+) -> Result<CleanerConfig, String> {
+    let rules_url = state
+        .config
+        .lock()
+        .unwrap()
+        .rules_url
+        .clone()
+        .ok_or("No rules URL configured")?;
+    let response = reqwest::get(&rules_url).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("Fetching rules failed: {}", response.status()));
+    }
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    bandwidth::record(&app_handle, &rules_url, 0, body.len() as u64);
+    let rules = match serde_json::from_slice::<RuleList>(&body).map_err(|e| format!("Invalid rule list: {}", e))? {
+        RuleList::Bare(rules) | RuleList::Wrapped { rules } => rules,
+    };
+    if rules.is_empty() || rules.iter().any(|r| r.param.trim().is_empty()) {
+        return Err("Rule list is empty or has rules without a parameter".to_string());
+    }
+
+    let updated = {
+        let mut config = state.config.lock().unwrap();
+        config.rules = rules;
+        config.rules_updated_at = Some(chrono::Utc::now().timestamp());
+        storage::save(&app_handle, CONFIG_FILE, &*config)?;
+        config.clone()
+    };
+    windowpool::drain(&app_handle);
+    Ok(updated)
+}
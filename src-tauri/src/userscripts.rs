@@ -0,0 +1,317 @@
+// Greasemonkey-style userscripts
+// Scripts are stored with their `// ==UserScript==` metadata parsed into match
+// patterns and a run-at time. document-start scripts are baked into each new
+// browser window's initialization script (so they apply to windows opened
+// after a change); document-end scripts are evaluated on every page load.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::{matching, pagebridge, safemode, storage, windowpool};
+
+const SCRIPTS_FILE: &str = "userscripts.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RunAt {
+    DocumentStart,
+    // document-idle is treated as document-end
+    #[default]
+    DocumentEnd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Userscript {
+    id: String,
+    name: String,
+    #[serde(default)]
+    namespace: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+    // @match/@include patterns with `*` wildcards (empty = all pages)
+    #[serde(default)]
+    matches: Vec<String>,
+    #[serde(default)]
+    excludes: Vec<String>,
+    #[serde(default)]
+    run_at: RunAt,
+    enabled: bool,
+    source: String,
+    updated_at: i64,
+}
+
+impl Userscript {
+    fn applies_to(&self, url: &str) -> bool {
+        self.enabled
+            && matching::any_match(&self.matches, url)
+            && !self.excludes.iter().any(|p| matching::wildcard_match(p, url))
+    }
+
+    // Run in its own scope so scripts can't break each other
+    fn wrapped(&self) -> String {
+        format!(
+            "(function() {{ try {{\n{}\n}} catch (e) {{ console.error('Userscript {} failed', e); }} }})();",
+            self.source,
+            self.name.replace('\\', "").replace('\'', "")
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct UserscriptState {
+    scripts: Mutex<Vec<Userscript>>,
+}
+
+impl UserscriptState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            scripts: Mutex::new(storage::load(app, SCRIPTS_FILE)),
+        }
+    }
+}
+
+struct Metadata {
+    name: Option<String>,
+    namespace: String,
+    version: String,
+    description: String,
+    matches: Vec<String>,
+    excludes: Vec<String>,
+    run_at: RunAt,
+}
+
+// Greasemonkey's special pattern for every page
+fn normalize_pattern(pattern: &str) -> String {
+    match pattern {
+        "<all_urls>" => "*".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_metadata(source: &str) -> Metadata {
+    let mut meta = Metadata {
+        name: None,
+        namespace: String::new(),
+        version: String::new(),
+        description: String::new(),
+        matches: Vec::new(),
+        excludes: Vec::new(),
+        run_at: RunAt::DocumentEnd,
+    };
+    let mut in_block = false;
+    for line in source.lines().map(str::trim) {
+        if line.starts_with("// ==UserScript==") {
+            in_block = true;
+            continue;
+        }
+        if line.starts_with("// ==/UserScript==") {
+            break;
+        }
+        let Some(entry) = line.strip_prefix("//").map(str::trim).filter(|_| in_block) else {
+            continue;
+        };
+        let Some(entry) = entry.strip_prefix('@') else { continue };
+        let (key, value) = entry.split_once(char::is_whitespace).unwrap_or((entry, ""));
+        let value = value.trim().to_string();
+        match key {
+            "name" => meta.name = Some(value),
+            "namespace" => meta.namespace = value,
+            "version" => meta.version = value,
+            "description" => meta.description = value,
+            "match" | "include" => meta.matches.push(normalize_pattern(&value)),
+            "exclude" | "exclude-match" => meta.excludes.push(normalize_pattern(&value)),
+            "run-at" => {
+                meta.run_at = match value.as_str() {
+                    "document-start" => RunAt::DocumentStart,
+                    _ => RunAt::DocumentEnd,
+                }
+            }
+            _ => {}
+        }
+    }
+    meta
+}
+
+fn js_patterns(patterns: &[String]) -> String {
+    let regexes: Vec<String> = patterns.iter().map(|p| matching::wildcard_js_regex(p)).collect();
+    serde_json::to_string(&regexes).unwrap_or_else(|_| "[]".to_string())
+}
+
+// Initialization script for new webviews: runs the enabled document-start
+// scripts whose patterns match the page being loaded. Like `inject`, it
+// leaves the app's own pages alone, since they can invoke every command.
+pub fn initialization_script(app: &AppHandle) -> String {
+    if safemode::active(app) {
+        return String::new();
+    }
+    let state = app.state::<UserscriptState>();
+    let scripts = state.scripts.lock().unwrap();
+    let app_origins = serde_json::to_string(&pagebridge::app_origins(app)).unwrap_or_else(|_| "[]".to_string());
+    let mut script = format!(
+        "(function() {{\n  if (window.top !== window.self) return;\n  \
+         if (!/^https?:$/.test(location.protocol) || {}.indexOf(location.origin) >= 0) return;\n  \
+         var url = location.href;\n  \
+         var test = function(patterns) {{ return patterns.some(function(p) {{ return new RegExp(p, 'i').test(url); }}); }};\n",
+        app_origins
+    );
+    for userscript in scripts.iter().filter(|s| s.enabled && s.run_at == RunAt::DocumentStart) {
+        let matches = if userscript.matches.is_empty() {
+            "true".to_string()
+        } else {
+            format!("test({})", js_patterns(&userscript.matches))
+        };
+        script.push_str(&format!(
+            "  if ({} && !test({})) {{\n{}\n  }}\n",
+            matches,
+            js_patterns(&userscript.excludes),
+            userscript.wrapped()
+        ));
+    }
+    script.push_str("})();");
+    script
+}
+
+// Run matching document-end scripts after a navigation. Only web pages get
+// them: the main window and other app pages have full command access.
+pub fn inject(window: &Window, url: &str) {
+    let app = window.app_handle();
+    if safemode::active(&app) || window.label() == "main" {
+        return;
+    }
+    // Anything outside http(s) counts as an app page
+    if url::Url::parse(url).map_or(true, |parsed| pagebridge::is_app_page(&app, &parsed)) {
+        return;
+    }
+    let state = window.state::<UserscriptState>();
+    let scripts = state.scripts.lock().unwrap();
+    for userscript in scripts
+        .iter()
+        .filter(|s| s.run_at == RunAt::DocumentEnd && s.applies_to(url))
+    {
+        let _ = window.eval(&userscript.wrapped());
+    }
+}
+
+// Recovery: disable every script, returning how many were enabled
+pub fn disable_all(app: &AppHandle) -> Result<usize, String> {
+    let state = app.state::<UserscriptState>();
+    let mut scripts = state.scripts.lock().unwrap();
+    let mut disabled = 0;
+    for script in scripts.iter_mut().filter(|s| s.enabled) {
+        script.enabled = false;
+        disabled += 1;
+    }
+    storage::save(app, SCRIPTS_FILE, &*scripts)?;
+    Ok(disabled)
+}
+
+// Create or update a script from its source. Scripts are identified by id or,
+// like Greasemonkey, by name and namespace.
+fn upsert(app: &AppHandle, id: Option<String>, source: String) -> Result<Userscript, String> {
+    let meta = parse_metadata(&source);
+    let name = meta
+        .name
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| "Userscript has no @name in its ==UserScript== block".to_string())?;
+
+    let state = app.state::<UserscriptState>();
+    let mut scripts = state.scripts.lock().unwrap();
+    let existing = match &id {
+        Some(id) => Some(
+            scripts
+                .iter()
+                .position(|s| &s.id == id)
+                .ok_or_else(|| format!("Unknown userscript: {}", id))?,
+        ),
+        None => scripts
+            .iter()
+            .position(|s| s.name == name && s.namespace == meta.namespace),
+    };
+    let now = chrono::Utc::now().timestamp();
+    let script = Userscript {
+        id: existing
+            .map(|i| scripts[i].id.clone())
+            .unwrap_or_else(|| format!("us-{}", chrono::Utc::now().timestamp_millis())),
+        name,
+        namespace: meta.namespace,
+        version: meta.version,
+        description: meta.description,
+        matches: meta.matches,
+        excludes: meta.excludes,
+        run_at: meta.run_at,
+        enabled: existing.map_or(true, |i| scripts[i].enabled),
+        source,
+        updated_at: now,
+    };
+    match existing {
+        Some(i) => scripts[i] = script.clone(),
+        None => scripts.push(script.clone()),
+    }
+    storage::save(app, SCRIPTS_FILE, &*scripts)?;
+    Ok(script)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_userscripts(state: tauri::State<'_, UserscriptState>) -> Result<Vec<Userscript>, String> {
+    Ok(state.scripts.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn save_userscript(app_handle: AppHandle, id: Option<String>, source: String) -> Result<Userscript, String> {
+    let script = upsert(&app_handle, id, source)?;
+    windowpool::drain(&app_handle);
+    Ok(script)
+}
+
+// Import an existing .user.js file
+#[tauri::command]
+#[specta::specta]
+pub async fn import_userscript(app_handle: AppHandle, path: String) -> Result<Userscript, String> {
+    let source = std::fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let script = upsert(&app_handle, None, source)?;
+    windowpool::drain(&app_handle);
+    Ok(script)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_userscript_enabled(
+    app_handle: AppHandle,
+    state: tauri::State<'_, UserscriptState>,
+    id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    {
+        let mut scripts = state.scripts.lock().unwrap();
+        let script = scripts
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| format!("Unknown userscript: {}", id))?;
+        script.enabled = enabled;
+        storage::save(&app_handle, SCRIPTS_FILE, &*scripts)?;
+    }
+    windowpool::drain(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_userscript(
+    app_handle: AppHandle,
+    state: tauri::State<'_, UserscriptState>,
+    id: String,
+) -> Result<(), String> {
+    {
+        let mut scripts = state.scripts.lock().unwrap();
+        scripts.retain(|s| s.id != id);
+        storage::save(&app_handle, SCRIPTS_FILE, &*scripts)?;
+    }
+    windowpool::drain(&app_handle);
+    Ok(())
+}
@@ -0,0 +1,207 @@
+// Validation and normalization of extracted rows
+// Applied by the workflow engine before export: emails are syntax-checked
+// (optionally against the domain's MX records), phones normalized to E.164,
+// URLs canonicalized and required fields enforced. Each row gets a
+// `quality_ok` / `quality_flags` pair describing what was wrong with it.
+
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::TokioAsyncResolver;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use url::Url;
+
+use crate::workflow::{self, Row};
+
+// Query parameters that only track the visitor
+const TRACKING_PARAMS: [&str; 6] = ["fbclid", "gclid", "msclkid", "mc_cid", "mc_eid", "ref"];
+
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationRules {
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+    #[serde(default)]
+    pub email_fields: Vec<String>,
+    #[serde(default)]
+    pub phone_fields: Vec<String>,
+    #[serde(default)]
+    pub url_fields: Vec<String>,
+    // ISO country used for phone numbers without a country code, e.g. "NO"
+    #[serde(default)]
+    pub default_region: Option<String>,
+    #[serde(default)]
+    pub check_mx: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ValidationSummary {
+    total: usize,
+    flagged: usize,
+    // Flag -> number of rows carrying it
+    flags: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ValidationResult {
+    rows: Vec<Row>,
+    summary: ValidationSummary,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateAction {
+    #[serde(flatten)]
+    pub rules: ValidationRules,
+}
+
+fn text(row: &Row, field: &str) -> Option<String> {
+    Some(workflow::cell_text(row.get(field)).trim().to_string()).filter(|s| !s.is_empty())
+}
+
+pub fn normalize_email(value: &str) -> Option<String> {
+    let value = value.trim().trim_start_matches("mailto:");
+    let address = lettre::Address::from_str(value).ok()?;
+    // Local parts are case-sensitive in theory, never in practice
+    Some(format!("{}@{}", address.user(), address.domain().to_lowercase()))
+}
+
+pub fn normalize_phone(value: &str, region: Option<&str>) -> Option<String> {
+    let region = region.and_then(|r| phonenumber::country::Id::from_str(&r.to_uppercase()).ok());
+    let number = phonenumber::parse(region, value).ok()?;
+    if !phonenumber::is_valid(&number) {
+        return None;
+    }
+    Some(number.format().mode(phonenumber::Mode::E164).to_string())
+}
+
+pub fn canonicalize_url(value: &str) -> Option<String> {
+    let value = value.trim();
+    let mut url = Url::parse(value)
+        .or_else(|_| Url::parse(&format!("https://{}", value)))
+        .ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().map_or(true, |h| !h.contains('.')) {
+        return None;
+    }
+    url.set_fragment(None);
+
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if query.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(query);
+    }
+
+    let mut canonical = url.to_string();
+    if url.path() == "/" && url.query().is_none() {
+        canonical.pop();
+    }
+    Some(canonical)
+}
+
+// Whether the domain accepts mail; None when the lookup itself failed
+async fn has_mx(resolver: &TokioAsyncResolver, domain: &str) -> Option<bool> {
+    match resolver.mx_lookup(format!("{}.", domain)).await {
+        Ok(lookup) => Some(lookup.iter().next().is_some()),
+        Err(e) => match e.kind() {
+            ResolveErrorKind::NoRecordsFound { .. } => Some(false),
+            _ => None,
+        },
+    }
+}
+
+pub async fn validate(rows: Vec<Row>, rules: &ValidationRules) -> Result<ValidationResult, String> {
+    let resolver = if rules.check_mx {
+        Some(TokioAsyncResolver::tokio_from_system_conf().map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+    let mut mx_cache: HashMap<String, Option<bool>> = HashMap::new();
+    let mut summary = ValidationSummary {
+        total: rows.len(),
+        flagged: 0,
+        flags: HashMap::new(),
+    };
+
+    let mut validated = Vec::with_capacity(rows.len());
+    for mut row in rows {
+        let mut flags: Vec<String> = Vec::new();
+
+        for field in &rules.required_fields {
+            if text(&row, field).is_none() {
+                flags.push(format!("missing:{}", field));
+            }
+        }
+
+        for field in &rules.email_fields {
+            let Some(value) = text(&row, field) else { continue };
+            let Some(email) = normalize_email(&value) else {
+                flags.push(format!("invalid_email:{}", field));
+                continue;
+            };
+            if let Some(resolver) = &resolver {
+                let domain = email.rsplit('@').next().unwrap_or_default().to_string();
+                if !mx_cache.contains_key(&domain) {
+                    let found = has_mx(resolver, &domain).await;
+                    mx_cache.insert(domain.clone(), found);
+                }
+                if mx_cache[&domain] == Some(false) {
+                    flags.push(format!("no_mx:{}", field));
+                }
+            }
+            row.insert(field.clone(), Value::String(email));
+        }
+
+        for field in &rules.phone_fields {
+            let Some(value) = text(&row, field) else { continue };
+            match normalize_phone(&value, rules.default_region.as_deref()) {
+                Some(phone) => {
+                    row.insert(field.clone(), Value::String(phone));
+                }
+                None => flags.push(format!("invalid_phone:{}", field)),
+            }
+        }
+
+        for field in &rules.url_fields {
+            let Some(value) = text(&row, field) else { continue };
+            match canonicalize_url(&value) {
+                Some(url) => {
+                    row.insert(field.clone(), Value::String(url));
+                }
+                None => flags.push(format!("invalid_url:{}", field)),
+            }
+        }
+
+        if !flags.is_empty() {
+            summary.flagged += 1;
+        }
+        for flag in &flags {
+            *summary.flags.entry(flag.clone()).or_default() += 1;
+        }
+        row.insert("quality_ok".to_string(), Value::Bool(flags.is_empty()));
+        row.insert("quality_flags".to_string(), Value::String(flags.join("; ")));
+        validated.push(row);
+    }
+
+    Ok(ValidationResult {
+        rows: validated,
+        summary,
+    })
+}
+
+pub async fn run_action(action: &ValidateAction, rows: &[Row]) -> Result<Value, String> {
+    let result = validate(rows.to_vec(), &action.rules).await?;
+    Ok(json!(result))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_rows(rows: Vec<Row>, rules: ValidationRules) -> Result<ValidationResult, String> {
+    validate(rows, &rules).await
+}
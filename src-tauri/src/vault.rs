@@ -0,0 +1,226 @@
+// Named secrets for workflows
+// Workflow definitions reference secrets as `{{secret:name}}`; the placeholders
+// are resolved from the keychain only when an action runs, so values never
+// appear in definitions, exports or logs. Secrets are scoped to a workflow, a
+// profile or globally; the narrowest scope wins. `{{totp:account}}` resolves
+// to the current two-factor code for a saved login (after user consent).
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::{self, AuditCategory};
+use crate::{idle, profiles, secrets, storage, totp};
+
+const VAULT_FILE: &str = "vault.json";
+const PLACEHOLDER_START: &str = "{{secret:";
+// Current two-factor code, see totp.rs
+const TOTP_PLACEHOLDER_START: &str = "{{totp:";
+const PLACEHOLDER_END: &str = "}}";
+const REDACTED: &str = "[secret]";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SecretScope {
+    Global,
+    Profile { id: String },
+    Workflow { id: String },
+}
+
+impl SecretScope {
+    fn key(&self, name: &str) -> String {
+        match self {
+            SecretScope::Global => format!("vault:global:{}", name),
+            SecretScope::Profile { id } => format!("vault:profile:{}:{}", id, name),
+            SecretScope::Workflow { id } => format!("vault:workflow:{}:{}", id, name),
+        }
+    }
+}
+
+// Index entry; the value itself only lives in the keychain
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultSecret {
+    name: String,
+    scope: SecretScope,
+    updated_at: i64,
+}
+
+#[derive(Default)]
+pub struct VaultState {
+    index: Mutex<Vec<VaultSecret>>,
+}
+
+impl VaultState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            index: Mutex::new(storage::load(app, VAULT_FILE)),
+        }
+    }
+}
+
+// Keychain entries of every indexed secret, for backups
+pub fn keychain_keys(app: &AppHandle) -> Vec<String> {
+    let index = app.state::<VaultState>().index.lock().unwrap();
+    index.iter().map(|s| s.scope.key(&s.name)).collect()
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid secret name: {}", name))
+    }
+}
+
+// Look a secret up, narrowest scope first
+fn lookup(app: &AppHandle, workflow_id: Option<&str>, name: &str) -> Result<String, String> {
+    let mut scopes = Vec::new();
+    if let Some(id) = workflow_id {
+        scopes.push(SecretScope::Workflow { id: id.to_string() });
+    }
+    scopes.push(SecretScope::Profile {
+        id: profiles::active_profile(app),
+    });
+    scopes.push(SecretScope::Global);
+
+    for scope in scopes {
+        if let Some(value) = secrets::get(&scope.key(name))? {
+            audit::record(
+                app,
+                AuditCategory::Credential,
+                "vault.resolve",
+                json!({ "name": name, "scope": scope, "workflow_id": workflow_id }),
+            );
+            return Ok(value);
+        }
+    }
+    Err(format!("Unknown secret: {}", name))
+}
+
+fn resolve_text(app: &AppHandle, workflow_id: Option<&str>, text: &str, used: &mut Vec<String>) -> Result<String, String> {
+    let mut resolved = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let next = [PLACEHOLDER_START, TOTP_PLACEHOLDER_START]
+            .into_iter()
+            .filter_map(|marker| rest.find(marker).map(|start| (start, marker)))
+            .min();
+        let Some((start, marker)) = next else { break };
+        let after = &rest[start + marker.len()..];
+        let Some(end) = after.find(PLACEHOLDER_END) else { break };
+        let name = after[..end].trim();
+        let value = if marker == TOTP_PLACEHOLDER_START {
+            let requester = match workflow_id {
+                Some(id) => format!("Workflow {}", id),
+                None => "An automation".to_string(),
+            };
+            totp::code_with_consent(app, name, &requester)?.code
+        } else {
+            validate_name(name)?;
+            lookup(app, workflow_id, name)?
+        };
+        resolved.push_str(&rest[..start]);
+        resolved.push_str(&value);
+        if !used.contains(&value) {
+            used.push(value);
+        }
+        rest = &after[end + PLACEHOLDER_END.len()..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+// Replace placeholders in every string of `value`; returns the secret values
+// used so callers can redact them from anything they report back
+pub fn resolve(app: &AppHandle, workflow_id: Option<&str>, value: &mut Value) -> Result<Vec<String>, String> {
+    fn walk(app: &AppHandle, workflow_id: Option<&str>, value: &mut Value, used: &mut Vec<String>) -> Result<(), String> {
+        match value {
+            Value::String(text) if text.contains(PLACEHOLDER_START) || text.contains(TOTP_PLACEHOLDER_START) => {
+                *text = resolve_text(app, workflow_id, text, used)?;
+            }
+            Value::Array(items) => {
+                for item in items {
+                    walk(app, workflow_id, item, used)?;
+                }
+            }
+            Value::Object(map) => {
+                for item in map.values_mut() {
+                    walk(app, workflow_id, item, used)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    let mut used = Vec::new();
+    walk(app, workflow_id, value, &mut used)?;
+    Ok(used)
+}
+
+pub fn redact(text: &str, values: &[String]) -> String {
+    values
+        .iter()
+        .filter(|v| !v.is_empty())
+        .fold(text.to_string(), |text, value| text.replace(value.as_str(), REDACTED))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_vault_secrets(
+    state: tauri::State<'_, VaultState>,
+    scope: Option<SecretScope>,
+) -> Result<Vec<VaultSecret>, String> {
+    let index = state.index.lock().unwrap();
+    Ok(index
+        .iter()
+        .filter(|s| scope.as_ref().map_or(true, |scope| &s.scope == scope))
+        .cloned()
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_vault_secret(
+    app_handle: AppHandle,
+    state: tauri::State<'_, VaultState>,
+    name: String,
+    scope: SecretScope,
+    value: String,
+) -> Result<(), String> {
+    idle::ensure_unlocked(&app_handle)?;
+    validate_name(&name)?;
+    secrets::set(&scope.key(&name), &value)?;
+    audit::record(&app_handle, AuditCategory::Settings, "vault.set", json!({ "name": name, "scope": scope }));
+
+    let mut index = state.index.lock().unwrap();
+    index.retain(|s| !(s.name == name && s.scope == scope));
+    index.push(VaultSecret {
+        name,
+        scope,
+        updated_at: chrono::Utc::now().timestamp(),
+    });
+    storage::save(&app_handle, VAULT_FILE, &*index)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_vault_secret(
+    app_handle: AppHandle,
+    state: tauri::State<'_, VaultState>,
+    name: String,
+    scope: SecretScope,
+) -> Result<(), String> {
+    idle::ensure_unlocked(&app_handle)?;
+    secrets::delete(&scope.key(&name))?;
+    audit::record(&app_handle, AuditCategory::Settings, "vault.delete", json!({ "name": name, "scope": scope }));
+
+    let mut index = state.index.lock().unwrap();
+    index.retain(|s| !(s.name == name && s.scope == scope));
+    storage::save(&app_handle, VAULT_FILE, &*index)
+}
@@ -0,0 +1,199 @@
+// Screenshot-based extraction with a vision model
+// Fallback for canvas-heavy or obfuscated pages where DOM scraping fails: the
+// visible part of the page is captured and a vision-capable model is asked for
+// JSON matching a caller-provided JSON schema.
+//
+// `ask_about_region` answers a free-form question about a rectangle of a
+// window in one round-trip: the region is captured, the page text laid out
+// inside it is read from the DOM (standing in for OCR, and exact where the
+// text is real text), and both go to the model with the question. The answer
+// streams back as AiTokenReceived events.
+
+use image::imageops::FilterType;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::ai::{self, AiTask};
+use crate::{capture, pagequery};
+
+const VISION_TIMEOUT: Duration = Duration::from_secs(90);
+// Providers downscale larger images anyway; sending less keeps requests small
+const MAX_IMAGE_SIDE: u32 = 2048;
+const REGION_TEXT_TIMEOUT: Duration = Duration::from_secs(3);
+// Longest page text sent along with a region
+const MAX_REGION_TEXT: usize = 4000;
+const SYSTEM_PROMPT: &str = "You extract structured data from screenshots of web pages. \
+Reply with JSON only, matching the given JSON schema. Use null for values that are not visible; \
+never guess or invent data.";
+
+const REGION_SYSTEM_PROMPT: &str = "You answer questions about a region of a web page the user selected. \
+You get a screenshot of the region and, when available, the page text laid out inside it. \
+Answer concisely from what is shown; say so when the region does not contain the answer.";
+
+// Text of the DOM nodes laid out inside a rectangle given in physical pixels
+const REGION_TEXT_SCRIPT: &str = r#"
+var ratio = window.devicePixelRatio || 1;
+var left = REGION.x / ratio, top = REGION.y / ratio;
+var right = left + REGION.width / ratio, bottom = top + REGION.height / ratio;
+var walker = document.createTreeWalker(document.body || document.documentElement, NodeFilter.SHOW_TEXT);
+var parts = [], range = document.createRange(), node;
+while ((node = walker.nextNode())) {
+  if (!node.textContent.trim()) continue;
+  range.selectNodeContents(node);
+  var inside = Array.prototype.some.call(range.getClientRects(), function (r) {
+    return r.right > left && r.left < right && r.bottom > top && r.top < bottom;
+  });
+  if (inside) parts.push(node.textContent.trim());
+}
+return parts.join(' ').slice(0, LIMIT);
+"#;
+
+// In physical pixels, relative to the window's content area
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+pub struct Region {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VisionExtractAction {
+    pub window_id: String,
+    pub schema: Value,
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+// Shallow schema check: top-level type and required properties
+fn check_schema(value: &Value, schema: &Value) -> Result<(), String> {
+    let matches_type = match schema["type"].as_str() {
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        _ => true,
+    };
+    if !matches_type {
+        return Err(format!("Model returned {} instead of the schema's type", value));
+    }
+    if let (Some(required), Some(object)) = (schema["required"].as_array(), value.as_object()) {
+        if let Some(missing) = required.iter().filter_map(Value::as_str).find(|key| !object.contains_key(*key)) {
+            return Err(format!("Model reply is missing required field: {}", missing));
+        }
+    }
+    Ok(())
+}
+
+fn downscale(image: RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if width.max(height) <= MAX_IMAGE_SIDE {
+        return image;
+    }
+    let scale = MAX_IMAGE_SIDE as f64 / width.max(height) as f64;
+    image::imageops::resize(
+        &image,
+        (width as f64 * scale) as u32,
+        (height as f64 * scale) as u32,
+        FilterType::Triangle,
+    )
+}
+
+pub async fn extract(app: &AppHandle, window_id: &str, schema: &Value, instructions: Option<&str>) -> Result<Value, String> {
+    if !schema.is_object() {
+        return Err("Schema must be a JSON schema object".to_string());
+    }
+    if !ai::is_configured(app) {
+        return Err("No AI provider configured".to_string());
+    }
+    let window = app
+        .get_window(window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+
+    let png = capture::to_png(&downscale(capture::capture_window(&window)?))?;
+
+    let mut prompt = format!(
+        "Page: {}\nExtract data matching this JSON schema:\n{}",
+        window.url(),
+        serde_json::to_string_pretty(schema).map_err(|e| e.to_string())?
+    );
+    if let Some(instructions) = instructions.filter(|i| !i.trim().is_empty()) {
+        prompt.push_str("\n\nAdditional instructions: ");
+        prompt.push_str(instructions);
+    }
+
+    let reply = ai::complete_vision(app, SYSTEM_PROMPT, &prompt, &png, schema, VISION_TIMEOUT).await?;
+    let value = ai::parse_json_reply(&reply)?;
+    check_schema(&value, schema)?;
+    Ok(value)
+}
+
+pub async fn run_action(app: &AppHandle, action: &VisionExtractAction) -> Result<Value, String> {
+    let data = extract(app, &action.window_id, &action.schema, action.instructions.as_deref()).await?;
+    Ok(json!({ "data": data }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_with_vision(
+    app_handle: AppHandle,
+    window_id: String,
+    schema: Value,
+    instructions: Option<String>,
+) -> Result<Value, String> {
+    extract(&app_handle, &window_id, &schema, instructions.as_deref()).await
+}
+
+// The answer is streamed as AiTokenReceived events under `request_id` and
+// returned in full at the end
+#[tauri::command]
+#[specta::specta]
+pub async fn ask_about_region(
+    app_handle: AppHandle,
+    window_id: String,
+    rect: Region,
+    question: String,
+    request_id: String,
+) -> Result<String, String> {
+    if question.trim().is_empty() {
+        return Err("Question cannot be empty".to_string());
+    }
+    if rect.width == 0 || rect.height == 0 {
+        return Err("Region is empty".to_string());
+    }
+    if !ai::is_configured(&app_handle) {
+        return Err("No AI provider configured".to_string());
+    }
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+
+    let region = capture::capture_region(&window, rect.x, rect.y, rect.width, rect.height)?;
+    let png = capture::to_png(&downscale(region))?;
+    // Pages that don't answer (or canvas-only regions) are left to the image
+    let script = REGION_TEXT_SCRIPT
+        .replace("REGION", &serde_json::to_string(&rect).map_err(|e| e.to_string())?)
+        .replace("LIMIT", &MAX_REGION_TEXT.to_string());
+    let text = pagequery::run(&app_handle, &window, &script, REGION_TEXT_TIMEOUT)
+        .await
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .filter(|text| !text.trim().is_empty());
+
+    let mut prompt = format!("Page: {}\n", window.url().map_err(|e| e.to_string())?);
+    if let Some(text) = text {
+        prompt.push_str(&format!("Text in the region:\n{}\n", text));
+    }
+    prompt.push_str(&format!("\nQuestion: {}", question.trim()));
+    let messages = json!([
+        { "role": "system", "content": REGION_SYSTEM_PROMPT },
+        { "role": "user", "content": [
+            { "type": "text", "text": prompt },
+            ai::image_part(&png),
+        ] },
+    ]);
+    ai::stream(&app_handle, AiTask::Vision, "ai.ask_region", messages, &request_id).await
+}
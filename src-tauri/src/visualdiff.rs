@@ -0,0 +1,351 @@
+// Rendered-screenshot diffs for page monitors
+// Complements the text diffs of the change detector: each check captures the
+// monitored window, compares it with the monitor's previous snapshot using a
+// pixelmatch-style perceptual colour delta, and stores an annotated image with
+// the changed regions boxed in red.
+
+use image::{Rgba, RgbaImage};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::{capture, payloads};
+
+const SNAPSHOTS_DIR: &str = "monitor-snapshots";
+// Maximum YIQ delta (pixelmatch's scale), used to normalize the threshold
+const MAX_DELTA: f64 = 35215.0;
+// Changed pixels are grouped into regions on a grid of this cell size
+const REGION_CELL: u32 = 16;
+const HIGHLIGHT: Rgba<u8> = Rgba([230, 30, 30, 255]);
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS monitor_checks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    monitor_id TEXT NOT NULL,
+    url TEXT NOT NULL DEFAULT '',
+    checked_at INTEGER NOT NULL,
+    changed_pixels INTEGER NOT NULL DEFAULT 0,
+    changed_ratio REAL NOT NULL DEFAULT 0,
+    regions TEXT NOT NULL DEFAULT '[]',
+    snapshot_path TEXT NOT NULL,
+    diff_path TEXT,
+    baseline_id INTEGER
+);
+CREATE INDEX IF NOT EXISTS monitor_checks_monitor ON monitor_checks (monitor_id, checked_at);
+";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub struct Region {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MonitorCheck {
+    id: i64,
+    monitor_id: String,
+    url: String,
+    checked_at: i64,
+    changed_pixels: i64,
+    changed_ratio: f64,
+    regions: Vec<Region>,
+    // The check this one was compared with; None for the first snapshot
+    baseline_id: Option<i64>,
+    #[serde(skip)]
+    snapshot_path: String,
+    #[serde(skip)]
+    diff_path: Option<String>,
+}
+
+impl MonitorCheck {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let regions: String = row.get(6)?;
+        Ok(Self {
+            id: row.get(0)?,
+            monitor_id: row.get(1)?,
+            url: row.get(2)?,
+            checked_at: row.get(3)?,
+            changed_pixels: row.get(4)?,
+            changed_ratio: row.get(5)?,
+            regions: serde_json::from_str(&regions).unwrap_or_default(),
+            snapshot_path: row.get(7)?,
+            diff_path: row.get(8)?,
+            baseline_id: row.get(9)?,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, monitor_id, url, checked_at, changed_pixels, changed_ratio, regions,
+    snapshot_path, diff_path, baseline_id FROM monitor_checks";
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MonitorDiff {
+    check: MonitorCheck,
+    // payload:// URLs of the PNGs
+    baseline: Option<String>,
+    snapshot: String,
+    diff: Option<String>,
+}
+
+struct Comparison {
+    annotated: RgbaImage,
+    changed_pixels: u64,
+    regions: Vec<Region>,
+}
+
+// Perceptual colour difference in YIQ space, as in pixelmatch
+fn color_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f64 {
+    // Blend against white so transparent pixels compare by what is shown
+    let blend = |p: &Rgba<u8>| {
+        let alpha = p[3] as f64 / 255.0;
+        [0, 1, 2].map(|i| 255.0 + (p[i] as f64 - 255.0) * alpha)
+    };
+    let (a, b) = (blend(a), blend(b));
+    let y = |c: [f64; 3]| c[0] * 0.29889531 + c[1] * 0.58662247 + c[2] * 0.11448223;
+    let i = |c: [f64; 3]| c[0] * 0.59597799 - c[1] * 0.27417610 - c[2] * 0.32180189;
+    let q = |c: [f64; 3]| c[0] * 0.21147017 - c[1] * 0.52261711 + c[2] * 0.31114694;
+    let (dy, di, dq) = (y(a) - y(b), i(a) - i(b), q(a) - q(b));
+    0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq
+}
+
+// Bounding boxes of connected grid cells that contain changed pixels
+fn regions(cells: &[bool], columns: u32, rows: u32, width: u32, height: u32) -> Vec<Region> {
+    let mut seen = vec![false; cells.len()];
+    let mut regions = Vec::new();
+    for start in 0..cells.len() {
+        if !cells[start] || seen[start] {
+            continue;
+        }
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+        let mut stack = vec![start];
+        seen[start] = true;
+        while let Some(index) = stack.pop() {
+            let (cx, cy) = (index as u32 % columns, index as u32 / columns);
+            min_x = min_x.min(cx);
+            min_y = min_y.min(cy);
+            max_x = max_x.max(cx);
+            max_y = max_y.max(cy);
+            let neighbours = [
+                (cx > 0).then(|| index - 1),
+                (cx + 1 < columns).then(|| index + 1),
+                (cy > 0).then(|| index - columns as usize),
+                (cy + 1 < rows).then(|| index + columns as usize),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if cells[next] && !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        let x = min_x * REGION_CELL;
+        let y = min_y * REGION_CELL;
+        regions.push(Region {
+            x,
+            y,
+            width: ((max_x + 1) * REGION_CELL).min(width) - x,
+            height: ((max_y + 1) * REGION_CELL).min(height) - y,
+        });
+    }
+    regions
+}
+
+fn draw_box(image: &mut RgbaImage, region: &Region) {
+    let right = (region.x + region.width).min(image.width()).saturating_sub(1);
+    let bottom = (region.y + region.height).min(image.height()).saturating_sub(1);
+    for x in region.x..=right {
+        image.put_pixel(x, region.y, HIGHLIGHT);
+        image.put_pixel(x, bottom, HIGHLIGHT);
+    }
+    for y in region.y..=bottom {
+        image.put_pixel(region.x, y, HIGHLIGHT);
+        image.put_pixel(right, y, HIGHLIGHT);
+    }
+}
+
+// Compare two screenshots; `threshold` is 0..1 like pixelmatch (0.1 by default).
+// Area outside the overlap of differently sized images counts as changed.
+fn compare(baseline: &RgbaImage, current: &RgbaImage, threshold: f64) -> Comparison {
+    let (width, height) = current.dimensions();
+    let max_delta = MAX_DELTA * threshold * threshold;
+    let columns = ((width + REGION_CELL - 1) / REGION_CELL).max(1);
+    let rows = ((height + REGION_CELL - 1) / REGION_CELL).max(1);
+    let mut cells = vec![false; (columns * rows) as usize];
+    let mut annotated = RgbaImage::new(width, height);
+    let mut changed_pixels = 0;
+
+    for (x, y, pixel) in current.enumerate_pixels() {
+        let changed = match baseline.get_pixel_checked(x, y) {
+            Some(old) => color_delta(old, pixel) > max_delta,
+            None => true,
+        };
+        if changed {
+            changed_pixels += 1;
+            cells[((y / REGION_CELL) * columns + x / REGION_CELL) as usize] = true;
+            annotated.put_pixel(x, y, HIGHLIGHT);
+        } else {
+            // Unchanged content is shown faded so the highlights stand out
+            let fade = |c: u8| ((c as u16 + 2 * 255) / 3) as u8;
+            annotated.put_pixel(x, y, Rgba([fade(pixel[0]), fade(pixel[1]), fade(pixel[2]), 255]));
+        }
+    }
+
+    let regions = regions(&cells, columns, rows, width, height);
+    for region in &regions {
+        draw_box(&mut annotated, region);
+    }
+    Comparison {
+        annotated,
+        changed_pixels,
+        regions,
+    }
+}
+
+fn snapshot_dir(app: &AppHandle, monitor_id: &str) -> Result<PathBuf, String> {
+    let safe: String = monitor_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "App data directory unavailable".to_string())?
+        .join(SNAPSHOTS_DIR)
+        .join(safe);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn find_check(db: &Database, check_id: i64) -> Result<Option<MonitorCheck>, String> {
+    db.with(|conn| {
+        conn.query_row(&format!("{} WHERE id = ?1", SELECT_COLUMNS), params![check_id], MonitorCheck::from_row)
+            .optional()
+    })
+}
+
+// Served over the payload channel rather than inlined into the response
+fn image_url(app: &AppHandle, path: &str) -> Option<String> {
+    let png = std::fs::read(path).ok()?;
+    Some(payloads::publish(app, png, "image/png").url)
+}
+
+// Capture the window showing a monitored page and diff it against the
+// monitor's previous snapshot
+#[tauri::command]
+#[specta::specta]
+pub async fn check_monitor_visual(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    monitor_id: String,
+    window_id: String,
+    threshold: Option<f64>,
+) -> Result<MonitorCheck, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    let url = window.url().to_string();
+    let current = capture::capture_window(&window)?;
+    let threshold = threshold.unwrap_or(0.1).clamp(0.0, 1.0);
+
+    let baseline: Option<MonitorCheck> = db.with(|conn| {
+        conn.query_row(
+            &format!("{} WHERE monitor_id = ?1 ORDER BY checked_at DESC, id DESC LIMIT 1", SELECT_COLUMNS),
+            params![monitor_id],
+            MonitorCheck::from_row,
+        )
+        .optional()
+    })?;
+    let baseline_image = baseline
+        .as_ref()
+        .and_then(|b| image::open(&b.snapshot_path).ok())
+        .map(|image| image.to_rgba8());
+
+    let comparison = baseline_image.map(|old| compare(&old, &current, threshold));
+    let checked_at = chrono::Utc::now().timestamp_millis();
+    let dir = snapshot_dir(&app_handle, &monitor_id)?;
+    let snapshot_path = dir.join(format!("{}.png", checked_at));
+    std::fs::write(&snapshot_path, capture::to_png(&current)?).map_err(|e| e.to_string())?;
+    let diff_path = match &comparison {
+        Some(comparison) => {
+            let path = dir.join(format!("{}-diff.png", checked_at));
+            std::fs::write(&path, capture::to_png(&comparison.annotated)?).map_err(|e| e.to_string())?;
+            Some(path.to_string_lossy().to_string())
+        }
+        None => None,
+    };
+
+    let total = (current.width() as u64 * current.height() as u64).max(1);
+    let changed_pixels = comparison.as_ref().map_or(0, |c| c.changed_pixels);
+    let regions = comparison.map(|c| c.regions).unwrap_or_default();
+    let mut check = MonitorCheck {
+        id: 0,
+        monitor_id,
+        url,
+        checked_at: checked_at / 1000,
+        changed_pixels: changed_pixels as i64,
+        changed_ratio: changed_pixels as f64 / total as f64,
+        regions,
+        baseline_id: baseline.map(|b| b.id),
+        snapshot_path: snapshot_path.to_string_lossy().to_string(),
+        diff_path,
+    };
+    let regions_json = serde_json::to_string(&check.regions).map_err(|e| e.to_string())?;
+    check.id = db.with(|conn| {
+        conn.execute(
+            "INSERT INTO monitor_checks (monitor_id, url, checked_at, changed_pixels, changed_ratio, regions,
+                snapshot_path, diff_path, baseline_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                check.monitor_id,
+                check.url,
+                check.checked_at,
+                check.changed_pixels,
+                check.changed_ratio,
+                regions_json,
+                check.snapshot_path,
+                check.diff_path,
+                check.baseline_id
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    })?;
+    Ok(check)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_monitor_checks(db: tauri::State<'_, Database>, monitor_id: String) -> Result<Vec<MonitorCheck>, String> {
+    db.with(|conn| {
+        let mut stmt = conn.prepare(&format!("{} WHERE monitor_id = ?1 ORDER BY checked_at DESC, id DESC", SELECT_COLUMNS))?;
+        let rows = stmt.query_map(params![monitor_id], MonitorCheck::from_row)?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_monitor_diff(
+    app_handle: AppHandle,
+    db: tauri::State<'_, Database>,
+    check_id: i64,
+) -> Result<MonitorDiff, String> {
+    let check = find_check(&db, check_id)?.ok_or_else(|| format!("Monitor check not found: {}", check_id))?;
+    let baseline = match check.baseline_id {
+        Some(id) => find_check(&db, id)?.and_then(|b| image_url(&app_handle, &b.snapshot_path)),
+        None => None,
+    };
+    let snapshot =
+        image_url(&app_handle, &check.snapshot_path).ok_or_else(|| "Snapshot image is missing".to_string())?;
+    let diff = check.diff_path.as_deref().and_then(|path| image_url(&app_handle, path));
+    Ok(MonitorDiff {
+        check,
+        baseline,
+        snapshot,
+        diff,
+    })
+}
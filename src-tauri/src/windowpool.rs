@@ -0,0 +1,191 @@
+// Pre-warmed window pool
+// Building a webview takes a noticeable moment, so a few hidden windows are
+// kept loaded with the app shell. `create_new_window` shows one of them
+// (navigating it first when a URL was asked for) and the pool is topped up in
+// the background. A pool size of 0 turns this off.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window, WindowBuilder, WindowUrl};
+
+use crate::{
+    appscheme, content_settings, downloads, fingerprint, i18n, pageerrors, policy, regional, storage, urlcleaner,
+    userscripts,
+};
+
+const CONFIG_FILE: &str = "window-pool.json";
+const MAX_POOL_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct WindowPoolConfig {
+    size: usize,
+}
+
+impl Default for WindowPoolConfig {
+    fn default() -> Self {
+        Self { size: 1 }
+    }
+}
+
+#[derive(Default)]
+pub struct WindowPoolState {
+    config: Mutex<WindowPoolConfig>,
+    // Labels of hidden windows ready to be handed out
+    idle: Mutex<VecDeque<String>>,
+    // Keeps labels unique when several windows are built in the same millisecond
+    counter: AtomicU32,
+    refilling: AtomicBool,
+    // Bumped by `drain`, so windows built before it are not pooled
+    generation: AtomicU32,
+}
+
+impl WindowPoolState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, CONFIG_FILE)),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn build_window(app: &AppHandle, url: WindowUrl, visible: bool) -> Result<Window, String> {
+    build_window_in(app, url, visible, None)
+}
+
+// `data_directory` gives the webview its own cookies and storage (see containers.rs)
+pub fn build_window_in(
+    app: &AppHandle,
+    url: WindowUrl,
+    visible: bool,
+    data_directory: Option<PathBuf>,
+) -> Result<Window, String> {
+    let state = app.state::<WindowPoolState>();
+    let label = format!(
+        "window_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        state.counter.fetch_add(1, Ordering::Relaxed)
+    );
+    let mut builder = WindowBuilder::new(app, label, url)
+        .title("MadEasy Browser")
+        .menu(crate::create_menu(&i18n::current(app)))
+        .initialization_script(pageerrors::BRIDGE_SCRIPT)
+        .initialization_script(&urlcleaner::script(app))
+        .initialization_script(&userscripts::initialization_script(app))
+        .initialization_script(&regional::initialization_script(app))
+        .initialization_script(&content_settings::initialization_script(app))
+        .initialization_script(&fingerprint::initialization_script(app, data_directory.as_deref()))
+        .inner_size(1200.0, 800.0)
+        .min_inner_size(800.0, 600.0)
+        .visible(visible);
+    if let Some(dir) = data_directory {
+        builder = builder.data_directory(dir);
+    }
+    let window = builder.build().map_err(|e| e.to_string())?;
+    regional::apply(app, &window);
+    policy::attach(app, &window);
+    downloads::attach(app, &window);
+    content_settings::attach(app, &window);
+    appscheme::attach(&window);
+    Ok(window)
+}
+
+// Hand out a pooled window, if one is ready
+pub fn take(app: &AppHandle) -> Option<Window> {
+    let state = app.state::<WindowPoolState>();
+    let mut idle = state.idle.lock().unwrap();
+    while let Some(label) = idle.pop_front() {
+        if let Some(window) = app.get_window(&label) {
+            return Some(window);
+        }
+    }
+    None
+}
+
+// Top the pool up to its configured size in the background
+pub fn refill(app: &AppHandle) {
+    if app.state::<WindowPoolState>().refilling.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<WindowPoolState>();
+        let size = state.config.lock().unwrap().size.min(MAX_POOL_SIZE);
+        while state.idle.lock().unwrap().len() < size {
+            let generation = state.generation.load(Ordering::SeqCst);
+            match build_window(&app, WindowUrl::App("index.html".into()), false) {
+                Ok(window) if state.generation.load(Ordering::SeqCst) != generation => {
+                    let _ = window.close();
+                }
+                Ok(window) => state.idle.lock().unwrap().push_back(window.label().to_string()),
+                Err(e) => {
+                    eprintln!("Failed to pre-warm window: {}", e);
+                    break;
+                }
+            }
+        }
+        state.refilling.store(false, Ordering::SeqCst);
+    });
+}
+
+// Replace the pooled windows, which carry the initialization scripts they were
+// built with; called when the active profile, userscripts, content settings or
+// URL cleaner rules change
+pub fn drain(app: &AppHandle) {
+    let state = app.state::<WindowPoolState>();
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    let pooled: Vec<String> = state.idle.lock().unwrap().drain(..).collect();
+    for label in pooled {
+        if let Some(window) = app.get_window(&label) {
+            let _ = window.close();
+        }
+    }
+    refill(app);
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<WindowPoolState>()
+        .idle
+        .lock()
+        .unwrap()
+        .retain(|l| l != label);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_window_pool_config(state: tauri::State<'_, WindowPoolState>) -> Result<WindowPoolConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_window_pool_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, WindowPoolState>,
+    config: WindowPoolConfig,
+) -> Result<(), String> {
+    if config.size > MAX_POOL_SIZE {
+        return Err(format!("Pool size can be at most {}", MAX_POOL_SIZE));
+    }
+    storage::save(&app_handle, CONFIG_FILE, &config)?;
+    let size = config.size;
+    *state.config.lock().unwrap() = config;
+
+    // Close windows beyond the new size
+    let surplus: Vec<String> = {
+        let mut idle = state.idle.lock().unwrap();
+        let keep = idle.len().min(size);
+        idle.drain(keep..).collect()
+    };
+    for label in surplus {
+        if let Some(window) = app_handle.get_window(&label) {
+            let _ = window.close();
+        }
+    }
+    refill(&app_handle);
+    Ok(())
+}
@@ -0,0 +1,160 @@
+// Native workflow actions
+// The workflow engine (server/workflows) runs in Node; actions that need the
+// desktop side (keychain credentials, local files, native network clients) are
+// executed here through `run_workflow_action`.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::artifacts::{self, SaveArtifactAction};
+use crate::bandwidth;
+use crate::click_guard::{self, GuardClickAction};
+use crate::crawler::{self, CrawlAction};
+use crate::dedupe::{self, DedupeAction};
+use crate::email::{self, EmailAction};
+use crate::enrichment::{self, EnrichAction};
+use crate::events::{self, AppEvent};
+use crate::i18n;
+use crate::notifications::{self, Notice, NotificationAction, NotificationCategory, BUTTON_OPEN};
+use crate::power::{self, GuardKind};
+use crate::s3::{self, S3UploadAction};
+use crate::upload::{self, UploadAction};
+use crate::validation::{self, ValidateAction};
+use crate::vault;
+use crate::vision::{self, VisionExtractAction};
+
+// One extracted record, as produced by scraping steps
+pub type Row = Map<String, Value>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub run_id: String,
+    // Scopes `{{secret:...}}` lookups to this workflow
+    #[serde(default)]
+    pub workflow_id: Option<String>,
+    pub workflow_name: String,
+    // "success", "failed", ...
+    pub status: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub rows: Vec<Row>,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkflowAction {
+    Email(EmailAction),
+    Upload(UploadAction),
+    S3Upload(S3UploadAction),
+    Validate(ValidateAction),
+    Enrich(EnrichAction),
+    Dedupe(DedupeAction),
+    SaveArtifact(SaveArtifactAction),
+    VisionExtract(VisionExtractAction),
+    Crawl(CrawlAction),
+    GuardClick(GuardClickAction),
+}
+
+// Column order: first appearance across all rows
+pub fn columns(rows: &[Row]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        for key in row.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    columns
+}
+
+pub fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+pub fn rows_to_csv(rows: &[Row]) -> Result<Vec<u8>, String> {
+    let columns = columns(rows);
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&columns).map_err(|e| e.to_string())?;
+    for row in rows {
+        writer
+            .write_record(columns.iter().map(|c| cell_text(row.get(c))))
+            .map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+// Event subscriber; failed runs get a notification that opens the run
+pub fn on_event(app: &AppHandle, event: &AppEvent) {
+    let AppEvent::WorkflowFailed { run_id, workflow_name, error } = event else { return };
+    let notice = Notice::new(
+        NotificationCategory::WorkflowFailure,
+        i18n::text_with(app, "notice-workflow-failed-title", &[("workflow", workflow_name.as_str().into())]),
+        error.clone(),
+    )
+    .with_action(NotificationAction::OpenWorkflowRun { run_id: run_id.clone() })
+    .with_button(BUTTON_OPEN, i18n::text(app, "notice-workflow-open-run"));
+    if let Err(e) = notifications::notify(app, notice) {
+        eprintln!("Failed to report failed workflow run {}: {}", run_id, e);
+    }
+}
+
+// `action` may contain `{{secret:name}}` placeholders; they are resolved here and
+// the secret values are redacted from any error returned to the engine
+#[tauri::command]
+#[specta::specta]
+pub async fn run_workflow_action(
+    app_handle: AppHandle,
+    action: Value,
+    report: RunReport,
+) -> Result<Value, String> {
+    let _awake = power::hold(&app_handle, GuardKind::Workflow, &report.run_id);
+    let mut action = action;
+    let result = match vault::resolve(&app_handle, report.workflow_id.as_deref(), &mut action) {
+        Ok(secrets) => match serde_json::from_value::<WorkflowAction>(action) {
+            Ok(action) => {
+                let workflow_id = report.workflow_id.as_deref();
+                bandwidth::in_run(&report.run_id, workflow_id, run(&app_handle, action, &report))
+                    .await
+                    .map_err(|e| vault::redact(&e, &secrets))
+            }
+            Err(e) => Err(vault::redact(&e.to_string(), &secrets)),
+        },
+        Err(e) => Err(e),
+    };
+    // A failed native step fails the run
+    if let Err(error) = &result {
+        events::publish(
+            &app_handle,
+            AppEvent::WorkflowFailed {
+                run_id: report.run_id.clone(),
+                workflow_name: report.workflow_name.clone(),
+                error: error.clone(),
+            },
+        );
+    }
+    result
+}
+
+async fn run(app_handle: &AppHandle, action: WorkflowAction, report: &RunReport) -> Result<Value, String> {
+    match action {
+        WorkflowAction::Email(action) => email::run_action(app_handle, &action, report).await,
+        WorkflowAction::Upload(action) => upload::run_action(app_handle, &action).await,
+        WorkflowAction::S3Upload(action) => s3::run_action(app_handle, &action).await,
+        WorkflowAction::Validate(action) => validation::run_action(&action, &report.rows).await,
+        WorkflowAction::Enrich(action) => enrichment::run_action(app_handle, &action, &report.rows).await,
+        WorkflowAction::Dedupe(action) => dedupe::run_action(app_handle, &action, report).await,
+        WorkflowAction::SaveArtifact(action) => artifacts::run_action(app_handle, &action, report).await,
+        WorkflowAction::VisionExtract(action) => vision::run_action(app_handle, &action).await,
+        WorkflowAction::Crawl(action) => crawler::run_action(app_handle, &action).await,
+        WorkflowAction::GuardClick(action) => click_guard::run_action(app_handle, &action, report).await,
+    }
+}
@@ -0,0 +1,381 @@
+// Local workflow definitions with typed parameters and environments
+// Definitions live as JSON files in `<app data>/workflows/`. A definition
+// declares parameters (string, number, enum, file path) and named
+// environments (e.g. staging/prod base URLs); `run_workflow` validates the
+// values for a run and substitutes `{{param:name}}` / `{{env:name}}` into the
+// steps before handing them to the engine. `{{secret:...}}` placeholders are
+// left for the vault to resolve when an action runs.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::click_guard::RiskKind;
+use crate::power::{self, GuardKind};
+use crate::workflow_git;
+
+pub const WORKFLOWS_DIR: &str = "workflows";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParameterKind {
+    String,
+    Number {
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+    Enum {
+        options: Vec<String>,
+    },
+    FilePath {
+        #[serde(default = "default_must_exist")]
+        must_exist: bool,
+    },
+}
+
+fn default_must_exist() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Parameter {
+    name: String,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(flatten)]
+    kind: ParameterKind,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    default: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Environment {
+    name: String,
+    // e.g. { "base_url": "https://staging.example.com" }
+    #[serde(default)]
+    variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WorkflowDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    parameters: Vec<Parameter>,
+    #[serde(default)]
+    environments: Vec<Environment>,
+    #[serde(default)]
+    default_environment: Option<String>,
+    #[serde(default)]
+    steps: Vec<Value>,
+    // Risky clicks this workflow may make without asking (see click_guard.rs)
+    #[serde(default)]
+    allow_risky_actions: Vec<RiskKind>,
+    // Generated or imported and not yet reviewed; drafts can't be run
+    #[serde(default)]
+    pub draft: bool,
+    // Engine-specific fields are kept as-is
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl WorkflowDefinition {
+    pub fn allows_risky_action(&self, risk: RiskKind) -> bool {
+        self.allow_risky_actions.contains(&risk)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PreparedRun {
+    run_id: String,
+    workflow_id: String,
+    workflow_name: String,
+    environment: Option<String>,
+    params: Map<String, Value>,
+    steps: Vec<Value>,
+    // Keeps the machine awake; the engine releases it with
+    // `release_power_guard` when the run ends (a failed run releases it too)
+    power_guard: Option<u64>,
+}
+
+pub fn validate_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 128
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid workflow id: {}", id))
+    }
+}
+
+pub fn workflows_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "App data directory unavailable".to_string())?
+        .join(WORKFLOWS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+pub fn definition_file(id: &str) -> String {
+    format!("{}.json", id)
+}
+
+pub fn load(app: &AppHandle, id: &str) -> Result<WorkflowDefinition, String> {
+    validate_id(id)?;
+    let path = workflows_dir(app)?.join(definition_file(id));
+    let bytes = std::fs::read(&path).map_err(|_| format!("Workflow not found: {}", id))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("Invalid workflow {}: {}", id, e))
+}
+
+fn check_value(parameter: &Parameter, value: Value) -> Result<Value, String> {
+    let name = &parameter.name;
+    match &parameter.kind {
+        ParameterKind::String => match value {
+            Value::String(_) => Ok(value),
+            Value::Number(n) => Ok(Value::String(n.to_string())),
+            _ => Err(format!("Parameter {} must be a string", name)),
+        },
+        ParameterKind::Number { min, max } => {
+            let number = match &value {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| format!("Parameter {} must be a number", name))?;
+            if min.map_or(false, |min| number < min) || max.map_or(false, |max| number > max) {
+                return Err(format!("Parameter {} is out of range", name));
+            }
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .ok_or_else(|| format!("Parameter {} must be a finite number", name))
+        }
+        ParameterKind::Enum { options } => match value.as_str() {
+            Some(choice) if options.iter().any(|o| o == choice) => Ok(value),
+            _ => Err(format!("Parameter {} must be one of: {}", name, options.join(", "))),
+        },
+        ParameterKind::FilePath { must_exist } => {
+            let path = value
+                .as_str()
+                .ok_or_else(|| format!("Parameter {} must be a file path", name))?;
+            if *must_exist && !std::path::Path::new(path).is_file() {
+                return Err(format!("File not found for parameter {}: {}", name, path));
+            }
+            Ok(value)
+        }
+    }
+}
+
+fn resolve_params(definition: &WorkflowDefinition, mut given: Map<String, Value>) -> Result<Map<String, Value>, String> {
+    if let Some(unknown) = given.keys().find(|k| !definition.parameters.iter().any(|p| &p.name == *k)) {
+        return Err(format!("Unknown parameter: {}", unknown));
+    }
+    let mut params = Map::new();
+    for parameter in &definition.parameters {
+        let value = given
+            .remove(&parameter.name)
+            .filter(|v| !v.is_null())
+            .or_else(|| parameter.default.clone());
+        match value {
+            Some(value) => {
+                params.insert(parameter.name.clone(), check_value(parameter, value)?);
+            }
+            None if parameter.required => return Err(format!("Missing required parameter: {}", parameter.name)),
+            None => {}
+        }
+    }
+    Ok(params)
+}
+
+fn placeholder_value(text: &str, params: &Map<String, Value>, env: &HashMap<String, String>) -> Option<Value> {
+    let inner = text.strip_prefix("{{")?.strip_suffix("}}")?;
+    match inner.split_once(':')? {
+        ("param", name) => params.get(name.trim()).cloned(),
+        ("env", name) => env.get(name.trim()).cloned().map(Value::String),
+        _ => None,
+    }
+}
+
+fn substitute_text(text: &str, params: &Map<String, Value>, env: &HashMap<String, String>) -> Result<String, String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else { break };
+        let placeholder = &rest[start..start + end + 2];
+        result.push_str(&rest[..start]);
+        match placeholder_value(placeholder, params, env) {
+            Some(Value::String(s)) => result.push_str(&s),
+            Some(other) => result.push_str(&other.to_string()),
+            None if placeholder.starts_with("{{param:") || placeholder.starts_with("{{env:") => {
+                return Err(format!("Unresolved placeholder {}", placeholder));
+            }
+            // Secrets and engine variables are resolved later
+            None => result.push_str(placeholder),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn substitute(value: &mut Value, params: &Map<String, Value>, env: &HashMap<String, String>) -> Result<(), String> {
+    match value {
+        Value::String(text) if text.contains("{{") => {
+            // A lone placeholder keeps the parameter's type (numbers stay numbers)
+            *value = match placeholder_value(text.trim(), params, env) {
+                Some(typed) => typed,
+                None => Value::String(substitute_text(text, params, env)?),
+            };
+        }
+        Value::Array(items) => {
+            for item in items {
+                substitute(item, params, env)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                substitute(item, params, env)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_workflows(app_handle: AppHandle) -> Result<Vec<WorkflowDefinition>, String> {
+    let mut definitions = Vec::new();
+    for entry in std::fs::read_dir(workflows_dir(&app_handle)?).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.extension().map_or(true, |e| e != "json") {
+            continue;
+        }
+        match std::fs::read(&path).ok().and_then(|b| serde_json::from_slice(&b).ok()) {
+            Some(definition) => definitions.push(definition),
+            None => eprintln!("Skipping unreadable workflow {}", path.display()),
+        }
+    }
+    definitions.sort_by(|a: &WorkflowDefinition, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    Ok(definitions)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_workflow(app_handle: AppHandle, workflow_id: String) -> Result<WorkflowDefinition, String> {
+    load(&app_handle, &workflow_id)
+}
+
+// `message` becomes the commit message when workflow versioning is enabled
+#[tauri::command]
+#[specta::specta]
+pub async fn save_workflow(
+    app_handle: AppHandle,
+    definition: WorkflowDefinition,
+    message: Option<String>,
+) -> Result<(), String> {
+    save(&app_handle, &definition, message)
+}
+
+pub fn save(app: &AppHandle, definition: &WorkflowDefinition, message: Option<String>) -> Result<(), String> {
+    validate_id(&definition.id)?;
+    let mut seen = Vec::new();
+    for parameter in &definition.parameters {
+        if seen.contains(&&parameter.name) {
+            return Err(format!("Duplicate parameter: {}", parameter.name));
+        }
+        seen.push(&parameter.name);
+        if let Some(default) = &parameter.default {
+            check_value(parameter, default.clone())?;
+        }
+    }
+    if let Some(name) = &definition.default_environment {
+        if !definition.environments.iter().any(|e| &e.name == name) {
+            return Err(format!("Unknown default environment: {}", name));
+        }
+    }
+
+    let json = serde_json::to_vec_pretty(definition).map_err(|e| e.to_string())?;
+    let path = workflows_dir(app)?.join(definition_file(&definition.id));
+    std::fs::write(path, json).map_err(|e| e.to_string())?;
+    let message = message
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| format!("Update {}", definition.name));
+    workflow_git::record(app, &definition.id, &message)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_workflow(app_handle: AppHandle, workflow_id: String) -> Result<(), String> {
+    validate_id(&workflow_id)?;
+    let path = workflows_dir(&app_handle)?.join(definition_file(&workflow_id));
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+    workflow_git::record(&app_handle, &workflow_id, &format!("Delete {}", workflow_id))
+}
+
+// Validate the run's parameters and environment, substitute them into the
+// steps and hand the prepared run to the engine via `workflow-run-requested`
+#[tauri::command]
+#[specta::specta]
+pub async fn run_workflow(
+    app_handle: AppHandle,
+    workflow_id: String,
+    params: Option<Map<String, Value>>,
+    environment: Option<String>,
+) -> Result<PreparedRun, String> {
+    let definition = load(&app_handle, &workflow_id)?;
+    if definition.draft {
+        return Err(format!("{} is a draft; review and save it before running", definition.name));
+    }
+    let params = resolve_params(&definition, params.unwrap_or_default())?;
+
+    let environment = environment.or_else(|| definition.default_environment.clone());
+    let variables = match &environment {
+        Some(name) => definition
+            .environments
+            .iter()
+            .find(|e| &e.name == name)
+            .map(|e| e.variables.clone())
+            .ok_or_else(|| format!("Unknown environment: {}", name))?,
+        None => HashMap::new(),
+    };
+
+    let mut steps = definition.steps.clone();
+    for step in &mut steps {
+        substitute(step, &params, &variables)?;
+    }
+
+    let run_id = format!("{}-{}", workflow_id, chrono::Utc::now().timestamp_millis());
+    let power_guard = match power::acquire(&app_handle, GuardKind::Workflow, &run_id) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            eprintln!("Failed to keep the system awake for {}: {}", run_id, e);
+            None
+        }
+    };
+    let run = PreparedRun {
+        run_id,
+        workflow_id,
+        workflow_name: definition.name,
+        environment,
+        params,
+        steps,
+        power_guard,
+    };
+    if let Err(e) = app_handle.emit_all("workflow-run-requested", &run) {
+        power::release_run(&app_handle, &run.run_id);
+        return Err(e.to_string());
+    }
+    Ok(run)
+}
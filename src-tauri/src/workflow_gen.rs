@@ -0,0 +1,206 @@
+// Workflow generation from a plain-language description
+// The configured AI provider is asked for a workflow definition in the same
+// JSON shape `save_workflow` takes. The steps are checked against the
+// engine's step types (see server/workflows/workflow-engine.ts); if they
+// don't pass, the errors are sent back once for a corrected version. The
+// result is saved as a draft, which `run_workflow` refuses until the user
+// has reviewed and saved it.
+
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::ai::{self, AiTask};
+use crate::workflow_defs::{self, WorkflowDefinition};
+
+const GENERATE_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_DESCRIPTION_CHARS: usize = 4000;
+// Nested loop steps deeper than this are rejected
+const MAX_DEPTH: usize = 4;
+
+// Step type -> config keys that must be present (any one of the group)
+const STEP_TYPES: [(&str, &[&[&str]]); 10] = [
+    ("navigate", &[&["url"]]),
+    ("wait", &[&["duration", "selector", "condition"]]),
+    ("click", &[&["selector"]]),
+    ("extract", &[&["selectors", "rules"]]),
+    ("fill", &[&["fields"]]),
+    ("condition", &[&["expression", "variable"]]),
+    ("loop", &[&["iterations", "collection"], &["steps"]]),
+    ("screenshot", &[]),
+    ("api", &[&["url"]]),
+    ("store", &[&["variable"]]),
+];
+
+const SYSTEM_PROMPT: &str = r#"You write browser automation workflows as JSON. Reply with a single JSON object and nothing else:
+{
+  "name": "short title",
+  "description": "one sentence",
+  "parameters": [{ "name": "query", "label": "Search text", "type": "string", "required": true }],
+  "steps": [{ "id": "step-1", "name": "...", "type": "...", "config": { ... }, "dependencies": ["earlier-step-id"] }]
+}
+Parameter types: "string", "number" (optional "min"/"max"), "enum" (with "options"), "file_path".
+Step types and their config:
+- navigate: { "url" }
+- wait: { "duration" (ms) } or { "selector" } or { "condition" }
+- click: { "selector" }
+- extract: { "selectors": { "field": "css selector" } } or { "rules" }
+- fill: { "fields": { "css selector": "value" } }
+- condition: { "expression" } or { "variable", "operator", "value" }
+- loop: { "iterations" or "collection", "steps": [nested steps] }
+- screenshot: {}
+- api: { "url", "method", "headers", "body" }
+- store: { "variable", "value" or "source" }
+Step ids are unique; dependencies name earlier steps. Use {{param:name}} for parameter values. Never put passwords or other secrets in the workflow."#;
+
+fn check_steps(steps: &Value, depth: usize, ids: &mut HashSet<String>, errors: &mut Vec<String>) {
+    let Some(steps) = steps.as_array() else {
+        errors.push("\"steps\" must be an array".to_string());
+        return;
+    };
+    if depth > MAX_DEPTH {
+        errors.push(format!("Loops are nested more than {} deep", MAX_DEPTH));
+        return;
+    }
+    for (index, step) in steps.iter().enumerate() {
+        let Some(id) = step.get("id").and_then(Value::as_str).filter(|id| !id.is_empty()) else {
+            errors.push(format!("Step {} has no id", index + 1));
+            continue;
+        };
+        if !ids.insert(id.to_string()) {
+            errors.push(format!("Duplicate step id: {}", id));
+        }
+        let kind = step.get("type").and_then(Value::as_str).unwrap_or_default();
+        let Some((_, required)) = STEP_TYPES.iter().find(|(name, _)| *name == kind) else {
+            errors.push(format!("Step {} has unknown type \"{}\"", id, kind));
+            continue;
+        };
+        let config = step.get("config").and_then(Value::as_object);
+        for group in required.iter() {
+            if !group.iter().any(|key| config.map_or(false, |c| c.contains_key(*key))) {
+                errors.push(format!("Step {} ({}) needs config.{}", id, kind, group.join(" or config.")));
+            }
+        }
+        // Dependencies must point at steps defined before this one
+        match step.get("dependencies") {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(deps)) => {
+                for dep in deps {
+                    match dep.as_str() {
+                        Some(dep) if ids.contains(dep) && dep != id => {}
+                        _ => errors.push(format!("Step {} depends on unknown step {}", id, dep)),
+                    }
+                }
+            }
+            Some(_) => errors.push(format!("Step {} dependencies must be an array", id)),
+        }
+        if kind == "loop" {
+            if let Some(nested) = config.and_then(|c| c.get("steps")) {
+                check_steps(nested, depth + 1, ids, errors);
+            }
+        }
+    }
+}
+
+fn validate(document: &Value) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    if !document.is_object() {
+        return Err(vec!["The reply must be a JSON object".to_string()]);
+    }
+    if document.get("name").and_then(Value::as_str).map_or(true, |n| n.trim().is_empty()) {
+        errors.push("\"name\" is missing".to_string());
+    }
+    match document.get("steps") {
+        Some(steps) if steps.as_array().map_or(false, |s| !s.is_empty()) => {
+            check_steps(steps, 0, &mut HashSet::new(), &mut errors)
+        }
+        _ => errors.push("\"steps\" must be a non-empty array".to_string()),
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// Lowercase slug of the name, suffixed until no definition uses it
+fn unique_id(app: &AppHandle, name: &str) -> Result<String, String> {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= 60 {
+            break;
+        }
+    }
+    let slug = match slug.trim_end_matches('-') {
+        "" => "generated-workflow".to_string(),
+        slug => slug.to_string(),
+    };
+    let dir = workflow_defs::workflows_dir(app)?;
+    let mut id = slug.clone();
+    let mut n = 2;
+    while dir.join(workflow_defs::definition_file(&id)).exists() {
+        id = format!("{}-{}", slug, n);
+        n += 1;
+    }
+    Ok(id)
+}
+
+async fn request(app: &AppHandle, prompt: &str) -> Result<Value, String> {
+    let reply = ai::complete(app, AiTask::Extraction, SYSTEM_PROMPT, prompt, GENERATE_TIMEOUT).await?;
+    ai::parse_json_reply(&reply)
+}
+
+// Ask the AI for a workflow matching `description` and save it as a draft
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_workflow_from_prompt(
+    app_handle: AppHandle,
+    description: String,
+) -> Result<WorkflowDefinition, String> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err("Describe what the workflow should do".to_string());
+    }
+    if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(format!("Description is longer than {} characters", MAX_DESCRIPTION_CHARS));
+    }
+
+    let mut document = request(&app_handle, description).await?;
+    if let Err(errors) = validate(&document) {
+        let retry = format!(
+            "{}\n\nYour previous workflow was invalid:\n{}\n\nPrevious workflow:\n{}\n\nReply with the corrected workflow.",
+            description,
+            errors.iter().map(|e| format!("- {}", e)).collect::<Vec<_>>().join("\n"),
+            document
+        );
+        document = request(&app_handle, &retry).await?;
+        validate(&document).map_err(|errors| format!("Generated workflow is invalid: {}", errors.join("; ")))?;
+    }
+
+    let mut fields: Map<String, Value> = match document {
+        Value::Object(fields) => fields,
+        _ => unreachable!("validated as an object"),
+    };
+    let name = fields["name"].as_str().unwrap_or_default().trim().to_string();
+    fields.insert("id".to_string(), json!(unique_id(&app_handle, &name)?));
+    fields.insert("name".to_string(), json!(name));
+    fields.insert("draft".to_string(), json!(true));
+    fields.insert("generated_from".to_string(), json!(description));
+    // Runs stay behind the normal confirmation for risky clicks
+    fields.remove("allow_risky_actions");
+
+    let definition: WorkflowDefinition = serde_json::from_value(Value::Object(fields))
+        .map_err(|e| format!("Generated workflow is invalid: {}", e))?;
+    workflow_defs::save(
+        &app_handle,
+        &definition,
+        Some(format!("Generate draft {}", definition.name)),
+    )?;
+    Ok(definition)
+}
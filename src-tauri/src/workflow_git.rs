@@ -0,0 +1,233 @@
+// Optional git history for the workflows directory
+// When enabled, every save or delete of a definition is committed, so broken
+// edits can be inspected (`diff_workflow_versions`) and reverted
+// (`rollback_workflow`). Uses a plain local repository in the workflows dir.
+
+use git2::{Oid, Repository, Signature, Sort};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::storage;
+use crate::workflow_defs::{self, WorkflowDefinition};
+
+const GIT_FILE: &str = "workflow-git.json";
+const AUTHOR_NAME: &str = "MadEasy Browser";
+const AUTHOR_EMAIL: &str = "workflows@madeasy.local";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct GitConfig {
+    enabled: bool,
+}
+
+#[derive(Default)]
+pub struct WorkflowGitState {
+    config: Mutex<GitConfig>,
+    // Serializes index updates and commits
+    lock: Mutex<()>,
+}
+
+impl WorkflowGitState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            config: Mutex::new(storage::load(app, GIT_FILE)),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WorkflowVersion {
+    commit: String,
+    message: String,
+    timestamp: i64,
+    // False when this commit deleted the workflow
+    exists: bool,
+}
+
+fn enabled(app: &AppHandle) -> bool {
+    app.state::<WorkflowGitState>().config.lock().unwrap().enabled
+}
+
+fn open(app: &AppHandle) -> Result<Repository, String> {
+    let dir = workflow_defs::workflows_dir(app)?;
+    Repository::open(&dir)
+        .or_else(|_| Repository::init(&dir))
+        .map_err(|e| e.to_string())
+}
+
+fn commit_paths(repo: &Repository, paths: &[&Path], message: &str) -> Result<Option<Oid>, git2::Error> {
+    let workdir = repo.workdir().map(Path::to_path_buf).unwrap_or_default();
+    let mut index = repo.index()?;
+    for path in paths {
+        if workdir.join(path).exists() {
+            index.add_path(path)?;
+        } else {
+            index.remove_path(path)?;
+        }
+    }
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    if parent.as_ref().map_or(false, |p| p.tree_id() == tree.id()) {
+        return Ok(None);
+    }
+    let signature = Signature::now(AUTHOR_NAME, AUTHOR_EMAIL)?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .map(Some)
+}
+
+// Commit the current state of one definition; a no-op when versioning is off
+pub fn record(app: &AppHandle, workflow_id: &str, message: &str) -> Result<(), String> {
+    if !enabled(app) {
+        return Ok(());
+    }
+    let state = app.state::<WorkflowGitState>();
+    let _guard = state.lock.lock().unwrap();
+    let repo = open(app)?;
+    let file = workflow_defs::definition_file(workflow_id);
+    commit_paths(&repo, &[Path::new(&file)], message).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn blob_at(repo: &Repository, commit: &git2::Commit, file: &str) -> Result<Option<Vec<u8>>, git2::Error> {
+    match commit.tree()?.get_path(Path::new(file)) {
+        Ok(entry) => Ok(Some(repo.find_blob(entry.id())?.content().to_vec())),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn find_commit<'r>(repo: &'r Repository, commit: &str) -> Result<git2::Commit<'r>, String> {
+    repo.revparse_single(commit)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| format!("Unknown version: {}", commit))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_workflow_git_config(state: tauri::State<'_, WorkflowGitState>) -> Result<GitConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+// Enabling versioning commits all existing definitions as the first version
+#[tauri::command]
+#[specta::specta]
+pub async fn set_workflow_git_config(
+    app_handle: AppHandle,
+    state: tauri::State<'_, WorkflowGitState>,
+    config: GitConfig,
+) -> Result<(), String> {
+    if config.enabled {
+        let _guard = state.lock.lock().unwrap();
+        let repo = open(&app_handle)?;
+        let mut index = repo.index().map_err(|e| e.to_string())?;
+        index
+            .add_all(["*.json"], git2::IndexAddOption::DEFAULT, None)
+            .map_err(|e| e.to_string())?;
+        index.write().map_err(|e| e.to_string())?;
+        commit_paths(&repo, &[], "Enable workflow versioning").map_err(|e| e.to_string())?;
+    }
+    storage::save(&app_handle, GIT_FILE, &config)?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+// Commits that changed the workflow, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_workflow_versions(app_handle: AppHandle, workflow_id: String) -> Result<Vec<WorkflowVersion>, String> {
+    workflow_defs::validate_id(&workflow_id)?;
+    if !enabled(&app_handle) {
+        return Err("Workflow versioning is not enabled".to_string());
+    }
+    let repo = open(&app_handle)?;
+    if repo.head().is_err() {
+        return Ok(Vec::new());
+    }
+    let file = workflow_defs::definition_file(&workflow_id);
+
+    let collect = || -> Result<Vec<WorkflowVersion>, git2::Error> {
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        walk.set_sorting(Sort::TIME)?;
+        let mut versions = Vec::new();
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            let content = blob_at(&repo, &commit, &file)?;
+            let previous = match commit.parent(0) {
+                Ok(parent) => blob_at(&repo, &parent, &file)?,
+                Err(_) => None,
+            };
+            if content != previous {
+                versions.push(WorkflowVersion {
+                    commit: commit.id().to_string(),
+                    message: commit.summary().unwrap_or_default().to_string(),
+                    timestamp: commit.time().seconds(),
+                    exists: content.is_some(),
+                });
+            }
+        }
+        Ok(versions)
+    };
+    collect().map_err(|e| e.to_string())
+}
+
+// Unified diff of the definition between two versions; `to` defaults to the
+// file currently on disk
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_workflow_versions(
+    app_handle: AppHandle,
+    workflow_id: String,
+    from: String,
+    to: Option<String>,
+) -> Result<String, String> {
+    workflow_defs::validate_id(&workflow_id)?;
+    let repo = open(&app_handle)?;
+    let file = workflow_defs::definition_file(&workflow_id);
+
+    let old = blob_at(&repo, &find_commit(&repo, &from)?, &file).map_err(|e| e.to_string())?;
+    let new = match &to {
+        Some(to) => blob_at(&repo, &find_commit(&repo, to)?, &file).map_err(|e| e.to_string())?,
+        None => std::fs::read(workflow_defs::workflows_dir(&app_handle)?.join(&file)).ok(),
+    };
+
+    let mut patch = git2::Patch::from_buffers(
+        old.as_deref().unwrap_or_default(),
+        Some(Path::new(&file)),
+        new.as_deref().unwrap_or_default(),
+        Some(Path::new(&file)),
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+    let diff = patch.to_buf().map_err(|e| e.to_string())?;
+    Ok(diff.as_str().unwrap_or_default().to_string())
+}
+
+// Restore the definition as it was at `commit` and record that as a new version
+#[tauri::command]
+#[specta::specta]
+pub async fn rollback_workflow(app_handle: AppHandle, workflow_id: String, commit: String) -> Result<(), String> {
+    workflow_defs::validate_id(&workflow_id)?;
+    if !enabled(&app_handle) {
+        return Err("Workflow versioning is not enabled".to_string());
+    }
+    let repo = open(&app_handle)?;
+    let file = workflow_defs::definition_file(&workflow_id);
+    let target = find_commit(&repo, &commit)?;
+    let content = blob_at(&repo, &target, &file)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Workflow {} does not exist in version {}", workflow_id, commit))?;
+    // Refuse to restore something the current build cannot load
+    serde_json::from_slice::<WorkflowDefinition>(&content).map_err(|e| format!("Invalid workflow version: {}", e))?;
+
+    std::fs::write(workflow_defs::workflows_dir(&app_handle)?.join(&file), content).map_err(|e| e.to_string())?;
+    let short = &target.id().to_string()[..7];
+    record(&app_handle, &workflow_id, &format!("Roll back {} to {}", workflow_id, short))
+}
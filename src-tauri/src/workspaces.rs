@@ -0,0 +1,337 @@
+// Workspaces
+// Browser windows can be grouped into named workspaces ("Project A",
+// "Research"). Switching workspace hides the windows of every other workspace
+// and shows the target's; a workspace with no open windows is restored from
+// its last saved snapshot. Snapshots record each window's URL, position, size
+// and container and are kept in workspaces.json. Windows in no workspace are
+// left alone, and the main window can't join one.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Window, WindowUrl};
+
+use crate::{containers, idle, kiosk, storage, windowpool};
+
+const WORKSPACES_FILE: &str = "workspaces.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WorkspaceWindow {
+    url: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    maximized: bool,
+    #[serde(default)]
+    container: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Workspace {
+    id: String,
+    name: String,
+    // Last saved snapshot
+    #[serde(default)]
+    windows: Vec<WorkspaceWindow>,
+    #[serde(default)]
+    saved_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct WorkspaceList {
+    workspaces: Vec<Workspace>,
+    active: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WorkspaceInfo {
+    #[serde(flatten)]
+    workspace: Workspace,
+    active: bool,
+    // Labels of the workspace's open windows
+    open_windows: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct WorkspaceState {
+    list: Mutex<WorkspaceList>,
+    // Window label -> workspace id
+    members: Mutex<HashMap<String, String>>,
+}
+
+impl WorkspaceState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            list: Mutex::new(storage::load(app, WORKSPACES_FILE)),
+            members: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+pub fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<WorkspaceState>().members.lock().unwrap().remove(label);
+}
+
+fn find_window(app: &AppHandle, window_id: &str) -> Result<Window, String> {
+    app.get_window(window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))
+}
+
+fn ensure_exists(state: &WorkspaceState, workspace_id: &str) -> Result<(), String> {
+    if state.list.lock().unwrap().workspaces.iter().any(|w| w.id == workspace_id) {
+        Ok(())
+    } else {
+        Err(format!("Unknown workspace: {}", workspace_id))
+    }
+}
+
+fn members_of(state: &WorkspaceState, workspace_id: &str) -> Vec<String> {
+    let mut labels: Vec<String> = state
+        .members
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, id)| *id == workspace_id)
+        .map(|(label, _)| label.clone())
+        .collect();
+    labels.sort();
+    labels
+}
+
+fn snapshot(app: &AppHandle, window: &Window) -> Result<WorkspaceWindow, String> {
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    Ok(WorkspaceWindow {
+        url: window.url().map_err(|e| e.to_string())?.to_string(),
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().unwrap_or(false),
+        container: containers::window_container(app, window.label()),
+    })
+}
+
+fn reopen(app: &AppHandle, saved: &WorkspaceWindow) -> Result<Window, String> {
+    let url = match saved.url.parse::<url::Url>() {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => WindowUrl::External(url),
+        _ => WindowUrl::App("index.html".into()),
+    };
+    let window = match &saved.container {
+        Some(container) => containers::open_window(app, url, container)?,
+        None => windowpool::build_window(app, url, true)?,
+    };
+    window
+        .set_size(PhysicalSize::new(saved.width, saved.height))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(saved.x, saved.y))
+        .map_err(|e| e.to_string())?;
+    if saved.maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    Ok(window)
+}
+
+// Open the saved windows of a workspace and add them to it
+fn restore(app: &AppHandle, workspace_id: &str) -> Result<Vec<String>, String> {
+    let state = app.state::<WorkspaceState>();
+    let saved = state
+        .list
+        .lock()
+        .unwrap()
+        .workspaces
+        .iter()
+        .find(|w| w.id == workspace_id)
+        .map(|w| w.windows.clone())
+        .ok_or_else(|| format!("Unknown workspace: {}", workspace_id))?;
+    let mut labels = Vec::new();
+    for window in &saved {
+        match reopen(app, window) {
+            Ok(opened) => {
+                let label = opened.label().to_string();
+                state.members.lock().unwrap().insert(label.clone(), workspace_id.to_string());
+                labels.push(label);
+            }
+            Err(e) => eprintln!("Failed to reopen {}: {}", window.url, e),
+        }
+    }
+    Ok(labels)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_workspaces(state: tauri::State<'_, WorkspaceState>) -> Result<Vec<WorkspaceInfo>, String> {
+    let list = state.list.lock().unwrap().clone();
+    Ok(list
+        .workspaces
+        .into_iter()
+        .map(|workspace| WorkspaceInfo {
+            active: list.active.as_deref() == Some(workspace.id.as_str()),
+            open_windows: members_of(&state, &workspace.id),
+            workspace,
+        })
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_workspace(
+    app_handle: AppHandle,
+    state: tauri::State<'_, WorkspaceState>,
+    name: String,
+) -> Result<Workspace, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Workspace name cannot be empty".to_string());
+    }
+
+    let mut list = state.list.lock().unwrap();
+    let base: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut id = base.clone();
+    let mut n = 1;
+    while list.workspaces.iter().any(|w| w.id == id) {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+
+    let workspace = Workspace {
+        id,
+        name,
+        windows: Vec::new(),
+        saved_at: None,
+    };
+    list.workspaces.push(workspace.clone());
+    storage::save(&app_handle, WORKSPACES_FILE, &*list)?;
+    Ok(workspace)
+}
+
+// Open windows of the workspace stay open but leave it
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_workspace(
+    app_handle: AppHandle,
+    state: tauri::State<'_, WorkspaceState>,
+    workspace_id: String,
+) -> Result<(), String> {
+    ensure_exists(&state, &workspace_id)?;
+    state.members.lock().unwrap().retain(|_, id| *id != workspace_id);
+    let mut list = state.list.lock().unwrap();
+    list.workspaces.retain(|w| w.id != workspace_id);
+    if list.active.as_deref() == Some(workspace_id.as_str()) {
+        list.active = None;
+    }
+    storage::save(&app_handle, WORKSPACES_FILE, &*list)
+}
+
+// Moves the window out of any workspace it was in
+#[tauri::command]
+#[specta::specta]
+pub async fn add_window_to_workspace(
+    app_handle: AppHandle,
+    state: tauri::State<'_, WorkspaceState>,
+    workspace_id: String,
+    window_id: String,
+) -> Result<(), String> {
+    if window_id == "main" || window_id == kiosk::WINDOW_LABEL {
+        return Err("This window can't join a workspace".to_string());
+    }
+    ensure_exists(&state, &workspace_id)?;
+    find_window(&app_handle, &window_id)?;
+    state.members.lock().unwrap().insert(window_id, workspace_id);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_window_from_workspace(
+    state: tauri::State<'_, WorkspaceState>,
+    window_id: String,
+) -> Result<(), String> {
+    state.members.lock().unwrap().remove(&window_id);
+    Ok(())
+}
+
+// Replace the workspace's snapshot with its open windows
+#[tauri::command]
+#[specta::specta]
+pub async fn save_workspace(
+    app_handle: AppHandle,
+    state: tauri::State<'_, WorkspaceState>,
+    workspace_id: String,
+) -> Result<Workspace, String> {
+    ensure_exists(&state, &workspace_id)?;
+    let mut windows = Vec::new();
+    for label in members_of(&state, &workspace_id) {
+        if let Some(window) = app_handle.get_window(&label) {
+            windows.push(snapshot(&app_handle, &window)?);
+        }
+    }
+    let mut list = state.list.lock().unwrap();
+    let workspace = list
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == workspace_id)
+        .ok_or_else(|| format!("Unknown workspace: {}", workspace_id))?;
+    workspace.windows = windows;
+    workspace.saved_at = Some(chrono::Utc::now().timestamp_millis());
+    let saved = workspace.clone();
+    storage::save(&app_handle, WORKSPACES_FILE, &*list)?;
+    Ok(saved)
+}
+
+// Opens the saved windows again, alongside any that are still open
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_workspace(app_handle: AppHandle, workspace_id: String) -> Result<Vec<String>, String> {
+    kiosk::ensure_inactive(&app_handle)?;
+    idle::ensure_unlocked(&app_handle)?;
+    restore(&app_handle, &workspace_id)
+}
+
+// Hide every other workspace's windows and show this one's
+#[tauri::command]
+#[specta::specta]
+pub async fn switch_workspace(
+    app_handle: AppHandle,
+    state: tauri::State<'_, WorkspaceState>,
+    workspace_id: String,
+) -> Result<(), String> {
+    kiosk::ensure_inactive(&app_handle)?;
+    idle::ensure_unlocked(&app_handle)?;
+    ensure_exists(&state, &workspace_id)?;
+    let members = state.members.lock().unwrap().clone();
+    for (label, id) in &members {
+        if *id != workspace_id {
+            if let Some(window) = app_handle.get_window(label) {
+                window.hide().map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    let mut shown = members_of(&state, &workspace_id);
+    if shown.is_empty() {
+        shown = restore(&app_handle, &workspace_id)?;
+    }
+    for label in &shown {
+        if let Some(window) = app_handle.get_window(label) {
+            window.show().map_err(|e| e.to_string())?;
+        }
+    }
+    if let Some(window) = shown.first().and_then(|label| app_handle.get_window(label)) {
+        let _ = window.set_focus();
+    }
+
+    let mut list = state.list.lock().unwrap();
+    list.active = Some(workspace_id.clone());
+    storage::save(&app_handle, WORKSPACES_FILE, &*list)?;
+    let _ = app_handle.emit_all("workspace-changed", workspace_id);
+    Ok(())
+}
@@ -0,0 +1,182 @@
+// Per-site zoom levels
+// Zoom is remembered per origin and reapplied whenever a page from that origin loads.
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::storage;
+
+const ZOOM_FILE: &str = "zoom-levels.json";
+const MIN_ZOOM: f64 = 0.25;
+const MAX_ZOOM: f64 = 5.0;
+
+#[derive(Default)]
+pub struct ZoomState {
+    // Origin -> zoom factor; origins at 100% are not stored
+    levels: Mutex<HashMap<String, f64>>,
+}
+
+impl ZoomState {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            levels: Mutex::new(storage::load(app, ZOOM_FILE)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+struct ZoomChanged {
+    window_id: String,
+    origin: Option<String>,
+    factor: f64,
+}
+
+fn origin_of(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    match url.origin() {
+        origin @ url::Origin::Tuple(..) => Some(origin.ascii_serialization()),
+        url::Origin::Opaque(_) => None,
+    }
+}
+
+fn zoom_for(app: &AppHandle, url: &str) -> f64 {
+    origin_of(url)
+        .and_then(|origin| app.state::<ZoomState>().levels.lock().unwrap().get(&origin).copied())
+        .unwrap_or(1.0)
+}
+
+fn apply(window: &Window, factor: f64) -> Result<(), String> {
+    platform::set_zoom(window, factor)?;
+    window
+        .emit_all(
+            "zoom-changed",
+            ZoomChanged {
+                window_id: window.label().to_string(),
+                origin: window.url().ok().and_then(|u| origin_of(u.as_str())),
+                factor,
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+pub fn on_page_load(window: &Window, url: &str) {
+    let factor = zoom_for(&window.app_handle(), url);
+    if let Err(e) = platform::set_zoom(window, factor) {
+        eprintln!("Failed to restore zoom for {}: {}", url, e);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_zoom(app_handle: AppHandle, window_id: String) -> Result<f64, String> {
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    let url = window.url().map_err(|e| e.to_string())?;
+    Ok(zoom_for(&app_handle, url.as_str()))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_zoom(
+    app_handle: AppHandle,
+    state: tauri::State<'_, ZoomState>,
+    window_id: String,
+    factor: f64,
+) -> Result<(), String> {
+    if !(MIN_ZOOM..=MAX_ZOOM).contains(&factor) {
+        return Err(format!("Zoom must be between {}% and {}%", MIN_ZOOM * 100.0, MAX_ZOOM * 100.0));
+    }
+    let window = app_handle
+        .get_window(&window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+
+    let origin = window.url().ok().and_then(|u| origin_of(u.as_str()));
+    if let Some(origin) = &origin {
+        let mut levels = state.levels.lock().unwrap();
+        if (factor - 1.0).abs() < f64::EPSILON {
+            levels.remove(origin);
+        } else {
+            levels.insert(origin.clone(), factor);
+        }
+        storage::save(&app_handle, ZOOM_FILE, &*levels)?;
+    }
+
+    // Other windows showing the same site follow along
+    for other in app_handle.windows().values() {
+        let same_site = other.label() == window.label()
+            || (origin.is_some() && other.url().ok().and_then(|u| origin_of(u.as_str())) == origin);
+        if same_site {
+            apply(other, factor)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn reset_zoom_all(app_handle: AppHandle, state: tauri::State<'_, ZoomState>) -> Result<(), String> {
+    {
+        let mut levels = state.levels.lock().unwrap();
+        levels.clear();
+        storage::save(&app_handle, ZOOM_FILE, &*levels)?;
+    }
+    for window in app_handle.windows().values() {
+        apply(window, 1.0)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::Window;
+
+    pub fn set_zoom(window: &Window, factor: f64) -> Result<(), String> {
+        window
+            .with_webview(move |webview| unsafe {
+                let _ = webview.controller().SetZoomFactor(factor);
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use cocoa::base::id;
+    use objc::{msg_send, sel, sel_impl};
+    use tauri::Window;
+
+    // WKWebView.pageZoom (macOS 11+)
+    pub fn set_zoom(window: &Window, factor: f64) -> Result<(), String> {
+        window
+            .with_webview(move |webview| unsafe {
+                let view = webview.inner() as id;
+                let _: () = msg_send![view, setPageZoom: factor];
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use tauri::Window;
+    use webkit2gtk::WebViewExt;
+
+    pub fn set_zoom(window: &Window, factor: f64) -> Result<(), String> {
+        window
+            .with_webview(move |webview| webview.inner().set_zoom_level(factor))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use tauri::Window;
+
+    pub fn set_zoom(_window: &Window, _factor: f64) -> Result<(), String> {
+        Err("Zoom is not supported on this platform".to_string())
+    }
+}